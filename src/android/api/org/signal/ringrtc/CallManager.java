@@ -9,8 +9,12 @@
 
 package org.signal.ringrtc;
 
+import android.Manifest;
 import android.content.Context;
+import android.content.pm.PackageManager;
+import android.media.AudioManager;
 import android.os.Build;
+import android.os.Process;
 import androidx.annotation.NonNull;
 import androidx.annotation.Nullable;
 
@@ -31,6 +35,10 @@ import org.webrtc.VideoDecoderFactory;
 import org.webrtc.VideoEncoderFactory;
 import org.webrtc.VideoSource;
 import org.webrtc.VideoTrack;
+import org.webrtc.audio.AudioDeviceModule;
+import org.webrtc.audio.JavaAudioDeviceModule;
+import org.webrtc.audio.JavaAudioDeviceModule.AudioRecordErrorCallback;
+import org.webrtc.audio.JavaAudioDeviceModule.AudioRecordStartErrorCode;
 
 import java.util.List;
 
@@ -48,6 +56,7 @@ public class CallManager {
   private long     nativeCallManager;
   @NonNull
   private Observer observer;
+  private boolean  poisoned;
 
   static {
     if (Build.VERSION.SDK_INT < 21) {
@@ -107,6 +116,20 @@ public class CallManager {
     if (nativeCallManager == 0) {
       throw new IllegalStateException("CallManager has been disposed.");
     }
+    if (poisoned) {
+      throw new IllegalStateException("CallManager is unusable after a fatal error.");
+    }
+  }
+
+  /**
+   *
+   * Returns true once the native code hit a fatal error, after which
+   * the CallManager can no longer be used.
+   *
+   * @see Observer#onFatalError
+   */
+  public boolean isPoisoned() {
+    return poisoned;
   }
 
   CallManager(@NonNull Observer observer) {
@@ -136,6 +159,39 @@ public class CallManager {
 
   }
 
+  /**
+   *
+   * Moves the native call manager, including any call in progress, to
+   * a new CallManager object, e.g. when the application recreates the
+   * component owning it.
+   *
+   * All further callbacks go to the new object's observer and this
+   * object can no longer be used.
+   *
+   * @param observer  the observer for the new CallManager
+   *
+   * @return the new CallManager
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  @NonNull
+  public CallManager reattach(@NonNull Observer observer)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "reattach():");
+
+    CallManager callManager = new CallManager(observer);
+
+    ringrtcUpdateCallManager(nativeCallManager, callManager);
+    callManager.nativeCallManager = nativeCallManager;
+    nativeCallManager = 0;
+
+    return callManager;
+  }
+
   /**
    *
    * Notification from application to close down the call manager.
@@ -151,6 +207,28 @@ public class CallManager {
     nativeCallManager = 0;
   }
 
+  /**
+   *
+   * Notification from application to shut down the call manager
+   * gracefully, e.g. from onDestroy().
+   *
+   * All calls are hung up and the hangup messages are given a
+   * bounded period to be sent before the call manager is closed.
+   * {@link Observer#onShutdownComplete} is called once done.
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void shutdown()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "shutdown():");
+    ringrtcShutdown(nativeCallManager);
+    nativeCallManager = 0;
+  }
+
   /**
    *
    * Indication from application to start a new outgoing call
@@ -211,17 +289,69 @@ public class CallManager {
                                               localRenderer,
                                               remoteRenderer,
                                               camera,
-                                              iceServers,
-                                              hideIp);
+                                              hideIp,
+                                              createAudioRecordErrorCallback(context));
 
     callContext.setVideoEnabled(enableCamera);
     ringrtcProceed(nativeCallManager,
                    callId.longValue(),
                    callContext,
+                   iceServers,
                    remoteDevices);
 
   }
 
+  /**
+   *
+   * Reports failures to initialize or start the audio recording as
+   * audio device failures of the active call.
+   *
+   */
+  private AudioRecordErrorCallback createAudioRecordErrorCallback(@NonNull Context context) {
+    return new AudioRecordErrorCallback() {
+      @Override
+      public void onWebRtcAudioRecordInitError(String errorMessage) {
+        Log.w(TAG, "onWebRtcAudioRecordInitError(): " + errorMessage);
+        reportAudioDeviceFailure(context, AudioDeviceError.UNKNOWN);
+      }
+
+      @Override
+      public void onWebRtcAudioRecordStartError(AudioRecordStartErrorCode errorCode, String errorMessage) {
+        Log.w(TAG, "onWebRtcAudioRecordStartError(): " + errorCode + ", " + errorMessage);
+        // A state mismatch means the recording did not start,
+        // typically because another app holds the microphone.
+        reportAudioDeviceFailure(context, errorCode == AudioRecordStartErrorCode.AUDIO_RECORD_START_STATE_MISMATCH
+                                            ? AudioDeviceError.BUSY : AudioDeviceError.UNKNOWN);
+      }
+
+      @Override
+      public void onWebRtcAudioRecordError(String errorMessage) {
+        Log.w(TAG, "onWebRtcAudioRecordError(): " + errorMessage);
+        reportAudioDeviceFailure(context, AudioDeviceError.UNKNOWN);
+      }
+    };
+  }
+
+  private void reportAudioDeviceFailure(@NonNull Context context, AudioDeviceError error) {
+    // Without the permission, the recording fails however it is
+    // reported.
+    if (context.checkPermission(Manifest.permission.RECORD_AUDIO, Process.myPid(), Process.myUid())
+        != PackageManager.PERMISSION_GRANTED) {
+      error = AudioDeviceError.PERMISSION_DENIED;
+    }
+
+    if (nativeCallManager == 0) {
+      Log.w(TAG, "reportAudioDeviceFailure(): call manager is closed, dropping " + error);
+      return;
+    }
+
+    try {
+      audioDeviceFailure(error);
+    } catch (CallException e) {
+      Log.w(TAG, "Unable to report audio device failure", e);
+    }
+  }
+
   /**
    *
    * Indication from application to drop the active call, without
@@ -241,6 +371,28 @@ public class CallManager {
     ringrtcDrop(nativeCallManager, callId.longValue());
   }
 
+  /**
+   *
+   * Indication from application to abort the setup of a call after
+   * proceeding or accepting, e.g. when the user cancels while the
+   * call is still connecting.  The call ends with {@link
+   * CallEvent#ENDED_SETUP_CANCELLED}.  Calls that already connected
+   * are not affected.
+   *
+   * @param callId   callId for the call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void cancelSetup(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "cancelSetup(): " + callId);
+    ringrtcCancelSetup(nativeCallManager, callId.longValue());
+  }
+
   /**
    *
    * Indication from application to completely reset the call manager.
@@ -259,6 +411,24 @@ public class CallManager {
     ringrtcReset(nativeCallManager);
   }
 
+  /**
+   *
+   * Sends the signaling messages persisted before a restart, as
+   * returned by {@link Observer#getPersistedSignaling}.  They are
+   * queued ahead of any sent afterwards.
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void restorePendingSignaling()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "restorePendingSignaling():");
+    ringrtcRestorePendingSignaling(nativeCallManager);
+  }
+
   /**
    *
    * Indication from application that signaling message was sent successfully
@@ -295,6 +465,35 @@ public class CallManager {
     ringrtcMessageSendFailure(nativeCallManager, callId.longValue());
   }
 
+  /**
+   *
+   * Notification from application of a received pre-offer, see
+   * {@link #setPreOffer}.  The application is told to warm up
+   * with {@link Observer#onReceivedPreOffer}, unless the offer
+   * arrived first.
+   *
+   * @param callId       callId for the call
+   * @param remote       remote side of the call
+   * @param remoteDevice deviceId of remote peer
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void receivedPreOffer(CallId  callId,
+                               Remote  remote,
+                               Integer remoteDevice)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "receivedPreOffer(): id: " + callId.format(remoteDevice));
+
+    ringrtcReceivedPreOffer(nativeCallManager,
+                            callId.longValue(),
+                            remote,
+                            remoteDevice.intValue());
+  }
+
   /**
    *
    * Notification from application of a received SDP Offer
@@ -306,6 +505,7 @@ public class CallManager {
    * @param remoteDevice deviceId of remote peer
    * @param offer        text of the SDP offer
    * @param timestamp    timestamp of when offer was sent, in milliseconds
+   * @param ageSec       how long the offer took to be delivered, in seconds
    *
    * @throws CallException for native code failures
    *
@@ -314,7 +514,8 @@ public class CallManager {
                             Remote  remote,
                             Integer remoteDevice,
                             String  offer,
-                            Long    timestamp)
+                            Long    timestamp,
+                            Long    ageSec)
     throws CallException
   {
     checkCallManagerExists();
@@ -326,7 +527,8 @@ public class CallManager {
                          remote,
                          remoteDevice.intValue(),
                          offer,
-                         timestamp.longValue());
+                         timestamp.longValue(),
+                         ageSec.longValue());
   }
 
   /**
@@ -381,19 +583,65 @@ public class CallManager {
    *
    * @param callId       callId for the call
    * @param remoteDevice deviceId of remote peer
+   * @param hangupType   why the remote peer hung up
    *
    * @throws CallException for native code failures
    *
    */
-  public void receivedHangup(CallId callId, Integer remoteDevice)
+  public void receivedHangup(CallId callId, Integer remoteDevice, HangupType hangupType)
     throws CallException
   {
     checkCallManagerExists();
 
-    Log.i(TAG, "receivedHangup(): id: " + callId.format(remoteDevice));
+    Log.i(TAG, "receivedHangup(): id: " + callId.format(remoteDevice) + ", type: " + hangupType);
     ringrtcReceivedHangup(nativeCallManager,
                           callId.longValue(),
-                          remoteDevice.intValue());
+                          remoteDevice.intValue(),
+                          hangupType.ordinal());
+  }
+
+  /**
+   *
+   * Notification from application of received declined Hangup
+   * message.  Once every remote device has declined, the call ends
+   * with {@link CallEvent#ENDED_REMOTE_DECLINED}.
+   *
+   * @param callId       callId for the call
+   * @param remoteDevice deviceId of remote peer
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void receivedDeclined(CallId callId, Integer remoteDevice)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "receivedDeclined(): id: " + callId.format(remoteDevice));
+    ringrtcReceivedDeclined(nativeCallManager,
+                            callId.longValue(),
+                            remoteDevice.intValue());
+  }
+
+  /**
+   *
+   * Notification from application of received Ringing message
+   *
+   * @param callId       callId for the call
+   * @param remoteDevice deviceId of remote peer
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void receivedRinging(CallId callId, Integer remoteDevice)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "receivedRinging(): id: " + callId.format(remoteDevice));
+    ringrtcReceivedRinging(nativeCallManager,
+                           callId.longValue(),
+                           remoteDevice.intValue());
   }
 
   /**
@@ -448,6 +696,9 @@ public class CallManager {
   {
     checkCallManagerExists();
 
+    CallContext callContext = ringrtcGetActiveCallContext(nativeCallManager);
+    callContext.setCommunicationMode();
+
     Connection connection = ringrtcGetActiveConnection(nativeCallManager);
     connection.setAudioPlayout(true);
     connection.setAudioRecording(true);
@@ -469,8 +720,12 @@ public class CallManager {
   {
     checkCallManagerExists();
 
-    Connection connection = ringrtcGetActiveConnection(nativeCallManager);
-    connection.setAudioEnabled(enable);
+    CallContext callContext = ringrtcGetActiveCallContext(nativeCallManager);
+    callContext.setAudioEnabled(enable);
+
+    // Applied to every connection of the call, including those whose
+    // audio track is not created yet.
+    ringrtcSetAudioEnable(nativeCallManager, enable);
   }
 
   /**
@@ -478,6 +733,9 @@ public class CallManager {
    * Notification from application to enable/disable local video
    * recording and transmission.
    *
+   * The camera is turned on or off by the native call, ordered with
+   * the teardown of the call, see {@link #onSetCameraEnabled}.
+   *
    * @param enable   if true, then enable local video recording and transmission
    *
    * @throws CallException for native code failures
@@ -488,445 +746,2234 @@ public class CallManager {
   {
     checkCallManagerExists();
 
-    CallContext callContext = ringrtcGetActiveCallContext(nativeCallManager);
-    callContext.setVideoEnabled(enable);
-
+    Log.i(TAG, "setVideoEnable(): " + enable);
     ringrtcSetVideoEnable(nativeCallManager, enable);
   }
 
   /**
    *
-   * Notification from application to hangup the active call.
+   * Switches the active call to the next camera, e.g. from the front
+   * to the back camera.
+   *
+   * Like {@link #setVideoEnable}, the switch is done by the native
+   * call, see {@link #onSwitchCamera}.
    *
    * @throws CallException for native code failures
    *
    */
-  public void hangup()
+  public void switchCamera()
     throws CallException
   {
     checkCallManagerExists();
 
-    Log.i(TAG, "hangup():");
-    ringrtcHangup(nativeCallManager);
+    Log.i(TAG, "switchCamera():");
+    ringrtcSwitchCamera(nativeCallManager);
   }
 
-  /****************************************************************************
+  /**
    *
-   * Below are only called by the native CM
+   * Starts or stops sharing the screen, on a video track of its own
+   * with a detailed or text content hint, so it can be sent alongside
+   * the camera or in place of it.  The remote side is notified with
+   * {@link CallEvent#REMOTE_SHARING_SCREEN} and {@link
+   * CallEvent#REMOTE_STOPPED_SHARING_SCREEN}.
+   *
+   * @param callId  callId for the active call
+   * @param enable  if true, then start sharing the screen, otherwise stop
+   *
+   * @throws CallException for native code failures, or if screen
+   *                       sharing was not negotiated
    *
    */
+  public void setScreenShareEnable(@NonNull CallId callId, boolean enable)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setScreenShareEnable(): " + callId + ", enable: " + enable);
+    ringrtcSetScreenShareEnable(nativeCallManager, callId.longValue(), enable);
+  }
 
   /**
    *
-   * Envoked by native CallManager to create a Connection object.
+   * Caps the resolution of the sent video, for bandwidth or thermal
+   * control.  The video is scaled down, preserving the aspect ratio
+   * of the capture, to fit within the given resolution.
+   *
+   * @param width   maximum width in pixels, 0 for uncapped
+   * @param height  maximum height in pixels, 0 for uncapped
+   *
+   * @throws CallException for native code failures
    *
    */
-  @CalledByNative
-  @Nullable
-  private Connection createConnection(long        nativeConnection,
-                                      long        nativeCallId,
-                                      int         remoteDevice,
-                                      CallContext callContext) {
-
-    CallId callId = new CallId(nativeCallId);
-
-    Log.i(TAG, "createConnection(): connectionId: " + callId.format(remoteDevice));
-
-    MediaConstraints                constraints   = new MediaConstraints();
-    PeerConnection.RTCConfiguration configuration = new PeerConnection.RTCConfiguration(callContext.iceServers);
-
-    configuration.bundlePolicy  = PeerConnection.BundlePolicy.MAXBUNDLE;
-    configuration.rtcpMuxPolicy = PeerConnection.RtcpMuxPolicy.REQUIRE;
-
-    if (callContext.hideIp) {
-      configuration.iceTransportsType = PeerConnection.IceTransportsType.RELAY;
-    }
-
-    constraints.optional.add(new MediaConstraints.KeyValuePair("DtlsSrtpKeyAgreement", "true"));
-
-    PeerConnectionFactory factory       = callContext.peerConnectionFactory;
-    CameraControl         cameraControl = callContext.cameraControl;
-    try {
-      long nativePeerConnection = ringrtcCreatePeerConnection(factory.getNativeOwnedFactoryAndThreads(),
-                                                              nativeConnection,
-                                                              configuration,
-                                                              constraints);
-      if (nativePeerConnection == 0) {
-        Log.w(TAG, "Unable to create native PeerConnection.");
-        return null;
-      }
-
-      Connection connection = new Connection(new Connection.NativeFactory(nativePeerConnection,
-                                                                          callId,
-                                                                          remoteDevice));
-
-      connection.setAudioPlayout(false);
-      connection.setAudioRecording(false);
-
-      MediaStream      mediaStream      = factory.createLocalMediaStream("ARDAMS");
-      MediaConstraints audioConstraints = new MediaConstraints();
-
-      audioConstraints.optional.add(new MediaConstraints.KeyValuePair("DtlsSrtpKeyAgreement", "true"));
-      AudioSource audioSource = factory.createAudioSource(audioConstraints);
-      AudioTrack  audioTrack  = factory.createAudioTrack("ARDAMSa0", audioSource);
-      audioTrack.setEnabled(false);
-      mediaStream.addTrack(audioTrack);
+  public void setVideoMaxResolution(int width, int height)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-      if (callContext.videoTrack != null) {
-        // We are sharing a single videoTrack with all the media
-        // streams.  As such, use addPreservedTrack() here so that
-        // when this MediaStream is disposed() the VideoTrack remains.
-        // We need to explicitly dispose() the VideoTrack at call
-        // termination.
-        mediaStream.addPreservedTrack(callContext.videoTrack);
-      }
+    Log.i(TAG, "setVideoMaxResolution(): " + width + "x" + height);
+    ringrtcSetVideoMaxResolution(nativeCallManager, width, height);
+  }
 
-      connection.addStream(mediaStream);
+  /**
+   *
+   * Notification from application that the video capture format
+   * changed, so the sent video scale can be recomputed.
+   *
+   * @param width   capture width in pixels
+   * @param height  capture height in pixels
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setVideoCaptureResolution(int width, int height)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-      connection.setAudioSource(audioSource,
-                                audioTrack);
+    Log.i(TAG, "setVideoCaptureResolution(): " + width + "x" + height);
+    ringrtcSetVideoCaptureResolution(nativeCallManager, width, height);
+  }
 
-      return connection;
+  /**
+   *
+   * Sets how the sent video degrades when bandwidth or CPU is
+   * constrained.  Screen sharing is best served by maintaining
+   * resolution, camera video by maintaining framerate.  The default
+   * is balanced.  The current adaptation is reported by
+   * {@link CallStats#videoAdaptation}.
+   *
+   * @param preference  the degradation preference
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setVideoDegradationPreference(DegradationPreference preference)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-    } catch  (CallException e) {
-      Log.w(TAG, "Unable to create Peer Connection with native call", e);
-      return null;
-    }
+    Log.i(TAG, "setVideoDegradationPreference(): " + preference);
+    ringrtcSetVideoDegradationPreference(nativeCallManager, preference.ordinal());
   }
 
   /**
    *
-   * Envoked by native CallManager when a remote "accepts" on a
-   * Connection, allowing MediaStream of connection to attach to
-   * application renderers and play back devices.
+   * Sets how much bandwidth the active call may use.  A connected
+   * call renegotiates, so the remote side limits what it sends as
+   * well.  The default is NORMAL.
+   *
+   * @param mode  the bandwidth mode
+   *
+   * @throws CallException for native code failures
    *
    */
-  @CalledByNative
-  private void onConnectMedia(@NonNull CallContext callContext,
-                              @NonNull MediaStream mediaStream)
+  public void setBandwidthMode(BandwidthMode mode)
+    throws CallException
   {
-    Log.i(TAG, "onConnectMedia(): mediaStream: " + mediaStream);
+    checkCallManagerExists();
 
-    if (mediaStream == null) {
-      Log.w(TAG, "Remote media stream unavailable");
-      return;
-    }
-
-    if (mediaStream.audioTracks == null) {
-      Log.w(TAG, "Remote media stream contains no audio tracks");
-      return;
-    }
+    Log.i(TAG, "setBandwidthMode(): " + mode);
+    ringrtcSetBandwidthMode(nativeCallManager, mode.ordinal());
+  }
 
-    for (AudioTrack remoteAudioTrack : mediaStream.audioTracks) {
-      Log.i(TAG, "onConnectMedia(): enabling audioTrack");
-      remoteAudioTrack.setEnabled(true);
-    }
+  /**
+   *
+   * Tells RingRTC where the application plays the audio of calls,
+   * after the user picked an output or a headset was plugged in or
+   * removed.  The default is EARPIECE.
+   *
+   * <p>RingRTC suggests other outputs from it, e.g. the speaker when
+   * the remote side turns its video on, applies them and reports
+   * them with {@link Observer#onAudioOutputChanged}.
+   *
+   * @param output  the audio output in use
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setAudioOutput(@NonNull AudioOutput output)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-    if (mediaStream.videoTracks == null) {
-      Log.w(TAG, "Remote media stream contains no video tracks");
-      return;
-    }
+    Log.i(TAG, "setAudioOutput(): " + output);
+    ringrtcSetAudioOutput(nativeCallManager, output.ordinal());
+  }
 
-    if (mediaStream.videoTracks.size() == 1) {
-      Log.i(TAG, "onConnectMedia(): enabling videoTrack(0)");
-      VideoTrack remoteVideoTrack = mediaStream.videoTracks.get(0);
-      remoteVideoTrack.setEnabled(true);
-      remoteVideoTrack.addSink(callContext.remoteRenderer);
-    } else {
-      Log.w(TAG, "onConnectMedia(): Media stream contains unexpected number of video tracks: " + mediaStream.videoTracks.size());
-    }
+  /**
+   *
+   * Tells RingRTC where Telecom routed the audio of calls added to
+   * it, from Connection.onCallAudioStateChanged(), e.g. to the
+   * Bluetooth headset of a car once it connects.
+   *
+   * @param output  the audio output picked by the system
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void audioRouteChangedBySystem(@NonNull AudioOutput output)
+    throws CallException
+  {
+    checkCallManagerExists();
 
+    Log.i(TAG, "audioRouteChangedBySystem(): " + output);
+    ringrtcAudioRouteChangedBySystem(nativeCallManager, output.ordinal());
   }
 
   /**
    *
-   * Envoked by native CallManager when closing down a call to
-   * shutdown media.
+   * Sets how the media sections of new PeerConnections are bundled
+   * onto transports.  The default is MAXBUNDLE, older endpoints that
+   * do not support BUNDLE need MAXCOMPAT.
+   *
+   * @param policy  the bundle policy
+   *
+   * @throws CallException for native code failures
    *
    */
-  @CalledByNative
-  private void onCloseMedia(@NonNull CallContext callContext)
+  public void setBundlePolicy(PeerConnection.BundlePolicy policy)
+    throws CallException
   {
-    Log.i(TAG, "onCloseMedia():");
-    callContext.setVideoEnabled(false);
-  }
+    checkCallManagerExists();
 
-  @CalledByNative
-  private void closeConnection(Connection connection) {
-    Log.i(TAG, "closeConnection(): " + connection);
-    connection.shutdown();
+    Log.i(TAG, "setBundlePolicy(): " + policy);
+    ringrtcSetBundlePolicy(nativeCallManager, policy.ordinal());
   }
 
-  @CalledByNative
-  private void closeCall(@NonNull CallContext callContext) {
-    Log.i(TAG, "closeCall():");
-    callContext.dispose();
-  }
+  /**
+   *
+   * Sets whether new PeerConnections multiplex RTP and RTCP onto one
+   * port.  The default is REQUIRE.
+   *
+   * @param policy  the rtcp-mux policy
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setRtcpMuxPolicy(PeerConnection.RtcpMuxPolicy policy)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-  @CalledByNative
-  private void onStartCall(Remote remote, long callId, boolean isOutgoing) {
-    Log.i(TAG, "onStartCall():");
-    observer.onStartCall(remote, new CallId(callId), new Boolean(isOutgoing));
+    Log.i(TAG, "setRtcpMuxPolicy(): " + policy);
+    ringrtcSetRtcpMuxPolicy(nativeCallManager, policy.ordinal());
   }
 
-  @CalledByNative
-  private void onEvent(Remote remote, CallEvent event) {
-    Log.i(TAG, "onEvent():");
-    observer.onCallEvent(remote, event);
-  }
+  /**
+   *
+   * Sets which ICE candidates new connections exchange with the
+   * remote side.  With relayOnly, media is always relayed through
+   * TURN and neither side learns the other's IP addresses.  By
+   * default all candidates are exchanged.
+   *
+   * @param relayOnly  if true, only exchange relay candidates
+   * @param noIpv6     if true, do not exchange IPv6 candidates
+   * @param noHost     if true, do not exchange host candidates
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setIceCandidatePolicy(boolean relayOnly, boolean noIpv6, boolean noHost)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-  @CalledByNative
-  private void onCallConcluded(Remote remote) {
-    Log.i(TAG, "onCallConcluded():");
-    observer.onCallConcluded(remote);
+    Log.i(TAG, "setIceCandidatePolicy(): relayOnly: " + relayOnly +
+               ", noIpv6: " + noIpv6 + ", noHost: " + noHost);
+    ringrtcSetIceCandidatePolicy(nativeCallManager, relayOnly, noIpv6, noHost);
   }
 
-  @CalledByNative
-  private void onSendOffer(long callId, Remote remote, int remoteDevice, boolean broadcast, String sdp) {
-    Log.i(TAG, "onSendOffer():");
-    observer.onSendOffer(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast), sdp);
-  }
+  /**
+   *
+   * Sets whether the active call ends when the audio device fails to
+   * initialize, instead of continuing without audio.  Disabled by
+   * default.
+   *
+   * @param enable  if true, end the call on audio device failure
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setEndOnAudioDeviceFailure(boolean enable)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-  @CalledByNative
-  private void onSendAnswer(long callId, Remote remote, int remoteDevice, boolean broadcast, String sdp) {
-    Log.i(TAG, "onSendAnswer():");
-    observer.onSendAnswer(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast), sdp);
+    Log.i(TAG, "setEndOnAudioDeviceFailure(): " + enable);
+    ringrtcSetEndOnAudioDeviceFailure(nativeCallManager, enable);
   }
 
-  @CalledByNative
-  private void onSendIceCandidates(long callId, Remote remote, int remoteDevice, boolean broadcast, List<IceCandidate> iceCandidates) {
-    Log.i(TAG, "onSendIceCandidates():");
-    observer.onSendIceCandidates(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast), iceCandidates);
-  }
+  /**
+   *
+   * Sets whether an incoming call from another remote peer may wait
+   * while the active call is connected, instead of being answered
+   * with busy.  The waiting call rings like any incoming call, and
+   * accepting it puts the active call on hold.  Disabled by default.
+   *
+   * @param enable  if true, enable call waiting
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setCallWaiting(boolean enable)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-  @CalledByNative
-  private void onSendHangup(long callId, Remote remote, int remoteDevice, boolean broadcast) {
-    Log.i(TAG, "onSendHangup():");
-    observer.onSendHangup(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast));
+    Log.i(TAG, "setCallWaiting(): " + enable);
+    ringrtcSetCallWaiting(nativeCallManager, enable);
   }
 
-  @CalledByNative
-  private void onSendBusy(long callId, Remote remote, int remoteDevice, boolean broadcast) {
-    Log.i(TAG, "onSendBusy():");
-    observer.onSendBusy(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast));
-  }
+  /**
+   *
+   * Notification that the audio device of the active call failed to
+   * initialize.  Failures of the audio recording are detected
+   * automatically, this is for failures detected by the application.
+   *
+   * @param error  why the audio device failed
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void audioDeviceFailure(AudioDeviceError error)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-  @CalledByNative
-  private boolean compareRemotes(Remote remote1, Remote remote2) {
-    Log.i(TAG, "compareRemotes():");
-    if (remote1 != null) {
-      return remote1.recipientEquals(remote2);
-    }
-    return false;
+    Log.w(TAG, "audioDeviceFailure(): " + error);
+    ringrtcAudioDeviceFailure(nativeCallManager, error.ordinal());
   }
 
   /**
    *
-   * Contains parameters for creating Connection objects
+   * Replaces the ICE servers of the call, e.g. with new TURN
+   * credentials once CallEvent.ICE_SERVERS_EXPIRING is reported.
+   * The servers are used for candidates gathered from now on,
+   * without restarting ICE.
+   *
+   * @param callId      callId for the call
+   * @param iceServers  the new ICE servers
+   *
+   * @throws CallException for native code failures
+   *
    */
-  static class CallContext {
+  public void updateIceServers(@NonNull CallId                         callId,
+                               @NonNull List<PeerConnection.IceServer> iceServers)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-    @NonNull  private final String TAG = CallManager.CallContext.class.getSimpleName();
-    /** CallId */
-    @NonNull  public final  CallId                         callId;
-    /** Connection factory */
-    @NonNull  public final  PeerConnectionFactory          peerConnectionFactory;
-    /** Remote camera surface renderer */
-    @NonNull  public final  SurfaceViewRenderer            remoteRenderer;
-    /** Camera controller */
-    @NonNull  public final  CameraControl                  cameraControl;
-    /** ICE server list */
-    @NonNull  public final  List<PeerConnection.IceServer> iceServers;
-    /** If true, use TURN servers */
-              public final  boolean                        hideIp;
-    @Nullable public final  VideoSource                    videoSource;
-    @Nullable public final  VideoTrack                     videoTrack;
+    Log.i(TAG, "updateIceServers(): callId: " + callId + ", size: " + iceServers.size());
+    ringrtcUpdateIceServers(nativeCallManager, callId.longValue(), iceServers);
+  }
 
-    public CallContext(@NonNull CallId                         callId,
-                       @NonNull Context                        context,
-                       @NonNull EglBase                        eglBase,
-                       @NonNull SurfaceViewRenderer            localRenderer,
-                       @NonNull SurfaceViewRenderer            remoteRenderer,
-                       @NonNull CameraControl                  camera,
-                       @NonNull List<PeerConnection.IceServer> iceServers,
-                                boolean                        hideIp) {
+  /**
+   *
+   * Attaches opaque metadata to the answer of an incoming call, e.g.
+   * the features the callee supports, for the caller to negotiate
+   * before the media connects.  Call before {@link #proceed}.
+   *
+   * @param callId    callId for the call
+   * @param metadata  the metadata, delivered to the caller's
+   *                  {@link Observer#onReceivedAnswerMetadata}
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setAnswerMetadata(@NonNull CallId callId,
+                                @NonNull byte[] metadata)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-      Log.i(TAG, "ctor(): " + callId);
+    Log.i(TAG, "setAnswerMetadata(): callId: " + callId + ", size: " + metadata.length);
+    ringrtcSetAnswerMetadata(nativeCallManager, callId.longValue(), metadata);
+  }
 
-      this.callId         = callId;
-      this.remoteRenderer = remoteRenderer;
-      this.cameraControl  = camera;
-      this.iceServers     = iceServers;
-      this.hideIp         = hideIp;
+  /**
+   *
+   * Replaces the ICE servers of the active connection, e.g. when
+   * TURN servers rotate.  TURN servers are tried in the order UDP,
+   * TCP, then TLS.  The servers must not be empty while the call
+   * hides the IP address.
+   *
+   * @param iceServers  the new ICE servers
+   * @param iceRestart  if true, restart ICE so the connection moves
+   *                    to the new servers right away
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setIceServers(@NonNull List<PeerConnection.IceServer> iceServers, boolean iceRestart)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-      VideoEncoderFactory encoderFactory = new DefaultVideoEncoderFactory(eglBase.getEglBaseContext(),
-                                                                          true, true);
-      VideoDecoderFactory decoderFactory = new DefaultVideoDecoderFactory(eglBase.getEglBaseContext());
+    Log.i(TAG, "setIceServers(): size: " + iceServers.size() + ", iceRestart: " + iceRestart);
+    ringrtcSetIceServers(nativeCallManager, iceServers, iceRestart);
+  }
 
-      this.peerConnectionFactory = PeerConnectionFactory.builder()
-        .setOptions(new PeerConnectionFactoryOptions())
-        .setVideoEncoderFactory(encoderFactory)
-        .setVideoDecoderFactory(decoderFactory)
-        .createPeerConnectionFactory();
+  /**
+   *
+   * Restarts ICE on the active connection, e.g. when the device
+   * switches from Wi-Fi to cellular.  The call stays up while new
+   * candidates are gathered over the new network.
+   *
+   * @param callId  callId for the active call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void restartIce(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-      // Create a video track that will be shared across all
-      // connection objects.  It must be disposed manually.
-      if (cameraControl.hasCapturer()) {
-        this.videoSource = peerConnectionFactory.createVideoSource(false);
-        this.videoTrack  = peerConnectionFactory.createVideoTrack("ARDAMSv0", videoSource);
-        videoTrack.setEnabled(false);
+    Log.i(TAG, "restartIce(): " + callId);
+    ringrtcRestartIce(nativeCallManager, callId.longValue());
+  }
 
-        // Connect camera as the local video source.
-        cameraControl.initCapturer(videoSource.getCapturerObserver());
-        videoTrack.addSink(localRenderer);
-      } else {
-        this.videoSource = null;
-        this.videoTrack  = null;
-      }
+  /**
+   *
+   * Puts the active call on hold, pausing the local audio and video,
+   * or resumes it.  The remote side is notified with {@link
+   * CallEvent#REMOTE_ON_HOLD} and {@link CallEvent#REMOTE_RESUMED}.
+   *
+   * @param callId  callId for the active call
+   * @param onHold  if true, then put the call on hold, otherwise resume it
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setOnHold(@NonNull CallId callId, boolean onHold)
+    throws CallException
+  {
+    checkCallManagerExists();
 
-    }
+    Log.i(TAG, "setOnHold(): " + callId + ", onHold: " + onHold);
+    ringrtcSetOnHold(nativeCallManager, callId.longValue(), onHold);
+  }
 
-    void setVideoEnabled(boolean enable) {
-      Log.i(TAG, "setVideoEnabled(): " + callId);
-      if (videoTrack != null) {
-        videoTrack.setEnabled(enable);
-        cameraControl.setEnabled(enable);
-      }
+  /**
+   *
+   * Tells RingRTC the application created the Connection of a call
+   * with its self-managed ConnectionService of Telecom.
+   *
+   * <p>From then on, Telecom routes the audio of the call, so
+   * RingRTC only reports the outputs it suggests with {@link
+   * Observer#onAudioOutputChanged}, and asks the application to hold
+   * the Connection with {@link Observer#onTelecomHoldRequested}
+   * whenever it holds the call by itself.
+   *
+   * @param callId  callId for the call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void callAddedToTelecom(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "callAddedToTelecom(): " + callId);
+    ringrtcCallAddedToTelecom(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Puts a call on hold, or resumes it, from Connection.onHold() and
+   * Connection.onUnhold().  Resuming a call in the background brings
+   * it to the foreground, holding the other call.
+   *
+   * @param callId  callId for the call
+   * @param onHold  if true, then put the call on hold, otherwise resume it
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void holdRequestedBySystem(@NonNull CallId callId, boolean onHold)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "holdRequestedBySystem(): " + callId + ", onHold: " + onHold);
+    ringrtcHoldRequestedBySystem(nativeCallManager, callId.longValue(), onHold);
+  }
+
+  /**
+   *
+   * Transfers the active call to another peer.  The remote side is
+   * asked to call the new remote in place of the local user, see
+   * {@link Observer#shouldTransfer}.  Once it accepts, the call ends
+   * with {@link CallEvent#ENDED_TRANSFERRED}, otherwise the call goes
+   * on and {@link CallEvent#TRANSFER_DECLINED} is sent.
+   *
+   * @param callId     callId for the active call
+   * @param newRemote  the peer to transfer the call to
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void transferCall(@NonNull CallId callId, @NonNull String newRemote)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "transferCall(): " + callId + ", newRemote: " + newRemote);
+    ringrtcTransferCall(nativeCallManager, callId.longValue(), newRemote);
+  }
+
+  /**
+   *
+   * Sets the gain of the outgoing audio of the active connection,
+   * e.g. for loud environments.  The gain is kept when the connection
+   * is rebuilt while reconnecting.
+   *
+   * A gain above 1.0 amplifies the microphone and may clip loud
+   * input.
+   *
+   * @param gain  the gain, 1.0 leaves the audio unchanged, clamped to
+   *              between 0.0 and 4.0
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setOutgoingAudioGain(float gain)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setOutgoingAudioGain(): " + gain);
+    ringrtcSetOutgoingAudioGain(nativeCallManager, gain);
+  }
+
+  /**
+   *
+   * Sends DTMF tones on the outgoing audio of the active connection,
+   * e.g. to dial the menu of a SIP gateway.  Tones queue behind any
+   * tones still being played.
+   *
+   * @param tones       the tones to send: 0-9, A-D, # and *, where ','
+   *                    inserts a two second pause
+   * @param durationMs  how long each tone plays, between 40 and 6000
+   *                    milliseconds
+   * @param gapMs       the silence between tones, at least 30
+   *                    milliseconds
+   *
+   * @throws CallException for invalid tones or native code failures
+   *
+   */
+  public void sendDtmf(String tones, int durationMs, int gapMs)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "sendDtmf(): " + tones);
+    ringrtcSendDtmf(nativeCallManager, tones, durationMs, gapMs);
+  }
+
+  /**
+   *
+   * Sends the remote peer of the active connection an application
+   * message, e.g. a reaction or a caption, on the data channel of
+   * the call.  The remote peer receives it with
+   * {@link Observer#onAppMessageReceived}, reliably and in order.
+   *
+   * @param payload  the message
+   *
+   * @throws CallException for native code failures, including while
+   *                       the data channel is past its high water mark
+   *
+   */
+  public void sendAppMessage(@NonNull byte[] payload)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "sendAppMessage(): size: " + payload.length);
+    ringrtcSendAppMessage(nativeCallManager, payload);
+  }
+
+  /**
+   *
+   * Sets the data channel buffering limit of the active connection.
+   * Once more than this many bytes are buffered, sends are refused
+   * until the buffer drains to half the limit, which is signaled by
+   * {@link Observer#onDataChannelBufferedAmountLow}.
+   *
+   * @param mark  the high water mark, in bytes
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setDataChannelHighWaterMark(long mark)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setDataChannelHighWaterMark(): " + mark);
+    ringrtcSetDataChannelHighWaterMark(nativeCallManager, mark);
+  }
+
+  /**
+   *
+   * Returns the number of bytes queued on the data channel of the
+   * active connection but not yet sent.
+   *
+   * @return the buffered amount, in bytes
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public long getActiveDataChannelBufferedAmount()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "getActiveDataChannelBufferedAmount():");
+    return ringrtcGetActiveDataChannelBufferedAmount(nativeCallManager);
+  }
+
+  /**
+   *
+   * Enables or disables {@link Observer#onCallDurationTick}, reported
+   * every second once the media of a call connects.  Disabled by
+   * default.
+   *
+   * @param enable  if true, report the call duration every second
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setCallDurationTicks(boolean enable)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setCallDurationTicks(): " + enable);
+    ringrtcSetCallDurationTicks(nativeCallManager, enable);
+  }
+
+  /**
+   *
+   * Limits the number of remote devices an outgoing call connects
+   * to, so remote peers with many linked devices do not need dozens
+   * of connections.  A device left out is only connected to if it
+   * answers anyway.  No limit by default.
+   *
+   * @param max  the maximum number of connections, zero for no limit
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setMaxConnections(int max)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setMaxConnections(): " + max);
+    ringrtcSetMaxConnections(nativeCallManager, max);
+  }
+
+  /**
+   *
+   * Sets how often {@link Observer#onStatsReport} reports the quality
+   * of the active call, once the media of a call connects.  Disabled
+   * by default.
+   *
+   * @param intervalMillis  the stats interval, zero to disable
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setStatsInterval(long intervalMillis)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setStatsInterval(): " + intervalMillis);
+    ringrtcSetStatsInterval(nativeCallManager, intervalMillis);
+  }
+
+  /**
+   *
+   * Sets how often {@link Observer#onAudioLevels} reports the audio
+   * levels of the active call, once the media of a call connects.
+   * Disabled by default.
+   *
+   * @param intervalMillis  the audio levels interval, zero to disable
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setAudioLevelsInterval(long intervalMillis)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setAudioLevelsInterval(): " + intervalMillis);
+    ringrtcSetAudioLevelsInterval(nativeCallManager, intervalMillis);
+  }
+
+  /**
+   *
+   * Sets how often {@link Observer#onBandwidthEstimate} reports the
+   * bandwidth available to the active call, once the media of a call
+   * connects.  Disabled by default.
+   *
+   * @param intervalMillis  the bandwidth estimate interval, zero to disable
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setBandwidthEstimateInterval(long intervalMillis)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setBandwidthEstimateInterval(): " + intervalMillis);
+    ringrtcSetBandwidthEstimateInterval(nativeCallManager, intervalMillis);
+  }
+
+  /**
+   *
+   * Sets whether outgoing calls send a pre-offer, with
+   * {@link Observer#onSendPreOffer}, as soon as they start.  The
+   * pre-offer reaches the callee while the offer is still being
+   * created, so it can warm up.  Disabled by default.
+   *
+   * @param enable  if true, enable pre-offers
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setPreOffer(boolean enable)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setPreOffer(): " + enable);
+    ringrtcSetPreOffer(nativeCallManager, enable);
+  }
+
+  /**
+   *
+   * Returns how long the active call has been connected, counted
+   * from when its media first connected.  Reconnections do not
+   * restart the count.
+   *
+   * @return the duration in milliseconds, 0 if not yet connected
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public long getActiveCallDuration()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "getActiveCallDuration():");
+    return ringrtcGetActiveCallDuration(nativeCallManager);
+  }
+
+  /**
+   *
+   * Returns the trace of the active connection, for attaching to bug
+   * reports.
+   *
+   * @return the trace events, ordered by timestamp
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public List<TraceEvent> getActiveConnectionTrace()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "getActiveConnectionTrace():");
+    return ringrtcGetActiveConnectionTrace(nativeCallManager);
+  }
+
+  /**
+   *
+   * Returns what the remote peer of the active connection supports,
+   * for feature gating.  Updated by every renegotiation.
+   *
+   * @return the remote capabilities, or null until the offer/answer
+   *         exchange completes
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  @Nullable
+  public RemoteCapabilities getActiveRemoteCapabilities()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "getActiveRemoteCapabilities():");
+    return ringrtcGetActiveRemoteCapabilities(nativeCallManager);
+  }
+
+  /**
+   *
+   * Notification from application to hangup the active call.
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void hangup()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "hangup():");
+    ringrtcHangup(nativeCallManager);
+  }
+
+  /****************************************************************************
+   *
+   * Below are only called by the native CM
+   *
+   */
+
+  /**
+   *
+   * Envoked by native CallManager to create a Connection object.
+   *
+   */
+  @CalledByNative
+  @Nullable
+  private Connection createConnection(long                           nativeConnection,
+                                      long                           nativeCallId,
+                                      int                            remoteDevice,
+                                      CallContext                    callContext,
+                                      List<PeerConnection.IceServer> iceServers,
+                                      int                            bundlePolicy,
+                                      int                            rtcpMuxPolicy) {
+
+    CallId callId = new CallId(nativeCallId);
+
+    Log.i(TAG, "createConnection(): connectionId: " + callId.format(remoteDevice) + ", iceServers: " + iceServers.size());
+
+    MediaConstraints                constraints   = new MediaConstraints();
+    PeerConnection.RTCConfiguration configuration = new PeerConnection.RTCConfiguration(iceServers);
+
+    configuration.bundlePolicy  = PeerConnection.BundlePolicy.values()[bundlePolicy];
+    configuration.rtcpMuxPolicy = PeerConnection.RtcpMuxPolicy.values()[rtcpMuxPolicy];
+
+    if (callContext.hideIp) {
+      configuration.iceTransportsType = PeerConnection.IceTransportsType.RELAY;
+    }
+
+    constraints.optional.add(new MediaConstraints.KeyValuePair("DtlsSrtpKeyAgreement", "true"));
+
+    PeerConnectionFactory factory       = callContext.peerConnectionFactory;
+    CameraControl         cameraControl = callContext.cameraControl;
+    try {
+      long nativePeerConnection = ringrtcCreatePeerConnection(factory.getNativeOwnedFactoryAndThreads(),
+                                                              nativeConnection,
+                                                              configuration,
+                                                              constraints);
+      if (nativePeerConnection == 0) {
+        Log.w(TAG, "Unable to create native PeerConnection.");
+        return null;
+      }
+
+      Connection connection = new Connection(new Connection.NativeFactory(nativePeerConnection,
+                                                                          callId,
+                                                                          remoteDevice));
+
+      // A PeerConnection created by a hard reset picks up the audio
+      // state of the connection it replaces.
+      connection.setAudioPlayout(callContext.communicationMode);
+      connection.setAudioRecording(callContext.communicationMode);
+
+      MediaStream      mediaStream      = factory.createLocalMediaStream("ARDAMS");
+      MediaConstraints audioConstraints = new MediaConstraints();
+
+      audioConstraints.optional.add(new MediaConstraints.KeyValuePair("DtlsSrtpKeyAgreement", "true"));
+      AudioSource audioSource = factory.createAudioSource(audioConstraints);
+      AudioTrack  audioTrack  = factory.createAudioTrack("ARDAMSa0", audioSource);
+      audioTrack.setEnabled(callContext.audioEnabled);
+      mediaStream.addTrack(audioTrack);
+
+      if (callContext.videoTrack != null) {
+        // We are sharing a single videoTrack with all the media
+        // streams.  As such, use addPreservedTrack() here so that
+        // when this MediaStream is disposed() the VideoTrack remains.
+        // We need to explicitly dispose() the VideoTrack at call
+        // termination.
+        mediaStream.addPreservedTrack(callContext.videoTrack);
+      }
+
+      connection.addStream(mediaStream);
+
+      connection.setAudioSource(audioSource,
+                                audioTrack);
+
+      return connection;
+
+    } catch  (CallException e) {
+      Log.w(TAG, "Unable to create Peer Connection with native call", e);
+      return null;
+    }
+  }
+
+  /**
+   *
+   * Envoked by native CallManager when a remote "accepts" on a
+   * Connection, allowing MediaStream of connection to attach to
+   * application renderers and play back devices.
+   *
+   */
+  @CalledByNative
+  private void onConnectMedia(@NonNull CallContext callContext,
+                              @NonNull MediaStream mediaStream)
+  {
+    Log.i(TAG, "onConnectMedia(): mediaStream: " + mediaStream);
+
+    if (mediaStream == null) {
+      Log.w(TAG, "Remote media stream unavailable");
+      return;
+    }
+
+    if (mediaStream.audioTracks == null) {
+      Log.w(TAG, "Remote media stream contains no audio tracks");
+      return;
+    }
+
+    for (AudioTrack remoteAudioTrack : mediaStream.audioTracks) {
+      Log.i(TAG, "onConnectMedia(): enabling audioTrack");
+      remoteAudioTrack.setEnabled(true);
+    }
+
+    if (mediaStream.videoTracks == null) {
+      Log.w(TAG, "Remote media stream contains no video tracks");
+      return;
+    }
+
+    if (mediaStream.videoTracks.size() == 1) {
+      Log.i(TAG, "onConnectMedia(): enabling videoTrack(0)");
+      VideoTrack remoteVideoTrack = mediaStream.videoTracks.get(0);
+      remoteVideoTrack.setEnabled(true);
+      remoteVideoTrack.addSink(callContext.remoteRenderer);
+    } else {
+      Log.w(TAG, "onConnectMedia(): Media stream contains unexpected number of video tracks: " + mediaStream.videoTracks.size());
+    }
+
+  }
+
+  /**
+   *
+   * Envoked by native CallManager to route the audio of a call to
+   * another output.
+   *
+   */
+  @CalledByNative
+  private void onAudioRouteSuggested(@NonNull CallContext callContext, int output)
+  {
+    AudioOutput audioOutput = AudioOutput.fromNativeIndex(output);
+
+    Log.i(TAG, "onAudioRouteSuggested(): " + audioOutput);
+    if (!callContext.telecom) {
+      callContext.setAudioOutput(audioOutput);
+    }
+    observer.onAudioOutputChanged(callContext.callId, audioOutput);
+  }
+
+  /**
+   *
+   * Envoked by native CallManager once it treats a call as managed
+   * by Telecom.
+   *
+   */
+  @CalledByNative
+  private void onCallAddedToTelecom(@NonNull CallContext callContext)
+  {
+    Log.i(TAG, "onCallAddedToTelecom(): " + callContext.callId);
+    callContext.telecom = true;
+    observer.onCallAddedToTelecom(callContext.callId);
+  }
+
+  /**
+   *
+   * Envoked by native CallManager to show a call managed by Telecom
+   * as on hold, or active again.
+   *
+   */
+  @CalledByNative
+  private void onRequestTelecomHold(@NonNull CallContext callContext, boolean onHold)
+  {
+    Log.i(TAG, "onRequestTelecomHold(): " + callContext.callId + ", onHold: " + onHold);
+    observer.onTelecomHoldRequested(callContext.callId, onHold);
+  }
+
+  /**
+   *
+   * Envoked by native CallManager to turn the camera of a call on or
+   * off.
+   *
+   */
+  @CalledByNative
+  private void onSetCameraEnabled(@NonNull CallContext callContext, boolean enable)
+  {
+    Log.i(TAG, "onSetCameraEnabled(): " + enable);
+    callContext.setVideoEnabled(enable);
+  }
+
+  /**
+   *
+   * Envoked by native CallManager to switch a call to the next
+   * camera.
+   *
+   */
+  @CalledByNative
+  private void onSwitchCamera(@NonNull CallContext callContext)
+  {
+    Log.i(TAG, "onSwitchCamera():");
+    callContext.switchCamera();
+  }
+
+  /**
+   *
+   * Envoked by native CallManager when closing down a call to
+   * shutdown media.
+   *
+   */
+  @CalledByNative
+  private void onCloseMedia(@NonNull CallContext callContext)
+  {
+    Log.i(TAG, "onCloseMedia():");
+    callContext.setVideoEnabled(false);
+  }
+
+  @CalledByNative
+  private void closeConnection(Connection connection) {
+    Log.i(TAG, "closeConnection(): " + connection);
+    connection.shutdown();
+  }
+
+  @CalledByNative
+  private void closeCall(@NonNull CallContext callContext) {
+    Log.i(TAG, "closeCall():");
+    callContext.dispose();
+  }
+
+  @CalledByNative
+  private void onStartCall(Remote remote, long callId, boolean isOutgoing) {
+    Log.i(TAG, "onStartCall():");
+    observer.onStartCall(remote, new CallId(callId), new Boolean(isOutgoing));
+  }
+
+  @CalledByNative
+  private void onEvent(long callId, Remote remote, CallEvent event) {
+    Log.i(TAG, "onEvent():");
+    observer.onCallEvent(new CallId(callId), remote, event);
+  }
+
+  @CalledByNative
+  private void onRemoteVideoOrientation(long callId, Remote remote, int remoteDevice, int degrees) {
+    Log.i(TAG, "onRemoteVideoOrientation():");
+    observer.onRemoteVideoOrientation(new CallId(callId), remote, new Integer(remoteDevice), new Integer(degrees));
+  }
+
+  @CalledByNative
+  private void onReceivedDtmf(long callId, Remote remote, int remoteDevice, char tone) {
+    Log.i(TAG, "onReceivedDtmf():");
+    observer.onReceivedDtmf(new CallId(callId), remote, new Integer(remoteDevice), new Character(tone));
+  }
+
+  @CalledByNative
+  private void onRouteDowngradedToRelay(long callId, Remote remote, int remoteDevice, String localCandidateType, String remoteCandidateType, String protocol) {
+    Log.i(TAG, "onRouteDowngradedToRelay():");
+    observer.onRouteDowngradedToRelay(new CallId(callId), remote, new Integer(remoteDevice), localCandidateType, remoteCandidateType, protocol);
+  }
+
+  @CalledByNative
+  private void onRouteUpgradedToDirect(long callId, Remote remote, int remoteDevice, String localCandidateType, String remoteCandidateType, String protocol) {
+    Log.i(TAG, "onRouteUpgradedToDirect():");
+    observer.onRouteUpgradedToDirect(new CallId(callId), remote, new Integer(remoteDevice), localCandidateType, remoteCandidateType, protocol);
+  }
+
+  @CalledByNative
+  private void onNetworkRouteChanged(long callId, Remote remote, NetworkAdapterType localAdapterType, boolean relayed) {
+    Log.i(TAG, "onNetworkRouteChanged(): " + localAdapterType + ", relayed: " + relayed);
+    observer.onNetworkRouteChanged(new CallId(callId), remote, localAdapterType, new Boolean(relayed));
+  }
+
+  @CalledByNative
+  private void onSafetyNumbers(long callId, Remote remote, int remoteDevice, String safetyNumbers) {
+    Log.i(TAG, "onSafetyNumbers():");
+    observer.onSafetyNumbers(new CallId(callId), remote, new Integer(remoteDevice), safetyNumbers);
+  }
+
+  @CalledByNative
+  private void onIceGatheringStateChanged(long callId, Remote remote, int remoteDevice, IceGatheringState state) {
+    Log.i(TAG, "onIceGatheringStateChanged(): " + state);
+    observer.onIceGatheringStateChanged(new CallId(callId), remote, new Integer(remoteDevice), state);
+  }
+
+  @CalledByNative
+  private void onDataChannelBufferedAmountLow(long callId, Remote remote, int remoteDevice) {
+    Log.i(TAG, "onDataChannelBufferedAmountLow():");
+    observer.onDataChannelBufferedAmountLow(new CallId(callId), remote, new Integer(remoteDevice));
+  }
+
+  @CalledByNative
+  private void onAppMessageReceived(long callId, Remote remote, int remoteDevice, byte[] payload) {
+    Log.i(TAG, "onAppMessageReceived(): size: " + payload.length);
+    observer.onAppMessageReceived(new CallId(callId), remote, new Integer(remoteDevice), payload);
+  }
+
+  @CalledByNative
+  private void onCallDurationTick(long callId, Remote remote, long durationMillis) {
+    observer.onCallDurationTick(new CallId(callId), remote, new Long(durationMillis));
+  }
+
+  @CalledByNative
+  private void onStatsReport(long callId, Remote remote, CallStats stats) {
+    observer.onStatsReport(new CallId(callId), remote, stats);
+  }
+
+  @CalledByNative
+  private void onAudioLevels(long callId, Remote remote, double captured, double received) {
+    observer.onAudioLevels(new CallId(callId), remote, captured, received);
+  }
+
+  @CalledByNative
+  private void onBandwidthEstimate(long callId, Remote remote, long sendBps, long receiveBps) {
+    observer.onBandwidthEstimate(new CallId(callId), remote, sendBps, receiveBps);
+  }
+
+  @CalledByNative
+  private void onCallRecord(long callId, Remote remote, CallRecord record) {
+    Log.i(TAG, "onCallRecord(): " + record);
+    observer.onCallRecord(new CallId(callId), remote, record);
+  }
+
+  @CalledByNative
+  private void onCallFailed(long callId, Remote remote, CallFailureReason reason) {
+    Log.i(TAG, "onCallFailed(): " + reason);
+    observer.onCallFailed(new CallId(callId), remote, reason);
+  }
+
+  @CalledByNative
+  private void onCallConcluded(long callId, Remote remote) {
+    Log.i(TAG, "onCallConcluded():");
+    observer.onCallConcluded(new CallId(callId), remote);
+  }
+
+  @CalledByNative
+  private void onShutdownComplete() {
+    Log.i(TAG, "onShutdownComplete():");
+    observer.onShutdownComplete();
+  }
+
+  @CalledByNative
+  private void persistPendingSignaling(List<PendingSignaling> pending) {
+    Log.i(TAG, "persistPendingSignaling(): size: " + pending.size());
+    observer.persistPendingSignaling(pending);
+  }
+
+  @CalledByNative
+  @Nullable
+  private List<PendingSignaling> getPersistedSignaling() {
+    Log.i(TAG, "getPersistedSignaling():");
+    return observer.getPersistedSignaling();
+  }
+
+  @CalledByNative
+  private void onFatalError(long callId, String message) {
+    Log.e(TAG, "onFatalError(): " + message);
+    poisoned = true;
+    observer.onFatalError(new CallId(callId), message);
+  }
+
+  @CalledByNative
+  private boolean shouldRing(long callId, Remote remote, int remoteDevice, long timestamp, boolean isVideo) {
+    Log.i(TAG, "shouldRing():");
+    return observer.shouldRing(new CallId(callId), remote, new Integer(remoteDevice), new Long(timestamp), new Boolean(isVideo));
+  }
+
+  @CalledByNative
+  private boolean shouldTransfer(long callId, Remote remote, String target) {
+    Log.i(TAG, "shouldTransfer():");
+    return observer.shouldTransfer(new CallId(callId), remote, target);
+  }
+
+  @CalledByNative
+  @Nullable
+  private List<PeerConnection.IceServer> getTurnServers(long callId, Remote remote) {
+    Log.i(TAG, "getTurnServers():");
+    return observer.getTurnServers(new CallId(callId), remote);
+  }
+
+  @CalledByNative
+  @Nullable
+  private List<CodecCapability> getCodecCapabilities() {
+    Log.i(TAG, "getCodecCapabilities():");
+    return observer.getCodecCapabilities();
+  }
+
+  @CalledByNative
+  private void onReceivedAnswerMetadata(long callId, Remote remote, int remoteDevice, byte[] metadata) {
+    Log.i(TAG, "onReceivedAnswerMetadata(): size: " + metadata.length);
+    observer.onReceivedAnswerMetadata(new CallId(callId), remote, new Integer(remoteDevice), metadata);
+  }
+
+  @CalledByNative
+  @Nullable
+  private byte[] seal(long callId, Remote remote, int remoteDevice, byte[] opaque) {
+    return observer.seal(new CallId(callId), remote, new Integer(remoteDevice), opaque);
+  }
+
+  @CalledByNative
+  @Nullable
+  private byte[] open(long callId, int remoteDevice, byte[] sealed) {
+    return observer.open(new CallId(callId), new Integer(remoteDevice), sealed);
+  }
+
+  @CalledByNative
+  private void onSendPreOffer(long callId, Remote remote, int remoteDevice) {
+    Log.i(TAG, "onSendPreOffer():");
+    observer.onSendPreOffer(new CallId(callId), remote, new Integer(remoteDevice));
+  }
+
+  @CalledByNative
+  private void onReceivedPreOffer(long callId, Remote remote, int remoteDevice) {
+    Log.i(TAG, "onReceivedPreOffer():");
+    observer.onReceivedPreOffer(new CallId(callId), remote, new Integer(remoteDevice));
+  }
+
+  @CalledByNative
+  private void onSendOffer(long callId, Remote remote, int remoteDevice, boolean broadcast, String sdp, MediaDescriptor descriptor) {
+    Log.i(TAG, "onSendOffer(): " + descriptor);
+    observer.onSendOffer(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast), sdp, descriptor);
+  }
+
+  @CalledByNative
+  private void onSendAnswer(long callId, Remote remote, int remoteDevice, boolean broadcast, String sdp, MediaDescriptor descriptor) {
+    Log.i(TAG, "onSendAnswer(): " + descriptor);
+    observer.onSendAnswer(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast), sdp, descriptor);
+  }
+
+  @CalledByNative
+  private void onSendIceCandidates(long callId, Remote remote, int remoteDevice, boolean broadcast, List<IceCandidate> iceCandidates) {
+    Log.i(TAG, "onSendIceCandidates():");
+    observer.onSendIceCandidates(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast), iceCandidates);
+  }
+
+  @CalledByNative
+  private void onSendHangup(long callId, Remote remote, int remoteDevice, boolean broadcast, int hangupType) {
+    Log.i(TAG, "onSendHangup():");
+    observer.onSendHangup(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast), HangupType.fromNativeIndex(hangupType));
+  }
+
+  @CalledByNative
+  private void onSendBusy(long callId, Remote remote, int remoteDevice, boolean broadcast) {
+    Log.i(TAG, "onSendBusy():");
+    observer.onSendBusy(new CallId(callId), remote, new Integer(remoteDevice), new Boolean(broadcast));
+  }
+
+  @CalledByNative
+  private void onSendRinging(long callId, Remote remote, int remoteDevice) {
+    Log.i(TAG, "onSendRinging():");
+    observer.onSendRinging(new CallId(callId), remote, new Integer(remoteDevice));
+  }
+
+  @CalledByNative
+  private boolean compareRemotes(Remote remote1, Remote remote2) {
+    Log.i(TAG, "compareRemotes():");
+    if (remote1 != null) {
+      return remote1.recipientEquals(remote2);
+    }
+    return false;
+  }
+
+  /**
+   *
+   * Contains parameters for creating Connection objects
+   */
+  static class CallContext {
+
+    @NonNull  private final String TAG = CallManager.CallContext.class.getSimpleName();
+    /** CallId */
+    @NonNull  public final  CallId                         callId;
+    /** Connection factory */
+    @NonNull  public final  PeerConnectionFactory          peerConnectionFactory;
+    /** Remote camera surface renderer */
+    @NonNull  public final  SurfaceViewRenderer            remoteRenderer;
+    /** Camera controller */
+    @NonNull  public final  CameraControl                  cameraControl;
+    /** If true, use TURN servers */
+              public final  boolean                        hideIp;
+    /** Audio device shared by all connections of the call */
+    @NonNull  private final AudioDeviceModule              audioDeviceModule;
+    /** Routes the audio of the call */
+    @NonNull  private final AudioManager                   audioManager;
+    @Nullable public final  VideoSource                    videoSource;
+    @Nullable public final  VideoTrack                     videoTrack;
+    /** If true, local audio recording and transmission is enabled */
+              private       boolean                        audioEnabled;
+    /** If true, audio playout and recording have been started */
+              private       boolean                        communicationMode;
+    /** If true, Telecom routes the audio of the call */
+              private       boolean                        telecom;
+
+    public CallContext(@NonNull CallId                         callId,
+                       @NonNull Context                        context,
+                       @NonNull EglBase                        eglBase,
+                       @NonNull SurfaceViewRenderer            localRenderer,
+                       @NonNull SurfaceViewRenderer            remoteRenderer,
+                       @NonNull CameraControl                  camera,
+                                boolean                        hideIp,
+                       @NonNull AudioRecordErrorCallback       audioRecordErrorCallback) {
+
+      Log.i(TAG, "ctor(): " + callId);
+
+      this.callId         = callId;
+      this.remoteRenderer = remoteRenderer;
+      this.cameraControl  = camera;
+      this.hideIp         = hideIp;
+      this.audioManager   = (AudioManager) context.getSystemService(Context.AUDIO_SERVICE);
+
+      VideoEncoderFactory encoderFactory = new DefaultVideoEncoderFactory(eglBase.getEglBaseContext(),
+                                                                          true, true);
+      VideoDecoderFactory decoderFactory = new DefaultVideoDecoderFactory(eglBase.getEglBaseContext());
+
+      this.audioDeviceModule = JavaAudioDeviceModule.builder(context)
+        .setAudioRecordErrorCallback(audioRecordErrorCallback)
+        .createAudioDeviceModule();
+
+      this.peerConnectionFactory = PeerConnectionFactory.builder()
+        .setOptions(new PeerConnectionFactoryOptions())
+        .setAudioDeviceModule(audioDeviceModule)
+        .setVideoEncoderFactory(encoderFactory)
+        .setVideoDecoderFactory(decoderFactory)
+        .createPeerConnectionFactory();
+
+      // Create a video track that will be shared across all
+      // connection objects.  It must be disposed manually.
+      if (cameraControl.hasCapturer()) {
+        this.videoSource = peerConnectionFactory.createVideoSource(false);
+        this.videoTrack  = peerConnectionFactory.createVideoTrack("ARDAMSv0", videoSource);
+        videoTrack.setEnabled(false);
+
+        // Connect camera as the local video source.
+        cameraControl.initCapturer(videoSource.getCapturerObserver());
+        videoTrack.addSink(localRenderer);
+      } else {
+        this.videoSource = null;
+        this.videoTrack  = null;
+      }
+
+    }
+
+    void setVideoEnabled(boolean enable) {
+      Log.i(TAG, "setVideoEnabled(): " + callId);
+      if (videoTrack != null) {
+        videoTrack.setEnabled(enable);
+        cameraControl.setEnabled(enable);
+      }
+    }
+
+    void setAudioOutput(@NonNull AudioOutput output) {
+      Log.i(TAG, "setAudioOutput(): " + callId + ", " + output);
+
+      boolean bluetooth = output == AudioOutput.BLUETOOTH;
+      if (bluetooth) {
+        audioManager.startBluetoothSco();
+      } else {
+        audioManager.stopBluetoothSco();
+      }
+      audioManager.setBluetoothScoOn(bluetooth);
+
+      // The earpiece and a wired headset are picked by the system
+      // once the speaker is off.
+      audioManager.setSpeakerphoneOn(output == AudioOutput.SPEAKER);
+    }
+
+    void switchCamera() {
+      Log.i(TAG, "switchCamera(): " + callId);
+      if (videoTrack != null) {
+        cameraControl.flip();
+      }
+    }
+
+    void setAudioEnabled(boolean enable) {
+      Log.i(TAG, "setAudioEnabled(): " + callId);
+      audioEnabled = enable;
+    }
+
+    void setCommunicationMode() {
+      Log.i(TAG, "setCommunicationMode(): " + callId);
+      communicationMode = true;
+    }
+
+    void dispose() {
+      Log.i(TAG, "dispose(): " + callId);
+
+      if (cameraControl != null) {
+        cameraControl.setEnabled(false);
+      }
+
+      if (videoSource != null) {
+        videoSource.dispose();
+      }
+
+      if (videoTrack != null) {
+        videoTrack.dispose();
+      }
+
+      peerConnectionFactory.dispose();
+      audioDeviceModule.release();
+    }
+
+    class PeerConnectionFactoryOptions extends PeerConnectionFactory.Options {
+      public PeerConnectionFactoryOptions() {
+        this.networkIgnoreMask = 1 << 4;
+      }
+    }
+
+  }
+
+  /**
+   *
+   * Enumeration of simple call status events
+   *
+   */
+  public enum CallEvent {
+
+    /** Inbound call only: The call signaling (ICE) is complete. */
+    LOCAL_RINGING,
+
+    /** Outbound call only: The call signaling (ICE) is complete. */
+    REMOTE_RINGING,
+
+    /** The local side has accepted and connected the call. */
+    LOCAL_CONNECTED,
+
+    /** The remote side has accepted and connected the call. */
+    REMOTE_CONNECTED,
+
+    /** The call ended because of a local hangup. */
+    ENDED_LOCAL_HANGUP,
+
+    /** The call ended because of a remote hangup. */
+    ENDED_REMOTE_HANGUP,
+
+    /** The call ended because of a remote busy message. */
+    ENDED_REMOTE_BUSY,
+
+    /** The call ended because of glare, the offer received from the same remote won. */
+    ENDED_REMOTE_GLARE,
+
+    /** The call ended because it timed out during setup. */
+    ENDED_TIMEOUT,
+
+    /** The call ended because of an internal error condition. */
+    ENDED_INTERNAL_FAILURE,
+
+    /** The call ended because a signaling message couldn't be sent. */
+    ENDED_SIGNALING_FAILURE,
+
+    /** The call ended because setting up the connection failed. */
+    ENDED_CONNECTION_FAILURE,
+
+    /** The call ended because the application wanted to drop the call. */
+    ENDED_APP_DROPPED_CALL,
+
+    /** The remote peer indicates its video stream is enabled. */
+    REMOTE_VIDEO_ENABLE,
+
+    /** The remote peer indicates its video stream is disabled. */
+    REMOTE_VIDEO_DISABLE,
+
+    /** The call dropped while connected and is now reconnecting. */
+    RECONNECTING,
+
+    /** The call dropped while connected and is now reconnected. */
+    RECONNECTED,
+
+    /** The received offer is expired. */
+    ENDED_RECEIVED_OFFER_EXPIRED,
+
+    /** Received an offer while already handling an active call. */
+    ENDED_RECEIVED_OFFER_WHILE_ACTIVE,
+
+    /** The received offer lacked the preferred codecs, a common codec was negotiated instead. */
+    CODEC_FALLBACK,
+
+    /** The remote side did not answer the renegotiation offer sent while reconnecting. */
+    RENEGOTIATION_TIMEOUT,
+
+    /** The audio device failed to initialize, for an unknown reason. */
+    AUDIO_DEVICE_FAILURE,
+
+    /** The audio device failed to initialize because microphone access was denied. */
+    AUDIO_DEVICE_PERMISSION_DENIED,
+
+    /** The audio device failed to initialize because it is in use. */
+    AUDIO_DEVICE_BUSY,
+
+    /** The call ended because the audio device failed to initialize. */
+    ENDED_AUDIO_DEVICE_FAILURE,
+
+    /** The call ended because every remote device declined it. */
+    ENDED_REMOTE_DECLINED,
+
+    /** Group call only: A participant connected to the call. */
+    GROUP_PARTICIPANT_JOINED,
+
+    /** Group call only: A participant disconnected from the call. */
+    GROUP_PARTICIPANT_LEFT,
+
+    /** The remote peer muted its audio. */
+    REMOTE_AUDIO_MUTED,
+
+    /** The remote peer unmuted its audio. */
+    REMOTE_AUDIO_UNMUTED,
+
+    /** The received offer lost to the active call of the same remote peer (glare). */
+    ENDED_RECEIVED_OFFER_WITH_GLARE,
+
+    /** The call ended because it was accepted on another device. */
+    ENDED_REMOTE_HANGUP_ACCEPTED,
+
+    /** The call ended because another device is busy. */
+    ENDED_REMOTE_HANGUP_BUSY,
+
+    /** The call ended because the remote side needs permission to take calls from the local user. */
+    ENDED_REMOTE_HANGUP_NEED_PERMISSION,
+
+    /** The incoming call ended because {@link Observer#shouldRing} refused to ring it. */
+    ENDED_RING_REFUSED,
+
+    /** The remote peer put the call on hold. */
+    REMOTE_ON_HOLD,
+
+    /** The remote peer resumed the call from hold. */
+    REMOTE_RESUMED,
+
+    /** The call ended because the application cancelled its setup. */
+    ENDED_SETUP_CANCELLED,
+
+    /** The remote peer declined to transfer the call. */
+    TRANSFER_DECLINED,
+
+    /** The call ended because the remote peer accepted its transfer. */
+    ENDED_TRANSFERRED,
+
+    /** Outbound call only: The call ended because of a remote busy message, and the remote peer will be called again. */
+    ENDED_REMOTE_BUSY_RETRY,
+
+    /** Outbound call only: Calling the busy remote peer again was cancelled. */
+    BUSY_RETRY_CANCELLED,
+
+    /** The credentials of the ICE servers are about to expire. */
+    ICE_SERVERS_EXPIRING,
+
+    /** The remote peer started recording the call. */
+    REMOTE_RECORDING_STARTED,
+
+    /** The remote peer stopped recording the call. */
+    REMOTE_RECORDING_STOPPED,
+
+    /** No media arrived from the remote peer for the media stall timeout, although ICE is connected. */
+    REMOTE_MEDIA_STALLED,
+
+    /** Media arrives from the remote peer again. */
+    REMOTE_MEDIA_RESUMED,
+
+    /** The remote peer started sharing its screen. */
+    REMOTE_SHARING_SCREEN,
+
+    /** The remote peer stopped sharing its screen. */
+    REMOTE_STOPPED_SHARING_SCREEN,
+
+    /** The call ended because the remote peer closed its media without hanging up. */
+    ENDED_REMOTE_MEDIA_CLOSED;
+
+    @CalledByNative
+    static CallEvent fromNativeIndex(int nativeIndex) {
+      return values()[nativeIndex];
+    }
+
+  }
+
+  /**
+   *
+   * Enumeration of why a call failed
+   *
+   */
+  public enum CallFailureReason {
+
+    /** ICE could not connect, or lost the connection for good. */
+    ICE_FAILED,
+
+    /** The call did not progress in time, e.g. the signaling never arrived. */
+    SIGNALING_TIMEOUT,
+
+    /** The media keys could not be negotiated. */
+    SRTP_KEY_ERROR,
+
+    /** The PeerConnection could not be created. */
+    PEER_CONNECTION_CREATE_FAILED,
+
+    /** Any other internal failure. */
+    INTERNAL_FSM_ERROR;
+
+    @CalledByNative
+    static CallFailureReason fromNativeIndex(int nativeIndex) {
+      return values()[nativeIndex];
+    }
+
+  }
+
+  /**
+   *
+   * Enumeration of the ICE candidate gathering states
+   *
+   */
+  public enum IceGatheringState {
+
+    /** Gathering has not started. */
+    NEW,
+
+    /** Local candidates are being gathered. */
+    GATHERING,
+
+    /** All candidates were gathered, even if only host candidates were found. */
+    COMPLETE;
+
+    @CalledByNative
+    static IceGatheringState fromNativeIndex(int nativeIndex) {
+      return values()[nativeIndex];
     }
 
-    void dispose() {
-      Log.i(TAG, "dispose(): " + callId);
+  }
 
-      if (cameraControl != null) {
-        cameraControl.setEnabled(false);
-      }
+  /**
+   *
+   * Enumeration of the kinds of network interface an ICE route can
+   * run over
+   *
+   */
+  public enum NetworkAdapterType {
 
-      if (videoSource != null) {
-        videoSource.dispose();
-      }
+    /** The kind of interface is not known. */
+    UNKNOWN(0),
 
-      if (videoTrack != null) {
-        videoTrack.dispose();
-      }
+    /** A wired network. */
+    ETHERNET(1),
 
-      peerConnectionFactory.dispose();
+    /** A Wi-Fi network. */
+    WIFI(2),
+
+    /** A mobile data network. */
+    CELLULAR(4),
+
+    /** A VPN tunnel. */
+    VPN(8),
+
+    /** The loopback interface. */
+    LOOPBACK(16);
+
+    private final int nativeValue;
+
+    NetworkAdapterType(int nativeValue) {
+      this.nativeValue = nativeValue;
     }
 
-    class PeerConnectionFactoryOptions extends PeerConnectionFactory.Options {
-      public PeerConnectionFactoryOptions() {
-        this.networkIgnoreMask = 1 << 4;
+    @CalledByNative
+    static NetworkAdapterType fromNativeIndex(int nativeIndex) {
+      for (NetworkAdapterType type : values()) {
+        if (type.nativeValue == nativeIndex) {
+          return type;
+        }
       }
+      return UNKNOWN;
     }
 
   }
 
   /**
    *
-   * Enumeration of simple call status events
+   * Enumeration of how the sent video degrades when bandwidth or CPU
+   * is constrained
    *
    */
-  public enum CallEvent {
+  public enum DegradationPreference {
 
-    /** Inbound call only: The call signaling (ICE) is complete. */
-    LOCAL_RINGING,
+    /** Lower the resolution, e.g. for camera video. */
+    MAINTAIN_FRAMERATE,
 
-    /** Outbound call only: The call signaling (ICE) is complete. */
-    REMOTE_RINGING,
+    /** Lower the framerate, e.g. for screen sharing. */
+    MAINTAIN_RESOLUTION,
 
-    /** The local side has accepted and connected the call. */
-    LOCAL_CONNECTED,
+    /** Lower both the resolution and the framerate. */
+    BALANCED;
 
-    /** The remote side has accepted and connected the call. */
-    REMOTE_CONNECTED,
+  }
 
-    /** The call ended because of a local hangup. */
-    ENDED_LOCAL_HANGUP,
+  /**
+   *
+   * Enumeration of how much bandwidth a call may use
+   *
+   */
+  public enum BandwidthMode {
 
-    /** The call ended because of a remote hangup. */
-    ENDED_REMOTE_HANGUP,
+    /** Audio only, at a low bitrate, e.g. for metered connections. */
+    LOW,
 
-    /** The call ended because of a remote busy message. */
-    ENDED_REMOTE_BUSY,
+    /** The usual bitrates for audio and video. */
+    NORMAL,
 
-    /** The call ended because of glare (received offer from same remote). */
-    ENDED_REMOTE_GLARE,
+    /** Higher video bitrates, for fast unmetered connections. */
+    HIGH;
 
-    /** The call ended because it timed out during setup. */
-    ENDED_TIMEOUT,
+  }
 
-    /** The call ended because of an internal error condition. */
-    ENDED_INTERNAL_FAILURE,
+  /**
+   *
+   * Enumeration of the audio outputs of a call
+   *
+   */
+  public enum AudioOutput {
+
+    /** The earpiece, for a phone held to the ear. */
+    EARPIECE,
+
+    /** The loudspeaker. */
+    SPEAKER,
+
+    /** A Bluetooth headset. */
+    BLUETOOTH,
+
+    /** A wired headset. */
+    WIRED;
+
+    static AudioOutput fromNativeIndex(int nativeIndex) {
+      return values()[nativeIndex];
+    }
+
+  }
+
+  /**
+   *
+   * Enumeration of why the audio device failed to initialize
+   *
+   */
+  public enum AudioDeviceError {
+
+    /** The reason is not known. */
+    UNKNOWN,
+
+    /** Microphone access was denied. */
+    PERMISSION_DENIED,
+
+    /** The device is in use, e.g. by another call. */
+    BUSY;
+
+  }
+
+  /**
+   *
+   * Enumeration of why a call was hung up, carried by the hangup
+   * message
+   *
+   */
+  public enum HangupType {
+
+    /** The call was hung up, or was never answered. */
+    NORMAL,
+
+    /** The call was accepted on another device. */
+    ACCEPTED,
+
+    /** The call was declined. */
+    DECLINED,
+
+    /** Another device is busy. */
+    BUSY,
+
+    /** The user needs to grant permission before taking calls. */
+    NEED_PERMISSION;
+
+    @CalledByNative
+    static HangupType fromNativeIndex(int nativeIndex) {
+      return values()[nativeIndex];
+    }
+
+  }
+
+  /**
+   *
+   * Interface for handling CallManager events and errors
+   *
+   */
+  public interface Observer {
+
+    /**
+     *
+     * Notification to start a call
+     *
+     * @param remote      remote peer of the call
+     * @param callId      callId for the call
+     * @param isOutgoing  true for an outgoing call, false for incoming
+     *
+     */
+    void onStartCall(Remote remote, CallId callId, Boolean isOutgoing);
+
+    /**
+     *
+     * Notification of an event for an active call sent to the UI
+     *
+     * @param callId callId for the call
+     * @param remote remote peer of the call
+     * @param event  event to be notified of
+     *
+     */
+    void onCallEvent(CallId callId, Remote remote, CallEvent event);
+
+    /**
+     *
+     * Notification that the rotation of the remote video changed
+     *
+     * Only sent when the video orientation RTP header extension was
+     * negotiated with the remote peer.
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the call
+     * @param remoteDevice  deviceId of remote peer
+     * @param degrees       rotation of the remote video: 0, 90, 180 or 270
+     *
+     */
+    void onRemoteVideoOrientation(CallId callId, Remote remote, Integer remoteDevice, Integer degrees);
+
+    /**
+     *
+     * Notification that a DTMF tone was received from the remote peer
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the call
+     * @param remoteDevice  deviceId of remote peer
+     * @param tone          the tone received: 0-9, A-D, # or *
+     *
+     */
+    void onReceivedDtmf(CallId callId, Remote remote, Integer remoteDevice, Character tone);
+
+    /**
+     *
+     * Notification that the route of the connected call changed from
+     * direct to relayed through a TURN server
+     *
+     * Only sent once the new route has held for the route change
+     * debounce period.
+     *
+     * @param callId               callId for the call
+     * @param remote               remote peer of the call
+     * @param remoteDevice         deviceId of remote peer
+     * @param localCandidateType   type of the local ICE candidate: local, stun, prflx or relay
+     * @param remoteCandidateType  type of the remote ICE candidate
+     * @param protocol             transport protocol of the route, e.g. udp
+     *
+     */
+    void onRouteDowngradedToRelay(CallId callId, Remote remote, Integer remoteDevice, String localCandidateType, String remoteCandidateType, String protocol);
+
+    /**
+     *
+     * Notification that the route of the connected call changed from
+     * relayed to direct
+     *
+     * Only sent once the new route has held for the route change
+     * debounce period.
+     *
+     * @param callId               callId for the call
+     * @param remote               remote peer of the call
+     * @param remoteDevice         deviceId of remote peer
+     * @param localCandidateType   type of the local ICE candidate: local, stun, prflx or relay
+     * @param remoteCandidateType  type of the remote ICE candidate
+     * @param protocol             transport protocol of the route, e.g. udp
+     *
+     */
+    void onRouteUpgradedToDirect(CallId callId, Remote remote, Integer remoteDevice, String localCandidateType, String remoteCandidateType, String protocol);
+
+    /**
+     *
+     * Notification of the network the ICE route of the connected call
+     * runs over, e.g. for "connecting via relay" or "switched to
+     * cellular" indicators.  Sent once the call connects, then
+     * whenever the kind of network or relaying of the route changes.
+     *
+     * @param callId            callId for the call
+     * @param remote            remote peer of the call
+     * @param localAdapterType  kind of the local network interface
+     * @param relayed           true if the route goes through a TURN server
+     *
+     */
+    void onNetworkRouteChanged(CallId callId, Remote remote, NetworkAdapterType localAdapterType, Boolean relayed);
+
+    /**
+     *
+     * Notification of the safety numbers of the connected call, for
+     * the users to read aloud and compare.  Matching numbers show
+     * that no one substituted the DTLS fingerprints on the signaling
+     * path.  Only sent when both sides sent a fingerprint.
+     *
+     * @param callId         callId for the call
+     * @param remote         remote peer of the call
+     * @param remoteDevice   deviceId of remote peer
+     * @param safetyNumbers  the safety numbers, in groups of digits
+     *
+     */
+    void onSafetyNumbers(CallId callId, Remote remote, Integer remoteDevice, String safetyNumbers);
+
+    /**
+     *
+     * Notification that the ICE candidate gathering state of a
+     * connection changed, e.g. for a "finding network path"
+     * indicator
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the call
+     * @param remoteDevice  deviceId of remote peer
+     * @param state         the new gathering state
+     *
+     */
+    void onIceGatheringStateChanged(CallId callId, Remote remote, Integer remoteDevice, IceGatheringState state);
+
+    /**
+     *
+     * Notification that the data channel of the connected call drained
+     * after refusing a send past the high water mark
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the call
+     * @param remoteDevice  deviceId of remote peer
+     *
+     */
+    void onDataChannelBufferedAmountLow(CallId callId, Remote remote, Integer remoteDevice);
+
+    /**
+     *
+     * Notification of an application message the remote peer sent
+     * with {@link CallManager#sendAppMessage}
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the call
+     * @param remoteDevice  deviceId of remote peer
+     * @param payload       the message
+     *
+     */
+    void onAppMessageReceived(CallId callId, Remote remote, Integer remoteDevice, byte[] payload);
+
+    /**
+     *
+     * Notification of how long the connected call has lasted, every
+     * second, if enabled with {@link CallManager#setCallDurationTicks}
+     *
+     * @param callId          callId for the call
+     * @param remote          remote peer of the call
+     * @param durationMillis  time since the media first connected
+     *
+     */
+    void onCallDurationTick(CallId callId, Remote remote, Long durationMillis);
+
+    /**
+     *
+     * Notification of the quality of the active call, every stats
+     * interval, if enabled with {@link CallManager#setStatsInterval}
+     *
+     * @param callId  callId for the call
+     * @param remote  remote peer of the call
+     * @param stats   quality of the call over the last interval
+     *
+     */
+    void onStatsReport(CallId callId, Remote remote, CallStats stats);
+
+    /**
+     *
+     * Notification of the audio levels of the active call, every
+     * audio levels interval, if enabled with
+     * {@link CallManager#setAudioLevelsInterval}
+     *
+     * @param callId    callId for the call
+     * @param remote    remote peer of the call
+     * @param captured  level of the captured audio, from 0.0 to 1.0
+     * @param received  level of the received audio, from 0.0 to 1.0
+     *
+     */
+    void onAudioLevels(CallId callId, Remote remote, double captured, double received);
+
+    /**
+     *
+     * Notification of the bandwidth available for sending and
+     * receiving the media of the active call, every bandwidth estimate
+     * interval, if enabled with
+     * {@link CallManager#setBandwidthEstimateInterval}
+     *
+     * @param callId      callId for the call
+     * @param remote      remote peer of the call
+     * @param sendBps     estimated send bandwidth, in bits per second
+     * @param receiveBps  estimated receive bandwidth, in bits per second
+     *
+     */
+    void onBandwidthEstimate(CallId callId, Remote remote, long sendBps, long receiveBps);
+
+    /**
+     *
+     * Asks whether an incoming call may ring, e.g. to apply a
+     * blocklist or do-not-disturb.  Called once the call has
+     * connected, just before it would ring.  If false, the call is
+     * hung up and ends with {@link CallEvent#ENDED_RING_REFUSED}.
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the call
+     * @param remoteDevice  deviceId of remote peer
+     * @param timestamp     when the offer was sent, in milliseconds since the epoch
+     * @param isVideo       true if the offer includes video
+     *
+     * @return true if the call may ring
+     *
+     */
+    Boolean shouldRing(CallId callId, Remote remote, Integer remoteDevice, Long timestamp, Boolean isVideo);
+
+    /**
+     *
+     * Asks whether to accept the remote side's request to transfer
+     * the call.  An application that accepts places the call to the
+     * target itself, and the remote side then hangs up.
+     *
+     * @param callId  callId for the call
+     * @param remote  remote peer of the call
+     * @param target  the peer the call is transferred to
+     *
+     * @return true if the transfer is accepted
+     *
+     */
+    Boolean shouldTransfer(CallId callId, Remote remote, String target);
+
+    /**
+     *
+     * Asks for fresh STUN and TURN servers, e.g. short-lived TURN
+     * credentials fetched from the application's server.  Called
+     * whenever a connection is created and whenever it restarts ICE,
+     * synchronously on a native thread.
+     *
+     * @param callId  callId for the call
+     * @param remote  remote peer of the call
+     *
+     * @return the servers, or null or an empty list to keep the
+     *         servers given to {@link CallManager#proceed}
+     *
+     */
+    @Nullable List<PeerConnection.IceServer> getTurnServers(CallId callId, Remote remote);
+
+    /**
+     *
+     * Asks which codecs the device encodes or decodes in hardware,
+     * e.g. from android.media.MediaCodecList.  They are merged with
+     * the codecs WebRTC supports in software.
+     *
+     * @return the hardware codecs, or null if there are none
+     *
+     */
+    @Nullable List<CodecCapability> getCodecCapabilities();
 
-    /** The call ended because a signaling message couldn't be sent. */
-    ENDED_SIGNALING_FAILURE,
+    /**
+     *
+     * The record of a concluded call, e.g. for the call history.
+     * Delivered just before {@link #onCallConcluded}.
+     *
+     * @param callId  callId for the call
+     * @param remote  remote peer of the call
+     * @param record  how the call went
+     *
+     */
+    void onCallRecord(CallId callId, Remote remote, CallRecord record);
 
-    /** The call ended because setting up the connection failed. */
-    ENDED_CONNECTION_FAILURE,
+    /**
+     *
+     * Why a call failed, e.g. to show an actionable error message.
+     * Delivered just before the ENDED event of the failed call.
+     *
+     * @param callId  callId for the call
+     * @param remote  remote peer of the call
+     * @param reason  why the call failed
+     *
+     */
+    void onCallFailed(CallId callId, Remote remote, CallFailureReason reason);
 
-    /** The call ended because the application wanted to drop the call. */
-    ENDED_APP_DROPPED_CALL,
+    /**
+     *
+     * Notification of that the call is completely concluded
+     *
+     * @param callId callId for the call
+     * @param remote remote peer of the call
+     *
+     */
+    void onCallConcluded(CallId callId, Remote remote);
 
-    /** The remote peer indicates its video stream is enabled. */
-    REMOTE_VIDEO_ENABLE,
+    /**
+     *
+     * Notification that a shutdown of the call manager is complete,
+     * all calls are torn down and the call manager is closed
+     *
+     */
+    void onShutdownComplete();
 
-    /** The remote peer indicates its video stream is disabled. */
-    REMOTE_VIDEO_DISABLE,
+    /**
+     *
+     * Asks to persist the signaling messages waiting to be sent,
+     * replacing any persisted before, so they survive a restart.
+     * Called synchronously on a native thread whenever the queue
+     * changes, with an empty list once it drains.
+     *
+     * @param pending  the messages, in the order they are sent
+     *
+     */
+    void persistPendingSignaling(List<PendingSignaling> pending);
 
-    /** The call dropped while connected and is now reconnecting. */
-    RECONNECTING,
+    /**
+     *
+     * Asks for the signaling messages last persisted, for
+     * {@link CallManager#restorePendingSignaling} to send.  Called
+     * synchronously on a native thread.
+     *
+     * @return the messages, or null if there are none
+     *
+     */
+    @Nullable List<PendingSignaling> getPersistedSignaling();
 
-    /** The call dropped while connected and is now reconnected. */
-    RECONNECTED,
+    /**
+     *
+     * Notification that the native code hit a fatal error, which
+     * would otherwise have aborted the process.
+     *
+     * <p>The native state can no longer be trusted, so every later
+     * method of any CallManager throws.  The application should tear
+     * down its calls and offer calling again once the process
+     * restarts.
+     *
+     * @param callId   callId of the call being handled, 0 if none
+     * @param message  description of the error
+     *
+     */
+    void onFatalError(CallId callId, String message);
 
-    /** The received offer is expired. */
-    ENDED_RECEIVED_OFFER_EXPIRED,
+    /**
+     *
+     * Notification that RingRTC routed the audio of a call to another
+     * output, e.g. to the speaker when the remote side turned its
+     * video on.
+     *
+     * <p>For a call added to Telecom, the output is only suggested,
+     * for the application to apply with Connection.setAudioRoute().
+     *
+     * @param callId  callId for the call
+     * @param output  the audio output now in use
+     *
+     */
+    void onAudioOutputChanged(CallId callId, AudioOutput output);
 
-    /** Received an offer while already handling an active call. */
-    ENDED_RECEIVED_OFFER_WHILE_ACTIVE;
+    /**
+     *
+     * Notification that RingRTC treats a call as managed by Telecom,
+     * after {@link CallManager#callAddedToTelecom}.
+     *
+     * @param callId  callId for the call
+     *
+     */
+    void onCallAddedToTelecom(CallId callId);
 
-    @CalledByNative
-    static CallEvent fromNativeIndex(int nativeIndex) {
-      return values()[nativeIndex];
-    }
+    /**
+     *
+     * Notification that RingRTC held or resumed a call added to
+     * Telecom by itself, e.g. to accept a waiting call.  The
+     * application updates the Connection of the call with
+     * Connection.setOnHold() or Connection.setActive().
+     *
+     * @param callId  callId for the call
+     * @param onHold  if true, the call is on hold, otherwise active
+     *
+     */
+    void onTelecomHoldRequested(CallId callId, boolean onHold);
 
-  }
+    /**
+     *
+     * Notification of the metadata the callee attached to its answer
+     * with {@link CallManager#setAnswerMetadata}.  Sent before the
+     * answer is applied, so features can be negotiated before the
+     * media connects.
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the call
+     * @param remoteDevice  deviceId of remote peer
+     * @param metadata      the opaque metadata
+     *
+     */
+    void onReceivedAnswerMetadata(CallId callId, Remote remote, Integer remoteDevice, byte[] metadata);
 
-  /**
-   *
-   * Interface for handling CallManager events and errors
-   *
-   */
-  public interface Observer {
+    /**
+     *
+     * Asks to seal an opaque signaling message for a remote peer,
+     * e.g. to encrypt and authenticate it with a key shared with the
+     * remote peer.  Called synchronously on a native thread.
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the call
+     * @param remoteDevice  deviceId of remote peer
+     * @param opaque        the message to seal
+     *
+     * @return the sealed message, or null to send the message as is
+     *
+     */
+    @Nullable byte[] seal(CallId callId, Remote remote, Integer remoteDevice, byte[] opaque);
 
     /**
      *
-     * Notification to start a call
+     * Asks to open a sealed signaling message received from a remote
+     * peer, returning the message as it was before {@link #seal}.
+     * Called synchronously on a native thread.
      *
-     * @param remote      remote peer of the call
-     * @param callId      callId for the call
-     * @param isOutgoing  true for an outgoing call, false for incoming
+     * @param callId        callId for the call
+     * @param remoteDevice  deviceId of remote peer
+     * @param sealed        the sealed message
+     *
+     * @return the opened message, or null if it is not authentic, in
+     *         which case it is dropped
      *
      */
-    void onStartCall(Remote remote, CallId callId, Boolean isOutgoing);
+    @Nullable byte[] open(CallId callId, Integer remoteDevice, byte[] sealed);
 
     /**
      *
-     * Notification of an event for the active call sent to the UI
+     * Notification that a pre-offer is ready to be sent to all the
+     * devices of the remote peer, see {@link CallManager#setPreOffer}
      *
-     * @param remote remote peer of the call
-     * @param event  event to be notified of
+     * @param callId        callId for the call
+     * @param remote        remote peer of the outgoing call
+     * @param remoteDevice  deviceId of remote peer
      *
      */
-    void onCallEvent(Remote remote, CallEvent event);
+    void onSendPreOffer(CallId callId, Remote remote, Integer remoteDevice);
 
     /**
      *
-     * Notification of that the call is completely concluded
+     * Notification that a remote peer is about to offer a call, so
+     * the application can warm up, e.g. initialize the audio device,
+     * while the offer is still in flight.  The offer may never arrive.
      *
-     * @param remote remote peer of the call
+     * @param callId        callId for the call
+     * @param remote        remote peer of the incoming call
+     * @param remoteDevice  deviceId of remote peer
      *
      */
-    void onCallConcluded(Remote remote);
+    void onReceivedPreOffer(CallId callId, Remote remote, Integer remoteDevice);
 
     /**
      *
@@ -937,9 +2984,10 @@ public class CallManager {
      * @param remoteDevice  deviceId of remote peer
      * @param broadcast     if true, send broadcast message
      * @param sdp           SDP offer
+     * @param descriptor    media profile of the SDP offer, for information only
      *
      */
-    void onSendOffer(CallId callId, Remote remote, Integer remoteDevice, Boolean broadcast, String sdp);
+    void onSendOffer(CallId callId, Remote remote, Integer remoteDevice, Boolean broadcast, String sdp, MediaDescriptor descriptor);
 
     /**
      *
@@ -950,9 +2998,10 @@ public class CallManager {
      * @param remoteDevice  deviceId of remote peer
      * @param broadcast     if true, send broadcast message
      * @param sdp           SDP offer
+     * @param descriptor    media profile of the SDP answer, for information only
      *
      */
-    void onSendAnswer(CallId callId, Remote remote, Integer remoteDevice, Boolean broadcast, String sdp);
+    void onSendAnswer(CallId callId, Remote remote, Integer remoteDevice, Boolean broadcast, String sdp, MediaDescriptor descriptor);
 
     /**
      *
@@ -975,9 +3024,10 @@ public class CallManager {
      * @param remote        remote peer of the call
      * @param remoteDevice  deviceId of remote peer
      * @param broadcast     if true, send broadcast message
+     * @param hangupType    why the call was hung up
      *
      */
-    void onSendHangup(CallId callId, Remote remote, Integer remoteDevice, Boolean broadcast);
+    void onSendHangup(CallId callId, Remote remote, Integer remoteDevice, Boolean broadcast, HangupType hangupType);
 
     /**
      *
@@ -991,6 +3041,17 @@ public class CallManager {
      */
     void onSendBusy(CallId callId, Remote remote, Integer remoteDevice, Boolean broadcast);
 
+    /**
+     *
+     * Notification that ringing message is ready to be sent
+     *
+     * @param callId        callId for the call
+     * @param remote        remote peer of the ringing incoming call
+     * @param remoteDevice  deviceId of remote peer
+     *
+     */
+    void onSendRinging(CallId callId, Remote remote, Integer remoteDevice);
+
   }
 
   /**
@@ -1020,6 +3081,10 @@ public class CallManager {
     long ringrtcCreateCallManager(CallManager callManager)
     throws CallException;
 
+  private native
+    void ringrtcUpdateCallManager(long nativeCallManager, CallManager callManager)
+    throws CallException;
+
   private native
     long ringrtcCreatePeerConnection(long                            nativePeerConnectionFactory,
                                      long                            nativeConnection,
@@ -1032,10 +3097,11 @@ public class CallManager {
     throws CallException;
 
   private native
-    void ringrtcProceed(long          nativeCallManager,
-                        long          callId,
-                        CallContext   callContext,
-                        List<Integer> remoteDevices)
+    void ringrtcProceed(long                           nativeCallManager,
+                        long                           callId,
+                        CallContext                    callContext,
+                        List<PeerConnection.IceServer> iceServers,
+                        List<Integer>                  remoteDevices)
     throws CallException;
 
   private native
@@ -1057,13 +3123,21 @@ public class CallManager {
                                String answer)
     throws CallException;
 
+  private native
+    void ringrtcReceivedPreOffer(long   nativeCallManager,
+                                 long   callId,
+                                 Remote remote,
+                                 int    remoteDevice)
+    throws CallException;
+
   private native
     void ringrtcReceivedOffer(long   nativeCallManager,
                               long   callId,
                               Remote remote,
                               int    remoteDevice,
                               String offer,
-                              long   timestamp)
+                              long   timestamp,
+                              long   ageSec)
     throws CallException;
 
   private native
@@ -1076,7 +3150,20 @@ public class CallManager {
   private native
     void ringrtcReceivedHangup(long nativeCallManager,
                                long callId,
-                               int  remoteDevice)
+                               int  remoteDevice,
+                               int  hangupType)
+    throws CallException;
+
+  private native
+    void ringrtcReceivedDeclined(long nativeCallManager,
+                                 long callId,
+                                 int  remoteDevice)
+    throws CallException;
+
+  private native
+    void ringrtcReceivedRinging(long nativeCallManager,
+                                long callId,
+                                int  remoteDevice)
     throws CallException;
 
   private native
@@ -1097,19 +3184,188 @@ public class CallManager {
     CallContext ringrtcGetActiveCallContext(long nativeCallManager)
     throws CallException;
 
+  private native
+    List<TraceEvent> ringrtcGetActiveConnectionTrace(long nativeCallManager)
+    throws CallException;
+
+  private native
+    RemoteCapabilities ringrtcGetActiveRemoteCapabilities(long nativeCallManager)
+    throws CallException;
+
   private native
     void ringrtcSetVideoEnable(long nativeCallManager, boolean enable)
     throws CallException;
 
+  private native
+    void ringrtcSwitchCamera(long nativeCallManager)
+    throws CallException;
+
+  private native
+    void ringrtcSetScreenShareEnable(long nativeCallManager, long callId, boolean enable)
+    throws CallException;
+
+  private native
+    void ringrtcSetAudioEnable(long nativeCallManager, boolean enable)
+    throws CallException;
+
+  private native
+    void ringrtcSetVideoMaxResolution(long nativeCallManager, int width, int height)
+    throws CallException;
+
+  private native
+    void ringrtcSetVideoCaptureResolution(long nativeCallManager, int width, int height)
+    throws CallException;
+
+  private native
+    void ringrtcSetVideoDegradationPreference(long nativeCallManager, int preference)
+    throws CallException;
+
+  private native
+    void ringrtcSetBandwidthMode(long nativeCallManager, int mode)
+    throws CallException;
+
+  private native
+    void ringrtcSetAudioOutput(long nativeCallManager, int output)
+    throws CallException;
+
+  private native
+    void ringrtcAudioRouteChangedBySystem(long nativeCallManager, int output)
+    throws CallException;
+
+  private native
+    void ringrtcSetBundlePolicy(long nativeCallManager, int policy)
+    throws CallException;
+
+  private native
+    void ringrtcSetRtcpMuxPolicy(long nativeCallManager, int policy)
+    throws CallException;
+
+  private native
+    void ringrtcSetIceCandidatePolicy(long    nativeCallManager,
+                                      boolean relayOnly,
+                                      boolean noIpv6,
+                                      boolean noHost)
+    throws CallException;
+
+  private native
+    void ringrtcSetAnswerMetadata(long   nativeCallManager,
+                                  long   callId,
+                                  byte[] metadata)
+    throws CallException;
+
+  private native
+    void ringrtcUpdateIceServers(long                           nativeCallManager,
+                                 long                           callId,
+                                 List<PeerConnection.IceServer> iceServers)
+    throws CallException;
+
+  private native
+    void ringrtcSetIceServers(long                           nativeCallManager,
+                              List<PeerConnection.IceServer> iceServers,
+                              boolean                        iceRestart)
+    throws CallException;
+
+  private native
+    void ringrtcRestartIce(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcSetOnHold(long nativeCallManager, long callId, boolean onHold)
+    throws CallException;
+
+  private native
+    void ringrtcCallAddedToTelecom(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcHoldRequestedBySystem(long nativeCallManager, long callId, boolean onHold)
+    throws CallException;
+
+  private native
+    void ringrtcTransferCall(long nativeCallManager, long callId, String newRemote)
+    throws CallException;
+
+  private native
+    void ringrtcSetOutgoingAudioGain(long nativeCallManager, float gain)
+    throws CallException;
+
+  private native
+    void ringrtcSendDtmf(long nativeCallManager, String tones, int durationMs, int gapMs)
+    throws CallException;
+
+  private native
+    void ringrtcSendAppMessage(long nativeCallManager, byte[] payload)
+    throws CallException;
+
+  private native
+    void ringrtcSetDataChannelHighWaterMark(long nativeCallManager, long mark)
+    throws CallException;
+
+  private native
+    long ringrtcGetActiveDataChannelBufferedAmount(long nativeCallManager)
+    throws CallException;
+
+  private native
+    void ringrtcSetCallDurationTicks(long nativeCallManager, boolean enable)
+    throws CallException;
+
+  private native
+    void ringrtcSetMaxConnections(long nativeCallManager, int max)
+    throws CallException;
+
+  private native
+    void ringrtcSetStatsInterval(long nativeCallManager, long intervalMillis)
+    throws CallException;
+
+  private native
+    void ringrtcSetAudioLevelsInterval(long nativeCallManager, long intervalMillis)
+    throws CallException;
+
+  private native
+    void ringrtcSetBandwidthEstimateInterval(long nativeCallManager, long intervalMillis)
+    throws CallException;
+
+  private native
+    void ringrtcSetPreOffer(long nativeCallManager, boolean enable)
+    throws CallException;
+
+  private native
+    long ringrtcGetActiveCallDuration(long nativeCallManager)
+    throws CallException;
+
+  private native
+    void ringrtcSetEndOnAudioDeviceFailure(long nativeCallManager, boolean enable)
+    throws CallException;
+
+  private native
+    void ringrtcSetCallWaiting(long nativeCallManager, boolean enable)
+    throws CallException;
+
+  private native
+    void ringrtcAudioDeviceFailure(long nativeCallManager, int error)
+    throws CallException;
+
   private native
     void ringrtcDrop(long nativeCallManager, long callId)
     throws CallException;
 
+  private native
+    void ringrtcCancelSetup(long nativeCallManager, long callId)
+    throws CallException;
+
   private native
     void ringrtcReset(long nativeCallManager)
     throws CallException;
 
+  private native
+    void ringrtcRestorePendingSignaling(long nativeCallManager)
+    throws CallException;
+
   private native
     void ringrtcClose(long nativeCallManager)
     throws CallException;
+
+  private native
+    void ringrtcShutdown(long nativeCallManager)
+    throws CallException;
 }
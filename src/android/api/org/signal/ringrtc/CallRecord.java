@@ -0,0 +1,54 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.Nullable;
+
+/**
+ *
+ * The record of a concluded call, for the call history.
+ *
+ */
+public final class CallRecord {
+
+  /** The kind of ICE route used by a call. */
+  public enum ConnectionType {
+    /** The route was relayed through a TURN server */
+    RELAYED,
+    /** The route was direct between the peers */
+    DIRECT;
+  }
+
+  /** True if the call was outgoing */
+            public final boolean                isOutgoing;
+  /** How long the call was connected, in milliseconds */
+            public final long                   durationMillis;
+  /** The event the call ended with, if the application was told */
+  @Nullable public final CallManager.CallEvent  reason;
+  /** The best ICE route used by the call, null if ICE never connected */
+  @Nullable public final ConnectionType         connectionType;
+  /** Average bitrate of all media while connected, in bits per second */
+            public final long                   averageBitrate;
+
+  @CalledByNative
+  CallRecord(boolean isOutgoing, long durationMillis, @Nullable CallManager.CallEvent reason, int connectionType, long averageBitrate) {
+    this.isOutgoing     = isOutgoing;
+    this.durationMillis = durationMillis;
+    this.reason         = reason;
+    this.connectionType = connectionType < 0 ? null : ConnectionType.values()[connectionType];
+    this.averageBitrate = averageBitrate;
+  }
+
+  @Override
+  public String toString() {
+    return "isOutgoing: " + isOutgoing + ", durationMillis: " + durationMillis + ", reason: " + reason + ", connectionType: " + connectionType + ", averageBitrate: " + averageBitrate;
+  }
+
+}
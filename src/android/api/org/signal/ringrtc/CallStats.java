@@ -0,0 +1,58 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.Nullable;
+
+/**
+ *
+ * The quality of the active call over the last stats interval.
+ *
+ */
+public final class CallStats {
+
+  /** Round trip time of the selected ICE route, in milliseconds */
+            public final double rttMillis;
+  /** Jitter of the received audio, in milliseconds */
+            public final double jitterMillis;
+  /** Average time the received audio spent in the jitter buffer, in milliseconds */
+            public final double jitterBufferMillis;
+  /** Fraction of the received packets that were lost, from 0.0 to 1.0 */
+            public final double packetLoss;
+  /** Bitrate of all sent media, in bits per second */
+            public final long   sendBitrate;
+  /** Bitrate of all received media, in bits per second */
+            public final long   receiveBitrate;
+  /** The sent audio codec, e.g. opus */
+  @Nullable public final String audioCodec;
+  /** The sent video codec, e.g. VP8 */
+  @Nullable public final String videoCodec;
+  /** How the sent video is adapted, e.g. CPU-limited at 360p, 15 fps */
+  @Nullable public final String videoAdaptation;
+
+  @CalledByNative
+  CallStats(double rttMillis, double jitterMillis, double jitterBufferMillis, double packetLoss, long sendBitrate, long receiveBitrate, @Nullable String audioCodec, @Nullable String videoCodec, @Nullable String videoAdaptation) {
+    this.rttMillis          = rttMillis;
+    this.jitterMillis       = jitterMillis;
+    this.jitterBufferMillis = jitterBufferMillis;
+    this.packetLoss         = packetLoss;
+    this.sendBitrate        = sendBitrate;
+    this.receiveBitrate     = receiveBitrate;
+    this.audioCodec         = audioCodec;
+    this.videoCodec         = videoCodec;
+    this.videoAdaptation    = videoAdaptation;
+  }
+
+  @Override
+  public String toString() {
+    return "rttMillis: " + rttMillis + ", jitterMillis: " + jitterMillis + ", jitterBufferMillis: " + jitterBufferMillis + ", packetLoss: " + packetLoss + ", sendBitrate: " + sendBitrate + ", receiveBitrate: " + receiveBitrate + ", audioCodec: " + audioCodec + ", videoCodec: " + videoCodec + ", videoAdaptation: " + videoAdaptation;
+  }
+
+}
@@ -0,0 +1,40 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.NonNull;
+
+/**
+ *
+ * A codec the device encodes and/or decodes in hardware, e.g. as
+ * listed by android.media.MediaCodecList.
+ *
+ */
+public final class CodecCapability {
+
+  /** The codec name used in the SDP, e.g. H264 */
+  @NonNull public final String  name;
+  /** If true, the codec is encoded in hardware */
+           public final boolean encode;
+  /** If true, the codec is decoded in hardware */
+           public final boolean decode;
+
+  public CodecCapability(@NonNull String name, boolean encode, boolean decode) {
+    this.name   = name;
+    this.encode = encode;
+    this.decode = decode;
+  }
+
+  @Override
+  public String toString() {
+    return name + ", encode: " + encode + ", decode: " + decode;
+  }
+
+}
@@ -67,11 +67,6 @@ public class Connection extends PeerConnection {
 
   }
 
-  void setAudioEnabled(boolean enabled) {
-    // enable microphone
-    audioTrack.setEnabled(enabled);
-  }
-
   /**
    *
    * Close the Connection object and clean up.
@@ -0,0 +1,51 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.Nullable;
+
+/**
+ *
+ * The media profile of a local offer or answer, derived from its SDP.
+ *
+ * The descriptor is informational only, the SDP is what gets sent.
+ *
+ */
+public final class MediaDescriptor {
+
+  /** An audio section is offered or accepted */
+            public final boolean audio;
+  /** A video section is offered or accepted */
+            public final boolean video;
+  /** A data channel section is offered or accepted */
+            public final boolean data;
+  /** The preferred audio codec, e.g. opus */
+  @Nullable public final String  audioCodec;
+  /** The preferred video codec, e.g. VP8 */
+  @Nullable public final String  videoCodec;
+  /** Media frames are end-to-end encrypted on top of SRTP */
+            public final boolean frameEncryption;
+
+  @CalledByNative
+  MediaDescriptor(boolean audio, boolean video, boolean data, @Nullable String audioCodec, @Nullable String videoCodec, boolean frameEncryption) {
+    this.audio           = audio;
+    this.video           = video;
+    this.data            = data;
+    this.audioCodec      = audioCodec;
+    this.videoCodec      = videoCodec;
+    this.frameEncryption = frameEncryption;
+  }
+
+  @Override
+  public String toString() {
+    return "audio: " + audio + ", video: " + video + ", data: " + data + ", audioCodec: " + audioCodec + ", videoCodec: " + videoCodec + ", frameEncryption: " + frameEncryption;
+  }
+
+}
@@ -0,0 +1,86 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.NonNull;
+import androidx.annotation.Nullable;
+
+import org.webrtc.IceCandidate;
+
+import java.util.List;
+
+/**
+ *
+ * A signaling message waiting to be sent, for the application to
+ * persist across a restart.
+ *
+ */
+public final class PendingSignaling {
+
+  /** The kind of a pending signaling message. */
+  public enum Type {
+    /** An SDP offer */
+    OFFER,
+    /** An SDP offer renegotiating with new ICE credentials */
+    RESTART_OFFER,
+    /** An SDP answer */
+    ANSWER,
+    /** A batch of ICE candidates */
+    ICE_CANDIDATES;
+  }
+
+  /** The remote peer the message is for */
+  @NonNull  public final Remote             remote;
+  /** The call the message belongs to */
+  @NonNull  public final CallId             callId;
+  /** The device of the remote peer the message is for */
+            public final int                remoteDevice;
+  /** The kind of the message */
+  @NonNull  public final Type               type;
+  /** The SDP of an offer or answer, null for ICE candidates */
+  @Nullable public final String             sdp;
+  /** The candidates of an ICE_CANDIDATES message, null otherwise */
+  @Nullable public final List<IceCandidate> iceCandidates;
+
+  public PendingSignaling(@NonNull  Remote             remote,
+                          @NonNull  CallId             callId,
+                                    int                remoteDevice,
+                          @NonNull  Type               type,
+                          @Nullable String             sdp,
+                          @Nullable List<IceCandidate> iceCandidates) {
+    this.remote        = remote;
+    this.callId        = callId;
+    this.remoteDevice  = remoteDevice;
+    this.type          = type;
+    this.sdp           = sdp;
+    this.iceCandidates = iceCandidates;
+  }
+
+  @CalledByNative
+  PendingSignaling(Remote remote, long callId, int remoteDevice, int type, @Nullable String sdp, @Nullable List<IceCandidate> iceCandidates) {
+    this(remote, new CallId(callId), remoteDevice, Type.values()[type], sdp, iceCandidates);
+  }
+
+  @CalledByNative
+  long getNativeCallId() {
+    return callId.longValue();
+  }
+
+  @CalledByNative
+  int getNativeType() {
+    return type.ordinal();
+  }
+
+  @Override
+  public String toString() {
+    return "callId: " + callId.format(remoteDevice) + ", type: " + type;
+  }
+
+}
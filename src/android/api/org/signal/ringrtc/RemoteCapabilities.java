@@ -0,0 +1,51 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.NonNull;
+
+import java.util.List;
+
+/**
+ *
+ * What the remote peer supports, as negotiated in the SDP.
+ *
+ */
+public final class RemoteCapabilities {
+
+  /** An audio section was negotiated */
+           public final boolean      audio;
+  /** A video section was negotiated */
+           public final boolean      video;
+  /** A data channel section was negotiated */
+           public final boolean      data;
+  /** The video section carries a simulcast description */
+           public final boolean      simulcast;
+  /** A video section carries a screen share */
+           public final boolean      screenShare;
+  /** The negotiated codec names, e.g. opus or VP8 */
+  @NonNull public final List<String> codecs;
+
+  @CalledByNative
+  RemoteCapabilities(boolean audio, boolean video, boolean data, boolean simulcast, boolean screenShare, @NonNull List<String> codecs) {
+    this.audio       = audio;
+    this.video       = video;
+    this.data        = data;
+    this.simulcast   = simulcast;
+    this.screenShare = screenShare;
+    this.codecs      = codecs;
+  }
+
+  @Override
+  public String toString() {
+    return "audio: " + audio + ", video: " + video + ", data: " + data + ", simulcast: " + simulcast + ", screenShare: " + screenShare + ", codecs: " + codecs;
+  }
+
+}
@@ -0,0 +1,40 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.NonNull;
+
+/**
+ *
+ * A single timestamped entry from a call trace.
+ *
+ */
+public final class TraceEvent {
+
+  /** Milliseconds since the epoch when the event was recorded */
+           public final long   timestamp;
+  /** Category of the event: StateChange, FsmEvent or Signaling */
+  @NonNull public final String kind;
+  /** Human readable description of the event */
+  @NonNull public final String description;
+
+  @CalledByNative
+  TraceEvent(long timestamp, @NonNull String kind, @NonNull String description) {
+    this.timestamp   = timestamp;
+    this.kind        = kind;
+    this.description = description;
+  }
+
+  @Override
+  public String toString() {
+    return timestamp + " " + kind + ": " + description;
+  }
+
+}
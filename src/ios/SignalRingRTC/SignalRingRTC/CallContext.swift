@@ -13,7 +13,6 @@ public class CallContext {
     // A camera queue on which to perform camera operations.
     private static let cameraQueue = DispatchQueue(label: "CallContextCameraQueue")
 
-    let iceServers: [RTCIceServer]
     let hideIp: Bool
 
     let audioSource: RTCAudioSource
@@ -25,8 +24,7 @@ public class CallContext {
     // Cache the latest settings so we don't repeat them.
     var currentVideoEnableSetting: Bool
 
-    init (iceServers: [RTCIceServer], hideIp: Bool, audioSource: RTCAudioSource, audioTrack: RTCAudioTrack, videoSource: RTCVideoSource, videoTrack: RTCVideoTrack, videoCaptureController: VideoCaptureController) {
-        self.iceServers = iceServers
+    init (hideIp: Bool, audioSource: RTCAudioSource, audioTrack: RTCAudioTrack, videoSource: RTCVideoSource, videoTrack: RTCVideoTrack, videoCaptureController: VideoCaptureController) {
         self.hideIp = hideIp
         self.audioSource = audioSource
         self.audioTrack = audioTrack
@@ -27,7 +27,7 @@ public enum CallManagerEvent: Int32 {
     case endedRemoteHangup = 5
     /// The call ended because of a remote busy message.
     case endedRemoteBusy = 6
-    /// The call ended because of glare (received offer from same remote).
+    /// The call ended because of glare, the offer received from the same remote won.
     case endedRemoteGlare = 7
     /// The call ended because it timed out during setup.
     case endedTimeout = 8
@@ -51,6 +51,118 @@ public enum CallManagerEvent: Int32 {
     case endedReceivedOfferExpired = 17
     /// Received an offer while already handling an active call.
     case endedReceivedOfferWhileActive = 18
+    /// The received offer lacked the preferred codecs, a common codec was negotiated instead.
+    case codecFallback = 19
+    /// The remote side did not answer the renegotiation offer sent while reconnecting.
+    case renegotiationTimeout = 20
+    /// The audio device failed to initialize, for an unknown reason.
+    case audioDeviceFailure = 21
+    /// The audio device failed to initialize because microphone access was denied.
+    case audioDevicePermissionDenied = 22
+    /// The audio device failed to initialize because it is in use.
+    case audioDeviceBusy = 23
+    /// The call ended because the audio device failed to initialize.
+    case endedAudioDeviceFailure = 24
+    /// The call ended because every remote device declined it.
+    case endedRemoteDeclined = 25
+    /// Group call only: A participant connected to the call.
+    case groupParticipantJoined = 26
+    /// Group call only: A participant disconnected from the call.
+    case groupParticipantLeft = 27
+    /// The remote peer muted its audio.
+    case remoteAudioMuted = 28
+    /// The remote peer unmuted its audio.
+    case remoteAudioUnmuted = 29
+    /// The received offer lost to the active call of the same remote peer (glare).
+    case endedReceivedOfferWithGlare = 30
+    /// The call ended because it was accepted on another device.
+    case endedRemoteHangupAccepted = 31
+    /// The call ended because another device is busy.
+    case endedRemoteHangupBusy = 32
+    /// The call ended because the remote side needs permission to take calls from the local user.
+    case endedRemoteHangupNeedPermission = 33
+    /// The application refused to ring the incoming call.
+    case endedRingRefused = 34
+    /// The remote side put the call on hold.
+    case remoteOnHold = 35
+    /// The remote side resumed the call from hold.
+    case remoteResumed = 36
+    /// The application cancelled the call setup.
+    case endedSetupCancelled = 37
+    /// The remote side declined to transfer the call.
+    case transferDeclined = 38
+    /// The call ended because the remote side accepted its transfer.
+    case endedTransferred = 39
+    /// The call ended because of a remote busy message, and the remote side will be called again.
+    case endedRemoteBusyRetry = 40
+    /// Calling the busy remote side again was cancelled.
+    case busyRetryCancelled = 41
+    /// The credentials of the ICE servers are about to expire.
+    case iceServersExpiring = 42
+    /// The remote side started recording the call.
+    case remoteRecordingStarted = 43
+    /// The remote side stopped recording the call.
+    case remoteRecordingStopped = 44
+    /// No media arrived from the remote side for the media stall timeout, although ICE is connected.
+    case remoteMediaStalled = 45
+    /// Media arrives from the remote side again.
+    case remoteMediaResumed = 46
+    /// The remote side started sharing its screen.
+    case remoteSharingScreen = 47
+    /// The remote side stopped sharing its screen.
+    case remoteStoppedSharingScreen = 48
+    /// The call ended because the remote side closed its media without hanging up.
+    case endedRemoteMediaClosed = 49
+}
+
+/// Why a call was hung up, carried by the hangup message.
+public enum CallManagerHangupType: Int32 {
+    /// The call was hung up, or was never answered.
+    case normal = 0
+    /// The call was accepted on another device.
+    case accepted = 1
+    /// The call was declined.
+    case declined = 2
+    /// Another device is busy.
+    case busy = 3
+    /// The user needs to grant permission before taking calls.
+    case needPermission = 4
+}
+
+/// How much bandwidth a call may use.
+public enum CallManagerBandwidthMode: Int32 {
+    /// Audio only, at a low bitrate, e.g. for metered connections.
+    case low = 0
+    /// The usual bitrates for audio and video.
+    case normal = 1
+    /// Higher video bitrates, for fast unmetered connections.
+    case high = 2
+}
+
+/// How the sent video degrades when bandwidth or CPU is constrained.
+public enum CallManagerDegradationPreference: Int32 {
+    /// Lower the resolution, e.g. for camera video.
+    case maintainFramerate = 0
+    /// Lower the framerate, e.g. for screen sharing.
+    case maintainResolution = 1
+    /// Lower both the resolution and the framerate.
+    case balanced = 2
+}
+
+/// The kind of network interface an ICE route runs over.
+public enum CallManagerNetworkAdapterType: Int32 {
+    /// The kind of interface is not known.
+    case unknown = 0
+    /// A wired network.
+    case ethernet = 1
+    /// A Wi-Fi network.
+    case wifi = 2
+    /// A mobile data network.
+    case cellular = 4
+    /// A VPN tunnel.
+    case vpn = 8
+    /// The loopback interface.
+    case loopback = 16
 }
 
 // We define our own structure for Ice Candidates so that the
@@ -68,6 +180,53 @@ public class CallManagerIceCandidate {
     }
 }
 
+/// The kind of a signaling message waiting to be sent.
+public enum CallManagerPendingSignalingType: Int32 {
+    /// An SDP offer.
+    case offer = 0
+    /// An SDP offer renegotiating with new ICE credentials.
+    case restartOffer = 1
+    /// An SDP answer.
+    case answer = 2
+    /// A batch of ICE candidates.
+    case iceCandidates = 3
+}
+
+// A signaling message waiting to be sent, for the application to
+// persist across a restart.
+public class CallManagerPendingSignaling<CallType> {
+    public let call: CallType
+    public let callId: UInt64
+    public let remoteDevice: UInt32
+    public let type: CallManagerPendingSignalingType
+    // The SDP of an offer or answer, nil for ICE candidates.
+    public let sdp: String?
+    public let candidates: [CallManagerIceCandidate]
+
+    public init(call: CallType, callId: UInt64, remoteDevice: UInt32, type: CallManagerPendingSignalingType, sdp: String?, candidates: [CallManagerIceCandidate]) {
+        self.call = call
+        self.callId = callId
+        self.remoteDevice = remoteDevice
+        self.type = type
+        self.sdp = sdp
+        self.candidates = candidates
+    }
+}
+
+// A codec the device encodes and/or decodes in hardware.
+public class CallManagerCodecCapability {
+    // The codec name used in the SDP, e.g. H264.
+    public let name: String
+    public let encode: Bool
+    public let decode: Bool
+
+    public init(name: String, encode: Bool, decode: Bool) {
+        self.name = name
+        self.encode = encode
+        self.decode = decode
+    }
+}
+
 public protocol CallManagerDelegate: class {
 
     associatedtype CallManagerDelegateCallType: CallManagerCallReference
@@ -82,7 +241,7 @@ public protocol CallManagerDelegate: class {
      * onEvent will be invoked in response to Call Manager library operations.
      * Invoked on the main thread, asychronously.
      */
-    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onEvent call: CallManagerDelegateCallType, event: CallManagerEvent)
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onEvent call: CallManagerDelegateCallType, callId: UInt64, event: CallManagerEvent)
 
     /**
      * An Offer message should be sent to the given remote.
@@ -110,7 +269,7 @@ public protocol CallManagerDelegate: class {
      * Invoked on the main thread, asychronously.
      * If there is any error, the UI can reset UI state and invoke the reset() API.
      */
-    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, shouldSendHangup callId: UInt64, call: CallManagerDelegateCallType, destDevice: UInt32?)
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, shouldSendHangup callId: UInt64, call: CallManagerDelegateCallType, destDevice: UInt32?, hangupType: CallManagerHangupType)
 
     /**
      * A Busy message should be sent to the given remote.
@@ -126,6 +285,102 @@ public protocol CallManagerDelegate: class {
      */
     func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, shouldCompareCalls call1: CallManagerDelegateCallType, call2: CallManagerDelegateCallType) -> Bool
 
+    /**
+     * Fresh STUN and TURN servers should be returned for the call, e.g. with
+     * short-lived TURN credentials. Asked whenever a connection is created and
+     * whenever it restarts ICE. An empty list keeps the servers given to proceed().
+     * Invoked *synchronously*, off the main thread.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, getTurnServers call: CallManagerDelegateCallType, callId: UInt64) -> [RTCIceServer]
+
+    /**
+     * The codecs the device encodes or decodes in hardware should be returned.
+     * They are merged with the codecs WebRTC supports in software.
+     * Invoked *synchronously*, off the main thread.
+     */
+    func callManagerGetCodecCapabilities(_ callManager: CallManager<CallManagerDelegateCallType, Self>) -> [CallManagerCodecCapability]
+
+    /**
+     * The bandwidth available for sending and receiving the media of the
+     * active call, in bits per second, if enabled with setBandwidthEstimateInterval().
+     * Invoked on the main thread, asychronously.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onBandwidthEstimate call: CallManagerDelegateCallType, callId: UInt64, sendBps: UInt64, receiveBps: UInt64)
+
+    /**
+     * The network the ICE route of the connected call runs over, e.g. for
+     * "connecting via relay" or "switched to cellular" indicators. Reported
+     * once the call connects, then whenever the kind of network or relaying changes.
+     * Invoked on the main thread, asychronously.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onNetworkRouteChanged call: CallManagerDelegateCallType, callId: UInt64, localAdapterType: CallManagerNetworkAdapterType, relayed: Bool)
+
+    /**
+     * The safety numbers of the connected call, for the users to read aloud
+     * and compare. Only reported when both sides sent a DTLS fingerprint.
+     * Invoked on the main thread, asychronously.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onSafetyNumbers call: CallManagerDelegateCallType, callId: UInt64, remoteDevice: UInt32, safetyNumbers: String)
+
+    /**
+     * The metadata the callee attached to its answer with setAnswerMetadata(),
+     * received before the answer is applied.
+     * Invoked on the main thread, asychronously.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onReceivedAnswerMetadata call: CallManagerDelegateCallType, callId: UInt64, remoteDevice: UInt32, metadata: Data)
+
+    /**
+     * An opaque signaling message for the remote peer should be sealed, e.g.
+     * encrypted and authenticated with a key shared with the remote peer.
+     * Returning nil sends the message as is.
+     * Invoked *synchronously*, off the main thread.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, seal call: CallManagerDelegateCallType, callId: UInt64, remoteDevice: UInt32, opaque: Data) -> Data?
+
+    /**
+     * A sealed signaling message should be opened, returning the message as it
+     * was before sealing. Returning nil drops the message as not authentic.
+     * Invoked *synchronously*, off the main thread.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, open callId: UInt64, remoteDevice: UInt32, sealed: Data) -> Data?
+
+    /**
+     * The signaling messages waiting to be sent should be persisted, replacing
+     * any persisted before, so they survive a restart. Reported whenever the
+     * queue changes, with an empty list once it drains.
+     * Invoked *synchronously*, off the main thread.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, persistPendingSignaling pending: [CallManagerPendingSignaling<CallManagerDelegateCallType>])
+
+    /**
+     * The signaling messages last persisted should be returned, for
+     * restorePendingSignaling() to send.
+     * Invoked *synchronously*, off the main thread.
+     */
+    func callManagerRestorePendingSignaling(_ callManager: CallManager<CallManagerDelegateCallType, Self>) -> [CallManagerPendingSignaling<CallManagerDelegateCallType>]
+
+    /**
+     * A pre-offer should be sent to all the devices of the given remote, if
+     * enabled with setPreOffer().
+     * Invoked on the main thread, asychronously.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, shouldSendPreOffer callId: UInt64, call: CallManagerDelegateCallType, sourceDevice: UInt32)
+
+    /**
+     * The given remote is about to offer a call, so the application can warm
+     * up, e.g. initialize the audio device, while the offer is in flight. The
+     * offer may never arrive.
+     * Invoked on the main thread, asychronously.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, didReceivePreOffer callId: UInt64, call: CallManagerDelegateCallType, sourceDevice: UInt32)
+
+    /**
+     * A message the remote peer sent with sendAppMessage(), e.g. a reaction
+     * or a caption, received reliably and in order.
+     * Invoked on the main thread, asychronously.
+     */
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onAppMessageReceived call: CallManagerDelegateCallType, callId: UInt64, remoteDevice: UInt32, payload: Data)
+
     /**
      * The local video track has been enabled and can be connected to the
      * UI's display surface/view for the outgoing media.
@@ -154,6 +409,10 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
 
     private var videoCaptureController: VideoCaptureController?
 
+    // Calls of restored signaling messages, kept alive while RingRTC
+    // sends the messages.
+    private var restoredCalls: [CallType] = []
+
     public init() {
         // Initialize the global object (mainly for logging).
         _ = CallManagerGlobal.shared
@@ -246,9 +505,12 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         // Create a call context object to hold on to some of
         // the settings needed by the application when actually
         // creating the connection.
-        let appCallContext = CallContext(iceServers: iceServers, hideIp: hideIp, audioSource: audioSource, audioTrack: audioTrack, videoSource: videoSource, videoTrack: videoTrack, videoCaptureController: videoCaptureController)
+        let appCallContext = CallContext(hideIp: hideIp, audioSource: audioSource, audioTrack: audioTrack, videoSource: videoSource, videoTrack: videoTrack, videoCaptureController: videoCaptureController)
 
-        let retPtr = ringrtcProceed(ringRtcCallManager, callId, appCallContext.getWrapper(), deviceList, deviceList.count)
+        let appIceServers = AppIceServers(iceServers)
+        let retPtr = withExtendedLifetime(appIceServers) {
+            ringrtcProceed(ringRtcCallManager, callId, appCallContext.getWrapper(), appIceServers.servers.baseAddress, appIceServers.count, deviceList, deviceList.count)
+        }
         if retPtr == nil {
             throw CallManagerError.apiFailed(description: "proceed() function failure")
         }
@@ -264,6 +526,167 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
+    public func cancelSetup(callId: UInt64) throws {
+        AssertIsOnMainThread()
+        Logger.debug("cancelSetup")
+
+        let retPtr = ringrtcCancelSetup(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "cancelSetup() function failure")
+        }
+    }
+
+    // Replaces the ICE servers of the call, e.g. with new TURN
+    // credentials once iceServersExpiring is reported. The servers are
+    // used for candidates gathered from now on, without restarting ICE.
+    public func updateIceServers(callId: UInt64, iceServers: [RTCIceServer]) throws {
+        AssertIsOnMainThread()
+        Logger.debug("updateIceServers")
+
+        let appIceServers = AppIceServers(iceServers)
+        let retPtr = withExtendedLifetime(appIceServers) {
+            ringrtcUpdateIceServers(ringRtcCallManager, callId, appIceServers.servers.baseAddress, appIceServers.count)
+        }
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "updateIceServers() function failure")
+        }
+    }
+
+    public func restartIce(callId: UInt64) throws {
+        AssertIsOnMainThread()
+        Logger.debug("restartIce")
+
+        let retPtr = ringrtcRestartIce(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "restartIce() function failure")
+        }
+    }
+
+    public func setOnHold(callId: UInt64, onHold: Bool) throws {
+        AssertIsOnMainThread()
+        Logger.debug("setOnHold")
+
+        let retPtr = ringrtcSetOnHold(ringRtcCallManager, callId, onHold)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setOnHold() function failure")
+        }
+    }
+
+    public func setScreenShareEnabled(callId: UInt64, enabled: Bool) throws {
+        AssertIsOnMainThread()
+        Logger.debug("setScreenShareEnabled(\(enabled))")
+
+        let retPtr = ringrtcSetScreenShareEnable(ringRtcCallManager, callId, enabled)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setScreenShareEnabled() function failure")
+        }
+    }
+
+    public func setVideoDegradationPreference(preference: CallManagerDegradationPreference) throws {
+        AssertIsOnMainThread()
+        Logger.debug("setVideoDegradationPreference")
+
+        let retPtr = ringrtcSetVideoDegradationPreference(ringRtcCallManager, preference.rawValue)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setVideoDegradationPreference() function failure")
+        }
+    }
+
+    public func setBandwidthMode(callId: UInt64, mode: CallManagerBandwidthMode) throws {
+        AssertIsOnMainThread()
+        Logger.debug("setBandwidthMode")
+
+        let retPtr = ringrtcSetBandwidthMode(ringRtcCallManager, callId, mode.rawValue)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setBandwidthMode() function failure")
+        }
+    }
+
+    public func transferCall(callId: UInt64, newRemote: String) throws {
+        AssertIsOnMainThread()
+        Logger.debug("transferCall")
+
+        let bytes = Array(newRemote.utf8)
+        let target = AppByteSlice(
+            bytes: bytes,
+            len: bytes.count)
+
+        let retPtr = ringrtcTransferCall(ringRtcCallManager, callId, target)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "transferCall() function failure")
+        }
+    }
+
+    public func setCallWaiting(enabled: Bool) throws {
+        AssertIsOnMainThread()
+        Logger.debug("setCallWaiting")
+
+        let retPtr = ringrtcSetCallWaiting(ringRtcCallManager, enabled)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setCallWaiting() function failure")
+        }
+    }
+
+    public func setAnswerMetadata(callId: UInt64, metadata: Data) throws {
+        AssertIsOnMainThread()
+        Logger.debug("setAnswerMetadata")
+
+        let bytes = [UInt8](metadata)
+        let slice = AppByteSlice(
+            bytes: bytes,
+            len: bytes.count)
+
+        let retPtr = ringrtcSetAnswerMetadata(ringRtcCallManager, callId, slice)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setAnswerMetadata() function failure")
+        }
+    }
+
+    public func sendAppMessage(payload: Data) throws {
+        AssertIsOnMainThread()
+        Logger.debug("sendAppMessage")
+
+        let bytes = [UInt8](payload)
+        let slice = AppByteSlice(
+            bytes: bytes,
+            len: bytes.count)
+
+        let retPtr = ringrtcSendAppMessage(ringRtcCallManager, slice)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "sendAppMessage() function failure")
+        }
+    }
+
+    public func setBandwidthEstimateInterval(intervalMillis: UInt64) throws {
+        AssertIsOnMainThread()
+        Logger.debug("setBandwidthEstimateInterval")
+
+        let retPtr = ringrtcSetBandwidthEstimateInterval(ringRtcCallManager, intervalMillis)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setBandwidthEstimateInterval() function failure")
+        }
+    }
+
+    public func setPreOffer(enabled: Bool) throws {
+        AssertIsOnMainThread()
+        Logger.debug("setPreOffer")
+
+        let retPtr = ringrtcSetPreOffer(ringRtcCallManager, enabled)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setPreOffer() function failure")
+        }
+    }
+
+    public func restorePendingSignaling() throws {
+        AssertIsOnMainThread()
+        Logger.debug("restorePendingSignaling")
+
+        let retPtr = ringrtcRestorePendingSignaling(ringRtcCallManager)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "restorePendingSignaling() function failure")
+        }
+    }
+
     public func signalingMessageDidSend(callId: UInt64) throws {
         AssertIsOnMainThread()
         Logger.debug("signalingMessageDidSend")
@@ -366,7 +789,20 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
 
     // MARK: - Signaling API
 
-    public func receivedOffer<CallType: CallManagerCallReference>(call: CallType, sourceDevice: UInt32, callId: UInt64, sdp: String, timestamp: UInt64) throws {
+    // The call is not retained, the application must keep it alive
+    // until the offer is received.
+    public func receivedPreOffer<CallType: CallManagerCallReference>(call: CallType, sourceDevice: UInt32, callId: UInt64) throws {
+        AssertIsOnMainThread()
+        Logger.debug("receivedPreOffer")
+
+        let unmanagedRemote: Unmanaged<CallType> = Unmanaged.passUnretained(call)
+        let retPtr = ringrtcReceivedPreOffer(ringRtcCallManager, callId, unmanagedRemote.toOpaque(), sourceDevice)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "receivedPreOffer() function failure")
+        }
+    }
+
+    public func receivedOffer<CallType: CallManagerCallReference>(call: CallType, sourceDevice: UInt32, callId: UInt64, sdp: String, timestamp: UInt64, ageSec: UInt64) throws {
         AssertIsOnMainThread()
         Logger.debug("receivedOffer")
 
@@ -376,7 +812,7 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
             len: bytes.count)
 
         let unmanagedRemote: Unmanaged<CallType> = Unmanaged.passUnretained(call)
-        let retPtr = ringrtcReceivedOffer(ringRtcCallManager, callId, unmanagedRemote.toOpaque(), sourceDevice, offer, timestamp)
+        let retPtr = ringrtcReceivedOffer(ringRtcCallManager, callId, unmanagedRemote.toOpaque(), sourceDevice, offer, timestamp, ageSec)
         if retPtr == nil {
             throw CallManagerError.apiFailed(description: "receivedOffer() function failure")
         }
@@ -421,16 +857,26 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
-    public func receivedHangup(sourceDevice: UInt32, callId: UInt64) throws {
+    public func receivedHangup(sourceDevice: UInt32, callId: UInt64, hangupType: CallManagerHangupType) throws {
         AssertIsOnMainThread()
         Logger.debug("receivedHangup")
 
-        let retPtr = ringrtcReceivedHangup(ringRtcCallManager, callId, sourceDevice)
+        let retPtr = ringrtcReceivedHangup(ringRtcCallManager, callId, sourceDevice, hangupType.rawValue)
         if retPtr == nil {
             throw CallManagerError.apiFailed(description: "receivedHangup() function failure")
         }
     }
 
+    public func receivedDeclined(sourceDevice: UInt32, callId: UInt64) throws {
+        AssertIsOnMainThread()
+        Logger.debug("receivedDeclined")
+
+        let retPtr = ringrtcReceivedDeclined(ringRtcCallManager, callId, sourceDevice)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "receivedDeclined() function failure")
+        }
+    }
+
     public func receivedBusy(sourceDevice: UInt32, callId: UInt64) throws {
         AssertIsOnMainThread()
         Logger.debug("receivedBusy")
@@ -456,7 +902,7 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
-    func onEvent(remote: UnsafeRawPointer, event: CallManagerEvent) {
+    func onEvent(remote: UnsafeRawPointer, callId: UInt64, event: CallManagerEvent) {
         Logger.debug("onEvent")
 
         DispatchQueue.main.async {
@@ -465,7 +911,7 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
             guard let delegate = self.delegate else { return }
 
             let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
-            delegate.callManager(self, onEvent: callReference, event: event)
+            delegate.callManager(self, onEvent: callReference, callId: callId, event: event)
         }
     }
 
@@ -510,7 +956,7 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
-    func onSendHangup(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?) {
+    func onSendHangup(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?, hangupType: CallManagerHangupType) {
         Logger.debug("onSendHangup")
 
         DispatchQueue.main.async {
@@ -519,7 +965,7 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
             guard let delegate = self.delegate else { return }
 
             let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
-            delegate.callManager(self, shouldSendHangup: callId, call: callReference, destDevice: deviceId)
+            delegate.callManager(self, shouldSendHangup: callId, call: callReference, destDevice: deviceId, hangupType: hangupType)
         }
     }
 
@@ -547,10 +993,8 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         // Create the configuration.
         let configuration = RTCConfiguration()
 
-        // Update the configuration with the provided Ice Servers.
-        // @todo Validate and if none, set a backup value, don't expect
-        // application to know what the backup should be.
-        configuration.iceServers = appCallContext.iceServers
+        // RingRTC applies the ICE servers of the call, ordered by
+        // transport, once the PeerConnection exists.
 
         // Initialize the configuration.
         configuration.bundlePolicy = .maxBundle
@@ -619,7 +1063,174 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         return delegate.callManager(self, shouldCompareCalls: callReference1, call2: callReference2)
     }
 
-    func onCallConcluded(remote: UnsafeRawPointer) {
+    func getTurnServers(remote: UnsafeRawPointer, callId: UInt64) -> [RTCIceServer] {
+        Logger.debug("getTurnServers")
+
+        // Invoke the delegate function synchronously.
+
+        guard let delegate = self.delegate else {
+            return []
+        }
+
+        let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+        return delegate.callManager(self, getTurnServers: callReference, callId: callId)
+    }
+
+    func getCodecCapabilities() -> [CallManagerCodecCapability] {
+        Logger.debug("getCodecCapabilities")
+
+        // Invoke the delegate function synchronously.
+
+        guard let delegate = self.delegate else {
+            return []
+        }
+
+        return delegate.callManagerGetCodecCapabilities(self)
+    }
+
+    func onBandwidthEstimate(remote: UnsafeRawPointer, callId: UInt64, sendBps: UInt64, receiveBps: UInt64) {
+        DispatchQueue.main.async {
+            guard let delegate = self.delegate else { return }
+
+            let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+            delegate.callManager(self, onBandwidthEstimate: callReference, callId: callId, sendBps: sendBps, receiveBps: receiveBps)
+        }
+    }
+
+    func onNetworkRouteChanged(remote: UnsafeRawPointer, callId: UInt64, localAdapterType: CallManagerNetworkAdapterType, relayed: Bool) {
+        Logger.debug("onNetworkRouteChanged")
+
+        DispatchQueue.main.async {
+            Logger.debug("onNetworkRouteChanged - main.async")
+
+            guard let delegate = self.delegate else { return }
+
+            let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+            delegate.callManager(self, onNetworkRouteChanged: callReference, callId: callId, localAdapterType: localAdapterType, relayed: relayed)
+        }
+    }
+
+    func onSafetyNumbers(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, safetyNumbers: String) {
+        Logger.debug("onSafetyNumbers")
+
+        DispatchQueue.main.async {
+            Logger.debug("onSafetyNumbers - main.async")
+
+            guard let delegate = self.delegate else { return }
+
+            let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+            delegate.callManager(self, onSafetyNumbers: callReference, callId: callId, remoteDevice: remoteDevice, safetyNumbers: safetyNumbers)
+        }
+    }
+
+    func onReceivedAnswerMetadata(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, metadata: Data) {
+        Logger.debug("onReceivedAnswerMetadata")
+
+        DispatchQueue.main.async {
+            Logger.debug("onReceivedAnswerMetadata - main.async")
+
+            guard let delegate = self.delegate else { return }
+
+            let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+            delegate.callManager(self, onReceivedAnswerMetadata: callReference, callId: callId, remoteDevice: remoteDevice, metadata: metadata)
+        }
+    }
+
+    func seal(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, opaque: Data) -> Data? {
+        // Invoke the delegate function synchronously.
+
+        guard let delegate = self.delegate else {
+            return nil
+        }
+
+        let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+        return delegate.callManager(self, seal: callReference, callId: callId, remoteDevice: remoteDevice, opaque: opaque)
+    }
+
+    func open(callId: UInt64, remoteDevice: UInt32, sealed: Data) -> Data? {
+        // Invoke the delegate function synchronously.
+
+        guard let delegate = self.delegate else {
+            return nil
+        }
+
+        return delegate.callManager(self, open: callId, remoteDevice: remoteDevice, sealed: sealed)
+    }
+
+    func persistPendingSignaling(pending: [CallManagerPendingSignaling<UnsafeRawPointer>]) {
+        Logger.debug("persistPendingSignaling")
+
+        // Invoke the delegate function synchronously.
+
+        guard let delegate = self.delegate else { return }
+
+        let callPending = pending.map { signaling -> CallManagerPendingSignaling<CallType> in
+            let callReference: CallType = Unmanaged.fromOpaque(signaling.call).takeUnretainedValue()
+            return CallManagerPendingSignaling(call: callReference, callId: signaling.callId, remoteDevice: signaling.remoteDevice, type: signaling.type, sdp: signaling.sdp, candidates: signaling.candidates)
+        }
+        delegate.callManager(self, persistPendingSignaling: callPending)
+    }
+
+    func restorePendingSignaling() -> [CallManagerPendingSignaling<UnsafeRawPointer>] {
+        Logger.debug("restorePendingSignaling")
+
+        // Invoke the delegate function synchronously.
+
+        guard let delegate = self.delegate else {
+            return []
+        }
+
+        let callPending = delegate.callManagerRestorePendingSignaling(self)
+
+        // RingRTC refers to the calls without retaining them.
+        restoredCalls.append(contentsOf: callPending.map { $0.call })
+
+        return callPending.map { signaling -> CallManagerPendingSignaling<UnsafeRawPointer> in
+            let remote = UnsafeRawPointer(Unmanaged.passUnretained(signaling.call).toOpaque())
+            return CallManagerPendingSignaling(call: remote, callId: signaling.callId, remoteDevice: signaling.remoteDevice, type: signaling.type, sdp: signaling.sdp, candidates: signaling.candidates)
+        }
+    }
+
+    func onSendPreOffer(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32) {
+        Logger.debug("onSendPreOffer")
+
+        DispatchQueue.main.async {
+            Logger.debug("onSendPreOffer - main.async")
+
+            guard let delegate = self.delegate else { return }
+
+            let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+            delegate.callManager(self, shouldSendPreOffer: callId, call: callReference, sourceDevice: deviceId)
+        }
+    }
+
+    func onReceivedPreOffer(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32) {
+        Logger.debug("onReceivedPreOffer")
+
+        DispatchQueue.main.async {
+            Logger.debug("onReceivedPreOffer - main.async")
+
+            guard let delegate = self.delegate else { return }
+
+            let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+            delegate.callManager(self, didReceivePreOffer: callId, call: callReference, sourceDevice: deviceId)
+        }
+    }
+
+    func onAppMessageReceived(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, payload: Data) {
+        Logger.debug("onAppMessageReceived")
+
+        DispatchQueue.main.async {
+            Logger.debug("onAppMessageReceived - main.async")
+
+            guard let delegate = self.delegate else { return }
+
+            let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+            delegate.callManager(self, onAppMessageReceived: callReference, callId: callId, remoteDevice: remoteDevice, payload: payload)
+        }
+    }
+
+    func onCallConcluded(remote: UnsafeRawPointer, callId: UInt64) {
         Logger.debug("onCallConcluded")
 
         DispatchQueue.main.async {
@@ -8,16 +8,29 @@ import SignalCoreKit
 
 protocol CallManagerInterfaceDelegate: class {
     func onStartCall(remote: UnsafeRawPointer, callId: UInt64, isOutgoing: Bool)
-    func onEvent(remote: UnsafeRawPointer, event: CallManagerEvent)
+    func onEvent(remote: UnsafeRawPointer, callId: UInt64, event: CallManagerEvent)
     func onSendOffer(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?, offer: String)
     func onSendAnswer(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?, answer: String)
     func onSendIceCandidates(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?, candidates: [CallManagerIceCandidate])
-    func onSendHangup(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?)
+    func onSendHangup(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?, hangupType: CallManagerHangupType)
     func onSendBusy(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?)
     func onCreateConnection(pcObserver: UnsafeMutableRawPointer?, deviceId: UInt32, appCallContext: CallContext) -> (connection: Connection, pc: UnsafeMutableRawPointer?)
     func onConnectMedia(remote: UnsafeRawPointer, appCallContext: CallContext, stream: RTCMediaStream)
     func onCompareRemotes(remote1: UnsafeRawPointer, remote2: UnsafeRawPointer) -> Bool
-    func onCallConcluded(remote: UnsafeRawPointer)
+    func onCallConcluded(remote: UnsafeRawPointer, callId: UInt64)
+    func getTurnServers(remote: UnsafeRawPointer, callId: UInt64) -> [RTCIceServer]
+    func getCodecCapabilities() -> [CallManagerCodecCapability]
+    func onBandwidthEstimate(remote: UnsafeRawPointer, callId: UInt64, sendBps: UInt64, receiveBps: UInt64)
+    func onNetworkRouteChanged(remote: UnsafeRawPointer, callId: UInt64, localAdapterType: CallManagerNetworkAdapterType, relayed: Bool)
+    func onSafetyNumbers(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, safetyNumbers: String)
+    func onReceivedAnswerMetadata(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, metadata: Data)
+    func seal(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, opaque: Data) -> Data?
+    func open(callId: UInt64, remoteDevice: UInt32, sealed: Data) -> Data?
+    func persistPendingSignaling(pending: [CallManagerPendingSignaling<UnsafeRawPointer>])
+    func restorePendingSignaling() -> [CallManagerPendingSignaling<UnsafeRawPointer>]
+    func onSendPreOffer(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32)
+    func onReceivedPreOffer(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32)
+    func onAppMessageReceived(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, payload: Data)
 }
 
 class CallManagerInterface {
@@ -51,7 +64,20 @@ class CallManagerInterface {
              onCreateMediaStreamInterface: callManagerInterfaceOnCreateMediaStreamInterface,
              onConnectMedia: callManagerInterfaceOnConnectMedia,
              onCompareRemotes: callManagerInterfaceOnCompareRemotes,
-             onCallConcluded: callManagerInterfaceOnCallConcluded)
+             onCallConcluded: callManagerInterfaceOnCallConcluded,
+             getTurnServers: callManagerInterfaceGetTurnServers,
+             getCodecCapabilities: callManagerInterfaceGetCodecCapabilities,
+             onBandwidthEstimate: callManagerInterfaceOnBandwidthEstimate,
+             onNetworkRouteChanged: callManagerInterfaceOnNetworkRouteChanged,
+             onSafetyNumbers: callManagerInterfaceOnSafetyNumbers,
+             onReceivedAnswerMetadata: callManagerInterfaceOnReceivedAnswerMetadata,
+             seal: callManagerInterfaceSeal,
+             open: callManagerInterfaceOpen,
+             persistPendingSignaling: callManagerInterfacePersistPendingSignaling,
+             restorePendingSignaling: callManagerInterfaceRestorePendingSignaling,
+             onSendPreOffer: callManagerInterfaceOnSendPreOffer,
+             onReceivedPreOffer: callManagerInterfaceOnReceivedPreOffer,
+             onAppMessageReceived: callManagerInterfaceOnAppMessageReceived)
      }
 
     // MARK: Delegate Handlers
@@ -64,13 +90,13 @@ class CallManagerInterface {
         delegate.onStartCall(remote: remote, callId: callId, isOutgoing: isOutgoing)
     }
 
-    func onEvent(remote: UnsafeRawPointer, event: Int32) {
+    func onEvent(remote: UnsafeRawPointer, callId: UInt64, event: Int32) {
         guard let delegate = self.callManagerObserverDelegate else {
             return
         }
 
         if let validEvent = CallManagerEvent(rawValue: event) {
-            delegate.onEvent(remote: remote, event: validEvent)
+            delegate.onEvent(remote: remote, callId: callId, event: validEvent)
         } else {
             owsFailDebug("invalid event: \(event)")
         }
@@ -100,12 +126,16 @@ class CallManagerInterface {
         delegate.onSendIceCandidates(callId: callId, remote: remote, deviceId: deviceId, candidates: candidates)
     }
 
-    func onSendHangup(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?) {
+    func onSendHangup(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?, hangupType: Int32) {
         guard let delegate = self.callManagerObserverDelegate else {
             return
         }
 
-        delegate.onSendHangup(callId: callId, remote: remote, deviceId: deviceId)
+        if let validHangupType = CallManagerHangupType(rawValue: hangupType) {
+            delegate.onSendHangup(callId: callId, remote: remote, deviceId: deviceId, hangupType: validHangupType)
+        } else {
+            owsFailDebug("invalid hangupType: \(hangupType)")
+        }
     }
 
     func onSendBusy(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32?) {
@@ -140,12 +170,117 @@ class CallManagerInterface {
         return delegate.onCompareRemotes(remote1: remote1, remote2: remote2)
     }
 
-    func onCallConcluded(remote: UnsafeRawPointer) {
+    func onCallConcluded(remote: UnsafeRawPointer, callId: UInt64) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.onCallConcluded(remote: remote, callId: callId)
+    }
+
+    func getTurnServers(remote: UnsafeRawPointer, callId: UInt64) -> [RTCIceServer] {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return []
+        }
+
+        return delegate.getTurnServers(remote: remote, callId: callId)
+    }
+
+    func getCodecCapabilities() -> [CallManagerCodecCapability] {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return []
+        }
+
+        return delegate.getCodecCapabilities()
+    }
+
+    func onBandwidthEstimate(remote: UnsafeRawPointer, callId: UInt64, sendBps: UInt64, receiveBps: UInt64) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.onBandwidthEstimate(remote: remote, callId: callId, sendBps: sendBps, receiveBps: receiveBps)
+    }
+
+    func onNetworkRouteChanged(remote: UnsafeRawPointer, callId: UInt64, localAdapterType: Int32, relayed: Bool) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        let adapterType = CallManagerNetworkAdapterType(rawValue: localAdapterType) ?? .unknown
+        delegate.onNetworkRouteChanged(remote: remote, callId: callId, localAdapterType: adapterType, relayed: relayed)
+    }
+
+    func onSafetyNumbers(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, safetyNumbers: String) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.onSafetyNumbers(remote: remote, callId: callId, remoteDevice: remoteDevice, safetyNumbers: safetyNumbers)
+    }
+
+    func onReceivedAnswerMetadata(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, metadata: Data) {
         guard let delegate = self.callManagerObserverDelegate else {
             return
         }
 
-        delegate.onCallConcluded(remote: remote)
+        delegate.onReceivedAnswerMetadata(remote: remote, callId: callId, remoteDevice: remoteDevice, metadata: metadata)
+    }
+
+    func seal(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, opaque: Data) -> Data? {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return nil
+        }
+
+        return delegate.seal(remote: remote, callId: callId, remoteDevice: remoteDevice, opaque: opaque)
+    }
+
+    func open(callId: UInt64, remoteDevice: UInt32, sealed: Data) -> Data? {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return nil
+        }
+
+        return delegate.open(callId: callId, remoteDevice: remoteDevice, sealed: sealed)
+    }
+
+    func persistPendingSignaling(pending: [CallManagerPendingSignaling<UnsafeRawPointer>]) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.persistPendingSignaling(pending: pending)
+    }
+
+    func restorePendingSignaling() -> [CallManagerPendingSignaling<UnsafeRawPointer>] {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return []
+        }
+
+        return delegate.restorePendingSignaling()
+    }
+
+    func onSendPreOffer(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.onSendPreOffer(callId: callId, remote: remote, deviceId: deviceId)
+    }
+
+    func onReceivedPreOffer(callId: UInt64, remote: UnsafeRawPointer, deviceId: UInt32) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.onReceivedPreOffer(callId: callId, remote: remote, deviceId: deviceId)
+    }
+
+    func onAppMessageReceived(remote: UnsafeRawPointer, callId: UInt64, remoteDevice: UInt32, payload: Data) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.onAppMessageReceived(remote: remote, callId: callId, remoteDevice: remoteDevice, payload: payload)
     }
 }
 
@@ -175,7 +310,7 @@ func callManagerInterfaceOnStartCall(object: UnsafeMutableRawPointer?, remote: U
     obj.onStartCall(remote: remote, callId: callId, isOutgoing: isOutgoing)
 }
 
-func callManagerInterfaceOnCallEvent(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, event: Int32) {
+func callManagerInterfaceOnCallEvent(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64, event: Int32) {
     guard let object = object else {
         owsFailDebug("object was unexpectedly nil")
         return
@@ -187,7 +322,7 @@ func callManagerInterfaceOnCallEvent(object: UnsafeMutableRawPointer?, remote: U
         return
     }
 
-    obj.onEvent(remote: remote, event: event)
+    obj.onEvent(remote: remote, callId: callId, event: event)
 }
 
 func callManagerInterfaceOnSendOffer(object: UnsafeMutableRawPointer?, callId: UInt64, remote: UnsafeRawPointer?, deviceId: UInt32, broadcast: Bool, offer: AppByteSlice) {
@@ -295,7 +430,7 @@ func callManagerInterfaceOnSendIceCandidates(object: UnsafeMutableRawPointer?, c
     obj.onSendIceCandidates(callId: callId, remote: remote, deviceId: deviceId, candidates: finalCandidates)
 }
 
-func callManagerInterfaceOnSendHangup(object: UnsafeMutableRawPointer?, callId: UInt64, remote: UnsafeRawPointer?, deviceId: UInt32, broadcast: Bool) {
+func callManagerInterfaceOnSendHangup(object: UnsafeMutableRawPointer?, callId: UInt64, remote: UnsafeRawPointer?, deviceId: UInt32, broadcast: Bool, hangupType: Int32) {
     guard let object = object else {
         owsFailDebug("object was unexpectedly nil")
         return
@@ -313,7 +448,7 @@ func callManagerInterfaceOnSendHangup(object: UnsafeMutableRawPointer?, callId:
         deviceId = nil
     }
 
-    obj.onSendHangup(callId: callId, remote: remote, deviceId: deviceId)
+    obj.onSendHangup(callId: callId, remote: remote, deviceId: deviceId, hangupType: hangupType)
 }
 
 func callManagerInterfaceOnSendBusy(object: UnsafeMutableRawPointer?, callId: UInt64, remote: UnsafeRawPointer?, deviceId: UInt32, broadcast: Bool) {
@@ -465,7 +600,227 @@ func callManagerInterfaceOnCompareRemotes(object: UnsafeMutableRawPointer?, remo
     return obj.onCompareRemotes(remote1: remote1, remote2: remote2)
 }
 
-func callManagerInterfaceOnCallConcluded(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?) {
+func callManagerInterfaceOnCallConcluded(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return
+    }
+
+    obj.onCallConcluded(remote: remote, callId: callId)
+}
+
+func callManagerInterfaceGetTurnServers(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64) -> AppIceServerArray {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return AppIceServers([]).getWrapper()
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return AppIceServers([]).getWrapper()
+    }
+
+    return AppIceServers(obj.getTurnServers(remote: remote, callId: callId)).getWrapper()
+}
+
+func callManagerInterfaceGetCodecCapabilities(object: UnsafeMutableRawPointer?) -> AppCodecCapabilityArray {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return AppCodecCapabilities([]).getWrapper()
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    return AppCodecCapabilities(obj.getCodecCapabilities()).getWrapper()
+}
+
+func callManagerInterfaceOnBandwidthEstimate(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64, sendBps: UInt64, receiveBps: UInt64) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return
+    }
+
+    obj.onBandwidthEstimate(remote: remote, callId: callId, sendBps: sendBps, receiveBps: receiveBps)
+}
+
+func callManagerInterfaceOnNetworkRouteChanged(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64, localAdapterType: Int32, relayed: Bool) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return
+    }
+
+    obj.onNetworkRouteChanged(remote: remote, callId: callId, localAdapterType: localAdapterType, relayed: relayed)
+}
+
+func callManagerInterfaceOnSafetyNumbers(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64, remoteDevice: UInt32, safetyNumbers: AppByteSlice) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return
+    }
+
+    guard let string = safetyNumbers.asString() else {
+        owsFailDebug("safetyNumbers was unexpectedly nil")
+        return
+    }
+
+    obj.onSafetyNumbers(remote: remote, callId: callId, remoteDevice: remoteDevice, safetyNumbers: string)
+}
+
+func callManagerInterfaceOnReceivedAnswerMetadata(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64, remoteDevice: UInt32, metadata: AppByteSlice) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return
+    }
+
+    obj.onReceivedAnswerMetadata(remote: remote, callId: callId, remoteDevice: remoteDevice, metadata: metadata.asData())
+}
+
+func callManagerInterfaceSeal(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64, remoteDevice: UInt32, opaque: AppByteSlice) -> AppByteArray {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return AppBytes(nil).getWrapper()
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return AppBytes(nil).getWrapper()
+    }
+
+    return AppBytes(obj.seal(remote: remote, callId: callId, remoteDevice: remoteDevice, opaque: opaque.asData())).getWrapper()
+}
+
+func callManagerInterfaceOpen(object: UnsafeMutableRawPointer?, callId: UInt64, remoteDevice: UInt32, sealed: AppByteSlice) -> AppByteArray {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return AppBytes(nil).getWrapper()
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    return AppBytes(obj.open(callId: callId, remoteDevice: remoteDevice, sealed: sealed.asData())).getWrapper()
+}
+
+func callManagerInterfacePersistPendingSignaling(object: UnsafeMutableRawPointer?, pending: UnsafePointer<AppPendingSignaling>?, count: Int) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    // Form the application level messages with copies of all
+    // strings before returning.
+    var finalPending: [CallManagerPendingSignaling<UnsafeRawPointer>] = []
+
+    for signaling in UnsafeBufferPointer(start: pending, count: count) {
+        guard let remote = signaling.remote else {
+            owsFailDebug("remote was unexpectedly nil")
+            continue
+        }
+
+        guard let type = CallManagerPendingSignalingType(rawValue: signaling.kind) else {
+            owsFailDebug("unexpected kind")
+            continue
+        }
+
+        var candidates: [CallManagerIceCandidate] = []
+        for iceCandidate in UnsafeBufferPointer(start: signaling.candidates, count: signaling.candidatesCount) {
+            guard let sdpString = iceCandidate.sdp.asString(), let sdpMidString = iceCandidate.sdpMid.asString() else {
+                owsFailDebug("unexpected string")
+                continue
+            }
+
+            candidates.append(CallManagerIceCandidate(sdp: sdpString, sdpMLineIndex: iceCandidate.sdpMLineIndex, sdpMid: sdpMidString))
+        }
+
+        let sdp = signaling.sdp.bytes == nil ? nil : signaling.sdp.asString()
+        finalPending.append(CallManagerPendingSignaling(call: remote, callId: signaling.callId, remoteDevice: signaling.remoteDevice, type: type, sdp: sdp, candidates: candidates))
+    }
+
+    obj.persistPendingSignaling(pending: finalPending)
+}
+
+func callManagerInterfaceRestorePendingSignaling(object: UnsafeMutableRawPointer?) -> AppPendingSignalingArray {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return AppPendingSignalings([]).getWrapper()
+    }
+
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    return AppPendingSignalings(obj.restorePendingSignaling()).getWrapper()
+}
+
+func callManagerInterfaceOnSendPreOffer(object: UnsafeMutableRawPointer?, callId: UInt64, remote: UnsafeRawPointer?, deviceId: UInt32) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return
+    }
+
+    obj.onSendPreOffer(callId: callId, remote: remote, deviceId: deviceId)
+}
+
+func callManagerInterfaceOnReceivedPreOffer(object: UnsafeMutableRawPointer?, callId: UInt64, remote: UnsafeRawPointer?, deviceId: UInt32) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        owsFailDebug("remote was unexpectedly nil")
+        return
+    }
+
+    obj.onReceivedPreOffer(callId: callId, remote: remote, deviceId: deviceId)
+}
+
+func callManagerInterfaceOnAppMessageReceived(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64, remoteDevice: UInt32, payload: AppByteSlice) {
     guard let object = object else {
         owsFailDebug("object was unexpectedly nil")
         return
@@ -478,5 +833,5 @@ func callManagerInterfaceOnCallConcluded(object: UnsafeMutableRawPointer?, remot
         return
     }
 
-    obj.onCallConcluded(remote: remote)
+    obj.onAppMessageReceived(remote: remote, callId: callId, remoteDevice: remoteDevice, payload: payload.asData())
 }
@@ -3,6 +3,8 @@
 //
 
 import SignalRingRTC.RingRTC
+import WebRTC
+import SignalCoreKit
 
 extension AppByteSlice {
     func asUnsafeBufferPointer() -> UnsafeBufferPointer<UInt8> {
@@ -12,4 +14,213 @@ extension AppByteSlice {
     func asString(encoding: String.Encoding = String.Encoding.utf8) -> String? {
         return String(bytes: asUnsafeBufferPointer(), encoding: encoding)
     }
+
+    func asData() -> Data {
+        return Data(asUnsafeBufferPointer())
+    }
+}
+
+// Holds copies of strings as AppByteSlices for as long as the
+// object lives.
+class AppByteSlices {
+    private var buffers: [UnsafeMutableBufferPointer<UInt8>] = []
+
+    deinit {
+        for buffer in buffers {
+            buffer.deallocate()
+        }
+    }
+
+    func slice(_ string: String) -> AppByteSlice {
+        let bytes = Array(string.utf8)
+        let buffer = UnsafeMutableBufferPointer<UInt8>.allocate(capacity: max(bytes.count, 1))
+        _ = buffer.initialize(from: bytes)
+        buffers.append(buffer)
+        return AppByteSlice(bytes: UnsafePointer(buffer.baseAddress), len: bytes.count)
+    }
+}
+
+// Holds ICE servers as AppIceServers, one per URL, for as long as
+// the object lives.
+class AppIceServers {
+    private let slices: AppByteSlices
+    let servers: UnsafeMutableBufferPointer<AppIceServer>
+    let count: Int
+
+    init(_ iceServers: [RTCIceServer]) {
+        let slices = AppByteSlices()
+        var appIceServers: [AppIceServer] = []
+        for iceServer in iceServers {
+            for url in iceServer.urlStrings {
+                appIceServers.append(AppIceServer(url: slices.slice(url), username: slices.slice(iceServer.username ?? ""), password: slices.slice(iceServer.credential ?? "")))
+            }
+        }
+
+        self.servers = UnsafeMutableBufferPointer<AppIceServer>.allocate(capacity: max(appIceServers.count, 1))
+        _ = self.servers.initialize(from: appIceServers)
+        self.count = appIceServers.count
+        self.slices = slices
+    }
+
+    deinit {
+        servers.deallocate()
+    }
+
+    // Passes the servers to RingRTC, which releases them when done.
+    func getWrapper() -> AppIceServerArray {
+        return AppIceServerArray(
+            servers: UnsafePointer(servers.baseAddress),
+            count: count,
+            object: UnsafeMutableRawPointer(Unmanaged.passRetained(self).toOpaque()),
+            destroy: appIceServersDestroy)
+    }
+}
+
+func appIceServersDestroy(object: UnsafeMutableRawPointer?) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    _ = Unmanaged<AppIceServers>.fromOpaque(object).takeRetainedValue()
+}
+
+// Holds hardware codecs as AppCodecCapabilities for as long as the
+// object lives.
+class AppCodecCapabilities {
+    private let slices: AppByteSlices
+    let codecs: UnsafeMutableBufferPointer<AppCodecCapability>
+    let count: Int
+
+    init(_ codecCapabilities: [CallManagerCodecCapability]) {
+        let slices = AppByteSlices()
+        var appCodecs: [AppCodecCapability] = []
+        for codec in codecCapabilities {
+            appCodecs.append(AppCodecCapability(name: slices.slice(codec.name), encode: codec.encode, decode: codec.decode))
+        }
+
+        self.codecs = UnsafeMutableBufferPointer<AppCodecCapability>.allocate(capacity: max(appCodecs.count, 1))
+        _ = self.codecs.initialize(from: appCodecs)
+        self.count = appCodecs.count
+        self.slices = slices
+    }
+
+    deinit {
+        codecs.deallocate()
+    }
+
+    // Passes the codecs to RingRTC, which releases them when done.
+    func getWrapper() -> AppCodecCapabilityArray {
+        return AppCodecCapabilityArray(
+            codecs: UnsafePointer(codecs.baseAddress),
+            count: count,
+            object: UnsafeMutableRawPointer(Unmanaged.passRetained(self).toOpaque()),
+            destroy: appCodecCapabilitiesDestroy)
+    }
+}
+
+func appCodecCapabilitiesDestroy(object: UnsafeMutableRawPointer?) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    _ = Unmanaged<AppCodecCapabilities>.fromOpaque(object).takeRetainedValue()
+}
+
+// Holds a copy of optional bytes, e.g. a sealed message, as an
+// AppByteArray for as long as the object lives.
+class AppBytes {
+    let bytes: UnsafeMutableBufferPointer<UInt8>?
+
+    init(_ data: Data?) {
+        if let data = data {
+            let buffer = UnsafeMutableBufferPointer<UInt8>.allocate(capacity: max(data.count, 1))
+            _ = buffer.initialize(from: data)
+            self.bytes = buffer
+        } else {
+            self.bytes = nil
+        }
+    }
+
+    deinit {
+        bytes?.deallocate()
+    }
+
+    // Passes the bytes to RingRTC, which releases them when done.
+    func getWrapper() -> AppByteArray {
+        return AppByteArray(
+            bytes: UnsafePointer(bytes?.baseAddress),
+            len: bytes?.count ?? 0,
+            object: UnsafeMutableRawPointer(Unmanaged.passRetained(self).toOpaque()),
+            destroy: appBytesDestroy)
+    }
+}
+
+func appBytesDestroy(object: UnsafeMutableRawPointer?) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    _ = Unmanaged<AppBytes>.fromOpaque(object).takeRetainedValue()
+}
+
+// Holds pending signaling messages as AppPendingSignalings for as
+// long as the object lives.
+class AppPendingSignalings {
+    private let slices: AppByteSlices
+    private let candidateBuffers: [UnsafeMutableBufferPointer<AppIceCandidate>]
+    let pending: UnsafeMutableBufferPointer<AppPendingSignaling>
+    let count: Int
+
+    init(_ pendingSignaling: [CallManagerPendingSignaling<UnsafeRawPointer>]) {
+        let slices = AppByteSlices()
+        var candidateBuffers: [UnsafeMutableBufferPointer<AppIceCandidate>] = []
+        var appPending: [AppPendingSignaling] = []
+        for signaling in pendingSignaling {
+            var appCandidates: [AppIceCandidate] = []
+            for candidate in signaling.candidates {
+                appCandidates.append(AppIceCandidate(sdpMid: slices.slice(candidate.sdpMid), sdpMLineIndex: candidate.sdpMLineIndex, sdp: slices.slice(candidate.sdp)))
+            }
+
+            let candidates = UnsafeMutableBufferPointer<AppIceCandidate>.allocate(capacity: max(appCandidates.count, 1))
+            _ = candidates.initialize(from: appCandidates)
+            candidateBuffers.append(candidates)
+
+            let sdp = signaling.sdp.map { slices.slice($0) } ?? AppByteSlice(bytes: nil, len: 0)
+            appPending.append(AppPendingSignaling(remote: signaling.call, callId: signaling.callId, remoteDevice: signaling.remoteDevice, kind: signaling.type.rawValue, sdp: sdp, candidates: UnsafePointer(candidates.baseAddress), candidatesCount: appCandidates.count))
+        }
+
+        self.pending = UnsafeMutableBufferPointer<AppPendingSignaling>.allocate(capacity: max(appPending.count, 1))
+        _ = self.pending.initialize(from: appPending)
+        self.count = appPending.count
+        self.candidateBuffers = candidateBuffers
+        self.slices = slices
+    }
+
+    deinit {
+        pending.deallocate()
+        for buffer in candidateBuffers {
+            buffer.deallocate()
+        }
+    }
+
+    // Passes the messages to RingRTC, which releases them when done.
+    func getWrapper() -> AppPendingSignalingArray {
+        return AppPendingSignalingArray(
+            pending: UnsafePointer(pending.baseAddress),
+            count: count,
+            object: UnsafeMutableRawPointer(Unmanaged.passRetained(self).toOpaque()),
+            destroy: appPendingSignalingsDestroy)
+    }
+}
+
+func appPendingSignalingsDestroy(object: UnsafeMutableRawPointer?) {
+    guard let object = object else {
+        owsFailDebug("object was unexpectedly nil")
+        return
+    }
+
+    _ = Unmanaged<AppPendingSignalings>.fromOpaque(object).takeRetainedValue()
 }
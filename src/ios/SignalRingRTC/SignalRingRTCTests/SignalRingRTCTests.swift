@@ -124,7 +124,7 @@ final class TestDelegate: CallManagerDelegate {
         }
     }
 
-    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onEvent call: OpaqueCallData, event: CallManagerEvent) {
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onEvent call: OpaqueCallData, callId: UInt64, event: CallManagerEvent) {
         Logger.debug("TestDelegate:onEvent")
         generalInvocationDetected = true
 
@@ -176,6 +176,68 @@ final class TestDelegate: CallManagerDelegate {
             Logger.debug("TestDelegate:endedReceivedOfferExpired")
         case .endedReceivedOfferWhileActive:
             Logger.debug("TestDelegate:endedReceivedOfferWhileActive")
+        case .codecFallback:
+            Logger.debug("TestDelegate:codecFallback")
+        case .renegotiationTimeout:
+            Logger.debug("TestDelegate:renegotiationTimeout")
+        case .audioDeviceFailure:
+            Logger.debug("TestDelegate:audioDeviceFailure")
+        case .audioDevicePermissionDenied:
+            Logger.debug("TestDelegate:audioDevicePermissionDenied")
+        case .audioDeviceBusy:
+            Logger.debug("TestDelegate:audioDeviceBusy")
+        case .endedAudioDeviceFailure:
+            Logger.debug("TestDelegate:endedAudioDeviceFailure")
+        case .endedRemoteDeclined:
+            Logger.debug("TestDelegate:endedRemoteDeclined")
+        case .groupParticipantJoined:
+            Logger.debug("TestDelegate:groupParticipantJoined")
+        case .groupParticipantLeft:
+            Logger.debug("TestDelegate:groupParticipantLeft")
+        case .remoteAudioMuted:
+            Logger.debug("TestDelegate:remoteAudioMuted")
+        case .remoteAudioUnmuted:
+            Logger.debug("TestDelegate:remoteAudioUnmuted")
+        case .endedReceivedOfferWithGlare:
+            Logger.debug("TestDelegate:endedReceivedOfferWithGlare")
+        case .endedRemoteHangupAccepted:
+            Logger.debug("TestDelegate:endedRemoteHangupAccepted")
+        case .endedRemoteHangupBusy:
+            Logger.debug("TestDelegate:endedRemoteHangupBusy")
+        case .endedRemoteHangupNeedPermission:
+            Logger.debug("TestDelegate:endedRemoteHangupNeedPermission")
+        case .endedRingRefused:
+            Logger.debug("TestDelegate:endedRingRefused")
+        case .remoteOnHold:
+            Logger.debug("TestDelegate:remoteOnHold")
+        case .remoteResumed:
+            Logger.debug("TestDelegate:remoteResumed")
+        case .endedSetupCancelled:
+            Logger.debug("TestDelegate:endedSetupCancelled")
+        case .transferDeclined:
+            Logger.debug("TestDelegate:transferDeclined")
+        case .endedTransferred:
+            Logger.debug("TestDelegate:endedTransferred")
+        case .endedRemoteBusyRetry:
+            Logger.debug("TestDelegate:endedRemoteBusyRetry")
+        case .busyRetryCancelled:
+            Logger.debug("TestDelegate:busyRetryCancelled")
+        case .iceServersExpiring:
+            Logger.debug("TestDelegate:iceServersExpiring")
+        case .remoteRecordingStarted:
+            Logger.debug("TestDelegate:remoteRecordingStarted")
+        case .remoteRecordingStopped:
+            Logger.debug("TestDelegate:remoteRecordingStopped")
+        case .remoteMediaStalled:
+            Logger.debug("TestDelegate:remoteMediaStalled")
+        case .remoteMediaResumed:
+            Logger.debug("TestDelegate:remoteMediaResumed")
+        case .remoteSharingScreen:
+            Logger.debug("TestDelegate:remoteSharingScreen")
+        case .remoteStoppedSharingScreen:
+            Logger.debug("TestDelegate:remoteStoppedSharingScreen")
+        case .endedRemoteMediaClosed:
+            Logger.debug("TestDelegate:endedRemoteMediaClosed")
         }
     }
 
@@ -277,7 +339,7 @@ final class TestDelegate: CallManagerDelegate {
         }
     }
 
-    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, shouldSendHangup callId: UInt64, call: OpaqueCallData, destDevice: UInt32?) {
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, shouldSendHangup callId: UInt64, call: OpaqueCallData, destDevice: UInt32?, hangupType: CallManagerHangupType) {
         Logger.debug("TestDelegate:shouldSendHangup")
         generalInvocationDetected = true
 
@@ -350,6 +412,83 @@ final class TestDelegate: CallManagerDelegate {
         }
     }
 
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, getTurnServers call: OpaqueCallData, callId: UInt64) -> [RTCIceServer] {
+        Logger.debug("TestDelegate:getTurnServers")
+        generalInvocationDetected = true
+
+        // Keep the servers given to proceed().
+        return []
+    }
+
+    func callManagerGetCodecCapabilities(_ callManager: CallManager<OpaqueCallData, TestDelegate>) -> [CallManagerCodecCapability] {
+        Logger.debug("TestDelegate:getCodecCapabilities")
+        generalInvocationDetected = true
+
+        return []
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onBandwidthEstimate call: OpaqueCallData, callId: UInt64, sendBps: UInt64, receiveBps: UInt64) {
+        Logger.debug("TestDelegate:onBandwidthEstimate")
+        generalInvocationDetected = true
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onNetworkRouteChanged call: OpaqueCallData, callId: UInt64, localAdapterType: CallManagerNetworkAdapterType, relayed: Bool) {
+        Logger.debug("TestDelegate:onNetworkRouteChanged")
+        generalInvocationDetected = true
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onSafetyNumbers call: OpaqueCallData, callId: UInt64, remoteDevice: UInt32, safetyNumbers: String) {
+        Logger.debug("TestDelegate:onSafetyNumbers")
+        generalInvocationDetected = true
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onReceivedAnswerMetadata call: OpaqueCallData, callId: UInt64, remoteDevice: UInt32, metadata: Data) {
+        Logger.debug("TestDelegate:onReceivedAnswerMetadata")
+        generalInvocationDetected = true
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, seal call: OpaqueCallData, callId: UInt64, remoteDevice: UInt32, opaque: Data) -> Data? {
+        Logger.debug("TestDelegate:seal")
+        generalInvocationDetected = true
+
+        // Send the message as is.
+        return nil
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, open callId: UInt64, remoteDevice: UInt32, sealed: Data) -> Data? {
+        Logger.debug("TestDelegate:open")
+        generalInvocationDetected = true
+
+        return nil
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, persistPendingSignaling pending: [CallManagerPendingSignaling<OpaqueCallData>]) {
+        Logger.debug("TestDelegate:persistPendingSignaling")
+        generalInvocationDetected = true
+    }
+
+    func callManagerRestorePendingSignaling(_ callManager: CallManager<OpaqueCallData, TestDelegate>) -> [CallManagerPendingSignaling<OpaqueCallData>] {
+        Logger.debug("TestDelegate:restorePendingSignaling")
+        generalInvocationDetected = true
+
+        return []
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, shouldSendPreOffer callId: UInt64, call: OpaqueCallData, sourceDevice: UInt32) {
+        Logger.debug("TestDelegate:shouldSendPreOffer")
+        generalInvocationDetected = true
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, didReceivePreOffer callId: UInt64, call: OpaqueCallData, sourceDevice: UInt32) {
+        Logger.debug("TestDelegate:didReceivePreOffer")
+        generalInvocationDetected = true
+    }
+
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onAppMessageReceived call: OpaqueCallData, callId: UInt64, remoteDevice: UInt32, payload: Data) {
+        Logger.debug("TestDelegate:onAppMessageReceived")
+        generalInvocationDetected = true
+    }
+
     func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onUpdateLocalVideoSession call: OpaqueCallData, session: AVCaptureSession?) {
         Logger.debug("TestDelegate:onUpdateLocalVideoSession")
         generalInvocationDetected = true
@@ -706,7 +845,7 @@ class SignalRingRTCTests: XCTestCase {
             // value injection mechanism.
             let timestamp = UInt64(Date().timeIntervalSince1970 * 1000)
 
-            try callManager?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: self.audioOffer, timestamp: timestamp)
+            try callManager?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: self.audioOffer, timestamp: timestamp, ageSec: 0)
         } catch {
             XCTFail("Call Manager receivedOffer() failed: \(error)")
             return
@@ -1068,7 +1207,7 @@ class SignalRingRTCTests: XCTestCase {
             // value injection mechanism.
             let timestamp = UInt64(Date().timeIntervalSince1970 * 1000)
 
-            try callManager?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: self.audioOffer, timestamp: timestamp)
+            try callManager?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: self.audioOffer, timestamp: timestamp, ageSec: 0)
         } catch {
             XCTFail("Call Manager receivedOffer() failed: \(error)")
             return
@@ -1081,7 +1220,7 @@ class SignalRingRTCTests: XCTestCase {
         // Say a hangup comes in immediately, because the other end does a quick hangup.
         do {
             Logger.debug("Test: Invoking receivedHangup()...")
-            try callManager?.receivedHangup(sourceDevice: sourceDevice, callId: callId)
+            try callManager?.receivedHangup(sourceDevice: sourceDevice, callId: callId, hangupType: .normal)
         } catch {
             XCTFail("Call Manager receivedHangup() failed: \(error)")
             return
@@ -1141,7 +1280,7 @@ class SignalRingRTCTests: XCTestCase {
             // value injection mechanism.
             let timestamp = UInt64(Date().timeIntervalSince1970 * 1000)
 
-            try callManager?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: self.audioOffer, timestamp: timestamp)
+            try callManager?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: self.audioOffer, timestamp: timestamp, ageSec: 0)
         } catch {
             XCTFail("Call Manager receivedOffer() failed: \(error)")
             return
@@ -1153,7 +1292,7 @@ class SignalRingRTCTests: XCTestCase {
         // Say a hangup comes in immediately, because the other end does a quick hangup.
         do {
             Logger.debug("Test: Invoking receivedHangup()...")
-            try callManager?.receivedHangup(sourceDevice: sourceDevice, callId: callId)
+            try callManager?.receivedHangup(sourceDevice: sourceDevice, callId: callId, hangupType: .normal)
         } catch {
             XCTFail("Call Manager receivedHangup() failed: \(error)")
             return
@@ -1216,7 +1355,7 @@ class SignalRingRTCTests: XCTestCase {
             // value injection mechanism.
             let timestamp = UInt64(Date().timeIntervalSince1970 * 1000)
 
-            try callManager?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: self.audioOffer, timestamp: timestamp)
+            try callManager?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: self.audioOffer, timestamp: timestamp, ageSec: 0)
         } catch {
             XCTFail("Call Manager receivedOffer() failed: \(error)")
             return
@@ -1228,7 +1367,7 @@ class SignalRingRTCTests: XCTestCase {
         // Say a hangup comes in immediately, because the other end does a quick hangup.
         do {
             Logger.debug("Test: Invoking receivedHangup()...")
-            try callManager?.receivedHangup(sourceDevice: sourceDevice, callId: callId)
+            try callManager?.receivedHangup(sourceDevice: sourceDevice, callId: callId, hangupType: .normal)
         } catch {
             XCTFail("Call Manager receivedHangup() failed: \(error)")
             return
@@ -1331,7 +1470,7 @@ class SignalRingRTCTests: XCTestCase {
             // value injection mechanism.
             let timestamp = UInt64(Date().timeIntervalSince1970 * 1000)
 
-            try callManagerCallee?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: delegateCaller.sentOffer, timestamp: timestamp)
+            try callManagerCallee?.receivedOffer(call: call, sourceDevice: sourceDevice, callId: callId, sdp: delegateCaller.sentOffer, timestamp: timestamp, ageSec: 0)
         } catch {
             XCTFail("Call Manager receivedOffer() failed: \(error)")
             return
@@ -53,6 +53,9 @@ Rust_dataChannelSend(webrtc::DataChannelInterface* data_channel,
                      size_t                        len,
                      bool                          binary);
 
+RUSTEXPORT uint64_t
+Rust_dataChannelBufferedAmount(webrtc::DataChannelInterface* data_channel);
+
 RUSTEXPORT const char*
 Rust_dataChannelGetLabel(webrtc::DataChannelInterface* data_channel);
 
@@ -13,6 +13,7 @@
 #include "api/peer_connection_interface.h"
 #include "rffi/api/data_channel.h"
 #include "rffi/api/sdp_observer_intf.h"
+#include "rffi/api/stats_observer_intf.h"
 
 /**
  * Rust friendly wrapper around some webrtc::PeerConnectionInterface
@@ -38,6 +39,9 @@ Rust_createSessionDescriptionAnswer(const char* description);
 RUSTEXPORT webrtc::SessionDescriptionInterface*
 Rust_createSessionDescriptionOffer(const char* description);
 
+RUSTEXPORT webrtc::SessionDescriptionInterface*
+Rust_createSessionDescriptionRollback();
+
 RUSTEXPORT void
 Rust_createAnswer(webrtc::PeerConnectionInterface*                    pc_interface,
                   webrtc::rffi::CreateSessionDescriptionObserverRffi* csd_observer);
@@ -68,4 +72,117 @@ Rust_addIceCandidate(webrtc::PeerConnectionInterface* pc_interface,
                      int32_t                          sdp_mline_index,
                      const char*                      sdp);
 
+RUSTEXPORT bool
+Rust_setVideoScaleResolutionDownBy(webrtc::PeerConnectionInterface* pc_interface,
+                                   double                           scale);
+
+/*
+ * NOTE: A framerate of 0 removes the cap.
+ */
+RUSTEXPORT bool
+Rust_setVideoMaxFramerate(webrtc::PeerConnectionInterface* pc_interface,
+                          uint32_t                         max_framerate);
+
+/*
+ * NOTE: The preference is 0 to maintain framerate, 1 to maintain
+ * resolution or 2 for balanced.
+ */
+RUSTEXPORT bool
+Rust_setVideoDegradationPreference(webrtc::PeerConnectionInterface* pc_interface,
+                                   int32_t                          preference);
+
+RUSTEXPORT bool
+Rust_setOutgoingAudioEnabled(webrtc::PeerConnectionInterface* pc_interface,
+                             bool                             enabled);
+
+/*
+ * NOTE: Video tracks with a detailed or text content hint carry a
+ * screen share, and are left to Rust_setOutgoingScreenShareEnabled().
+ */
+RUSTEXPORT bool
+Rust_setOutgoingVideoEnabled(webrtc::PeerConnectionInterface* pc_interface,
+                             bool                             enabled);
+
+RUSTEXPORT bool
+Rust_setOutgoingScreenShareEnabled(webrtc::PeerConnectionInterface* pc_interface,
+                                   bool                             enabled);
+
+/*
+ * NOTE: The gain is a linear multiplier, 1.0 leaves the audio
+ * unchanged.
+ */
+RUSTEXPORT bool
+Rust_setOutgoingAudioGain(webrtc::PeerConnectionInterface* pc_interface,
+                          double                           gain);
+
+/*
+ * NOTE: A negative delay lets WebRTC choose the delay of the audio
+ * jitter buffers again.
+ */
+RUSTEXPORT bool
+Rust_setAudioJitterBufferMinimumDelay(webrtc::PeerConnectionInterface* pc_interface,
+                                      double                           delay_seconds);
+
+/*
+ * NOTE: The tones are queued on the DTMF sender of the first audio
+ * sender, behind any tones still being played.
+ */
+RUSTEXPORT bool
+Rust_insertDtmf(webrtc::PeerConnectionInterface* pc_interface,
+                const char*                      tones,
+                int32_t                          duration_ms,
+                int32_t                          gap_ms);
+
+/*
+ * NOTE: The bitrate caps all media sent, audio and video together.
+ */
+RUSTEXPORT bool
+Rust_setMaxBitrate(webrtc::PeerConnectionInterface* pc_interface,
+                   int32_t                          bitrate_bps);
+
+/* A STUN or TURN server, with the transport explicit in the URL */
+typedef struct {
+  const char* url;
+  const char* username;
+  const char* password;
+} RffiIceServer;
+
+/*
+ * NOTE: Fails if the set is empty while the ICE transport policy is
+ * relay only.
+ */
+RUSTEXPORT bool
+Rust_setIceServers(webrtc::PeerConnectionInterface* pc_interface,
+                   const RffiIceServer*             ice_servers,
+                   size_t                           ice_servers_size);
+
+RUSTEXPORT void
+Rust_restartIce(webrtc::PeerConnectionInterface* pc_interface);
+
+/* Largest SRTP keying material, two keys and salts of AEAD_AES_256_GCM */
+#define RFFI_MAX_SRTP_KEYING_MATERIAL (2 * (32 + 12))
+
+/* SRTP keying material exported from DTLS, see RFC 5764 */
+typedef struct {
+  // SRTP protection profile, e.g. 1 for SRTP_AES128_CM_SHA1_80
+  int32_t crypto_suite;
+  // Whether the local side was the DTLS client
+  bool    dtls_client;
+  // Client key, server key, client salt and server salt
+  uint8_t material[RFFI_MAX_SRTP_KEYING_MATERIAL];
+  size_t  material_size;
+} RffiSrtpKeyingMaterial;
+
+/*
+ * NOTE: Only used by Rust built with the srtp_key_export feature.
+ * Fails until DTLS has negotiated the keys.
+ */
+RUSTEXPORT bool
+Rust_exportSrtpKeyingMaterial(webrtc::PeerConnectionInterface* pc_interface,
+                              RffiSrtpKeyingMaterial*          material);
+
+RUSTEXPORT void
+Rust_getStats(webrtc::PeerConnectionInterface* pc_interface,
+              webrtc::rffi::StatsObserverRffi* stats_observer);
+
 #endif /* RFFI_API_PEER_CONNECTION_INTERFACE_INTF_H__ */
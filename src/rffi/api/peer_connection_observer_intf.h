@@ -40,6 +40,9 @@ typedef struct {
   void (*onRenegotiationNeeded)(rust_object);
   void (*onAddTrack)(rust_object);
   void (*onTrack)(rust_object);
+  void (*onVideoRotationChanged)(rust_object, int32_t);
+  void (*onIceSelectedCandidatePairChanged)(rust_object, const RustIceRoute*);
+  void (*onDtmfReceived)(rust_object, char);
 } PeerConnectionObserverCallbacks;
 
 RUSTEXPORT webrtc::rffi::PeerConnectionObserverRffi*
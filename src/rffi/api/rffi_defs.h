@@ -28,4 +28,11 @@ typedef struct {
   const char* sdp;
 } RustIceCandidate;
 
+/* Selected ICE candidate pair structure passed from c++ to Rust */
+typedef struct {
+  const char* local_candidate_type;
+  const char* remote_candidate_type;
+  const char* protocol;
+} RustIceRoute;
+
 #endif /* RFFI_API_DEFS_H__ */
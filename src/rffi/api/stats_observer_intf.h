@@ -0,0 +1,55 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+#ifndef RFFI_API_STATS_OBSERVER_INTF_H__
+#define RFFI_API_STATS_OBSERVER_INTF_H__
+
+#include "api/peer_connection_interface.h"
+#include "rffi/api/rffi_defs.h"
+
+/**
+ * Rust friendly wrapper for creating objects that implement the
+ * webrtc::RTCStatsCollectorCallback interface.
+ *
+ */
+
+namespace webrtc {
+namespace rffi {
+  class StatsObserverRffi;
+} // namespace rffi
+} // namespace webrtc
+
+/* Counters flattened from an RTCStatsReport, summed over all RTP streams */
+typedef struct {
+  // Round trip time of the nominated ICE candidate pair, in seconds
+  double      current_round_trip_time;
+  // Jitter of the received audio, in seconds
+  double      jitter;
+  uint64_t    packets_received;
+  int64_t     packets_lost;
+  uint64_t    bytes_sent;
+  uint64_t    bytes_received;
+  // MIME types of the sent codecs, e.g. "audio/opus", or NULL
+  const char* audio_codec;
+  const char* video_codec;
+  // Levels of the captured and received audio, from 0.0 to 1.0
+  double      captured_audio_level;
+  double      received_audio_level;
+} RffiStatsReport;
+
+/* Stats Observer callback function pointers */
+typedef struct {
+  void (*onStatsComplete)(rust_object, const RffiStatsReport* report);
+} StatsObserverCallbacks;
+
+RUSTEXPORT webrtc::rffi::StatsObserverRffi*
+Rust_createStatsObserver(const rust_object             stats_observer,
+                         const StatsObserverCallbacks* stats_observer_cbs);
+
+#endif /* RFFI_API_STATS_OBSERVER_INTF_H__ */
@@ -26,6 +26,11 @@ Rust_dataChannelSend(DataChannelInterface* data_channel,
   return ret;
 }
 
+RUSTEXPORT uint64_t
+Rust_dataChannelBufferedAmount(DataChannelInterface* data_channel) {
+  return data_channel->buffered_amount();
+}
+
 RUSTEXPORT const char*
 Rust_dataChannelGetLabel(DataChannelInterface* data_channel) {
   std::string label = data_channel->label();
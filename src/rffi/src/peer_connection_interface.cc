@@ -8,10 +8,15 @@
  */
 
 #include "api/data_channel_interface.h"
+#include "api/dtmf_sender_interface.h"
+#include "api/media_stream_interface.h"
 #include "api/peer_connection_interface.h"
+#include "pc/dtls_transport.h"
+#include "rtc_base/ssl_stream_adapter.h"
 #include "sdk/media_constraints.h"
 #include "rffi/api/peer_connection_interface_intf.h"
 #include "rffi/src/sdp_observer.h"
+#include "rffi/src/stats_observer.h"
 
 #include <string>
 
@@ -73,6 +78,11 @@ Rust_createSessionDescriptionOffer(const char* description) {
   return createSessionDescriptionInterface(SdpType::kOffer, description);
 }
 
+RUSTEXPORT SessionDescriptionInterface*
+Rust_createSessionDescriptionRollback() {
+  return createSessionDescriptionInterface(SdpType::kRollback, "");
+}
+
 RUSTEXPORT void
 Rust_createAnswer(PeerConnectionInterface*              pc_interface,
                   CreateSessionDescriptionObserverRffi* csd_observer) {
@@ -130,5 +140,345 @@ Rust_addIceCandidate(PeerConnectionInterface* pc_interface,
   return pc_interface->AddIceCandidate(candidate.get());
 }
 
+RUSTEXPORT bool
+Rust_setVideoScaleResolutionDownBy(PeerConnectionInterface* pc_interface,
+                                   double                   scale) {
+
+  bool set_ok = true;
+  for (const auto& sender : pc_interface->GetSenders()) {
+    if (sender->media_type() != cricket::MEDIA_TYPE_VIDEO) {
+      continue;
+    }
+
+    RtpParameters parameters = sender->GetParameters();
+    for (auto& encoding : parameters.encodings) {
+      encoding.scale_resolution_down_by = scale;
+    }
+
+    RTCError error = sender->SetParameters(parameters);
+    if (!error.ok()) {
+      RTC_LOG(LS_ERROR) << "Unable to set video scale: " << error.message();
+      set_ok = false;
+    }
+  }
+
+  return set_ok;
+}
+
+RUSTEXPORT bool
+Rust_setVideoMaxFramerate(PeerConnectionInterface* pc_interface,
+                          uint32_t                 max_framerate) {
+
+  absl::optional<int> framerate;
+  if (max_framerate > 0) {
+    framerate = max_framerate;
+  }
+
+  bool set_ok = true;
+  for (const auto& sender : pc_interface->GetSenders()) {
+    if (sender->media_type() != cricket::MEDIA_TYPE_VIDEO) {
+      continue;
+    }
+
+    RtpParameters parameters = sender->GetParameters();
+    for (auto& encoding : parameters.encodings) {
+      encoding.max_framerate = framerate;
+    }
+
+    RTCError error = sender->SetParameters(parameters);
+    if (!error.ok()) {
+      RTC_LOG(LS_ERROR) << "Unable to set video max framerate: " << error.message();
+      set_ok = false;
+    }
+  }
+
+  return set_ok;
+}
+
+RUSTEXPORT bool
+Rust_setVideoDegradationPreference(PeerConnectionInterface* pc_interface,
+                                   int32_t                  preference) {
+
+  DegradationPreference degradation_preference;
+  switch (preference) {
+    case 0:
+      degradation_preference = DegradationPreference::MAINTAIN_FRAMERATE;
+      break;
+    case 1:
+      degradation_preference = DegradationPreference::MAINTAIN_RESOLUTION;
+      break;
+    case 2:
+      degradation_preference = DegradationPreference::BALANCED;
+      break;
+    default:
+      RTC_LOG(LS_ERROR) << "Unknown degradation preference: " << preference;
+      return false;
+  }
+
+  bool set_ok = true;
+  for (const auto& sender : pc_interface->GetSenders()) {
+    if (sender->media_type() != cricket::MEDIA_TYPE_VIDEO) {
+      continue;
+    }
+
+    RtpParameters parameters = sender->GetParameters();
+    parameters.degradation_preference = degradation_preference;
+
+    RTCError error = sender->SetParameters(parameters);
+    if (!error.ok()) {
+      RTC_LOG(LS_ERROR) << "Unable to set degradation preference: " << error.message();
+      set_ok = false;
+    }
+  }
+
+  return set_ok;
+}
+
+RUSTEXPORT bool
+Rust_setOutgoingAudioEnabled(PeerConnectionInterface* pc_interface,
+                             bool                     enabled) {
+
+  bool set_ok = true;
+  for (const auto& sender : pc_interface->GetSenders()) {
+    if (sender->media_type() != cricket::MEDIA_TYPE_AUDIO) {
+      continue;
+    }
+
+    rtc::scoped_refptr<MediaStreamTrackInterface> track = sender->track();
+    if (!track) {
+      RTC_LOG(LS_ERROR) << "Audio sender has no track";
+      set_ok = false;
+      continue;
+    }
+    track->set_enabled(enabled);
+  }
+
+  return set_ok;
+}
+
+// Enables or disables the tracks of the video senders, either those
+// carrying a screen share or those carrying the camera.
+static bool
+SetOutgoingVideoTracksEnabled(PeerConnectionInterface* pc_interface,
+                              bool                     screen_share,
+                              bool                     enabled) {
+
+  bool set_ok = true;
+  for (const auto& sender : pc_interface->GetSenders()) {
+    if (sender->media_type() != cricket::MEDIA_TYPE_VIDEO) {
+      continue;
+    }
+
+    rtc::scoped_refptr<MediaStreamTrackInterface> track = sender->track();
+    if (!track) {
+      RTC_LOG(LS_ERROR) << "Video sender has no track";
+      set_ok = false;
+      continue;
+    }
+
+    VideoTrackInterface::ContentHint hint =
+      static_cast<VideoTrackInterface*>(track.get())->content_hint();
+    bool is_screen_share = hint == VideoTrackInterface::ContentHint::kDetailed ||
+                           hint == VideoTrackInterface::ContentHint::kText;
+    if (is_screen_share != screen_share) {
+      continue;
+    }
+    track->set_enabled(enabled);
+  }
+
+  return set_ok;
+}
+
+RUSTEXPORT bool
+Rust_setOutgoingVideoEnabled(PeerConnectionInterface* pc_interface,
+                             bool                     enabled) {
+  return SetOutgoingVideoTracksEnabled(pc_interface, false, enabled);
+}
+
+RUSTEXPORT bool
+Rust_setOutgoingScreenShareEnabled(PeerConnectionInterface* pc_interface,
+                                   bool                     enabled) {
+  return SetOutgoingVideoTracksEnabled(pc_interface, true, enabled);
+}
+
+RUSTEXPORT bool
+Rust_setOutgoingAudioGain(PeerConnectionInterface* pc_interface,
+                          double                   gain) {
+
+  bool set_ok = true;
+  for (const auto& sender : pc_interface->GetSenders()) {
+    if (sender->media_type() != cricket::MEDIA_TYPE_AUDIO) {
+      continue;
+    }
+
+    rtc::scoped_refptr<MediaStreamTrackInterface> track = sender->track();
+    if (!track) {
+      RTC_LOG(LS_ERROR) << "Audio sender has no track";
+      set_ok = false;
+      continue;
+    }
+
+    AudioSourceInterface* source = static_cast<AudioTrackInterface*>(track.get())->GetSource();
+    if (!source) {
+      RTC_LOG(LS_ERROR) << "Audio track has no source";
+      set_ok = false;
+      continue;
+    }
+    source->SetVolume(gain);
+  }
+
+  return set_ok;
+}
+
+RUSTEXPORT bool
+Rust_setAudioJitterBufferMinimumDelay(PeerConnectionInterface* pc_interface,
+                                      double                   delay_seconds) {
+
+  absl::optional<double> delay;
+  if (delay_seconds >= 0) {
+    delay = delay_seconds;
+  }
+  for (const auto& receiver : pc_interface->GetReceivers()) {
+    if (receiver->media_type() == cricket::MEDIA_TYPE_AUDIO) {
+      receiver->SetJitterBufferMinimumDelay(delay);
+    }
+  }
+  return true;
+}
+
+RUSTEXPORT bool
+Rust_insertDtmf(PeerConnectionInterface* pc_interface,
+                const char*              tones,
+                int32_t                  duration_ms,
+                int32_t                  gap_ms) {
+
+  for (const auto& sender : pc_interface->GetSenders()) {
+    if (sender->media_type() != cricket::MEDIA_TYPE_AUDIO) {
+      continue;
+    }
+
+    rtc::scoped_refptr<DtmfSenderInterface> dtmf_sender = sender->GetDtmfSender();
+    if (!dtmf_sender || !dtmf_sender->CanInsertDtmf()) {
+      RTC_LOG(LS_ERROR) << "Audio sender can not insert DTMF";
+      return false;
+    }
+
+    // Append to the tones still being played, which InsertDtmf()
+    // would otherwise cancel.
+    std::string queued_tones = dtmf_sender->tones() + std::string(tones);
+    return dtmf_sender->InsertDtmf(queued_tones, duration_ms, gap_ms);
+  }
+
+  RTC_LOG(LS_ERROR) << "No audio sender to insert DTMF";
+  return false;
+}
+
+RUSTEXPORT bool
+Rust_setMaxBitrate(PeerConnectionInterface* pc_interface,
+                   int32_t                  bitrate_bps) {
+
+  BitrateSettings settings;
+  settings.max_bitrate_bps = bitrate_bps;
+
+  RTCError error = pc_interface->SetBitrate(settings);
+  if (!error.ok()) {
+    RTC_LOG(LS_ERROR) << "SetBitrate failed: " << error.message();
+    return false;
+  }
+  return true;
+}
+
+RUSTEXPORT bool
+Rust_setIceServers(PeerConnectionInterface* pc_interface,
+                   const RffiIceServer*     ice_servers,
+                   size_t                   ice_servers_size) {
+
+  PeerConnectionInterface::RTCConfiguration config = pc_interface->GetConfiguration();
+
+  if (ice_servers_size == 0 && config.type == PeerConnectionInterface::kRelay) {
+    RTC_LOG(LS_ERROR) << "No ICE servers while the ICE transport policy is relay only";
+    return false;
+  }
+
+  config.servers.clear();
+  for (size_t i = 0; i < ice_servers_size; i++) {
+    PeerConnectionInterface::IceServer server;
+    server.urls.push_back(std::string(ice_servers[i].url));
+    server.username = std::string(ice_servers[i].username);
+    server.password = std::string(ice_servers[i].password);
+    config.servers.push_back(server);
+  }
+
+  RTCError error = pc_interface->SetConfiguration(config);
+  if (!error.ok()) {
+    RTC_LOG(LS_ERROR) << "SetConfiguration() failed: " << error.message();
+    return false;
+  }
+  return true;
+}
+
+RUSTEXPORT void
+Rust_restartIce(PeerConnectionInterface* pc_interface) {
+  pc_interface->RestartIce();
+}
+
+// The exporter label of DTLS-SRTP, RFC 5764.
+static const char kDtlsSrtpExporterLabel[] = "EXTRACTOR-dtls_srtp";
+
+RUSTEXPORT bool
+Rust_exportSrtpKeyingMaterial(PeerConnectionInterface* pc_interface,
+                              RffiSrtpKeyingMaterial*  material) {
+
+  // With BUNDLE all media shares the DTLS transport of the first
+  // sender.
+  for (const auto& sender : pc_interface->GetSenders()) {
+    rtc::scoped_refptr<DtlsTransportInterface> transport = sender->dtls_transport();
+    if (!transport) {
+      continue;
+    }
+    cricket::DtlsTransportInternal* internal =
+      static_cast<DtlsTransport*>(transport.get())->internal();
+    if (!internal) {
+      break;
+    }
+
+    int          crypto_suite;
+    rtc::SSLRole role;
+    int          key_len;
+    int          salt_len;
+    if (!internal->GetSrtpCryptoSuite(&crypto_suite) ||
+        !internal->GetDtlsRole(&role) ||
+        !rtc::GetSrtpKeyAndSaltLengths(crypto_suite, &key_len, &salt_len)) {
+      RTC_LOG(LS_ERROR) << "DTLS has not negotiated SRTP";
+      return false;
+    }
+
+    size_t material_size = 2 * (key_len + salt_len);
+    if (material_size > RFFI_MAX_SRTP_KEYING_MATERIAL) {
+      RTC_LOG(LS_ERROR) << "Unexpected SRTP crypto suite: " << crypto_suite;
+      return false;
+    }
+    if (!internal->ExportKeyingMaterial(kDtlsSrtpExporterLabel, nullptr, 0, false,
+                                        material->material, material_size)) {
+      RTC_LOG(LS_ERROR) << "ExportKeyingMaterial() failed";
+      return false;
+    }
+
+    material->crypto_suite  = crypto_suite;
+    material->dtls_client   = role == rtc::SSL_CLIENT;
+    material->material_size = material_size;
+    return true;
+  }
+
+  RTC_LOG(LS_ERROR) << "No DTLS transport to export the SRTP keys of";
+  return false;
+}
+
+RUSTEXPORT void
+Rust_getStats(PeerConnectionInterface* pc_interface,
+              StatsObserverRffi*       stats_observer) {
+  pc_interface->GetStats(stats_observer);
+}
+
 } // namespace rffi
 } // namespace webrtc
@@ -23,6 +23,9 @@ PeerConnectionObserverRffi::PeerConnectionObserverRffi(
 
 PeerConnectionObserverRffi::~PeerConnectionObserverRffi() {
   RTC_LOG(LS_INFO) << "PeerConnectionObserverRffi:dtor(): " << this->call_connection_;
+  if (remote_video_track_) {
+    remote_video_track_->RemoveSink(this);
+  }
 }
 
 void PeerConnectionObserverRffi::OnIceCandidate(const IceCandidateInterface* candidate) {
@@ -74,10 +77,35 @@ void PeerConnectionObserverRffi::OnIceGatheringChange(
   pc_observer_cbs_.onIceGatheringChange(call_connection_, new_state);
 }
 
+void PeerConnectionObserverRffi::OnIceSelectedCandidatePairChanged(
+    const cricket::CandidatePairChangeEvent& event) {
+  const cricket::Candidate& local = event.selected_candidate_pair.local_candidate();
+  const cricket::Candidate& remote = event.selected_candidate_pair.remote_candidate();
+
+  RTC_LOG(LS_INFO) << "OnIceSelectedCandidatePairChanged(): local: " << local.type()
+                   << ", remote: " << remote.type() << ", protocol: " << local.protocol();
+
+  RustIceRoute rust_route;
+  rust_route.local_candidate_type = local.type().c_str();
+  rust_route.remote_candidate_type = remote.type().c_str();
+  rust_route.protocol = local.protocol().c_str();
+
+  pc_observer_cbs_.onIceSelectedCandidatePairChanged(call_connection_, &rust_route);
+}
+
 void PeerConnectionObserverRffi::OnAddStream(
     rtc::scoped_refptr<MediaStreamInterface> stream) {
   RTC_LOG(LS_INFO) << "OnAddStream()";
 
+  // Watch the rotation of the received video.  The frames only carry
+  // a rotation when the video orientation header extension was
+  // negotiated, which the Rust side checks before reporting it.
+  VideoTrackVector video_tracks = stream->GetVideoTracks();
+  if (!video_tracks.empty()) {
+    remote_video_track_ = video_tracks[0];
+    remote_video_track_->AddOrUpdateSink(this, rtc::VideoSinkWants());
+  }
+
   // Ownership of |stream| is transfered to the rust call back
   // handler.  Someone must call RefCountInterface::Release()
   // eventually.
@@ -116,6 +144,15 @@ void PeerConnectionObserverRffi::OnTrack(
   pc_observer_cbs_.onTrack(call_connection_);
 }
 
+void PeerConnectionObserverRffi::OnFrame(const VideoFrame& frame) {
+  int rotation = frame.rotation();
+  if (rotation != video_rotation_) {
+    RTC_LOG(LS_INFO) << "OnFrame(): rotation: " << rotation;
+    video_rotation_ = rotation;
+    pc_observer_cbs_.onVideoRotationChanged(call_connection_, rotation);
+  }
+}
+
 RUSTEXPORT PeerConnectionObserverRffi*
 Rust_createPeerConnectionObserver(const rust_object call_connection,
                                   const PeerConnectionObserverCallbacks* pc_observer_cbs) {
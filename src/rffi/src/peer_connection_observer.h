@@ -11,6 +11,8 @@
 #define RFFI_PEER_CONNECTION_OBSERVER_H__
 
 #include "api/peer_connection_interface.h"
+#include "api/video/video_frame.h"
+#include "api/video/video_sink_interface.h"
 
 /**
  * Adapter between the C++ PeerConnectionObserver interface and the
@@ -21,7 +23,8 @@
 namespace webrtc {
 namespace rffi {
 
-class PeerConnectionObserverRffi : public PeerConnectionObserver {
+class PeerConnectionObserverRffi : public PeerConnectionObserver,
+                                   public rtc::VideoSinkInterface<VideoFrame> {
  public:
   PeerConnectionObserverRffi(const rust_object call_connection,
                              const PeerConnectionObserverCallbacks* pc_observer_cbs);
@@ -41,6 +44,8 @@ class PeerConnectionObserverRffi : public PeerConnectionObserver {
   void OnIceConnectionReceivingChange(bool receiving) override;
   void OnIceGatheringChange(
       PeerConnectionInterface::IceGatheringState new_state) override;
+  void OnIceSelectedCandidatePairChanged(
+      const cricket::CandidatePairChangeEvent& event) override;
   void OnAddStream(rtc::scoped_refptr<MediaStreamInterface> stream) override;
   void OnRemoveStream(rtc::scoped_refptr<MediaStreamInterface> stream) override;
   void OnDataChannel(rtc::scoped_refptr<DataChannelInterface> channel) override;
@@ -51,9 +56,16 @@ class PeerConnectionObserverRffi : public PeerConnectionObserver {
   void OnTrack(
       rtc::scoped_refptr<RtpTransceiverInterface> transceiver) override;
 
+  // Implementation of VideoSinkInterface, attached to the remote
+  // video track to report changes in the rotation of the received
+  // frames.
+  void OnFrame(const VideoFrame& frame) override;
+
  private:
   const rust_object call_connection_;
   PeerConnectionObserverCallbacks pc_observer_cbs_;
+  rtc::scoped_refptr<VideoTrackInterface> remote_video_track_;
+  int video_rotation_ = -1;
 
 };
 
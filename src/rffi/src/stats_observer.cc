@@ -0,0 +1,111 @@
+/*
+ *
+ *  Copyright (C) 2020 Signal Messenger, LLC.
+ *  All rights reserved.
+ *
+ *  SPDX-License-Identifier: GPL-3.0-only
+ *
+ */
+
+#include "api/stats/rtcstats_objects.h"
+#include "rffi/api/stats_observer_intf.h"
+#include "rffi/src/stats_observer.h"
+
+#include <string>
+
+namespace webrtc {
+namespace rffi {
+
+StatsObserverRffi::StatsObserverRffi(const rust_object             stats_observer,
+                                     const StatsObserverCallbacks* stats_observer_cbs)
+  : stats_observer_(stats_observer), stats_observer_cbs_(*stats_observer_cbs)
+{
+  RTC_LOG(LS_INFO) << "StatsObserverRffi:ctor(): " << this->stats_observer_;
+}
+
+StatsObserverRffi::~StatsObserverRffi() {
+  RTC_LOG(LS_INFO) << "StatsObserverRffi:dtor(): " << this->stats_observer_;
+}
+
+// Returns the MIME type of the codec with the given ID, or an empty
+// string.
+static std::string codec_mime_type(const rtc::scoped_refptr<const RTCStatsReport>& report,
+                                   const RTCStatsMember<std::string>&              codec_id) {
+  if (!codec_id.is_defined()) {
+    return std::string();
+  }
+  const RTCStats* stats = report->Get(*codec_id);
+  if (!stats) {
+    return std::string();
+  }
+  const RTCCodecStats& codec = stats->cast_to<RTCCodecStats>();
+  return codec.mime_type.is_defined() ? *codec.mime_type : std::string();
+}
+
+void StatsObserverRffi::OnStatsDelivered(const rtc::scoped_refptr<const RTCStatsReport>& report) {
+  RTC_LOG(LS_INFO) << "StatsObserverRffi:OnStatsDelivered(): ";
+
+  RffiStatsReport rffi_report = {};
+  std::string audio_codec;
+  std::string video_codec;
+
+  for (const auto* pair : report->GetStatsOfType<RTCIceCandidatePairStats>()) {
+    if (pair->nominated.is_defined() && *pair->nominated &&
+        pair->current_round_trip_time.is_defined()) {
+      rffi_report.current_round_trip_time = *pair->current_round_trip_time;
+    }
+  }
+
+  for (const auto* inbound : report->GetStatsOfType<RTCInboundRTPStreamStats>()) {
+    if (inbound->packets_received.is_defined()) {
+      rffi_report.packets_received += *inbound->packets_received;
+    }
+    if (inbound->packets_lost.is_defined()) {
+      rffi_report.packets_lost += *inbound->packets_lost;
+    }
+    if (inbound->bytes_received.is_defined()) {
+      rffi_report.bytes_received += *inbound->bytes_received;
+    }
+    if (inbound->kind.is_defined() && *inbound->kind == "audio" && inbound->jitter.is_defined()) {
+      rffi_report.jitter = *inbound->jitter;
+    }
+  }
+
+  for (const auto* track : report->GetStatsOfType<RTCMediaStreamTrackStats>()) {
+    if (!track->kind.is_defined() || *track->kind != "audio" || !track->audio_level.is_defined()) {
+      continue;
+    }
+    if (track->remote_source.is_defined() && *track->remote_source) {
+      rffi_report.received_audio_level = *track->audio_level;
+    } else {
+      rffi_report.captured_audio_level = *track->audio_level;
+    }
+  }
+
+  for (const auto* outbound : report->GetStatsOfType<RTCOutboundRTPStreamStats>()) {
+    if (outbound->bytes_sent.is_defined()) {
+      rffi_report.bytes_sent += *outbound->bytes_sent;
+    }
+    if (outbound->kind.is_defined() && *outbound->kind == "audio") {
+      audio_codec = codec_mime_type(report, outbound->codec_id);
+    } else if (outbound->kind.is_defined() && *outbound->kind == "video") {
+      video_codec = codec_mime_type(report, outbound->codec_id);
+    }
+  }
+
+  rffi_report.audio_codec = audio_codec.empty() ? nullptr : audio_codec.c_str();
+  rffi_report.video_codec = video_codec.empty() ? nullptr : video_codec.c_str();
+
+  // The strings only live for the duration of the callback, Rust
+  // copies them.
+  this->stats_observer_cbs_.onStatsComplete(this->stats_observer_, &rffi_report);
+}
+
+RUSTEXPORT StatsObserverRffi*
+Rust_createStatsObserver(const rust_object             stats_observer,
+                         const StatsObserverCallbacks* stats_observer_cbs) {
+  return new rtc::RefCountedObject<StatsObserverRffi>(stats_observer, stats_observer_cbs);
+}
+
+} // namespace rffi
+} // namespace webrtc
@@ -9,25 +9,216 @@
 
 use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 
-use jni::objects::{GlobalRef, JObject, JValue};
-use jni::sys::{jint, jlong};
+use jni::objects::{GlobalRef, JObject, JString, JValue};
+use jni::sys::{jchar, jint, jlong};
 use jni::{JNIEnv, JavaVM};
 
 // use crate::android::call_connection_observer::AndroidCallConnectionObserver;
+use crate::android::call_manager::{ice_candidates_from_jni, ice_servers_from_jni};
 use crate::android::error::AndroidError;
 use crate::android::jni_util::*;
 use crate::android::webrtc_java_media_stream::JavaMediaStream;
-use crate::common::{ApplicationEvent, CallDirection, CallId, ConnectionId, DeviceId, Result};
+use crate::common::{
+    ApplicationEvent,
+    CallDirection,
+    CallId,
+    ConnectionId,
+    DeviceId,
+    HangupType,
+    Result,
+};
+use crate::core::audio_route::AudioRoute;
 use crate::core::call::Call;
+use crate::core::call_failure::CallFailureReason;
+use crate::core::call_record::CallRecord;
+use crate::core::call_stats::CallStats;
+use crate::core::codec::CodecCapability;
 use crate::core::connection::Connection;
+use crate::core::connection_config::ConnectionConfig;
+use crate::core::ice_server::IceServerConfig;
+use crate::core::media_descriptor::MediaDescriptor;
+use crate::core::offer_metadata::OfferMetadata;
+use crate::core::pending_signaling::{PendingMessage, PendingSignaling};
 use crate::core::platform::{Platform, PlatformItem};
-use crate::webrtc::ice_candidate::IceCandidate;
+use crate::core::util::Swappable;
+use crate::error::RingRtcError;
+use crate::webrtc::ice_candidate::{IceCandidate, IceRoute, NetworkAdapterType};
 use crate::webrtc::media_stream::MediaStream;
+use crate::webrtc::peer_connection_observer::IceGatheringState;
 
 const RINGRTC_PACKAGE: &str = "org/signal/ringrtc";
 const CALL_MANAGER_CLASS: &str = "CallManager";
+const CALL_MANAGER_CLASS_PATH: &str = "org/signal/ringrtc/CallManager";
 const ICE_CANDIDATE_CLASS: &str = "org/webrtc/IceCandidate";
+const ICE_SERVER_CLASS: &str = "org/webrtc/PeerConnection$IceServer";
+const PENDING_SIGNALING_CLASS: &str = "org/signal/ringrtc/PendingSignaling";
+const MEDIA_DESCRIPTOR_CLASS: &str = "org/signal/ringrtc/MediaDescriptor";
+const CALL_STATS_CLASS: &str = "org/signal/ringrtc/CallStats";
+const CALL_RECORD_CLASS: &str = "org/signal/ringrtc/CallRecord";
+
+// The callbacks of the Java CallManager.
+const CLOSE_CALL_METHOD: &str = "closeCall";
+const CLOSE_CALL_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;)V";
+const CLOSE_CONNECTION_METHOD: &str = "closeConnection";
+const CLOSE_CONNECTION_SIG: &str = "(Lorg/signal/ringrtc/Connection;)V";
+const START_CALL_METHOD: &str = "onStartCall";
+const START_CALL_SIG: &str = "(Lorg/signal/ringrtc/Remote;JZ)V";
+const ON_EVENT_METHOD: &str = "onEvent";
+const ON_EVENT_SIG: &str =
+    "(JLorg/signal/ringrtc/Remote;Lorg/signal/ringrtc/CallManager$CallEvent;)V";
+const REMOTE_VIDEO_ORIENTATION_METHOD: &str = "onRemoteVideoOrientation";
+const REMOTE_VIDEO_ORIENTATION_SIG: &str = "(JLorg/signal/ringrtc/Remote;II)V";
+const RECEIVED_DTMF_METHOD: &str = "onReceivedDtmf";
+const RECEIVED_DTMF_SIG: &str = "(JLorg/signal/ringrtc/Remote;IC)V";
+const SAFETY_NUMBERS_METHOD: &str = "onSafetyNumbers";
+const SAFETY_NUMBERS_SIG: &str = "(JLorg/signal/ringrtc/Remote;ILjava/lang/String;)V";
+const BUFFERED_AMOUNT_LOW_METHOD: &str = "onDataChannelBufferedAmountLow";
+const BUFFERED_AMOUNT_LOW_SIG: &str = "(JLorg/signal/ringrtc/Remote;I)V";
+const APP_MESSAGE_METHOD: &str = "onAppMessageReceived";
+const APP_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;I[B)V";
+const DURATION_TICK_METHOD: &str = "onCallDurationTick";
+const DURATION_TICK_SIG: &str = "(JLorg/signal/ringrtc/Remote;J)V";
+const STATS_REPORT_METHOD: &str = "onStatsReport";
+const STATS_REPORT_SIG: &str = "(JLorg/signal/ringrtc/Remote;Lorg/signal/ringrtc/CallStats;)V";
+const AUDIO_LEVELS_METHOD: &str = "onAudioLevels";
+const AUDIO_LEVELS_SIG: &str = "(JLorg/signal/ringrtc/Remote;DD)V";
+const BANDWIDTH_ESTIMATE_METHOD: &str = "onBandwidthEstimate";
+const BANDWIDTH_ESTIMATE_SIG: &str = "(JLorg/signal/ringrtc/Remote;JJ)V";
+const NETWORK_ROUTE_CHANGED_METHOD: &str = "onNetworkRouteChanged";
+const NETWORK_ROUTE_CHANGED_SIG: &str =
+    "(JLorg/signal/ringrtc/Remote;Lorg/signal/ringrtc/CallManager$NetworkAdapterType;Z)V";
+const ICE_GATHERING_STATE_METHOD: &str = "onIceGatheringStateChanged";
+const ICE_GATHERING_STATE_SIG: &str =
+    "(JLorg/signal/ringrtc/Remote;ILorg/signal/ringrtc/CallManager$IceGatheringState;)V";
+const SHOULD_RING_METHOD: &str = "shouldRing";
+const SHOULD_RING_SIG: &str = "(JLorg/signal/ringrtc/Remote;IJZ)Z";
+const SHOULD_TRANSFER_METHOD: &str = "shouldTransfer";
+const SHOULD_TRANSFER_SIG: &str = "(JLorg/signal/ringrtc/Remote;Ljava/lang/String;)Z";
+const GET_TURN_SERVERS_METHOD: &str = "getTurnServers";
+const GET_TURN_SERVERS_SIG: &str = "(JLorg/signal/ringrtc/Remote;)Ljava/util/List;";
+const GET_CODEC_CAPABILITIES_METHOD: &str = "getCodecCapabilities";
+const GET_CODEC_CAPABILITIES_SIG: &str = "()Ljava/util/List;";
+const ANSWER_METADATA_METHOD: &str = "onReceivedAnswerMetadata";
+const ANSWER_METADATA_SIG: &str = "(JLorg/signal/ringrtc/Remote;I[B)V";
+const SEND_PRE_OFFER_MESSAGE_METHOD: &str = "onSendPreOffer";
+const SEND_PRE_OFFER_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;I)V";
+const RECEIVED_PRE_OFFER_METHOD: &str = "onReceivedPreOffer";
+const RECEIVED_PRE_OFFER_SIG: &str = "(JLorg/signal/ringrtc/Remote;I)V";
+const SEND_OFFER_MESSAGE_METHOD: &str = "onSendOffer";
+const SEND_OFFER_MESSAGE_SIG: &str =
+    "(JLorg/signal/ringrtc/Remote;IZLjava/lang/String;Lorg/signal/ringrtc/MediaDescriptor;)V";
+const SEND_ANSWER_MESSAGE_METHOD: &str = "onSendAnswer";
+const SEND_ANSWER_MESSAGE_SIG: &str =
+    "(JLorg/signal/ringrtc/Remote;IZLjava/lang/String;Lorg/signal/ringrtc/MediaDescriptor;)V";
+const ON_SEND_ICE_CANDIDATES_METHOD: &str = "onSendIceCandidates";
+const ON_SEND_ICE_CANDIDATES_SIG: &str = "(JLorg/signal/ringrtc/Remote;IZLjava/util/List;)V";
+const SEND_HANGUP_MESSAGE_METHOD: &str = "onSendHangup";
+const SEND_HANGUP_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;IZI)V";
+const SEND_BUSY_MESSAGE_METHOD: &str = "onSendBusy";
+const SEND_BUSY_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;IZ)V";
+const SEND_RINGING_MESSAGE_METHOD: &str = "onSendRinging";
+const SEND_RINGING_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;I)V";
+const SEAL_METHOD: &str = "seal";
+const SEAL_SIG: &str = "(JLorg/signal/ringrtc/Remote;I[B)[B";
+const OPEN_METHOD: &str = "open";
+const OPEN_SIG: &str = "(JI[B)[B";
+const CONNECT_MEDIA_METHOD: &str = "onConnectMedia";
+const CONNECT_MEDIA_SIG: &str =
+    "(Lorg/signal/ringrtc/CallManager$CallContext;Lorg/webrtc/MediaStream;)V";
+const AUDIO_ROUTE_SUGGESTED_METHOD: &str = "onAudioRouteSuggested";
+const AUDIO_ROUTE_SUGGESTED_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;I)V";
+const CALL_ADDED_TO_TELECOM_METHOD: &str = "onCallAddedToTelecom";
+const CALL_ADDED_TO_TELECOM_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;)V";
+const REQUEST_TELECOM_HOLD_METHOD: &str = "onRequestTelecomHold";
+const REQUEST_TELECOM_HOLD_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;Z)V";
+const SET_CAMERA_ENABLED_METHOD: &str = "onSetCameraEnabled";
+const SET_CAMERA_ENABLED_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;Z)V";
+const SWITCH_CAMERA_METHOD: &str = "onSwitchCamera";
+const SWITCH_CAMERA_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;)V";
+const CLOSE_MEDIA_METHOD: &str = "onCloseMedia";
+const CLOSE_MEDIA_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;)V";
+const COMPARE_REMOTES_METHOD: &str = "compareRemotes";
+const COMPARE_REMOTES_SIG: &str = "(Lorg/signal/ringrtc/Remote;Lorg/signal/ringrtc/Remote;)Z";
+const CALL_RECORD_METHOD: &str = "onCallRecord";
+const CALL_RECORD_SIG: &str = "(JLorg/signal/ringrtc/Remote;Lorg/signal/ringrtc/CallRecord;)V";
+const CALL_FAILED_METHOD: &str = "onCallFailed";
+const CALL_FAILED_SIG: &str =
+    "(JLorg/signal/ringrtc/Remote;Lorg/signal/ringrtc/CallManager$CallFailureReason;)V";
+const CALL_CONCLUDED_METHOD: &str = "onCallConcluded";
+const CALL_CONCLUDED_SIG: &str = "(JLorg/signal/ringrtc/Remote;)V";
+const PERSIST_PENDING_SIGNALING_METHOD: &str = "persistPendingSignaling";
+const PERSIST_PENDING_SIGNALING_SIG: &str = "(Ljava/util/List;)V";
+const PERSISTED_SIGNALING_METHOD: &str = "getPersistedSignaling";
+const PERSISTED_SIGNALING_SIG: &str = "()Ljava/util/List;";
+const SHUTDOWN_COMPLETE_METHOD: &str = "onShutdownComplete";
+const SHUTDOWN_COMPLETE_SIG: &str = "()V";
+const CREATE_CONNECTION_METHOD: &str = "createConnection";
+const CREATE_CONNECTION_SIG: &str =
+    "(JJILorg/signal/ringrtc/CallManager$CallContext;Ljava/util/List;II)Lorg/signal/ringrtc/Connection;";
+const ROUTE_DOWNGRADED_TO_RELAY_METHOD: &str = "onRouteDowngradedToRelay";
+const ROUTE_UPGRADED_TO_DIRECT_METHOD: &str = "onRouteUpgradedToDirect";
+const ROUTE_CHANGED_SIG: &str =
+    "(JLorg/signal/ringrtc/Remote;ILjava/lang/String;Ljava/lang/String;Ljava/lang/String;)V";
+
+/// Every callback of the Java CallManager, resolved when the platform
+/// is created so that a missing one fails there instead of mid-call.
+const CALL_MANAGER_CALLBACKS: &[(&str, &str)] = &[
+    (CLOSE_CALL_METHOD, CLOSE_CALL_SIG),
+    (CLOSE_CONNECTION_METHOD, CLOSE_CONNECTION_SIG),
+    (START_CALL_METHOD, START_CALL_SIG),
+    (ON_EVENT_METHOD, ON_EVENT_SIG),
+    (
+        REMOTE_VIDEO_ORIENTATION_METHOD,
+        REMOTE_VIDEO_ORIENTATION_SIG,
+    ),
+    (RECEIVED_DTMF_METHOD, RECEIVED_DTMF_SIG),
+    (SAFETY_NUMBERS_METHOD, SAFETY_NUMBERS_SIG),
+    (BUFFERED_AMOUNT_LOW_METHOD, BUFFERED_AMOUNT_LOW_SIG),
+    (APP_MESSAGE_METHOD, APP_MESSAGE_SIG),
+    (DURATION_TICK_METHOD, DURATION_TICK_SIG),
+    (STATS_REPORT_METHOD, STATS_REPORT_SIG),
+    (AUDIO_LEVELS_METHOD, AUDIO_LEVELS_SIG),
+    (BANDWIDTH_ESTIMATE_METHOD, BANDWIDTH_ESTIMATE_SIG),
+    (NETWORK_ROUTE_CHANGED_METHOD, NETWORK_ROUTE_CHANGED_SIG),
+    (ICE_GATHERING_STATE_METHOD, ICE_GATHERING_STATE_SIG),
+    (SHOULD_RING_METHOD, SHOULD_RING_SIG),
+    (SHOULD_TRANSFER_METHOD, SHOULD_TRANSFER_SIG),
+    (GET_TURN_SERVERS_METHOD, GET_TURN_SERVERS_SIG),
+    (GET_CODEC_CAPABILITIES_METHOD, GET_CODEC_CAPABILITIES_SIG),
+    (ANSWER_METADATA_METHOD, ANSWER_METADATA_SIG),
+    (SEND_PRE_OFFER_MESSAGE_METHOD, SEND_PRE_OFFER_MESSAGE_SIG),
+    (RECEIVED_PRE_OFFER_METHOD, RECEIVED_PRE_OFFER_SIG),
+    (SEND_OFFER_MESSAGE_METHOD, SEND_OFFER_MESSAGE_SIG),
+    (SEND_ANSWER_MESSAGE_METHOD, SEND_ANSWER_MESSAGE_SIG),
+    (ON_SEND_ICE_CANDIDATES_METHOD, ON_SEND_ICE_CANDIDATES_SIG),
+    (SEND_HANGUP_MESSAGE_METHOD, SEND_HANGUP_MESSAGE_SIG),
+    (SEND_BUSY_MESSAGE_METHOD, SEND_BUSY_MESSAGE_SIG),
+    (SEND_RINGING_MESSAGE_METHOD, SEND_RINGING_MESSAGE_SIG),
+    (SEAL_METHOD, SEAL_SIG),
+    (OPEN_METHOD, OPEN_SIG),
+    (CONNECT_MEDIA_METHOD, CONNECT_MEDIA_SIG),
+    (AUDIO_ROUTE_SUGGESTED_METHOD, AUDIO_ROUTE_SUGGESTED_SIG),
+    (CALL_ADDED_TO_TELECOM_METHOD, CALL_ADDED_TO_TELECOM_SIG),
+    (REQUEST_TELECOM_HOLD_METHOD, REQUEST_TELECOM_HOLD_SIG),
+    (SET_CAMERA_ENABLED_METHOD, SET_CAMERA_ENABLED_SIG),
+    (SWITCH_CAMERA_METHOD, SWITCH_CAMERA_SIG),
+    (CLOSE_MEDIA_METHOD, CLOSE_MEDIA_SIG),
+    (COMPARE_REMOTES_METHOD, COMPARE_REMOTES_SIG),
+    (CALL_RECORD_METHOD, CALL_RECORD_SIG),
+    (CALL_FAILED_METHOD, CALL_FAILED_SIG),
+    (CALL_CONCLUDED_METHOD, CALL_CONCLUDED_SIG),
+    (
+        PERSIST_PENDING_SIGNALING_METHOD,
+        PERSIST_PENDING_SIGNALING_SIG,
+    ),
+    (PERSISTED_SIGNALING_METHOD, PERSISTED_SIGNALING_SIG),
+    (SHUTDOWN_COMPLETE_METHOD, SHUTDOWN_COMPLETE_SIG),
+    (CREATE_CONNECTION_METHOD, CREATE_CONNECTION_SIG),
+    (ROUTE_DOWNGRADED_TO_RELAY_METHOD, ROUTE_CHANGED_SIG),
+    (ROUTE_UPGRADED_TO_DIRECT_METHOD, ROUTE_CHANGED_SIG),
+];
 
 /// Android implmentation for platform::Platform::AppMediaStream
 pub type AndroidMediaStream = JavaMediaStream;
@@ -41,6 +232,8 @@ impl PlatformItem for AndroidGlobalRef {}
 struct JavaCallContext {
     /// Java JVM object.
     platform:         AndroidPlatform,
+    /// The call the context belongs to.
+    call_id:          CallId,
     /// Java CallContext object.
     jni_call_context: GlobalRef,
 }
@@ -50,15 +243,16 @@ impl Drop for JavaCallContext {
         info!("JavaCallContext::drop()");
 
         // call into CMI to close CallContext object
-        if let Ok(env) = self.platform.java_env() {
-            let jni_call_manager = self.platform.jni_call_manager.as_obj();
+        if let (Ok(env), Ok(jni_call_manager)) =
+            (self.platform.java_env(), self.platform.jni_call_manager())
+        {
+            let jni_call_manager = jni_call_manager.as_obj();
             let jni_call_context = self.jni_call_context.as_obj();
 
-            const CLOSE_CALL_METHOD: &str = "closeCall";
-            const CLOSE_CALL_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;)V";
             let args = [jni_call_context.into()];
-            let _ = jni_call_method(
+            let _ = self.platform.call_manager_method(
                 &env,
+                self.call_id,
                 jni_call_manager,
                 CLOSE_CALL_METHOD,
                 CLOSE_CALL_SIG,
@@ -78,15 +272,20 @@ unsafe impl Send for AndroidCallContext {}
 impl PlatformItem for AndroidCallContext {}
 
 impl AndroidCallContext {
-    pub fn new(platform: AndroidPlatform, jni_call_context: GlobalRef) -> Self {
+    pub fn new(platform: AndroidPlatform, call_id: CallId, jni_call_context: GlobalRef) -> Self {
         Self {
             inner: Arc::new(JavaCallContext {
                 platform,
+                call_id,
                 jni_call_context,
             }),
         }
     }
 
+    pub fn call_id(&self) -> CallId {
+        self.inner.call_id
+    }
+
     pub fn to_jni(&self) -> GlobalRef {
         self.inner.jni_call_context.clone()
     }
@@ -96,6 +295,8 @@ impl AndroidCallContext {
 struct JavaConnection {
     /// Java JVM object.
     platform:       AndroidPlatform,
+    /// The call the connection belongs to.
+    call_id:        CallId,
     /// Java Connection object.
     jni_connection: GlobalRef,
 }
@@ -105,15 +306,16 @@ impl Drop for JavaConnection {
         info!("JavaConnection::drop()");
 
         // call into CMI to close Connection object
-        if let Ok(env) = self.platform.java_env() {
-            let jni_call_manager = self.platform.jni_call_manager.as_obj();
+        if let (Ok(env), Ok(jni_call_manager)) =
+            (self.platform.java_env(), self.platform.jni_call_manager())
+        {
+            let jni_call_manager = jni_call_manager.as_obj();
             let jni_connection = self.jni_connection.as_obj();
 
-            const CLOSE_CONNECTION_METHOD: &str = "closeConnection";
-            const CLOSE_CONNECTION_SIG: &str = "(Lorg/signal/ringrtc/Connection;)V";
             let args = [jni_connection.into()];
-            let _ = jni_call_method(
+            let _ = self.platform.call_manager_method(
                 &env,
+                self.call_id,
                 jni_call_manager,
                 CLOSE_CONNECTION_METHOD,
                 CLOSE_CONNECTION_SIG,
@@ -133,10 +335,11 @@ unsafe impl Send for AndroidConnection {}
 impl PlatformItem for AndroidConnection {}
 
 impl AndroidConnection {
-    fn new(platform: AndroidPlatform, jni_connection: GlobalRef) -> Self {
+    fn new(platform: AndroidPlatform, call_id: CallId, jni_connection: GlobalRef) -> Self {
         Self {
             inner: Arc::new(JavaConnection {
                 platform,
+                call_id,
                 jni_connection,
             }),
         }
@@ -151,8 +354,9 @@ impl AndroidConnection {
 pub struct AndroidPlatform {
     /// Java JVM object.
     jvm:              JavaVM,
-    /// Java org.signal.ringrtc.CallManager object.
-    jni_call_manager: GlobalRef,
+    /// Java org.signal.ringrtc.CallManager object, shared by all
+    /// clones of the platform.
+    jni_call_manager: Swappable<GlobalRef>,
     /// Cache of Java classes needed at runtime
     class_cache:      ClassCache,
 }
@@ -191,50 +395,31 @@ impl Platform for AndroidPlatform {
         &mut self,
         call: &Call<Self>,
         remote_device: DeviceId,
+        config: ConnectionConfig,
     ) -> Result<Connection<Self>> {
         let connection_id = ConnectionId::new(call.call_id(), remote_device);
 
-        info!("create_connection(): {}", connection_id);
+        info!("create_connection(): {}, {}", connection_id, config);
 
         let connection = Connection::new(call.clone(), remote_device)?;
+        self.create_jni_connection(call, &connection, config)?;
 
-        let connection_ptr = connection.get_connection_ptr()?;
-        let call_id_jlong = u64::from(call.call_id()) as jlong;
-        let jni_remote_device = remote_device as jint;
-
-        // call into CMI to create webrtc PeerConnection
-        let env = self.java_env()?;
-        let android_call_context = call.call_context()?;
-        let jni_call_context = android_call_context.to_jni();
-        let jni_call_manager = self.jni_call_manager.as_obj();
-
-        const CREATE_CONNECTION_METHOD: &str = "createConnection";
-        const CREATE_CONNECTION_SIG: &str =
-            "(JJILorg/signal/ringrtc/CallManager$CallContext;)Lorg/signal/ringrtc/Connection;";
-        let args = [
-            (connection_ptr as jlong).into(),
-            call_id_jlong.into(),
-            jni_remote_device.into(),
-            jni_call_context.as_obj().into(),
-        ];
-        let result = jni_call_method(
-            &env,
-            jni_call_manager,
-            CREATE_CONNECTION_METHOD,
-            CREATE_CONNECTION_SIG,
-            &args,
-        )?;
+        Ok(connection)
+    }
 
-        let jni_connection = result.l()?;
-        if (*jni_connection).is_null() {
-            return Err(AndroidError::CreateJniConnection.into());
-        }
-        let jni_connection = env.new_global_ref(jni_connection)?;
-        let platform = self.try_clone()?;
-        let android_connection = AndroidConnection::new(platform, jni_connection);
-        connection.set_app_connection(android_connection)?;
+    fn recreate_peer_connection(
+        &mut self,
+        call: &Call<Self>,
+        connection: &Connection<Self>,
+        config: ConnectionConfig,
+    ) -> Result<()> {
+        info!(
+            "recreate_peer_connection(): {}, {}",
+            connection.id(),
+            config
+        );
 
-        Ok(connection)
+        self.create_jni_connection(call, connection, config)
     }
 
     fn on_start_call(
@@ -250,19 +435,18 @@ impl Platform for AndroidPlatform {
 
         let env = self.java_env()?;
         let jni_remote = remote_peer.as_obj();
-        let jni_call_manager = self.jni_call_manager.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
         let call_id_jlong = u64::from(call_id) as jlong;
         let is_outgoing = match direction {
             CallDirection::OutGoing => true,
             CallDirection::InComing => false,
         };
 
-        const START_CALL_METHOD: &str = "onStartCall";
-        const START_CALL_SIG: &str = "(Lorg/signal/ringrtc/Remote;JZ)V";
-
         let args = [jni_remote.into(), call_id_jlong.into(), is_outgoing.into()];
-        let _ = jni_call_method(
+        let _ = self.call_manager_method(
             &env,
+            call_id,
             jni_call_manager,
             START_CALL_METHOD,
             START_CALL_SIG,
@@ -271,46 +455,28 @@ impl Platform for AndroidPlatform {
         Ok(())
     }
 
-    fn on_event(&self, remote_peer: &Self::AppRemotePeer, event: ApplicationEvent) -> Result<()> {
-        info!("on_event(): {}", event);
+    fn on_event(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        event: ApplicationEvent,
+    ) -> Result<()> {
+        info!("on_event(): call_id: {}, event: {}", call_id, event);
 
         let env = self.java_env()?;
         let jni_remote = remote_peer.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
 
         // convert rust enum into Java enum
-        let class = "CallEvent";
-        let class_path = format!("{}/{}${}", RINGRTC_PACKAGE, CALL_MANAGER_CLASS, class);
-        let class_object = self.class_cache.get_class(&class_path)?;
-
-        const ENUM_FROM_NATIVE_INDEX_METHOD: &str = "fromNativeIndex";
-        let method_signature = format!("(I)L{};", class_path);
-        let args = [JValue::from(event as i32)];
-        let jni_enum = match env.call_static_method(
-            class_object,
-            ENUM_FROM_NATIVE_INDEX_METHOD,
-            &method_signature,
-            &args,
-        ) {
-            Ok(v) => v.l()?,
-            Err(_) => {
-                return Err(AndroidError::JniCallStaticMethod(
-                    class_path,
-                    ENUM_FROM_NATIVE_INDEX_METHOD.to_string(),
-                    method_signature.to_string(),
-                )
-                .into())
-            }
-        };
-
-        const ON_EVENT_METHOD: &str = "onEvent";
-        const ON_EVENT_SIG: &str =
-            "(Lorg/signal/ringrtc/Remote;Lorg/signal/ringrtc/CallManager$CallEvent;)V";
+        let jni_enum = self.java_enum(&env, "CallEvent", event as i32)?;
 
-        let args = [jni_remote.into(), jni_enum.into()];
+        let args = [call_id_jlong.into(), jni_remote.into(), jni_enum.into()];
 
-        let _ = jni_call_method(
+        let jni_call_manager = self.jni_call_manager()?;
+        let _ = self.call_manager_method(
             &env,
-            self.jni_call_manager.as_obj(),
+            call_id,
+            jni_call_manager.as_obj(),
             ON_EVENT_METHOD,
             ON_EVENT_SIG,
             &args,
@@ -318,353 +484,1741 @@ impl Platform for AndroidPlatform {
         Ok(())
     }
 
-    fn on_send_offer(
+    fn on_remote_video_orientation(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
-        broadcast: bool,
-        description: &str,
+        degrees: u16,
     ) -> Result<()> {
         info!(
-            "on_send_offer(): id: {}, broadcast: {}",
-            connection_id, broadcast
+            "on_remote_video_orientation(): id: {}, degrees: {}",
+            connection_id, degrees
         );
 
         let env = self.java_env()?;
         let jni_remote = remote_peer.as_obj();
-        let jni_call_manager = self.jni_call_manager.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
         let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
         let remote_device = connection_id.remote_device() as jint;
-
-        const SEND_OFFER_MESSAGE_METHOD: &str = "onSendOffer";
-        const SEND_OFFER_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;IZLjava/lang/String;)V";
+        let degrees = jint::from(degrees);
 
         let args = [
             call_id_jlong.into(),
             jni_remote.into(),
             remote_device.into(),
-            broadcast.into(),
-            JObject::from(env.new_string(description)?).into(),
+            degrees.into(),
         ];
-        let _ = jni_call_method(
+        let _ = self.call_manager_method(
             &env,
+            connection_id.call_id(),
             jni_call_manager,
-            SEND_OFFER_MESSAGE_METHOD,
-            SEND_OFFER_MESSAGE_SIG,
+            REMOTE_VIDEO_ORIENTATION_METHOD,
+            REMOTE_VIDEO_ORIENTATION_SIG,
             &args,
         )?;
         Ok(())
     }
 
-    fn on_send_answer(
+    fn on_received_dtmf(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
-        broadcast: bool,
-        description: &str,
+        tone: char,
     ) -> Result<()> {
-        info!(
-            "on_send_answer(): id: {}, broadcast: {}",
-            connection_id, broadcast
-        );
+        info!("on_received_dtmf(): id: {}, tone: {}", connection_id, tone);
 
         let env = self.java_env()?;
         let jni_remote = remote_peer.as_obj();
-        let jni_call_manager = self.jni_call_manager.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
         let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
         let remote_device = connection_id.remote_device() as jint;
-
-        const SEND_ANSWER_MESSAGE_METHOD: &str = "onSendAnswer";
-        const SEND_ANSWER_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;IZLjava/lang/String;)V";
+        let tone = tone as jchar;
 
         let args = [
             call_id_jlong.into(),
             jni_remote.into(),
             remote_device.into(),
-            broadcast.into(),
-            JObject::from(env.new_string(description)?).into(),
+            tone.into(),
         ];
-        let _ = jni_call_method(
+        let _ = self.call_manager_method(
             &env,
+            connection_id.call_id(),
             jni_call_manager,
-            SEND_ANSWER_MESSAGE_METHOD,
-            SEND_ANSWER_MESSAGE_SIG,
+            RECEIVED_DTMF_METHOD,
+            RECEIVED_DTMF_SIG,
             &args,
         )?;
         Ok(())
     }
 
-    fn on_send_ice_candidates(
+    fn on_route_downgraded_to_relay(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
-        broadcast: bool,
-        ice_candidates: &[IceCandidate],
+        route: &IceRoute,
     ) -> Result<()> {
         info!(
-            "on_send_ice_candidates(): id: {}, broadcast: {}",
-            connection_id, broadcast
+            "on_route_downgraded_to_relay(): id: {}, route: {}",
+            connection_id, route
+        );
+
+        self.notify_route_changed(
+            ROUTE_DOWNGRADED_TO_RELAY_METHOD,
+            remote_peer,
+            connection_id,
+            route,
+        )
+    }
+
+    fn on_route_upgraded_to_direct(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        route: &IceRoute,
+    ) -> Result<()> {
+        info!(
+            "on_route_upgraded_to_direct(): id: {}, route: {}",
+            connection_id, route
         );
 
+        self.notify_route_changed(
+            ROUTE_UPGRADED_TO_DIRECT_METHOD,
+            remote_peer,
+            connection_id,
+            route,
+        )
+    }
+
+    fn on_safety_numbers(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        safety_numbers: &str,
+    ) -> Result<()> {
+        info!("on_safety_numbers(): id: {}", connection_id);
+
         let env = self.java_env()?;
         let jni_remote = remote_peer.as_obj();
-        let jni_call_manager = self.jni_call_manager.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
         let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
         let remote_device = connection_id.remote_device() as jint;
-
-        // create Java List<org.webrtc.IceCandidate>
-        let ice_candidate_class = self.class_cache.get_class(ICE_CANDIDATE_CLASS)?;
-        let ice_candidate_list = jni_new_linked_list(&env)?;
-
-        for candidate in ice_candidates {
-            const ICE_CANDIDATE_CTOR_SIG: &str = "(Ljava/lang/String;ILjava/lang/String;)V";
-            let sdp_mid = env.new_string(&candidate.sdp_mid)?;
-            let sdp = env.new_string(&candidate.sdp)?;
-            let args = [
-                JObject::from(sdp_mid).into(),
-                candidate.sdp_mline_index.into(),
-                JObject::from(sdp).into(),
-            ];
-            let ice_update_message_obj =
-                env.new_object(ice_candidate_class, ICE_CANDIDATE_CTOR_SIG, &args)?;
-            ice_candidate_list.add(ice_update_message_obj)?;
-        }
-
-        const ON_SEND_ICE_CANDIDATES_METHOD: &str = "onSendIceCandidates";
-        const ON_SEND_ICE_CANDIDATES_SIG: &str =
-            "(JLorg/signal/ringrtc/Remote;IZLjava/util/List;)V";
+        let jni_safety_numbers = JObject::from(env.new_string(safety_numbers)?);
 
         let args = [
             call_id_jlong.into(),
             jni_remote.into(),
             remote_device.into(),
-            broadcast.into(),
-            JObject::from(ice_candidate_list).into(),
+            jni_safety_numbers.into(),
         ];
-        let _ = jni_call_method(
+        let _ = self.call_manager_method(
             &env,
+            connection_id.call_id(),
             jni_call_manager,
-            ON_SEND_ICE_CANDIDATES_METHOD,
-            ON_SEND_ICE_CANDIDATES_SIG,
+            SAFETY_NUMBERS_METHOD,
+            SAFETY_NUMBERS_SIG,
             &args,
         )?;
         Ok(())
     }
 
-    fn on_send_hangup(
+    fn on_data_channel_buffered_amount_low(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
-        broadcast: bool,
     ) -> Result<()> {
         info!(
-            "on_send_hangup(): id: {}, broadcast: {}",
-            connection_id, broadcast
+            "on_data_channel_buffered_amount_low(): id: {}",
+            connection_id
         );
 
         let env = self.java_env()?;
         let jni_remote = remote_peer.as_obj();
-        let jni_call_manager = self.jni_call_manager.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
         let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
         let remote_device = connection_id.remote_device() as jint;
 
-        const SEND_HANGUP_MESSAGE_METHOD: &str = "onSendHangup";
-        const SEND_HANGUP_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;IZ)V";
-
         let args = [
             call_id_jlong.into(),
             jni_remote.into(),
             remote_device.into(),
-            broadcast.into(),
         ];
-        let _ = jni_call_method(
+        let _ = self.call_manager_method(
             &env,
+            connection_id.call_id(),
             jni_call_manager,
-            SEND_HANGUP_MESSAGE_METHOD,
-            SEND_HANGUP_MESSAGE_SIG,
+            BUFFERED_AMOUNT_LOW_METHOD,
+            BUFFERED_AMOUNT_LOW_SIG,
             &args,
         )?;
         Ok(())
     }
 
-    fn on_send_busy(
+    fn on_app_message_received(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
-        broadcast: bool,
+        payload: &[u8],
     ) -> Result<()> {
         info!(
-            "on_send_busy(): id: {}, broadcast: {}",
-            connection_id, broadcast
+            "on_app_message_received(): id: {}, size: {}",
+            connection_id,
+            payload.len()
         );
 
         let env = self.java_env()?;
         let jni_remote = remote_peer.as_obj();
-        let jni_call_manager = self.jni_call_manager.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
         let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
         let remote_device = connection_id.remote_device() as jint;
-
-        const SEND_BUSY_MESSAGE_METHOD: &str = "onSendBusy";
-        const SEND_BUSY_MESSAGE_SIG: &str = "(JLorg/signal/ringrtc/Remote;IZ)V";
+        let jni_payload = JObject::from(env.byte_array_from_slice(payload)?);
 
         let args = [
             call_id_jlong.into(),
             jni_remote.into(),
             remote_device.into(),
-            broadcast.into(),
+            jni_payload.into(),
         ];
-        let _ = jni_call_method(
+        let _ = self.call_manager_method(
             &env,
+            connection_id.call_id(),
             jni_call_manager,
-            SEND_BUSY_MESSAGE_METHOD,
-            SEND_BUSY_MESSAGE_SIG,
+            APP_MESSAGE_METHOD,
+            APP_MESSAGE_SIG,
             &args,
         )?;
         Ok(())
     }
 
-    fn create_media_stream(
-        &self,
-        _connection: &Connection<Self>,
-        stream: MediaStream,
-    ) -> Result<Self::AppMediaStream> {
-        JavaMediaStream::new(stream)
-    }
-
-    fn on_connect_media(
+    fn on_call_duration_tick(
         &self,
-        _remote_peer: &Self::AppRemotePeer,
-        app_call_context: &Self::AppCallContext,
-        media_stream: &Self::AppMediaStream,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        duration: Duration,
     ) -> Result<()> {
-        info!("on_connect_media():");
+        debug!(
+            "on_call_duration_tick(): call_id: {}, duration: {:?}",
+            call_id, duration
+        );
 
         let env = self.java_env()?;
-        let jni_call_manager = self.jni_call_manager.as_obj();
-        let jni_call_context = app_call_context.to_jni();
-        let jni_media_stream = media_stream.global_ref(&env)?;
-
-        const CONNECT_MEDIA_METHOD: &str = "onConnectMedia";
-        const CONNECT_MEDIA_SIG: &str =
-            "(Lorg/signal/ringrtc/CallManager$CallContext;Lorg/webrtc/MediaStream;)V";
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
+        let duration_jlong = duration.as_millis() as jlong;
 
         let args = [
-            jni_call_context.as_obj().into(),
-            jni_media_stream.as_obj().into(),
+            call_id_jlong.into(),
+            jni_remote.into(),
+            duration_jlong.into(),
         ];
-        let _ = jni_call_method(
+        let _ = self.call_manager_method(
             &env,
+            call_id,
             jni_call_manager,
-            CONNECT_MEDIA_METHOD,
-            CONNECT_MEDIA_SIG,
+            DURATION_TICK_METHOD,
+            DURATION_TICK_SIG,
             &args,
         )?;
         Ok(())
     }
 
-    fn on_close_media(&self, app_call_context: &Self::AppCallContext) -> Result<()> {
-        info!("on_close_media():");
+    fn on_stats_report(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        stats: &CallStats,
+    ) -> Result<()> {
+        debug!("on_stats_report(): call_id: {}, {}", call_id, stats);
 
         let env = self.java_env()?;
-        let jni_call_manager = self.jni_call_manager.as_obj();
-        let jni_call_context = app_call_context.to_jni();
-
-        const CLOSE_MEDIA_METHOD: &str = "onCloseMedia";
-        const CLOSE_MEDIA_SIG: &str = "(Lorg/signal/ringrtc/CallManager$CallContext;)V";
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
+        let jni_stats = self.jni_call_stats(&env, stats)?;
 
-        let args = [jni_call_context.as_obj().into()];
-        let _ = jni_call_method(
+        let args = [call_id_jlong.into(), jni_remote.into(), jni_stats.into()];
+        let _ = self.call_manager_method(
             &env,
+            call_id,
             jni_call_manager,
-            CLOSE_MEDIA_METHOD,
-            CLOSE_MEDIA_SIG,
+            STATS_REPORT_METHOD,
+            STATS_REPORT_SIG,
             &args,
         )?;
         Ok(())
     }
 
-    fn compare_remotes(
+    fn on_audio_levels(
         &self,
-        remote_peer1: &Self::AppRemotePeer,
-        remote_peer2: &Self::AppRemotePeer,
-    ) -> Result<bool> {
-        info!("remotes_equal():");
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        captured: f64,
+        received: f64,
+    ) -> Result<()> {
+        debug!(
+            "on_audio_levels(): call_id: {}, captured: {}, received: {}",
+            call_id, captured, received
+        );
 
         let env = self.java_env()?;
-        let jni_call_manager = self.jni_call_manager.as_obj();
-        let jni_remote1 = remote_peer1.as_obj();
-        let jni_remote2 = remote_peer2.as_obj();
-
-        const COMPARE_REMOTES_METHOD: &str = "compareRemotes";
-        const COMPARE_REMOTES_SIG: &str =
-            "(Lorg/signal/ringrtc/Remote;Lorg/signal/ringrtc/Remote;)Z";
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
 
-        let args = [jni_remote1.into(), jni_remote2.into()];
-        let result = jni_call_method(
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            captured.into(),
+            received.into(),
+        ];
+        let _ = self.call_manager_method(
             &env,
+            call_id,
             jni_call_manager,
-            COMPARE_REMOTES_METHOD,
-            COMPARE_REMOTES_SIG,
+            AUDIO_LEVELS_METHOD,
+            AUDIO_LEVELS_SIG,
             &args,
-        )?
-        .z()?;
-        Ok(result)
+        )?;
+        Ok(())
     }
 
-    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer) -> Result<()> {
-        info!("on_call_concluded():");
+    fn on_bandwidth_estimate(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        send_bps: u64,
+        receive_bps: u64,
+    ) -> Result<()> {
+        debug!(
+            "on_bandwidth_estimate(): call_id: {}, send: {}, receive: {}",
+            call_id, send_bps, receive_bps
+        );
 
         let env = self.java_env()?;
-        let jni_call_manager = self.jni_call_manager.as_obj();
-        let jni_remote_peer = remote_peer.as_obj();
-
-        const CALL_CONCLUDED_METHOD: &str = "onCallConcluded";
-        const CALL_CONCLUDED_SIG: &str = "(Lorg/signal/ringrtc/Remote;)V";
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
+        let send_bps = send_bps as jlong;
+        let receive_bps = receive_bps as jlong;
 
-        let args = [jni_remote_peer.into()];
-        let _ = jni_call_method(
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            send_bps.into(),
+            receive_bps.into(),
+        ];
+        let _ = self.call_manager_method(
             &env,
+            call_id,
             jni_call_manager,
-            CALL_CONCLUDED_METHOD,
-            CALL_CONCLUDED_SIG,
+            BANDWIDTH_ESTIMATE_METHOD,
+            BANDWIDTH_ESTIMATE_SIG,
             &args,
         )?;
         Ok(())
     }
-}
 
-impl AndroidPlatform {
-    /// Create a new AndroidPlatform object.
-    pub fn new(env: &JNIEnv, jni_call_manager: GlobalRef) -> Result<Self> {
-        let mut class_cache = ClassCache::new();
-        for class in &[
-            "org/signal/ringrtc/CallManager$CallEvent",
-            ICE_CANDIDATE_CLASS,
-        ] {
-            class_cache.add_class(env, class)?;
-        }
+    fn on_network_route_changed(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        local_adapter_type: NetworkAdapterType,
+        relayed: bool,
+    ) -> Result<()> {
+        info!(
+            "on_network_route_changed(): call_id: {}, adapter: {}, relayed: {}",
+            call_id, local_adapter_type, relayed
+        );
 
-        Ok(Self {
-            jvm: env.get_java_vm()?,
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
+        let jni_adapter_type =
+            self.java_enum(&env, "NetworkAdapterType", local_adapter_type as i32)?;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            jni_adapter_type.into(),
+            relayed.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            call_id,
             jni_call_manager,
-            class_cache,
-        })
+            NETWORK_ROUTE_CHANGED_METHOD,
+            NETWORK_ROUTE_CHANGED_SIG,
+            &args,
+        )?;
+        Ok(())
     }
 
-    /// Return the Java JNIEnv.
-    fn java_env(&self) -> Result<JNIEnv> {
-        match self.jvm.get_env() {
-            Ok(v) => Ok(v),
-            Err(_e) => Ok(self.jvm.attach_current_thread_as_daemon()?),
-        }
-    }
+    fn on_ice_gathering_state_changed(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        state: IceGatheringState,
+    ) -> Result<()> {
+        info!(
+            "on_ice_gathering_state_changed(): id: {}, state: {}",
+            connection_id, state
+        );
 
-    pub fn try_clone(&self) -> Result<Self> {
         let env = self.java_env()?;
-        Ok(Self {
-            jvm:              env.get_java_vm()?,
-            jni_call_manager: self.jni_call_manager.clone(),
-            class_cache:      self.class_cache.clone(),
-        })
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+        let jni_state = self.java_enum(&env, "IceGatheringState", state as i32)?;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            jni_state.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            ICE_GATHERING_STATE_METHOD,
+            ICE_GATHERING_STATE_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn should_ring(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        offer_metadata: &OfferMetadata,
+    ) -> Result<bool> {
+        info!("should_ring(): {}", offer_metadata);
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(offer_metadata.call_id) as jlong;
+        let remote_device = offer_metadata.remote_device as jint;
+        let timestamp_jlong = offer_metadata.timestamp as jlong;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            timestamp_jlong.into(),
+            offer_metadata.video.into(),
+        ];
+        let result = self
+            .call_manager_method(
+                &env,
+                offer_metadata.call_id,
+                jni_call_manager,
+                SHOULD_RING_METHOD,
+                SHOULD_RING_SIG,
+                &args,
+            )?
+            .z()?;
+        Ok(result)
+    }
+
+    fn should_transfer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        target: &str,
+    ) -> Result<bool> {
+        info!(
+            "should_transfer(): call_id: {}, target: {}",
+            call_id, target
+        );
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            JObject::from(env.new_string(target)?).into(),
+        ];
+        let result = self
+            .call_manager_method(
+                &env,
+                call_id,
+                jni_call_manager,
+                SHOULD_TRANSFER_METHOD,
+                SHOULD_TRANSFER_SIG,
+                &args,
+            )?
+            .z()?;
+        Ok(result)
+    }
+
+    fn get_turn_servers(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+    ) -> Result<Vec<IceServerConfig>> {
+        info!("get_turn_servers(): call_id: {}", call_id);
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
+
+        let args = [call_id_jlong.into(), jni_remote.into()];
+        let jni_ice_servers = self
+            .call_manager_method(
+                &env,
+                call_id,
+                jni_call_manager,
+                GET_TURN_SERVERS_METHOD,
+                GET_TURN_SERVERS_SIG,
+                &args,
+            )?
+            .l()?;
+
+        // A null list keeps the servers passed to proceed().
+        if (*jni_ice_servers).is_null() {
+            return Ok(Vec::new());
+        }
+        ice_servers_from_jni(&env, jni_ice_servers)
+    }
+
+    fn get_codec_capabilities(&self) -> Result<Vec<CodecCapability>> {
+        info!("get_codec_capabilities():");
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+
+        let jni_codecs = self
+            .call_manager_method_without_call(
+                &env,
+                jni_call_manager,
+                GET_CODEC_CAPABILITIES_METHOD,
+                GET_CODEC_CAPABILITIES_SIG,
+                &[],
+            )?
+            .l()?;
+        if (*jni_codecs).is_null() {
+            return Ok(Vec::new());
+        }
+
+        // Convert Java list of org.signal.ringrtc.CodecCapability into
+        // Rust Vector of hardware CodecCapability
+        let mut codecs = Vec::new();
+        for jni_codec in env.get_list(jni_codecs)?.iter()? {
+            const NAME_FIELD: &str = "name";
+            const STRING_TYPE: &str = "Ljava/lang/String;";
+            let name = jni_get_field(&env, jni_codec, NAME_FIELD, STRING_TYPE)?.l()?;
+            let name: String = env.get_string(JString::from(name))?.into();
+
+            const ENCODE_FIELD: &str = "encode";
+            const DECODE_FIELD: &str = "decode";
+            const BOOLEAN_TYPE: &str = "Z";
+            let encode = jni_get_field(&env, jni_codec, ENCODE_FIELD, BOOLEAN_TYPE)?.z()?;
+            let decode = jni_get_field(&env, jni_codec, DECODE_FIELD, BOOLEAN_TYPE)?.z()?;
+
+            codecs.push(CodecCapability::hardware(&name, encode, decode));
+        }
+        Ok(codecs)
+    }
+
+    fn on_received_answer_metadata(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        metadata: &[u8],
+    ) -> Result<()> {
+        info!(
+            "on_received_answer_metadata(): id: {}, size: {}",
+            connection_id,
+            metadata.len()
+        );
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+        let jni_metadata = JObject::from(env.byte_array_from_slice(metadata)?);
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            jni_metadata.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            ANSWER_METADATA_METHOD,
+            ANSWER_METADATA_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_send_pre_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!("on_send_pre_offer(): id: {}", connection_id);
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            SEND_PRE_OFFER_MESSAGE_METHOD,
+            SEND_PRE_OFFER_MESSAGE_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_received_pre_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!("on_received_pre_offer(): id: {}", connection_id);
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            RECEIVED_PRE_OFFER_METHOD,
+            RECEIVED_PRE_OFFER_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_send_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        info!(
+            "on_send_offer(): id: {}, broadcast: {}, descriptor: {}",
+            connection_id, broadcast, descriptor
+        );
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            broadcast.into(),
+            JObject::from(env.new_string(description)?).into(),
+            self.jni_media_descriptor(&env, descriptor)?.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            SEND_OFFER_MESSAGE_METHOD,
+            SEND_OFFER_MESSAGE_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_send_answer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        info!(
+            "on_send_answer(): id: {}, broadcast: {}, descriptor: {}",
+            connection_id, broadcast, descriptor
+        );
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            broadcast.into(),
+            JObject::from(env.new_string(description)?).into(),
+            self.jni_media_descriptor(&env, descriptor)?.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            SEND_ANSWER_MESSAGE_METHOD,
+            SEND_ANSWER_MESSAGE_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_send_ice_candidates(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        ice_candidates: &[IceCandidate],
+    ) -> Result<()> {
+        info!(
+            "on_send_ice_candidates(): id: {}, broadcast: {}",
+            connection_id, broadcast
+        );
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+
+        let jni_ice_candidates = self.jni_ice_candidates(&env, ice_candidates)?;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            broadcast.into(),
+            jni_ice_candidates.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            ON_SEND_ICE_CANDIDATES_METHOD,
+            ON_SEND_ICE_CANDIDATES_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_send_hangup(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        info!(
+            "on_send_hangup(): id: {}, broadcast: {}, type: {}",
+            connection_id, broadcast, hangup_type
+        );
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+        let hangup_type = hangup_type as jint;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            broadcast.into(),
+            hangup_type.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            SEND_HANGUP_MESSAGE_METHOD,
+            SEND_HANGUP_MESSAGE_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_send_busy(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+    ) -> Result<()> {
+        info!(
+            "on_send_busy(): id: {}, broadcast: {}",
+            connection_id, broadcast
+        );
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            broadcast.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            SEND_BUSY_MESSAGE_METHOD,
+            SEND_BUSY_MESSAGE_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_send_ringing(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!("on_send_ringing(): id: {}", connection_id);
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            SEND_RINGING_MESSAGE_METHOD,
+            SEND_RINGING_MESSAGE_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn seal(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        opaque: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        debug!("seal(): id: {}, size: {}", connection_id, opaque.len());
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+        let jni_opaque = JObject::from(env.byte_array_from_slice(opaque)?);
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            jni_opaque.into(),
+        ];
+        let jni_sealed = self
+            .call_manager_method(
+                &env,
+                connection_id.call_id(),
+                jni_call_manager,
+                SEAL_METHOD,
+                SEAL_SIG,
+                &args,
+            )?
+            .l()?;
+
+        if (*jni_sealed).is_null() {
+            return Ok(None);
+        }
+        Ok(Some(env.convert_byte_array(jni_sealed.into_inner())?))
+    }
+
+    fn open(&self, connection_id: ConnectionId, sealed: &[u8]) -> Result<Vec<u8>> {
+        debug!("open(): id: {}, size: {}", connection_id, sealed.len());
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+        let jni_sealed = JObject::from(env.byte_array_from_slice(sealed)?);
+
+        let args = [
+            call_id_jlong.into(),
+            remote_device.into(),
+            jni_sealed.into(),
+        ];
+        let jni_opened = self
+            .call_manager_method(
+                &env,
+                connection_id.call_id(),
+                jni_call_manager,
+                OPEN_METHOD,
+                OPEN_SIG,
+                &args,
+            )?
+            .l()?;
+
+        if (*jni_opened).is_null() {
+            return Err(RingRtcError::OpenSealedMessage("not authentic".to_owned()).into());
+        }
+        Ok(env.convert_byte_array(jni_opened.into_inner())?)
+    }
+
+    fn create_media_stream(
+        &self,
+        _connection: &Connection<Self>,
+        stream: MediaStream,
+    ) -> Result<Self::AppMediaStream> {
+        JavaMediaStream::new(stream)
+    }
+
+    fn on_connect_media(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        app_call_context: &Self::AppCallContext,
+        media_stream: &Self::AppMediaStream,
+    ) -> Result<()> {
+        info!("on_connect_media():");
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_call_context = app_call_context.to_jni();
+        let jni_media_stream = media_stream.global_ref(&env)?;
+
+        let args = [
+            jni_call_context.as_obj().into(),
+            jni_media_stream.as_obj().into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            app_call_context.call_id(),
+            jni_call_manager,
+            CONNECT_MEDIA_METHOD,
+            CONNECT_MEDIA_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_audio_route_suggested(
+        &self,
+        app_call_context: &Self::AppCallContext,
+        route: AudioRoute,
+    ) -> Result<()> {
+        info!("on_audio_route_suggested(): route: {}", route);
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_call_context = app_call_context.to_jni();
+
+        let args = [jni_call_context.as_obj().into(), (route as jint).into()];
+        let _ = self.call_manager_method(
+            &env,
+            app_call_context.call_id(),
+            jni_call_manager,
+            AUDIO_ROUTE_SUGGESTED_METHOD,
+            AUDIO_ROUTE_SUGGESTED_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_call_added_to_system(&self, app_call_context: &Self::AppCallContext) -> Result<()> {
+        info!("on_call_added_to_system():");
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_call_context = app_call_context.to_jni();
+
+        let args = [jni_call_context.as_obj().into()];
+        let _ = self.call_manager_method(
+            &env,
+            app_call_context.call_id(),
+            jni_call_manager,
+            CALL_ADDED_TO_TELECOM_METHOD,
+            CALL_ADDED_TO_TELECOM_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_request_system_hold(
+        &self,
+        app_call_context: &Self::AppCallContext,
+        on_hold: bool,
+    ) -> Result<()> {
+        info!("on_request_system_hold(): on_hold: {}", on_hold);
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_call_context = app_call_context.to_jni();
+
+        let args = [jni_call_context.as_obj().into(), on_hold.into()];
+        let _ = self.call_manager_method(
+            &env,
+            app_call_context.call_id(),
+            jni_call_manager,
+            REQUEST_TELECOM_HOLD_METHOD,
+            REQUEST_TELECOM_HOLD_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_set_camera_enabled(
+        &self,
+        app_call_context: &Self::AppCallContext,
+        enabled: bool,
+    ) -> Result<()> {
+        info!("on_set_camera_enabled(): enabled: {}", enabled);
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_call_context = app_call_context.to_jni();
+
+        let args = [jni_call_context.as_obj().into(), enabled.into()];
+        let _ = self.call_manager_method(
+            &env,
+            app_call_context.call_id(),
+            jni_call_manager,
+            SET_CAMERA_ENABLED_METHOD,
+            SET_CAMERA_ENABLED_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_switch_camera(&self, app_call_context: &Self::AppCallContext) -> Result<()> {
+        info!("on_switch_camera():");
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_call_context = app_call_context.to_jni();
+
+        let args = [jni_call_context.as_obj().into()];
+        let _ = self.call_manager_method(
+            &env,
+            app_call_context.call_id(),
+            jni_call_manager,
+            SWITCH_CAMERA_METHOD,
+            SWITCH_CAMERA_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_close_media(&self, app_call_context: &Self::AppCallContext) -> Result<()> {
+        info!("on_close_media():");
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_call_context = app_call_context.to_jni();
+
+        let args = [jni_call_context.as_obj().into()];
+        let _ = self.call_manager_method(
+            &env,
+            app_call_context.call_id(),
+            jni_call_manager,
+            CLOSE_MEDIA_METHOD,
+            CLOSE_MEDIA_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn compare_remotes(
+        &self,
+        remote_peer1: &Self::AppRemotePeer,
+        remote_peer2: &Self::AppRemotePeer,
+    ) -> Result<bool> {
+        info!("remotes_equal():");
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_remote1 = remote_peer1.as_obj();
+        let jni_remote2 = remote_peer2.as_obj();
+
+        let args = [jni_remote1.into(), jni_remote2.into()];
+        let result = self
+            .call_manager_method_without_call(
+                &env,
+                jni_call_manager,
+                COMPARE_REMOTES_METHOD,
+                COMPARE_REMOTES_SIG,
+                &args,
+            )?
+            .z()?;
+        Ok(result)
+    }
+
+    fn on_call_record(&self, remote_peer: &Self::AppRemotePeer, record: &CallRecord) -> Result<()> {
+        info!("on_call_record(): {}", record);
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(record.call_id) as jlong;
+        let jni_record = self.jni_call_record(&env, record)?;
+
+        let args = [call_id_jlong.into(), jni_remote.into(), jni_record.into()];
+        let _ = self.call_manager_method(
+            &env,
+            record.call_id,
+            jni_call_manager,
+            CALL_RECORD_METHOD,
+            CALL_RECORD_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_call_failed(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        reason: CallFailureReason,
+    ) -> Result<()> {
+        info!("on_call_failed(): call_id: {}, reason: {}", call_id, reason);
+
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
+
+        // convert rust enum into Java enum
+        let jni_enum = self.java_enum(&env, "CallFailureReason", reason as i32)?;
+
+        let args = [call_id_jlong.into(), jni_remote.into(), jni_enum.into()];
+
+        let jni_call_manager = self.jni_call_manager()?;
+        let _ = self.call_manager_method(
+            &env,
+            call_id,
+            jni_call_manager.as_obj(),
+            CALL_FAILED_METHOD,
+            CALL_FAILED_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer, call_id: CallId) -> Result<()> {
+        info!("on_call_concluded(): call_id: {}", call_id);
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let jni_remote_peer = remote_peer.as_obj();
+        let call_id_jlong = u64::from(call_id) as jlong;
+
+        let args = [call_id_jlong.into(), jni_remote_peer.into()];
+        let _ = self.call_manager_method(
+            &env,
+            call_id,
+            jni_call_manager,
+            CALL_CONCLUDED_METHOD,
+            CALL_CONCLUDED_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn persist_pending_signaling(
+        &self,
+        pending: &[PendingSignaling<Self::AppRemotePeer>],
+    ) -> Result<()> {
+        info!("persist_pending_signaling(): len: {}", pending.len());
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+
+        let jni_pending_list = jni_new_linked_list(&env)?;
+        for signaling in pending {
+            jni_pending_list.add(self.jni_pending_signaling(&env, signaling)?)?;
+        }
+
+        let args = [JObject::from(jni_pending_list).into()];
+        let _ = self.call_manager_method_without_call(
+            &env,
+            jni_call_manager,
+            PERSIST_PENDING_SIGNALING_METHOD,
+            PERSIST_PENDING_SIGNALING_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    fn restore_pending_signaling(&self) -> Result<Vec<PendingSignaling<Self::AppRemotePeer>>> {
+        info!("restore_pending_signaling():");
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+
+        let jni_pending_list = self
+            .call_manager_method_without_call(
+                &env,
+                jni_call_manager,
+                PERSISTED_SIGNALING_METHOD,
+                PERSISTED_SIGNALING_SIG,
+                &[],
+            )?
+            .l()?;
+
+        let mut pending = Vec::new();
+        if (*jni_pending_list).is_null() {
+            return Ok(pending);
+        }
+        for jni_pending in env.get_list(jni_pending_list)?.iter()? {
+            pending.push(self.pending_signaling_from_jni(&env, jni_pending)?);
+        }
+        Ok(pending)
+    }
+
+    fn on_shutdown_complete(&self) -> Result<()> {
+        info!("on_shutdown_complete():");
+
+        let env = self.java_env()?;
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+
+        let _ = self.call_manager_method_without_call(
+            &env,
+            jni_call_manager,
+            SHUTDOWN_COMPLETE_METHOD,
+            SHUTDOWN_COMPLETE_SIG,
+            &[],
+        )?;
+        Ok(())
+    }
+}
+
+impl AndroidPlatform {
+    /// Create a new AndroidPlatform object.
+    pub fn new(env: &JNIEnv, jni_call_manager: GlobalRef) -> Result<Self> {
+        let mut class_cache = ClassCache::new();
+        for class in &[
+            CALL_MANAGER_CLASS_PATH,
+            "org/signal/ringrtc/CallManager$CallEvent",
+            "org/signal/ringrtc/CallManager$CallFailureReason",
+            "org/signal/ringrtc/CallManager$IceGatheringState",
+            "org/signal/ringrtc/CallManager$NetworkAdapterType",
+            ICE_CANDIDATE_CLASS,
+            ICE_SERVER_CLASS,
+            PENDING_SIGNALING_CLASS,
+            MEDIA_DESCRIPTOR_CLASS,
+            CALL_STATS_CLASS,
+            CALL_RECORD_CLASS,
+        ] {
+            class_cache.add_class(env, class)?;
+        }
+        for (name, sig) in CALL_MANAGER_CALLBACKS {
+            class_cache.add_method(env, CALL_MANAGER_CLASS_PATH, name, sig)?;
+        }
+
+        Ok(Self {
+            jvm: env.get_java_vm()?,
+            jni_call_manager: Swappable::new(jni_call_manager, "jni_call_manager"),
+            class_cache,
+        })
+    }
+
+    /// Return the Java CallManager object that callbacks are made on.
+    fn jni_call_manager(&self) -> Result<GlobalRef> {
+        self.jni_call_manager.get()
+    }
+
+    /// Replace the Java CallManager object that callbacks are made on,
+    /// for this platform and all its clones.
+    ///
+    /// Callbacks in progress complete on the old object.
+    pub fn update_call_manager(&self, env: &JNIEnv, jni_call_manager: JObject) -> Result<()> {
+        let jni_call_manager = env.new_global_ref(jni_call_manager)?;
+        let _ = self.jni_call_manager.replace(jni_call_manager)?;
+        Ok(())
+    }
+
+    /// Return the Java JNIEnv.
+    fn java_env(&self) -> Result<JNIEnv> {
+        match self.jvm.get_env() {
+            Ok(v) => Ok(v),
+            Err(_e) => Ok(self.jvm.attach_current_thread_as_daemon()?),
+        }
+    }
+
+    /// Convert a Rust enum into the Java enum nested in CallManager
+    /// named `class`, using the enum's `fromNativeIndex()` method.
+    fn java_enum<'a>(&self, env: &JNIEnv<'a>, class: &str, index: i32) -> Result<JObject<'a>> {
+        let class_path = format!("{}/{}${}", RINGRTC_PACKAGE, CALL_MANAGER_CLASS, class);
+        let class_object = self.class_cache.get_class(&class_path)?;
+
+        const ENUM_FROM_NATIVE_INDEX_METHOD: &str = "fromNativeIndex";
+        let method_signature = format!("(I)L{};", class_path);
+        let args = [JValue::from(index)];
+        match env.call_static_method(
+            class_object,
+            ENUM_FROM_NATIVE_INDEX_METHOD,
+            &method_signature,
+            &args,
+        ) {
+            Ok(v) => Ok(v.l()?),
+            Err(_) => Err(AndroidError::JniCallStaticMethod(
+                class_path,
+                ENUM_FROM_NATIVE_INDEX_METHOD.to_string(),
+                method_signature,
+            )
+            .into()),
+        }
+    }
+
+    /// Convert a MediaDescriptor into an
+    /// org.signal.ringrtc.MediaDescriptor object.
+    fn jni_media_descriptor<'a>(
+        &self,
+        env: &JNIEnv<'a>,
+        descriptor: &MediaDescriptor,
+    ) -> Result<JObject<'a>> {
+        let class_object = self.class_cache.get_class(MEDIA_DESCRIPTOR_CLASS)?;
+
+        const MEDIA_DESCRIPTOR_SIG: &str = "(ZZZLjava/lang/String;Ljava/lang/String;Z)V";
+
+        let codec = |codec: &Option<String>| -> Result<JObject<'a>> {
+            match codec {
+                Some(v) => Ok(JObject::from(env.new_string(v)?)),
+                None => Ok(JObject::null()),
+            }
+        };
+
+        let args = [
+            descriptor.audio.into(),
+            descriptor.video.into(),
+            descriptor.data.into(),
+            codec(&descriptor.audio_codec)?.into(),
+            codec(&descriptor.video_codec)?.into(),
+            descriptor.frame_encryption.into(),
+        ];
+        Ok(env.new_object(class_object, MEDIA_DESCRIPTOR_SIG, &args)?)
+    }
+
+    /// Create a Java CallStats object from the Rust stats.
+    fn jni_call_stats<'a>(&self, env: &JNIEnv<'a>, stats: &CallStats) -> Result<JObject<'a>> {
+        let class_object = self.class_cache.get_class(CALL_STATS_CLASS)?;
+
+        const CALL_STATS_SIG: &str =
+            "(DDDDJJLjava/lang/String;Ljava/lang/String;Ljava/lang/String;)V";
+
+        let string = |string: &Option<String>| -> Result<JObject<'a>> {
+            match string {
+                Some(v) => Ok(JObject::from(env.new_string(v)?)),
+                None => Ok(JObject::null()),
+            }
+        };
+
+        let video_adaptation = stats
+            .video_adaptation
+            .map(|adaptation| adaptation.to_string());
+
+        let args = [
+            stats.rtt_ms.into(),
+            stats.jitter_ms.into(),
+            stats.jitter_buffer_ms.into(),
+            stats.packet_loss.into(),
+            (stats.send_bitrate_bps as jlong).into(),
+            (stats.receive_bitrate_bps as jlong).into(),
+            string(&stats.audio_codec)?.into(),
+            string(&stats.video_codec)?.into(),
+            string(&video_adaptation)?.into(),
+        ];
+        Ok(env.new_object(class_object, CALL_STATS_SIG, &args)?)
+    }
+
+    /// Create a Java CallRecord object from the Rust record.
+    fn jni_call_record<'a>(&self, env: &JNIEnv<'a>, record: &CallRecord) -> Result<JObject<'a>> {
+        let class_object = self.class_cache.get_class(CALL_RECORD_CLASS)?;
+
+        const CALL_RECORD_CTOR_SIG: &str = "(ZJLorg/signal/ringrtc/CallManager$CallEvent;IJ)V";
+
+        let is_outgoing = match record.direction {
+            CallDirection::OutGoing => true,
+            CallDirection::InComing => false,
+        };
+        let jni_reason = match record.reason {
+            Some(reason) => self.java_enum(env, "CallEvent", reason as i32)?,
+            None => JObject::null(),
+        };
+        // -1 tells the application that ICE never connected.
+        let connection_type = match record.connection_type {
+            Some(connection_type) => connection_type as jint,
+            None => -1,
+        };
+
+        let args = [
+            is_outgoing.into(),
+            (record.duration.as_millis() as jlong).into(),
+            jni_reason.into(),
+            connection_type.into(),
+            (record.average_bitrate_bps as jlong).into(),
+        ];
+        Ok(env.new_object(class_object, CALL_RECORD_CTOR_SIG, &args)?)
+    }
+
+    /// Convert IceCandidates into a java.util.List of
+    /// org.webrtc.IceCandidate objects.
+    fn jni_ice_candidates<'a>(
+        &self,
+        env: &'a JNIEnv,
+        ice_candidates: &[IceCandidate],
+    ) -> Result<JObject<'a>> {
+        let ice_candidate_list = jni_new_linked_list(env)?;
+
+        for candidate in ice_candidates {
+            const ICE_CANDIDATE_CTOR_SIG: &str = "(Ljava/lang/String;ILjava/lang/String;)V";
+            let sdp_mid = env.new_string(&candidate.sdp_mid)?;
+            let sdp = env.new_string(&candidate.sdp)?;
+            let args = [
+                JObject::from(sdp_mid).into(),
+                candidate.sdp_mline_index.into(),
+                JObject::from(sdp).into(),
+            ];
+            let jni_candidate = self.class_cache.new_object(
+                env,
+                ICE_CANDIDATE_CLASS,
+                ICE_CANDIDATE_CTOR_SIG,
+                &args,
+            )?;
+            ice_candidate_list.add(jni_candidate)?;
+        }
+
+        Ok(JObject::from(ice_candidate_list))
+    }
+
+    /// Convert a PendingSignaling into an
+    /// org.signal.ringrtc.PendingSignaling object.
+    fn jni_pending_signaling<'a>(
+        &self,
+        env: &'a JNIEnv,
+        signaling: &PendingSignaling<AndroidGlobalRef>,
+    ) -> Result<JObject<'a>> {
+        let jni_sdp = match signaling.message.description() {
+            Some(description) => JObject::from(env.new_string(description)?),
+            None => JObject::null(),
+        };
+        let jni_ice_candidates =
+            self.jni_ice_candidates(env, signaling.message.ice_candidates())?;
+
+        const PENDING_SIGNALING_CTOR_SIG: &str =
+            "(Lorg/signal/ringrtc/Remote;JIILjava/lang/String;Ljava/util/List;)V";
+        let args = [
+            signaling.remote_peer.as_obj().into(),
+            (u64::from(signaling.connection_id.call_id()) as jlong).into(),
+            (signaling.connection_id.remote_device() as jint).into(),
+            signaling.message.kind().into(),
+            jni_sdp.into(),
+            jni_ice_candidates.into(),
+        ];
+        self.class_cache.new_object(
+            env,
+            PENDING_SIGNALING_CLASS,
+            PENDING_SIGNALING_CTOR_SIG,
+            &args,
+        )
+    }
+
+    /// Convert an org.signal.ringrtc.PendingSignaling object, as
+    /// persisted by the application, into a PendingSignaling.
+    fn pending_signaling_from_jni(
+        &self,
+        env: &JNIEnv,
+        jni_pending: JObject,
+    ) -> Result<PendingSignaling<AndroidGlobalRef>> {
+        const REMOTE_FIELD: &str = "remote";
+        const REMOTE_TYPE: &str = "Lorg/signal/ringrtc/Remote;";
+        let jni_remote = jni_get_field(env, jni_pending, REMOTE_FIELD, REMOTE_TYPE)?.l()?;
+
+        const NATIVE_CALL_ID_METHOD: &str = "getNativeCallId";
+        const NATIVE_CALL_ID_SIG: &str = "()J";
+        let call_id = jni_call_method(
+            env,
+            jni_pending,
+            NATIVE_CALL_ID_METHOD,
+            NATIVE_CALL_ID_SIG,
+            &[],
+        )?
+        .j()?;
+
+        const REMOTE_DEVICE_FIELD: &str = "remoteDevice";
+        const INT_TYPE: &str = "I";
+        let remote_device = jni_get_field(env, jni_pending, REMOTE_DEVICE_FIELD, INT_TYPE)?.i()?;
+
+        const NATIVE_TYPE_METHOD: &str = "getNativeType";
+        const NATIVE_TYPE_SIG: &str = "()I";
+        let kind =
+            jni_call_method(env, jni_pending, NATIVE_TYPE_METHOD, NATIVE_TYPE_SIG, &[])?.i()?;
+
+        const SDP_FIELD: &str = "sdp";
+        const STRING_TYPE: &str = "Ljava/lang/String;";
+        let jni_sdp = jni_get_field(env, jni_pending, SDP_FIELD, STRING_TYPE)?.l()?;
+        let sdp = if jni_sdp.is_null() {
+            None
+        } else {
+            Some(env.get_string(JString::from(jni_sdp))?.into())
+        };
+
+        const ICE_CANDIDATES_FIELD: &str = "iceCandidates";
+        const LIST_TYPE: &str = "Ljava/util/List;";
+        let jni_ice_candidates =
+            jni_get_field(env, jni_pending, ICE_CANDIDATES_FIELD, LIST_TYPE)?.l()?;
+        let ice_candidates = ice_candidates_from_jni(env, jni_ice_candidates)?;
+
+        Ok(PendingSignaling {
+            remote_peer:   env.new_global_ref(jni_remote)?,
+            connection_id: ConnectionId::new(CallId::from(call_id), remote_device as DeviceId),
+            message:       PendingMessage::from_kind(kind, sdp, ice_candidates)?,
+        })
+    }
+
+    /// Call into the CMI to notify the application of a change of the
+    /// ICE route, using one of the route change methods.
+    fn notify_route_changed(
+        &self,
+        method: &'static str,
+        remote_peer: &<Self as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        route: &IceRoute,
+    ) -> Result<()> {
+        let env = self.java_env()?;
+        let jni_remote = remote_peer.as_obj();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+        let call_id_jlong = u64::from(connection_id.call_id()) as jlong;
+        let remote_device = connection_id.remote_device() as jint;
+
+        let args = [
+            call_id_jlong.into(),
+            jni_remote.into(),
+            remote_device.into(),
+            JObject::from(env.new_string(&route.local_candidate_type)?).into(),
+            JObject::from(env.new_string(&route.remote_candidate_type)?).into(),
+            JObject::from(env.new_string(&route.protocol)?).into(),
+        ];
+        let _ = self.call_manager_method(
+            &env,
+            connection_id.call_id(),
+            jni_call_manager,
+            method,
+            ROUTE_CHANGED_SIG,
+            &args,
+        )?;
+        Ok(())
+    }
+
+    /// Call the method `name`, with signature `sig`, of the Java
+    /// CallManager object on behalf of the call `call_id`, using the
+    /// method ID resolved by `new()`.
+    ///
+    /// A Java exception thrown by the method is cleared and returned
+    /// as an `AndroidError::JavaException`, so the call fails with the
+    /// exception's description.
+    fn call_manager_method<'a>(
+        &self,
+        env: &'a JNIEnv,
+        call_id: CallId,
+        jni_call_manager: JObject,
+        name: &'static str,
+        sig: &'static str,
+        args: &[JValue],
+    ) -> Result<JValue<'a>> {
+        match self.class_cache.call_method(
+            env,
+            jni_call_manager,
+            CALL_MANAGER_CLASS_PATH,
+            name,
+            sig,
+            args,
+        ) {
+            Ok(v) => Ok(v),
+            Err(e) => match jni_take_exception(env) {
+                Some(description) => Err(AndroidError::JavaException(call_id, description).into()),
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Call the method `name`, with signature `sig`, of the Java
+    /// CallManager object, for the callbacks not tied to a call.
+    ///
+    /// A Java exception thrown by the method is cleared and logged.
+    fn call_manager_method_without_call<'a>(
+        &self,
+        env: &'a JNIEnv,
+        jni_call_manager: JObject,
+        name: &'static str,
+        sig: &'static str,
+        args: &[JValue],
+    ) -> Result<JValue<'a>> {
+        let result = self.class_cache.call_method(
+            env,
+            jni_call_manager,
+            CALL_MANAGER_CLASS_PATH,
+            name,
+            sig,
+            args,
+        );
+        if result.is_err() {
+            if let Some(description) = jni_take_exception(env) {
+                error!("{}() threw: {}", name, description);
+            }
+        }
+        result
+    }
+
+    pub fn try_clone(&self) -> Result<Self> {
+        let env = self.java_env()?;
+        Ok(Self {
+            jvm:              env.get_java_vm()?,
+            jni_call_manager: self.jni_call_manager.clone(),
+            class_cache:      self.class_cache.clone(),
+        })
+    }
+
+    /// Call into the CMI to create the webrtc PeerConnection, and its
+    /// Java Connection object, for a Connection.
+    fn create_jni_connection(
+        &self,
+        call: &Call<Self>,
+        connection: &Connection<Self>,
+        config: ConnectionConfig,
+    ) -> Result<()> {
+        let connection_ptr = connection.get_connection_ptr()?;
+        let call_id_jlong = u64::from(call.call_id()) as jlong;
+        let jni_remote_device = connection.remote_device() as jint;
+
+        let env = self.java_env()?;
+        let android_call_context = call.call_context()?;
+        let jni_call_context = android_call_context.to_jni();
+        let jni_call_manager = self.jni_call_manager()?;
+        let jni_call_manager = jni_call_manager.as_obj();
+
+        // create Java List<org.webrtc.PeerConnection.IceServer>, one
+        // server per URL, keeping the order
+        let ice_server_list = jni_new_linked_list(&env)?;
+        for ice_server in &config.ice_servers {
+            const ICE_SERVER_CTOR_SIG: &str =
+                "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)V";
+            let url = env.new_string(ice_server.rtc_url())?;
+            let username = env.new_string(&ice_server.username)?;
+            let password = env.new_string(&ice_server.password)?;
+            let args = [
+                JObject::from(url).into(),
+                JObject::from(username).into(),
+                JObject::from(password).into(),
+            ];
+            let jni_ice_server =
+                self.class_cache
+                    .new_object(&env, ICE_SERVER_CLASS, ICE_SERVER_CTOR_SIG, &args)?;
+            ice_server_list.add(jni_ice_server)?;
+        }
+
+        let args = [
+            (connection_ptr as jlong).into(),
+            call_id_jlong.into(),
+            jni_remote_device.into(),
+            jni_call_context.as_obj().into(),
+            JObject::from(ice_server_list).into(),
+            (config.bundle_policy as jint).into(),
+            (config.rtcp_mux_policy as jint).into(),
+        ];
+        let result = self.call_manager_method(
+            &env,
+            call.call_id(),
+            jni_call_manager,
+            CREATE_CONNECTION_METHOD,
+            CREATE_CONNECTION_SIG,
+            &args,
+        )?;
+
+        let jni_connection = result.l()?;
+        if (*jni_connection).is_null() {
+            return Err(AndroidError::CreateJniConnection.into());
+        }
+        let jni_connection = env.new_global_ref(jni_connection)?;
+        let platform = self.try_clone()?;
+        let android_connection = AndroidConnection::new(platform, call.call_id(), jni_connection);
+        connection.set_app_connection(android_connection)
     }
 }
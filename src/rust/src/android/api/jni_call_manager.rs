@@ -9,9 +9,12 @@
 //!
 //! Native JNI interfaces, called by
 //! org.signal.ringrtc.CallManager objects.
+//!
+//! Every function runs within `error::catch_fatal()`, so a panic is
+//! reported to the CallManager rather than aborting the process.
 
 use jni::objects::{JClass, JObject, JString};
-use jni::sys::{jboolean, jint, jlong, jobject};
+use jni::sys::{jboolean, jbyteArray, jfloat, jint, jlong, jobject};
 use jni::JNIEnv;
 
 use crate::android::android_platform::AndroidPlatform;
@@ -27,13 +30,9 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcGetBuildInfo
     env: JNIEnv,
     _class: JClass,
 ) -> jobject {
-    match call_manager::get_build_info(&env) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-            0 as jobject
-        }
-    }
+    error::catch_fatal(&env, JObject::null(), 0, 0 as jobject, || {
+        call_manager::get_build_info(&env)
+    })
 }
 
 #[no_mangle]
@@ -42,9 +41,9 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcInitialize(
     env: JNIEnv,
     _class: JClass,
 ) {
-    if let Err(e) = call_manager::initialize(&env) {
-        error::throw_error(&env, e);
-    }
+    error::catch_fatal(&env, JObject::null(), 0, (), || {
+        call_manager::initialize(&env)
+    })
 }
 
 #[no_mangle]
@@ -54,351 +53,964 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCreateCallMa
     _class: JClass,
     jni_call_manager: JObject,
 ) -> jlong {
-    match call_manager::create_call_manager(&env, jni_call_manager) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-            0
-        }
-    }
+    error::catch_fatal(&env, jni_call_manager, 0, 0, || {
+        call_manager::create_call_manager(&env, jni_call_manager)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcUpdateCallManager(
+    env: JNIEnv,
+    object: JObject,
+    call_manager: jlong,
+    jni_call_manager: JObject,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::update_call_manager(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            jni_call_manager,
+        )
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCreatePeerConnection(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     peer_connection_factory: jlong,
     native_connection: jlong,
     jni_rtc_config: JObject,
     jni_media_constraints: JObject,
 ) -> jlong {
-    match call_manager::create_peer_connection(
-        &env,
-        peer_connection_factory,
-        native_connection as *mut Connection<AndroidPlatform>,
-        jni_rtc_config,
-        jni_media_constraints,
-    ) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-            0
-        }
-    }
+    error::catch_fatal(&env, object, 0, 0, || {
+        call_manager::create_peer_connection(
+            &env,
+            peer_connection_factory,
+            native_connection as *mut Connection<AndroidPlatform>,
+            jni_rtc_config,
+            jni_media_constraints,
+        )
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCall(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     jni_remote: JObject,
 ) {
-    match call_manager::call(&env, call_manager as *mut AndroidCallManager, jni_remote) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::call(&env, call_manager as *mut AndroidCallManager, jni_remote)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcProceed(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
     jni_call_context: JObject,
+    jni_ice_servers: JObject,
     jni_remote_devices: JObject,
 ) {
-    match call_manager::proceed(
-        &env,
-        call_manager as *mut AndroidCallManager,
-        call_id,
-        jni_call_context,
-        jni_remote_devices,
-    ) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::proceed(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            jni_call_context,
+            jni_ice_servers,
+            jni_remote_devices,
+        )
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcMessageSent(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
 ) {
-    match call_manager::message_sent(call_manager as *mut AndroidCallManager, call_id) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::message_sent(call_manager as *mut AndroidCallManager, call_id)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcMessageSendFailure(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
 ) {
-    match call_manager::message_send_failure(call_manager as *mut AndroidCallManager, call_id) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::message_send_failure(call_manager as *mut AndroidCallManager, call_id)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHangup(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
 ) {
-    match call_manager::hangup(call_manager as *mut AndroidCallManager) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::hangup(call_manager as *mut AndroidCallManager)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReceivedAnswer(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
     remote_device: jint,
     jni_answer: JString,
 ) {
-    match call_manager::received_answer(
-        &env,
-        call_manager as *mut AndroidCallManager,
-        call_id,
-        remote_device as DeviceId,
-        jni_answer,
-    ) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::received_answer(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            remote_device as DeviceId,
+            jni_answer,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReceivedPreOffer(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    jni_remote: JObject,
+    remote_device: jint,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::received_pre_offer(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            jni_remote,
+            remote_device as DeviceId,
+        )
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReceivedOffer(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
     jni_remote: JObject,
     remote_device: jint,
     jni_offer: JString,
     timestamp: jlong,
+    age_sec: jlong,
 ) {
-    match call_manager::received_offer(
-        &env,
-        call_manager as *mut AndroidCallManager,
-        call_id,
-        jni_remote,
-        remote_device as DeviceId,
-        jni_offer,
-        timestamp as u64,
-    ) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::received_offer(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            jni_remote,
+            remote_device as DeviceId,
+            jni_offer,
+            timestamp as u64,
+            age_sec as u64,
+        )
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReceivedIceCandidates(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
     remote_device: jint,
     jni_ice_candidates: JObject,
 ) {
-    match call_manager::received_ice_candidates(
-        &env,
-        call_manager as *mut AndroidCallManager,
-        call_id,
-        remote_device as DeviceId,
-        jni_ice_candidates,
-    ) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::received_ice_candidates(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            remote_device as DeviceId,
+            jni_ice_candidates,
+        )
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReceivedHangup(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    remote_device: jint,
+    hangup_type: jint,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::received_hangup(
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            remote_device as DeviceId,
+            hangup_type,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReceivedDeclined(
+    env: JNIEnv<'static>,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
     remote_device: jint,
 ) {
-    match call_manager::received_hangup(
-        call_manager as *mut AndroidCallManager,
-        call_id,
-        remote_device as DeviceId,
-    ) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::received_declined(
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            remote_device as DeviceId,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReceivedRinging(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    remote_device: jint,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::received_ringing(
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            remote_device as DeviceId,
+        )
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReceivedBusy(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
     remote_device: jint,
 ) {
-    match call_manager::received_busy(
-        call_manager as *mut AndroidCallManager,
-        call_id,
-        remote_device as DeviceId,
-    ) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::received_busy(
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            remote_device as DeviceId,
+        )
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcAcceptCall(
     env: JNIEnv,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
 ) {
-    match call_manager::accept_call(call_manager as *mut AndroidCallManager, call_id) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::accept_call(call_manager as *mut AndroidCallManager, call_id)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcGetActiveConnection(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
 ) -> jobject {
-    match call_manager::get_active_connection(call_manager as *mut AndroidCallManager) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-            0 as jobject
-        }
-    }
+    error::catch_fatal(&env, object, 0, 0 as jobject, || {
+        call_manager::get_active_connection(call_manager as *mut AndroidCallManager)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcGetActiveCallContext(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
 ) -> jobject {
-    match call_manager::get_active_call_context(call_manager as *mut AndroidCallManager) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-            0 as jobject
-        }
-    }
+    error::catch_fatal(&env, object, 0, 0 as jobject, || {
+        call_manager::get_active_call_context(call_manager as *mut AndroidCallManager)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcGetActiveConnectionTrace(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+) -> jobject {
+    error::catch_fatal(&env, object, 0, 0 as jobject, || {
+        call_manager::get_active_connection_trace(&env, call_manager as *mut AndroidCallManager)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcGetActiveRemoteCapabilities(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+) -> jobject {
+    error::catch_fatal(&env, object, 0, 0 as jobject, || {
+        call_manager::get_active_remote_capabilities(&env, call_manager as *mut AndroidCallManager)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetVideoEnable(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
+    call_manager: jlong,
+    enable: jboolean,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_video_enable(call_manager as *mut AndroidCallManager, enable != 0)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSwitchCamera(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::switch_camera(call_manager as *mut AndroidCallManager)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetScreenShareEnable(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    enable: jboolean,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::set_screen_share_enable(
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            enable != 0,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetAudioEnable(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    enable: jboolean,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_audio_enable(call_manager as *mut AndroidCallManager, enable != 0)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetVideoMaxResolution(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    width: jint,
+    height: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_video_max_resolution(
+            call_manager as *mut AndroidCallManager,
+            width,
+            height,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetVideoCaptureResolution(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    width: jint,
+    height: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_video_capture_resolution(
+            call_manager as *mut AndroidCallManager,
+            width,
+            height,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetVideoDegradationPreference(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    preference: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_video_degradation_preference(
+            call_manager as *mut AndroidCallManager,
+            preference,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetBandwidthMode(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    mode: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_bandwidth_mode(call_manager as *mut AndroidCallManager, mode)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetAudioOutput(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    route: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_audio_output(call_manager as *mut AndroidCallManager, route)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcAudioRouteChangedBySystem(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    route: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::audio_route_changed_by_system(call_manager as *mut AndroidCallManager, route)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetBundlePolicy(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    policy: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_bundle_policy(call_manager as *mut AndroidCallManager, policy)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetRtcpMuxPolicy(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    policy: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_rtcp_mux_policy(call_manager as *mut AndroidCallManager, policy)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetIceCandidatePolicy(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    relay_only: jboolean,
+    no_ipv6: jboolean,
+    no_host: jboolean,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_ice_candidate_policy(
+            call_manager as *mut AndroidCallManager,
+            relay_only != 0,
+            no_ipv6 != 0,
+            no_host != 0,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetAnswerMetadata(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    metadata: jbyteArray,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::set_answer_metadata(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            metadata,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcUpdateIceServers(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    jni_ice_servers: JObject,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::update_ice_servers(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            jni_ice_servers,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetIceServers(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    jni_ice_servers: JObject,
+    ice_restart: jboolean,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_ice_servers(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            jni_ice_servers,
+            ice_restart != 0,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcRestartIce(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::restart_ice(call_manager as *mut AndroidCallManager, call_id)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetOnHold(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    on_hold: jboolean,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::set_on_hold(
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            on_hold != 0,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCallAddedToTelecom(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::call_added_to_telecom(call_manager as *mut AndroidCallManager, call_id)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHoldRequestedBySystem(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    on_hold: jboolean,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::hold_requested_by_system(
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            on_hold != 0,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcTransferCall(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    new_remote: JString,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::transfer_call(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            call_id,
+            new_remote,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetOutgoingAudioGain(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    gain: jfloat,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_outgoing_audio_gain(call_manager as *mut AndroidCallManager, gain)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSendDtmf(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    tones: JString,
+    duration_ms: jint,
+    gap_ms: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::send_dtmf(
+            &env,
+            call_manager as *mut AndroidCallManager,
+            tones,
+            duration_ms,
+            gap_ms,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSendAppMessage(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    payload: jbyteArray,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::send_app_message(&env, call_manager as *mut AndroidCallManager, payload)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetDataChannelHighWaterMark(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    mark: jlong,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_data_channel_high_water_mark(
+            call_manager as *mut AndroidCallManager,
+            mark,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcGetActiveDataChannelBufferedAmount(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+) -> jlong {
+    error::catch_fatal(&env, object, 0, 0, || {
+        call_manager::get_active_data_channel_buffered_amount(
+            call_manager as *mut AndroidCallManager,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetEndOnAudioDeviceFailure(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    enable: jboolean,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_end_on_audio_device_failure(
+            call_manager as *mut AndroidCallManager,
+            enable != 0,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetCallWaiting(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    enable: jboolean,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_call_waiting(call_manager as *mut AndroidCallManager, enable != 0)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetMaxConnections(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    max: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_max_connections(call_manager as *mut AndroidCallManager, max)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcAudioDeviceFailure(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    device_error: jint,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::audio_device_failure(call_manager as *mut AndroidCallManager, device_error)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetCallDurationTicks(
+    env: JNIEnv<'static>,
+    object: JObject,
     call_manager: jlong,
     enable: jboolean,
 ) {
-    match call_manager::set_video_enable(call_manager as *mut AndroidCallManager, enable != 0) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_call_duration_ticks(call_manager as *mut AndroidCallManager, enable != 0)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetStatsInterval(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    interval_millis: jlong,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_stats_interval(call_manager as *mut AndroidCallManager, interval_millis)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetAudioLevelsInterval(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    interval_millis: jlong,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_audio_levels_interval(
+            call_manager as *mut AndroidCallManager,
+            interval_millis,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetBandwidthEstimateInterval(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    interval_millis: jlong,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_bandwidth_estimate_interval(
+            call_manager as *mut AndroidCallManager,
+            interval_millis,
+        )
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetPreOffer(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    enable: jboolean,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::set_pre_offer(call_manager as *mut AndroidCallManager, enable != 0)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcGetActiveCallDuration(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+) -> jlong {
+    error::catch_fatal(&env, object, 0, 0, || {
+        call_manager::get_active_call_duration(call_manager as *mut AndroidCallManager)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCancelSetup(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::cancel_setup(call_manager as *mut AndroidCallManager, call_id)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcDrop(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
     call_id: jlong,
 ) {
-    match call_manager::drop_call(call_manager as *mut AndroidCallManager, call_id) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, call_id, (), || {
+        call_manager::drop_call(call_manager as *mut AndroidCallManager, call_id)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcRestorePendingSignaling(
+    env: JNIEnv<'static>,
+    object: JObject,
+    call_manager: jlong,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::restore_pending_signaling(call_manager as *mut AndroidCallManager)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcReset(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
     call_manager: jlong,
 ) {
-    match call_manager::reset(call_manager as *mut AndroidCallManager) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::reset(call_manager as *mut AndroidCallManager)
+    })
 }
 
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcClose(
     env: JNIEnv<'static>,
-    _object: JObject,
+    object: JObject,
+    call_manager: jlong,
+) {
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::close(call_manager as *mut AndroidCallManager)
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcShutdown(
+    env: JNIEnv<'static>,
+    object: JObject,
     call_manager: jlong,
 ) {
-    match call_manager::close(call_manager as *mut AndroidCallManager) {
-        Ok(v) => v,
-        Err(e) => {
-            error::throw_error(&env, e);
-        }
-    }
+    error::catch_fatal(&env, object, 0, (), || {
+        call_manager::shutdown(call_manager as *mut AndroidCallManager)
+    })
 }
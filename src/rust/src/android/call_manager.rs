@@ -8,9 +8,10 @@
 //! Android CallManager Interface.
 
 use std::panic;
+use std::time::Duration;
 
 use jni::objects::{JClass, JObject, JString};
-use jni::sys::{jlong, jobject};
+use jni::sys::{jbyteArray, jfloat, jint, jlong, jobject};
 use jni::JNIEnv;
 use log::Level;
 
@@ -19,9 +20,25 @@ use crate::android::error::AndroidError;
 use crate::android::jni_util::*;
 use crate::android::logging::init_logging;
 use crate::android::webrtc_peer_connection_factory::*;
-use crate::common::{CallDirection, CallId, ConnectionId, DeviceId, Result, DATA_CHANNEL_NAME};
+use crate::common::{
+    AudioDeviceError,
+    CallDirection,
+    CallId,
+    ConnectionId,
+    DeviceId,
+    HangupType,
+    Result,
+    DATA_CHANNEL_NAME,
+};
+use crate::core::audio_route::AudioRoute;
+use crate::core::bandwidth_mode::BandwidthMode;
+use crate::core::call_config::CallConfig;
 use crate::core::connection::Connection;
+use crate::core::connection_config::{BundlePolicy, RtcpMuxPolicy};
+use crate::core::ice_candidate_policy::IceCandidatePolicy;
+use crate::core::ice_server::{order_by_transport, IceServerConfig, DEFAULT_TURN_TRANSPORT_ORDER};
 use crate::core::util::{ptr_as_box, ptr_as_mut};
+use crate::core::video::{DegradationPreference, VideoResolution};
 
 use crate::core::call_manager::CallManager;
 
@@ -75,6 +92,20 @@ pub fn create_call_manager(env: &JNIEnv, jni_call_manager: JObject) -> Result<jl
     Ok(Box::into_raw(call_manager_box) as jlong)
 }
 
+/// Points an AndroidCallManager at a new Java CallManager object,
+/// e.g. after the application recreated it.
+pub fn update_call_manager(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    jni_call_manager: JObject,
+) -> Result<()> {
+    info!("update_call_manager():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let platform = call_manager.platform()?;
+    platform.update_call_manager(env, jni_call_manager)
+}
+
 /// Create a org.webrtc.PeerConnection object
 pub fn create_peer_connection(
     env: &JNIEnv,
@@ -142,7 +173,7 @@ pub fn call(
 
     let app_remote_peer = env.new_global_ref(jni_remote)?;
 
-    call_manager.call(app_remote_peer)
+    call_manager.call(app_remote_peer, CallConfig::default())
 }
 
 /// Application notification to proceed with a new call
@@ -151,6 +182,7 @@ pub fn proceed(
     call_manager: *mut AndroidCallManager,
     call_id: jlong,
     jni_call_context: JObject,
+    jni_ice_servers: JObject,
     jni_remote_devices: JObject,
 ) -> Result<()> {
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
@@ -177,10 +209,14 @@ pub fn proceed(
         info!("proceed(): device id: {}", device);
     }
 
+    let ice_servers = ice_servers_from_jni(env, jni_ice_servers)?;
+    info!("proceed(): ice_servers size: {}", ice_servers.len());
+
     let platform = call_manager.platform()?.try_clone()?;
     let android_call_context =
-        AndroidCallContext::new(platform, env.new_global_ref(jni_call_context)?);
-    call_manager.proceed(call_id, android_call_context, remote_devices)
+        AndroidCallContext::new(platform, call_id, env.new_global_ref(jni_call_context)?);
+    let _ = call_manager.proceed(call_id, android_call_context, remote_devices, ice_servers)?;
+    Ok(())
 }
 
 /// Application notification that signal message was sent successfully
@@ -224,6 +260,24 @@ pub fn received_answer(
     call_manager.received_answer(connection_id, env.get_string(jni_answer)?.into())
 }
 
+/// Application notification of received pre-offer message
+pub fn received_pre_offer(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    jni_remote: JObject,
+    remote_device: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
+
+    info!("received_pre_offer(): id: {}", connection_id);
+
+    let app_remote_peer = env.new_global_ref(jni_remote)?;
+
+    call_manager.received_pre_offer(app_remote_peer, connection_id)
+}
+
 /// Application notification of received SDP offer message
 pub fn received_offer(
     env: &JNIEnv,
@@ -233,11 +287,12 @@ pub fn received_offer(
     remote_device: DeviceId,
     jni_offer: JString,
     timestamp: u64,
+    age_sec: u64,
 ) -> Result<()> {
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
     let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
 
-    info!("received_offer(): id: {}", connection_id);
+    info!("received_offer(): id: {}, age: {}s", connection_id, age_sec);
 
     let app_remote_peer = env.new_global_ref(jni_remote)?;
 
@@ -246,6 +301,8 @@ pub fn received_offer(
         connection_id,
         env.get_string(jni_offer)?.into(),
         timestamp,
+        Duration::from_secs(age_sec),
+        CallConfig::default(),
     )
 }
 
@@ -270,9 +327,25 @@ pub fn received_ice_candidates(
         return Ok(());
     }
 
-    // Convert Java list of org.webrtc.IceCandidate into Rust Vector of IceCandidate
-    let candidate_list = env.get_list(jni_ice_candidates)?;
+    let ice_candidates = ice_candidates_from_jni(env, jni_ice_candidates)?;
+    info!("ice_candidate size: {}", ice_candidates.len());
+    call_manager.received_ice_candidates(connection_id, &ice_candidates, Duration::from_secs(0))?;
+
+    Ok(())
+}
+
+/// Convert a Java list of org.webrtc.IceCandidate objects into
+/// IceCandidates, a null list into an empty one.
+pub fn ice_candidates_from_jni(
+    env: &JNIEnv,
+    jni_ice_candidates: JObject,
+) -> Result<Vec<IceCandidate>> {
     let mut ice_candidates = Vec::new();
+    if jni_ice_candidates.is_null() {
+        return Ok(ice_candidates);
+    }
+
+    let candidate_list = env.get_list(jni_ice_candidates)?;
     for jni_candidate in candidate_list.iter()? {
         const SDP_MID_FIELD: &str = "sdpMid";
         const STRING_TYPE: &str = "Ljava/lang/String;";
@@ -296,11 +369,7 @@ pub fn received_ice_candidates(
         let ice_candidate = IceCandidate::new(sdp_mid, sdp_m_line, sdp);
         ice_candidates.push(ice_candidate);
     }
-
-    info!("ice_candidate size: {}", ice_candidates.len());
-    call_manager.received_ice_candidates(connection_id, &ice_candidates)?;
-
-    Ok(())
+    Ok(ice_candidates)
 }
 
 /// Application notification of received Hangup message
@@ -308,13 +377,46 @@ pub fn received_hangup(
     call_manager: *mut AndroidCallManager,
     call_id: jlong,
     remote_device: DeviceId,
+    hangup_type: jint,
 ) -> Result<()> {
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
     let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
+    let hangup_type = HangupType::from_i32(hangup_type)?;
 
-    info!("received_hangup(): id: {}", connection_id);
+    info!(
+        "received_hangup(): id: {}, type: {}",
+        connection_id, hangup_type
+    );
 
-    call_manager.received_hangup(connection_id)
+    call_manager.received_hangup(connection_id, hangup_type)
+}
+
+/// Application notification of received declined Hangup message
+pub fn received_declined(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    remote_device: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
+
+    info!("received_declined(): id: {}", connection_id);
+
+    call_manager.received_declined(connection_id)
+}
+
+/// Application notification of received Ringing message
+pub fn received_ringing(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    remote_device: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
+
+    info!("received_ringing(): id: {}", connection_id);
+
+    call_manager.received_ringing(connection_id)
 }
 
 /// Application notification of received Busy message
@@ -338,7 +440,8 @@ pub fn accept_call(call_manager: *mut AndroidCallManager, call_id: jlong) -> Res
     info!("accept_call(): {}", call_id);
 
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
-    call_manager.accept_call(call_id)
+    let _ = call_manager.accept_call(call_id)?;
+    Ok(())
 }
 
 /// CMI request for the active Connection object
@@ -363,13 +466,588 @@ pub fn get_active_call_context(call_manager: *mut AndroidCallManager) -> Result<
     Ok(android_call_context.to_jni().as_obj().into_inner())
 }
 
+/// CMI request for the trace of the active Connection.
+///
+/// Returns a java.util.List of org.signal.ringrtc.TraceEvent objects,
+/// ordered by timestamp.
+pub fn get_active_connection_trace(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+) -> Result<jobject> {
+    info!("get_active_connection_trace():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let connection = call_manager.active_connection()?;
+    let events = connection.export_trace()?;
+
+    const TRACE_EVENT_CLASS: &str = "org/signal/ringrtc/TraceEvent";
+    const TRACE_EVENT_SIG: &str = "(JLjava/lang/String;Ljava/lang/String;)V";
+
+    let trace_list = jni_new_linked_list(env)?;
+    for event in events {
+        let kind = env.new_string(format!("{}", event.kind))?;
+        let description = env.new_string(&event.description)?;
+        let args = [
+            (event.timestamp_ms as jlong).into(),
+            JObject::from(kind).into(),
+            JObject::from(description).into(),
+        ];
+        let jni_event = jni_new_object(env, TRACE_EVENT_CLASS, TRACE_EVENT_SIG, &args)?;
+        trace_list.add(jni_event)?;
+    }
+
+    Ok(JObject::from(trace_list).into_inner())
+}
+
+/// CMI request for the capabilities of the remote peer of the active
+/// Connection.
+///
+/// Returns an org.signal.ringrtc.RemoteCapabilities object, or null
+/// until the offer/answer exchange completes.
+pub fn get_active_remote_capabilities(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+) -> Result<jobject> {
+    info!("get_active_remote_capabilities():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let connection = call_manager.active_connection()?;
+    let capabilities = match connection.remote_capabilities()? {
+        Some(v) => v,
+        None => return Ok(JObject::null().into_inner()),
+    };
+
+    const REMOTE_CAPABILITIES_CLASS: &str = "org/signal/ringrtc/RemoteCapabilities";
+    const REMOTE_CAPABILITIES_SIG: &str = "(ZZZZZLjava/util/List;)V";
+
+    let codec_list = jni_new_linked_list(env)?;
+    for codec in &capabilities.codecs {
+        codec_list.add(JObject::from(env.new_string(codec)?))?;
+    }
+
+    let args = [
+        capabilities.audio.into(),
+        capabilities.video.into(),
+        capabilities.data.into(),
+        capabilities.simulcast.into(),
+        capabilities.screen_share.into(),
+        JObject::from(codec_list).into(),
+    ];
+    let jni_capabilities = jni_new_object(
+        env,
+        REMOTE_CAPABILITIES_CLASS,
+        REMOTE_CAPABILITIES_SIG,
+        &args,
+    )?;
+
+    Ok(jni_capabilities.into_inner())
+}
+
 /// CMI request to set the video status
 pub fn set_video_enable(call_manager: *mut AndroidCallManager, enable: bool) -> Result<()> {
     info!("set_video_enable():");
 
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = call_manager.active_call()?.call_id();
+    call_manager.set_camera_enabled(call_id, enable)?;
+    call_manager.set_outgoing_video_enabled(call_id, enable)
+}
+
+/// CMI request to switch to the next camera
+pub fn switch_camera(call_manager: *mut AndroidCallManager) -> Result<()> {
+    info!("switch_camera():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = call_manager.active_call()?.call_id();
+    call_manager.switch_camera(call_id)
+}
+
+/// CMI request to start or stop sharing the screen
+pub fn set_screen_share_enable(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    enable: bool,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+
+    info!("set_screen_share_enable(): {}, enable: {}", call_id, enable);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_outgoing_screen_share_enabled(call_id, enable)
+}
+
+/// CMI request to set the outgoing audio status
+pub fn set_audio_enable(call_manager: *mut AndroidCallManager, enable: bool) -> Result<()> {
+    info!("set_audio_enable():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = call_manager.active_call()?.call_id();
+    call_manager.set_outgoing_audio_enabled(call_id, enable)
+}
+
+/// CMI request to cap the sent video resolution
+pub fn set_video_max_resolution(
+    call_manager: *mut AndroidCallManager,
+    width: jint,
+    height: jint,
+) -> Result<()> {
+    info!("set_video_max_resolution(): {}x{}", width, height);
+
+    let resolution = VideoResolution::from_i32(width, height)?;
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = call_manager.active_call()?.call_id();
+    call_manager.set_video_max_resolution(call_id, resolution.width, resolution.height)
+}
+
+/// CMI request to update the video capture resolution
+pub fn set_video_capture_resolution(
+    call_manager: *mut AndroidCallManager,
+    width: jint,
+    height: jint,
+) -> Result<()> {
+    info!("set_video_capture_resolution(): {}x{}", width, height);
+
+    let resolution = VideoResolution::from_i32(width, height)?;
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    active_connection.set_video_capture_resolution(resolution.width, resolution.height)
+}
+
+/// CMI request to set how the sent video degrades
+pub fn set_video_degradation_preference(
+    call_manager: *mut AndroidCallManager,
+    preference: jint,
+) -> Result<()> {
+    let preference = DegradationPreference::from_i32(preference)?;
+    info!("set_video_degradation_preference(): {}", preference);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    active_connection.set_degradation_preference(preference)
+}
+
+/// CMI request to set how much bandwidth the active call may use
+pub fn set_bandwidth_mode(call_manager: *mut AndroidCallManager, mode: jint) -> Result<()> {
+    let mode = BandwidthMode::from_i32(mode)?;
+    info!("set_bandwidth_mode(): {}", mode);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = call_manager.active_call()?.call_id();
+    call_manager.set_bandwidth_mode(call_id, mode)
+}
+
+/// CMI request to set where the application plays the audio of calls
+pub fn set_audio_output(call_manager: *mut AndroidCallManager, route: jint) -> Result<()> {
+    let route = AudioRoute::from_i32(route)?;
+    info!("set_audio_output(): {}", route);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_audio_output(route)
+}
+
+/// CMI request to set where the system routed the audio of calls
+pub fn audio_route_changed_by_system(
+    call_manager: *mut AndroidCallManager,
+    route: jint,
+) -> Result<()> {
+    let route = AudioRoute::from_i32(route)?;
+    info!("audio_route_changed_by_system(): {}", route);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_audio_output(route)
+}
+
+/// CMI request to set how media sections are bundled onto transports
+pub fn set_bundle_policy(call_manager: *mut AndroidCallManager, policy: jint) -> Result<()> {
+    let policy = BundlePolicy::from_i32(policy)?;
+    info!("set_bundle_policy(): {}", policy);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_bundle_policy(policy)
+}
+
+/// CMI request to set whether RTP and RTCP share a port
+pub fn set_rtcp_mux_policy(call_manager: *mut AndroidCallManager, policy: jint) -> Result<()> {
+    let policy = RtcpMuxPolicy::from_i32(policy)?;
+    info!("set_rtcp_mux_policy(): {}", policy);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_rtcp_mux_policy(policy)
+}
+
+/// CMI request to set which ICE candidates new connections exchange
+pub fn set_ice_candidate_policy(
+    call_manager: *mut AndroidCallManager,
+    relay_only: bool,
+    no_ipv6: bool,
+    no_host: bool,
+) -> Result<()> {
+    let policy = IceCandidatePolicy {
+        relay_only,
+        no_ipv6,
+        no_host,
+    };
+    info!("set_ice_candidate_policy(): {}", policy);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_ice_candidate_policy(policy)
+}
+
+/// CMI request to set the gain of the outgoing audio of the active
+/// connection
+pub fn set_outgoing_audio_gain(call_manager: *mut AndroidCallManager, gain: jfloat) -> Result<()> {
+    info!("set_outgoing_audio_gain(): {}", gain);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    active_connection.set_outgoing_audio_gain(gain)
+}
+
+/// CMI request to send DTMF tones on the active connection
+pub fn send_dtmf(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    tones: JString,
+    duration_ms: jint,
+    gap_ms: jint,
+) -> Result<()> {
+    let tones: String = env.get_string(tones)?.into();
+    info!(
+        "send_dtmf(): tones: {}, duration_ms: {}, gap_ms: {}",
+        tones, duration_ms, gap_ms
+    );
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    active_connection.send_dtmf(&tones, duration_ms, gap_ms)
+}
+
+/// Convert a Java list of org.webrtc.PeerConnection.IceServer into a
+/// Rust Vector of IceServerConfig, one per URL.
+pub fn ice_servers_from_jni(
+    env: &JNIEnv,
+    jni_ice_servers: JObject,
+) -> Result<Vec<IceServerConfig>> {
+    let server_list = env.get_list(jni_ice_servers)?;
+    let mut ice_servers = Vec::new();
+    for jni_server in server_list.iter()? {
+        const STRING_TYPE: &str = "Ljava/lang/String;";
+        const USERNAME_FIELD: &str = "username";
+        let username = jni_get_field(&env, jni_server, USERNAME_FIELD, STRING_TYPE)?.l()?;
+        let username: String = env.get_string(JString::from(username))?.into();
+
+        const PASSWORD_FIELD: &str = "password";
+        let password = jni_get_field(&env, jni_server, PASSWORD_FIELD, STRING_TYPE)?.l()?;
+        let password: String = env.get_string(JString::from(password))?.into();
+
+        const URLS_FIELD: &str = "urls";
+        const LIST_TYPE: &str = "Ljava/util/List;";
+        let urls = jni_get_field(&env, jni_server, URLS_FIELD, LIST_TYPE)?.l()?;
+        for url in env.get_list(urls)?.iter()? {
+            let url: String = env.get_string(JString::from(url))?.into();
+            ice_servers.push(IceServerConfig::new(&url, &username, &password)?);
+        }
+    }
+    Ok(ice_servers)
+}
+
+/// CMI request to replace the ICE servers of the active call
+pub fn update_ice_servers(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    jni_ice_servers: JObject,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    let ice_servers = ice_servers_from_jni(env, jni_ice_servers)?;
+
+    info!(
+        "update_ice_servers(): {}, size: {}",
+        call_id,
+        ice_servers.len()
+    );
+    call_manager.update_ice_servers(call_id, ice_servers)
+}
+
+/// CMI request to replace the ICE servers of the active connection
+pub fn set_ice_servers(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    jni_ice_servers: JObject,
+    ice_restart: bool,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+
+    let ice_servers = ice_servers_from_jni(env, jni_ice_servers)?;
+    let ice_servers = order_by_transport(&ice_servers, &DEFAULT_TURN_TRANSPORT_ORDER);
+
+    info!(
+        "set_ice_servers(): size: {}, ice_restart: {}",
+        ice_servers.len(),
+        ice_restart
+    );
+
     let mut active_connection = call_manager.active_connection()?;
-    active_connection.inject_local_video_status(enable)
+    active_connection.set_ice_servers(ice_servers, ice_restart)
+}
+
+/// CMI request to restart ICE on the active connection
+pub fn restart_ice(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_id = CallId::from(call_id);
+
+    info!("restart_ice(): {}", call_id);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.restart_ice(call_id)
+}
+
+/// CMI request to put the active call on hold, or resume it
+pub fn set_on_hold(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    on_hold: bool,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+
+    info!("set_on_hold(): {}, on_hold: {}", call_id, on_hold);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_on_hold(call_id, on_hold)
+}
+
+/// CMI request to mark a call as managed by Telecom
+pub fn call_added_to_telecom(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_id = CallId::from(call_id);
+
+    info!("call_added_to_telecom(): {}", call_id);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.call_added_to_system(call_id)
+}
+
+/// CMI request from Telecom to put a call on hold, or resume it
+pub fn hold_requested_by_system(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    on_hold: bool,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+
+    info!(
+        "hold_requested_by_system(): {}, on_hold: {}",
+        call_id, on_hold
+    );
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.hold_requested_by_system(call_id, on_hold)
+}
+
+/// CMI request to transfer the active call to another peer
+pub fn transfer_call(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    new_remote: JString,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let new_remote: String = env.get_string(new_remote)?.into();
+
+    info!("transfer_call(): {}, new_remote: {}", call_id, new_remote);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.transfer_call(call_id, new_remote)
+}
+
+/// CMI request to send the remote peer of the active connection an
+/// application message
+pub fn send_app_message(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    payload: jbyteArray,
+) -> Result<()> {
+    let payload = env.convert_byte_array(payload)?;
+    info!("send_app_message(): size: {}", payload.len());
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    active_connection.send_app_message(&payload)
+}
+
+/// CMI request to set the data channel high water mark of the active
+/// connection
+pub fn set_data_channel_high_water_mark(
+    call_manager: *mut AndroidCallManager,
+    mark: jlong,
+) -> Result<()> {
+    info!("set_data_channel_high_water_mark(): {}", mark);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    active_connection.set_data_channel_high_water_mark(mark as u64)
+}
+
+/// CMI request for the data channel buffered amount of the active
+/// connection
+pub fn get_active_data_channel_buffered_amount(
+    call_manager: *mut AndroidCallManager,
+) -> Result<jlong> {
+    info!("get_active_data_channel_buffered_amount():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    Ok(active_connection.data_channel_buffered_amount()? as jlong)
+}
+
+/// CMI request to enable or disable ending the active call on audio
+/// device failure
+pub fn set_end_on_audio_device_failure(
+    call_manager: *mut AndroidCallManager,
+    enable: bool,
+) -> Result<()> {
+    info!("set_end_on_audio_device_failure(): {}", enable);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_end_on_audio_device_failure(enable)
+}
+
+/// CMI request to enable or disable call waiting
+pub fn set_call_waiting(call_manager: *mut AndroidCallManager, enable: bool) -> Result<()> {
+    info!("set_call_waiting(): {}", enable);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_call_waiting(enable)
+}
+
+/// CMI request to report an audio device failure of the active call
+pub fn audio_device_failure(call_manager: *mut AndroidCallManager, error: jint) -> Result<()> {
+    let error = AudioDeviceError::from_i32(error)?;
+    info!("audio_device_failure(): {}", error);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.audio_device_failure(error)
+}
+
+/// CMI request to limit the number of connections of an outgoing
+/// call, zero or less for no limit
+pub fn set_max_connections(call_manager: *mut AndroidCallManager, max: jint) -> Result<()> {
+    info!("set_max_connections(): {}", max);
+
+    let max = if max > 0 { Some(max as usize) } else { None };
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_max_connections(max)
+}
+
+/// CMI request to enable or disable the call duration ticks
+pub fn set_call_duration_ticks(call_manager: *mut AndroidCallManager, enable: bool) -> Result<()> {
+    info!("set_call_duration_ticks(): {}", enable);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_call_duration_ticks(enable)
+}
+
+/// CMI request to set how often the stats of the active call are
+/// reported, zero or less to disable
+pub fn set_stats_interval(
+    call_manager: *mut AndroidCallManager,
+    interval_millis: jlong,
+) -> Result<()> {
+    info!("set_stats_interval(): {}", interval_millis);
+
+    let interval = if interval_millis > 0 {
+        Some(Duration::from_millis(interval_millis as u64))
+    } else {
+        None
+    };
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_stats_interval(interval)
+}
+
+/// CMI request to set how often the audio levels of the active call
+/// are reported, zero or less to disable
+pub fn set_audio_levels_interval(
+    call_manager: *mut AndroidCallManager,
+    interval_millis: jlong,
+) -> Result<()> {
+    info!("set_audio_levels_interval(): {}", interval_millis);
+
+    let interval = if interval_millis > 0 {
+        Some(Duration::from_millis(interval_millis as u64))
+    } else {
+        None
+    };
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_audio_levels_interval(interval)
+}
+
+/// CMI request to attach opaque metadata to the answer of an incoming
+/// call
+pub fn set_answer_metadata(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    metadata: jbyteArray,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    let metadata = env.convert_byte_array(metadata)?;
+
+    info!(
+        "set_answer_metadata(): {}, size: {}",
+        call_id,
+        metadata.len()
+    );
+    call_manager.set_answer_metadata(call_id, metadata)
+}
+
+/// CMI request to set how often the bandwidth estimate of the active
+/// call is reported, zero or less to disable
+pub fn set_bandwidth_estimate_interval(
+    call_manager: *mut AndroidCallManager,
+    interval_millis: jlong,
+) -> Result<()> {
+    info!("set_bandwidth_estimate_interval(): {}", interval_millis);
+
+    let interval = if interval_millis > 0 {
+        Some(Duration::from_millis(interval_millis as u64))
+    } else {
+        None
+    };
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_bandwidth_estimate_interval(interval)
+}
+
+/// CMI request to enable or disable pre-offers
+pub fn set_pre_offer(call_manager: *mut AndroidCallManager, enable: bool) -> Result<()> {
+    info!("set_pre_offer(): {}", enable);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_pre_offer(enable)
+}
+
+/// CMI request for the duration of the active call, in milliseconds
+pub fn get_active_call_duration(call_manager: *mut AndroidCallManager) -> Result<jlong> {
+    info!("get_active_call_duration():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_call = call_manager.active_call()?;
+    Ok(active_call.duration()?.as_millis() as jlong)
+}
+
+/// CMI request to cancel the setup of an active call
+pub fn cancel_setup(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_id = CallId::from(call_id);
+
+    info!("cancel_setup(): {}", call_id);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.cancel_setup(call_id)
 }
 
 /// CMI request to drop the active call
@@ -382,6 +1060,15 @@ pub fn drop_call(call_manager: *mut AndroidCallManager, call_id: jlong) -> Resul
     call_manager.drop_call(call_id)
 }
 
+/// CMI request to send the signaling messages persisted before a
+/// restart
+pub fn restore_pending_signaling(call_manager: *mut AndroidCallManager) -> Result<()> {
+    info!("restore_pending_signaling():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.restore_pending_signaling()
+}
+
 /// CMI request to reset the Call Manager
 pub fn reset(call_manager: *mut AndroidCallManager) -> Result<()> {
     info!("reset():");
@@ -401,3 +1088,15 @@ pub fn close(call_manager: *mut AndroidCallManager) -> Result<()> {
     let mut call_manager = unsafe { ptr_as_box(call_manager)? };
     call_manager.close()
 }
+
+/// CMI request to shut down the Call Manager gracefully.
+///
+/// This is a blocking call.
+pub fn shutdown(call_manager: *mut AndroidCallManager) -> Result<()> {
+    info!("shutdown():");
+
+    // Convert the raw pointer back into a Box and let it go out of
+    // scope when this function exits.
+    let mut call_manager = unsafe { ptr_as_box(call_manager)? };
+    call_manager.shutdown()
+}
@@ -7,11 +7,19 @@
 
 //! Android Error Codes and Utilities.
 
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use failure::Error;
 use jni::errors;
 use jni::objects::JObject;
+use jni::sys::jlong;
 use jni::JNIEnv;
 
+use crate::android::jni_util::jni_call_method;
+use crate::common::{CallId, Result};
+
 const CALL_EXCEPTION_CLASS: &str = "org/signal/ringrtc/CallException";
 
 /// Convert a `Error` into a Java `org.signal.ringrtc.CallException`
@@ -60,6 +68,99 @@ pub fn throw_error(env: &JNIEnv, error: Error) {
     }
 }
 
+/// Set once a panic reached a JNI boundary, after which the native
+/// state can no longer be trusted.
+static POISONED: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` once a panic reached a JNI boundary.
+pub fn poisoned() -> bool {
+    POISONED.load(Ordering::Acquire)
+}
+
+/// Run the body of a JNI function, catching any panic before it
+/// unwinds into the JVM and aborts the whole process.
+///
+/// A panic poisons the native code: it is reported with the
+/// `CallManager.onFatalError()` callback of `jni_call_manager`, or
+/// thrown as a `CallException` without one, and every later JNI call
+/// throws without running.  An error returned by `body` is thrown as
+/// usual.  Either way `default` is returned to Java.
+pub fn catch_fatal<T, F>(
+    env: &JNIEnv,
+    jni_call_manager: JObject,
+    call_id: jlong,
+    default: T,
+    body: F,
+) -> T
+where
+    F: FnOnce() -> Result<T>,
+{
+    if poisoned() {
+        throw_error(env, AndroidError::Poisoned.into());
+        return default;
+    }
+
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(v)) => v,
+        Ok(Err(e)) => {
+            throw_error(env, e);
+            default
+        }
+        Err(payload) => {
+            POISONED.store(true, Ordering::Release);
+            let message = panic_message(&*payload);
+            error!("Fatal native error, call_id: {}: {}", call_id, message);
+
+            let reported = if jni_call_manager.is_null() {
+                false
+            } else {
+                report_fatal_error(env, jni_call_manager, call_id, &message)
+                    .map_err(|e| error!("Unable to report fatal error: {}", e))
+                    .is_ok()
+            };
+            if !reported {
+                throw_error(env, AndroidError::FatalError(message).into());
+            }
+            default
+        }
+    }
+}
+
+/// Extract the message of a panic, as passed to `panic!()`.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+/// Hand a fatal error to `CallManager.onFatalError()`.
+fn report_fatal_error(
+    env: &JNIEnv,
+    jni_call_manager: JObject,
+    call_id: jlong,
+    message: &str,
+) -> Result<()> {
+    const ON_FATAL_ERROR_METHOD: &str = "onFatalError";
+    const ON_FATAL_ERROR_SIG: &str = "(JLjava/lang/String;)V";
+
+    let args = [
+        call_id.into(),
+        JObject::from(env.new_string(message)?).into(),
+    ];
+    let _ = jni_call_method(
+        env,
+        jni_call_manager,
+        ON_FATAL_ERROR_METHOD,
+        ON_FATAL_ERROR_SIG,
+        &args,
+    )?;
+    Ok(())
+}
+
 /// Android specific error codes.
 #[derive(Fail, Debug)]
 pub enum AndroidError {
@@ -86,6 +187,11 @@ pub enum AndroidError {
     JniCallConstructor(String, String),
     #[fail(display = "JNI: getting field failed.  Field: {}, Type: {}", _0, _1)]
     JniGetField(String, String),
+    #[fail(
+        display = "JNI: Java exception thrown.  CallId: {}, Exception: {}",
+        _0, _1
+    )]
+    JavaException(CallId, String),
 
     // Android Class Cache error codes
     #[fail(display = "ClassCache: Class is already in cache: {}", _0)]
@@ -94,6 +200,11 @@ pub enum AndroidError {
     ClassCacheNotFound(String),
     #[fail(display = "ClassCache: class not found in cache: {}", _0)]
     ClassCacheLookup(String),
+    #[fail(
+        display = "ClassCache: method not found.  Class: {}, Method: {}, Sig: {}",
+        _0, _1, _2
+    )]
+    ClassCacheMethodNotFound(String, String, String),
 
     // Android Misc error codes
     #[fail(display = "Creating JNI PeerConnection failed")]
@@ -106,4 +217,10 @@ pub enum AndroidError {
     // WebRTC / JNI C++ error codes
     #[fail(display = "Unable to create C++ JavaMediaStream")]
     CreateJavaMediaStream,
+
+    // Fatal error codes
+    #[fail(display = "Fatal native error: {}", _0)]
+    FatalError(String),
+    #[fail(display = "Native code is unusable after a fatal error")]
+    Poisoned,
 }
@@ -8,12 +8,16 @@
 //! Utility helpers for JNI access
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use jni::objects::{GlobalRef, JClass, JList, JObject, JValue};
+use jni::objects::{GlobalRef, JClass, JList, JMethodID, JObject, JString, JThrowable, JValue};
+use jni::signature::{JavaType, Primitive, TypeSignature};
+use jni::sys::jmethodID;
 use jni::JNIEnv;
 
 use crate::android::error::AndroidError;
 use crate::common::Result;
+use crate::core::call_mutex::CallMutex;
 
 /// Wrapper around JNIEnv::call_method() with logging.
 pub fn jni_call_method<'a>(
@@ -30,7 +34,6 @@ pub fn jni_call_method<'a>(
 }
 
 /// Wrapper around JNIEnv::call_static_method() with logging.
-#[allow(dead_code)]
 pub fn jni_call_static_method<'a>(
     env: &'a JNIEnv,
     class: &str,
@@ -84,6 +87,70 @@ pub fn jni_new_linked_list<'a>(env: &'a JNIEnv) -> Result<JList<'a, 'a>> {
     Ok(env.get_list(list)?)
 }
 
+/// Clears the pending Java exception, if any, and returns its
+/// description, i.e. its class, message and stack trace.
+///
+/// JNI functions cannot be called while an exception is pending, so
+/// the exception thrown by a Java method must be cleared before the
+/// error is handled.
+pub fn jni_take_exception(env: &JNIEnv) -> Option<String> {
+    match env.exception_check() {
+        Ok(true) => {}
+        _ => return None,
+    }
+
+    let throwable = env.exception_occurred();
+    let _ = env.exception_clear();
+    let description = match throwable {
+        Ok(throwable) => jni_describe_throwable(env, throwable),
+        Err(e) => Err(e.into()),
+    };
+    // Describing the exception can throw as well.
+    let _ = env.exception_clear();
+
+    match description {
+        Ok(v) => Some(v),
+        Err(e) => Some(format!("<undescribed exception: {}>", e)),
+    }
+}
+
+/// Returns the description of a java.lang.Throwable, as printed by
+/// its printStackTrace() method.
+fn jni_describe_throwable(env: &JNIEnv, throwable: JThrowable) -> Result<String> {
+    const LOG_CLASS: &str = "android/util/Log";
+    const GET_STACK_TRACE_STRING_METHOD: &str = "getStackTraceString";
+    const GET_STACK_TRACE_STRING_SIG: &str = "(Ljava/lang/Throwable;)Ljava/lang/String;";
+
+    let args = [JObject::from(throwable).into()];
+    let description = jni_call_static_method(
+        env,
+        LOG_CLASS,
+        GET_STACK_TRACE_STRING_METHOD,
+        GET_STACK_TRACE_STRING_SIG,
+        &args,
+    )?
+    .l()?;
+    Ok(env.get_string(JString::from(description))?.into())
+}
+
+/// The name of the constructors of Java classes.
+const CONSTRUCTOR_NAME: &str = "<init>";
+
+/// A method ID, along with the return type needed to call it.
+#[derive(Clone)]
+struct CachedMethod {
+    id:  jmethodID,
+    ret: JavaType,
+}
+
+// Method IDs are not tied to the thread that looked them up, see
+// https://docs.oracle.com/javase/8/docs/technotes/guides/jni/spec/design.html#accessing_fields_and_methods
+unsafe impl Sync for CachedMethod {}
+unsafe impl Send for CachedMethod {}
+
+/// Method IDs keyed by class name, method name and signature.
+type MethodMap = HashMap<(&'static str, &'static str, &'static str), CachedMethod>;
+
 /// A cache of Java class objects
 ///
 /// JNI cannot lookup classes by name from threads other than the main
@@ -93,17 +160,26 @@ pub fn jni_new_linked_list<'a>(env: &'a JNIEnv) -> Result<JList<'a, 'a>> {
 /// The solution here is to look up the class objects at init time on
 /// the main thread and cache a global reference to the object for
 /// later use.
+///
+/// The IDs of the methods called on the cached classes are cached
+/// too, on their first call, sparing every later call the lookup of
+/// the method by its name and signature.  A method ID stays valid as
+/// long as its class is loaded, which the global reference ensures.
 #[derive(Clone)]
 pub struct ClassCache {
     /// HashMap mapping the class name (String) to Java object
-    map: HashMap<String, GlobalRef>,
+    map:     HashMap<String, GlobalRef>,
+    /// Method IDs looked up so far, shared by all clones of the
+    /// cache.
+    methods: Arc<CallMutex<MethodMap>>,
 }
 
 impl ClassCache {
     /// Returns an empty cache
     pub fn new() -> Self {
         Self {
-            map: HashMap::new(),
+            map:     HashMap::new(),
+            methods: Arc::new(CallMutex::new(HashMap::new(), "class_cache_methods")),
         }
     }
 
@@ -139,4 +215,104 @@ impl ClassCache {
             Err(AndroidError::ClassCacheLookup(class_name.to_string()).into())
         }
     }
+
+    /// Retrieve the ID of the method `name`, with signature `sig`, of
+    /// the class specified by `class_name`, looking it up on first
+    /// use.
+    ///
+    /// * If the class is not in the cache, return an error.
+    /// * If the method lookup fails, return an error.
+    fn get_method(
+        &self,
+        env: &JNIEnv,
+        class_name: &'static str,
+        name: &'static str,
+        sig: &'static str,
+    ) -> Result<CachedMethod> {
+        let key = (class_name, name, sig);
+        if let Some(method) = self.methods.lock()?.get(&key) {
+            return Ok(method.clone());
+        }
+
+        let not_found = || {
+            AndroidError::ClassCacheMethodNotFound(
+                class_name.to_string(),
+                name.to_string(),
+                sig.to_string(),
+            )
+        };
+        let class_object = self.get_class(class_name)?;
+        let id = match env.get_method_id(class_object, name, sig) {
+            Ok(v) => v.into_inner(),
+            Err(_) => return Err(not_found().into()),
+        };
+        let ret = if name == CONSTRUCTOR_NAME {
+            JavaType::Primitive(Primitive::Void)
+        } else {
+            match TypeSignature::from_str(sig) {
+                Ok(v) => v.ret,
+                Err(_) => return Err(not_found().into()),
+            }
+        };
+
+        let method = CachedMethod { id, ret };
+        self.methods.lock()?.insert(key, method.clone());
+        Ok(method)
+    }
+
+    /// Look up the ID of the method `name`, with signature `sig`, of
+    /// the class specified by `class_name` and store it in the cache.
+    ///
+    /// * If the class is not in the cache, return an error.
+    /// * If the method lookup fails, return an error.
+    pub fn add_method(
+        &self,
+        env: &JNIEnv,
+        class_name: &'static str,
+        name: &'static str,
+        sig: &'static str,
+    ) -> Result<()> {
+        let _ = self.get_method(env, class_name, name, sig)?;
+        Ok(())
+    }
+
+    /// Call the method `name`, with signature `sig`, on `object`, an
+    /// instance of the class specified by `class_name`, using the
+    /// cached method ID.
+    pub fn call_method<'a>(
+        &self,
+        env: &'a JNIEnv,
+        object: JObject,
+        class_name: &'static str,
+        name: &'static str,
+        sig: &'static str,
+        args: &[JValue],
+    ) -> Result<JValue<'a>> {
+        let method = self.get_method(env, class_name, name, sig)?;
+        match env.call_method_unchecked(object, JMethodID::from(method.id), method.ret, args) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(AndroidError::JniCallMethod(name.to_string(), sig.to_string(), e).into()),
+        }
+    }
+
+    /// Create an instance of the class specified by `class_name`,
+    /// using the cached ID of its constructor with signature `sig`.
+    pub fn new_object<'a>(
+        &self,
+        env: &'a JNIEnv,
+        class_name: &'static str,
+        sig: &'static str,
+        args: &[JValue],
+    ) -> Result<JObject<'a>> {
+        let class_object = self.get_class(class_name)?;
+        let constructor = self.get_method(env, class_name, CONSTRUCTOR_NAME, sig)?;
+        match env.new_object_unchecked(class_object, JMethodID::from(constructor.id), args) {
+            Ok(v) => Ok(v),
+            Err(_) => Err(AndroidError::JniCallConstructor(
+                class_name.to_string(),
+                sig.to_string(),
+            )
+            .into()),
+        }
+    }
 }
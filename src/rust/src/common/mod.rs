@@ -10,6 +10,10 @@
 use std::fmt;
 
 use rand;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::error::RingRtcError;
 
 /// Common Result type, using `failure::Error` for Error.
 pub type Result<T> = std::result::Result<T, failure::Error>;
@@ -41,6 +45,13 @@ impl CallId {
         Self::new(rand::random())
     }
 
+    /// Returns a CallId drawn from the cryptographically secure
+    /// random number generator of the operating system, so remote
+    /// peers cannot predict it.
+    pub fn secure_random() -> Self {
+        Self::new(OsRng.next_u64())
+    }
+
     pub fn format(self, device_id: DeviceId) -> String {
         format!("0x{:x}-{}", self.id, device_id)
     }
@@ -151,7 +162,8 @@ pub enum ApplicationEvent {
     /// The call ended because of a remote busy message.
     EndedRemoteBusy,
 
-    /// The call ended because of glare (received offer from same remote).
+    /// The call ended because of glare, the offer received from the
+    /// same remote won.
     EndedRemoteGlare,
 
     /// The call ended because it timed out during setup.
@@ -186,6 +198,109 @@ pub enum ApplicationEvent {
 
     /// Received an offer while already handling an active call.
     EndedReceivedOfferWhileActive,
+
+    /// The received offer contained none of the preferred codecs, so
+    /// a common codec was negotiated instead.
+    CodecFallback,
+
+    /// The remote peer did not answer the renegotiation offer sent
+    /// while reconnecting.
+    RenegotiationTimeout,
+
+    /// The audio device failed to initialize, for an unknown reason.
+    AudioDeviceFailure,
+
+    /// The audio device failed to initialize because microphone
+    /// access was denied.
+    AudioDevicePermissionDenied,
+
+    /// The audio device failed to initialize because it is in use.
+    AudioDeviceBusy,
+
+    /// The call ended because the audio device failed to initialize.
+    EndedAudioDeviceFailure,
+
+    /// The call ended because every remote device declined it.
+    EndedRemoteDeclined,
+
+    /// Group call only: A participant connected to the call.
+    GroupParticipantJoined,
+
+    /// Group call only: A participant disconnected from the call.
+    GroupParticipantLeft,
+
+    /// The remote peer muted its audio.
+    RemoteAudioMuted,
+
+    /// The remote peer unmuted its audio.
+    RemoteAudioUnmuted,
+
+    /// The received offer lost to the active call of the same remote
+    /// peer, i.e. glare.
+    EndedReceivedOfferWithGlare,
+
+    /// The call ended because it was accepted on another device.
+    EndedRemoteHangupAccepted,
+
+    /// The call ended because another device is busy.
+    EndedRemoteHangupBusy,
+
+    /// The call ended because the remote peer needs permission to
+    /// take calls from the local user.
+    EndedRemoteHangupNeedPermission,
+
+    /// The application refused to ring the incoming call.
+    EndedRingRefused,
+
+    /// The remote peer put the call on hold.
+    RemoteOnHold,
+
+    /// The remote peer resumed the call from hold.
+    RemoteResumed,
+
+    /// The application cancelled the call setup.
+    EndedSetupCancelled,
+
+    /// The remote peer declined to transfer the call.
+    TransferDeclined,
+
+    /// The call ended because the remote peer accepted its transfer.
+    EndedTransferred,
+
+    /// Outbound call only: The call ended because of a remote busy
+    /// message, and the remote peer will be called again.
+    EndedRemoteBusyRetry,
+
+    /// Outbound call only: Calling the busy remote peer again was
+    /// cancelled.
+    BusyRetryCancelled,
+
+    /// The credentials of the ICE servers are about to expire, and
+    /// should be replaced with `CallManager::update_ice_servers()`.
+    IceServersExpiring,
+
+    /// The remote peer started recording the call.
+    RemoteRecordingStarted,
+
+    /// The remote peer stopped recording the call.
+    RemoteRecordingStopped,
+
+    /// No media arrived from the remote peer for the media stall
+    /// timeout, although ICE is connected.
+    RemoteMediaStalled,
+
+    /// Media arrives from the remote peer again.
+    RemoteMediaResumed,
+
+    /// The remote peer started sharing its screen.
+    RemoteSharingScreen,
+
+    /// The remote peer stopped sharing its screen.
+    RemoteStoppedSharingScreen,
+
+    /// The call ended because the remote peer closed its media
+    /// without hanging up, e.g. because its client crashed.
+    EndedRemoteMediaClosed,
 }
 
 impl Clone for ApplicationEvent {
@@ -222,9 +337,18 @@ pub enum ConnectionState {
     /// ICE is reconnecting after an ICE disconnect event.
     IceReconnecting,
 
+    /// The connected call is restarting ICE, for example after a
+    /// network change, and waiting for the answer to the restart
+    /// offer.  Media keeps flowing over the current route meanwhile.
+    IceRestarting,
+
     /// The callee has accepted the call and the call is connected.
     CallConnected,
 
+    /// The connected call is on hold locally, with the outgoing
+    /// media paused.
+    OnHold,
+
     /// The call is in the process of shutting down.
     Terminating,
 
@@ -265,5 +389,135 @@ impl CallDirection {
     }
 }
 
+/// Why the audio device failed to initialize, as far as the platform
+/// can tell.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioDeviceError {
+    /// The platform did not report a reason.
+    Unknown = 0,
+    /// Microphone access was denied.
+    PermissionDenied,
+    /// The device is in use, e.g. by another call.
+    Busy,
+}
+
+impl fmt::Display for AudioDeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl AudioDeviceError {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(AudioDeviceError::Unknown),
+            1 => Ok(AudioDeviceError::PermissionDenied),
+            2 => Ok(AudioDeviceError::Busy),
+            _ => Err(RingRtcError::UnknownAudioDeviceError(value).into()),
+        }
+    }
+
+    /// Returns the event reporting this error to the application.
+    pub fn application_event(self) -> ApplicationEvent {
+        match self {
+            AudioDeviceError::Unknown => ApplicationEvent::AudioDeviceFailure,
+            AudioDeviceError::PermissionDenied => ApplicationEvent::AudioDevicePermissionDenied,
+            AudioDeviceError::Busy => ApplicationEvent::AudioDeviceBusy,
+        }
+    }
+}
+
+/// Why a call was hung up, sent with the hangup message so the
+/// remote side can tell a declined call from a missed one.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HangupType {
+    /// The call was hung up, or was never answered.
+    Normal = 0,
+    /// The call was accepted on another device.
+    Accepted,
+    /// The call was declined.
+    Declined,
+    /// Another device is busy.
+    Busy,
+    /// The user needs to grant permission before taking calls.
+    NeedPermission,
+}
+
+impl fmt::Display for HangupType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl HangupType {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(HangupType::Normal),
+            1 => Ok(HangupType::Accepted),
+            2 => Ok(HangupType::Declined),
+            3 => Ok(HangupType::Busy),
+            4 => Ok(HangupType::NeedPermission),
+            _ => Err(RingRtcError::UnknownHangupType(value).into()),
+        }
+    }
+
+    /// Returns the event reporting a hangup of this type, received
+    /// from the remote peer, to the application.
+    pub fn application_event(self) -> ApplicationEvent {
+        match self {
+            HangupType::Normal => ApplicationEvent::EndedRemoteHangup,
+            HangupType::Accepted => ApplicationEvent::EndedRemoteHangupAccepted,
+            HangupType::Declined => ApplicationEvent::EndedRemoteDeclined,
+            HangupType::Busy => ApplicationEvent::EndedRemoteHangupBusy,
+            HangupType::NeedPermission => ApplicationEvent::EndedRemoteHangupNeedPermission,
+        }
+    }
+}
+
+/// A step of the transfer of a connected call to another peer, sent
+/// over the DataChannel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransferMessage {
+    /// Asks the remote peer to call the target in place of the
+    /// sender.
+    Request(String),
+    /// The remote peer accepted the transfer.
+    Accepted,
+    /// The remote peer declined the transfer.
+    Declined,
+}
+
+impl fmt::Display for TransferMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A session description of a negotiation that adds or removes
+/// tracks of a connected call, sent over the DataChannel.
+#[derive(Clone, PartialEq, Eq)]
+pub enum NegotiationMessage {
+    Offer(String),
+    Answer(String),
+}
+
+/// Never shows the session description.
+impl fmt::Debug for NegotiationMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NegotiationMessage::Offer(sdp) => write!(f, "Offer, size: {}", sdp.len()),
+            NegotiationMessage::Answer(sdp) => write!(f, "Answer, size: {}", sdp.len()),
+        }
+    }
+}
+
+impl fmt::Display for NegotiationMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// The label of the WebRTC DataChannel.
 pub const DATA_CHANNEL_NAME: &str = "signaling";
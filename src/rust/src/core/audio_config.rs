@@ -0,0 +1,61 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Audio processing chosen by the application.
+//!
+//! WebRTC runs the captured audio through an audio processing module
+//! before encoding it.  By default every stage is enabled, which
+//! suits a phone held to the ear or on speaker, while e.g. a headset
+//! with its own echo canceller or a music performance may want some
+//! of them off.
+//!
+//! The default for all calls is set with
+//! `CallManager::set_default_audio_config()`, which platforms apply
+//! when creating the PeerConnection factory, and a call may override
+//! it with `CallConfig::audio_config` or
+//! `CallManager::set_audio_config()`.
+
+use std::fmt;
+
+/// The stages of the WebRTC audio processing module, matching the
+/// `cricket::AudioOptions` of the same names.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AudioConfig {
+    /// Cancel the echo of the received audio in the captured audio.
+    pub echo_cancellation: bool,
+    /// Suppress steady background noise.
+    pub noise_suppression: bool,
+    /// Adjust the gain of the captured audio to a steady level.
+    pub auto_gain_control: bool,
+    /// Remove low frequency hum and rumble.
+    pub high_pass_filter:  bool,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            echo_cancellation: true,
+            noise_suppression: true,
+            auto_gain_control: true,
+            high_pass_filter:  true,
+        }
+    }
+}
+
+impl fmt::Display for AudioConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "echo_cancellation: {}, noise_suppression: {}, auto_gain_control: {}, high_pass_filter: {}",
+            self.echo_cancellation,
+            self.noise_suppression,
+            self.auto_gain_control,
+            self.high_pass_filter
+        )
+    }
+}
@@ -0,0 +1,198 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! The client-to-mixer audio level RTP header extension, RFC 6464.
+//!
+//! Each audio packet carries the level of the audio it encodes, so
+//! the levels of the captured and received audio are known without
+//! decoding the audio, or polling the stats of the PeerConnection.
+
+use crate::webrtc::media::AudioDirection;
+
+/// The URI of the audio level RTP header extension.
+pub const AUDIO_LEVEL_EXTENSION: &str = "urn:ietf:params:rtp-hdrext:ssrc-audio-level";
+
+/// Largest ID of a one-byte RTP header extension.
+const MAX_EXTENSION_ID: u32 = 14;
+
+/// Quietest level the extension carries, in -dBov.
+const MIN_AUDIO_LEVEL_DBOV: u8 = 127;
+
+/// Prefix of the SDP attribute mapping an RTP header extension.
+const EXTMAP_ATTRIBUTE: &str = "a=extmap:";
+
+/// Returns the ID of an `a=extmap:` line, if it is one.
+fn extension_id(line: &str) -> Option<u32> {
+    let line = line.trim();
+    if !line.starts_with(EXTMAP_ATTRIBUTE) {
+        return None;
+    }
+    line[EXTMAP_ATTRIBUTE.len()..]
+        .split(['/', ' '])
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Returns true if an SDP carries the audio level RTP header
+/// extension.
+pub fn audio_level_negotiated(sdp: &str) -> bool {
+    sdp.lines().any(|line| {
+        line.starts_with(EXTMAP_ATTRIBUTE)
+            && line.split_whitespace().nth(1) == Some(AUDIO_LEVEL_EXTENSION)
+    })
+}
+
+/// Offer the audio level RTP header extension in the audio section
+/// of a local offer, with the lowest ID no other extension uses.
+///
+/// The SDP is returned unchanged if it already offers the extension,
+/// has no audio section, or no ID is left.
+pub fn add_audio_level_extension(sdp: &str) -> String {
+    if audio_level_negotiated(sdp) {
+        return sdp.to_owned();
+    }
+
+    // With BUNDLE the IDs are shared by all media sections.
+    let used: Vec<u32> = sdp.lines().filter_map(extension_id).collect();
+    let id = match (1..=MAX_EXTENSION_ID).find(|id| !used.contains(id)) {
+        Some(v) => v,
+        None => return sdp.to_owned(),
+    };
+
+    let mut lines: Vec<&str> = sdp.split("\r\n").collect();
+    let start = match lines.iter().position(|line| line.starts_with("m=audio")) {
+        Some(v) => v,
+        None => return sdp.to_owned(),
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.starts_with("m=") || line.is_empty())
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    let extmap = format!("a=extmap:{} {}", id, AUDIO_LEVEL_EXTENSION);
+    lines.insert(end, &extmap);
+    lines.join("\r\n")
+}
+
+/// Converts a level in -dBov, from 0 for the loudest to 127 for
+/// silence, to the 0.0 to 1.0 scale of the stats.
+pub fn level_from_dbov(dbov: u8) -> f64 {
+    10f64.powf(-f64::from(dbov.min(MIN_AUDIO_LEVEL_DBOV)) / 20.0)
+}
+
+/// Tracks the levels the audio level RTP header extension reports.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AudioLevels {
+    /// The audio level RTP header extension was negotiated.
+    negotiated: bool,
+    /// The last level of the captured audio, from 0.0 to 1.0.
+    captured:   Option<f64>,
+    /// The last level of the received audio, from 0.0 to 1.0.
+    received:   Option<f64>,
+}
+
+impl AudioLevels {
+    /// Record whether the extension was negotiated, forgetting the
+    /// levels reported so far.
+    pub fn set_negotiated(&mut self, negotiated: bool) {
+        *self = Self {
+            negotiated,
+            ..Default::default()
+        };
+    }
+
+    /// Record a level, in -dBov, reported for the captured or
+    /// received audio.
+    ///
+    /// Nothing is recorded when the extension was not negotiated.
+    pub fn update(&mut self, direction: AudioDirection, dbov: u8) {
+        if !self.negotiated {
+            return;
+        }
+
+        let level = Some(level_from_dbov(dbov));
+        match direction {
+            AudioDirection::Captured => self.captured = level,
+            AudioDirection::Received => self.received = level,
+        }
+    }
+
+    /// Returns the last levels of the captured and received audio,
+    /// once both were reported.
+    pub fn levels(&self) -> Option<(f64, f64)> {
+        match (self.captured, self.received) {
+            (Some(captured), Some(received)) => Some((captured, received)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFER: &str = "v=0\r\n\
+                         a=group:BUNDLE 0 1\r\n\
+                         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                         a=mid:0\r\n\
+                         a=extmap:1 urn:ietf:params:rtp-hdrext:toffset\r\n\
+                         a=rtpmap:111 opus/48000/2\r\n\
+                         m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                         a=mid:1\r\n\
+                         a=extmap:2/sendrecv urn:3gpp:video-orientation\r\n\
+                         a=rtpmap:96 VP8/90000\r\n";
+
+    #[test]
+    fn check_add_audio_level_extension() {
+        assert!(!audio_level_negotiated(OFFER));
+
+        let offer = add_audio_level_extension(OFFER);
+        assert!(audio_level_negotiated(&offer));
+        assert!(offer.contains(
+            "a=rtpmap:111 opus/48000/2\r\n\
+             a=extmap:3 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\n\
+             m=video"
+        ));
+
+        // Offered once.
+        assert_eq!(add_audio_level_extension(&offer), offer);
+
+        // The last section ends with the SDP.
+        let offer = add_audio_level_extension("m=audio 9 RTP/AVP 0\r\na=rtpmap:0 PCMU/8000\r\n");
+        assert_eq!(
+            offer,
+            "m=audio 9 RTP/AVP 0\r\na=rtpmap:0 PCMU/8000\r\n\
+             a=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\n"
+        );
+
+        // Without an audio section, nothing is offered.
+        assert_eq!(add_audio_level_extension("FAKE SDP"), "FAKE SDP");
+    }
+
+    #[test]
+    fn check_audio_levels() {
+        assert_eq!(level_from_dbov(0), 1.0);
+        assert!((level_from_dbov(20) - 0.1).abs() < 1e-9);
+        assert!(level_from_dbov(127) < 1e-6);
+        assert_eq!(level_from_dbov(255), level_from_dbov(127));
+
+        let mut levels = AudioLevels::default();
+        levels.update(AudioDirection::Captured, 0);
+        levels.update(AudioDirection::Received, 0);
+        assert_eq!(levels.levels(), None);
+
+        levels.set_negotiated(true);
+        levels.update(AudioDirection::Captured, 0);
+        assert_eq!(levels.levels(), None);
+        levels.update(AudioDirection::Received, 127);
+        assert_eq!(levels.levels(), Some((1.0, level_from_dbov(127))));
+
+        levels.set_negotiated(true);
+        assert_eq!(levels.levels(), None);
+    }
+}
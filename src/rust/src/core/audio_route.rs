@@ -0,0 +1,90 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Where the audio of a call is played.
+//!
+//! The application owns the audio devices and tells the call manager
+//! which output it routed the audio to.  The call manager in turn
+//! suggests a route when the call changes in a way that calls for
+//! another one, e.g. the speaker once the remote peer turns its
+//! video on, as the phone is then held away from the ear.
+
+use std::fmt;
+
+use crate::common::Result;
+use crate::error::RingRtcError;
+
+/// An audio output of the device.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AudioRoute {
+    /// The earpiece, for a phone held to the ear.
+    #[default]
+    Earpiece = 0,
+    /// The loudspeaker.
+    Speaker,
+    /// A Bluetooth headset.
+    Bluetooth,
+    /// A wired headset.
+    Wired,
+}
+
+impl fmt::Display for AudioRoute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl AudioRoute {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(AudioRoute::Earpiece),
+            1 => Ok(AudioRoute::Speaker),
+            2 => Ok(AudioRoute::Bluetooth),
+            3 => Ok(AudioRoute::Wired),
+            _ => Err(RingRtcError::UnknownAudioRoute(value).into()),
+        }
+    }
+
+    /// Returns the route to suggest when video starts, if the audio
+    /// is on this route, or `None` to keep it.
+    ///
+    /// Only the earpiece is left for the speaker, the audio of a
+    /// headset stays private.
+    pub fn for_video(self) -> Option<Self> {
+        match self {
+            AudioRoute::Earpiece => Some(AudioRoute::Speaker),
+            AudioRoute::Speaker | AudioRoute::Bluetooth | AudioRoute::Wired => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_from_i32() {
+        for route in &[
+            AudioRoute::Earpiece,
+            AudioRoute::Speaker,
+            AudioRoute::Bluetooth,
+            AudioRoute::Wired,
+        ] {
+            assert_eq!(AudioRoute::from_i32(*route as i32).unwrap(), *route);
+        }
+        assert!(AudioRoute::from_i32(4).is_err());
+    }
+
+    #[test]
+    fn check_for_video() {
+        assert_eq!(AudioRoute::Earpiece.for_video(), Some(AudioRoute::Speaker));
+        assert_eq!(AudioRoute::Speaker.for_video(), None);
+        assert_eq!(AudioRoute::Bluetooth.for_video(), None);
+        assert_eq!(AudioRoute::Wired.for_video(), None);
+    }
+}
@@ -0,0 +1,158 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Bandwidth used by a call.
+//!
+//! The mode caps the bitrate of all media sent by the call and, in
+//! `Low` mode, asks the remote peer to do the same through `b=AS`
+//! lines in the SDP: audio is limited to a low bitrate and no video
+//! is received, for clients on metered connections.
+
+use std::fmt;
+
+use crate::common::Result;
+use crate::error::RingRtcError;
+
+/// Bandwidth the received audio may use in `Low` mode, in kbps.
+const LOW_AUDIO_BANDWIDTH_KBPS: u32 = 32;
+
+/// How much bandwidth a call may use.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BandwidthMode {
+    /// Audio only, at a low bitrate.
+    Low = 0,
+    /// The usual bitrates for audio and video.
+    #[default]
+    Normal,
+    /// Higher video bitrates, for fast unmetered connections.
+    High,
+}
+
+impl fmt::Display for BandwidthMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl BandwidthMode {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(BandwidthMode::Low),
+            1 => Ok(BandwidthMode::Normal),
+            2 => Ok(BandwidthMode::High),
+            _ => Err(RingRtcError::UnknownBandwidthMode(value).into()),
+        }
+    }
+
+    /// Returns the maximum bitrate of all media sent, in bits per
+    /// second.
+    pub fn max_bitrate_bps(self) -> u32 {
+        match self {
+            BandwidthMode::Low => LOW_AUDIO_BANDWIDTH_KBPS * 1000,
+            BandwidthMode::Normal => 2_000_000,
+            BandwidthMode::High => 6_000_000,
+        }
+    }
+
+    /// Returns the bandwidth the remote peer may use for the media
+    /// `kind`, e.g. `audio` or `video`, in kbps, if limited.
+    fn media_bandwidth_kbps(self, kind: &str) -> Option<u32> {
+        match (self, kind) {
+            (BandwidthMode::Low, "audio") => Some(LOW_AUDIO_BANDWIDTH_KBPS),
+            (BandwidthMode::Low, _) => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of the local `sdp` with a `b=AS` line limiting
+    /// the bandwidth of every media section, replacing any existing
+    /// one.
+    ///
+    /// Returns `None` when the mode does not limit the bandwidth.
+    pub fn limit_sdp(self, sdp: &str) -> Option<String> {
+        self.media_bandwidth_kbps("audio")?;
+
+        let mut lines: Vec<String> = Vec::new();
+        // The b= line still to be added to the current media section.
+        let mut pending: Option<String> = None;
+        for line in sdp.split('\n') {
+            let content = line.trim_end_matches('\r');
+            if let Some(description) = content.strip_prefix("m=") {
+                if let Some(bandwidth) = pending.take() {
+                    lines.push(bandwidth);
+                }
+                let kind = description.split(' ').next().unwrap_or("");
+                pending = self
+                    .media_bandwidth_kbps(kind)
+                    .map(|kbps| format!("b=AS:{}{}", kbps, &line[content.len()..]));
+                lines.push(line.to_string());
+                continue;
+            }
+
+            // The b= line follows the optional i= and c= lines.
+            if pending.is_some() && !content.starts_with("i=") && !content.starts_with("c=") {
+                lines.push(pending.take().unwrap());
+            }
+            if !content.starts_with("b=AS:") {
+                lines.push(line.to_string());
+            }
+        }
+        if let Some(bandwidth) = pending {
+            lines.push(bandwidth);
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDP: &str = "v=0\r\n\
+                       m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                       c=IN IP4 0.0.0.0\r\n\
+                       b=AS:64\r\n\
+                       a=rtpmap:111 opus/48000/2\r\n\
+                       m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                       a=rtpmap:96 VP8/90000\r\n";
+
+    #[test]
+    fn check_limit_sdp() {
+        assert_eq!(BandwidthMode::Normal.limit_sdp(SDP), None);
+        assert_eq!(BandwidthMode::High.limit_sdp(SDP), None);
+
+        let limited = BandwidthMode::Low.limit_sdp(SDP).unwrap();
+        assert_eq!(
+            limited,
+            "v=0\r\n\
+             m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+             c=IN IP4 0.0.0.0\r\n\
+             b=AS:32\r\n\
+             a=rtpmap:111 opus/48000/2\r\n\
+             m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+             b=AS:0\r\n\
+             a=rtpmap:96 VP8/90000\r\n"
+        );
+
+        // A media section without attributes.
+        assert_eq!(
+            BandwidthMode::Low
+                .limit_sdp("v=0\nm=audio 9 RTP/AVP 0")
+                .unwrap(),
+            "v=0\nm=audio 9 RTP/AVP 0\nb=AS:32"
+        );
+    }
+
+    #[test]
+    fn check_max_bitrate() {
+        assert!(BandwidthMode::Low.max_bitrate_bps() < BandwidthMode::Normal.max_bitrate_bps());
+        assert!(BandwidthMode::Normal.max_bitrate_bps() < BandwidthMode::High.max_bitrate_bps());
+        assert_eq!(BandwidthMode::from_i32(0).unwrap(), BandwidthMode::Low);
+        assert!(BandwidthMode::from_i32(3).is_err());
+    }
+}
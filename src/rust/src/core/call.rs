@@ -7,16 +7,16 @@
 
 //! A peer-to-peer call connection interface.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::sync::{atomic::AtomicBool, atomic::Ordering, Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use futures::sync::mpsc::{Receiver, Sender};
-use futures::Future;
+use futures::{Future, Stream};
 use tokio::runtime;
-use tokio::timer::Delay;
+use tokio::timer::{Delay, Interval};
 
 use crate::common::{
     ApplicationEvent,
@@ -25,19 +25,125 @@ use crate::common::{
     CallState,
     ConnectionId,
     DeviceId,
+    HangupType,
     Result,
 };
 // use crate::core::call_connection_observer::ClientEvent;
+use crate::core::audio_config::AudioConfig;
+use crate::core::bandwidth_mode::BandwidthMode;
+use crate::core::call_config::CallConfig;
 use crate::core::call_fsm::{CallEvent, CallStateMachine};
 use crate::core::call_manager::CallManager;
 use crate::core::call_mutex::CallMutex;
+use crate::core::call_record::{self, CallRecord};
+use crate::core::cancellation::CancellationToken;
 use crate::core::connection::{Connection, ObserverEvent};
+use crate::core::ice_server::{refresh_period, IceServerConfig};
+use crate::core::offer_metadata::OfferMetadata;
 use crate::core::platform::Platform;
+use crate::core::trace::{TraceBuffer, TraceEvent, TraceKind};
 use crate::error::RingRtcError;
 use crate::webrtc::ice_candidate::IceCandidate;
 use crate::webrtc::media_stream::MediaStream;
+use crate::webrtc::peer_connection_observer::IceGatheringState;
 use crate::webrtc::sdp_observer::SessionDescriptionInterface;
 
+/// Period, in milliseconds, of the call duration ticks.
+const CALL_DURATION_TICK_PERIOD: u64 = 1000;
+
+/// Chooses the remote devices an outgoing call connects to when there
+/// are more devices than the connection limit.
+///
+/// Set with `CallManager::set_device_selection_policy()`.
+pub trait DeviceSelectionPolicy: Send + 'static {
+    /// Return up to `max` of `remote_devices` to connect to.
+    fn select(&self, remote_devices: &[DeviceId], max: usize) -> Vec<DeviceId>;
+}
+
+/// The default device selection policy, which connects to the first
+/// remote devices in the order given to `CallManager::proceed()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InOrderSelection;
+
+impl DeviceSelectionPolicy for InOrderSelection {
+    fn select(&self, remote_devices: &[DeviceId], max: usize) -> Vec<DeviceId> {
+        remote_devices.iter().take(max).cloned().collect()
+    }
+}
+
+/// A remote device of a forked outgoing call, as seen by a
+/// `ForkingPolicy`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForkCandidate {
+    /// The remote device.
+    pub remote_device: DeviceId,
+    /// Whether the remote device accepted the call.
+    pub accepted:      bool,
+    /// Round trip time of the connection to the remote device, if
+    /// known.
+    pub rtt:           Option<Duration>,
+}
+
+/// Chooses which remote device wins a forked outgoing call, staying
+/// connected while the other devices are hung up.
+///
+/// Consulted whenever a remote device accepts the call, and whenever
+/// one declines while an accepted device waits.  The candidates are
+/// the remote devices that have not declined, the accepted ones
+/// first, in the order they accepted.
+///
+/// Set with `CallManager::set_forking_policy()`.
+pub trait ForkingPolicy: Send + 'static {
+    /// Return the accepted remote device that wins the call, or
+    /// `None` to wait for another device to accept.
+    fn select_winner(&self, candidates: &[ForkCandidate]) -> Option<DeviceId>;
+}
+
+/// The default forking policy, under which the first remote device
+/// to accept wins.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirstToConnect;
+
+impl ForkingPolicy for FirstToConnect {
+    fn select_winner(&self, candidates: &[ForkCandidate]) -> Option<DeviceId> {
+        candidates
+            .iter()
+            .find(|c| c.accepted)
+            .map(|c| c.remote_device)
+    }
+}
+
+/// A forking policy preferring one remote device, which wins if it
+/// accepts.  Other accepted devices wait until it declines, or win
+/// right away if it is not a candidate.
+#[derive(Clone, Copy, Debug)]
+pub struct PreferDevice(pub DeviceId);
+
+impl ForkingPolicy for PreferDevice {
+    fn select_winner(&self, candidates: &[ForkCandidate]) -> Option<DeviceId> {
+        match candidates.iter().find(|c| c.remote_device == self.0) {
+            Some(preferred) if preferred.accepted => Some(preferred.remote_device),
+            Some(_) => None,
+            None => FirstToConnect.select_winner(candidates),
+        }
+    }
+}
+
+/// A forking policy under which the accepted remote device with the
+/// lowest round trip time wins, the first to accept among equals.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LowestRtt;
+
+impl ForkingPolicy for LowestRtt {
+    fn select_winner(&self, candidates: &[ForkCandidate]) -> Option<DeviceId> {
+        candidates
+            .iter()
+            .filter(|c| c.accepted)
+            .min_by_key(|c| c.rtt.unwrap_or(Duration::from_secs(u64::MAX)))
+            .map(|c| c.remote_device)
+    }
+}
+
 /// Encapsulates the FSM and runtime upon which a Call runs.
 struct FsmContext {
     /// Runtime upon which the CallStateMachine runs.
@@ -134,6 +240,45 @@ where
     terminate_condvar: Arc<(Mutex<bool>, Condvar)>,
     /// Whether or not an offer has been sent via messaging for this call.
     did_send_offer:    Arc<AtomicBool>,
+    /// Whether the application was told the remote peer is ringing.
+    /// Outgoing calls only.
+    remote_ringing:    Arc<AtomicBool>,
+    /// Whether the calling service of the system, e.g. Telecom on
+    /// Android, manages the call.
+    system_managed:    Arc<AtomicBool>,
+    /// Whether the outgoing audio is enabled, for all connections.
+    audio_enabled:     Arc<AtomicBool>,
+    /// How much bandwidth the call may use, for all connections.
+    bandwidth_mode:    Arc<CallMutex<BandwidthMode>>,
+    /// The audio processing of the call, for all connections, if not
+    /// the default of the CallManager.
+    audio_config:      Arc<CallMutex<Option<AudioConfig>>>,
+    /// Bounded history of call activity, for post-mortem debugging.
+    trace:             Arc<CallMutex<TraceBuffer>>,
+    /// When the media was first connected.
+    connected_at:      Arc<CallMutex<Option<Instant>>>,
+    /// The remote devices that declined the call.
+    declined_devices:  Arc<CallMutex<HashSet<DeviceId>>>,
+    /// The remote devices left out by the connection limit.
+    deferred_devices:  Arc<CallMutex<Vec<DeviceId>>>,
+    /// The remote devices that accepted the call, in order.
+    accepted_devices:  Arc<CallMutex<Vec<DeviceId>>>,
+    /// The timeouts of the call, if any.
+    config:            Option<CallConfig>,
+    /// The type of hangup sent to the remote peer.
+    hangup_type:       Arc<CallMutex<HangupType>>,
+    /// The metadata of the received offer.  Incoming calls only.
+    offer_metadata:    Arc<CallMutex<Option<OfferMetadata>>>,
+    /// The metadata to attach to the answer.  Incoming calls only.
+    answer_metadata:   Arc<CallMutex<Option<Vec<u8>>>>,
+    /// Cancelled when the application aborts the call setup.
+    setup_token:       CancellationToken,
+    /// The record of the call, filled in as the call ends.
+    record:            Arc<CallMutex<CallRecord>>,
+    /// The ICE servers set by the application, for all connections.
+    ice_servers:       Arc<CallMutex<Vec<IceServerConfig>>>,
+    /// When the credentials of the ICE servers should be refreshed.
+    ice_refresh_at:    Arc<CallMutex<Option<Instant>>>,
 }
 
 impl<T> fmt::Display for Call<T>
@@ -177,7 +322,10 @@ where
             // release the the remote object.
             if let Ok(call_manager) = self.call_manager() {
                 if let Ok(remote_peer) = self.remote_peer() {
-                    let _ = call_manager.notify_call_concluded(&*remote_peer);
+                    if let Ok(record) = self.record.lock() {
+                        let _ = call_manager.call_record(&*remote_peer, &record);
+                    }
+                    let _ = call_manager.notify_call_concluded(&*remote_peer, self.call_id);
                 }
             }
         } else {
@@ -209,6 +357,24 @@ where
             connection_map:    Arc::clone(&self.connection_map),
             terminate_condvar: Arc::clone(&self.terminate_condvar),
             did_send_offer:    Arc::clone(&self.did_send_offer),
+            remote_ringing:    Arc::clone(&self.remote_ringing),
+            system_managed:    Arc::clone(&self.system_managed),
+            audio_enabled:     Arc::clone(&self.audio_enabled),
+            bandwidth_mode:    Arc::clone(&self.bandwidth_mode),
+            audio_config:      Arc::clone(&self.audio_config),
+            trace:             Arc::clone(&self.trace),
+            connected_at:      Arc::clone(&self.connected_at),
+            declined_devices:  Arc::clone(&self.declined_devices),
+            deferred_devices:  Arc::clone(&self.deferred_devices),
+            accepted_devices:  Arc::clone(&self.accepted_devices),
+            config:            self.config,
+            hangup_type:       Arc::clone(&self.hangup_type),
+            offer_metadata:    Arc::clone(&self.offer_metadata),
+            answer_metadata:   Arc::clone(&self.answer_metadata),
+            setup_token:       self.setup_token.clone(),
+            record:            Arc::clone(&self.record),
+            ice_servers:       Arc::clone(&self.ice_servers),
+            ice_refresh_at:    Arc::clone(&self.ice_refresh_at),
         }
     }
 }
@@ -218,18 +384,22 @@ where
     T: Platform,
 {
     /// Create a new Call.
+    ///
+    /// A call without a `config` never times out.
     #[allow(clippy::mutex_atomic)]
     pub fn new(
         app_remote_peer: <T as Platform>::AppRemotePeer,
         call_id: CallId,
         direction: CallDirection,
-        time_out_period: u64,
+        config: Option<CallConfig>,
         call_manager: CallManager<T>,
     ) -> Result<Self> {
         info!("new(): call_id: {}", call_id);
 
+        let tracing = call_manager.tracing()?;
+
         // create a FSM runtime for this connection
-        let mut fsm_context = FsmContext::new(time_out_period > 0)?;
+        let mut fsm_context = FsmContext::new(config.is_some())?;
         let (event_pump, receiver) = futures::sync::mpsc::channel(256);
         let call_fsm = CallStateMachine::new(receiver)?
             .map_err(|e| info!("call state machine returned error: {}", e));
@@ -249,36 +419,97 @@ where
             connection_map: Arc::new(CallMutex::new(HashMap::new(), "connection_map")),
             terminate_condvar: Arc::new((Mutex::new(false), Condvar::new())),
             did_send_offer: Arc::new(AtomicBool::new(false)),
+            remote_ringing: Arc::new(AtomicBool::new(false)),
+            system_managed: Arc::new(AtomicBool::new(false)),
+            audio_enabled: Arc::new(AtomicBool::new(
+                config.is_none_or(|c| !c.media_constraints.start_audio_muted),
+            )),
+            bandwidth_mode: Arc::new(CallMutex::new(
+                config.map(|c| c.bandwidth_mode).unwrap_or_default(),
+                "bandwidth_mode",
+            )),
+            audio_config: Arc::new(CallMutex::new(
+                config.and_then(|c| c.audio_config),
+                "audio_config",
+            )),
+            trace: Arc::new(CallMutex::new(
+                TraceBuffer::new(call_id, None, tracing),
+                "trace",
+            )),
+            connected_at: Arc::new(CallMutex::new(None, "connected_at")),
+            declined_devices: Arc::new(CallMutex::new(HashSet::new(), "declined_devices")),
+            deferred_devices: Arc::new(CallMutex::new(Vec::new(), "deferred_devices")),
+            accepted_devices: Arc::new(CallMutex::new(Vec::new(), "accepted_devices")),
+            config,
+            hangup_type: Arc::new(CallMutex::new(HangupType::Normal, "hangup_type")),
+            offer_metadata: Arc::new(CallMutex::new(None, "offer_metadata")),
+            answer_metadata: Arc::new(CallMutex::new(None, "answer_metadata")),
+            setup_token: CancellationToken::new(),
+            record: Arc::new(CallMutex::new(
+                CallRecord::new(call_id, direction),
+                "record",
+            )),
+            ice_servers: Arc::new(CallMutex::new(Vec::new(), "ice_servers")),
+            ice_refresh_at: Arc::new(CallMutex::new(None, "ice_refresh_at")),
         };
 
-        if time_out_period > 0 {
-            // Create a two minute call setup timeout thread
-            let mut call_clone = call.clone();
-            let when = Instant::now() + Duration::from_secs(time_out_period);
-            let call_timeout_future = Delay::new(when)
-                .map_err(|e| error!("Call timeout Delay failed: {:?}", e))
-                .and_then(move |_| {
-                    call_clone
-                        .inject_call_timeout()
-                        .map_err(|e| error!("Inject call timeout failed: {:?}", e))
-                });
-
-            debug!("new(): spawning call timeout task");
-            if let Ok(mut fsm_context) = call.fsm_context.lock() {
-                if let Some(timeout_runtime) = &mut fsm_context.timeout_runtime {
-                    timeout_runtime.spawn(call_timeout_future);
-                }
-            }
+        if let Some(config) = config {
+            info!("new(): {}", config);
+            call.start_timeout(config.connect_timeout, CallEvent::CallTimeout)?;
+            call.start_timeout(config.ice_gathering_timeout, CallEvent::IceGatheringTimeout)?;
         }
 
         Ok(call)
     }
 
+    /// Inject `event` once `timeout` passes.
+    fn start_timeout(&self, timeout: Duration, event: CallEvent) -> Result<()> {
+        let mut call = self.clone();
+        let when = Instant::now() + timeout;
+        let timeout_future = Delay::new(when)
+            .map_err(|e| error!("Call timeout Delay failed: {:?}", e))
+            .and_then(move |_| {
+                call.inject_event(event)
+                    .map_err(|e| error!("Inject call timeout failed: {:?}", e))
+            });
+
+        debug!("start_timeout(): spawning call timeout task");
+        let mut fsm_context = self.fsm_context.lock()?;
+        if let Some(timeout_runtime) = &mut fsm_context.timeout_runtime {
+            timeout_runtime.spawn(timeout_future);
+        }
+        Ok(())
+    }
+
+    /// Return the timeouts of the call, if any.
+    pub fn config(&self) -> Option<CallConfig> {
+        self.config
+    }
+
+    /// Start the ring timeout, once the call rings.
+    pub fn start_ring_timeout(&self) -> Result<()> {
+        match self.config {
+            Some(config) => self.start_timeout(config.ring_timeout, CallEvent::RingTimeout),
+            None => Ok(()),
+        }
+    }
+
     /// Return the Call identifier.
     pub fn call_id(&self) -> CallId {
         self.call_id
     }
 
+    /// Abort the setup of the call.  The FSM drops the events of a
+    /// call whose setup was cancelled until it is concluded.
+    pub fn cancel_setup(&self) {
+        self.setup_token.cancel();
+    }
+
+    /// Returns `true` if the application aborted the call setup.
+    pub fn setup_cancelled(&self) -> bool {
+        self.setup_token.is_cancelled()
+    }
+
     /// Return the Call direction.
     pub fn direction(&self) -> CallDirection {
         self.direction
@@ -293,10 +524,51 @@ where
     /// Update the current Call state.
     pub fn set_state(&self, new_state: CallState) -> Result<()> {
         let mut state = self.state.lock()?;
+        if *state != new_state {
+            self.record_trace(
+                TraceKind::StateChange,
+                format!("{} -> {}", *state, new_state),
+            );
+        }
         *state = new_state;
         Ok(())
     }
 
+    /// Record an event in the call trace.
+    ///
+    /// Tracing is best effort, so failures are ignored.
+    pub fn record_trace(&self, kind: TraceKind, description: String) {
+        if let Ok(mut trace) = self.trace.lock() {
+            trace.record(kind, description);
+        }
+    }
+
+    /// Return a copy of the call level trace events, oldest first.
+    ///
+    /// The trace outlives the call's FSM, so this is safe to call
+    /// after the call has concluded.
+    pub fn export_trace(&self) -> Result<Vec<TraceEvent>> {
+        let trace = self.trace.lock()?;
+        Ok(trace.events())
+    }
+
+    /// Return the call level trace merged with the traces of all the
+    /// connections of the call, ordered by timestamp.
+    pub fn export_full_trace(&self) -> Result<Vec<TraceEvent>> {
+        let mut events = self.export_trace()?;
+        let connection_map = self.connection_map.lock()?;
+        for connection in connection_map.values() {
+            events.append(&mut connection.export_connection_trace()?);
+        }
+        events.sort_by_key(|e| e.timestamp_ms);
+        Ok(events)
+    }
+
+    /// Return whether the call and its connections record a trace.
+    pub fn tracing(&self) -> Result<bool> {
+        Ok(self.trace.lock()?.enabled())
+    }
+
     /// Set the active device ID this call is connected to.
     pub fn set_active_device_id(&self, remote_device: DeviceId) -> Result<()> {
         let mut active_device_id = self.active_device_id.lock()?;
@@ -404,10 +676,16 @@ where
     ///
     /// This is a pass through to the CallManager.
     pub fn handle_start_call(&self) -> Result<()> {
-        let call_manager = self.call_manager()?;
-        let remote_peer = self.remote_peer()?;
+        let mut call_manager = self.call_manager()?;
+        {
+            let remote_peer = self.remote_peer()?;
+            call_manager.start_call(&*remote_peer, self.call_id, self.direction)?;
+        }
 
-        call_manager.start_call(&*remote_peer, self.call_id, self.direction)
+        if self.direction == CallDirection::OutGoing {
+            call_manager.send_pre_offer(self.clone())?;
+        }
+        Ok(())
     }
 
     /// Notify application of an event.
@@ -417,7 +695,136 @@ where
         let call_manager = self.call_manager()?;
         let remote_peer = self.remote_peer()?;
 
-        call_manager.notify_application(&*remote_peer, event)
+        call_manager.notify_application(&*remote_peer, self.call_id, event)
+    }
+
+    /// Notify application of a change in the remote video rotation.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn remote_video_orientation(&self, remote_device: DeviceId, degrees: u16) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+        let connection_id = ConnectionId::new(self.call_id, remote_device);
+
+        call_manager.remote_video_orientation(&*remote_peer, connection_id, degrees)
+    }
+
+    /// Notify application of an application message received from
+    /// the remote peer.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn app_message_received(&self, remote_device: DeviceId, payload: &[u8]) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+        let connection_id = ConnectionId::new(self.call_id, remote_device);
+
+        call_manager.app_message_received(&*remote_peer, connection_id, payload)
+    }
+
+    /// Notify application of the safety numbers of the active
+    /// connection, if both sides sent a DTLS fingerprint.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn notify_safety_numbers(&self) -> Result<()> {
+        let connection = self.active_connection()?;
+        let safety_numbers = match connection.get_safety_numbers()? {
+            Some(safety_numbers) => safety_numbers,
+            None => return Ok(()),
+        };
+
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+
+        call_manager.safety_numbers(&*remote_peer, connection.id(), &safety_numbers)
+    }
+
+    /// Ask the application whether to accept the transfer requested
+    /// by the remote peer, and answer the remote peer.
+    pub fn transfer_requested(&self) -> Result<()> {
+        let mut connection = self.active_connection()?;
+        let target = match connection.remote_transfer_target()? {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+        let accepted = call_manager.should_transfer(&*remote_peer, self.call_id, &target)?;
+        connection.inject_transfer_response(accepted)
+    }
+
+    /// Notify application of a DTMF tone received on the active
+    /// connection.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn received_dtmf(&self, remote_device: DeviceId, tone: char) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+        let connection_id = ConnectionId::new(self.call_id, remote_device);
+
+        call_manager.received_dtmf(&*remote_peer, connection_id, tone)
+    }
+
+    /// Notify application of a change of the ICE route of the active
+    /// connection between direct and relayed.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn ice_route_changed(&self, remote_device: DeviceId) -> Result<()> {
+        let route = match self.active_connection()?.route_monitor()?.reported() {
+            Some(route) => route,
+            None => return Ok(()),
+        };
+
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+        let connection_id = ConnectionId::new(self.call_id, remote_device);
+
+        call_manager.ice_route_changed(&*remote_peer, connection_id, &route)
+    }
+
+    /// Notify application of the network the ICE route of the active
+    /// connection runs over, if it changed since last notified.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn network_route_changed(&self) -> Result<()> {
+        let connection = self.active_connection()?;
+        let network_route = match connection.route_monitor()?.network_route_changed() {
+            Some(network_route) => network_route,
+            None => return Ok(()),
+        };
+
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+
+        call_manager.network_route_changed(&*remote_peer, self.call_id, network_route)
+    }
+
+    /// Notify application of the ICE candidate gathering state of a
+    /// connection.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn ice_gathering_state_changed(
+        &self,
+        remote_device: DeviceId,
+        state: IceGatheringState,
+    ) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+        let connection_id = ConnectionId::new(self.call_id, remote_device);
+
+        call_manager.ice_gathering_state_changed(&*remote_peer, connection_id, state)
+    }
+
+    /// Notify application that the DataChannel send buffer of the
+    /// active connection drained after a refused send.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn data_channel_buffered_amount_low(&self, remote_device: DeviceId) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+        let connection_id = ConnectionId::new(self.call_id, remote_device);
+
+        call_manager.data_channel_buffered_amount_low(&*remote_peer, connection_id)
     }
 
     /// Notify call manager of an internal error.
@@ -451,6 +858,11 @@ where
                 // We have handed at least one Offer message to the platform...
                 self.did_send_offer.store(true, Ordering::Release);
 
+                self.record_trace(
+                    TraceKind::Signaling,
+                    format!("TX offer, device: {}", connection.remote_device()),
+                );
+
                 call_manager.send_offer(self.clone(), connection, offer)
             }
         }
@@ -476,6 +888,10 @@ where
             _ => {
                 let mut call_manager = self.call_manager()?;
 
+                self.record_trace(
+                    TraceKind::Signaling,
+                    format!("TX answer, device: {}", connection.remote_device()),
+                );
                 call_manager.send_answer(self.clone(), connection, answer)
             }
         }
@@ -497,6 +913,10 @@ where
             _ => {
                 let mut call_manager = self.call_manager()?;
 
+                self.record_trace(
+                    TraceKind::Signaling,
+                    format!("TX ice candidates, device: {}", connection.remote_device()),
+                );
                 call_manager.send_ice_candidates(self.clone(), connection)
             }
         }
@@ -517,12 +937,298 @@ where
 
     /// Associate a MediaStream with a Connection.
     ///
+    /// The first time the media is connected the call duration
+    /// starts counting, and the duration ticks, stats reports, audio
+    /// levels and bandwidth estimates start if enabled.
+    ///
     /// This is a pass through to the CallManager.
     pub fn connect_media(&self, app_media_stream: &<T as Platform>::AppMediaStream) -> Result<()> {
+        let (duration_ticks, stats_interval, audio_levels_interval, bandwidth_interval) = {
+            let call_manager = self.call_manager()?;
+            let remote_peer = self.remote_peer()?;
+
+            call_manager.connect_media(&*remote_peer, &self.call_context()?, app_media_stream)?;
+            (
+                call_manager.call_duration_ticks()?,
+                call_manager.stats_interval()?,
+                call_manager.audio_levels_interval()?,
+                call_manager.bandwidth_estimate_interval()?,
+            )
+        };
+
+        let first_connect = {
+            let mut connected_at = self.connected_at.lock()?;
+            if connected_at.is_none() {
+                *connected_at = Some(Instant::now());
+                true
+            } else {
+                false
+            }
+        };
+
+        if first_connect && duration_ticks {
+            self.start_duration_ticker()?;
+        }
+        if first_connect {
+            if let Some(interval) = stats_interval {
+                self.start_stats_ticker(interval)?;
+            }
+            if let Some(interval) = audio_levels_interval {
+                self.start_audio_levels_ticker(interval)?;
+            }
+            if let Some(interval) = bandwidth_interval {
+                self.start_bandwidth_estimate_ticker(interval)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return how long the call has been connected, counted from when
+    /// the media was first connected.
+    ///
+    /// Reconnections do not restart the count.  The duration is zero
+    /// until the media is connected.
+    pub fn duration(&self) -> Result<Duration> {
+        match *self.connected_at.lock()? {
+            Some(connected_at) => Ok(connected_at.elapsed()),
+            None => Ok(Duration::default()),
+        }
+    }
+
+    /// Inject a `CallDurationTick` every tick period for as long as
+    /// the call is connected or reconnecting.
+    fn start_duration_ticker(&self) -> Result<()> {
+        let call = self.clone();
+        let mut tick_call = self.clone();
+        let period = Duration::from_millis(CALL_DURATION_TICK_PERIOD);
+        let ticker = Interval::new(Instant::now() + period, period)
+            .map_err(|e| error!("Call duration Interval failed: {:?}", e))
+            .take_while(move |_| match call.state() {
+                Ok(CallState::Connected) | Ok(CallState::Reconnecting) => Ok(true),
+                _ => Ok(false),
+            })
+            .for_each(move |_| {
+                tick_call
+                    .inject_call_duration_tick()
+                    .map_err(|e| error!("Inject call duration tick failed: {:?}", e))
+            });
+
+        debug!("start_duration_ticker(): spawning call duration task");
+        let mut fsm_context = self.fsm_context.lock()?;
+        match &mut fsm_context.timeout_runtime {
+            Some(timeout_runtime) => {
+                let _ = timeout_runtime.spawn(ticker);
+            }
+            None => warn!("start_duration_ticker(): no timeout runtime, not ticking"),
+        }
+        Ok(())
+    }
+
+    /// Inject a `StatsTick` every `interval` for as long as the call
+    /// is connected or reconnecting.
+    fn start_stats_ticker(&self, interval: Duration) -> Result<()> {
+        let call = self.clone();
+        let mut tick_call = self.clone();
+        let ticker = Interval::new(Instant::now() + interval, interval)
+            .map_err(|e| error!("Stats Interval failed: {:?}", e))
+            .take_while(move |_| match call.state() {
+                Ok(CallState::Connected) | Ok(CallState::Reconnecting) => Ok(true),
+                _ => Ok(false),
+            })
+            .for_each(move |_| {
+                tick_call
+                    .inject_stats_tick()
+                    .map_err(|e| error!("Inject stats tick failed: {:?}", e))
+            });
+
+        debug!("start_stats_ticker(): spawning stats task");
+        let mut fsm_context = self.fsm_context.lock()?;
+        match &mut fsm_context.timeout_runtime {
+            Some(timeout_runtime) => {
+                let _ = timeout_runtime.spawn(ticker);
+            }
+            None => warn!("start_stats_ticker(): no timeout runtime, not reporting stats"),
+        }
+        Ok(())
+    }
+
+    /// Inject an `AudioLevelsTick` every `interval` for as long as the
+    /// call is connected or reconnecting.
+    fn start_audio_levels_ticker(&self, interval: Duration) -> Result<()> {
+        let call = self.clone();
+        let mut tick_call = self.clone();
+        let ticker = Interval::new(Instant::now() + interval, interval)
+            .map_err(|e| error!("Audio levels Interval failed: {:?}", e))
+            .take_while(move |_| match call.state() {
+                Ok(CallState::Connected) | Ok(CallState::Reconnecting) => Ok(true),
+                _ => Ok(false),
+            })
+            .for_each(move |_| {
+                tick_call
+                    .inject_audio_levels_tick()
+                    .map_err(|e| error!("Inject audio levels tick failed: {:?}", e))
+            });
+
+        debug!("start_audio_levels_ticker(): spawning audio levels task");
+        let mut fsm_context = self.fsm_context.lock()?;
+        match &mut fsm_context.timeout_runtime {
+            Some(timeout_runtime) => {
+                let _ = timeout_runtime.spawn(ticker);
+            }
+            None => warn!("start_audio_levels_ticker(): no timeout runtime, not sampling"),
+        }
+        Ok(())
+    }
+
+    /// Inject a `BandwidthEstimateTick` every `interval` for as long
+    /// as the call is connected or reconnecting.
+    fn start_bandwidth_estimate_ticker(&self, interval: Duration) -> Result<()> {
+        let call = self.clone();
+        let mut tick_call = self.clone();
+        let ticker = Interval::new(Instant::now() + interval, interval)
+            .map_err(|e| error!("Bandwidth estimate Interval failed: {:?}", e))
+            .take_while(move |_| match call.state() {
+                Ok(CallState::Connected) | Ok(CallState::Reconnecting) => Ok(true),
+                _ => Ok(false),
+            })
+            .for_each(move |_| {
+                tick_call
+                    .inject_bandwidth_estimate_tick()
+                    .map_err(|e| error!("Inject bandwidth estimate tick failed: {:?}", e))
+            });
+
+        debug!("start_bandwidth_estimate_ticker(): spawning bandwidth estimate task");
+        let mut fsm_context = self.fsm_context.lock()?;
+        match &mut fsm_context.timeout_runtime {
+            Some(timeout_runtime) => {
+                let _ = timeout_runtime.spawn(ticker);
+            }
+            None => warn!("start_bandwidth_estimate_ticker(): no timeout runtime, not sampling"),
+        }
+        Ok(())
+    }
+
+    /// Mark the remote peer as ringing, returning `true` the first
+    /// time.
+    ///
+    /// The caller learns the remote peer is ringing from either the
+    /// ringing acknowledgment or ICE connecting, whichever comes
+    /// first, and only tells the application once.
+    pub fn set_remote_ringing(&self) -> bool {
+        !self.remote_ringing.swap(true, Ordering::AcqRel)
+    }
+
+    /// Mark the call as managed by the calling service of the
+    /// system.
+    pub fn set_system_managed(&self) {
+        self.system_managed.store(true, Ordering::Release);
+    }
+
+    /// Return whether the calling service of the system manages the
+    /// call.
+    pub fn system_managed(&self) -> bool {
+        self.system_managed.load(Ordering::Acquire)
+    }
+
+    /// Acknowledge to the caller that this incoming call is ringing.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn send_ringing(&self) -> Result<()> {
+        let remote_device = self.active_device_id()?;
+        let mut call_manager = self.call_manager()?;
+
+        self.record_trace(
+            TraceKind::Signaling,
+            format!("TX ringing, device: {}", remote_device),
+        );
+        call_manager.send_ringing(self.clone(), ConnectionId::new(self.call_id, remote_device))
+    }
+
+    /// Notify application of the stats of the active connection,
+    /// covering the interval since the previous report.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn report_stats(&self) -> Result<()> {
+        let stats = self.active_connection()?.call_stats()?;
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+
+        call_manager.stats_report(&*remote_peer, self.call_id, &stats)
+    }
+
+    /// Suggest the application a route for the audio of the call, now
+    /// that the remote peer sends video.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn suggest_audio_route_for_video(&self) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        call_manager.suggest_audio_route_for_video(&self.call_context()?)
+    }
+
+    /// Turn the camera of the call on or off.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn set_camera_enabled(&self, enabled: bool) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        call_manager.camera_enabled(&self.call_context()?, enabled)
+    }
+
+    /// Switch to the next camera of the call.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn switch_camera(&self) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        call_manager.camera_switched(&self.call_context()?)
+    }
+
+    /// Notify application that the system now manages the call.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn notify_added_to_system(&self) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        call_manager.added_to_system(&self.call_context()?)
+    }
+
+    /// Ask the system to show the call as on hold or active.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn request_system_hold(&self, on_hold: bool) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        call_manager.system_hold_requested(&self.call_context()?, on_hold)
+    }
+
+    /// Notify application of the captured and received audio levels
+    /// of the active connection.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn report_audio_levels(&self) -> Result<()> {
+        let (captured, received) = self.active_connection()?.audio_levels()?;
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+
+        call_manager.audio_levels(&*remote_peer, self.call_id, captured, received)
+    }
+
+    /// Notify application of the bandwidth estimate of the active
+    /// connection.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn report_bandwidth_estimate(&self) -> Result<()> {
+        let (send_bps, receive_bps) = self.active_connection()?.bandwidth_estimate()?;
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+
+        call_manager.bandwidth_estimate(&*remote_peer, self.call_id, send_bps, receive_bps)
+    }
+
+    /// Notify application of the duration of the connected call.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn call_duration_tick(&self) -> Result<()> {
         let call_manager = self.call_manager()?;
         let remote_peer = self.remote_peer()?;
 
-        call_manager.connect_media(&*remote_peer, &self.call_context()?, app_media_stream)
+        call_manager.call_duration_tick(&*remote_peer, self.call_id, self.duration()?)
     }
 
     /// Proceed with the current call.
@@ -552,7 +1258,10 @@ where
                     );
 
                     let mut connection =
-                        call_manager.create_connection(self, pending_call.remote_device)?;
+                        match self.create_connection(&call_manager, pending_call.remote_device)? {
+                            Some(v) => v,
+                            None => return Ok(()),
+                        };
                     connection.inject_handle_offer(pending_call.offer)?;
 
                     if !pending_call.ice_candidates.is_empty() {
@@ -563,9 +1272,6 @@ where
                         connection.inject_received_ice_candidates(pending_call.ice_candidates)?;
                     }
 
-                    let mut connection_map = self.connection_map.lock()?;
-                    connection_map.insert(pending_call.remote_device, connection);
-
                     // For incoming calls we only have 1 connection and it is the active connection.
                     self.set_active_device_id(pending_call.remote_device)?;
                 } else {
@@ -577,27 +1283,222 @@ where
                 }
             }
             CallDirection::OutGoing => {
+                let (remote_devices, deferred_devices) =
+                    call_manager.select_devices(remote_devices)?;
+                if !deferred_devices.is_empty() {
+                    info!(
+                        "proceed(): outgoing: deferring remote_devices: {:?}",
+                        deferred_devices
+                    );
+                }
+                *self.deferred_devices.lock()? = deferred_devices;
+
                 for remote_device in remote_devices {
                     info!("proceed(): outgoing: remote_device: {}", remote_device);
 
-                    let mut connection = call_manager.create_connection(self, remote_device)?;
+                    let mut connection =
+                        match self.create_connection(&call_manager, remote_device)? {
+                            Some(v) => v,
+                            None => return Ok(()),
+                        };
                     connection.inject_send_offer()?;
-
-                    let mut connection_map = self.connection_map.lock()?;
-                    connection_map.insert(remote_device, connection);
                 }
             }
         }
         Ok(())
     }
 
+    /// Create a Connection to a remote device and add it to the call.
+    ///
+    /// Returns `None`, without creating the Connection, once the call
+    /// has concluded.  A Connection created while the call concluded
+    /// is closed right away, as `close_connections()` may already have
+    /// run and would otherwise leak it.
+    fn create_connection(
+        &self,
+        call_manager: &CallManager<T>,
+        remote_device: DeviceId,
+    ) -> Result<Option<Connection<T>>> {
+        if !call_manager.call_is_active(self.call_id)? {
+            info!(
+                "create_connection(): skipping concluded call, id: {}",
+                self.call_id().format(remote_device)
+            );
+            return Ok(None);
+        }
+
+        let mut ice_servers = {
+            let remote_peer = self.remote_peer()?;
+            call_manager.turn_servers(&*remote_peer, self.call_id)?
+        };
+        if ice_servers.is_empty() {
+            ice_servers = self.ice_servers.lock()?.clone();
+        }
+        let mut connection = call_manager.create_connection(self, remote_device, ice_servers)?;
+
+        {
+            // The call concludes before its connections are closed,
+            // so check again while holding the connection map.
+            let mut connection_map = self.connection_map.lock()?;
+            if call_manager.call_is_active(self.call_id)? {
+                connection_map.insert(remote_device, connection.clone());
+                return Ok(Some(connection));
+            }
+        }
+
+        info!(
+            "create_connection(): call concluded, closing id: {}",
+            connection.id()
+        );
+        connection.close()?;
+        Ok(None)
+    }
+
+    /// Set the ICE servers of every connection, replacing any
+    /// previous ones, e.g. when rotating TURN credentials.
+    ///
+    /// The servers are remembered for connections created later.  If
+    /// any credentials expire, the application is asked to refresh
+    /// them with `ApplicationEvent::IceServersExpiring`.
+    pub fn set_ice_servers(&self, ice_servers: Vec<IceServerConfig>) -> Result<()> {
+        info!("set_ice_servers(): size: {}", ice_servers.len());
+
+        {
+            let connection_map = self.connection_map.lock()?;
+            for connection in connection_map.values() {
+                let mut connection = connection.clone();
+                connection.set_ice_servers(ice_servers.clone(), false)?;
+            }
+        }
+
+        let refresh = refresh_period(&ice_servers);
+        *self.ice_servers.lock()? = ice_servers;
+        *self.ice_refresh_at.lock()? = refresh.map(|period| Instant::now() + period);
+        match refresh {
+            Some(period) => self.start_timeout(period, CallEvent::IceServersExpiring),
+            None => Ok(()),
+        }
+    }
+
+    /// Fetch fresh ICE servers from the application, e.g. before
+    /// restarting ICE.
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn turn_servers(&self) -> Result<Vec<IceServerConfig>> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+
+        call_manager.turn_servers(&*remote_peer, self.call_id)
+    }
+
+    /// Returns `true` if the credentials of the ICE servers are due
+    /// for a refresh.
+    ///
+    /// A timeout started for servers that were replaced since is not
+    /// due.
+    pub fn ice_servers_expiring(&self) -> Result<bool> {
+        Ok(match *self.ice_refresh_at.lock()? {
+            Some(refresh_at) => Instant::now() >= refresh_at,
+            None => false,
+        })
+    }
+
+    /// Enable or disable the outgoing audio of every connection,
+    /// signaling the change to the remote peer.
+    ///
+    /// The setting is remembered for connections created later.
+    pub fn set_outgoing_audio_enabled(&self, enabled: bool) -> Result<()> {
+        info!("set_outgoing_audio_enabled(): enabled: {}", enabled);
+
+        self.audio_enabled.store(enabled, Ordering::Release);
+
+        let connection_map = self.connection_map.lock()?;
+        for connection in connection_map.values() {
+            let mut connection = connection.clone();
+            connection.set_audio_enabled(enabled)?;
+        }
+        Ok(())
+    }
+
+    /// Return whether the outgoing audio is enabled.
+    pub fn outgoing_audio_enabled(&self) -> bool {
+        self.audio_enabled.load(Ordering::Acquire)
+    }
+
+    /// Set how much bandwidth every connection may use.
+    ///
+    /// The setting is remembered for connections created later.  Once
+    /// the call is connected, the caller renegotiates so the remote
+    /// peer learns of the new limits.
+    pub fn set_bandwidth_mode(&self, mode: BandwidthMode) -> Result<()> {
+        info!("set_bandwidth_mode(): mode: {}", mode);
+
+        *self.bandwidth_mode.lock()? = mode;
+
+        {
+            let connection_map = self.connection_map.lock()?;
+            for connection in connection_map.values() {
+                connection.set_bandwidth_mode(mode)?;
+            }
+        }
+
+        match self.state()? {
+            CallState::Connected | CallState::Reconnecting => {
+                self.active_connection()?.inject_restart_ice()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Return how much bandwidth the call may use.
+    pub fn bandwidth_mode(&self) -> Result<BandwidthMode> {
+        Ok(*self.bandwidth_mode.lock()?)
+    }
+
+    /// Set the audio processing of every connection.
+    ///
+    /// The setting is remembered for connections created later.
+    pub fn set_audio_config(&self, config: AudioConfig) -> Result<()> {
+        info!("set_audio_config(): {}", config);
+
+        *self.audio_config.lock()? = Some(config);
+
+        let connection_map = self.connection_map.lock()?;
+        for connection in connection_map.values() {
+            connection.set_audio_config(config)?;
+        }
+        Ok(())
+    }
+
+    /// Return the audio processing of the call, if not the default
+    /// of the CallManager.
+    pub fn audio_config(&self) -> Result<Option<AudioConfig>> {
+        Ok(*self.audio_config.lock()?)
+    }
+
     /// Handle the received SDP answer.
+    ///
+    /// An answer from a remote device left out by the connection
+    /// limit, e.g. to an offer the application delivered to all the
+    /// devices, connects to that device after all.  The answer does
+    /// not match the offer of the new Connection, so it is dropped
+    /// and the device answers the new offer instead.
     pub fn received_answer(&self, remote_device: DeviceId, answer: String) -> Result<()> {
         info!(
             "received_answer(): id: {}",
             self.call_id().format(remote_device)
         );
 
+        if self.take_deferred_device(remote_device)? {
+            info!("received_answer(): connecting to deferred remote_device");
+
+            let call_manager = self.call_manager()?;
+            if let Some(mut connection) = self.create_connection(&call_manager, remote_device)? {
+                connection.inject_send_offer()?;
+            }
+            return Ok(());
+        }
+
         let mut connection_map = self.connection_map.lock()?;
         let connection = match connection_map.get_mut(&remote_device) {
             Some(v) => v,
@@ -606,6 +1507,38 @@ where
         connection.inject_handle_answer(answer)
     }
 
+    /// Returns `true`, once, if `remote_device` was left out by the
+    /// connection limit and the call is still connecting.
+    fn take_deferred_device(&self, remote_device: DeviceId) -> Result<bool> {
+        if self.state()? != CallState::Connecting {
+            return Ok(false);
+        }
+
+        let mut deferred_devices = self.deferred_devices.lock()?;
+        match deferred_devices.iter().position(|d| *d == remote_device) {
+            Some(index) => {
+                let _ = deferred_devices.remove(index);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Handle an SDP offer received for the active call.
+    pub fn received_offer(&self, remote_device: DeviceId, offer: String) -> Result<()> {
+        info!(
+            "received_offer(): id: {}",
+            self.call_id().format(remote_device)
+        );
+
+        let mut connection_map = self.connection_map.lock()?;
+        let connection = match connection_map.get_mut(&remote_device) {
+            Some(v) => v,
+            None => return Err(RingRtcError::ConnectionNotFound(remote_device).into()),
+        };
+        connection.inject_handle_offer(offer)
+    }
+
     /// Handle the received ICE candidates.
     pub fn received_ice_candidates(
         &self,
@@ -644,20 +1577,234 @@ where
         }
     }
 
+    /// Return the type of hangup sent to the remote peer.
+    pub fn hangup_type(&self) -> Result<HangupType> {
+        let hangup_type = self.hangup_type.lock()?;
+        Ok(*hangup_type)
+    }
+
+    /// Update the type of hangup sent to the remote peer.
+    pub fn set_hangup_type(&self, hangup_type: HangupType) -> Result<()> {
+        let mut current = self.hangup_type.lock()?;
+        *current = hangup_type;
+        Ok(())
+    }
+
+    /// Return the metadata of the received offer, if any.
+    pub fn offer_metadata(&self) -> Result<Option<OfferMetadata>> {
+        Ok(self.offer_metadata.lock()?.clone())
+    }
+
+    /// Store the metadata of the received offer.
+    pub fn set_offer_metadata(&self, offer_metadata: OfferMetadata) -> Result<()> {
+        *self.offer_metadata.lock()? = Some(offer_metadata);
+        Ok(())
+    }
+
+    /// Return the metadata to attach to the answer, if any.
+    pub fn answer_metadata(&self) -> Result<Option<Vec<u8>>> {
+        Ok(self.answer_metadata.lock()?.clone())
+    }
+
+    /// Store the metadata to attach to the answer.
+    pub fn set_answer_metadata(&self, metadata: Vec<u8>) -> Result<()> {
+        *self.answer_metadata.lock()? = Some(metadata);
+        Ok(())
+    }
+
+    /// Report the metadata attached to an answer received on
+    /// `connection_id` to the application.
+    pub fn received_answer_metadata(
+        &self,
+        connection_id: ConnectionId,
+        metadata: &[u8],
+    ) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+        call_manager.received_answer_metadata(&*remote_peer, connection_id, metadata)
+    }
+
+    /// Record the event the call ended with.
+    pub fn set_end_reason(&self, event: ApplicationEvent) -> Result<()> {
+        self.record.lock()?.reason = Some(event);
+        Ok(())
+    }
+
+    /// Fill in the connected duration, route and bitrate of the call
+    /// record, while the connections are still open.
+    fn complete_record(&self) -> Result<()> {
+        let connected_at = *self.connected_at.lock()?;
+        let duration = self.duration()?;
+        let mut record = self.record.lock()?;
+
+        record.duration = duration;
+        if let Ok(connection) = self.active_connection() {
+            record.connection_type = connection.route_monitor()?.best_connection_type();
+            if let (Some(connected_at), Some(report)) =
+                (connected_at, connection.last_stats_report()?)
+            {
+                record.average_bitrate_bps = call_record::average_bitrate(connected_at, &report);
+            }
+        }
+        Ok(())
+    }
+
     /// Hangup this Call.
     ///
     /// Sends a hanging on all underlying Connections.
     pub fn hangup(&mut self) -> Result<()> {
-        info!("hangup(): {}", self.call_id());
+        let hangup_type = self.hangup_type()?;
+        info!("hangup(): {}, type: {}", self.call_id(), hangup_type);
 
         let mut connection_map = self.connection_map.lock()?;
         for connection in connection_map.values_mut() {
             info!("hangup(): id: {}", connection.id());
-            connection.inject_hangup()?;
+            connection.inject_hangup(hangup_type)?;
         }
         Ok(())
     }
 
+    /// Returns the remote devices the call has Connections to, in
+    /// order.
+    pub fn remote_devices(&self) -> Result<Vec<DeviceId>> {
+        let mut remote_devices: Vec<DeviceId> =
+            self.connection_map.lock()?.keys().cloned().collect();
+        remote_devices.sort();
+        Ok(remote_devices)
+    }
+
+    /// Sends a hangup of the given type on the Connections of all
+    /// remote devices except `remote_device`.
+    ///
+    /// Returns the other devices, in order, none if the call has no
+    /// other devices.
+    pub fn hangup_other_devices(
+        &self,
+        remote_device: DeviceId,
+        hangup_type: HangupType,
+    ) -> Result<Vec<DeviceId>> {
+        let mut connection_map = self.connection_map.lock()?;
+        let mut other_devices = Vec::new();
+        for (device, connection) in connection_map.iter_mut() {
+            if *device != remote_device {
+                info!("hangup_other_devices(): id: {}", connection.id());
+                connection.inject_hangup(hangup_type)?;
+                other_devices.push(*device);
+            }
+        }
+        other_devices.sort();
+        Ok(other_devices)
+    }
+
+    /// A remote device declined the call.
+    ///
+    /// Returns true if this was the last outstanding device, i.e. every
+    /// remaining device has now declined.  Once a device has accepted
+    /// the call, declines from the other devices are ignored.
+    ///
+    /// For an incoming call the decline comes from another of the
+    /// callee's devices, which ends the call unless it was accepted
+    /// here.
+    pub fn remote_declined(&self, remote_device: DeviceId) -> Result<bool> {
+        info!(
+            "remote_declined(): id: {}",
+            self.call_id().format(remote_device)
+        );
+
+        // The caller relays a decline from another of our devices.
+        if self.direction == CallDirection::InComing {
+            let state = self.state()?;
+            return Ok(state != CallState::Connected && state != CallState::Reconnecting);
+        }
+
+        if self.active_device_id.lock()?.is_some() {
+            info!("remote_declined(): ignoring, call already accepted");
+            return Ok(false);
+        }
+
+        let connection_map = self.connection_map.lock()?;
+        if !connection_map.contains_key(&remote_device) {
+            info!("remote_declined(): ignoring unknown device");
+            return Ok(false);
+        }
+
+        let mut declined_devices = self.declined_devices.lock()?;
+        let _ = declined_devices.insert(remote_device);
+        Ok(connection_map
+            .keys()
+            .all(|device| declined_devices.contains(device)))
+    }
+
+    /// A remote device accepted the call (caller only).
+    ///
+    /// Returns the remote device that wins the call, if the forking
+    /// policy chose one.
+    pub fn remote_accepted(&self, remote_device: DeviceId) -> Result<Option<DeviceId>> {
+        info!(
+            "remote_accepted(): id: {}",
+            self.call_id().format(remote_device)
+        );
+
+        {
+            let mut accepted_devices = self.accepted_devices.lock()?;
+            if !accepted_devices.contains(&remote_device) {
+                accepted_devices.push(remote_device);
+            }
+        }
+        self.select_fork_winner()
+    }
+
+    /// Ask the forking policy which accepted remote device wins the
+    /// call, if any (caller only).
+    pub fn select_fork_winner(&self) -> Result<Option<DeviceId>> {
+        let accepted_devices = self.accepted_devices.lock()?.clone();
+        if accepted_devices.is_empty() || self.active_device_id.lock()?.is_some() {
+            return Ok(None);
+        }
+
+        let candidates: Vec<ForkCandidate> = {
+            let connection_map = self.connection_map.lock()?;
+            let declined_devices = self.declined_devices.lock()?;
+            let mut remaining: Vec<&DeviceId> = connection_map
+                .keys()
+                .filter(|d| !declined_devices.contains(d) && !accepted_devices.contains(d))
+                .collect();
+            remaining.sort();
+            accepted_devices
+                .iter()
+                .filter(|d| connection_map.contains_key(d) && !declined_devices.contains(d))
+                .chain(remaining)
+                .map(|d| ForkCandidate {
+                    remote_device: *d,
+                    accepted:      accepted_devices.contains(d),
+                    rtt:           connection_map[d].round_trip_time().ok(),
+                })
+                .collect()
+        };
+
+        let winner = self.call_manager()?.select_fork_winner(&candidates)?;
+        match winner {
+            Some(winner) if accepted_devices.contains(&winner) => {
+                info!(
+                    "select_fork_winner(): id: {}",
+                    self.call_id().format(winner)
+                );
+                Ok(Some(winner))
+            }
+            Some(winner) => {
+                warn!(
+                    "select_fork_winner(): ignoring device that did not accept: {}",
+                    winner
+                );
+                Ok(None)
+            }
+            None => {
+                info!("select_fork_winner(): waiting for another device");
+                Ok(None)
+            }
+        }
+    }
+
     /// A connection failed to connect ICE.
     ///
     pub fn connection_failed(&mut self, remote_device: DeviceId) -> Result<()> {
@@ -704,6 +1851,7 @@ where
             );
             return Ok(());
         }
+        self.record_trace(TraceKind::FsmEvent, format!("{}", event));
         self.event_pump.try_send((self.clone(), event))?;
         Ok(())
     }
@@ -740,6 +1888,9 @@ where
         self.inject_event(CallEvent::EndCall)?;
         self.wait_for_terminate()?;
 
+        if let Err(e) = self.complete_record() {
+            warn!("close(): unable to complete the call record: {}", e);
+        }
         self.close_connections()?;
 
         // close down the FSM context
@@ -824,6 +1975,31 @@ where
         self.inject_event(CallEvent::LocalAccept)
     }
 
+    /// Inject a `StartRinging` event into the FSM.
+    pub fn inject_start_ringing(&mut self) -> Result<()> {
+        self.inject_event(CallEvent::StartRinging)
+    }
+
+    /// Inject a `SetCameraEnabled` event into the FSM.
+    pub fn inject_set_camera_enabled(&mut self, enabled: bool) -> Result<()> {
+        self.inject_event(CallEvent::SetCameraEnabled(enabled))
+    }
+
+    /// Inject a `SwitchCamera` event into the FSM.
+    pub fn inject_switch_camera(&mut self) -> Result<()> {
+        self.inject_event(CallEvent::SwitchCamera)
+    }
+
+    /// Inject an `AddedToSystem` event into the FSM.
+    pub fn inject_added_to_system(&mut self) -> Result<()> {
+        self.inject_event(CallEvent::AddedToSystem)
+    }
+
+    /// Inject a `RequestSystemHold` event into the FSM.
+    pub fn inject_request_system_hold(&mut self, on_hold: bool) -> Result<()> {
+        self.inject_event(CallEvent::RequestSystemHold(on_hold))
+    }
+
     /// Inject a local `HangUp` event into the FSM.
     pub fn inject_hangup(&mut self) -> Result<()> {
         self.set_state(CallState::Terminating)?;
@@ -840,6 +2016,16 @@ where
         self.inject_event(event)
     }
 
+    /// Inject a `ReceivedOffer` event into the FSM
+    pub fn inject_received_offer(
+        &mut self,
+        connection_id: ConnectionId,
+        offer: String,
+    ) -> Result<()> {
+        let event = CallEvent::ReceivedOffer(offer, connection_id.remote_device());
+        self.inject_event(event)
+    }
+
     /// Inject a `ReceivedIceCandidates` event into the FSM
     pub fn inject_received_ice_candidates(
         &mut self,
@@ -851,8 +2037,24 @@ where
     }
 
     /// Inject a `ReceivedHangup` event into the FSM
-    pub fn inject_received_hangup(&mut self, connection_id: ConnectionId) -> Result<()> {
-        let event = CallEvent::ReceivedHangup(connection_id.remote_device());
+    pub fn inject_received_hangup(
+        &mut self,
+        connection_id: ConnectionId,
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        let event = CallEvent::ReceivedHangup(connection_id.remote_device(), hangup_type);
+        self.inject_event(event)
+    }
+
+    /// Inject a `ReceivedDeclined` event into the FSM
+    pub fn inject_received_declined(&mut self, connection_id: ConnectionId) -> Result<()> {
+        let event = CallEvent::ReceivedDeclined(connection_id.remote_device());
+        self.inject_event(event)
+    }
+
+    /// Inject a `ReceivedRinging` event into the FSM
+    pub fn inject_received_ringing(&mut self, connection_id: ConnectionId) -> Result<()> {
+        let event = CallEvent::ReceivedRinging(connection_id.remote_device());
         self.inject_event(event)
     }
 
@@ -888,6 +2090,42 @@ where
         ))
     }
 
+    /// Inject a local `CallDurationTick` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    ///
+    pub fn inject_call_duration_tick(&mut self) -> Result<()> {
+        let event = CallEvent::CallDurationTick;
+        self.inject_event(event)
+    }
+
+    /// Inject a local `StatsTick` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    ///
+    pub fn inject_stats_tick(&mut self) -> Result<()> {
+        let event = CallEvent::StatsTick;
+        self.inject_event(event)
+    }
+
+    /// Inject a local `AudioLevelsTick` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    ///
+    pub fn inject_audio_levels_tick(&mut self) -> Result<()> {
+        let event = CallEvent::AudioLevelsTick;
+        self.inject_event(event)
+    }
+
+    /// Inject a local `BandwidthEstimateTick` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    ///
+    pub fn inject_bandwidth_estimate_tick(&mut self) -> Result<()> {
+        let event = CallEvent::BandwidthEstimateTick;
+        self.inject_event(event)
+    }
+
     /// Inject a local `CallTimeout` event into the FSM.
     ///
     /// `Called By:` Local timeout thread.
@@ -897,6 +2135,24 @@ where
         self.inject_event(event)
     }
 
+    /// Inject a local `IceGatheringTimeout` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    ///
+    pub fn inject_ice_gathering_timeout(&mut self) -> Result<()> {
+        let event = CallEvent::IceGatheringTimeout;
+        self.inject_event(event)
+    }
+
+    /// Inject a local `RingTimeout` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    ///
+    pub fn inject_ring_timeout(&mut self) -> Result<()> {
+        let event = CallEvent::RingTimeout;
+        self.inject_event(event)
+    }
+
     #[allow(clippy::mutex_atomic)]
     /// Inject a synchronizing event into the FSM.
     ///
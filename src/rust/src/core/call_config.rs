@@ -0,0 +1,181 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Per call timeouts and bandwidth chosen by the application.
+//!
+//! Each timeout ends a call that has not progressed far enough with
+//! `EndedTimeout`.  The defaults all match the two minute setup
+//! timeout, so by default only the connect timeout ever fires.
+//!
+//! An outgoing call may also call a busy remote peer again, see
+//! `CallConfig::retry_on_busy()`, and prefer a video or audio codec,
+//! see `CallConfig::preferred_video_codec()`.  Any call may process
+//! its audio differently from the other calls, see
+//! `CallConfig::with_audio_config()`, and limit its outgoing media
+//! from the start, see `CallConfig::with_media_constraints()`.  With
+//! the `srtp_key_export`
+//! feature, a call may allow its SRTP keys to be exported, see
+//! `CallConfig::with_srtp_key_export()`.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::core::audio_config::AudioConfig;
+use crate::core::bandwidth_mode::BandwidthMode;
+use crate::core::codec::{AudioCodec, VideoCodec};
+use crate::core::media_constraints::MediaConstraints;
+
+/// Default time, in seconds, a call may take to connect.
+const DEFAULT_TIMEOUT_PERIOD: u64 = 120;
+
+/// Default time, in seconds, before calling a busy remote peer again.
+const DEFAULT_BUSY_RETRY_PERIOD: u64 = 30;
+
+/// The timeouts and bandwidth of a single call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallConfig {
+    /// How long a call may ring before it is answered, starting when
+    /// the call rings.
+    pub ring_timeout:          Duration,
+    /// How long a call may gather and check ICE candidates before a
+    /// connection rings, starting when the call starts.
+    pub ice_gathering_timeout: Duration,
+    /// How long a call may take to connect, starting when the call
+    /// starts.
+    pub connect_timeout:       Duration,
+    /// How much bandwidth the call may use, until changed with
+    /// `CallManager::set_bandwidth_mode()`.
+    pub bandwidth_mode:        BandwidthMode,
+    /// How long to wait before calling a busy remote peer again.
+    pub busy_retry_interval:   Duration,
+    /// How many more times to call a busy remote peer, none by
+    /// default.
+    pub busy_retry_attempts:   u32,
+    /// The video codec moved to the front of the local descriptions,
+    /// if any.
+    pub video_codec:           Option<VideoCodec>,
+    /// The audio codec moved to the front of the local descriptions,
+    /// if any.
+    pub audio_codec:           Option<AudioCodec>,
+    /// The audio processing of the call, until changed with
+    /// `CallManager::set_audio_config()`, if not the default of the
+    /// CallManager.
+    pub audio_config:          Option<AudioConfig>,
+    /// The limits on the outgoing media, applied to each connection
+    /// as it is created.
+    pub media_constraints:     MediaConstraints,
+    /// Whether `CallManager::export_srtp_keys()` may export the SRTP
+    /// keys of the call.  Only built with the `srtp_key_export`
+    /// feature.
+    pub srtp_key_export:       bool,
+}
+
+impl Default for CallConfig {
+    fn default() -> Self {
+        Self {
+            ring_timeout:          Duration::from_secs(DEFAULT_TIMEOUT_PERIOD),
+            ice_gathering_timeout: Duration::from_secs(DEFAULT_TIMEOUT_PERIOD),
+            connect_timeout:       Duration::from_secs(DEFAULT_TIMEOUT_PERIOD),
+            bandwidth_mode:        BandwidthMode::default(),
+            busy_retry_interval:   Duration::from_secs(DEFAULT_BUSY_RETRY_PERIOD),
+            busy_retry_attempts:   0,
+            video_codec:           None,
+            audio_codec:           None,
+            audio_config:          None,
+            media_constraints:     MediaConstraints::default(),
+            srtp_key_export:       false,
+        }
+    }
+}
+
+impl fmt::Display for CallConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ring_timeout: {:?}, ice_gathering_timeout: {:?}, connect_timeout: {:?}, bandwidth_mode: {}, busy_retry_interval: {:?}, busy_retry_attempts: {}, video_codec: {:?}, audio_codec: {:?}, audio_config: {:?}, media_constraints: {}, srtp_key_export: {}",
+            self.ring_timeout,
+            self.ice_gathering_timeout,
+            self.connect_timeout,
+            self.bandwidth_mode,
+            self.busy_retry_interval,
+            self.busy_retry_attempts,
+            self.video_codec,
+            self.audio_codec,
+            self.audio_config,
+            self.media_constraints,
+            self.srtp_key_export
+        )
+    }
+}
+
+impl CallConfig {
+    /// Call a busy remote peer again after `interval`, up to
+    /// `max_attempts` times (outgoing calls only).
+    ///
+    /// The busy call ends with `EndedRemoteBusyRetry` instead of
+    /// `EndedRemoteBusy`, and each new call starts like any other
+    /// outgoing call.  Hanging up before then cancels the retry with
+    /// `BusyRetryCancelled`.
+    pub fn retry_on_busy(mut self, interval: Duration, max_attempts: u32) -> Self {
+        self.busy_retry_interval = interval;
+        self.busy_retry_attempts = max_attempts;
+        self
+    }
+
+    /// Prefer the video `codec`, e.g. H264 on a device with a
+    /// hardware encoder.
+    ///
+    /// The codec is moved to the front of the offers and answers of
+    /// the call, so it is used whenever the remote peer supports it.
+    pub fn preferred_video_codec(mut self, codec: VideoCodec) -> Self {
+        self.video_codec = Some(codec);
+        self
+    }
+
+    /// Prefer the audio `codec`, as `preferred_video_codec()` does for
+    /// video.
+    pub fn preferred_audio_codec(mut self, codec: AudioCodec) -> Self {
+        self.audio_codec = Some(codec);
+        self
+    }
+
+    /// Process the audio of the call with `config` instead of the
+    /// default set with `CallManager::set_default_audio_config()`.
+    pub fn with_audio_config(mut self, config: AudioConfig) -> Self {
+        self.audio_config = Some(config);
+        self
+    }
+
+    /// Start the outgoing media of the call within `constraints`,
+    /// rather than at full quality.
+    ///
+    /// The video caps stay until changed, e.g. with
+    /// `Connection::set_video_max_resolution()`, and muted media until
+    /// enabled with `CallManager::set_outgoing_audio_enabled()` or
+    /// `CallManager::set_outgoing_video_enabled()`.
+    pub fn with_media_constraints(mut self, constraints: MediaConstraints) -> Self {
+        self.media_constraints = constraints;
+        self
+    }
+
+    /// Allow the SRTP keys of the call to be exported, e.g. for a
+    /// compliance recorder, see `CallManager::export_srtp_keys()`.
+    #[cfg(feature = "srtp_key_export")]
+    pub fn with_srtp_key_export(mut self) -> Self {
+        self.srtp_key_export = true;
+        self
+    }
+
+    /// Returns the names of the preferred codecs, video first.
+    pub fn preferred_codecs(&self) -> Vec<&'static str> {
+        self.video_codec
+            .map(VideoCodec::name)
+            .into_iter()
+            .chain(self.audio_codec.map(AudioCodec::name))
+            .collect()
+    }
+}
@@ -0,0 +1,84 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Why a call failed, for the application to show an actionable
+//! error message.
+//!
+//! The `Ended*` events only say that a call ended with a failure.
+//! Before a failed call ends, `Platform::on_call_failed()` is handed
+//! the reason the failure was classified as.
+
+use std::fmt;
+
+use crate::error::RingRtcError;
+
+/// The reason a call failed.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CallFailureReason {
+    /// ICE could not connect, or lost the connection for good.
+    IceFailed = 0,
+    /// The call did not progress in time, e.g. because the signaling
+    /// messages of the remote peer never arrived.
+    SignalingTimeout,
+    /// The media keys could not be negotiated, as when a session
+    /// description is rejected.
+    SrtpKeyError,
+    /// The PeerConnection could not be created.
+    PeerConnectionCreateFailed,
+    /// Any other internal failure of the call or connection state
+    /// machines.
+    InternalFsmError,
+}
+
+impl fmt::Display for CallFailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<&failure::Error> for CallFailureReason {
+    fn from(error: &failure::Error) -> Self {
+        match error.downcast_ref::<RingRtcError>() {
+            Some(RingRtcError::CreatePeerConnection(_))
+            | Some(RingRtcError::CreatePeerConnectionObserver) => {
+                CallFailureReason::PeerConnectionCreateFailed
+            }
+            Some(RingRtcError::SetSessionDescriptionObserver(_, _))
+            | Some(RingRtcError::SetSessionDescriptionObserverResult(_)) => {
+                CallFailureReason::SrtpKeyError
+            }
+            _ => CallFailureReason::InternalFsmError,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_from_error() {
+        let error = RingRtcError::CreatePeerConnection("fault".to_owned()).into();
+        assert_eq!(
+            CallFailureReason::from(&error),
+            CallFailureReason::PeerConnectionCreateFailed
+        );
+
+        let error = RingRtcError::SetSessionDescriptionObserver("fault".to_owned(), 1).into();
+        assert_eq!(
+            CallFailureReason::from(&error),
+            CallFailureReason::SrtpKeyError
+        );
+
+        let error = RingRtcError::NoActiveCall.into();
+        assert_eq!(
+            CallFailureReason::from(&error),
+            CallFailureReason::InternalFsmError
+        );
+    }
+}
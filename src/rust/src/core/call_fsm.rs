@@ -20,15 +20,34 @@
 //!
 //! ## Flow events from client application
 //! - Proceed
+//! - StartRinging
 //! - Drop
 //! - Abort
 //!
+//! ## Camera events from client application
+//! - SetCameraEnabled
+//! - SwitchCamera
+//!
+//! ## System integration events
+//! - AddedToSystem
+//! - RequestSystemHold
+//!
 //! ## From connection observer interfaces
 //!
 //! - Ringing
 //! - Connected
 //! - RemoteVideoEnabled
 //! - RemoteVideoDisabled
+//! - RemoteAudioMuted
+//! - RemoteAudioUnmuted
+//! - RemoteOnHold
+//! - RemoteResumed
+//! - RemoteRecordingStarted
+//! - RemoteRecordingStopped
+//! - RemoteMediaStalled
+//! - RemoteMediaResumed
+//! - RemoteSharingScreen
+//! - RemoteStoppedSharingScreen
 //! - RemoteHangup
 //! - ConnectionFailed
 //! - Timeout
@@ -36,11 +55,21 @@
 //!
 //! ## Signaling events from client application
 //! - ReceivedAnswer
+//! - ReceivedOffer
 //! - ReceivedIceCandidates
+//! - ReceivedDeclined
+//! - ReceivedRinging
 //!
 //! ## From Internal runtime
 //!
 //! - CallTimeout
+//! - IceGatheringTimeout
+//! - RingTimeout
+//! - IceServersExpiring
+//! - CallDurationTick
+//! - StatsTick
+//! - AudioLevelsTick
+//! - BandwidthEstimateTick
 //! - InternalError
 
 extern crate tokio;
@@ -56,10 +85,18 @@ use tokio::runtime;
 
 use crate::error::RingRtcError;
 
-use crate::common::{ApplicationEvent, CallDirection, CallState, ConnectionId, DeviceId, Result};
+use crate::common::{
+    ApplicationEvent,
+    CallDirection,
+    CallState,
+    ConnectionId,
+    DeviceId,
+    HangupType,
+    Result,
+};
 
 use crate::core::call::{Call, EventStream};
-use crate::core::connection::ObserverEvent;
+use crate::core::connection::{MediaFlow, ObserverEvent};
 use crate::core::platform::Platform;
 
 use crate::webrtc::ice_candidate::IceCandidate;
@@ -77,15 +114,42 @@ pub enum CallEvent {
     // Flow events from client application
     /// OK to proceed with call setup.
     Proceed(Vec<DeviceId>),
+    /// The application allowed the incoming call to ring (callee
+    /// only).
+    StartRinging,
+
+    // Camera events from client application
+    /// Turn the camera on or off.
+    SetCameraEnabled(bool),
+    /// Switch to the next camera, e.g. from the front to the back
+    /// camera.
+    SwitchCamera,
+
+    // System integration events
+    /// The application handed the call to the calling service of the
+    /// system, e.g. Telecom on Android.
+    AddedToSystem,
+    /// Ask the system to show the call as on hold, or active again,
+    /// after the call manager held or resumed it by itself.
+    RequestSystemHold(bool),
 
     // Signaling events from client application
     /// Received SDP answer signal message from remote peer (caller
     /// only).
     ReceivedAnswer(String, DeviceId),
+    /// Received SDP offer signal message for the active call, from a
+    /// remote peer renegotiating its connection (callee only).
+    ReceivedOffer(String, DeviceId),
     /// Received ICE candidates signal message from remote peer.
     ReceivedIceCandidates(Vec<IceCandidate>, DeviceId),
     /// Received hangup signal message from remote peer.
-    ReceivedHangup(DeviceId),
+    ReceivedHangup(DeviceId, HangupType),
+    /// Received declined hangup signal message from remote peer
+    /// (caller only).
+    ReceivedDeclined(DeviceId),
+    /// Received ringing acknowledgment signal message from remote
+    /// peer (caller only).
+    ReceivedRinging(DeviceId),
 
     /// Connection observer event
     ConnectionEvent(ObserverEvent, DeviceId),
@@ -97,6 +161,21 @@ pub enum CallEvent {
     InternalError(failure::Error),
     /// The call timed out while establishing a connection.
     CallTimeout,
+    /// The call timed out before any connection rang.
+    IceGatheringTimeout,
+    /// The call timed out while ringing.
+    RingTimeout,
+    /// The credentials of the ICE servers are about to expire.
+    IceServersExpiring,
+    /// Another tick period of the connected call passed.
+    CallDurationTick,
+    /// Another stats interval of the connected call passed.
+    StatsTick,
+    /// Another audio levels interval of the connected call passed.
+    AudioLevelsTick,
+    /// Another bandwidth estimate interval of the connected call
+    /// passed.
+    BandwidthEstimateTick,
     /// Synchronize the FSM.
     Synchronize(Arc<(Mutex<bool>, Condvar)>),
     /// Shutdown the call.
@@ -110,11 +189,21 @@ impl fmt::Display for CallEvent {
             CallEvent::LocalAccept => "LocalAccept".to_string(),
             CallEvent::LocalHangup => "LocalHangup".to_string(),
             CallEvent::Proceed(devices) => format!("Proceed, devices: {:?}", devices),
+            CallEvent::StartRinging => "StartRinging".to_string(),
+            CallEvent::SetCameraEnabled(enabled) => format!("SetCameraEnabled: {}", enabled),
+            CallEvent::SwitchCamera => "SwitchCamera".to_string(),
+            CallEvent::AddedToSystem => "AddedToSystem".to_string(),
+            CallEvent::RequestSystemHold(on_hold) => format!("RequestSystemHold: {}", on_hold),
             CallEvent::ReceivedAnswer(_, d) => format!("ReceivedAnswer, device: {}", d),
+            CallEvent::ReceivedOffer(_, d) => format!("ReceivedOffer, device: {}", d),
             CallEvent::ReceivedIceCandidates(_, d) => {
                 format!("ReceivedIceCandidates, device: {}", d)
             }
-            CallEvent::ReceivedHangup(d) => format!("ReceivedHangup, device: {}", d),
+            CallEvent::ReceivedHangup(d, t) => {
+                format!("ReceivedHangup, device: {}, type: {}", d, t)
+            }
+            CallEvent::ReceivedDeclined(d) => format!("ReceivedDeclined, device: {}", d),
+            CallEvent::ReceivedRinging(d) => format!("ReceivedRinging, device: {}", d),
             CallEvent::ConnectionEvent(e, d) => {
                 format!("ConnectionEvent, event: {}, device: {}", e, d)
             }
@@ -123,6 +212,13 @@ impl fmt::Display for CallEvent {
             }
             CallEvent::InternalError(e) => format!("InternalError: {}", e),
             CallEvent::CallTimeout => "CallTimeout".to_string(),
+            CallEvent::IceGatheringTimeout => "IceGatheringTimeout".to_string(),
+            CallEvent::RingTimeout => "RingTimeout".to_string(),
+            CallEvent::IceServersExpiring => "IceServersExpiring".to_string(),
+            CallEvent::CallDurationTick => "CallDurationTick".to_string(),
+            CallEvent::StatsTick => "StatsTick".to_string(),
+            CallEvent::AudioLevelsTick => "AudioLevelsTick".to_string(),
+            CallEvent::BandwidthEstimateTick => "BandwidthEstimateTick".to_string(),
             CallEvent::Synchronize(_) => "Synchronize".to_string(),
             CallEvent::EndCall => "EndCall".to_string(),
         };
@@ -326,18 +422,49 @@ where
             _ => (),
         }
 
+        // If the application cancelled the call setup, drop all other
+        // events until the call is concluded, so it can neither ring
+        // nor connect.
+        if call.setup_cancelled() {
+            match state {
+                CallState::Connected | CallState::Reconnecting => {}
+                _ => {
+                    debug!("handle_event(): dropping event {} after cancel", event);
+                    return Ok(());
+                }
+            }
+        }
+
         match event {
             CallEvent::StartCall => self.handle_start_call(call, state),
             CallEvent::Proceed(remote_devices) => self.handle_proceed(call, state, remote_devices),
             CallEvent::LocalAccept => self.handle_local_accept(call, state),
+            CallEvent::StartRinging => self.handle_start_ringing(call, state),
+            CallEvent::SetCameraEnabled(enabled) => {
+                self.handle_set_camera_enabled(call, state, enabled)
+            }
+            CallEvent::SwitchCamera => self.handle_switch_camera(call, state),
+            CallEvent::AddedToSystem => self.handle_added_to_system(call, state),
+            CallEvent::RequestSystemHold(on_hold) => {
+                self.handle_request_system_hold(call, state, on_hold)
+            }
             CallEvent::ReceivedAnswer(answer, remote_device) => {
                 self.handle_received_answer(call, state, remote_device, answer)
             }
+            CallEvent::ReceivedOffer(offer, remote_device) => {
+                self.handle_received_offer(call, state, remote_device, offer)
+            }
             CallEvent::ReceivedIceCandidates(ice_candidates, remote_device) => {
                 self.handle_received_ice_candidates(call, state, ice_candidates, remote_device)
             }
-            CallEvent::ReceivedHangup(remote_device) => {
-                self.handle_received_hangup(call, remote_device)
+            CallEvent::ReceivedHangup(remote_device, hangup_type) => {
+                self.handle_received_hangup(call, state, remote_device, hangup_type)
+            }
+            CallEvent::ReceivedDeclined(remote_device) => {
+                self.handle_received_declined(call, remote_device)
+            }
+            CallEvent::ReceivedRinging(remote_device) => {
+                self.handle_received_ringing(call, state, remote_device)
             }
             CallEvent::ConnectionEvent(event, remote_device) => {
                 self.handle_connection_event(call, state, event, remote_device)
@@ -347,6 +474,13 @@ where
             }
             CallEvent::InternalError(error) => self.handle_internal_error(call, error),
             CallEvent::CallTimeout => self.handle_call_timeout(call, state),
+            CallEvent::IceGatheringTimeout => self.handle_ice_gathering_timeout(call, state),
+            CallEvent::RingTimeout => self.handle_ring_timeout(call, state),
+            CallEvent::IceServersExpiring => self.handle_ice_servers_expiring(call, state),
+            CallEvent::CallDurationTick => self.handle_call_duration_tick(call, state),
+            CallEvent::StatsTick => self.handle_stats_tick(call, state),
+            CallEvent::AudioLevelsTick => self.handle_audio_levels_tick(call, state),
+            CallEvent::BandwidthEstimateTick => self.handle_bandwidth_estimate_tick(call, state),
             CallEvent::LocalHangup => Ok(()),
             CallEvent::Synchronize(_) => Ok(()),
             CallEvent::EndCall => Ok(()),
@@ -368,6 +502,20 @@ where
         self.notify_spawn(notify_app_future);
     }
 
+    fn suggest_audio_route_for_video(&mut self, call: Call<T>) {
+        let mut err_call = call.clone();
+        // Follows the event that the remote video is enabled.
+        let suggest_future = lazy(move || {
+            if call.terminating()? {
+                return Ok(());
+            }
+            call.suggest_audio_route_for_video()
+        })
+        .map_err(move |err| err_call.inject_internal_error(err, "Suggest audio route failed"));
+
+        self.notify_spawn(suggest_future);
+    }
+
     fn handle_start_call(&mut self, call: Call<T>, state: CallState) -> Result<()> {
         info!("handle_start_call():");
 
@@ -416,7 +564,9 @@ where
         remote_device: DeviceId,
         answer: String,
     ) -> Result<()> {
-        if let CallState::Connecting = state {
+        // Once connected the answer is for a renegotiation offer,
+        // sent by a hard reset or an ICE restart.
+        if let CallState::Connecting | CallState::Connected | CallState::Reconnecting = state {
             let mut err_call = call.clone();
             let handle_answer_future = lazy(move || {
                 if call.terminating()? {
@@ -435,6 +585,33 @@ where
         Ok(())
     }
 
+    fn handle_received_offer(
+        &mut self,
+        call: Call<T>,
+        state: CallState,
+        remote_device: DeviceId,
+        offer: String,
+    ) -> Result<()> {
+        match state {
+            CallState::Connected | CallState::Reconnecting => {
+                let mut err_call = call.clone();
+                let handle_offer_future = lazy(move || {
+                    if call.terminating()? {
+                        return Ok(());
+                    }
+                    call.received_offer(remote_device, offer)
+                })
+                .map_err(move |err| {
+                    err_call.inject_internal_error(err, "Handle Received Offer Future failed")
+                });
+
+                self.worker_spawn(handle_offer_future);
+            }
+            _ => self.unexpected_state(state, "HandleReceivedOffer"),
+        }
+        Ok(())
+    }
+
     fn handle_received_ice_candidates(
         &mut self,
         call: Call<T>,
@@ -466,12 +643,27 @@ where
         Ok(())
     }
 
-    fn handle_received_hangup(&mut self, call: Call<T>, _remote_device: DeviceId) -> Result<()> {
+    fn handle_received_hangup(
+        &mut self,
+        call: Call<T>,
+        state: CallState,
+        _remote_device: DeviceId,
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        // The caller tells all of our devices which one accepted the
+        // call, only the others hang up.
+        if let (CallDirection::InComing, HangupType::Accepted) = (call.direction(), hangup_type) {
+            if let CallState::Connected | CallState::Reconnecting = state {
+                info!("handle_received_hangup(): ignoring, call accepted on this device");
+                return Ok(());
+            }
+        }
+
         call.set_state(CallState::Terminating)?;
         let mut err_call = call.clone();
         let remote_hangup_future = lazy(move || {
             let mut call_manager = call.call_manager()?;
-            call_manager.remote_hangup(call.call_id())
+            call_manager.remote_hangup(call.call_id(), hangup_type)
         })
         .map_err(move |err| {
             err_call.inject_internal_error(err, "Processing remote hangup request failed")
@@ -481,6 +673,219 @@ where
         Ok(())
     }
 
+    fn handle_remote_media_closed(&mut self, call: Call<T>) -> Result<()> {
+        call.set_state(CallState::Terminating)?;
+        let mut err_call = call.clone();
+        let media_closed_future = lazy(move || {
+            let mut call_manager = call.call_manager()?;
+            call_manager.remote_media_closed(call.call_id())
+        })
+        .map_err(move |err| {
+            err_call.inject_internal_error(err, "Processing remote media closed failed")
+        });
+
+        self.worker_spawn(media_closed_future);
+        Ok(())
+    }
+
+    fn handle_received_declined(&mut self, call: Call<T>, remote_device: DeviceId) -> Result<()> {
+        if !call.remote_declined(remote_device)? {
+            // An accepted device may have been waiting for the
+            // declined one.
+            if call.direction() == CallDirection::OutGoing && call.state()? == CallState::Ringing {
+                if let Some(winner) = call.select_fork_winner()? {
+                    self.connect_fork_winner(call, winner)?;
+                }
+            }
+            return Ok(());
+        }
+
+        call.set_state(CallState::Terminating)?;
+        let mut err_call = call.clone();
+        let remote_declined_future = lazy(move || {
+            let mut call_manager = call.call_manager()?;
+            call_manager.remote_declined(call.call_id())
+        })
+        .map_err(move |err| {
+            err_call.inject_internal_error(err, "Processing remote declined request failed")
+        });
+
+        self.worker_spawn(remote_declined_future);
+        Ok(())
+    }
+
+    /// Connect the remote device that won the forked outgoing call,
+    /// hanging up the others.
+    fn connect_fork_winner(&mut self, call: Call<T>, remote_device: DeviceId) -> Result<()> {
+        call.set_state(CallState::Connected)?;
+        call.set_active_device_id(remote_device)?;
+        let mut err_call = call.clone();
+        let media_future = lazy(move || {
+            if call.terminating()? {
+                return Ok(());
+            }
+            let connection = call.active_connection()?;
+            connection.connect_media()?;
+            call.call_manager()?
+                .send_accepted_hangup(call.clone(), remote_device)?;
+            call.notify_application(ApplicationEvent::RemoteConnected)?;
+            call.notify_safety_numbers()?;
+            call.network_route_changed()
+        })
+        .map_err(move |err| {
+            err_call.inject_internal_error(err, "Processing connect_media request failed")
+        });
+        self.worker_spawn(media_future);
+        Ok(())
+    }
+
+    fn handle_received_ringing(
+        &mut self,
+        call: Call<T>,
+        state: CallState,
+        remote_device: DeviceId,
+    ) -> Result<()> {
+        info!(
+            "handle_received_ringing(): remote_device: {}",
+            remote_device
+        );
+
+        match (call.direction(), state) {
+            (CallDirection::OutGoing, CallState::Connecting) => {
+                // The remote device rings before ICE connected here.
+                if call.set_remote_ringing() {
+                    self.notify_application(call, ApplicationEvent::RemoteRinging);
+                }
+            }
+            // ICE connected first, the application already knows.
+            (CallDirection::OutGoing, _) => {}
+            _ => self.unexpected_state(state, "ReceivedRinging"),
+        }
+        Ok(())
+    }
+
+    fn handle_start_ringing(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        info!("handle_start_ringing():");
+
+        if let CallState::Connecting = state {
+            call.set_state(CallState::Ringing)?;
+            call.start_ring_timeout()?;
+            self.notify_application(call.clone(), ApplicationEvent::LocalRinging);
+
+            let mut err_call = call.clone();
+            let ringing_future = lazy(move || call.send_ringing()).map_err(move |err| {
+                err_call.inject_internal_error(err, "Sending ringing acknowledgment failed")
+            });
+            self.worker_spawn(ringing_future);
+        } else {
+            self.unexpected_state(state, "StartRinging");
+        }
+        Ok(())
+    }
+
+    fn handle_set_camera_enabled(
+        &mut self,
+        call: Call<T>,
+        state: CallState,
+        enabled: bool,
+    ) -> Result<()> {
+        info!("handle_set_camera_enabled(): enabled: {}", enabled);
+
+        match state {
+            CallState::Terminating | CallState::Closed => {}
+            _ => {
+                let mut err_call = call.clone();
+                // Run on the worker, after any media set up before and
+                // before the teardown of the call, so the platform
+                // never controls a camera already released.
+                let camera_future = lazy(move || {
+                    if call.terminating()? {
+                        return Ok(());
+                    }
+                    call.set_camera_enabled(enabled)
+                })
+                .map_err(move |err| err_call.inject_internal_error(err, "Set camera failed"));
+
+                self.worker_spawn(camera_future);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_switch_camera(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        info!("handle_switch_camera():");
+
+        match state {
+            CallState::Terminating | CallState::Closed => {}
+            _ => {
+                let mut err_call = call.clone();
+                let camera_future = lazy(move || {
+                    if call.terminating()? {
+                        return Ok(());
+                    }
+                    call.switch_camera()
+                })
+                .map_err(move |err| err_call.inject_internal_error(err, "Switch camera failed"));
+
+                self.worker_spawn(camera_future);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_added_to_system(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        info!("handle_added_to_system():");
+
+        match state {
+            CallState::Terminating | CallState::Closed => {}
+            _ => {
+                call.set_system_managed();
+
+                let mut err_call = call.clone();
+                let system_future = lazy(move || {
+                    if call.terminating()? {
+                        return Ok(());
+                    }
+                    call.notify_added_to_system()
+                })
+                .map_err(move |err| {
+                    err_call.inject_internal_error(err, "Notify added to system failed")
+                });
+
+                self.notify_spawn(system_future);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request_system_hold(
+        &mut self,
+        call: Call<T>,
+        state: CallState,
+        on_hold: bool,
+    ) -> Result<()> {
+        info!("handle_request_system_hold(): on_hold: {}", on_hold);
+
+        match state {
+            CallState::Terminating | CallState::Closed => {}
+            _ => {
+                let mut err_call = call.clone();
+                let system_future = lazy(move || {
+                    if call.terminating()? {
+                        return Ok(());
+                    }
+                    call.request_system_hold(on_hold)
+                })
+                .map_err(move |err| {
+                    err_call.inject_internal_error(err, "Request system hold failed")
+                });
+
+                self.notify_spawn(system_future);
+            }
+        }
+        Ok(())
+    }
+
     fn handle_local_accept(&mut self, call: Call<T>, state: CallState) -> Result<()> {
         info!("handle_local_accept():");
         match state {
@@ -494,7 +899,9 @@ where
                     let mut connection = call.active_connection()?;
                     connection.inject_accept_call()?;
                     connection.connect_media()?;
-                    call.notify_application(ApplicationEvent::LocalConnected)
+                    call.notify_application(ApplicationEvent::LocalConnected)?;
+                    call.notify_safety_numbers()?;
+                    call.network_route_changed()
                 })
                 .map_err(move |err| {
                     err_call.inject_internal_error(err, "Processing local accept request failed")
@@ -542,14 +949,36 @@ where
         match event {
             ObserverEvent::ConnectionRinging => {
                 match state {
-                    CallState::Connecting => {
-                        call.set_state(CallState::Ringing)?;
-                        if let CallDirection::InComing = call.direction() {
-                            self.notify_application(call, ApplicationEvent::LocalRinging)
-                        } else {
-                            self.notify_application(call, ApplicationEvent::RemoteRinging)
+                    CallState::Connecting => match call.direction() {
+                        CallDirection::InComing => {
+                            // Let the application screen the call
+                            // before it rings.
+                            let mut err_call = call.clone();
+                            let should_ring_future = lazy(move || {
+                                if call.terminating()? {
+                                    return Ok(());
+                                }
+                                let should_ring = call.call_manager()?.should_ring(&call)?;
+                                if should_ring {
+                                    call.inject_start_ringing()
+                                } else {
+                                    let mut call_manager = call.call_manager()?;
+                                    call_manager.ring_refused(call.call_id())
+                                }
+                            })
+                            .map_err(move |err| {
+                                err_call.inject_internal_error(err, "Processing should_ring failed")
+                            });
+                            self.worker_spawn(should_ring_future);
                         }
-                    }
+                        CallDirection::OutGoing => {
+                            call.set_state(CallState::Ringing)?;
+                            call.start_ring_timeout()?;
+                            if call.set_remote_ringing() {
+                                self.notify_application(call, ApplicationEvent::RemoteRinging)
+                            }
+                        }
+                    },
                     _ => {
                         self.ignore_connection_event(connection_id, state, event);
                     }
@@ -560,24 +989,9 @@ where
                 match call.direction() {
                     CallDirection::OutGoing => match state {
                         CallState::Ringing => {
-                            call.set_state(CallState::Connected)?;
-                            call.set_active_device_id(remote_device)?;
-                            let mut err_call = call.clone();
-                            let media_future = lazy(move || {
-                                if call.terminating()? {
-                                    return Ok(());
-                                }
-                                let connection = call.active_connection()?;
-                                connection.connect_media()?;
-                                call.notify_application(ApplicationEvent::RemoteConnected)
-                            })
-                            .map_err(move |err| {
-                                err_call.inject_internal_error(
-                                    err,
-                                    "Processing connect_media request failed",
-                                )
-                            });
-                            self.worker_spawn(media_future);
+                            if let Some(winner) = call.remote_accepted(remote_device)? {
+                                self.connect_fork_winner(call, winner)?;
+                            }
                         }
                         _ => {
                             self.ignore_connection_event(connection_id, state, event);
@@ -592,13 +1006,22 @@ where
                 }
                 Ok(())
             }
-            ObserverEvent::RemoteHangup => self.handle_received_hangup(call, remote_device),
+            ObserverEvent::RemoteHangup(HangupType::Declined) => {
+                self.handle_received_declined(call, remote_device)
+            }
+            ObserverEvent::RemoteHangup(hangup_type) => {
+                self.handle_received_hangup(call, state, remote_device, hangup_type)
+            }
             ObserverEvent::RemoteVideoStatus(enable) => {
                 if call.active_device_id()? == remote_device {
                     match state {
                         CallState::Connected => {
                             if enable {
-                                self.notify_application(call, ApplicationEvent::RemoteVideoEnable)
+                                self.notify_application(
+                                    call.clone(),
+                                    ApplicationEvent::RemoteVideoEnable,
+                                );
+                                self.suggest_audio_route_for_video(call)
                             } else {
                                 self.notify_application(call, ApplicationEvent::RemoteVideoDisable)
                             }
@@ -615,12 +1038,15 @@ where
                 }
                 Ok(())
             }
-            ObserverEvent::ConnectionReconnecting => {
+            ObserverEvent::RemoteAudioStatus(enable) => {
                 if call.active_device_id()? == remote_device {
                     match state {
-                        CallState::Connected => {
-                            call.set_state(CallState::Reconnecting)?;
-                            self.notify_application(call, ApplicationEvent::Reconnecting)
+                        CallState::Connected | CallState::Reconnecting => {
+                            if enable {
+                                self.notify_application(call, ApplicationEvent::RemoteAudioUnmuted)
+                            } else {
+                                self.notify_application(call, ApplicationEvent::RemoteAudioMuted)
+                            }
                         }
                         _ => {
                             self.ignore_connection_event(connection_id, state, event);
@@ -634,12 +1060,15 @@ where
                 }
                 Ok(())
             }
-            ObserverEvent::ConnectionReconnected => {
+            ObserverEvent::RemoteHold(on_hold) => {
                 if call.active_device_id()? == remote_device {
                     match state {
-                        CallState::Reconnecting => {
-                            call.set_state(CallState::Connected)?;
-                            self.notify_application(call, ApplicationEvent::Reconnected)
+                        CallState::Connected | CallState::Reconnecting => {
+                            if on_hold {
+                                self.notify_application(call, ApplicationEvent::RemoteOnHold)
+                            } else {
+                                self.notify_application(call, ApplicationEvent::RemoteResumed)
+                            }
                         }
                         _ => {
                             self.ignore_connection_event(connection_id, state, event);
@@ -653,32 +1082,476 @@ where
                 }
                 Ok(())
             }
-            ObserverEvent::ConnectionFailed => {
-                let mut err_call = call.clone();
-                let future = lazy(move || {
-                    if call.terminating()? {
-                        return Ok(());
+            ObserverEvent::RemoteScreenShareStatus(sharing) => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected | CallState::Reconnecting => {
+                            if sharing {
+                                self.notify_application(call, ApplicationEvent::RemoteSharingScreen)
+                            } else {
+                                self.notify_application(
+                                    call,
+                                    ApplicationEvent::RemoteStoppedSharingScreen,
+                                )
+                            }
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
                     }
-                    call.connection_failed(remote_device)
-                })
-                .map_err(move |err| {
-                    err_call
-                        .inject_internal_error(err, "Processing connection_failed request failed")
-                });
-                self.worker_spawn(future);
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
                 Ok(())
             }
-        }
-    }
-
-    fn handle_internal_error(&mut self, call: Call<T>, error: failure::Error) -> Result<()> {
-        info!("handle_internal_error():");
-
-        let internal_error_future =
-            lazy(move || call.internal_error(error)).map_err(move |err: failure::Error| {
-                error!("Processing internal error future failed: {}", err);
-                // Nothing else to do here
-            });
+            ObserverEvent::RemoteRecordingStatus(recording) => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected | CallState::Reconnecting => {
+                            if recording {
+                                self.notify_application(
+                                    call,
+                                    ApplicationEvent::RemoteRecordingStarted,
+                                )
+                            } else {
+                                self.notify_application(
+                                    call,
+                                    ApplicationEvent::RemoteRecordingStopped,
+                                )
+                            }
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::RemoteMediaFlow(flow) => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected | CallState::Reconnecting => {
+                            let app_event = match flow {
+                                MediaFlow::Stalled => ApplicationEvent::RemoteMediaStalled,
+                                MediaFlow::Resumed => ApplicationEvent::RemoteMediaResumed,
+                            };
+                            self.notify_application(call, app_event)
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::RemoteMediaClosed => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected | CallState::Reconnecting => {
+                            self.handle_remote_media_closed(call)?;
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::TransferRequested => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected | CallState::Reconnecting => {
+                            // Let the application decide, and answer
+                            // the remote peer.
+                            let mut err_call = call.clone();
+                            let transfer_future = lazy(move || {
+                                if call.terminating()? {
+                                    return Ok(());
+                                }
+                                call.transfer_requested()
+                            })
+                            .map_err(move |err| {
+                                err_call.inject_internal_error(
+                                    err,
+                                    "Processing transfer request failed",
+                                )
+                            });
+                            self.worker_spawn(transfer_future);
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::TransferAccepted => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected | CallState::Reconnecting => {
+                            // The remote peer is calling the transfer
+                            // target, so hang up this call.
+                            call.set_state(CallState::Terminating)?;
+                            let mut err_call = call.clone();
+                            let transferred_future = lazy(move || {
+                                let mut call_manager = call.call_manager()?;
+                                call_manager.transfer_accepted(call.call_id())
+                            })
+                            .map_err(move |err| {
+                                err_call.inject_internal_error(
+                                    err,
+                                    "Processing transfer accepted failed",
+                                )
+                            });
+                            self.worker_spawn(transferred_future);
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::TransferDeclined => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected | CallState::Reconnecting => {
+                            self.notify_application(call, ApplicationEvent::TransferDeclined)
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::ConnectionReconnecting => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected => {
+                            call.set_state(CallState::Reconnecting)?;
+                            self.notify_application(call, ApplicationEvent::Reconnecting)
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::ConnectionHardReset => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Connected => {
+                            call.set_state(CallState::Reconnecting)?;
+                            self.notify_application(call, ApplicationEvent::Reconnecting)
+                        }
+                        CallState::Reconnecting => {
+                            self.notify_application(call, ApplicationEvent::Reconnecting)
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::RenegotiationTimeout => {
+                match state {
+                    CallState::Reconnecting => {
+                        self.notify_application(call, ApplicationEvent::RenegotiationTimeout)
+                    }
+                    _ => {
+                        self.ignore_connection_event(connection_id, state, event);
+                    }
+                }
+                Ok(())
+            }
+            ObserverEvent::ConnectionReconnected => {
+                if call.active_device_id()? == remote_device {
+                    match state {
+                        CallState::Reconnecting => {
+                            call.set_state(CallState::Connected)?;
+                            self.notify_application(call, ApplicationEvent::Reconnected)
+                        }
+                        _ => {
+                            self.ignore_connection_event(connection_id, state, event);
+                        }
+                    }
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::ConnectionFailed => {
+                let mut err_call = call.clone();
+                let future = lazy(move || {
+                    if call.terminating()? {
+                        return Ok(());
+                    }
+                    call.connection_failed(remote_device)
+                })
+                .map_err(move |err| {
+                    err_call
+                        .inject_internal_error(err, "Processing connection_failed request failed")
+                });
+                self.worker_spawn(future);
+                Ok(())
+            }
+            ObserverEvent::RemoteVideoOrientation(degrees) => {
+                // The video is received before the call connects, in
+                // which case the rotation is reported once the media
+                // is connected.
+                match state {
+                    CallState::Connected | CallState::Reconnecting => {
+                        if call.active_device_id()? == remote_device {
+                            let mut err_call = call.clone();
+                            let orientation_future = lazy(move || {
+                                if call.terminating()? {
+                                    return Ok(());
+                                }
+                                call.remote_video_orientation(remote_device, degrees)
+                            })
+                            .map_err(move |err| {
+                                err_call.inject_internal_error(
+                                    err,
+                                    "Notify remote video orientation failed",
+                                )
+                            });
+                            self.notify_spawn(orientation_future);
+                        } else {
+                            info!(
+                                "id: {}: Ignoring event: {}, from inactive connection.",
+                                connection_id, event
+                            );
+                        }
+                    }
+                    _ => {
+                        self.ignore_connection_event(connection_id, state, event);
+                    }
+                }
+                Ok(())
+            }
+            ObserverEvent::ReceivedDtmf(tone) => {
+                match state {
+                    CallState::Connected | CallState::Reconnecting => {
+                        if call.active_device_id()? == remote_device {
+                            let mut err_call = call.clone();
+                            let dtmf_future = lazy(move || {
+                                if call.terminating()? {
+                                    return Ok(());
+                                }
+                                call.received_dtmf(remote_device, tone)
+                            })
+                            .map_err(move |err| {
+                                err_call.inject_internal_error(err, "Notify received DTMF failed")
+                            });
+                            self.notify_spawn(dtmf_future);
+                        } else {
+                            info!(
+                                "id: {}: Ignoring event: {}, from inactive connection.",
+                                connection_id, event
+                            );
+                        }
+                    }
+                    _ => {
+                        self.ignore_connection_event(connection_id, state, event);
+                    }
+                }
+                Ok(())
+            }
+            ObserverEvent::IceRouteChanged => {
+                match state {
+                    CallState::Connected | CallState::Reconnecting => {
+                        if call.active_device_id()? == remote_device {
+                            let mut err_call = call.clone();
+                            let route_future = lazy(move || {
+                                if call.terminating()? {
+                                    return Ok(());
+                                }
+                                call.ice_route_changed(remote_device)
+                            })
+                            .map_err(move |err| {
+                                err_call
+                                    .inject_internal_error(err, "Notify ICE route change failed")
+                            });
+                            self.notify_spawn(route_future);
+                        } else {
+                            info!(
+                                "id: {}: Ignoring event: {}, from inactive connection.",
+                                connection_id, event
+                            );
+                        }
+                    }
+                    _ => {
+                        self.ignore_connection_event(connection_id, state, event);
+                    }
+                }
+                Ok(())
+            }
+            ObserverEvent::NetworkRouteChanged => {
+                match state {
+                    CallState::Connected | CallState::Reconnecting => {
+                        if call.active_device_id()? == remote_device {
+                            let mut err_call = call.clone();
+                            let network_route_future = lazy(move || {
+                                if call.terminating()? {
+                                    return Ok(());
+                                }
+                                call.network_route_changed()
+                            })
+                            .map_err(move |err| {
+                                err_call.inject_internal_error(
+                                    err,
+                                    "Notify network route change failed",
+                                )
+                            });
+                            self.notify_spawn(network_route_future);
+                        } else {
+                            info!(
+                                "id: {}: Ignoring event: {}, from inactive connection.",
+                                connection_id, event
+                            );
+                        }
+                    }
+                    _ => {
+                        self.ignore_connection_event(connection_id, state, event);
+                    }
+                }
+                Ok(())
+            }
+            ObserverEvent::IceGatheringStateChanged(gathering_state) => {
+                // While the call is being set up every connection is
+                // gathering, afterwards only the active one matters.
+                let report = match state {
+                    CallState::Starting | CallState::Connecting | CallState::Ringing => true,
+                    CallState::Connected | CallState::Reconnecting => {
+                        call.active_device_id()? == remote_device
+                    }
+                    _ => {
+                        self.ignore_connection_event(connection_id, state, event);
+                        return Ok(());
+                    }
+                };
+                if report {
+                    let mut err_call = call.clone();
+                    let gathering_future = lazy(move || {
+                        if call.terminating()? {
+                            return Ok(());
+                        }
+                        call.ice_gathering_state_changed(remote_device, gathering_state)
+                    })
+                    .map_err(move |err| {
+                        err_call.inject_internal_error(err, "Notify ICE gathering state failed")
+                    });
+                    self.notify_spawn(gathering_future);
+                } else {
+                    info!(
+                        "id: {}: Ignoring event: {}, from inactive connection.",
+                        connection_id, event
+                    );
+                }
+                Ok(())
+            }
+            ObserverEvent::DataChannelBufferedAmountLow => {
+                match state {
+                    CallState::Connected | CallState::Reconnecting => {
+                        if call.active_device_id()? == remote_device {
+                            let mut err_call = call.clone();
+                            let buffered_amount_low_future = lazy(move || {
+                                if call.terminating()? {
+                                    return Ok(());
+                                }
+                                call.data_channel_buffered_amount_low(remote_device)
+                            })
+                            .map_err(move |err| {
+                                err_call.inject_internal_error(
+                                    err,
+                                    "Notify data channel buffered amount low failed",
+                                )
+                            });
+                            self.notify_spawn(buffered_amount_low_future);
+                        } else {
+                            info!(
+                                "id: {}: Ignoring event: {}, from inactive connection.",
+                                connection_id, event
+                            );
+                        }
+                    }
+                    _ => {
+                        self.ignore_connection_event(connection_id, state, event);
+                    }
+                }
+                Ok(())
+            }
+            ObserverEvent::CodecFallback => {
+                match state {
+                    CallState::Connecting => {
+                        self.notify_application(call, ApplicationEvent::CodecFallback)
+                    }
+                    _ => {
+                        self.ignore_connection_event(connection_id, state, event);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_internal_error(&mut self, call: Call<T>, error: failure::Error) -> Result<()> {
+        info!("handle_internal_error():");
+
+        let internal_error_future =
+            lazy(move || call.internal_error(error)).map_err(move |err: failure::Error| {
+                error!("Processing internal error future failed: {}", err);
+                // Nothing else to do here
+            });
 
         self.worker_spawn(internal_error_future);
         Ok(())
@@ -703,18 +1576,144 @@ where
 
         match state {
             CallState::Connected | CallState::Reconnecting => {} // Ok
-            _ => {
+            _ => self.conclude_timed_out_call(call),
+        }
+        Ok(())
+    }
+
+    fn handle_ice_gathering_timeout(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        info!("handle_ice_gathering_timeout():");
+
+        match state {
+            CallState::Starting | CallState::Connecting => self.conclude_timed_out_call(call),
+            _ => {} // Ok
+        }
+        Ok(())
+    }
+
+    fn handle_ring_timeout(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        info!("handle_ring_timeout():");
+
+        if let CallState::Ringing = state {
+            self.conclude_timed_out_call(call);
+        }
+        Ok(())
+    }
+
+    fn handle_ice_servers_expiring(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        info!("handle_ice_servers_expiring():");
+
+        // The servers may have been replaced since the timeout
+        // started.
+        if let CallState::Connecting
+        | CallState::Ringing
+        | CallState::Connected
+        | CallState::Reconnecting = state
+        {
+            if call.ice_servers_expiring()? {
+                self.notify_application(call, ApplicationEvent::IceServersExpiring);
+            }
+        }
+        Ok(())
+    }
+
+    fn conclude_timed_out_call(&mut self, call: Call<T>) {
+        let mut err_call = call.clone();
+        let timeout_future = lazy(move || {
+            let mut call_manager = call.call_manager()?;
+            call_manager.timeout(call.call_id())
+        })
+        .map_err(move |err| err_call.inject_internal_error(err, "Processing call timeout failed"));
+
+        self.worker_spawn(timeout_future);
+    }
+
+    fn handle_call_duration_tick(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        match state {
+            CallState::Connected | CallState::Reconnecting => {
+                let mut err_call = call.clone();
+                let tick_future = lazy(move || {
+                    // The call may have concluded since the tick was
+                    // queued.
+                    match call.state()? {
+                        CallState::Connected | CallState::Reconnecting => call.call_duration_tick(),
+                        _ => Ok(()),
+                    }
+                })
+                .map_err(move |err| {
+                    err_call.inject_internal_error(err, "Notify call duration tick failed")
+                });
+
+                self.notify_spawn(tick_future);
+            }
+            // A tick may be queued just before the call ends.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_stats_tick(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        match state {
+            CallState::Connected | CallState::Reconnecting => {
                 let mut err_call = call.clone();
-                let timeout_future = lazy(move || {
-                    let mut call_manager = call.call_manager()?;
-                    call_manager.timeout(call.call_id())
+                // Collecting the stats waits on WebRTC, so keep it off
+                // the notify thread.
+                let stats_future = lazy(move || match call.state()? {
+                    CallState::Connected | CallState::Reconnecting => call.report_stats(),
+                    _ => Ok(()),
+                })
+                .map_err(move |err| err_call.inject_internal_error(err, "Report stats failed"));
+
+                self.worker_spawn(stats_future);
+            }
+            // A tick may be queued just before the call ends.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_audio_levels_tick(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        match state {
+            CallState::Connected | CallState::Reconnecting => {
+                let mut err_call = call.clone();
+                // Sampling the levels waits on WebRTC, so keep it off
+                // the notify thread.
+                let levels_future = lazy(move || match call.state()? {
+                    CallState::Connected | CallState::Reconnecting => call.report_audio_levels(),
+                    _ => Ok(()),
+                })
+                .map_err(move |err| {
+                    err_call.inject_internal_error(err, "Report audio levels failed")
+                });
+
+                self.worker_spawn(levels_future);
+            }
+            // A tick may be queued just before the call ends.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_bandwidth_estimate_tick(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        match state {
+            CallState::Connected | CallState::Reconnecting => {
+                let mut err_call = call.clone();
+                // Sampling the estimate waits on WebRTC, so keep it off
+                // the notify thread.
+                let estimate_future = lazy(move || match call.state()? {
+                    CallState::Connected | CallState::Reconnecting => {
+                        call.report_bandwidth_estimate()
+                    }
+                    _ => Ok(()),
                 })
                 .map_err(move |err| {
-                    err_call.inject_internal_error(err, "Processing call timeout failed")
+                    err_call.inject_internal_error(err, "Report bandwidth estimate failed")
                 });
 
-                self.worker_spawn(timeout_future);
+                self.worker_spawn(estimate_future);
             }
+            // A tick may be queued just before the call ends.
+            _ => {}
         }
         Ok(())
     }
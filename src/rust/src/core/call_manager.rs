@@ -12,32 +12,114 @@ use std::fmt;
 use std::stringify;
 use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use futures::future::lazy;
 use futures::Future;
 use tokio::runtime;
+use tokio::timer::Delay;
 
 use crate::common::{
     ApplicationEvent,
+    AudioDeviceError,
     CallDirection,
     CallId,
     CallState,
     ConnectionId,
     DeviceId,
+    HangupType,
     Result,
 };
-use crate::core::call::Call;
+use crate::core::audio_config::AudioConfig;
+use crate::core::audio_route::AudioRoute;
+use crate::core::bandwidth_mode::BandwidthMode;
+use crate::core::call::{
+    Call,
+    DeviceSelectionPolicy,
+    FirstToConnect,
+    ForkCandidate,
+    ForkingPolicy,
+    InOrderSelection,
+};
+use crate::core::call_config::CallConfig;
+use crate::core::call_failure::CallFailureReason;
 use crate::core::call_mutex::CallMutex;
+use crate::core::call_record::CallRecord;
+use crate::core::call_stats::CallStats;
+use crate::core::cancellation::SetupHandle;
+use crate::core::codec::{merge_capabilities, CodecCapability};
 use crate::core::connection::Connection;
+use crate::core::connection_config::{BundlePolicy, ConnectionConfig, RtcpMuxPolicy};
+use crate::core::group_call::{GroupCall, ParticipantId, ParticipantState};
+use crate::core::ice_candidate_policy::IceCandidatePolicy;
+use crate::core::ice_encoding::{decode_ice_candidates, encode_ice_candidates};
+use crate::core::ice_server::{order_by_transport, IceServerConfig, DEFAULT_TURN_TRANSPORT_ORDER};
+use crate::core::media_descriptor::MediaDescriptor;
+use crate::core::offer_metadata::OfferMetadata;
+use crate::core::pending_signaling::{PendingMessage, PendingSignaling};
 use crate::core::platform::Platform;
+use crate::core::recording::{RecordingConfig, RecordingSink};
+use crate::core::route::NetworkRoute;
+use crate::core::sequencing::{
+    EarlyIceCandidates,
+    ReceivedMessage,
+    ReceivedMessages,
+    ReceivedSequences,
+};
+use crate::core::signaling::{self, Answer, Offer, COMPRESSION_PROTOCOL_VERSION};
+use crate::core::signaling_transport::{PlatformTransport, SignalingTransport};
+#[cfg(feature = "srtp_key_export")]
+use crate::core::srtp_keys::SrtpKeys;
+use crate::core::trace::TraceEvent;
 use crate::error::RingRtcError;
 
-use crate::webrtc::ice_candidate::IceCandidate;
+use crate::webrtc::frame_crypto::MediaCryptoProvider;
+use crate::webrtc::ice_candidate::{IceCandidate, IceRoute};
 use crate::webrtc::media_stream::MediaStream;
+use crate::webrtc::peer_connection_observer::IceGatheringState;
 use crate::webrtc::sdp_observer::SessionDescriptionInterface;
 
-const TIME_OUT_PERIOD: u64 = 120;
+/// Default period, in seconds, to wait for the answer to a
+/// renegotiation offer.
+const RENEGOTIATION_TIME_OUT_PERIOD: u64 = 10;
+
+/// Default number of ICE restarts that must fail before the
+/// connection of a reconnecting call is hard reset.
+const ICE_RESTARTS_BEFORE_HARD_RESET: u32 = 1;
+
+/// Default number of automatic ICE restarts attempted while a
+/// connected call is reconnecting.
+const RECOVERY_ATTEMPTS: u32 = 3;
+
+/// Default period, in milliseconds, before the first automatic ICE
+/// restart, doubling for each further attempt.
+const RECOVERY_BACKOFF_PERIOD: u64 = 1000;
+
+/// Default maximum length, in bytes, of an SDP handed to the
+/// application for sending.
+const MAX_SDP_LENGTH: usize = 128 * 1024;
+
+/// Default maximum age, in seconds, of a received offer.
+const MAX_OFFER_AGE_PERIOD: u64 = 120;
+
+/// Default maximum age, in seconds, of received ICE candidates.
+const MAX_ICE_CANDIDATE_AGE_PERIOD: u64 = 60;
+
+/// Default period, in milliseconds, an ICE route must hold before a
+/// change between direct and relayed is reported.
+const ROUTE_CHANGE_DEBOUNCE_PERIOD: u64 = 2000;
+
+/// Maximum number of active calls, i.e. the foreground call and one
+/// waiting or held call.
+const MAX_ACTIVE_CALLS: usize = 2;
+
+/// Maximum period, in milliseconds, a shutdown waits for the hangup
+/// messages to be sent and the calls to be torn down.
+const SHUTDOWN_DRAIN_TIME_OUT_PERIOD: u64 = 5000;
+
+/// Period, in milliseconds, at which a shutdown checks whether the
+/// call manager has drained.
+const SHUTDOWN_DRAIN_POLL_PERIOD: u64 = 10;
 
 /// Spawns a task on the worker runtime thread to handle an API
 /// request with error handling.
@@ -79,6 +161,22 @@ macro_rules! check_active_call {
             }
         }
     };
+    (
+        $s:ident,
+        $call_id:expr,
+        $f:expr
+    ) => {
+        match $s.active_call_by_id($call_id) {
+            Ok(v) => {
+                info!("{}(): call_id: {}", $f, v.call_id());
+                v
+            }
+            _ => {
+                info!("{}(): skipping inactive call_id: {}", $f, $call_id);
+                return Ok(());
+            }
+        }
+    };
 }
 
 /// Spawns a task on the worker runtime thread to handle an API
@@ -106,11 +204,13 @@ macro_rules! handle_api {
 #[derive(Debug, PartialEq)]
 pub enum SignalingMessageType {
     None,
+    PreOffer,
     Offer,
     Answer,
     Ice,
     Hangup,
     Busy,
+    Ringing,
 }
 
 /// A structure to hold messages in the message_queue, identified by their CallId.
@@ -124,8 +224,16 @@ where
     message_type:    SignalingMessageType,
     /// The closure to be called which will send the message.
     message_closure: Box<dyn Fn(&CallManager<T>) -> Result<()> + Send>,
+    /// The closure returning the message for the application to
+    /// persist while it waits to be sent, if any.
+    pending_closure: Option<PendingClosure<T>>,
 }
 
+/// Returns a queued message for the application to persist, `None`
+/// if it no longer needs sending.
+type PendingClosure<T> =
+    Box<dyn Fn() -> Result<Option<PendingSignaling<<T as Platform>::AppRemotePeer>>> + Send>;
+
 /// A structure implementing a message queue used to control the
 /// timing of sending Signaling messages. This helps ensure that
 /// messages are sent with the same cadence that they can actually
@@ -143,6 +251,9 @@ where
     /// of being sent). We will only send one at a time to the
     /// application.
     messages_in_flight:     bool,
+    /// Whether the messages last handed to the application to persist
+    /// included any.
+    persisted:              bool,
 }
 
 impl<T> SignalingMessageQueue<T>
@@ -155,24 +266,121 @@ where
             queue:                  VecDeque::new(),
             last_sent_message_type: SignalingMessageType::None,
             messages_in_flight:     false,
+            persisted:              false,
         })
     }
 }
 
+/// An outgoing call that ended busy, waiting to call the remote peer
+/// again.
+struct BusyRetry<T>
+where
+    T: Platform,
+{
+    /// CallId of the call that ended busy.
+    call_id:     CallId,
+    /// The busy remote peer.
+    remote_peer: <T as Platform>::AppRemotePeer,
+    /// The config of the new call, with one attempt less.
+    config:      CallConfig,
+}
+
 pub struct CallManager<T>
 where
     T: Platform,
 {
     /// Interface to platform specific methods.
-    platform:       Arc<CallMutex<T>>,
+    platform:              Arc<CallMutex<T>>,
     /// Map of all calls, indexed by CallId.
-    call_map:       Arc<CallMutex<HashMap<CallId, Call<T>>>>,
-    /// CallId of the active call.
-    active_call_id: Arc<CallMutex<Option<CallId>>>,
+    call_map:              Arc<CallMutex<HashMap<CallId, Call<T>>>>,
+    /// CallIds of the active calls, the foreground call first.
+    active_call_ids:       Arc<CallMutex<Vec<CallId>>>,
     /// Tokio runtime for back ground task execution.
-    worker_runtime: Arc<CallMutex<Option<runtime::Runtime>>>,
+    worker_runtime:        Arc<CallMutex<Option<runtime::Runtime>>>,
     /// Signaling message queue.
-    message_queue:  Arc<CallMutex<SignalingMessageQueue<T>>>,
+    message_queue:         Arc<CallMutex<SignalingMessageQueue<T>>>,
+    /// Advisory codec preferences, highest priority first.
+    codec_preferences:     Arc<CallMutex<Vec<String>>>,
+    /// How long to wait for the answer to a renegotiation offer.
+    renegotiation_timeout: Arc<CallMutex<Duration>>,
+    /// Failed ICE restarts tolerated before a hard reset.
+    ice_restart_limit:     Arc<CallMutex<u32>>,
+    /// Automatic ICE restarts attempted while reconnecting.
+    recovery_attempts:     Arc<CallMutex<u32>>,
+    /// Delay before the first automatic ICE restart.
+    recovery_backoff:      Arc<CallMutex<Duration>>,
+    /// Maximum length of an SDP handed to the application.
+    max_sdp_length:        Arc<CallMutex<usize>>,
+    /// Maximum age of a received offer that may still ring.
+    max_offer_age:         Arc<CallMutex<Duration>>,
+    /// Maximum age of received ICE candidates that are still added.
+    max_ice_candidate_age: Arc<CallMutex<Duration>>,
+    /// How long an ICE route must hold before a change is reported.
+    route_change_debounce: Arc<CallMutex<Duration>>,
+    /// Whether the application is notified of the call duration
+    /// every second.
+    call_duration_ticks:   Arc<CallMutex<bool>>,
+    /// Whether new calls record a trace.
+    tracing:               Arc<CallMutex<bool>>,
+    /// How often the application is sent the stats of the active
+    /// call, if at all.
+    stats_interval:        Arc<CallMutex<Option<Duration>>>,
+    /// How often the application is sent the audio levels of the
+    /// active call, if at all.
+    audio_levels_interval: Arc<CallMutex<Option<Duration>>>,
+    /// How often the application is sent the bandwidth estimate of
+    /// the active call, if at all.
+    bandwidth_interval:    Arc<CallMutex<Option<Duration>>>,
+    /// How long the media of the active call may stop arriving
+    /// before the application is told, if at all.
+    media_stall_timeout:   Arc<CallMutex<Option<Duration>>>,
+    /// Whether the active call ends when the audio device fails.
+    end_on_audio_failure:  Arc<CallMutex<bool>>,
+    /// Whether an incoming call may wait while another call is
+    /// connected, instead of being busy.
+    call_waiting:          Arc<CallMutex<bool>>,
+    /// Configuration of new PeerConnections.
+    connection_config:     Arc<CallMutex<ConnectionConfig>>,
+    /// Which ICE candidates new connections exchange.
+    ice_candidate_policy:  Arc<CallMutex<IceCandidatePolicy>>,
+    /// Maximum number of connections of an outgoing call, if limited.
+    max_connections:       Arc<CallMutex<Option<usize>>>,
+    /// Chooses the remote devices of an outgoing call within the
+    /// connection limit.
+    device_selection:      Arc<CallMutex<Box<dyn DeviceSelectionPolicy>>>,
+    /// Chooses which remote device wins a forked outgoing call.
+    forking_policy:        Arc<CallMutex<Box<dyn ForkingPolicy>>>,
+    /// Map of all group calls, indexed by CallId.
+    group_calls:           Arc<CallMutex<HashMap<CallId, GroupCall<T>>>>,
+    /// The busy call waiting to call the remote peer again, if any.
+    busy_retry:            Arc<CallMutex<Option<BusyRetry<T>>>>,
+    /// Whether a CallId chosen by the application that collides
+    /// with a live call is replaced, instead of failing.
+    regenerate_call_ids:   Arc<CallMutex<bool>>,
+    /// Whether offers and answers are sent as opaque bytes.
+    opaque_signaling:      Arc<CallMutex<bool>>,
+    /// Whether offers are compressed before the remote peer's
+    /// protocol version is known.
+    sdp_compression:       Arc<CallMutex<bool>>,
+    /// Whether outgoing calls send a pre-offer.
+    pre_offer:             Arc<CallMutex<bool>>,
+    /// How long local ICE candidates are batched before sending.
+    ice_batch_period:      Arc<CallMutex<Duration>>,
+    /// The sequence numbers of the received opaque messages.
+    received_sequences:    Arc<CallMutex<ReceivedSequences>>,
+    /// The offers and hangups recently received.
+    received_messages:     Arc<CallMutex<ReceivedMessages>>,
+    /// ICE candidates received before the offer of their call.
+    early_ice_candidates:  Arc<CallMutex<EarlyIceCandidates>>,
+    /// Carries the signaling messages to the remote peers.
+    signaling_transport:   Arc<CallMutex<Box<dyn SignalingTransport<T>>>>,
+    /// Encrypts the media frames of new connections end-to-end, if
+    /// set.
+    media_crypto_provider: Arc<CallMutex<Option<Arc<dyn MediaCryptoProvider>>>>,
+    /// The audio processing of calls without one of their own.
+    default_audio_config:  Arc<CallMutex<AudioConfig>>,
+    /// Where the application plays the audio of calls.
+    audio_route:           Arc<CallMutex<AudioRoute>>,
 }
 
 impl<T> fmt::Display for CallManager<T>
@@ -184,16 +392,16 @@ where
             Ok(v) => format!("{}", v),
             Err(_) => "unavailable".to_string(),
         };
-        let active_call_id = match self.active_call_id.lock() {
+        let active_call_ids = match self.active_call_ids.lock() {
             Ok(v) => format!("{:?}", v),
             Err(_) => "unavailable".to_string(),
         };
         write!(
             f,
-            "thread: {:?}, platform: ({}), active_call_id: ({})",
+            "thread: {:?}, platform: ({}), active_call_ids: ({})",
             thread::current().id(),
             platform,
-            active_call_id
+            active_call_ids
         )
     }
 }
@@ -226,11 +434,47 @@ where
 {
     fn clone(&self) -> Self {
         Self {
-            platform:       Arc::clone(&self.platform),
-            call_map:       Arc::clone(&self.call_map),
-            active_call_id: Arc::clone(&self.active_call_id),
-            worker_runtime: Arc::clone(&self.worker_runtime),
-            message_queue:  Arc::clone(&self.message_queue),
+            platform:              Arc::clone(&self.platform),
+            call_map:              Arc::clone(&self.call_map),
+            active_call_ids:       Arc::clone(&self.active_call_ids),
+            worker_runtime:        Arc::clone(&self.worker_runtime),
+            message_queue:         Arc::clone(&self.message_queue),
+            codec_preferences:     Arc::clone(&self.codec_preferences),
+            renegotiation_timeout: Arc::clone(&self.renegotiation_timeout),
+            ice_restart_limit:     Arc::clone(&self.ice_restart_limit),
+            recovery_attempts:     Arc::clone(&self.recovery_attempts),
+            recovery_backoff:      Arc::clone(&self.recovery_backoff),
+            max_sdp_length:        Arc::clone(&self.max_sdp_length),
+            max_offer_age:         Arc::clone(&self.max_offer_age),
+            max_ice_candidate_age: Arc::clone(&self.max_ice_candidate_age),
+            route_change_debounce: Arc::clone(&self.route_change_debounce),
+            call_duration_ticks:   Arc::clone(&self.call_duration_ticks),
+            tracing:               Arc::clone(&self.tracing),
+            stats_interval:        Arc::clone(&self.stats_interval),
+            audio_levels_interval: Arc::clone(&self.audio_levels_interval),
+            bandwidth_interval:    Arc::clone(&self.bandwidth_interval),
+            media_stall_timeout:   Arc::clone(&self.media_stall_timeout),
+            end_on_audio_failure:  Arc::clone(&self.end_on_audio_failure),
+            call_waiting:          Arc::clone(&self.call_waiting),
+            connection_config:     Arc::clone(&self.connection_config),
+            ice_candidate_policy:  Arc::clone(&self.ice_candidate_policy),
+            max_connections:       Arc::clone(&self.max_connections),
+            device_selection:      Arc::clone(&self.device_selection),
+            forking_policy:        Arc::clone(&self.forking_policy),
+            group_calls:           Arc::clone(&self.group_calls),
+            busy_retry:            Arc::clone(&self.busy_retry),
+            regenerate_call_ids:   Arc::clone(&self.regenerate_call_ids),
+            opaque_signaling:      Arc::clone(&self.opaque_signaling),
+            sdp_compression:       Arc::clone(&self.sdp_compression),
+            pre_offer:             Arc::clone(&self.pre_offer),
+            ice_batch_period:      Arc::clone(&self.ice_batch_period),
+            received_sequences:    Arc::clone(&self.received_sequences),
+            received_messages:     Arc::clone(&self.received_messages),
+            early_ice_candidates:  Arc::clone(&self.early_ice_candidates),
+            signaling_transport:   Arc::clone(&self.signaling_transport),
+            media_crypto_provider: Arc::clone(&self.media_crypto_provider),
+            default_audio_config:  Arc::clone(&self.default_audio_config),
+            audio_route:           Arc::clone(&self.audio_route),
         }
     }
 }
@@ -248,10 +492,10 @@ where
     /// Create a new CallManager.
     pub fn new(platform: T) -> Result<Self> {
         Ok(Self {
-            platform:       Arc::new(CallMutex::new(platform, "platform")),
-            call_map:       Arc::new(CallMutex::new(HashMap::new(), "hash_map")),
-            active_call_id: Arc::new(CallMutex::new(None, "active_call_id")),
-            worker_runtime: Arc::new(CallMutex::new(
+            platform:              Arc::new(CallMutex::new(platform, "platform")),
+            call_map:              Arc::new(CallMutex::new(HashMap::new(), "hash_map")),
+            active_call_ids:       Arc::new(CallMutex::new(Vec::new(), "active_call_ids")),
+            worker_runtime:        Arc::new(CallMutex::new(
                 Some(
                     runtime::Builder::new()
                         .core_threads(1)
@@ -260,53 +504,213 @@ where
                 ),
                 "worker_runtime",
             )),
-            message_queue:  Arc::new(CallMutex::new(
+            message_queue:         Arc::new(CallMutex::new(
                 SignalingMessageQueue::new()?,
                 "message_queue",
             )),
+            codec_preferences:     Arc::new(CallMutex::new(Vec::new(), "codec_preferences")),
+            renegotiation_timeout: Arc::new(CallMutex::new(
+                Duration::from_secs(RENEGOTIATION_TIME_OUT_PERIOD),
+                "renegotiation_timeout",
+            )),
+            ice_restart_limit:     Arc::new(CallMutex::new(
+                ICE_RESTARTS_BEFORE_HARD_RESET,
+                "ice_restart_limit",
+            )),
+            recovery_attempts:     Arc::new(CallMutex::new(RECOVERY_ATTEMPTS, "recovery_attempts")),
+            recovery_backoff:      Arc::new(CallMutex::new(
+                Duration::from_millis(RECOVERY_BACKOFF_PERIOD),
+                "recovery_backoff",
+            )),
+            max_sdp_length:        Arc::new(CallMutex::new(MAX_SDP_LENGTH, "max_sdp_length")),
+            max_offer_age:         Arc::new(CallMutex::new(
+                Duration::from_secs(MAX_OFFER_AGE_PERIOD),
+                "max_offer_age",
+            )),
+            max_ice_candidate_age: Arc::new(CallMutex::new(
+                Duration::from_secs(MAX_ICE_CANDIDATE_AGE_PERIOD),
+                "max_ice_candidate_age",
+            )),
+            route_change_debounce: Arc::new(CallMutex::new(
+                Duration::from_millis(ROUTE_CHANGE_DEBOUNCE_PERIOD),
+                "route_change_debounce",
+            )),
+            call_duration_ticks:   Arc::new(CallMutex::new(false, "call_duration_ticks")),
+            tracing:               Arc::new(CallMutex::new(true, "tracing")),
+            stats_interval:        Arc::new(CallMutex::new(None, "stats_interval")),
+            audio_levels_interval: Arc::new(CallMutex::new(None, "audio_levels_interval")),
+            bandwidth_interval:    Arc::new(CallMutex::new(None, "bandwidth_interval")),
+            media_stall_timeout:   Arc::new(CallMutex::new(None, "media_stall_timeout")),
+            end_on_audio_failure:  Arc::new(CallMutex::new(false, "end_on_audio_failure")),
+            call_waiting:          Arc::new(CallMutex::new(false, "call_waiting")),
+            connection_config:     Arc::new(CallMutex::new(
+                ConnectionConfig::default(),
+                "connection_config",
+            )),
+            ice_candidate_policy:  Arc::new(CallMutex::new(
+                IceCandidatePolicy::default(),
+                "ice_candidate_policy",
+            )),
+            max_connections:       Arc::new(CallMutex::new(None, "max_connections")),
+            device_selection:      Arc::new(CallMutex::new(
+                Box::new(InOrderSelection),
+                "device_selection",
+            )),
+            forking_policy:        Arc::new(CallMutex::new(
+                Box::new(FirstToConnect),
+                "forking_policy",
+            )),
+            group_calls:           Arc::new(CallMutex::new(HashMap::new(), "group_calls")),
+            busy_retry:            Arc::new(CallMutex::new(None, "busy_retry")),
+            regenerate_call_ids:   Arc::new(CallMutex::new(false, "regenerate_call_ids")),
+            opaque_signaling:      Arc::new(CallMutex::new(false, "opaque_signaling")),
+            sdp_compression:       Arc::new(CallMutex::new(false, "sdp_compression")),
+            pre_offer:             Arc::new(CallMutex::new(false, "pre_offer")),
+            ice_batch_period:      Arc::new(CallMutex::new(
+                Duration::from_secs(0),
+                "ice_batch_period",
+            )),
+            received_sequences:    Arc::new(CallMutex::new(
+                ReceivedSequences::default(),
+                "received_sequences",
+            )),
+            received_messages:     Arc::new(CallMutex::new(
+                ReceivedMessages::default(),
+                "received_messages",
+            )),
+            early_ice_candidates:  Arc::new(CallMutex::new(
+                EarlyIceCandidates::default(),
+                "early_ice_candidates",
+            )),
+            signaling_transport:   Arc::new(CallMutex::new(
+                Box::new(PlatformTransport),
+                "signaling_transport",
+            )),
+            media_crypto_provider: Arc::new(CallMutex::new(None, "media_crypto_provider")),
+            default_audio_config:  Arc::new(CallMutex::new(
+                AudioConfig::default(),
+                "default_audio_config",
+            )),
+            audio_route:           Arc::new(CallMutex::new(AudioRoute::default(), "audio_route")),
         })
     }
 
-    /// Create an outgoing call.
-    pub fn call(&mut self, remote_peer: <T as Platform>::AppRemotePeer) -> Result<()> {
+    /// Create an outgoing call, with the timeouts of `config`.
+    pub fn call(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        config: CallConfig,
+    ) -> Result<()> {
         info!("API:call():");
 
+        self.spawn_call(remote_peer, CallId::secure_random(), config)
+    }
+
+    /// Create an outgoing call identified by the `call_id` of the
+    /// application, with the timeouts of `config`.
+    ///
+    /// A `call_id` that already belongs to a live call fails with
+    /// `RingRtcError::CallIdCollision`, unless
+    /// `set_regenerate_call_ids()` enabled replacing it with a new
+    /// random CallId.  Returns the CallId of the new call.
+    pub fn call_with_id(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        config: CallConfig,
+    ) -> Result<CallId> {
+        info!("API:call_with_id(): call_id: {}", call_id);
+
+        let call_id = self.unique_call_id(call_id)?;
+        self.spawn_call(remote_peer, call_id, config)?;
+        Ok(call_id)
+    }
+
+    /// Spawn the creation of an outgoing call.
+    fn spawn_call(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        config: CallConfig,
+    ) -> Result<()> {
         let mut call_manager = self.clone();
         let mut cm_error = self.clone();
         let remote_peer_error = remote_peer.clone();
-        let future = lazy(move || call_manager.handle_call(remote_peer)).map_err(move |err| {
-            error!("Handle call failed: {}", err);
-            cm_error.internal_create_api_error(&remote_peer_error, err);
-        });
+        let future = lazy(move || call_manager.handle_call(remote_peer, call_id, config)).map_err(
+            move |err| {
+                error!("Handle call failed: {}", err);
+                cm_error.internal_create_api_error(&remote_peer_error, call_id, err);
+            },
+        );
         self.worker_spawn(future)
     }
 
     /// Accept an incoming call.
-    pub fn accept_call(&mut self, call_id: CallId) -> Result<()> {
-        handle_active_call_api!(self, CallManager::handle_accept_call, call_id)
+    ///
+    /// Accepting a waiting call brings it to the foreground and puts
+    /// the previous foreground call on hold.  The returned handle can
+    /// abort the call until it connects.
+    pub fn accept_call(&mut self, call_id: CallId) -> Result<SetupHandle<T>> {
+        handle_active_call_api!(self, CallManager::handle_accept_call, call_id)?;
+        Ok(SetupHandle::new(call_id, self.clone()))
     }
 
-    /// Drop the active call.
+    /// Drop an active call.
     pub fn drop_call(&mut self, call_id: CallId) -> Result<()> {
         handle_active_call_api!(self, CallManager::handle_drop_call, call_id)
     }
 
-    /// Proceed with the outgoing call.
+    /// Proceed with the call setup.
+    ///
+    /// The connections use `ice_servers` as the STUN and TURN
+    /// servers, if any are given, see `update_ice_servers()`.  The
+    /// returned handle can abort the call until it connects.
     pub fn proceed(
         &mut self,
         call_id: CallId,
         app_call_context: <T as Platform>::AppCallContext,
         remote_devices: Vec<DeviceId>,
-    ) -> Result<()> {
+        ice_servers: Vec<IceServerConfig>,
+    ) -> Result<SetupHandle<T>> {
         handle_active_call_api!(
             self,
             CallManager::handle_proceed,
             call_id,
             app_call_context,
-            remote_devices
+            remote_devices,
+            ice_servers
+        )?;
+        Ok(SetupHandle::new(call_id, self.clone()))
+    }
+
+    /// Replace the STUN and TURN servers of the call, e.g. with new
+    /// TURN credentials once `ApplicationEvent::IceServersExpiring`
+    /// is reported.
+    ///
+    /// The servers are ordered by transport, see
+    /// `order_by_transport()`, and used for candidates gathered from
+    /// now on, without restarting ICE.
+    pub fn update_ice_servers(
+        &mut self,
+        call_id: CallId,
+        ice_servers: Vec<IceServerConfig>,
+    ) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_update_ice_servers,
+            call_id,
+            ice_servers
         )
     }
 
+    /// Abort the setup of an active call, ending it with
+    /// `ApplicationEvent::EndedSetupCancelled`.
+    ///
+    /// Calls that already connected are not affected.
+    pub fn cancel_setup(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_cancel_setup, call_id)
+    }
+
     /// OK for the library to continue to send signaling messages.
     pub fn message_sent(&mut self, call_id: CallId) -> Result<()> {
         handle_active_call_api!(self, CallManager::handle_message_sent, call_id)
@@ -317,36 +721,113 @@ where
         handle_active_call_api!(self, CallManager::handle_message_send_failure, call_id)
     }
 
-    /// Local hangup of the active call.
+    /// Local hangup of the foreground call.
+    ///
+    /// Also cancels calling a busy remote peer again.
     pub fn hangup(&mut self) -> Result<()> {
         handle_active_call_api!(self, CallManager::handle_hangup)
     }
 
+    /// Received pre-offer from application.
+    ///
+    /// Tells the application, with `Platform::on_received_pre_offer()`,
+    /// to warm up for the incoming call whose offer is on its way,
+    /// unless the offer arrived first.
+    pub fn received_pre_offer(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!("API:received_pre_offer(): id: {}", connection_id);
+
+        let call_manager = self.clone();
+        let future =
+            lazy(move || call_manager.handle_received_pre_offer(remote_peer, connection_id))
+                .map_err(|err| error!("Handle received pre-offer failed: {}", err));
+        self.worker_spawn(future)
+    }
+
     /// Received SDP offer from application.
+    ///
+    /// The `age` is how long the offer took to be delivered, as
+    /// measured by the application, e.g. from the server timestamps
+    /// of the message.  Offers older than the maximum offer age end
+    /// with `ApplicationEvent::EndedReceivedOfferExpired` instead of
+    /// ringing.
+    ///
+    /// The incoming call, if any, gets the timeouts of `config`.  An
+    /// offer received twice from the same device, other than for
+    /// the active call, is rejected with
+    /// `RingRtcError::ReplayedSignalingMessage`.
     pub fn received_offer(
         &mut self,
         remote_peer: <T as Platform>::AppRemotePeer,
         connection_id: ConnectionId,
         offer: String,
         timestamp: u64,
+        age: Duration,
+        config: CallConfig,
     ) -> Result<()> {
-        info!("API:received_offer():");
+        info!("API:received_offer(): age: {:?}", age);
 
+        // An offer for the active call may renegotiate its
+        // connection, any other offer is only received once.
+        if !self.call_is_active(connection_id.call_id())? {
+            self.check_replay(connection_id, ReceivedMessage::Offer)?;
+        }
+        let offer = signaling::decompress(offer)?;
         let mut call_manager = self.clone();
         let mut cm_error = self.clone();
         let remote_peer_error = remote_peer.clone();
         let future = lazy(move || {
-            call_manager.handle_received_offer(remote_peer, connection_id, offer, timestamp)
+            call_manager.handle_received_offer(
+                remote_peer,
+                connection_id,
+                offer,
+                timestamp,
+                age,
+                config,
+            )
         })
         .map_err(move |err| {
             error!("Handle received offer failed: {}", err);
-            cm_error.internal_create_api_error(&remote_peer_error, err);
+            cm_error.internal_create_api_error(&remote_peer_error, connection_id.call_id(), err);
         });
         self.worker_spawn(future)
     }
 
+    /// Received opaque offer from application.
+    ///
+    /// Handled like `received_offer()`, see
+    /// `set_opaque_signaling()`.
+    pub fn received_opaque_offer(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        opaque: &[u8],
+        timestamp: u64,
+        age: Duration,
+        config: CallConfig,
+    ) -> Result<()> {
+        info!("API:received_opaque_offer(): length: {}", opaque.len());
+
+        let offer = Offer::from_opaque(&self.open_opaque(connection_id, opaque)?)?;
+        if !self.record_sequence(connection_id, offer.sequence)? {
+            return Ok(());
+        }
+        self.received_offer(
+            remote_peer,
+            connection_id,
+            offer.to_wire(),
+            timestamp,
+            age,
+            config,
+        )
+    }
+
     /// Received SDP answer from application.
     pub fn received_answer(&mut self, connection_id: ConnectionId, answer: String) -> Result<()> {
+        let answer = signaling::decompress(answer)?;
         handle_active_call_api!(
             self,
             CallManager::handle_received_answer,
@@ -355,166 +836,1278 @@ where
         )
     }
 
+    /// Received opaque answer from application.
+    ///
+    /// Handled like `received_answer()`, see
+    /// `set_opaque_signaling()`.
+    pub fn received_opaque_answer(
+        &mut self,
+        connection_id: ConnectionId,
+        opaque: &[u8],
+    ) -> Result<()> {
+        info!("API:received_opaque_answer(): length: {}", opaque.len());
+
+        let answer = Answer::from_opaque(&self.open_opaque(connection_id, opaque)?)?;
+        if !self.record_sequence(connection_id, answer.sequence)? {
+            return Ok(());
+        }
+        self.received_answer(connection_id, answer.to_wire())
+    }
+
     /// Received ICE candidates from application.
+    ///
+    /// Candidates that took longer than the maximum age to be
+    /// delivered, see `set_max_ice_candidate_age()`, are discarded.
     pub fn received_ice_candidates(
         &mut self,
         connection_id: ConnectionId,
         ice_candidates: &[IceCandidate],
+        age: Duration,
     ) -> Result<()> {
         let ice_vec: Vec<IceCandidate> = ice_candidates.into();
         handle_active_call_api!(
             self,
             CallManager::handle_received_ice_candidates,
             connection_id,
-            ice_vec
+            ice_vec,
+            age
         )
     }
 
-    /// Received hangup message from application.
-    pub fn received_hangup(&mut self, connection_id: ConnectionId) -> Result<()> {
-        handle_active_call_api!(self, CallManager::handle_received_hangup, connection_id)
-    }
-
-    /// Received busy message from application.
-    pub fn received_busy(&mut self, connection_id: ConnectionId) -> Result<()> {
-        handle_active_call_api!(self, CallManager::handle_received_busy, connection_id)
-    }
-
-    /// Request to reset the Call Manager.
+    /// Received opaque ICE candidates from application.
     ///
-    /// Conclude all calls and clear active callId.  Do not notify the
-    /// application at the conclusion.
-    pub fn reset(&mut self) -> Result<()> {
-        handle_api!(self, CallManager::handle_reset)
+    /// Handled like `received_ice_candidates()`, see
+    /// `set_opaque_signaling()`.
+    pub fn received_opaque_ice_candidates(
+        &mut self,
+        connection_id: ConnectionId,
+        opaque: &[u8],
+        age: Duration,
+    ) -> Result<()> {
+        info!(
+            "API:received_opaque_ice_candidates(): length: {}",
+            opaque.len()
+        );
+
+        let (sequence, ice_candidates) =
+            decode_ice_candidates(&self.open_opaque(connection_id, opaque)?)?;
+        if !self.record_sequence(connection_id, sequence)? {
+            return Ok(());
+        }
+        self.received_ice_candidates(connection_id, &ice_candidates, age)
     }
 
-    /// Close down the Call Manager.
-    ///
-    /// Close down the call manager and all the calls it is currently managing.
-    ///
-    /// This is a blocking call.
-    #[allow(clippy::mutex_atomic)]
-    pub fn close(&mut self) -> Result<()> {
-        info!("close():");
+    /// Open an opaque message received on `connection_id`, if it was
+    /// sealed, see `signaling::seal()`.
+    fn open_opaque(&self, connection_id: ConnectionId, opaque: &[u8]) -> Result<Vec<u8>> {
+        if !signaling::is_sealed(opaque) {
+            return Ok(opaque.to_vec());
+        }
 
-        if self.worker_runtime.lock()?.is_some() {
-            // Clear out any outstanding calls
-            let _ = self.reset();
+        let platform = self.platform.lock()?;
+        signaling::open(&*platform, connection_id, opaque)
+    }
 
-            self.sync_runtime()?;
+    /// Record the sequence number of an opaque message received on
+    /// `connection_id`, returning `false` for a duplicate, which is
+    /// dropped.
+    fn record_sequence(&self, connection_id: ConnectionId, sequence: u64) -> Result<bool> {
+        let recorded = self
+            .received_sequences
+            .lock()?
+            .record(connection_id, sequence);
+        if !recorded {
+            info!(
+                "id: {}, dropping duplicate signaling message, sequence: {}",
+                connection_id, sequence
+            );
+        }
+        Ok(recorded)
+    }
 
-            // close the runtime
-            let _ = self.close_runtime();
-            info!("close(): complete");
-        } else {
-            info!("close(): already closed.");
+    /// Record an offer or hangup received on `connection_id`, failing
+    /// with `RingRtcError::ReplayedSignalingMessage` if the same
+    /// message was received before, see `ReceivedMessages`.
+    fn check_replay(&self, connection_id: ConnectionId, message: ReceivedMessage) -> Result<()> {
+        if self
+            .received_messages
+            .lock()?
+            .record(connection_id, message)
+        {
+            return Ok(());
         }
 
-        Ok(())
+        warn!(
+            "id: {}, rejecting replayed signaling message: {:?}",
+            connection_id, message
+        );
+        Err(RingRtcError::ReplayedSignalingMessage(
+            connection_id.call_id(),
+            connection_id.remote_device(),
+            format!("{:?}", message),
+        )
+        .into())
     }
 
-    /// Returns the active Call
-    pub fn active_call(&self) -> Result<Call<T>> {
-        let active_call_id = self.active_call_id.lock()?;
-        match *active_call_id {
-            Some(call_id) => {
-                let call_map = self.call_map.lock()?;
-                match call_map.get(&call_id) {
-                    Some(call) => Ok(call.clone()),
-                    None => Err(RingRtcError::CallIdNotFound(call_id).into()),
-                }
+    /// Received hangup message from application.
+    ///
+    /// A `Declined` hangup is handled like a received declined
+    /// message.  A hangup received twice from the same device is
+    /// rejected with `RingRtcError::ReplayedSignalingMessage`.
+    pub fn received_hangup(
+        &mut self,
+        connection_id: ConnectionId,
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        info!(
+            "received_hangup(): id: {}, type: {}",
+            connection_id, hangup_type
+        );
+
+        self.check_replay(connection_id, ReceivedMessage::Hangup(hangup_type))?;
+
+        match hangup_type {
+            HangupType::Declined => {
+                handle_active_call_api!(self, CallManager::handle_received_declined, connection_id)
             }
-            None => Err(RingRtcError::NoActiveCall.into()),
+            _ => handle_active_call_api!(
+                self,
+                CallManager::handle_received_hangup,
+                connection_id,
+                hangup_type
+            ),
         }
     }
 
-    /// Return active connection ID
-    pub fn active_connection_id(&self) -> Result<ConnectionId> {
-        info!("active_connection_id():");
-        let active_call = self.active_call()?;
-        let active_device_id = active_call.active_device_id()?;
-        Ok(ConnectionId::new(active_call.call_id(), active_device_id))
+    /// Received declined hangup message from application.
+    ///
+    /// Once every remote device has declined, without any of them
+    /// accepting, the call ends with
+    /// `ApplicationEvent::EndedRemoteDeclined`.
+    pub fn received_declined(&mut self, connection_id: ConnectionId) -> Result<()> {
+        self.check_replay(connection_id, ReceivedMessage::Hangup(HangupType::Declined))?;
+        handle_active_call_api!(self, CallManager::handle_received_declined, connection_id)
     }
 
-    /// Return active connection object.
-    pub fn active_connection(&self) -> Result<Connection<T>> {
-        info!("active_connection():");
-        let active_call = self.active_call()?;
-        active_call.active_connection()
+    /// Restart ICE on the active connection of the call, keeping the
+    /// call up across a network change, e.g. from Wi-Fi to cellular.
+    ///
+    /// Only the caller restarts ICE, the callee answers the restart
+    /// offer.
+    pub fn restart_ice(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_restart_ice, call_id)
     }
 
-    /// Checks if a call is active.
-    pub fn call_active(&self) -> Result<bool> {
-        Ok(self.active_call_id.lock()?.is_some())
+    /// Received ringing acknowledgment message from application.
+    ///
+    /// Tells the application the remote device is ringing, if ICE
+    /// has not already connected.
+    pub fn received_ringing(&mut self, connection_id: ConnectionId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_received_ringing, connection_id)
     }
 
-    /// Check if call_id refers to the active call.
-    pub fn call_is_active(&self, call_id: CallId) -> Result<bool> {
-        let active_call_id = self.active_call_id.lock()?;
-        match *active_call_id {
-            Some(v) => Ok(v == call_id),
-            None => Ok(false),
-        }
+    /// Received busy message from application.
+    pub fn received_busy(&mut self, connection_id: ConnectionId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_received_busy, connection_id)
     }
 
-    /// Return the platform, under a locked mutex.
-    pub fn platform(&self) -> Result<MutexGuard<'_, T>> {
-        self.platform.lock()
+    /// The platform failed to initialize the audio device for the
+    /// foreground call.
+    ///
+    /// The application is notified with the event matching `error`
+    /// and, if enabled with `set_end_on_audio_device_failure()`, the
+    /// call then ends with `ApplicationEvent::EndedAudioDeviceFailure`.
+    pub fn audio_device_failure(&mut self, error: AudioDeviceError) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_audio_device_failure, error)
     }
 
-    /// Synchronize the call manager and all call FSMs.
+    /// Set the advisory codec preferences, highest priority first.
     ///
-    /// Blocks the caller while the call manager and call FSM event
-    /// queues are flushed.
-    ///
-    /// `Called By:` Test infrastructure
-    #[cfg(feature = "sim")]
-    pub fn synchronize(&mut self) -> Result<()> {
-        info!("synchronize():");
-
-        self.sync_runtime()?;
+    /// When answering, the first preferred codec found in the remote
+    /// offer is favored.  If the offer contains none of them the
+    /// answer falls back to any codec common to both sides and the
+    /// application is notified with `ApplicationEvent::CodecFallback`.
+    pub fn set_codec_preferences(&mut self, codecs: Vec<String>) -> Result<()> {
+        info!("set_codec_preferences(): codecs: {:?}", codecs);
+
+        *self.codec_preferences.lock()? = codecs;
+        Ok(())
+    }
 
-        // sync twice, as simulated error injection can put more
-        // events on the FSMs.
-        for i in 0..2 {
-            info!("synchronize(): pass: {}", i);
-            let mut map_clone = self.call_map.lock()?.clone();
-            for (_, call) in map_clone.iter_mut() {
-                info!("synchronize(): syncing call: {}", call.call_id());
-                call.synchronize()?;
-            }
+    /// Return the codecs the device supports: the software codecs of
+    /// WebRTC merged with the hardware codecs the platform reports.
+    ///
+    /// Without any codec preferences set, an answer favors the
+    /// hardware accelerated codecs.
+    pub fn supported_codecs(&self) -> Result<Vec<CodecCapability>> {
+        let platform = self.platform.lock()?;
+        let codecs = merge_capabilities(&platform.get_codec_capabilities()?);
+        info!("supported_codecs(): {:?}", codecs);
+        Ok(codecs)
+    }
 
-            self.sync_runtime()?;
-        }
+    /// Set how long to wait for the answer to a renegotiation offer.
+    ///
+    /// A renegotiation offer is sent when the connection is hard
+    /// reset while reconnecting.  If it is not answered in time the
+    /// offer is rolled back and retried once, after which the
+    /// application is notified with
+    /// `ApplicationEvent::RenegotiationTimeout`.
+    pub fn set_renegotiation_timeout(&mut self, timeout: Duration) -> Result<()> {
+        info!("set_renegotiation_timeout(): timeout: {:?}", timeout);
+
+        *self.renegotiation_timeout.lock()? = timeout;
+        Ok(())
+    }
 
-        info!("synchronize(): complete");
+    /// Set how many ICE restarts must fail before the connection of a
+    /// reconnecting call is hard reset, rebuilding the PeerConnection
+    /// from scratch.
+    ///
+    /// When ICE fails while reconnecting, the caller restarts ICE
+    /// until `restarts` ICE restarts have failed since the call last
+    /// connected.  With zero, the connection is hard reset as soon as
+    /// ICE fails.
+    pub fn set_ice_restarts_before_hard_reset(&mut self, restarts: u32) -> Result<()> {
+        info!("set_ice_restarts_before_hard_reset(): {}", restarts);
+
+        *self.ice_restart_limit.lock()? = restarts;
         Ok(())
     }
 
-    ////////////////////////////////////////////////////////////////////////
-    // Private internal functions start here
-    ////////////////////////////////////////////////////////////////////////
+    /// Set whether `call_with_id()` replaces a CallId that collides
+    /// with a live call by a new random one, instead of failing.  The
+    /// default is to fail.
+    pub fn set_regenerate_call_ids(&mut self, enabled: bool) -> Result<()> {
+        info!("set_regenerate_call_ids(): {}", enabled);
 
-    /// Return the strong reference count on the platform.
-    fn ref_count(&self) -> usize {
-        Arc::strong_count(&self.platform)
+        *self.regenerate_call_ids.lock()? = enabled;
+        Ok(())
     }
 
-    /// Spawn a future on the worker runtime if enabled.
-    fn worker_spawn<F>(&mut self, future: F) -> Result<()>
-    where
-        F: Future<Item = (), Error = ()> + Send + 'static,
-    {
-        let mut worker_runtime = self.worker_runtime.lock()?;
-        if let Some(worker_runtime) = &mut *worker_runtime {
-            worker_runtime.spawn(future);
-        } else {
-            warn!("worker_spawn(): worker_runtime unavailable");
-        }
+    /// Set whether offers, answers and ICE candidates are handed to
+    /// the application as opaque bytes, via
+    /// `Platform::on_send_opaque_offer()`,
+    /// `Platform::on_send_opaque_answer()` and
+    /// `Platform::on_send_opaque_ice_candidates()`, instead of SDP
+    /// strings.  The default is SDP strings.
+    ///
+    /// Only enable it if the remote peers accept opaque messages, and
+    /// pass the ones they send to `received_opaque_offer()`,
+    /// `received_opaque_answer()` and
+    /// `received_opaque_ice_candidates()`.  Offers restarting ICE are
+    /// always SDP strings.
+    ///
+    /// Opaque messages are sealed if the platform implements
+    /// `Platform::seal()`, see `signaling::seal()`.
+    pub fn set_opaque_signaling(&mut self, enabled: bool) -> Result<()> {
+        info!("set_opaque_signaling(): {}", enabled);
+
+        *self.opaque_signaling.lock()? = enabled;
         Ok(())
     }
 
-    fn runtime_start_sync(&mut self, sync_condvar: Arc<(Mutex<bool>, Condvar)>) -> Result<()> {
+    /// Set whether offers are compressed even before the remote
+    /// peer's protocol version is known.  The default is to only
+    /// compress once it is, i.e. answers and offers renegotiating a
+    /// call.
+    ///
+    /// Only enable it if the remote peers accept compressed SDP,
+    /// i.e. they all speak `signaling::COMPRESSION_PROTOCOL_VERSION`.
+    /// Received compressed offers and answers are always accepted.
+    pub fn set_sdp_compression(&mut self, enabled: bool) -> Result<()> {
+        info!("set_sdp_compression(): {}", enabled);
+
+        *self.sdp_compression.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Set whether outgoing calls send a pre-offer as soon as they
+    /// start, before the offer.  Disabled by default.
+    ///
+    /// The pre-offer carries no SDP, so it reaches the callee while
+    /// the offer is still being created, and the callee can warm up,
+    /// see `received_pre_offer()`.  Only enable it if the application
+    /// sends pre-offers, see `Platform::on_send_pre_offer()`.
+    pub fn set_pre_offer(&mut self, enabled: bool) -> Result<()> {
+        info!("set_pre_offer(): {}", enabled);
+
+        *self.pre_offer.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Set how a connected call recovers when ICE disconnects.
+    ///
+    /// While reconnecting, the caller restarts ICE up to `attempts`
+    /// times, waiting `backoff` before the first attempt and twice as
+    /// long before each further one.  An ICE restart that fails
+    /// still falls back to a hard reset.  Zero `attempts` leaves the
+    /// recovery to the application, see `restart_ice()`.  The
+    /// default is 3 attempts after a backoff of one second.
+    pub fn set_auto_recovery(&mut self, attempts: u32, backoff: Duration) -> Result<()> {
+        info!(
+            "set_auto_recovery(): attempts: {}, backoff: {:?}",
+            attempts, backoff
+        );
+
+        *self.recovery_attempts.lock()? = attempts;
+        *self.recovery_backoff.lock()? = backoff;
+        Ok(())
+    }
+
+    /// Set the maximum length, in bytes, of an SDP offer or answer
+    /// handed to the application for sending.
+    ///
+    /// Longer descriptions are rejected with
+    /// `RingRtcError::SdpTooLong` rather than being copied into a
+    /// platform string.
+    pub fn set_max_sdp_length(&mut self, length: usize) -> Result<()> {
+        info!("set_max_sdp_length(): length: {}", length);
+
+        *self.max_sdp_length.lock()? = length;
+        Ok(())
+    }
+
+    /// Set the maximum age of a received offer, two minutes by
+    /// default.
+    ///
+    /// Older offers, e.g. delivered late by a push notification, end
+    /// with `ApplicationEvent::EndedReceivedOfferExpired`.
+    pub fn set_max_offer_age(&mut self, age: Duration) -> Result<()> {
+        info!("set_max_offer_age(): age: {:?}", age);
+
+        *self.max_offer_age.lock()? = age;
+        Ok(())
+    }
+
+    /// Set the maximum age of received ICE candidates, one minute by
+    /// default.
+    ///
+    /// Older candidates, e.g. delivered late by a push notification,
+    /// likely point at addresses the remote peer no longer listens
+    /// on, so they are discarded and, for an outgoing call that is
+    /// already connected, ICE is restarted to exchange fresh ones.
+    pub fn set_max_ice_candidate_age(&mut self, age: Duration) -> Result<()> {
+        info!("set_max_ice_candidate_age(): age: {:?}", age);
+
+        *self.max_ice_candidate_age.lock()? = age;
+        Ok(())
+    }
+
+    /// Set how long a newly selected ICE route must hold before a
+    /// change between a direct and a relayed route is reported.
+    ///
+    /// Changes that revert within the period, e.g. brief flaps
+    /// between candidate pairs, are not reported.
+    pub fn set_route_change_debounce(&mut self, period: Duration) -> Result<()> {
+        info!("set_route_change_debounce(): period: {:?}", period);
+
+        *self.route_change_debounce.lock()? = period;
+        Ok(())
+    }
+
+    /// Set how long local ICE candidates are batched before they are
+    /// sent.
+    ///
+    /// Candidates gathered within `period` of the first unsent one
+    /// are coalesced into a single `Platform::on_send_ice_candidates()`
+    /// call, reducing the number of signaling messages.  The default,
+    /// zero, sends each candidate as soon as the signaling channel is
+    /// free.
+    pub fn set_ice_candidate_batch_period(&mut self, period: Duration) -> Result<()> {
+        info!("set_ice_candidate_batch_period(): period: {:?}", period);
+
+        *self.ice_batch_period.lock()? = period;
+        Ok(())
+    }
+
+    /// Set how media sections are bundled onto transports, for
+    /// interoperating with endpoints that do not support BUNDLE.  The
+    /// default is `BundlePolicy::MaxBundle`.
+    ///
+    /// Takes effect for PeerConnections created afterwards.
+    pub fn set_bundle_policy(&mut self, policy: BundlePolicy) -> Result<()> {
+        info!("set_bundle_policy(): policy: {}", policy);
+
+        self.connection_config.lock()?.bundle_policy = policy;
+        Ok(())
+    }
+
+    /// Set whether RTP and RTCP share a port, for interoperating with
+    /// endpoints that do not support rtcp-mux.  The default is
+    /// `RtcpMuxPolicy::Require`.
+    ///
+    /// Takes effect for PeerConnections created afterwards.
+    pub fn set_rtcp_mux_policy(&mut self, policy: RtcpMuxPolicy) -> Result<()> {
+        info!("set_rtcp_mux_policy(): policy: {}", policy);
+
+        self.connection_config.lock()?.rtcp_mux_policy = policy;
+        Ok(())
+    }
+
+    /// Set which ICE candidates connections exchange with the remote
+    /// peer, e.g. relay candidates only, so deployments that must not
+    /// reveal IP addresses can force relaying through TURN.  By
+    /// default all candidates are exchanged.
+    ///
+    /// Takes effect for connections created afterwards, use
+    /// `Connection::set_ice_candidate_policy()` to change an existing
+    /// connection.
+    pub fn set_ice_candidate_policy(&mut self, policy: IceCandidatePolicy) -> Result<()> {
+        info!("set_ice_candidate_policy(): policy: {}", policy);
+
+        *self.ice_candidate_policy.lock()? = policy;
+        Ok(())
+    }
+
+    /// Enable or disable notifying the application of the call
+    /// duration every second, from when the media of a call connects
+    /// until the call ends.  Disabled by default.
+    ///
+    /// Takes effect for calls whose media connects afterwards.
+    pub fn set_call_duration_ticks(&mut self, enabled: bool) -> Result<()> {
+        info!("set_call_duration_ticks(): enabled: {}", enabled);
+
+        *self.call_duration_ticks.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Enable or disable recording the trace of state transitions,
+    /// FSM events and signaling traffic of each call.  Enabled by
+    /// default.
+    ///
+    /// Takes effect for calls started afterwards.
+    pub fn set_tracing(&mut self, enabled: bool) -> Result<()> {
+        info!("set_tracing(): enabled: {}", enabled);
+
+        *self.tracing.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Return the trace of the call identified by `call_id` and of all
+    /// its connections, ordered by timestamp.
+    ///
+    /// Useful to debug a call that is stuck, for as long as the call
+    /// is not yet concluded.
+    pub fn get_call_trace(&self, call_id: CallId) -> Result<Vec<TraceEvent>> {
+        info!("get_call_trace(): call_id: {}", call_id);
+
+        self.call_by_id(call_id)?.export_full_trace()
+    }
+
+    /// Attach opaque metadata to the answer of the incoming call
+    /// identified by `call_id`, e.g. the features the callee supports,
+    /// for the caller to negotiate before the media connects.
+    ///
+    /// Call before `proceed()`.  The caller receives the metadata with
+    /// `Platform::on_received_answer_metadata()`.
+    pub fn set_answer_metadata(&mut self, call_id: CallId, metadata: Vec<u8>) -> Result<()> {
+        info!(
+            "set_answer_metadata(): call_id: {}, size: {}",
+            call_id,
+            metadata.len()
+        );
+
+        self.call_by_id(call_id)?.set_answer_metadata(metadata)
+    }
+
+    /// Set how often the application is sent the stats of the active
+    /// call, from when the media of a call connects until the call
+    /// ends.  `None`, the default, disables the stats reports.
+    ///
+    /// Takes effect for calls whose media connects afterwards.
+    pub fn set_stats_interval(&mut self, interval: Option<Duration>) -> Result<()> {
+        info!("set_stats_interval(): interval: {:?}", interval);
+
+        *self.stats_interval.lock()? = interval.filter(|i| *i > Duration::from_secs(0));
+        Ok(())
+    }
+
+    /// Set how often the application is sent the captured and received
+    /// audio levels of the active call, e.g. to animate speaking
+    /// indicators, from when the media of a call connects until the
+    /// call ends.  `None`, the default, disables the audio levels.
+    ///
+    /// Takes effect for calls whose media connects afterwards.
+    pub fn set_audio_levels_interval(&mut self, interval: Option<Duration>) -> Result<()> {
+        info!("set_audio_levels_interval(): interval: {:?}", interval);
+
+        *self.audio_levels_interval.lock()? = interval.filter(|i| *i > Duration::from_secs(0));
+        Ok(())
+    }
+
+    /// Set how often the application is sent the bandwidth estimate
+    /// of the active call, from when the media of a call connects
+    /// until the call ends.  `None`, the default, disables the
+    /// estimates.
+    ///
+    /// Takes effect for calls whose media connects afterwards.
+    pub fn set_bandwidth_estimate_interval(&mut self, interval: Option<Duration>) -> Result<()> {
+        info!(
+            "set_bandwidth_estimate_interval(): interval: {:?}",
+            interval
+        );
+
+        *self.bandwidth_interval.lock()? = interval.filter(|i| *i > Duration::from_secs(0));
+        Ok(())
+    }
+
+    /// Set how long no media may arrive on the active call, while
+    /// ICE is connected, before the application is notified with
+    /// `RemoteMediaStalled`, and with `RemoteMediaResumed` once media
+    /// arrives again.  `None`, the default, disables the monitoring.
+    ///
+    /// Takes effect for calls whose media connects afterwards.
+    pub fn set_media_stall_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        info!("set_media_stall_timeout(): timeout: {:?}", timeout);
+
+        *self.media_stall_timeout.lock()? = timeout.filter(|t| *t > Duration::from_secs(0));
+        Ok(())
+    }
+
+    /// Enable or disable ending the active call when the audio device
+    /// fails to initialize, instead of continuing without audio.
+    /// Disabled by default.
+    pub fn set_end_on_audio_device_failure(&mut self, enabled: bool) -> Result<()> {
+        info!("set_end_on_audio_device_failure(): enabled: {}", enabled);
+
+        *self.end_on_audio_failure.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Limit the number of connections, and so PeerConnections, an
+    /// outgoing call creates to `max` remote devices.  `None`, the
+    /// default, connects to every remote device.
+    ///
+    /// The device selection policy chooses the devices to connect to.
+    /// A device left out is only connected to if it answers anyway.
+    /// Takes effect for calls that proceed afterwards.
+    pub fn set_max_connections(&mut self, max: Option<usize>) -> Result<()> {
+        info!("set_max_connections(): max: {:?}", max);
+
+        *self.max_connections.lock()? = max.filter(|m| *m > 0);
+        Ok(())
+    }
+
+    /// Set the policy choosing the remote devices an outgoing call
+    /// connects to when there are more devices than the connection
+    /// limit.  Defaults to `InOrderSelection`.
+    pub fn set_device_selection_policy(
+        &mut self,
+        policy: Box<dyn DeviceSelectionPolicy>,
+    ) -> Result<()> {
+        info!("set_device_selection_policy():");
+
+        *self.device_selection.lock()? = policy;
+        Ok(())
+    }
+
+    /// Set the policy choosing which remote device wins an outgoing
+    /// call forked to several devices.  Defaults to `FirstToConnect`.
+    pub fn set_forking_policy(&mut self, policy: Box<dyn ForkingPolicy>) -> Result<()> {
+        info!("set_forking_policy():");
+
+        *self.forking_policy.lock()? = policy;
+        Ok(())
+    }
+
+    /// Set the transport carrying the signaling messages to the
+    /// remote peers.  Defaults to `PlatformTransport`, which hands
+    /// them to the application.
+    ///
+    /// The transport reports each message as sent with
+    /// `message_sent()`, see `SignalingTransport`.
+    pub fn set_signaling_transport(
+        &mut self,
+        transport: Box<dyn SignalingTransport<T>>,
+    ) -> Result<()> {
+        info!("set_signaling_transport():");
+
+        *self.signaling_transport.lock()? = transport;
+        Ok(())
+    }
+
+    /// Set the provider encrypting the media frames of connections
+    /// end-to-end, independent of the DTLS-SRTP encryption of each
+    /// hop, or stop if `None`.  The remote peer must decrypt them
+    /// with a provider of its own.
+    ///
+    /// Takes effect for connections created afterwards, use
+    /// `Connection::set_media_crypto_provider()` to change an
+    /// existing connection.
+    pub fn set_media_crypto_provider(
+        &mut self,
+        provider: Option<Arc<dyn MediaCryptoProvider>>,
+    ) -> Result<()> {
+        info!("set_media_crypto_provider(): {}", provider.is_some());
+
+        *self.media_crypto_provider.lock()? = provider;
+        Ok(())
+    }
+
+    /// Set the audio processing of calls without one of their own,
+    /// see `CallConfig::with_audio_config()`.  Every stage is enabled
+    /// by default.
+    ///
+    /// Platforms apply it when creating the PeerConnection factory,
+    /// see `default_audio_config()`.  Takes effect for connections
+    /// created afterwards.
+    pub fn set_default_audio_config(&mut self, config: AudioConfig) -> Result<()> {
+        info!("set_default_audio_config(): {}", config);
+
+        *self.default_audio_config.lock()? = config;
+        Ok(())
+    }
+
+    /// Return the audio processing of calls without one of their own.
+    pub fn default_audio_config(&self) -> Result<AudioConfig> {
+        Ok(*self.default_audio_config.lock()?)
+    }
+
+    /// Tell the call manager where the application plays the audio of
+    /// calls, after the user picked an output or a headset came or
+    /// went.  The earpiece by default.
+    ///
+    /// The call manager suggests other routes from it, see
+    /// `Platform::on_audio_route_suggested()`.
+    pub fn set_audio_output(&mut self, route: AudioRoute) -> Result<()> {
+        info!("set_audio_output(): {}", route);
+
+        *self.audio_route.lock()? = route;
+        Ok(())
+    }
+
+    /// Return where the application plays the audio of calls.
+    pub fn audio_output(&self) -> Result<AudioRoute> {
+        Ok(*self.audio_route.lock()?)
+    }
+
+    /// Enable or disable call waiting.  Disabled by default.
+    ///
+    /// With call waiting, an offer from another remote peer received
+    /// while the foreground call is connected starts a second active
+    /// call, which rings like any incoming call, rather than being
+    /// answered with busy.  Accepting it puts the foreground call on
+    /// hold.  At most two calls are active at a time.
+    pub fn set_call_waiting(&mut self, enabled: bool) -> Result<()> {
+        info!("set_call_waiting(): enabled: {}", enabled);
+
+        *self.call_waiting.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Enable or disable the outgoing audio of an active call.
+    ///
+    /// The setting is remembered, so muting before the media is
+    /// established applies to the audio track as soon as it is
+    /// created.  The remote peer is told of the change once the call
+    /// is connected.
+    pub fn set_outgoing_audio_enabled(&mut self, call_id: CallId, enabled: bool) -> Result<()> {
+        info!(
+            "set_outgoing_audio_enabled(): call_id: {}, enabled: {}",
+            call_id, enabled
+        );
+
+        if !self.call_is_active(call_id)? {
+            info!(
+                "set_outgoing_audio_enabled(): skipping inactive call_id: {}",
+                call_id
+            );
+            return Ok(());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        active_call.set_outgoing_audio_enabled(enabled)
+    }
+
+    /// Set how much bandwidth an active call may use.
+    ///
+    /// The maximum bitrate applies right away.  A connected call also
+    /// renegotiates, so the remote peer limits the media it sends.
+    pub fn set_bandwidth_mode(&mut self, call_id: CallId, mode: BandwidthMode) -> Result<()> {
+        info!("set_bandwidth_mode(): call_id: {}, mode: {}", call_id, mode);
+
+        if !self.call_is_active(call_id)? {
+            info!(
+                "set_bandwidth_mode(): skipping inactive call_id: {}",
+                call_id
+            );
+            return Ok(());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        active_call.set_bandwidth_mode(mode)
+    }
+
+    /// Set the audio processing of an active call, e.g. to turn echo
+    /// cancellation off when a headset is plugged in.
+    pub fn set_audio_config(&mut self, call_id: CallId, config: AudioConfig) -> Result<()> {
+        info!("set_audio_config(): call_id: {}, {}", call_id, config);
+
+        if !self.call_is_active(call_id)? {
+            info!("set_audio_config(): skipping inactive call_id: {}", call_id);
+            return Ok(());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        active_call.set_audio_config(config)
+    }
+
+    /// Tell the remote peer of an active call whether the local video
+    /// is streaming.
+    ///
+    /// The status is sent over the DataChannel of the active
+    /// connection, and the remote side reports it to its application
+    /// as `RemoteVideoEnable` or `RemoteVideoDisable`.
+    pub fn set_outgoing_video_enabled(&mut self, call_id: CallId, enabled: bool) -> Result<()> {
+        info!(
+            "set_outgoing_video_enabled(): call_id: {}, enabled: {}",
+            call_id, enabled
+        );
+
+        if !self.call_is_active(call_id)? {
+            info!(
+                "set_outgoing_video_enabled(): skipping inactive call_id: {}",
+                call_id
+            );
+            return Ok(());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        let mut active_connection = active_call.active_connection()?;
+        active_connection.inject_local_video_status(enabled)
+    }
+
+    /// Turn the camera of an active call on or off.
+    ///
+    /// The platform controls the camera from the worker of the call,
+    /// see `Platform::on_set_camera_enabled()`, so the request is
+    /// ordered with the teardown of the call rather than racing it.
+    /// The remote peer is told separately, with
+    /// `set_outgoing_video_enabled()`.
+    pub fn set_camera_enabled(&mut self, call_id: CallId, enabled: bool) -> Result<()> {
+        info!(
+            "set_camera_enabled(): call_id: {}, enabled: {}",
+            call_id, enabled
+        );
+
+        if !self.call_is_active(call_id)? {
+            info!(
+                "set_camera_enabled(): skipping inactive call_id: {}",
+                call_id
+            );
+            return Ok(());
+        }
+        let mut active_call = self.active_call_by_id(call_id)?;
+        active_call.inject_set_camera_enabled(enabled)
+    }
+
+    /// Switch to the next camera of an active call, e.g. from the
+    /// front to the back camera.
+    ///
+    /// Like `set_camera_enabled()`, the platform switches the camera
+    /// from the worker of the call, see `Platform::on_switch_camera()`.
+    pub fn switch_camera(&mut self, call_id: CallId) -> Result<()> {
+        info!("switch_camera(): call_id: {}", call_id);
+
+        if !self.call_is_active(call_id)? {
+            info!("switch_camera(): skipping inactive call_id: {}", call_id);
+            return Ok(());
+        }
+        let mut active_call = self.active_call_by_id(call_id)?;
+        active_call.inject_switch_camera()
+    }
+
+    /// Cap the resolution of the video sent on an active call, see
+    /// `Connection::set_video_max_resolution()`.
+    pub fn set_video_max_resolution(
+        &mut self,
+        call_id: CallId,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        info!(
+            "set_video_max_resolution(): call_id: {}, {}x{}",
+            call_id, width, height
+        );
+
+        if !self.call_is_active(call_id)? {
+            info!(
+                "set_video_max_resolution(): skipping inactive call_id: {}",
+                call_id
+            );
+            return Ok(());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        active_call
+            .active_connection()?
+            .set_video_max_resolution(width, height)
+    }
+
+    /// Start or stop sharing the screen of an active call.
+    ///
+    /// The screen is sent on a video track of its own, added by the
+    /// platform with a `detailed` or `text` content hint, so it can
+    /// stream alongside the camera video or in place of it.  The
+    /// status is sent over the DataChannel of the active connection,
+    /// and the remote side reports it to its application as
+    /// `RemoteSharingScreen` or `RemoteStoppedSharingScreen`.
+    ///
+    /// Sharing fails if the negotiated SDP has no screen share
+    /// section, see `RemoteCapabilities::screen_share`.
+    pub fn set_outgoing_screen_share_enabled(
+        &mut self,
+        call_id: CallId,
+        enabled: bool,
+    ) -> Result<()> {
+        info!(
+            "set_outgoing_screen_share_enabled(): call_id: {}, enabled: {}",
+            call_id, enabled
+        );
+
+        if !self.call_is_active(call_id)? {
+            info!(
+                "set_outgoing_screen_share_enabled(): skipping inactive call_id: {}",
+                call_id
+            );
+            return Ok(());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        let mut active_connection = active_call.active_connection()?;
+        if enabled {
+            if let Some(capabilities) = active_connection.remote_capabilities()? {
+                if !capabilities.screen_share {
+                    return Err(RingRtcError::ScreenShareNotNegotiated(call_id).into());
+                }
+            }
+        }
+        active_connection.inject_local_screen_share_status(enabled)
+    }
+
+    /// Put an active call on hold, or resume it.
+    ///
+    /// Holding pauses the outgoing audio and video of the active
+    /// connection, and the remote side reports it to its application
+    /// as `RemoteOnHold`, resuming as `RemoteResumed`.  Only a
+    /// connected call can be put on hold.
+    pub fn set_on_hold(&mut self, call_id: CallId, on_hold: bool) -> Result<()> {
+        info!("set_on_hold(): call_id: {}, on_hold: {}", call_id, on_hold);
+
+        if !self.call_is_active(call_id)? {
+            info!("set_on_hold(): skipping inactive call_id: {}", call_id);
+            return Ok(());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        let mut active_connection = active_call.active_connection()?;
+        active_connection.set_on_hold(on_hold)
+    }
+
+    /// Tell the call manager the application handed an active call to
+    /// the calling service of the system, e.g. a self-managed
+    /// ConnectionService of Telecom on Android.
+    ///
+    /// From then on, the system is asked to show the call as on hold
+    /// whenever the call manager holds it by itself, e.g. to accept a
+    /// waiting call, see `Platform::on_request_system_hold()`.
+    pub fn call_added_to_system(&mut self, call_id: CallId) -> Result<()> {
+        info!("call_added_to_system(): call_id: {}", call_id);
+
+        if !self.call_is_active(call_id)? {
+            info!(
+                "call_added_to_system(): skipping inactive call_id: {}",
+                call_id
+            );
+            return Ok(());
+        }
+        let mut active_call = self.active_call_by_id(call_id)?;
+        active_call.inject_added_to_system()
+    }
+
+    /// Put an active call on hold, or resume it, as requested by the
+    /// calling service of the system, e.g. when a call of another
+    /// application starts.
+    ///
+    /// Resuming a call in the background brings it to the
+    /// foreground, holding the previous foreground call.  Requests
+    /// for a call that is not connected are ignored.
+    pub fn hold_requested_by_system(&mut self, call_id: CallId, on_hold: bool) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_hold_requested_by_system,
+            call_id,
+            on_hold
+        )
+    }
+
+    /// Transfer an active call to another peer.
+    ///
+    /// The remote peer is asked to call `new_remote` in place of the
+    /// local peer.  Once it accepts, the call ends with
+    /// `EndedTransferred`.  If it declines, the call goes on and the
+    /// application is notified with `TransferDeclined`.  Only a
+    /// connected call can be transferred.
+    pub fn transfer_call(&mut self, call_id: CallId, new_remote: String) -> Result<()> {
+        info!(
+            "transfer_call(): call_id: {}, new_remote: {}",
+            call_id, new_remote
+        );
+
+        if !self.call_is_active(call_id)? {
+            info!("transfer_call(): skipping inactive call_id: {}", call_id);
+            return Ok(());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        let mut active_connection = active_call.active_connection()?;
+        active_connection.transfer(new_remote)
+    }
+
+    /// Start recording an active call to `sink`.
+    ///
+    /// The captured and received audio are mixed into one track, and
+    /// the incoming video is added if `config` asks for it.  The
+    /// remote side reports the recording to its application as
+    /// `RemoteRecordingStarted`, and as `RemoteRecordingStopped` once
+    /// it stops.  Only a connected call can be recorded, and the
+    /// recording is finished when the call ends.
+    pub fn start_recording(
+        &mut self,
+        call_id: CallId,
+        sink: Box<dyn RecordingSink>,
+        config: RecordingConfig,
+    ) -> Result<()> {
+        info!("start_recording(): call_id: {}, {}", call_id, config);
+
+        if !self.call_is_active(call_id)? {
+            return Err(RingRtcError::CallNotActive(call_id).into());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        match active_call.state()? {
+            CallState::Connected | CallState::Reconnecting => {}
+            _ => return Err(RingRtcError::CallNotConnected(call_id).into()),
+        }
+        let active_connection = active_call.active_connection()?;
+        active_connection.start_recording(sink, config)
+    }
+
+    /// Export the SRTP keys of an active call, e.g. for a compliance
+    /// recorder to decrypt a copy of its media.
+    ///
+    /// The call must have been opted in with
+    /// `CallConfig::with_srtp_key_export()`, and be connected so DTLS
+    /// negotiated the keys.  The keys change when the PeerConnection
+    /// is rebuilt, e.g. by a hard reset, and must be exported again.
+    #[cfg(feature = "srtp_key_export")]
+    pub fn export_srtp_keys(&mut self, call_id: CallId) -> Result<SrtpKeys> {
+        info!("export_srtp_keys(): call_id: {}", call_id);
+
+        if !self.call_is_active(call_id)? {
+            return Err(RingRtcError::CallNotActive(call_id).into());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        if !active_call
+            .config()
+            .map_or(false, |config| config.srtp_key_export)
+        {
+            return Err(RingRtcError::SrtpKeyExportNotEnabled(call_id).into());
+        }
+        match active_call.state()? {
+            CallState::Connected | CallState::Reconnecting => {}
+            _ => return Err(RingRtcError::CallNotConnected(call_id).into()),
+        }
+        let active_connection = active_call.active_connection()?;
+        let keys = active_connection.export_srtp_keys()?;
+        warn!(
+            "export_srtp_keys(): exported the SRTP keys of call_id: {}, profile: {}",
+            call_id, keys.profile
+        );
+        Ok(keys)
+    }
+
+    /// Stop recording an active call, finishing the stream.
+    pub fn stop_recording(&mut self, call_id: CallId) -> Result<()> {
+        info!("stop_recording(): call_id: {}", call_id);
+
+        if !self.call_is_active(call_id)? {
+            return Err(RingRtcError::CallNotActive(call_id).into());
+        }
+        let active_call = self.active_call_by_id(call_id)?;
+        let active_connection = active_call.active_connection()?;
+        active_connection.stop_recording()
+    }
+
+    /// Create a group call with the given participants, returning
+    /// its CallId.
+    ///
+    /// Group calls are independent of the active direct call.  Each
+    /// participant starts out joining, and joins once the application
+    /// reports the connection to it as connected.
+    pub fn create_group_call(
+        &mut self,
+        participants: Vec<(ParticipantId, <T as Platform>::AppRemotePeer)>,
+    ) -> Result<CallId> {
+        let call_id = CallId::secure_random();
+        handle_api!(
+            self,
+            CallManager::handle_create_group_call,
+            call_id,
+            participants
+        )?;
+        Ok(call_id)
+    }
+
+    /// Add a participant to a group call.
+    pub fn add_group_participant(
+        &mut self,
+        call_id: CallId,
+        participant_id: ParticipantId,
+        remote_peer: <T as Platform>::AppRemotePeer,
+    ) -> Result<()> {
+        handle_api!(
+            self,
+            CallManager::handle_add_group_participant,
+            call_id,
+            participant_id,
+            remote_peer
+        )
+    }
+
+    /// Notification that the connection to a group call participant
+    /// is connected.
+    pub fn group_participant_connected(
+        &mut self,
+        call_id: CallId,
+        participant_id: ParticipantId,
+    ) -> Result<()> {
+        handle_api!(
+            self,
+            CallManager::handle_group_participant_connected,
+            call_id,
+            participant_id
+        )
+    }
+
+    /// Notification that the connection to a group call participant
+    /// is disconnected.
+    pub fn group_participant_disconnected(
+        &mut self,
+        call_id: CallId,
+        participant_id: ParticipantId,
+    ) -> Result<()> {
+        handle_api!(
+            self,
+            CallManager::handle_group_participant_disconnected,
+            call_id,
+            participant_id
+        )
+    }
+
+    /// Route the media stream received from a group call participant
+    /// to the application, once the participant has joined.
+    pub fn group_participant_media(
+        &mut self,
+        call_id: CallId,
+        participant_id: ParticipantId,
+        app_media_stream: <T as Platform>::AppMediaStream,
+    ) -> Result<()> {
+        handle_api!(
+            self,
+            CallManager::handle_group_participant_media,
+            call_id,
+            participant_id,
+            app_media_stream
+        )
+    }
+
+    /// End a group call.  Every joined participant leaves.
+    pub fn end_group_call(&mut self, call_id: CallId) -> Result<()> {
+        handle_api!(self, CallManager::handle_end_group_call, call_id)
+    }
+
+    /// Send the signaling messages the application persisted before
+    /// a restart, as returned by `Platform::restore_pending_signaling()`.
+    ///
+    /// The messages are queued ahead of any sent afterwards.
+    pub fn restore_pending_signaling(&mut self) -> Result<()> {
+        handle_api!(self, CallManager::handle_restore_pending_signaling)
+    }
+
+    /// Request to reset the Call Manager.
+    ///
+    /// Conclude all calls and clear active callId.  Do not notify the
+    /// application at the conclusion.
+    pub fn reset(&mut self) -> Result<()> {
+        handle_api!(self, CallManager::handle_reset)
+    }
+
+    /// Close down the Call Manager.
+    ///
+    /// Close down the call manager and all the calls it is currently managing.
+    ///
+    /// This is a blocking call.
+    #[allow(clippy::mutex_atomic)]
+    pub fn close(&mut self) -> Result<()> {
+        info!("close():");
+
+        if self.worker_runtime.lock()?.is_some() {
+            // Clear out any outstanding calls
+            let _ = self.reset();
+
+            self.sync_runtime()?;
+
+            // close the runtime
+            let _ = self.close_runtime();
+            info!("close(): complete");
+        } else {
+            info!("close(): already closed.");
+        }
+
+        Ok(())
+    }
+
+    /// Shut down the Call Manager gracefully.
+    ///
+    /// Hang up all calls, notifying the application, and end all
+    /// group calls.  Then wait, for a bounded period, for the hangup
+    /// messages to be sent and the calls to be torn down, before
+    /// closing down the call manager.  The application is notified
+    /// with `Platform::on_shutdown_complete()` once done.
+    ///
+    /// This is a blocking call.
+    pub fn shutdown(&mut self) -> Result<()> {
+        info!("shutdown():");
+
+        if self.worker_runtime.lock()?.is_none() {
+            info!("shutdown(): already closed.");
+            return Ok(());
+        }
+
+        handle_api!(self, CallManager::handle_shutdown)?;
+        self.sync_runtime()?;
+
+        let timeout = Duration::from_millis(SHUTDOWN_DRAIN_TIME_OUT_PERIOD);
+        if !self.wait_for_drain(timeout)? {
+            warn!("shutdown(): timed out draining, closing anyway");
+        }
+
+        self.close()?;
+
+        info!("shutdown(): complete");
+        self.platform.lock()?.on_shutdown_complete()
+    }
+
+    /// Returns the active Call in the foreground.
+    pub fn active_call(&self) -> Result<Call<T>> {
+        let active_call_ids = self.active_call_ids.lock()?;
+        match active_call_ids.first() {
+            Some(call_id) => self.call_by_id(*call_id),
+            None => Err(RingRtcError::NoActiveCall.into()),
+        }
+    }
+
+    /// Returns the active Call identified by `call_id`, whether in
+    /// the foreground or not.
+    pub fn active_call_by_id(&self, call_id: CallId) -> Result<Call<T>> {
+        if self.call_is_active(call_id)? {
+            self.call_by_id(call_id)
+        } else {
+            Err(RingRtcError::CallNotActive(call_id).into())
+        }
+    }
+
+    /// Returns the CallIds of the active calls, the foreground call
+    /// first.
+    pub fn active_call_ids(&self) -> Result<Vec<CallId>> {
+        Ok(self.active_call_ids.lock()?.clone())
+    }
+
+    /// Returns the group call identified by `call_id`.
+    pub fn group_call(&self, call_id: CallId) -> Result<GroupCall<T>> {
+        let group_calls = self.group_calls.lock()?;
+        match group_calls.get(&call_id) {
+            Some(group_call) => Ok(group_call.clone()),
+            None => Err(RingRtcError::GroupCallNotFound(call_id).into()),
+        }
+    }
+
+    /// Return active connection ID
+    pub fn active_connection_id(&self) -> Result<ConnectionId> {
+        info!("active_connection_id():");
+        let active_call = self.active_call()?;
+        let active_device_id = active_call.active_device_id()?;
+        Ok(ConnectionId::new(active_call.call_id(), active_device_id))
+    }
+
+    /// Return active connection object.
+    pub fn active_connection(&self) -> Result<Connection<T>> {
+        info!("active_connection():");
+        let active_call = self.active_call()?;
+        active_call.active_connection()
+    }
+
+    /// Checks if a call is active.
+    pub fn call_active(&self) -> Result<bool> {
+        Ok(!self.active_call_ids.lock()?.is_empty())
+    }
+
+    /// Check if call_id refers to an active call.
+    pub fn call_is_active(&self, call_id: CallId) -> Result<bool> {
+        Ok(self.active_call_ids.lock()?.contains(&call_id))
+    }
+
+    /// Return the platform, under a locked mutex.
+    pub fn platform(&self) -> Result<MutexGuard<'_, T>> {
+        self.platform.lock()
+    }
+
+    /// Synchronize the call manager and all call FSMs.
+    ///
+    /// Blocks the caller while the call manager and call FSM event
+    /// queues are flushed.
+    ///
+    /// `Called By:` Test infrastructure
+    #[cfg(feature = "sim")]
+    pub fn synchronize(&mut self) -> Result<()> {
+        info!("synchronize():");
+
+        self.sync_runtime()?;
+
+        // sync twice, as simulated error injection can put more
+        // events on the FSMs.
+        for i in 0..2 {
+            info!("synchronize(): pass: {}", i);
+            let mut map_clone = self.call_map.lock()?.clone();
+            for (_, call) in map_clone.iter_mut() {
+                info!("synchronize(): syncing call: {}", call.call_id());
+                call.synchronize()?;
+            }
+
+            self.sync_runtime()?;
+        }
+
+        info!("synchronize(): complete");
+        Ok(())
+    }
+
+    ////////////////////////////////////////////////////////////////////////
+    // Private internal functions start here
+    ////////////////////////////////////////////////////////////////////////
+
+    /// Return the strong reference count on the platform.
+    fn ref_count(&self) -> usize {
+        Arc::strong_count(&self.platform)
+    }
+
+    /// Spawn a future on the worker runtime if enabled.
+    fn worker_spawn<F>(&mut self, future: F) -> Result<()>
+    where
+        F: Future<Item = (), Error = ()> + Send + 'static,
+    {
+        let mut worker_runtime = self.worker_runtime.lock()?;
+        if let Some(worker_runtime) = &mut *worker_runtime {
+            worker_runtime.spawn(future);
+        } else {
+            warn!("worker_spawn(): worker_runtime unavailable");
+        }
+        Ok(())
+    }
+
+    fn runtime_start_sync(&mut self, sync_condvar: Arc<(Mutex<bool>, Condvar)>) -> Result<()> {
         let future = lazy(move || {
             // signal the condvar
             info!("sync_runtime(): syncing runtime");
@@ -590,9 +2183,71 @@ where
         Ok(())
     }
 
-    /// Clears the active call_id
-    fn clear_active_call(&mut self) -> Result<()> {
-        let _ = self.active_call_id.lock()?.take();
+    /// Returns true once no signaling messages are queued or in
+    /// flight and all calls are disposed.
+    fn drained(&self) -> Result<bool> {
+        let messages_pending = {
+            let message_queue = self.message_queue.lock()?;
+            !message_queue.queue.is_empty() || message_queue.messages_in_flight
+        };
+        Ok(!messages_pending && self.call_map.lock()?.is_empty())
+    }
+
+    /// Wait, for at most `timeout`, for the call manager to drain.
+    ///
+    /// Returns false if the call manager did not drain in time.
+    fn wait_for_drain(&self, timeout: Duration) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.drained()? {
+                return Ok(true);
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            thread::sleep(Duration::from_millis(SHUTDOWN_DRAIN_POLL_PERIOD));
+        }
+    }
+
+    /// Returns the Call identified by `call_id` from the call map.
+    fn call_by_id(&self, call_id: CallId) -> Result<Call<T>> {
+        let call_map = self.call_map.lock()?;
+        match call_map.get(&call_id) {
+            Some(call) => Ok(call.clone()),
+            None => Err(RingRtcError::CallIdNotFound(call_id).into()),
+        }
+    }
+
+    /// Clears call_id from the active calls.  When it was the
+    /// foreground call, the next active call moves to the foreground.
+    fn clear_active_call(&mut self, call_id: CallId) -> Result<()> {
+        self.active_call_ids.lock()?.retain(|v| *v != call_id);
+        Ok(())
+    }
+
+    /// Move the active call_id to the foreground, putting the
+    /// previous foreground call on hold if it is connected.
+    fn bring_to_foreground(&mut self, call_id: CallId) -> Result<()> {
+        let mut foreground_call = self.active_call()?;
+        if foreground_call.call_id() == call_id {
+            return Ok(());
+        }
+
+        info!(
+            "bring_to_foreground(): call_id: {}, holding call_id: {}",
+            call_id,
+            foreground_call.call_id()
+        );
+        if let CallState::Connected | CallState::Reconnecting = foreground_call.state()? {
+            foreground_call.active_connection()?.set_on_hold(true)?;
+            if foreground_call.system_managed() {
+                foreground_call.inject_request_system_hold(true)?;
+            }
+        }
+
+        let mut active_call_ids = self.active_call_ids.lock()?;
+        active_call_ids.retain(|v| *v != call_id);
+        active_call_ids.insert(0, call_id);
         Ok(())
     }
 
@@ -609,25 +2264,82 @@ where
         call.close()
     }
 
-    /// Sends a hangup message to a remote_peer via the application.
+    /// Sends a hangup message to a remote_peer via the application,
+    /// addressed to all the remote devices of the call at once.
     fn send_hangup(&mut self, call: Call<T>, call_id: CallId) -> Result<()> {
         info!("send_hangup(): call_id: {}", call_id);
 
         let connection_id = ConnectionId::new(call_id, 0);
+        let remote_devices = call.remote_devices()?;
 
         let hangup_closure = Box::new(move |cm: &CallManager<T>| {
             info!("send_hangup(): closure");
 
+            let remote_peer = call.remote_peer()?;
+            let hangup_type = call.hangup_type()?;
+
+            let platform = cm.platform.lock()?;
+            let transport = cm.signaling_transport.lock()?;
+            transport.send_hangup_to_devices(
+                &*platform,
+                &*remote_peer,
+                connection_id,
+                &remote_devices,
+                hangup_type,
+            )
+        });
+
+        let message_item = SignalingMessageItem {
+            call_id:         connection_id.call_id(),
+            message_type:    SignalingMessageType::Hangup,
+            message_closure: hangup_closure,
+            pending_closure: None,
+        };
+
+        self.send_next_message(Some(message_item))
+    }
+
+    /// Tells the other devices of the remote peer that `remote_device`
+    /// accepted the call, so they stop ringing.
+    ///
+    /// The `Accepted` hangup is sent on the DataChannels of the other
+    /// connections and via the application in a single message to all
+    /// the other devices, with the ID of the accepting device.
+    /// Nothing is sent if the call only reached one device.
+    pub(super) fn send_accepted_hangup(
+        &mut self,
+        call: Call<T>,
+        remote_device: DeviceId,
+    ) -> Result<()> {
+        let connection_id = ConnectionId::new(call.call_id(), remote_device);
+        info!("send_accepted_hangup(): id: {}", connection_id);
+
+        let other_devices = call.hangup_other_devices(remote_device, HangupType::Accepted)?;
+        if other_devices.is_empty() {
+            return Ok(());
+        }
+
+        let hangup_closure = Box::new(move |cm: &CallManager<T>| {
+            info!("send_accepted_hangup(): closure");
+
             let remote_peer = call.remote_peer()?;
 
             let platform = cm.platform.lock()?;
-            platform.on_send_hangup(&*remote_peer, connection_id, true)
+            let transport = cm.signaling_transport.lock()?;
+            transport.send_hangup_to_devices(
+                &*platform,
+                &*remote_peer,
+                connection_id,
+                &other_devices,
+                HangupType::Accepted,
+            )
         });
 
         let message_item = SignalingMessageItem {
             call_id:         connection_id.call_id(),
             message_type:    SignalingMessageType::Hangup,
             message_closure: hangup_closure,
+            pending_closure: None,
         };
 
         self.send_next_message(Some(message_item))
@@ -654,8 +2366,9 @@ where
         self.trim_messages(call_id)?;
 
         if let Some(event) = event {
+            call.set_end_reason(event)?;
             let remote_peer = call.remote_peer()?;
-            self.notify_application(&*remote_peer, event)?;
+            self.notify_application(&*remote_peer, call_id, event)?;
         }
 
         if send_hangup {
@@ -679,49 +2392,72 @@ where
         .map_err(move |err| {
             error!("Conclude call future failed: {}", err);
             if let Ok(remote_peer) = call_error.remote_peer() {
-                let _ = cm_error
-                    .notify_application(&*remote_peer, ApplicationEvent::EndedInternalFailure);
+                let _ = cm_error.notify_call_failed(
+                    &*remote_peer,
+                    call_id,
+                    CallFailureReason::from(&err),
+                );
+                let _ = cm_error.notify_application(
+                    &*remote_peer,
+                    call_id,
+                    ApplicationEvent::EndedInternalFailure,
+                );
             }
         });
         self.worker_spawn(future)
     }
 
-    /// Concludes the active call.
-    fn conclude_active_call(&mut self, send_hangup: bool, event: ApplicationEvent) -> Result<()> {
-        info!("conclude_active_call():");
+    /// Concludes the active call identified by `call_id`.
+    fn conclude_active_call(
+        &mut self,
+        call_id: CallId,
+        send_hangup: bool,
+        event: ApplicationEvent,
+    ) -> Result<()> {
+        info!("conclude_active_call(): call_id: {}", call_id);
 
-        if !self.call_active()? {
-            info!("conclude_active_call(): skipping, no active call");
+        if !self.call_is_active(call_id)? {
+            info!("conclude_active_call(): skipping inactive call");
             return Ok(());
         }
 
-        let call = self.active_call()?;
-        self.clear_active_call()?;
+        let call = self.active_call_by_id(call_id)?;
+        self.clear_active_call(call_id)?;
 
         self.conclude_call(call, send_hangup, Some(event))
     }
 
     /// Handle call() API from application.
-    fn handle_call(&mut self, remote_peer: <T as Platform>::AppRemotePeer) -> Result<()> {
-        info!("handle_call():");
+    fn handle_call(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        config: CallConfig,
+    ) -> Result<()> {
+        info!("handle_call(): call_id: {}", call_id);
+
+        // A new call replaces calling a busy remote peer again.
+        self.cancel_busy_retry()?;
 
         // if no active call, create a new call
-        let mut active_call_id = self.active_call_id.lock()?;
-        match *active_call_id {
-            Some(v) => Err(RingRtcError::CallAlreadyInProgress(v).into()),
+        let mut active_call_ids = self.active_call_ids.lock()?;
+        match active_call_ids.first() {
+            Some(v) => Err(RingRtcError::CallAlreadyInProgress(*v).into()),
+            None if self.call_map.lock()?.contains_key(&call_id) => {
+                Err(RingRtcError::CallIdCollision(call_id).into())
+            }
             None => {
-                let call_id = CallId::random();
                 let mut call = Call::new(
                     remote_peer,
                     call_id,
                     CallDirection::OutGoing,
-                    TIME_OUT_PERIOD,
+                    Some(config),
                     self.clone(),
                 )?;
                 let mut call_map = self.call_map.lock()?;
 
                 call_map.insert(call_id, call.clone());
-                *active_call_id = Some(call_id);
+                active_call_ids.push(call_id);
                 call.inject_start_call()
             }
         }
@@ -729,18 +2465,31 @@ where
 
     /// Handle accept_call() API from application.
     fn handle_accept_call(&mut self, call_id: CallId) -> Result<()> {
-        let mut active_call = check_active_call!(self, "handle_accept_call");
+        let mut active_call = check_active_call!(self, call_id, "handle_accept_call");
 
-        if active_call.call_id() != call_id {
-            info!(
-                "handle_accept_call(): {} no match for active call_id {}",
-                call_id,
-                active_call.call_id()
-            );
-            return Ok(());
+        self.bring_to_foreground(call_id)?;
+        active_call.inject_accept_call()
+    }
+
+    /// Handle hold_requested_by_system() API from application.
+    fn handle_hold_requested_by_system(&mut self, call_id: CallId, on_hold: bool) -> Result<()> {
+        let active_call = check_active_call!(self, call_id, "handle_hold_requested_by_system");
+
+        match active_call.state()? {
+            CallState::Connected | CallState::Reconnecting => {}
+            state => {
+                info!(
+                    "handle_hold_requested_by_system(): ignoring call_id: {}, state: {}",
+                    call_id, state
+                );
+                return Ok(());
+            }
         }
 
-        active_call.inject_accept_call()
+        if !on_hold {
+            self.bring_to_foreground(call_id)?;
+        }
+        active_call.active_connection()?.set_on_hold(on_hold)
     }
 
     fn handle_conclude_active_call(
@@ -749,46 +2498,69 @@ where
         send_hangup: bool,
         event: ApplicationEvent,
     ) -> Result<()> {
-        self.clear_active_call()?;
+        self.clear_active_call(active_call.call_id())?;
         self.conclude_call(active_call, send_hangup, Some(event))
     }
 
     /// Handle drop_call() API from application.
     fn handle_drop_call(&mut self, call_id: CallId) -> Result<()> {
-        let active_call = check_active_call!(self, "handle_drop_call");
-
-        if active_call.call_id() != call_id {
-            info!(
-                "handle_drop_call(): {} no match for active call_id {}",
-                call_id,
-                active_call.call_id()
-            );
-            return Ok(());
-        }
+        let active_call = check_active_call!(self, call_id, "handle_drop_call");
 
         self.handle_conclude_active_call(active_call, true, ApplicationEvent::EndedAppDroppedCall)
     }
 
-    /// Handle proceed() API from application.
-    fn handle_proceed(
+    /// Handle proceed() API from application.
+    fn handle_proceed(
+        &mut self,
+        call_id: CallId,
+        app_call_context: <T as Platform>::AppCallContext,
+        remote_devices: Vec<DeviceId>,
+        ice_servers: Vec<IceServerConfig>,
+    ) -> Result<()> {
+        let mut active_call = check_active_call!(self, call_id, "handle_proceed");
+
+        active_call.set_call_context(app_call_context)?;
+        if !ice_servers.is_empty() {
+            active_call.set_ice_servers(order_by_transport(
+                &ice_servers,
+                &DEFAULT_TURN_TRANSPORT_ORDER,
+            ))?;
+        }
+        active_call.inject_proceed(remote_devices)
+    }
+
+    /// Handle update_ice_servers() API from application.
+    fn handle_update_ice_servers(
         &mut self,
         call_id: CallId,
-        app_call_context: <T as Platform>::AppCallContext,
-        remote_devices: Vec<DeviceId>,
+        ice_servers: Vec<IceServerConfig>,
     ) -> Result<()> {
-        let mut active_call = check_active_call!(self, "handle_proceed");
+        let active_call = check_active_call!(self, call_id, "handle_update_ice_servers");
 
-        if active_call.call_id() != call_id {
-            info!(
-                "handle_proceed(): {} no match for active call_id {}",
-                call_id,
-                active_call.call_id()
-            );
-            return Ok(());
-        }
+        active_call.set_ice_servers(order_by_transport(
+            &ice_servers,
+            &DEFAULT_TURN_TRANSPORT_ORDER,
+        ))
+    }
 
-        active_call.set_call_context(app_call_context)?;
-        active_call.inject_proceed(remote_devices)
+    /// Handle cancel_setup() API from application.
+    fn handle_cancel_setup(&mut self, call_id: CallId) -> Result<()> {
+        let active_call = check_active_call!(self, call_id, "handle_cancel_setup");
+
+        // Cancel first, so the FSM cannot connect the call once the
+        // state is checked.
+        active_call.cancel_setup();
+        match active_call.state()? {
+            CallState::Connected | CallState::Reconnecting => {
+                info!("handle_cancel_setup(): ignoring for connected call");
+                Ok(())
+            }
+            _ => self.handle_conclude_active_call(
+                active_call,
+                true,
+                ApplicationEvent::EndedSetupCancelled,
+            ),
+        }
     }
 
     /// Handle message_sent() API from application.
@@ -810,29 +2582,29 @@ where
 
     /// Handle message_send_failure() API from application.
     fn handle_message_send_failure(&mut self, call_id: CallId) -> Result<()> {
-        // Get the last sent message type and see if it was for Ice.
+        // Get the last sent message type and see if it was for Ice,
+        // or a ringing acknowledgment or pre-offer, which are as
+        // harmless to lose.
         let mut last_sent_message_ice = false;
         if let Ok(message_queue) = self.message_queue.lock() {
-            if message_queue.last_sent_message_type == SignalingMessageType::Ice {
+            if message_queue.last_sent_message_type == SignalingMessageType::Ice
+                || message_queue.last_sent_message_type == SignalingMessageType::Ringing
+                || message_queue.last_sent_message_type == SignalingMessageType::PreOffer
+            {
                 last_sent_message_ice = true
             }
         }
 
         let mut handle_active_call = false;
-        if let Ok(active_call) = self.active_call() {
-            if active_call.call_id() == call_id {
-                handle_active_call = true;
-                if let Ok(state) = active_call.state() {
-                    match state {
-                        CallState::Ringing | CallState::Connected | CallState::Reconnecting => {
-                            // We are in some connected state, ignore if the failed message
-                            // was an Ice message.
-                            if last_sent_message_ice {
-                                handle_active_call = false;
-                            }
-                        }
-                        _ => {}
-                    }
+        if let Ok(active_call) = self.active_call_by_id(call_id) {
+            handle_active_call = true;
+            if let Ok(CallState::Ringing | CallState::Connected | CallState::Reconnecting) =
+                active_call.state()
+            {
+                // We are in some connected state, ignore if the failed message
+                // was an Ice message.
+                if last_sent_message_ice {
+                    handle_active_call = false;
                 }
             }
         }
@@ -843,7 +2615,8 @@ where
                 call_id
             );
 
-            let _ = self.conclude_active_call(true, ApplicationEvent::EndedSignalingFailure);
+            let _ =
+                self.conclude_active_call(call_id, true, ApplicationEvent::EndedSignalingFailure);
         } else {
             // See if the associated call is in the call map.
             let mut call = None;
@@ -884,8 +2657,25 @@ where
     }
 
     /// Handle hangup() API from application.
+    ///
+    /// Hanging up an incoming call before it connects declines it.
     fn handle_hangup(&mut self) -> Result<()> {
+        self.cancel_busy_retry()?;
+
         let active_call = check_active_call!(self, "handle_hangup");
+
+        self.local_hangup(active_call)
+    }
+
+    /// Hang up the active call on behalf of the local user.
+    fn local_hangup(&mut self, active_call: Call<T>) -> Result<()> {
+        if active_call.direction() == CallDirection::InComing {
+            match active_call.state()? {
+                CallState::Connected | CallState::Reconnecting => {}
+                _ => active_call.set_hangup_type(HangupType::Declined)?,
+            }
+        }
+
         self.handle_conclude_active_call(active_call, true, ApplicationEvent::EndedLocalHangup)
     }
 
@@ -896,58 +2686,123 @@ where
         connection_id: ConnectionId,
         offer: String,
         timestamp: u64,
+        age: Duration,
+        config: CallConfig,
     ) -> Result<()> {
         info!("handle_received_offer(): id: {}", connection_id);
-        if is_expired(timestamp, Duration::from_secs(120)) {
-            info!("expired_offer(): id: {}", connection_id);
-            self.notify_application(&remote_peer, ApplicationEvent::EndedReceivedOfferExpired)?;
+        let max_offer_age = *self.max_offer_age.lock()?;
+        if age > max_offer_age || is_expired(timestamp, max_offer_age) {
+            info!("expired_offer(): id: {}, age: {:?}", connection_id, age);
+            let call_id = connection_id.call_id();
+            self.notify_application(
+                &remote_peer,
+                call_id,
+                ApplicationEvent::EndedReceivedOfferExpired,
+            )?;
             // Notify application we are completely done with this remote.
-            self.notify_call_concluded(&remote_peer)?;
+            self.notify_call_concluded(&remote_peer, call_id)?;
             return Ok(());
         }
 
         let call_id = connection_id.call_id();
 
-        if self.call_active()? {
-            // Make a call object to ensure that the busy message can be sent
-            // in the future. It does not go into the call map and should not
-            // start a timeout timer.
-            let call = Call::new(
-                remote_peer.clone(),
-                call_id,
-                CallDirection::InComing,
-                0,
-                self.clone(),
-            )?;
-
-            self.notify_application(
-                &remote_peer,
-                ApplicationEvent::EndedReceivedOfferWhileActive,
-            )?;
-            self.send_busy(call, connection_id)?;
-            self.check_for_glare(&remote_peer)?;
-            return Ok(());
+        if self.call_is_active(call_id)? {
+            // The remote peer may be renegotiating the connection
+            // of the active call, see `signaling::Renegotiation`.
+            let mut active_call = self.active_call_by_id(call_id)?;
+            return active_call.inject_received_offer(connection_id, offer);
         }
 
-        let mut active_call_id = self.active_call_id.lock()?;
-        match *active_call_id {
-            Some(v) => Err(RingRtcError::CallAlreadyInProgress(v).into()),
-            None => {
-                let mut call = Call::new(
-                    remote_peer,
+        if self.call_active()? {
+            // If an active remote peer sent this offer, the two
+            // users are calling each other at the same time, i.e.
+            // glare.  Both sides keep the call with the larger
+            // CallId, so they agree without further signaling.
+            let glare_call = self.active_call_with_remote_peer(&remote_peer);
+            let glare = glare_call.is_some();
+            let glare_won = glare_call.filter(|c| u64::from(call_id) > u64::from(c.call_id()));
+            if let Some(glare_call) = glare_won {
+                info!("handle_received_offer(): glare, received offer wins");
+                self.handle_conclude_active_call(
+                    glare_call,
+                    true,
+                    ApplicationEvent::EndedRemoteGlare,
+                )?;
+            } else if !glare && self.may_wait()? {
+                info!("handle_received_offer(): call waiting");
+            } else {
+                // Make a call object to ensure that the busy message can be sent
+                // in the future. It does not go into the call map and should not
+                // start a timeout timer.
+                let call = Call::new(
+                    remote_peer.clone(),
                     call_id,
                     CallDirection::InComing,
-                    TIME_OUT_PERIOD,
+                    None,
                     self.clone(),
                 )?;
-                let mut call_map = self.call_map.lock()?;
 
-                call_map.insert(call_id, call.clone());
-                *active_call_id = Some(call_id);
-                call.set_pending_call(connection_id.remote_device(), offer)?;
-                call.inject_start_call()
+                let event = if glare {
+                    info!("handle_received_offer(): glare, active call wins");
+                    ApplicationEvent::EndedReceivedOfferWithGlare
+                } else {
+                    ApplicationEvent::EndedReceivedOfferWhileActive
+                };
+                self.notify_application(&remote_peer, call_id, event)?;
+                return self.send_busy(call, connection_id);
+            }
+        }
+
+        let mut active_call_ids = self.active_call_ids.lock()?;
+        if active_call_ids.len() >= MAX_ACTIVE_CALLS {
+            return Err(RingRtcError::CallAlreadyInProgress(active_call_ids[0]).into());
+        }
+        if self.call_map.lock()?.contains_key(&call_id) {
+            // A call concluding meanwhile has the same CallId, do not
+            // replace it.
+            return Err(RingRtcError::CallIdCollision(call_id).into());
+        }
+
+        let mut call = Call::new(
+            remote_peer,
+            call_id,
+            CallDirection::InComing,
+            Some(config),
+            self.clone(),
+        )?;
+        let mut call_map = self.call_map.lock()?;
+
+        call_map.insert(call_id, call.clone());
+        active_call_ids.push(call_id);
+        call.set_offer_metadata(OfferMetadata::new(connection_id, &offer, timestamp))?;
+        call.set_pending_call(connection_id.remote_device(), offer)?;
+        call.inject_start_call()?;
+
+        match self.early_ice_candidates.lock()?.take(connection_id) {
+            Some(ice_candidates) => {
+                call.inject_received_ice_candidates(connection_id, ice_candidates)
             }
+            None => Ok(()),
+        }
+    }
+
+    /// Handle received_pre_offer() API from application.
+    fn handle_received_pre_offer(
+        &self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        let call_id = connection_id.call_id();
+        if self.call_map.lock()?.contains_key(&call_id) {
+            info!(
+                "handle_received_pre_offer(): call_id: {}, offer already received",
+                call_id
+            );
+            return Ok(());
         }
+
+        let platform = self.platform.lock()?;
+        platform.on_received_pre_offer(&remote_peer, connection_id)
     }
 
     /// Handle received_answer() API from application.
@@ -956,97 +2811,560 @@ where
         connection_id: ConnectionId,
         answer: String,
     ) -> Result<()> {
-        let mut active_call = check_active_call!(self, "handle_received_answer");
+        let mut active_call =
+            check_active_call!(self, connection_id.call_id(), "handle_received_answer");
+
+        active_call.inject_received_answer(connection_id, answer)
+    }
+
+    /// Handle received_ice_candidates() API from application.
+    fn handle_received_ice_candidates(
+        &mut self,
+        connection_id: ConnectionId,
+        ice_candidates: Vec<IceCandidate>,
+        age: Duration,
+    ) -> Result<()> {
+        if age > *self.max_ice_candidate_age.lock()? {
+            info!(
+                "handle_received_ice_candidates(): id: {}, discarding expired, age: {:?}",
+                connection_id, age
+            );
+            return self.handle_expired_ice_candidates(connection_id);
+        }
 
-        if active_call.call_id() != connection_id.call_id() {
+        if !self.call_map.lock()?.contains_key(&connection_id.call_id()) {
+            // The offer of the call may still be on its way.
             info!(
-                "handle_received_answer(): skipping inactive call_id: {}",
-                connection_id.call_id()
+                "handle_received_ice_candidates(): id: {}, holding until the offer",
+                connection_id
             );
+            self.early_ice_candidates
+                .lock()?
+                .hold(connection_id, ice_candidates);
             return Ok(());
         }
 
-        active_call.inject_received_answer(connection_id, answer)
+        let mut active_call = check_active_call!(
+            self,
+            connection_id.call_id(),
+            "handle_received_ice_candidates"
+        );
+
+        active_call.inject_received_ice_candidates(connection_id, ice_candidates)
     }
 
-    /// Handle received_ice_candidates() API from application.
-    fn handle_received_ice_candidates(
+    /// Request fresh ICE candidates in place of expired ones.
+    ///
+    /// Restarting ICE renegotiates the candidates, which the
+    /// connection only does as the caller of an already connected
+    /// call; before that the remote peer is still sending its
+    /// candidates.
+    fn handle_expired_ice_candidates(&mut self, connection_id: ConnectionId) -> Result<()> {
+        if !self.call_is_active(connection_id.call_id())? {
+            return Ok(());
+        }
+
+        let active_call = self.active_call_by_id(connection_id.call_id())?;
+        match active_call.active_connection() {
+            Ok(mut connection) if connection.id() == connection_id => {
+                connection.inject_restart_ice()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Handle received_hangup() API from application.
+    fn handle_received_hangup(
         &mut self,
         connection_id: ConnectionId,
-        ice_candidates: Vec<IceCandidate>,
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        let mut active_call =
+            check_active_call!(self, connection_id.call_id(), "handle_received_hangup");
+
+        active_call.inject_received_hangup(connection_id, hangup_type)
+    }
+
+    /// Handle received_declined() API from application.
+    fn handle_received_declined(&mut self, connection_id: ConnectionId) -> Result<()> {
+        let mut active_call =
+            check_active_call!(self, connection_id.call_id(), "handle_received_declined");
+
+        active_call.inject_received_declined(connection_id)
+    }
+
+    /// Handle restart_ice() API from application.
+    fn handle_restart_ice(&mut self, call_id: CallId) -> Result<()> {
+        let active_call = check_active_call!(self, call_id, "handle_restart_ice");
+
+        active_call.active_connection()?.inject_restart_ice()
+    }
+
+    /// Handle received_ringing() API from application.
+    fn handle_received_ringing(&mut self, connection_id: ConnectionId) -> Result<()> {
+        let mut active_call =
+            check_active_call!(self, connection_id.call_id(), "handle_received_ringing");
+
+        active_call.inject_received_ringing(connection_id)
+    }
+
+    /// Handle received_busy() API from application.
+    ///
+    /// A call configured with `CallConfig::retry_on_busy()` calls the
+    /// remote peer again later, until its attempts are exhausted.
+    fn handle_received_busy(&mut self, connection_id: ConnectionId) -> Result<()> {
+        let active_call = check_active_call!(self, connection_id.call_id(), "handle_received_busy");
+
+        let config = match active_call.config() {
+            Some(config) if config.busy_retry_attempts > 0 => config,
+            _ => {
+                return self.handle_conclude_active_call(
+                    active_call,
+                    false,
+                    ApplicationEvent::EndedRemoteBusy,
+                )
+            }
+        };
+
+        let call_id = active_call.call_id();
+        let remote_peer = active_call.remote_peer()?.clone();
+        *self.busy_retry.lock()? = Some(BusyRetry {
+            call_id,
+            remote_peer,
+            config: CallConfig {
+                busy_retry_attempts: config.busy_retry_attempts - 1,
+                ..config
+            },
+        });
+        info!(
+            "handle_received_busy(): call_id: {}, retrying in {:?}, attempts left: {}",
+            call_id, config.busy_retry_interval, config.busy_retry_attempts
+        );
+
+        self.handle_conclude_active_call(
+            active_call,
+            false,
+            ApplicationEvent::EndedRemoteBusyRetry,
+        )?;
+
+        let mut call_manager = self.clone();
+        let retry_future = Delay::new(Instant::now() + config.busy_retry_interval)
+            .map_err(|e| error!("Busy retry Delay failed: {:?}", e))
+            .and_then(move |_| {
+                call_manager
+                    .handle_busy_retry(call_id)
+                    .map_err(|e| error!("Busy retry failed: {}", e))
+            });
+        self.worker_spawn(retry_future)
+    }
+
+    /// Call the busy remote peer of `busy_call_id` again, unless the
+    /// retry was cancelled meanwhile.
+    fn handle_busy_retry(&mut self, busy_call_id: CallId) -> Result<()> {
+        let retry = {
+            let mut busy_retry = self.busy_retry.lock()?;
+            match &*busy_retry {
+                Some(retry) if retry.call_id == busy_call_id => busy_retry.take(),
+                _ => None,
+            }
+        };
+        let retry = match retry {
+            Some(v) => v,
+            None => {
+                info!("handle_busy_retry(): call_id: {}, cancelled", busy_call_id);
+                return Ok(());
+            }
+        };
+
+        // Do not interrupt a call that started meanwhile, e.g. an
+        // incoming one.
+        if self.active_call().is_ok() {
+            info!(
+                "handle_busy_retry(): call_id: {}, call in progress",
+                busy_call_id
+            );
+            return self.notify_application(
+                &retry.remote_peer,
+                busy_call_id,
+                ApplicationEvent::BusyRetryCancelled,
+            );
+        }
+
+        let call_id = CallId::random();
+        info!(
+            "handle_busy_retry(): call_id: {}, calling again with call_id: {}",
+            busy_call_id, call_id
+        );
+        if let Err(err) = self.handle_call(retry.remote_peer.clone(), call_id, retry.config) {
+            error!("Handle call failed: {}", err);
+            self.internal_create_api_error(&retry.remote_peer, call_id, err);
+        }
+        Ok(())
+    }
+
+    /// Return `call_id` if no live call has it, otherwise a new
+    /// random CallId if enabled, see `set_regenerate_call_ids()`.
+    fn unique_call_id(&self, call_id: CallId) -> Result<CallId> {
+        let call_map = self.call_map.lock()?;
+        let group_calls = self.group_calls.lock()?;
+        let is_live = |id: &CallId| call_map.contains_key(id) || group_calls.contains_key(id);
+
+        if !is_live(&call_id) {
+            return Ok(call_id);
+        }
+        if !*self.regenerate_call_ids.lock()? {
+            return Err(RingRtcError::CallIdCollision(call_id).into());
+        }
+
+        loop {
+            let new_call_id = CallId::secure_random();
+            if !is_live(&new_call_id) {
+                info!(
+                    "unique_call_id(): call_id: {} collides, using: {}",
+                    call_id, new_call_id
+                );
+                return Ok(new_call_id);
+            }
+        }
+    }
+
+    /// Cancel calling a busy remote peer again, if pending.
+    fn cancel_busy_retry(&mut self) -> Result<()> {
+        let retry = self.busy_retry.lock()?.take();
+        match retry {
+            Some(retry) => {
+                info!("cancel_busy_retry(): call_id: {}", retry.call_id);
+                self.notify_application(
+                    &retry.remote_peer,
+                    retry.call_id,
+                    ApplicationEvent::BusyRetryCancelled,
+                )
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Handle audio_device_failure() API from platform.
+    fn handle_audio_device_failure(&mut self, error: AudioDeviceError) -> Result<()> {
+        let active_call = check_active_call!(self, "handle_audio_device_failure");
+
+        warn!("handle_audio_device_failure(): error: {}", error);
+
+        {
+            let remote_peer = active_call.remote_peer()?;
+            self.notify_application(
+                &*remote_peer,
+                active_call.call_id(),
+                error.application_event(),
+            )?;
+        }
+
+        if *self.end_on_audio_failure.lock()? {
+            self.handle_conclude_active_call(
+                active_call,
+                true,
+                ApplicationEvent::EndedAudioDeviceFailure,
+            )
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Handle restore_pending_signaling() API from application.
+    fn handle_restore_pending_signaling(&mut self) -> Result<()> {
+        let pending = self.platform.lock()?.restore_pending_signaling()?;
+        info!("handle_restore_pending_signaling(): len: {}", pending.len());
+
+        for signaling in pending {
+            let message_item = Self::restored_message_item(signaling);
+            self.send_next_message(Some(message_item))?;
+        }
+        Ok(())
+    }
+
+    /// Create a message item sending a restored signaling message,
+    /// which no longer belongs to any call.
+    fn restored_message_item(
+        signaling: PendingSignaling<<T as Platform>::AppRemotePeer>,
+    ) -> SignalingMessageItem<T> {
+        let call_id = signaling.connection_id.call_id();
+        let message_type = match signaling.message {
+            PendingMessage::Offer { .. } => SignalingMessageType::Offer,
+            PendingMessage::Answer { .. } => SignalingMessageType::Answer,
+            PendingMessage::IceCandidates(_) => SignalingMessageType::Ice,
+        };
+
+        let persisted = signaling.clone();
+        let message_closure = Box::new(move |cm: &CallManager<T>| {
+            info!("restore_pending_signaling(): closure: {}", signaling);
+
+            let remote_peer = &signaling.remote_peer;
+            let connection_id = signaling.connection_id;
+            let platform = cm.platform.lock()?;
+            let transport = cm.signaling_transport.lock()?;
+            match &signaling.message {
+                PendingMessage::Offer {
+                    description,
+                    ice_restart,
+                } => {
+                    let descriptor = MediaDescriptor::from_sdp(description);
+                    if *ice_restart {
+                        transport.send_restart_offer(
+                            &*platform,
+                            remote_peer,
+                            connection_id,
+                            false,
+                            description,
+                            &descriptor,
+                        )
+                    } else {
+                        transport.send_offer(
+                            &*platform,
+                            remote_peer,
+                            connection_id,
+                            false,
+                            description,
+                            &descriptor,
+                        )
+                    }
+                }
+                PendingMessage::Answer { description } => transport.send_answer(
+                    &*platform,
+                    remote_peer,
+                    connection_id,
+                    false,
+                    description,
+                    &MediaDescriptor::from_sdp(description),
+                ),
+                PendingMessage::IceCandidates(candidates) => transport.send_ice_candidates(
+                    &*platform,
+                    remote_peer,
+                    connection_id,
+                    false,
+                    candidates,
+                ),
+            }
+        });
+
+        SignalingMessageItem {
+            call_id,
+            message_type,
+            message_closure,
+            pending_closure: Some(Box::new(move || Ok(Some(persisted.clone())))),
+        }
+    }
+
+    /// Handle reset() API from application.
+    ///
+    /// Conclude all calls and clear the active callIds.  Do not notify
+    /// the application at the conclusion.
+    fn handle_reset(&mut self) -> Result<()> {
+        info!("handle_reset():");
+
+        // gather all the calls from the call_map.
+        let calls: Vec<Call<T>> = {
+            let call_map = self.call_map.lock()?;
+            call_map.values().cloned().collect()
+        };
+
+        // foreach call, conclude without notifying application
+        for call in calls {
+            info!("reset(): concluding call_id: {}", call.call_id());
+            let _ = self.conclude_call(call, true, None);
+        }
+
+        self.active_call_ids.lock()?.clear();
+
+        // forget calling a busy remote peer again
+        *self.busy_retry.lock()? = None;
+
+        // drop the ICE candidates of calls that never arrived
+        self.early_ice_candidates.lock()?.clear();
+
+        // drop the group calls, without notifying the application
+        self.group_calls.lock()?.clear();
+
+        // clear out the message queue, the app gave up on everything
+        {
+            let mut message_queue = self.message_queue.lock()?;
+            message_queue.queue.clear();
+            message_queue.messages_in_flight = false;
+        }
+        self.persist_pending_signaling();
+
+        info!("reset(): complete");
+        Ok(())
+    }
+
+    /// Handle shutdown() API from application.
+    ///
+    /// Hang up all active calls and end all group calls, notifying
+    /// the application.
+    fn handle_shutdown(&mut self) -> Result<()> {
+        info!("handle_shutdown():");
+
+        for call_id in self.active_call_ids()? {
+            info!("handle_shutdown(): hanging up call_id: {}", call_id);
+            let active_call = self.call_by_id(call_id)?;
+            let _ = self.local_hangup(active_call);
+        }
+
+        let group_call_ids: Vec<CallId> = self.group_calls.lock()?.keys().cloned().collect();
+        for call_id in group_call_ids {
+            info!("handle_shutdown(): ending group call_id: {}", call_id);
+            let _ = self.handle_end_group_call(call_id);
+        }
+
+        info!("handle_shutdown(): complete");
+        Ok(())
+    }
+
+    /// Handle create_group_call() API from application.
+    fn handle_create_group_call(
+        &mut self,
+        call_id: CallId,
+        participants: Vec<(ParticipantId, <T as Platform>::AppRemotePeer)>,
+    ) -> Result<()> {
+        info!("handle_create_group_call(): call_id: {}", call_id);
+
+        let group_call = GroupCall::new(call_id);
+        for (participant_id, remote_peer) in participants {
+            group_call.add_participant(participant_id, remote_peer)?;
+        }
+
+        let mut group_calls = self.group_calls.lock()?;
+        group_calls.insert(call_id, group_call);
+        Ok(())
+    }
+
+    /// Handle add_group_participant() API from application.
+    fn handle_add_group_participant(
+        &mut self,
+        call_id: CallId,
+        participant_id: ParticipantId,
+        remote_peer: <T as Platform>::AppRemotePeer,
+    ) -> Result<()> {
+        info!(
+            "handle_add_group_participant(): call_id: {}, participant: {}",
+            call_id, participant_id
+        );
+
+        self.group_call(call_id)?
+            .add_participant(participant_id, remote_peer)
+    }
+
+    /// Handle group_participant_connected() API from application.
+    fn handle_group_participant_connected(
+        &mut self,
+        call_id: CallId,
+        participant_id: ParticipantId,
     ) -> Result<()> {
-        let mut active_call = check_active_call!(self, "handle_received_ice_candidates");
+        info!(
+            "handle_group_participant_connected(): call_id: {}, participant: {}",
+            call_id, participant_id
+        );
 
-        if active_call.call_id() != connection_id.call_id() {
+        let group_call = self.group_call(call_id)?;
+        let state = group_call.participant_state(participant_id)?;
+        if state != ParticipantState::Joining {
             info!(
-                "handle_received_ice_candidates(): skipping inactive call_id: {}",
-                connection_id.call_id()
+                "handle_group_participant_connected(): ignoring, participant is {}",
+                state
             );
             return Ok(());
         }
 
-        active_call.inject_received_ice_candidates(connection_id, ice_candidates)
+        group_call.set_participant_state(participant_id, ParticipantState::Joined)?;
+        self.notify_application(
+            &group_call.remote_peer(participant_id)?,
+            group_call.call_id(),
+            ApplicationEvent::GroupParticipantJoined,
+        )?;
+        self.connect_participant_media(&group_call, participant_id)
     }
 
-    /// Handle received_hangup() API from application.
-    fn handle_received_hangup(&mut self, connection_id: ConnectionId) -> Result<()> {
-        let mut active_call = check_active_call!(self, "handle_received_hangup");
-
-        if active_call.call_id() != connection_id.call_id() {
-            info!(
-                "handle_received_hangup(): skipping inactive call_id: {}",
-                connection_id.call_id()
-            );
-            return Ok(());
-        }
+    /// Handle group_participant_disconnected() API from application.
+    fn handle_group_participant_disconnected(
+        &mut self,
+        call_id: CallId,
+        participant_id: ParticipantId,
+    ) -> Result<()> {
+        info!(
+            "handle_group_participant_disconnected(): call_id: {}, participant: {}",
+            call_id, participant_id
+        );
 
-        active_call.inject_received_hangup(connection_id)
+        let group_call = self.group_call(call_id)?;
+        self.participant_left(&group_call, participant_id)
     }
 
-    /// Handle received_busy() API from application.
-    fn handle_received_busy(&mut self, connection_id: ConnectionId) -> Result<()> {
-        let active_call = check_active_call!(self, "handle_received_busy");
+    /// Handle group_participant_media() API from application.
+    fn handle_group_participant_media(
+        &mut self,
+        call_id: CallId,
+        participant_id: ParticipantId,
+        app_media_stream: <T as Platform>::AppMediaStream,
+    ) -> Result<()> {
+        info!(
+            "handle_group_participant_media(): call_id: {}, participant: {}",
+            call_id, participant_id
+        );
 
-        if active_call.call_id() != connection_id.call_id() {
-            info!(
-                "handle_received_busy(): skipping inactive call_id: {}",
-                connection_id.call_id()
-            );
-            return Ok(());
-        }
-        self.handle_conclude_active_call(active_call, false, ApplicationEvent::EndedRemoteBusy)
+        let group_call = self.group_call(call_id)?;
+        group_call.set_media_stream(participant_id, app_media_stream)?;
+        self.connect_participant_media(&group_call, participant_id)
     }
 
-    /// Handle reset() API from application.
-    ///
-    /// Conclude all calls and clear active callId.  Do not notify the
-    /// application at the conclusion.
-    fn handle_reset(&mut self) -> Result<()> {
-        info!("handle_reset():");
+    /// Handle end_group_call() API from application.
+    fn handle_end_group_call(&mut self, call_id: CallId) -> Result<()> {
+        info!("handle_end_group_call(): call_id: {}", call_id);
 
-        // gather all the calls from the call_map.
-        let calls: Vec<Call<T>> = {
-            let call_map = self.call_map.lock()?;
-            call_map.values().cloned().collect()
+        let group_call = match self.group_calls.lock()?.remove(&call_id) {
+            Some(v) => v,
+            None => return Err(RingRtcError::GroupCallNotFound(call_id).into()),
         };
 
-        // foreach call, conclude without notifying application
-        for call in calls {
-            info!("reset(): concluding call_id: {}", call.call_id());
-            let _ = self.conclude_call(call, true, None);
+        for participant_id in group_call.participant_ids()? {
+            self.participant_left(&group_call, participant_id)?;
         }
+        Ok(())
+    }
 
-        self.clear_active_call()?;
-
-        // clear out the message queue, the app gave up on everything
-        let mut message_queue = self.message_queue.lock()?;
-        message_queue.queue.clear();
-        message_queue.messages_in_flight = false;
-
-        info!("reset(): complete");
+    /// Move a group call participant to the `Left` state, notifying
+    /// the application if it had joined.
+    fn participant_left(
+        &self,
+        group_call: &GroupCall<T>,
+        participant_id: ParticipantId,
+    ) -> Result<()> {
+        let state = group_call.set_participant_state(participant_id, ParticipantState::Left)?;
+        if state == ParticipantState::Joined {
+            self.notify_application(
+                &group_call.remote_peer(participant_id)?,
+                group_call.call_id(),
+                ApplicationEvent::GroupParticipantLeft,
+            )?;
+        }
         Ok(())
     }
 
+    /// Hand the media stream of a joined group call participant to
+    /// the application.
+    fn connect_participant_media(
+        &self,
+        group_call: &GroupCall<T>,
+        participant_id: ParticipantId,
+    ) -> Result<()> {
+        let platform = self.platform.lock()?;
+        group_call.with_joined_media(participant_id, |remote_peer, app_media_stream| {
+            platform.on_connect_participant_media(
+                remote_peer,
+                group_call.call_id(),
+                app_media_stream,
+            )
+        })
+    }
+
     fn send_busy(&mut self, call: Call<T>, connection_id: ConnectionId) -> Result<()> {
         info!("send_busy(): id: {}", connection_id);
 
@@ -1056,47 +3374,111 @@ where
             let remote_peer = call.remote_peer()?;
 
             let platform = cm.platform.lock()?;
-            platform.on_send_busy(&*remote_peer, connection_id, true)
+            let transport = cm.signaling_transport.lock()?;
+            transport.send_busy(&*platform, &*remote_peer, connection_id, true)
         });
 
         let message_item = SignalingMessageItem {
             call_id:         connection_id.call_id(),
             message_type:    SignalingMessageType::Busy,
             message_closure: busy_closure,
+            pending_closure: None,
         };
 
         self.send_next_message(Some(message_item))
     }
 
-    /// If the active_remote_peer equals this remote peer, then we
-    /// have glare, i.e. two users are trying to call each other at
-    /// the same time.
-    fn check_for_glare(&mut self, remote_peer: &<T as Platform>::AppRemotePeer) -> Result<()> {
-        if self.remote_peer_equals_active(remote_peer) {
-            info!("check_for_glare(): remotes are equal, hanging up.");
-            self.handle_conclude_active_call(
-                self.active_call()?,
-                true,
-                ApplicationEvent::EndedRemoteGlare,
-            )
-        } else {
-            Ok(())
+    /// Send a ringing acknowledgment to the caller.
+    pub(super) fn send_ringing(
+        &mut self,
+        call: Call<T>,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!("send_ringing(): id: {}", connection_id);
+
+        let ringing_closure = Box::new(move |cm: &CallManager<T>| {
+            info!("send_ringing(): closure");
+
+            let remote_peer = call.remote_peer()?;
+
+            let platform = cm.platform.lock()?;
+            let transport = cm.signaling_transport.lock()?;
+            transport.send_ringing(&*platform, &*remote_peer, connection_id)
+        });
+
+        let message_item = SignalingMessageItem {
+            call_id:         connection_id.call_id(),
+            message_type:    SignalingMessageType::Ringing,
+            message_closure: ringing_closure,
+            pending_closure: None,
+        };
+
+        self.send_next_message(Some(message_item))
+    }
+
+    /// Send a pre-offer to all the devices of the remote peer, if
+    /// enabled with `set_pre_offer()`.
+    pub(super) fn send_pre_offer(&mut self, call: Call<T>) -> Result<()> {
+        if !*self.pre_offer.lock()? {
+            return Ok(());
         }
+
+        let connection_id = ConnectionId::new(call.call_id(), 0);
+        info!("send_pre_offer(): id: {}", connection_id);
+
+        let pre_offer_closure = Box::new(move |cm: &CallManager<T>| {
+            info!("send_pre_offer(): closure");
+
+            let remote_peer = call.remote_peer()?;
+
+            let platform = cm.platform.lock()?;
+            let transport = cm.signaling_transport.lock()?;
+            transport.send_pre_offer(&*platform, &*remote_peer, connection_id)
+        });
+
+        let message_item = SignalingMessageItem {
+            call_id:         connection_id.call_id(),
+            message_type:    SignalingMessageType::PreOffer,
+            message_closure: pre_offer_closure,
+            pending_closure: None,
+        };
+
+        self.send_next_message(Some(message_item))
     }
 
-    /// Check if the remote_peer matches the remote_peer in the active
-    /// call.
-    fn remote_peer_equals_active(&self, remote_peer: &<T as Platform>::AppRemotePeer) -> bool {
-        if let Ok(active_call) = self.active_call() {
-            if let Ok(active_remote_peer) = active_call.remote_peer() {
-                if let Ok(platform) = self.platform.lock() {
-                    if let Ok(result) = platform.compare_remotes(&active_remote_peer, remote_peer) {
-                        return result;
-                    }
-                }
-            }
+    /// Return the active call, if any, whose remote_peer matches
+    /// remote_peer.
+    fn active_call_with_remote_peer(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+    ) -> Option<Call<T>> {
+        let active_calls: Vec<Call<T>> = self
+            .active_call_ids()
+            .ok()?
+            .into_iter()
+            .filter_map(|call_id| self.call_by_id(call_id).ok())
+            .collect();
+        let platform = self.platform.lock().ok()?;
+        active_calls
+            .into_iter()
+            .find(|call| match call.remote_peer() {
+                Ok(active_remote_peer) => platform
+                    .compare_remotes(&active_remote_peer, remote_peer)
+                    .unwrap_or(false),
+                Err(_) => false,
+            })
+    }
+
+    /// Check if a received offer may start a waiting call rather than
+    /// be answered with busy.
+    fn may_wait(&self) -> Result<bool> {
+        if !*self.call_waiting.lock()? || self.active_call_ids.lock()?.len() >= MAX_ACTIVE_CALLS {
+            return Ok(false);
+        }
+        match self.active_call()?.state()? {
+            CallState::Connected | CallState::Reconnecting => Ok(true),
+            _ => Ok(false),
         }
-        false
     }
 
     /// Internal failure during API future that creates a call.
@@ -1107,24 +3489,37 @@ where
     fn internal_create_api_error(
         &mut self,
         remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
         error: failure::Error,
     ) {
-        info!("internal_create_api_error(): error: {}", error);
-        if self.remote_peer_equals_active(remote_peer) {
+        info!(
+            "internal_create_api_error(): call_id: {}, error: {}",
+            call_id, error
+        );
+        if let Some(RingRtcError::CallIdCollision(_)) = error.downcast_ref::<RingRtcError>() {
+            // The CallId belongs to another call, leave that alone.
+            return;
+        }
+        if let Ok(true) = self.call_is_active(call_id) {
             // The future managed to create the active call and then
             // hit problems.  Error out with active call clean up.
-            let _ = self.internal_api_error(error);
+            let _ = self.internal_error(call_id, error);
         } else {
             // The future hit problems before creating an active call.
             // Simply notify the application with no call clean up.
-            let _ = self.notify_application(remote_peer, ApplicationEvent::EndedInternalFailure);
-            let _ = self.notify_call_concluded(remote_peer);
+            let _ = self.notify_call_failed(remote_peer, call_id, CallFailureReason::from(&error));
+            let _ = self.notify_application(
+                remote_peer,
+                call_id,
+                ApplicationEvent::EndedInternalFailure,
+            );
+            let _ = self.notify_call_concluded(remote_peer, call_id);
         }
     }
 
     /// Internal error occured on an API future.
     ///
-    /// This shuts down the specified call if active and notifies the
+    /// This shuts down the foreground call, if any, and notifies the
     /// application.
     fn internal_api_error(&mut self, error: failure::Error) -> Result<()> {
         info!("internal_api_error(): error: {}", error);
@@ -1139,9 +3534,58 @@ where
     /// Push the given message and send the next message in
     /// the queue if no other message is currently in the
     /// process of being sent (in flight).
+    ///
+    /// The messages left waiting are handed to the application to
+    /// persist.
     fn send_next_message(
         &mut self,
         message_item_option: Option<SignalingMessageItem<T>>,
+    ) -> Result<()> {
+        let result = self.send_next_queued_message(message_item_option);
+        self.persist_pending_signaling();
+        result
+    }
+
+    /// Hand the messages waiting in the queue to the application to
+    /// persist, unless neither they nor those persisted before hold
+    /// any message.
+    ///
+    /// Failing to persist does not stop the messages being sent, so
+    /// errors are only logged.
+    fn persist_pending_signaling(&self) {
+        let persist = || -> Result<()> {
+            let pending = {
+                let mut message_queue = self.message_queue.lock()?;
+                let mut pending = Vec::new();
+                for item in &message_queue.queue {
+                    if let Some(pending_closure) = item.pending_closure.as_ref() {
+                        if let Some(signaling) = pending_closure()? {
+                            pending.push(signaling);
+                        }
+                    }
+                }
+                if pending.is_empty() && !message_queue.persisted {
+                    return Ok(());
+                }
+                message_queue.persisted = !pending.is_empty();
+                pending
+            };
+
+            info!("persist_pending_signaling(): len: {}", pending.len());
+            let platform = self.platform.lock()?;
+            platform.persist_pending_signaling(&pending)
+        };
+
+        if let Err(e) = persist() {
+            warn!("persist_pending_signaling(): failed: {}", e);
+        }
+    }
+
+    /// Push the given message and send the next message in the
+    /// queue, as for `send_next_message()`.
+    fn send_next_queued_message(
+        &mut self,
+        message_item_option: Option<SignalingMessageItem<T>>,
     ) -> Result<()> {
         info!("send_next_message():");
 
@@ -1226,54 +3670,315 @@ where
         let mq = &mut *message_queue;
 
         debug!(
-            "trim_messages(): start id: {} len: {}",
-            call_id,
-            mq.queue.len()
+            "trim_messages(): start id: {} len: {}",
+            call_id,
+            mq.queue.len()
+        );
+        mq.queue
+            .retain(|x| (x.call_id != call_id) || (x.message_type == SignalingMessageType::Busy));
+        debug!("trim_messages(): end len: {}", mq.queue.len());
+        drop(message_queue);
+
+        self.persist_pending_signaling();
+        Ok(())
+    }
+
+    ////////////////////////////////////////////////////////////////////////
+    // Module level public functions start here
+    ////////////////////////////////////////////////////////////////////////
+
+    /// Inform the application that a call should be started.
+    pub(super) fn start_call(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        direction: CallDirection,
+    ) -> Result<()> {
+        info!("handle_start_call(): direction: {}", direction);
+
+        let platform = self.platform.lock()?;
+        platform.on_start_call(remote_peer, call_id, direction)
+    }
+
+    /// Notify application of an event.
+    pub(super) fn notify_application(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        event: ApplicationEvent,
+    ) -> Result<()> {
+        info!(
+            "notify_application(): call_id: {}, event: {}",
+            call_id, event
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_event(remote_peer, call_id, event)
+    }
+
+    /// Notify application of a change in the remote video rotation.
+    pub(super) fn remote_video_orientation(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        degrees: u16,
+    ) -> Result<()> {
+        info!(
+            "remote_video_orientation(): id: {}, degrees: {}",
+            connection_id, degrees
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_remote_video_orientation(remote_peer, connection_id, degrees)
+    }
+
+    /// Notify application of an application message received from
+    /// the remote peer.
+    pub(super) fn app_message_received(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        payload: &[u8],
+    ) -> Result<()> {
+        info!(
+            "app_message_received(): id: {}, size: {}",
+            connection_id,
+            payload.len()
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_app_message_received(remote_peer, connection_id, payload)
+    }
+
+    /// Notify application of the safety numbers of the connected
+    /// call.
+    pub(super) fn safety_numbers(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        safety_numbers: &str,
+    ) -> Result<()> {
+        info!("safety_numbers(): id: {}", connection_id);
+
+        let platform = self.platform.lock()?;
+        platform.on_safety_numbers(remote_peer, connection_id, safety_numbers)
+    }
+
+    /// Notify application of a DTMF tone received from the remote
+    /// peer.
+    pub(super) fn received_dtmf(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        tone: char,
+    ) -> Result<()> {
+        info!("received_dtmf(): id: {}, tone: {}", connection_id, tone);
+
+        let platform = self.platform.lock()?;
+        platform.on_received_dtmf(remote_peer, connection_id, tone)
+    }
+
+    /// Notify application of a change of the ICE route between direct
+    /// and relayed.
+    pub(super) fn ice_route_changed(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        route: &IceRoute,
+    ) -> Result<()> {
+        info!(
+            "ice_route_changed(): id: {}, route: {}",
+            connection_id, route
+        );
+
+        let platform = self.platform.lock()?;
+        if route.relayed() {
+            platform.on_route_downgraded_to_relay(remote_peer, connection_id, route)
+        } else {
+            platform.on_route_upgraded_to_direct(remote_peer, connection_id, route)
+        }
+    }
+
+    /// Notify application of the network the ICE route of the call
+    /// runs over.
+    pub(super) fn network_route_changed(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        network_route: NetworkRoute,
+    ) -> Result<()> {
+        info!(
+            "network_route_changed(): call_id: {}, adapter: {}, relayed: {}",
+            call_id, network_route.local_adapter_type, network_route.relayed
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_network_route_changed(
+            remote_peer,
+            call_id,
+            network_route.local_adapter_type,
+            network_route.relayed,
+        )
+    }
+
+    /// Notify application of the duration of the connected call.
+    pub(super) fn call_duration_tick(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        duration: Duration,
+    ) -> Result<()> {
+        debug!(
+            "call_duration_tick(): call_id: {}, duration: {:?}",
+            call_id, duration
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_call_duration_tick(remote_peer, call_id, duration)
+    }
+
+    /// Notify application of the stats of the active call.
+    pub(super) fn stats_report(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        stats: &CallStats,
+    ) -> Result<()> {
+        debug!("stats_report(): call_id: {}, {}", call_id, stats);
+
+        let platform = self.platform.lock()?;
+        platform.on_stats_report(remote_peer, call_id, stats)
+    }
+
+    /// Notify application of the audio levels of the active call.
+    pub(super) fn audio_levels(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        captured: f64,
+        received: f64,
+    ) -> Result<()> {
+        debug!(
+            "audio_levels(): call_id: {}, captured: {}, received: {}",
+            call_id, captured, received
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_audio_levels(remote_peer, call_id, captured, received)
+    }
+
+    /// Notify application of the bandwidth estimate of the active
+    /// call.
+    pub(super) fn bandwidth_estimate(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        send_bps: u64,
+        receive_bps: u64,
+    ) -> Result<()> {
+        debug!(
+            "bandwidth_estimate(): call_id: {}, send_bps: {}, receive_bps: {}",
+            call_id, send_bps, receive_bps
         );
-        mq.queue
-            .retain(|x| (x.call_id != call_id) || (x.message_type == SignalingMessageType::Busy));
-        debug!("trim_messages(): end len: {}", mq.queue.len());
 
-        Ok(())
+        let platform = self.platform.lock()?;
+        platform.on_bandwidth_estimate(remote_peer, call_id, send_bps, receive_bps)
     }
 
-    ////////////////////////////////////////////////////////////////////////
-    // Module level public functions start here
-    ////////////////////////////////////////////////////////////////////////
-
-    /// Inform the application that a call should be started.
-    pub(super) fn start_call(
+    /// Notify application of the ICE candidate gathering state of a
+    /// connection.
+    pub(super) fn ice_gathering_state_changed(
         &self,
         remote_peer: &<T as Platform>::AppRemotePeer,
-        call_id: CallId,
-        direction: CallDirection,
+        connection_id: ConnectionId,
+        state: IceGatheringState,
     ) -> Result<()> {
-        info!("handle_start_call(): direction: {}", direction);
+        info!(
+            "ice_gathering_state_changed(): id: {}, state: {}",
+            connection_id, state
+        );
 
         let platform = self.platform.lock()?;
-        platform.on_start_call(remote_peer, call_id, direction)
+        platform.on_ice_gathering_state_changed(remote_peer, connection_id, state)
     }
 
-    /// Notify application of an event.
-    pub(super) fn notify_application(
+    /// Notify application that the DataChannel send buffer drained
+    /// after a refused send.
+    pub(super) fn data_channel_buffered_amount_low(
         &self,
         remote_peer: &<T as Platform>::AppRemotePeer,
-        event: ApplicationEvent,
+        connection_id: ConnectionId,
     ) -> Result<()> {
-        info!("notify_application(): event: {}", event);
+        info!("data_channel_buffered_amount_low(): id: {}", connection_id);
 
         let platform = self.platform.lock()?;
-        platform.on_event(remote_peer, event)
+        platform.on_data_channel_buffered_amount_low(remote_peer, connection_id)
     }
 
-    /// Create a new connection to a remote device
+    /// Create a new connection to a remote device, its PeerConnection
+    /// using `ice_servers`.
     pub(super) fn create_connection(
         &self,
         call: &Call<T>,
         device_id: DeviceId,
+        ice_servers: Vec<IceServerConfig>,
     ) -> Result<Connection<T>> {
+        let config = ConnectionConfig {
+            ice_servers: ice_servers.clone(),
+            ..self.connection_config.lock()?.clone()
+        };
+        let mut platform = self.platform.lock()?;
+        let connection = platform
+            .create_connection(call, device_id, config)
+            .map_err(|err| RingRtcError::CreatePeerConnection(err.to_string()))?;
+
+        // Remember the servers for a PeerConnection rebuilt later.
+        if !ice_servers.is_empty() {
+            connection.init_ice_servers(ice_servers)?;
+        }
+
+        // The platform created the audio sender, apply any change to
+        // the outgoing audio requested before it existed.
+        connection.set_outgoing_audio_enabled(call.outgoing_audio_enabled())?;
+        let constraints = call
+            .config()
+            .map(|c| c.media_constraints)
+            .unwrap_or_default();
+        connection.apply_media_constraints(constraints)?;
+        connection.set_bandwidth_mode(call.bandwidth_mode()?)?;
+        let audio_config = match call.audio_config()? {
+            Some(config) => config,
+            None => *self.default_audio_config.lock()?,
+        };
+        connection.set_audio_config(audio_config)?;
+        connection.set_ice_candidate_policy(*self.ice_candidate_policy.lock()?)?;
+        if let Some(provider) = self.media_crypto_provider.lock()?.clone() {
+            connection.set_media_crypto_provider(Some(provider))?;
+        }
+        Ok(connection)
+    }
+
+    /// Create a new PeerConnection for a connection being hard reset
+    pub(super) fn recreate_peer_connection(
+        &self,
+        call: &Call<T>,
+        connection: &Connection<T>,
+    ) -> Result<()> {
+        let config = ConnectionConfig {
+            ice_servers: connection.ice_servers()?.unwrap_or_default(),
+            ..self.connection_config.lock()?.clone()
+        };
         let mut platform = self.platform.lock()?;
-        platform.create_connection(call, device_id)
+        platform.recreate_peer_connection(call, connection, config)?;
+        connection.set_outgoing_audio_enabled(call.outgoing_audio_enabled())?;
+        connection.apply_outgoing_video()?;
+        connection.apply_screen_share()?;
+
+        // Keep the media of a call on hold paused.
+        if connection.on_hold()? {
+            connection.apply_hold()?;
+        }
+        Ok(())
     }
 
     /// Create a new application specific media stream
@@ -1297,6 +4002,68 @@ where
         platform.on_connect_media(remote_peer, app_call_context, app_media_stream)
     }
 
+    /// Suggest the application a route for the audio of the call now
+    /// that it shows video, if the current one does not suit it.
+    ///
+    /// The suggested route is taken as the current one, so it is
+    /// suggested once.
+    pub(super) fn suggest_audio_route_for_video(
+        &self,
+        app_call_context: &<T as Platform>::AppCallContext,
+    ) -> Result<()> {
+        let route = {
+            let mut audio_route = self.audio_route.lock()?;
+            match audio_route.for_video() {
+                Some(route) => {
+                    *audio_route = route;
+                    route
+                }
+                None => return Ok(()),
+            }
+        };
+
+        let platform = self.platform.lock()?;
+        platform.on_audio_route_suggested(app_call_context, route)
+    }
+
+    /// Notify the application that the system manages the call
+    pub(super) fn added_to_system(
+        &self,
+        app_call_context: &<T as Platform>::AppCallContext,
+    ) -> Result<()> {
+        let platform = self.platform.lock()?;
+        platform.on_call_added_to_system(app_call_context)
+    }
+
+    /// Ask the system to show the call as on hold or active
+    pub(super) fn system_hold_requested(
+        &self,
+        app_call_context: &<T as Platform>::AppCallContext,
+        on_hold: bool,
+    ) -> Result<()> {
+        let platform = self.platform.lock()?;
+        platform.on_request_system_hold(app_call_context, on_hold)
+    }
+
+    /// Turn the camera of the call on or off
+    pub(super) fn camera_enabled(
+        &self,
+        app_call_context: &<T as Platform>::AppCallContext,
+        enabled: bool,
+    ) -> Result<()> {
+        let platform = self.platform.lock()?;
+        platform.on_set_camera_enabled(app_call_context, enabled)
+    }
+
+    /// Switch to the next camera of the call
+    pub(super) fn camera_switched(
+        &self,
+        app_call_context: &<T as Platform>::AppCallContext,
+    ) -> Result<()> {
+        let platform = self.platform.lock()?;
+        platform.on_switch_camera(app_call_context)
+    }
+
     /// Close the media associated with the call
     pub(super) fn close_media(
         &self,
@@ -1306,27 +4073,333 @@ where
         platform.on_close_media(app_call_context)
     }
 
+    /// Return a copy of the advisory codec preferences.
+    pub(super) fn codec_preferences(&self) -> Result<Vec<String>> {
+        Ok(self.codec_preferences.lock()?.clone())
+    }
+
+    /// Return how long to wait for the answer to a renegotiation offer.
+    pub(super) fn renegotiation_timeout(&self) -> Result<Duration> {
+        Ok(*self.renegotiation_timeout.lock()?)
+    }
+
+    /// Return how many ICE restarts must fail before a hard reset.
+    pub(super) fn ice_restarts_before_hard_reset(&self) -> Result<u32> {
+        Ok(*self.ice_restart_limit.lock()?)
+    }
+
+    /// Return the number of automatic ICE restarts attempted while
+    /// reconnecting, and the delay before the first.
+    pub(super) fn auto_recovery(&self) -> Result<(u32, Duration)> {
+        Ok((
+            *self.recovery_attempts.lock()?,
+            *self.recovery_backoff.lock()?,
+        ))
+    }
+
+    /// Return how long an ICE route must hold before a change is
+    /// reported.
+    pub(super) fn route_change_debounce(&self) -> Result<Duration> {
+        Ok(*self.route_change_debounce.lock()?)
+    }
+
+    /// Return how long local ICE candidates are batched before
+    /// sending.
+    pub(super) fn ice_candidate_batch_period(&self) -> Result<Duration> {
+        Ok(*self.ice_batch_period.lock()?)
+    }
+
+    /// Return whether the application is notified of the call
+    /// duration every second.
+    pub(super) fn call_duration_ticks(&self) -> Result<bool> {
+        Ok(*self.call_duration_ticks.lock()?)
+    }
+
+    /// Return whether new calls record a trace.
+    pub(super) fn tracing(&self) -> Result<bool> {
+        Ok(*self.tracing.lock()?)
+    }
+
+    /// Return how often the application is sent the stats of the
+    /// active call, if at all.
+    pub(super) fn stats_interval(&self) -> Result<Option<Duration>> {
+        Ok(*self.stats_interval.lock()?)
+    }
+
+    /// Return how often the application is sent the audio levels of
+    /// the active call, if at all.
+    pub(super) fn audio_levels_interval(&self) -> Result<Option<Duration>> {
+        Ok(*self.audio_levels_interval.lock()?)
+    }
+
+    /// Return how often the application is sent the bandwidth
+    /// estimate of the active call, if at all.
+    pub(super) fn bandwidth_estimate_interval(&self) -> Result<Option<Duration>> {
+        Ok(*self.bandwidth_interval.lock()?)
+    }
+
+    /// Return how long the media of the active call may stop arriving
+    /// before the application is told, if at all.
+    pub(super) fn media_stall_timeout(&self) -> Result<Option<Duration>> {
+        Ok(*self.media_stall_timeout.lock()?)
+    }
+
+    /// Ask the forking policy which of the `candidates` wins a forked
+    /// outgoing call, if any.
+    pub(super) fn select_fork_winner(
+        &self,
+        candidates: &[ForkCandidate],
+    ) -> Result<Option<DeviceId>> {
+        Ok(self.forking_policy.lock()?.select_winner(candidates))
+    }
+
+    /// Split the remote devices of an outgoing call into the devices
+    /// to connect to and the devices left out by the connection
+    /// limit.
+    pub(super) fn select_devices(
+        &self,
+        remote_devices: Vec<DeviceId>,
+    ) -> Result<(Vec<DeviceId>, Vec<DeviceId>)> {
+        let max = match *self.max_connections.lock()? {
+            Some(max) if remote_devices.len() > max => max,
+            _ => return Ok((remote_devices, Vec::new())),
+        };
+
+        // Only take the devices of the call from the policy, each
+        // once, up to the limit.
+        let mut selected = Vec::new();
+        for remote_device in self.device_selection.lock()?.select(&remote_devices, max) {
+            if selected.len() < max
+                && remote_devices.contains(&remote_device)
+                && !selected.contains(&remote_device)
+            {
+                selected.push(remote_device);
+            }
+        }
+        let deferred = remote_devices
+            .into_iter()
+            .filter(|d| !selected.contains(d))
+            .collect();
+        Ok((selected, deferred))
+    }
+
     /// Remote hangup of the active call.
-    pub(super) fn remote_hangup(&mut self, call_id: CallId) -> Result<()> {
-        info!("remote_hangup(): call_id: {}", call_id);
+    pub(super) fn remote_hangup(&mut self, call_id: CallId, hangup_type: HangupType) -> Result<()> {
+        info!(
+            "remote_hangup(): call_id: {}, type: {}",
+            call_id, hangup_type
+        );
 
         if self.call_is_active(call_id)? {
-            self.conclude_active_call(false, ApplicationEvent::EndedRemoteHangup)
+            self.conclude_active_call(call_id, false, hangup_type.application_event())
         } else {
             info!("remote_hangup(): ignoring for inactive call");
             Ok(())
         }
     }
 
+    /// The remote peer closed the media of the active call, e.g.
+    /// because its client crashed.
+    ///
+    /// A hangup is still sent, in case only the media of the remote
+    /// peer went away.
+    pub(super) fn remote_media_closed(&mut self, call_id: CallId) -> Result<()> {
+        info!("remote_media_closed(): call_id: {}", call_id);
+
+        if self.call_is_active(call_id)? {
+            self.conclude_active_call(call_id, true, ApplicationEvent::EndedRemoteMediaClosed)
+        } else {
+            info!("remote_media_closed(): ignoring for inactive call");
+            Ok(())
+        }
+    }
+
+    /// Ask the application whether the incoming call may ring.
+    pub(super) fn should_ring(&self, call: &Call<T>) -> Result<bool> {
+        let offer_metadata = match call.offer_metadata()? {
+            Some(v) => v,
+            None => return Ok(true),
+        };
+        info!("should_ring(): {}", offer_metadata);
+
+        let remote_peer = call.remote_peer()?;
+        let platform = self.platform.lock()?;
+        platform.should_ring(&*remote_peer, &offer_metadata)
+    }
+
+    /// Ask the application whether to accept the transfer of the call
+    /// to `target`, requested by the remote peer.
+    pub(super) fn should_transfer(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        target: &str,
+    ) -> Result<bool> {
+        info!(
+            "should_transfer(): call_id: {}, target: {}",
+            call_id, target
+        );
+
+        let platform = self.platform.lock()?;
+        platform.should_transfer(remote_peer, call_id, target)
+    }
+
+    /// Fetch fresh ICE servers from the client application, ordered
+    /// by transport preference.
+    pub(super) fn turn_servers(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+    ) -> Result<Vec<IceServerConfig>> {
+        info!("turn_servers(): call_id: {}", call_id);
+
+        let platform = self.platform.lock()?;
+        let ice_servers = platform.get_turn_servers(remote_peer, call_id)?;
+        Ok(order_by_transport(
+            &ice_servers,
+            &DEFAULT_TURN_TRANSPORT_ORDER,
+        ))
+    }
+
+    /// Let the application transform a local description.
+    pub(super) fn transform_local_description(
+        &self,
+        connection_id: ConnectionId,
+        sdp: String,
+    ) -> Result<String> {
+        let platform = self.platform.lock()?;
+        platform.transform_local_description(connection_id, sdp)
+    }
+
+    /// Let the application transform a remote description.
+    pub(super) fn transform_remote_description(
+        &self,
+        connection_id: ConnectionId,
+        sdp: String,
+    ) -> Result<String> {
+        let platform = self.platform.lock()?;
+        platform.transform_remote_description(connection_id, sdp)
+    }
+
+    /// Notify the application of the metadata attached to an answer.
+    pub(super) fn received_answer_metadata(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        metadata: &[u8],
+    ) -> Result<()> {
+        info!(
+            "received_answer_metadata(): id: {}, size: {}",
+            connection_id,
+            metadata.len()
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_received_answer_metadata(remote_peer, connection_id, metadata)
+    }
+
+    /// The remote peer accepted the transfer of the active call.
+    pub(super) fn transfer_accepted(&mut self, call_id: CallId) -> Result<()> {
+        info!("transfer_accepted(): call_id: {}", call_id);
+
+        if self.call_is_active(call_id)? {
+            self.conclude_active_call(call_id, true, ApplicationEvent::EndedTransferred)
+        } else {
+            info!("transfer_accepted(): ignoring for inactive call");
+            Ok(())
+        }
+    }
+
+    /// The application refused to ring the incoming call.
+    pub(super) fn ring_refused(&mut self, call_id: CallId) -> Result<()> {
+        info!("ring_refused(): call_id: {}", call_id);
+
+        if self.call_is_active(call_id)? {
+            self.conclude_active_call(call_id, true, ApplicationEvent::EndedRingRefused)
+        } else {
+            info!("ring_refused(): ignoring for inactive call");
+            Ok(())
+        }
+    }
+
+    /// Every remote device declined the active call.
+    pub(super) fn remote_declined(&mut self, call_id: CallId) -> Result<()> {
+        info!("remote_declined(): call_id: {}", call_id);
+
+        if self.call_is_active(call_id)? {
+            self.conclude_active_call(call_id, false, ApplicationEvent::EndedRemoteDeclined)
+        } else {
+            info!("remote_declined(): ignoring for inactive call");
+            Ok(())
+        }
+    }
+
+    /// Tell the application why a call failed.
+    pub(super) fn notify_call_failed(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        reason: CallFailureReason,
+    ) -> Result<()> {
+        info!(
+            "notify_call_failed(): call_id: {}, reason: {}",
+            call_id, reason
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_call_failed(remote_peer, call_id, reason)
+    }
+
+    /// Tell the application why the active call identified by
+    /// `call_id` failed.
+    ///
+    /// The trace of the call is written to the log, to debug the
+    /// failure.
+    fn active_call_failed(&mut self, call_id: CallId, reason: CallFailureReason) -> Result<()> {
+        let call = self.active_call_by_id(call_id)?;
+        self.log_call_trace(call.clone())?;
+
+        let remote_peer = call.remote_peer()?;
+        self.notify_call_failed(&*remote_peer, call_id, reason)
+    }
+
+    /// Write the trace of `call` to the log.
+    ///
+    /// The failure may be reported while the connections of the call
+    /// are locked, so the trace is written from a future.
+    fn log_call_trace(&mut self, call: Call<T>) -> Result<()> {
+        let future = lazy(move || {
+            for event in call.export_full_trace()? {
+                info!("trace: {}", event);
+            }
+            Ok(())
+        })
+        .map_err(|err: failure::Error| error!("Log call trace future failed: {}", err));
+        self.worker_spawn(future)
+    }
+
     /// Notify application that the call is concluded.
     pub(super) fn notify_call_concluded(
         &self,
         remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+    ) -> Result<()> {
+        info!("notify_call_concluded(): call_id: {}", call_id);
+
+        let platform = self.platform.lock()?;
+        platform.on_call_concluded(remote_peer, call_id)
+    }
+
+    /// Hand the record of a concluded call to the application.
+    pub(super) fn call_record(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        record: &CallRecord,
     ) -> Result<()> {
-        info!("notify_call_concluded():");
+        info!("call_record(): {}", record);
 
         let platform = self.platform.lock()?;
-        platform.on_call_concluded(remote_peer)
+        platform.on_call_record(remote_peer, record)
     }
 
     /// Local timeout of the active call.
@@ -1334,7 +4407,8 @@ where
         info!("timeout(): call_id: {}", call_id);
 
         if self.call_is_active(call_id)? {
-            self.conclude_active_call(true, ApplicationEvent::EndedTimeout)
+            let _ = self.active_call_failed(call_id, CallFailureReason::SignalingTimeout);
+            self.conclude_active_call(call_id, true, ApplicationEvent::EndedTimeout)
         } else {
             info!("timeout(): ignoring for inactive call");
             Ok(())
@@ -1346,7 +4420,8 @@ where
         info!("call_failed(): call_id: {}", call_id);
 
         if self.call_is_active(call_id)? {
-            self.conclude_active_call(true, ApplicationEvent::EndedConnectionFailure)
+            let _ = self.active_call_failed(call_id, CallFailureReason::IceFailed);
+            self.conclude_active_call(call_id, true, ApplicationEvent::EndedConnectionFailure)
         } else {
             info!("call_failed(): ignoring for inactive call");
             Ok(())
@@ -1361,13 +4436,29 @@ where
         info!("internal_error(): call_id: {}, error: {}", call_id, error);
 
         if self.call_is_active(call_id)? {
-            self.conclude_active_call(true, ApplicationEvent::EndedInternalFailure)
+            let _ = self.active_call_failed(call_id, CallFailureReason::from(&error));
+            self.conclude_active_call(call_id, true, ApplicationEvent::EndedInternalFailure)
         } else {
             info!("internal_error(): ignoring for inactive call");
             Ok(())
         }
     }
 
+    /// Reject an SDP longer than the maximum length.
+    fn check_sdp_length(&self, connection_id: ConnectionId, description: &str) -> Result<()> {
+        let max_length = *self.max_sdp_length.lock()?;
+        if description.len() > max_length {
+            warn!(
+                "id: {}, SDP length: {} exceeds the maximum length: {}",
+                connection_id,
+                description.len(),
+                max_length
+            );
+            return Err(RingRtcError::SdpTooLong(description.len(), max_length).into());
+        }
+        Ok(())
+    }
+
     /// Send SDP offer to remote_peer via the application.
     pub(super) fn send_offer(
         &mut self,
@@ -1379,7 +4470,40 @@ where
         info!("send_offer(): id: {}", connection_id);
 
         // Hold the description string for the closure.
-        let description = offer.get_description()?;
+        let offer = Offer {
+            renegotiation: connection.reconnection()?.renegotiation(),
+            ..Offer::new(offer.get_description()?)
+        };
+        let descriptor = MediaDescriptor {
+            frame_encryption: connection.frame_encryption()?,
+            ..MediaDescriptor::from_sdp(&offer.sdp)
+        };
+        let description = if *self.sdp_compression.lock()?
+            || connection.protocol_version()? >= COMPRESSION_PROTOCOL_VERSION
+        {
+            offer.to_compressed_wire()
+        } else {
+            offer.to_wire()
+        };
+        self.check_sdp_length(connection_id, &description)?;
+        let ice_restart = connection.reconnection()?.restarting_ice();
+        let opaque = if !ice_restart && *self.opaque_signaling.lock()? {
+            let offer = Offer {
+                sequence: connection.next_signaling_sequence()?,
+                ..offer
+            };
+            Some(offer.to_opaque())
+        } else {
+            None
+        };
+
+        let pending_description = description.clone();
+        let pending_closure = Self::pending_closure(&call, &connection, move |_| {
+            Ok(Some(PendingMessage::Offer {
+                description: pending_description.clone(),
+                ice_restart,
+            }))
+        });
 
         let offer_closure = Box::new(move |cm: &CallManager<T>| {
             info!("send_offer(): closure");
@@ -1388,7 +4512,37 @@ where
 
             if connection.can_send_messages() {
                 let platform = cm.platform.lock()?;
-                platform.on_send_offer(&*remote_peer, connection_id, false, description.as_str())
+                let transport = cm.signaling_transport.lock()?;
+                if ice_restart {
+                    transport.send_restart_offer(
+                        &*platform,
+                        &*remote_peer,
+                        connection_id,
+                        false,
+                        description.as_str(),
+                        &descriptor,
+                    )
+                } else if let Some(opaque) = &opaque {
+                    let opaque =
+                        signaling::seal(&*platform, &*remote_peer, connection_id, opaque.clone())?;
+                    transport.send_opaque_offer(
+                        &*platform,
+                        &*remote_peer,
+                        connection_id,
+                        false,
+                        &opaque,
+                        &descriptor,
+                    )
+                } else {
+                    transport.send_offer(
+                        &*platform,
+                        &*remote_peer,
+                        connection_id,
+                        false,
+                        description.as_str(),
+                        &descriptor,
+                    )
+                }
             } else {
                 Ok(())
             }
@@ -1398,6 +4552,7 @@ where
             call_id:         connection_id.call_id(),
             message_type:    SignalingMessageType::Offer,
             message_closure: offer_closure,
+            pending_closure: Some(pending_closure),
         };
 
         self.send_next_message(Some(message_item))
@@ -1414,7 +4569,36 @@ where
         info!("send_answer(): id: {}", connection_id);
 
         // Hold the description string for the closure.
-        let description = answer.get_description()?;
+        let answer = Answer {
+            metadata: call.answer_metadata()?,
+            ..Answer::new(answer.get_description()?, connection.protocol_version()?)
+        };
+        let descriptor = MediaDescriptor {
+            frame_encryption: connection.frame_encryption()?,
+            ..MediaDescriptor::from_sdp(&answer.sdp)
+        };
+        let description = if answer.version >= COMPRESSION_PROTOCOL_VERSION {
+            answer.to_compressed_wire()
+        } else {
+            answer.to_wire()
+        };
+        self.check_sdp_length(connection_id, &description)?;
+        let opaque = if *self.opaque_signaling.lock()? {
+            let answer = Answer {
+                sequence: connection.next_signaling_sequence()?,
+                ..answer
+            };
+            Some(answer.to_opaque())
+        } else {
+            None
+        };
+
+        let pending_description = description.clone();
+        let pending_closure = Self::pending_closure(&call, &connection, move |_| {
+            Ok(Some(PendingMessage::Answer {
+                description: pending_description.clone(),
+            }))
+        });
 
         let answer_closure = Box::new(move |cm: &CallManager<T>| {
             info!("send_answer(): closure");
@@ -1423,7 +4607,25 @@ where
 
             if connection.can_send_messages() {
                 let platform = cm.platform.lock()?;
-                platform.on_send_answer(&*remote_peer, connection_id, false, description.as_str())
+                let transport = cm.signaling_transport.lock()?;
+                match &opaque {
+                    Some(opaque) => transport.send_opaque_answer(
+                        &*platform,
+                        &*remote_peer,
+                        connection_id,
+                        false,
+                        &signaling::seal(&*platform, &*remote_peer, connection_id, opaque.clone())?,
+                        &descriptor,
+                    ),
+                    None => transport.send_answer(
+                        &*platform,
+                        &*remote_peer,
+                        connection_id,
+                        false,
+                        description.as_str(),
+                        &descriptor,
+                    ),
+                }
             } else {
                 Ok(())
             }
@@ -1433,6 +4635,7 @@ where
             call_id:         connection_id.call_id(),
             message_type:    SignalingMessageType::Answer,
             message_closure: answer_closure,
+            pending_closure: Some(pending_closure),
         };
 
         self.send_next_message(Some(message_item))
@@ -1447,6 +4650,15 @@ where
         let connection_id = connection.id();
         info!("send_ice_candidates(): id: {}", connection_id);
 
+        let pending_closure = Self::pending_closure(&call, &connection, |connection| {
+            let candidates = connection.peek_pending_ice_updates()?;
+            if candidates.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(PendingMessage::IceCandidates(candidates)))
+            }
+        });
+
         let ice_closure = Box::new(move |cm: &CallManager<T>| {
             info!("send_ice_candidates(): closure");
 
@@ -1458,17 +4670,69 @@ where
             }
 
             let platform = cm.platform.lock()?;
-            platform.on_send_ice_candidates(&*remote_peer, connection_id, false, &*candidates)
+            let transport = cm.signaling_transport.lock()?;
+            if *cm.opaque_signaling.lock()? {
+                let sequence = connection.next_signaling_sequence()?;
+                let opaque = signaling::seal(
+                    &*platform,
+                    &*remote_peer,
+                    connection_id,
+                    encode_ice_candidates(sequence, &candidates),
+                )?;
+                transport.send_opaque_ice_candidates(
+                    &*platform,
+                    &*remote_peer,
+                    connection_id,
+                    false,
+                    &opaque,
+                )
+            } else {
+                transport.send_ice_candidates(
+                    &*platform,
+                    &*remote_peer,
+                    connection_id,
+                    false,
+                    &candidates,
+                )
+            }
         });
 
         let message_item = SignalingMessageItem {
             call_id:         connection_id.call_id(),
             message_type:    SignalingMessageType::Ice,
             message_closure: ice_closure,
+            pending_closure: Some(pending_closure),
         };
 
         self.send_next_message(Some(message_item))
     }
+
+    /// Create the closure returning the message `message` computes
+    /// for `connection`, for the application to persist.
+    fn pending_closure<F>(
+        call: &Call<T>,
+        connection: &Connection<T>,
+        message: F,
+    ) -> PendingClosure<T>
+    where
+        F: Fn(&Connection<T>) -> Result<Option<PendingMessage>> + Send + 'static,
+    {
+        let call = call.clone();
+        let connection = connection.clone();
+        Box::new(move || {
+            if !connection.can_send_messages() {
+                return Ok(None);
+            }
+            match message(&connection)? {
+                Some(message) => Ok(Some(PendingSignaling {
+                    remote_peer: call.remote_peer()?.clone(),
+                    connection_id: connection.id(),
+                    message,
+                })),
+                None => Ok(None),
+            }
+        })
+    }
 }
 
 /// Check if the input `timestamp` matches the current system time
@@ -0,0 +1,171 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! End of call records for the application's call history.
+//!
+//! A call fills in its record as it ends, and the record is handed to
+//! `Platform::on_call_record()` when the call concludes, so the
+//! application does not have to reconstruct its call log from the
+//! individual events of the call.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::common::{ApplicationEvent, CallDirection, CallId};
+use crate::webrtc::ice_candidate::IceRoute;
+use crate::webrtc::stats_observer::StatsReport;
+
+/// The kind of ICE route used by a call, ordered from worst to best.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConnectionType {
+    /// The route was relayed through a TURN server.
+    Relayed = 0,
+    /// The route was direct between the peers.
+    Direct,
+}
+
+impl fmt::Display for ConnectionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<&IceRoute> for ConnectionType {
+    fn from(route: &IceRoute) -> Self {
+        if route.relayed() {
+            ConnectionType::Relayed
+        } else {
+            ConnectionType::Direct
+        }
+    }
+}
+
+/// The record of a concluded call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallRecord {
+    /// The call the record describes.
+    pub call_id:             CallId,
+    /// Whether the call was incoming or outgoing.
+    pub direction:           CallDirection,
+    /// How long the call was connected, zero if it never connected.
+    pub duration:            Duration,
+    /// The event the call ended with, if the application was told
+    /// why the call ended.
+    pub reason:              Option<ApplicationEvent>,
+    /// The best ICE route used by the call, if ICE ever connected.
+    pub connection_type:     Option<ConnectionType>,
+    /// Average bitrate of all sent and received media while the call
+    /// was connected, in bits per second.
+    ///
+    /// Derived from the last stats report, so zero unless stats were
+    /// enabled with `CallManager::set_stats_interval()`.
+    pub average_bitrate_bps: u64,
+}
+
+impl fmt::Display for CallRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "call_id: {}, direction: {}, duration: {:?}, reason: {:?}, connection_type: {:?}, average_bitrate_bps: {}",
+            self.call_id,
+            self.direction,
+            self.duration,
+            self.reason,
+            self.connection_type,
+            self.average_bitrate_bps
+        )
+    }
+}
+
+impl CallRecord {
+    /// Create an empty record for the call identified by `call_id`.
+    pub fn new(call_id: CallId, direction: CallDirection) -> Self {
+        Self {
+            call_id,
+            direction,
+            duration: Duration::default(),
+            reason: None,
+            connection_type: None,
+            average_bitrate_bps: 0,
+        }
+    }
+}
+
+/// Return the average bitrate of all media sent and received between
+/// `connected_at` and the time of `report`.
+pub fn average_bitrate(connected_at: Instant, report: &StatsReport) -> u64 {
+    let elapsed = report
+        .timestamp
+        .checked_duration_since(connected_at)
+        .unwrap_or_default()
+        .as_secs_f64();
+    if elapsed > 0.0 {
+        ((report.bytes_sent() + report.bytes_received()) as f64 * 8.0 / elapsed) as u64
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::webrtc::media::MediaKind;
+    use crate::webrtc::stats_observer::{
+        InboundRtpStats,
+        OutboundRtpStats,
+        QualityLimitationReason,
+    };
+
+    #[test]
+    fn check_connection_type() {
+        let direct = IceRoute::new("local", "stun", "udp");
+        let relay = IceRoute::new("relay", "local", "udp");
+        assert_eq!(ConnectionType::from(&direct), ConnectionType::Direct);
+        assert_eq!(ConnectionType::from(&relay), ConnectionType::Relayed);
+        assert!(ConnectionType::Direct > ConnectionType::Relayed);
+    }
+
+    #[test]
+    fn check_average_bitrate() {
+        let connected_at = Instant::now();
+        let report = StatsReport {
+            timestamp: connected_at + Duration::from_secs(4),
+            outbound_rtp: vec![OutboundRtpStats {
+                ssrc:                      1,
+                kind:                      MediaKind::Video,
+                mime_type:                 None,
+                packets_sent:              3,
+                bytes_sent:                3000,
+                frame_width:               640,
+                frame_height:              360,
+                frames_per_second:         30.0,
+                quality_limitation_reason: QualityLimitationReason::None,
+            }],
+            inbound_rtp: vec![InboundRtpStats {
+                ssrc:                        2,
+                kind:                        MediaKind::Video,
+                mime_type:                   None,
+                packets_received:            1,
+                packets_lost:                0,
+                bytes_received:              1000,
+                jitter:                      0.0,
+                jitter_buffer_delay:         0.0,
+                jitter_buffer_emitted_count: 0,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(average_bitrate(connected_at, &report), 8000);
+
+        let report = StatsReport {
+            timestamp: connected_at,
+            ..report
+        };
+        assert_eq!(average_bitrate(connected_at, &report), 0);
+    }
+}
@@ -0,0 +1,232 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Call quality statistics for the application.
+//!
+//! Each report compares a stats snapshot of the PeerConnection with
+//! the previous one, so bitrates and packet loss cover the last
+//! reporting interval rather than the whole call.
+
+use std::fmt;
+
+use crate::core::video::VideoAdaptation;
+use crate::webrtc::stats_observer::StatsReport;
+
+/// The quality of a call over the last reporting interval.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CallStats {
+    /// Round trip time of the selected ICE route, in milliseconds.
+    pub rtt_ms:              f64,
+    /// Jitter of the received audio, in milliseconds.
+    pub jitter_ms:           f64,
+    /// Average time the received audio spent in the jitter buffer,
+    /// in milliseconds.
+    pub jitter_buffer_ms:    f64,
+    /// Fraction of the received packets that were lost, from 0.0 to
+    /// 1.0.
+    pub packet_loss:         f64,
+    /// Bitrate of all sent RTP streams, in bits per second.
+    pub send_bitrate_bps:    u64,
+    /// Bitrate of all received RTP streams, in bits per second.
+    pub receive_bitrate_bps: u64,
+    /// The sent audio codec, e.g. opus.
+    pub audio_codec:         Option<String>,
+    /// The sent video codec, e.g. VP8.
+    pub video_codec:         Option<String>,
+    /// How the sent video is adapted, if video is sent.
+    pub video_adaptation:    Option<VideoAdaptation>,
+}
+
+impl fmt::Display for CallStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "rtt_ms: {:.1}, jitter_ms: {:.1}, jitter_buffer_ms: {:.1}, packet_loss: {:.3}, send_bitrate_bps: {}, receive_bitrate_bps: {}, audio_codec: {:?}, video_codec: {:?}, video_adaptation: {:?}",
+            self.rtt_ms,
+            self.jitter_ms,
+            self.jitter_buffer_ms,
+            self.packet_loss,
+            self.send_bitrate_bps,
+            self.receive_bitrate_bps,
+            self.audio_codec,
+            self.video_codec,
+            self.video_adaptation.map(|adaptation| adaptation.to_string())
+        )
+    }
+}
+
+/// Return the codec name of a MIME type, e.g. `opus` for
+/// `audio/opus`.
+fn codec_name(mime_type: &Option<String>) -> Option<String> {
+    mime_type
+        .as_ref()
+        .map(|m| m.rsplit('/').next().unwrap_or(m).to_string())
+}
+
+impl CallStats {
+    /// Derive the stats of the interval between two reports of the
+    /// same PeerConnection.
+    ///
+    /// Without a previous report, or when the counters went backwards
+    /// because the PeerConnection was rebuilt, packet loss covers the
+    /// whole report and the bitrates are zero.
+    pub fn new(previous: Option<&StatsReport>, current: &StatsReport) -> Self {
+        let previous = previous.filter(|p| {
+            p.packets_received() <= current.packets_received()
+                && p.bytes_sent() <= current.bytes_sent()
+                && p.bytes_received() <= current.bytes_received()
+        });
+
+        let (packets_received, packets_lost) = match previous {
+            Some(p) => (
+                current.packets_received() - p.packets_received(),
+                (current.packets_lost() - p.packets_lost()).max(0),
+            ),
+            None => (current.packets_received(), current.packets_lost().max(0)),
+        };
+        let packets_expected = packets_received + packets_lost as u64;
+        let packet_loss = if packets_expected > 0 {
+            packets_lost as f64 / packets_expected as f64
+        } else {
+            0.0
+        };
+
+        let (jitter_buffer_delay, jitter_buffer_emitted_count) = {
+            let (delay, emitted_count) = current.audio_jitter_buffer();
+            match previous {
+                Some(p) => {
+                    let (previous_delay, previous_emitted_count) = p.audio_jitter_buffer();
+                    (
+                        delay - previous_delay,
+                        emitted_count.saturating_sub(previous_emitted_count),
+                    )
+                }
+                None => (delay, emitted_count),
+            }
+        };
+        let jitter_buffer_ms = if jitter_buffer_emitted_count > 0 {
+            jitter_buffer_delay / jitter_buffer_emitted_count as f64 * 1000.0
+        } else {
+            0.0
+        };
+
+        let (send_bitrate_bps, receive_bitrate_bps) = match previous {
+            Some(p) => {
+                let elapsed = current.timestamp.duration_since(p.timestamp).as_secs_f64();
+                if elapsed > 0.0 {
+                    let bitrate = |bytes: u64| (bytes as f64 * 8.0 / elapsed) as u64;
+                    (
+                        bitrate(current.bytes_sent() - p.bytes_sent()),
+                        bitrate(current.bytes_received() - p.bytes_received()),
+                    )
+                } else {
+                    (0, 0)
+                }
+            }
+            None => (0, 0),
+        };
+
+        Self {
+            rtt_ms: current.current_round_trip_time() * 1000.0,
+            jitter_ms: current.jitter() * 1000.0,
+            jitter_buffer_ms,
+            packet_loss,
+            send_bitrate_bps,
+            receive_bitrate_bps,
+            audio_codec: codec_name(&current.audio_codec()),
+            video_codec: codec_name(&current.video_codec()),
+            video_adaptation: current.video_adaptation(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::webrtc::media::MediaKind;
+    use crate::webrtc::stats_observer::{
+        IceCandidatePairStats,
+        InboundRtpStats,
+        OutboundRtpStats,
+        QualityLimitationReason,
+    };
+
+    fn report(packets_received: u64, packets_lost: i64, bytes: u64) -> StatsReport {
+        StatsReport {
+            ice_candidate_pairs: vec![IceCandidatePairStats {
+                nominated: true,
+                current_round_trip_time: 0.1,
+                ..Default::default()
+            }],
+            outbound_rtp: vec![OutboundRtpStats {
+                ssrc:                      1,
+                kind:                      MediaKind::Audio,
+                mime_type:                 Some("audio/opus".to_string()),
+                packets_sent:              0,
+                bytes_sent:                bytes,
+                frame_width:               0,
+                frame_height:              0,
+                frames_per_second:         0.0,
+                quality_limitation_reason: QualityLimitationReason::None,
+            }],
+            inbound_rtp: vec![InboundRtpStats {
+                ssrc: 2,
+                kind: MediaKind::Audio,
+                mime_type: Some("audio/opus".to_string()),
+                packets_received,
+                packets_lost,
+                bytes_received: bytes,
+                jitter: 0.02,
+                jitter_buffer_delay: packets_received as f64 * 0.0625,
+                jitter_buffer_emitted_count: packets_received,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn check_first_report() {
+        let stats = CallStats::new(None, &report(90, 10, 1000));
+        assert_eq!(stats.rtt_ms, 100.0);
+        assert_eq!(stats.jitter_ms, 20.0);
+        assert_eq!(stats.jitter_buffer_ms, 62.5);
+        assert_eq!(stats.packet_loss, 0.1);
+        assert_eq!(stats.send_bitrate_bps, 0);
+        assert_eq!(stats.receive_bitrate_bps, 0);
+        assert_eq!(stats.audio_codec, Some("opus".to_string()));
+        assert_eq!(stats.video_codec, None);
+        assert_eq!(stats.video_adaptation, None);
+    }
+
+    #[test]
+    fn check_interval() {
+        let previous = report(90, 10, 1000);
+        let mut current = report(190, 10, 3000);
+        current.timestamp = previous.timestamp + Duration::from_secs(2);
+
+        let stats = CallStats::new(Some(&previous), &current);
+        assert_eq!(stats.packet_loss, 0.0);
+        assert_eq!(stats.jitter_buffer_ms, 62.5);
+        assert_eq!(stats.send_bitrate_bps, 8000);
+        assert_eq!(stats.receive_bitrate_bps, 8000);
+    }
+
+    #[test]
+    fn check_rebuilt_peer_connection() {
+        let previous = report(1000, 100, 100_000);
+        let mut current = report(45, 5, 1000);
+        current.timestamp = previous.timestamp + Duration::from_secs(2);
+
+        let stats = CallStats::new(Some(&previous), &current);
+        assert_eq!(stats.packet_loss, 0.1);
+        assert_eq!(stats.send_bitrate_bps, 0);
+        assert_eq!(stats.receive_bitrate_bps, 0);
+    }
+}
@@ -0,0 +1,117 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Cancellation of an in-flight call setup.
+//!
+//! `CallManager::proceed()` and `CallManager::accept_call()` return a
+//! `SetupHandle`, so the application can abort the setup, e.g. when
+//! the user cancels while ICE is still gathering.  The call FSM
+//! checks the call's `CancellationToken` before acting on any event,
+//! so a cancelled call never goes on to ring or connect.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::common::{CallId, Result};
+use crate::core::call_manager::CallManager;
+use crate::core::platform::Platform;
+
+/// A flag shared between a call and the parties that may cancel it.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new, uncancelled, token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token.  Cancelling is permanent.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Handle to the setup of a call, started by `proceed()` or
+/// `accept_call()`.
+pub struct SetupHandle<T>
+where
+    T: Platform,
+{
+    /// The call being set up.
+    call_id:      CallId,
+    /// The call manager of the call.
+    call_manager: CallManager<T>,
+}
+
+impl<T> fmt::Display for SetupHandle<T>
+where
+    T: Platform,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "call_id: {}", self.call_id)
+    }
+}
+
+impl<T> fmt::Debug for SetupHandle<T>
+where
+    T: Platform,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl<T> SetupHandle<T>
+where
+    T: Platform,
+{
+    /// Create a new SetupHandle for the call identified by `call_id`.
+    pub fn new(call_id: CallId, call_manager: CallManager<T>) -> Self {
+        Self {
+            call_id,
+            call_manager,
+        }
+    }
+
+    /// Return the identifier of the call being set up.
+    pub fn call_id(&self) -> CallId {
+        self.call_id
+    }
+
+    /// Abort the setup of the call.
+    ///
+    /// The call ends with `ApplicationEvent::EndedSetupCancelled`,
+    /// unless it already connected, in which case this does nothing.
+    pub fn cancel(&mut self) -> Result<()> {
+        self.call_manager.cancel_setup(self.call_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_cancellation_token() {
+        let token = CancellationToken::new();
+        let shared = token.clone();
+        assert!(!shared.is_cancelled());
+
+        token.cancel();
+        assert!(shared.is_cancelled());
+        assert!(token.is_cancelled());
+    }
+}
@@ -0,0 +1,161 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Capabilities of the remote peer, for feature gating.
+//!
+//! The capabilities are parsed from the negotiated SDP.  For the
+//! caller that is the remote answer.  For the callee it is the remote
+//! offer, limited to what the local answer accepted.
+//!
+//! A video section describing its content as `slides`, RFC 4796,
+//! carries a screen share, separate from the camera video.
+
+use std::fmt;
+
+use crate::core::codec::offered_codecs;
+
+/// The content attribute of a video section carrying a screen share.
+pub const SCREEN_SHARE_CONTENT: &str = "a=content:slides";
+
+/// What the remote peer supports, as negotiated in the SDP.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemoteCapabilities {
+    /// An audio section was negotiated.
+    pub audio:        bool,
+    /// A video section was negotiated.
+    pub video:        bool,
+    /// A data channel section was negotiated.
+    pub data:         bool,
+    /// The video section carries a simulcast description.
+    pub simulcast:    bool,
+    /// A video section carries a screen share.
+    pub screen_share: bool,
+    /// The negotiated codec names, in order of first appearance.
+    pub codecs:       Vec<String>,
+}
+
+impl fmt::Display for RemoteCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "audio: {}, video: {}, data: {}, simulcast: {}, screen_share: {}, codecs: {:?}",
+            self.audio, self.video, self.data, self.simulcast, self.screen_share, self.codecs
+        )
+    }
+}
+
+impl RemoteCapabilities {
+    /// Parse the capabilities described by an SDP.
+    ///
+    /// Media sections rejected with a zero port are ignored.
+    pub fn from_sdp(sdp: &str) -> Self {
+        let mut capabilities = Self::default();
+        let mut media = "";
+        for line in sdp.lines() {
+            if let Some(description) = line.strip_prefix("m=") {
+                let mut fields = description.split_whitespace();
+                let kind = fields.next().unwrap_or("");
+                let port = fields.next().unwrap_or("0");
+                media = if port == "0" { "" } else { kind };
+                match media {
+                    "audio" => capabilities.audio = true,
+                    "video" => capabilities.video = true,
+                    "application" => capabilities.data = true,
+                    _ => {}
+                }
+            } else if media == "video" && line.starts_with("a=simulcast:") {
+                capabilities.simulcast = true;
+            } else if media == "video" && line.trim_end() == SCREEN_SHARE_CONTENT {
+                capabilities.screen_share = true;
+            }
+        }
+        capabilities.codecs = offered_codecs(sdp);
+        capabilities
+    }
+
+    /// Limit the capabilities to those also present in `other`.
+    pub fn intersect(&self, other: &RemoteCapabilities) -> Self {
+        Self {
+            audio:        self.audio && other.audio,
+            video:        self.video && other.video,
+            data:         self.data && other.data,
+            simulcast:    self.simulcast && other.simulcast,
+            screen_share: self.screen_share && other.screen_share,
+            codecs:       self
+                .codecs
+                .iter()
+                .filter(|c| other.codecs.iter().any(|o| o.eq_ignore_ascii_case(c)))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFER: &str = "v=0\r\n\
+                         m=audio 9 UDP/TLS/RTP/SAVPF 111 0\r\n\
+                         a=rtpmap:111 opus/48000/2\r\n\
+                         a=rtpmap:0 PCMU/8000\r\n\
+                         m=video 9 UDP/TLS/RTP/SAVPF 96 98\r\n\
+                         a=rtpmap:96 VP8/90000\r\n\
+                         a=rtpmap:98 VP9/90000\r\n\
+                         a=simulcast:send 1;2\r\n\
+                         m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                         a=rtpmap:96 VP8/90000\r\n\
+                         a=content:slides\r\n\
+                         m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n";
+
+    const ANSWER: &str = "v=0\r\n\
+                          m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                          a=rtpmap:111 opus/48000/2\r\n\
+                          m=video 0 UDP/TLS/RTP/SAVPF 96\r\n\
+                          a=rtpmap:96 VP8/90000\r\n\
+                          m=video 0 UDP/TLS/RTP/SAVPF 96\r\n\
+                          a=rtpmap:96 VP8/90000\r\n\
+                          a=content:slides\r\n\
+                          m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n";
+
+    #[test]
+    fn check_from_sdp() {
+        let capabilities = RemoteCapabilities::from_sdp(OFFER);
+        assert!(capabilities.audio);
+        assert!(capabilities.video);
+        assert!(capabilities.data);
+        assert!(capabilities.simulcast);
+        assert!(capabilities.screen_share);
+        assert_eq!(capabilities.codecs, vec!["opus", "PCMU", "VP8", "VP9"]);
+
+        let capabilities = RemoteCapabilities::from_sdp(ANSWER);
+        assert!(capabilities.audio);
+        assert!(!capabilities.video);
+        assert!(capabilities.data);
+        assert!(!capabilities.simulcast);
+        assert!(!capabilities.screen_share);
+
+        assert_eq!(
+            RemoteCapabilities::from_sdp("FAKE SDP"),
+            RemoteCapabilities::default()
+        );
+    }
+
+    #[test]
+    fn check_intersect() {
+        let offer = RemoteCapabilities::from_sdp(OFFER);
+        let answer = RemoteCapabilities::from_sdp(ANSWER);
+
+        let capabilities = offer.intersect(&answer);
+        assert!(capabilities.audio);
+        assert!(!capabilities.video);
+        assert!(capabilities.data);
+        assert!(!capabilities.simulcast);
+        assert!(!capabilities.screen_share);
+        assert_eq!(capabilities.codecs, vec!["opus", "VP8"]);
+    }
+}
@@ -0,0 +1,497 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Codec selection for SDP answers.
+//!
+//! Codec preferences set by the application are advisory.  When the
+//! remote offer contains a preferred codec it is moved to the front
+//! of the answer's payload list, otherwise the answer is left to
+//! negotiate any codec common to both sides.
+//!
+//! A call may also prefer a video and an audio codec of its own, see
+//! `CallConfig::preferred_video_codec()`, which are moved to the
+//! front of both the offers and the answers of the call, e.g. so a
+//! device with a hardware H264 encoder sends H264.
+//!
+//! The codecs a device supports, see `CallManager::supported_codecs()`,
+//! are the software codecs built into WebRTC merged with the hardware
+//! codecs reported by the platform.  Without any preferences, an
+//! answer favors the hardware accelerated codecs.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// A video codec a call may prefer.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VideoCodec {
+    Vp8 = 0,
+    Vp9,
+    H264,
+    Av1,
+}
+
+impl VideoCodec {
+    /// Returns the codec name used in the SDP.
+    pub fn name(self) -> &'static str {
+        match self {
+            VideoCodec::Vp8 => "VP8",
+            VideoCodec::Vp9 => "VP9",
+            VideoCodec::H264 => "H264",
+            VideoCodec::Av1 => "AV1",
+        }
+    }
+}
+
+impl fmt::Display for VideoCodec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// An audio codec a call may prefer.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AudioCodec {
+    Opus = 0,
+    G722,
+    Pcmu,
+    Pcma,
+}
+
+impl AudioCodec {
+    /// Returns the codec name used in the SDP.
+    pub fn name(self) -> &'static str {
+        match self {
+            AudioCodec::Opus => "opus",
+            AudioCodec::G722 => "G722",
+            AudioCodec::Pcmu => "PCMU",
+            AudioCodec::Pcma => "PCMA",
+        }
+    }
+}
+
+impl fmt::Display for AudioCodec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The video codecs WebRTC encodes and decodes in software.
+pub const SOFTWARE_VIDEO_CODECS: [VideoCodec; 2] = [VideoCodec::Vp8, VideoCodec::Vp9];
+
+/// The audio codecs WebRTC encodes and decodes in software.
+pub const SOFTWARE_AUDIO_CODECS: [AudioCodec; 4] = [
+    AudioCodec::Opus,
+    AudioCodec::G722,
+    AudioCodec::Pcmu,
+    AudioCodec::Pcma,
+];
+
+/// Every video codec a call may prefer.
+const VIDEO_CODECS: [VideoCodec; 4] = [
+    VideoCodec::Vp8,
+    VideoCodec::Vp9,
+    VideoCodec::H264,
+    VideoCodec::Av1,
+];
+
+/// Returns the media kind of a codec a call may prefer, `audio` or
+/// `video`, or `None` for any other codec.
+pub fn codec_kind(name: &str) -> Option<&'static str> {
+    if VIDEO_CODECS
+        .iter()
+        .any(|codec| codec.name().eq_ignore_ascii_case(name))
+    {
+        Some("video")
+    } else if SOFTWARE_AUDIO_CODECS
+        .iter()
+        .any(|codec| codec.name().eq_ignore_ascii_case(name))
+    {
+        Some("audio")
+    } else {
+        None
+    }
+}
+
+/// How a device encodes or decodes a codec.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CodecImplementation {
+    Software = 0,
+    Hardware,
+}
+
+impl fmt::Display for CodecImplementation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A codec the device can encode, decode, or both.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodecCapability {
+    /// The codec name used in the SDP, e.g. `H264`.
+    pub name:    String,
+    /// How the codec is encoded, if it can be.
+    pub encoder: Option<CodecImplementation>,
+    /// How the codec is decoded, if it can be.
+    pub decoder: Option<CodecImplementation>,
+}
+
+impl CodecCapability {
+    /// A codec WebRTC encodes and decodes in software.
+    pub fn software(name: &str) -> Self {
+        Self {
+            name:    name.to_string(),
+            encoder: Some(CodecImplementation::Software),
+            decoder: Some(CodecImplementation::Software),
+        }
+    }
+
+    /// A codec the platform encodes and/or decodes in hardware.
+    pub fn hardware(name: &str, encode: bool, decode: bool) -> Self {
+        let implementation = |enabled: bool| {
+            if enabled {
+                Some(CodecImplementation::Hardware)
+            } else {
+                None
+            }
+        };
+        Self {
+            name:    name.to_string(),
+            encoder: implementation(encode),
+            decoder: implementation(decode),
+        }
+    }
+
+    /// Returns true if the codec can be both encoded and decoded.
+    pub fn is_supported(&self) -> bool {
+        self.encoder.is_some() && self.decoder.is_some()
+    }
+
+    /// Returns true if the codec is encoded or decoded in hardware.
+    pub fn is_hardware_accelerated(&self) -> bool {
+        self.encoder == Some(CodecImplementation::Hardware)
+            || self.decoder == Some(CodecImplementation::Hardware)
+    }
+}
+
+impl fmt::Display for CodecCapability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, encoder: {:?}, decoder: {:?}",
+            self.name, self.encoder, self.decoder
+        )
+    }
+}
+
+/// Merge the codecs reported by the platform into the software
+/// codecs of WebRTC.
+///
+/// A codec the platform encodes or decodes in hardware replaces the
+/// software implementation for that direction, and a codec WebRTC
+/// lacks is appended.
+pub fn merge_capabilities(platform: &[CodecCapability]) -> Vec<CodecCapability> {
+    let mut codecs: Vec<CodecCapability> = SOFTWARE_VIDEO_CODECS
+        .iter()
+        .map(|codec| codec.name())
+        .chain(SOFTWARE_AUDIO_CODECS.iter().map(|codec| codec.name()))
+        .map(CodecCapability::software)
+        .collect();
+
+    for capability in platform {
+        match codecs
+            .iter_mut()
+            .find(|c| c.name.eq_ignore_ascii_case(&capability.name))
+        {
+            Some(codec) => {
+                codec.encoder = capability.encoder.or(codec.encoder);
+                codec.decoder = capability.decoder.or(codec.decoder);
+            }
+            None => codecs.push(capability.clone()),
+        }
+    }
+    codecs
+}
+
+/// Returns the names of the supported codecs that are hardware
+/// accelerated, in order.
+pub fn hardware_accelerated_codecs(supported: &[CodecCapability]) -> Vec<&str> {
+    supported
+        .iter()
+        .filter(|c| c.is_supported() && c.is_hardware_accelerated())
+        .map(|c| c.name.as_str())
+        .collect()
+}
+
+/// The outcome of matching codec preferences against an SDP offer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CodecSelection {
+    /// No preferences are set for the media in the offer, or the
+    /// offer lists no codecs.
+    Unconstrained,
+    /// The named preferred codec is present in the offer.
+    Preferred(String),
+    /// None of the preferred codecs are present in the offer.
+    Fallback,
+}
+
+/// Parse an `a=rtpmap:<payload type> <codec>/<clock rate>` line,
+/// returning the payload type and codec name.
+fn parse_rtpmap(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_end();
+    if !line.starts_with("a=rtpmap:") {
+        return None;
+    }
+
+    let mut fields = line["a=rtpmap:".len()..].splitn(2, ' ');
+    let pt = fields.next()?;
+    let name = fields.next()?.split('/').next()?;
+    Some((pt, name))
+}
+
+/// Returns the codec names present in an SDP, in order of first
+/// appearance and without duplicates.
+pub fn offered_codecs(sdp: &str) -> Vec<String> {
+    let mut codecs: Vec<String> = Vec::new();
+    for (_, name) in sdp.lines().filter_map(parse_rtpmap) {
+        if !codecs.iter().any(|c| c.eq_ignore_ascii_case(name)) {
+            codecs.push(name.to_string());
+        }
+    }
+    codecs
+}
+
+/// Returns the codec of the first payload type on the media line of
+/// the first accepted `kind` section, e.g. `audio` or `video`.
+///
+/// Sections rejected with a zero port are skipped.
+pub fn primary_codec(sdp: &str, kind: &str) -> Option<String> {
+    let payload_types: HashMap<&str, &str> = sdp.lines().filter_map(parse_rtpmap).collect();
+    sdp.lines()
+        .filter(|line| line.starts_with("m="))
+        .map(|line| line["m=".len()..].split_whitespace().collect::<Vec<&str>>())
+        .filter(|fields| fields.len() > 3 && fields[0] == kind && fields[1] != "0")
+        .find_map(|fields| payload_types.get(fields[3]).map(|name| name.to_string()))
+}
+
+/// Returns the media kinds of the accepted sections of an SDP, e.g.
+/// `audio` or `video`, in order and without duplicates.
+pub fn offered_kinds(sdp: &str) -> Vec<String> {
+    let mut kinds: Vec<String> = Vec::new();
+    for line in sdp.lines() {
+        if let Some(description) = line.strip_prefix("m=") {
+            let mut fields = description.split_whitespace();
+            let kind = fields.next().unwrap_or("");
+            if fields.next().unwrap_or("0") != "0" && !kinds.iter().any(|k| k == kind) {
+                kinds.push(kind.to_string());
+            }
+        }
+    }
+    kinds
+}
+
+/// Match the `preferences`, highest priority first, against the
+/// codecs in an offer with media of the given `kinds`.
+///
+/// Preferences for a media kind absent from the offer, e.g. video
+/// codecs for an audio only offer, are ignored.
+pub fn select_codec(
+    preferences: &[String],
+    offered: &[String],
+    kinds: &[String],
+) -> CodecSelection {
+    let preferences: Vec<&String> = preferences
+        .iter()
+        .filter(|preference| {
+            codec_kind(preference).is_none_or(|kind| kinds.iter().any(|k| k == kind))
+        })
+        .collect();
+    if preferences.is_empty() || offered.is_empty() {
+        return CodecSelection::Unconstrained;
+    }
+
+    for preference in preferences {
+        if let Some(codec) = offered.iter().find(|c| c.eq_ignore_ascii_case(preference)) {
+            return CodecSelection::Preferred(codec.clone());
+        }
+    }
+
+    CodecSelection::Fallback
+}
+
+/// Returns a copy of `sdp` with the payload types of `codec` moved to
+/// the front of every media line.
+///
+/// Media lines that do not carry `codec` are left untouched.
+pub fn prefer_codec(sdp: &str, codec: &str) -> String {
+    let payload_types: HashMap<&str, &str> = sdp.lines().filter_map(parse_rtpmap).collect();
+    let is_codec = |pt: &&str| match payload_types.get(*pt) {
+        Some(name) => name.eq_ignore_ascii_case(codec),
+        None => false,
+    };
+
+    sdp.split('\n')
+        .map(|line| {
+            let content = line.trim_end_matches('\r');
+            let fields: Vec<&str> = content.split(' ').collect();
+            if !content.starts_with("m=") || fields.len() <= 3 {
+                return line.to_string();
+            }
+
+            let (preferred, others): (Vec<&str>, Vec<&str>) =
+                fields[3..].iter().partition(|pt| is_codec(pt));
+
+            let mut reordered = fields[..3].to_vec();
+            reordered.extend(preferred);
+            reordered.extend(others);
+
+            format!("{}{}", reordered.join(" "), &line[content.len()..])
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Returns a copy of `sdp` with the payload types of `codecs` moved
+/// to the front of every media line, earlier codecs ahead of later
+/// ones.
+pub fn prefer_codecs(sdp: &str, codecs: &[&str]) -> String {
+    codecs
+        .iter()
+        .rev()
+        .fold(sdp.to_string(), |sdp, codec| prefer_codec(&sdp, codec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFER: &str = "v=0\r\n\
+                         m=audio 9 UDP/TLS/RTP/SAVPF 111 103 0\r\n\
+                         a=rtpmap:111 opus/48000/2\r\n\
+                         a=rtpmap:103 ISAC/16000\r\n\
+                         a=rtpmap:0 PCMU/8000\r\n\
+                         m=video 9 UDP/TLS/RTP/SAVPF 96 98\r\n\
+                         a=rtpmap:96 VP8/90000\r\n\
+                         a=rtpmap:98 VP9/90000\r\n";
+
+    #[test]
+    fn check_offered_codecs() {
+        assert_eq!(
+            offered_codecs(OFFER),
+            vec!["opus", "ISAC", "PCMU", "VP8", "VP9"]
+        );
+        assert!(offered_codecs("FAKE SDP").is_empty());
+    }
+
+    #[test]
+    fn check_primary_codec() {
+        assert_eq!(primary_codec(OFFER, "audio"), Some("opus".to_string()));
+        assert_eq!(primary_codec(OFFER, "video"), Some("VP8".to_string()));
+        assert_eq!(primary_codec(OFFER, "application"), None);
+
+        let rejected = OFFER.replace("m=video 9", "m=video 0");
+        assert_eq!(primary_codec(&rejected, "video"), None);
+    }
+
+    #[test]
+    fn check_offered_kinds() {
+        assert_eq!(offered_kinds(OFFER), vec!["audio", "video"]);
+
+        let rejected = OFFER.replace("m=video 9", "m=video 0");
+        assert_eq!(offered_kinds(&rejected), vec!["audio"]);
+    }
+
+    #[test]
+    fn check_select_codec() {
+        let offered = offered_codecs(OFFER);
+        let kinds = offered_kinds(OFFER);
+
+        assert_eq!(
+            select_codec(&[], &offered, &kinds),
+            CodecSelection::Unconstrained
+        );
+        assert_eq!(
+            select_codec(&["H264".to_string(), "vp9".to_string()], &offered, &kinds),
+            CodecSelection::Preferred("VP9".to_string())
+        );
+        assert_eq!(
+            select_codec(&["H264".to_string()], &offered, &kinds),
+            CodecSelection::Fallback
+        );
+
+        // Video preferences do not apply to an audio only offer.
+        let audio = vec!["audio".to_string()];
+        assert_eq!(
+            select_codec(&["H264".to_string()], &offered, &audio),
+            CodecSelection::Unconstrained
+        );
+        assert_eq!(
+            select_codec(&["H264".to_string(), "ISAC".to_string()], &offered, &audio),
+            CodecSelection::Preferred("ISAC".to_string())
+        );
+    }
+
+    #[test]
+    fn check_prefer_codec() {
+        let sdp = prefer_codec(OFFER, "vp9");
+        assert!(sdp.contains("m=audio 9 UDP/TLS/RTP/SAVPF 111 103 0\r\n"));
+        assert!(sdp.contains("m=video 9 UDP/TLS/RTP/SAVPF 98 96\r\n"));
+        assert_eq!(sdp.len(), OFFER.len());
+
+        assert_eq!(prefer_codec(OFFER, "H264"), OFFER);
+    }
+
+    #[test]
+    fn check_prefer_codecs() {
+        let sdp = prefer_codecs(OFFER, &[VideoCodec::Vp9.name(), AudioCodec::Pcmu.name()]);
+        assert!(sdp.contains("m=audio 9 UDP/TLS/RTP/SAVPF 0 111 103\r\n"));
+        assert!(sdp.contains("m=video 9 UDP/TLS/RTP/SAVPF 98 96\r\n"));
+
+        let sdp = prefer_codecs(OFFER, &["ISAC", "PCMU"]);
+        assert!(sdp.contains("m=audio 9 UDP/TLS/RTP/SAVPF 103 0 111\r\n"));
+
+        assert_eq!(prefer_codecs(OFFER, &[VideoCodec::H264.name()]), OFFER);
+        assert_eq!(prefer_codecs(OFFER, &[]), OFFER);
+    }
+
+    #[test]
+    fn check_merge_capabilities() {
+        let software = merge_capabilities(&[]);
+        assert_eq!(
+            software
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["VP8", "VP9", "opus", "G722", "PCMU", "PCMA"]
+        );
+        assert!(software.iter().all(|c| c.is_supported()));
+        assert!(hardware_accelerated_codecs(&software).is_empty());
+
+        let codecs = merge_capabilities(&[
+            CodecCapability::hardware("vp8", false, true),
+            CodecCapability::hardware("H264", true, true),
+            CodecCapability::hardware("AV1", false, true),
+        ]);
+        assert_eq!(codecs.len(), 8);
+        assert_eq!(
+            codecs[0],
+            CodecCapability {
+                name:    "VP8".to_string(),
+                encoder: Some(CodecImplementation::Software),
+                decoder: Some(CodecImplementation::Hardware),
+            }
+        );
+        assert_eq!(codecs[6], CodecCapability::hardware("H264", true, true));
+        assert!(!codecs[7].is_supported());
+
+        // A decoder alone is not enough to prefer AV1.
+        assert_eq!(hardware_accelerated_codecs(&codecs), vec!["VP8", "H264"]);
+    }
+}
@@ -9,32 +9,113 @@
 
 extern crate tokio;
 
+use std::collections::VecDeque;
 use std::fmt;
 use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use futures::sync::mpsc::{Receiver, Sender};
-use futures::Future;
+use futures::{Future, Stream};
 use tokio::runtime;
-
-use crate::common::{CallDirection, CallId, ConnectionId, ConnectionState, DeviceId, Result};
+use tokio::timer::{Delay, Interval};
+
+use crate::common::{
+    CallDirection,
+    CallId,
+    ConnectionId,
+    ConnectionState,
+    DeviceId,
+    HangupType,
+    NegotiationMessage,
+    Result,
+    TransferMessage,
+};
+use crate::core::audio_config::AudioConfig;
+use crate::core::audio_level::{add_audio_level_extension, audio_level_negotiated, AudioLevels};
+use crate::core::bandwidth_mode::BandwidthMode;
 use crate::core::call::Call;
 use crate::core::call_mutex::CallMutex;
+use crate::core::call_stats::CallStats;
+use crate::core::cancellation::CancellationToken;
+use crate::core::capabilities::RemoteCapabilities;
+use crate::core::codec::{
+    hardware_accelerated_codecs,
+    offered_codecs,
+    offered_kinds,
+    prefer_codec,
+    prefer_codecs,
+    select_codec,
+    CodecSelection,
+};
 use crate::core::connection_fsm::{ConnectionEvent, ConnectionStateMachine};
+use crate::core::ice_candidate_policy::IceCandidatePolicy;
+use crate::core::ice_server::IceServerConfig;
+use crate::core::media_constraints::MediaConstraints;
 use crate::core::platform::Platform;
+use crate::core::rate_limit::RateLimiter;
+use crate::core::recording::{Recorder, RecordingConfig, RecordingSink};
+use crate::core::route::RouteMonitor;
+use crate::core::safety_numbers::Fingerprints;
+use crate::core::sequencing::UNSEQUENCED;
+use crate::core::signaling::{negotiate, Answer, Offer, Renegotiation, LEGACY_PROTOCOL_VERSION};
+#[cfg(feature = "srtp_key_export")]
+use crate::core::srtp_keys::SrtpKeys;
+use crate::core::trace::{TraceBuffer, TraceEvent, TraceKind};
 use crate::core::util::{ptr_as_box, redact_string};
+use crate::core::video::{
+    video_orientation_negotiated,
+    DegradationPreference,
+    Simulcast,
+    SimulcastLayer,
+    VideoOrientation,
+    VideoResolution,
+    VideoScaling,
+    MAX_SIMULCAST_LAYERS,
+};
 
 use crate::error::RingRtcError;
-use crate::webrtc::data_channel::DataChannel;
+use crate::webrtc::data_channel::{DataChannel, DEFAULT_HIGH_WATER_MARK};
 use crate::webrtc::data_channel_observer::DataChannelObserver;
-use crate::webrtc::ice_candidate::IceCandidate;
-use crate::webrtc::media_stream::MediaStream;
+use crate::webrtc::frame_crypto::{FrameCryptor, MediaCryptoProvider};
+use crate::webrtc::ice_candidate::{IceCandidate, IceRoute};
+use crate::webrtc::media::{AudioDirection, AudioFrame, AudioTap};
+use crate::webrtc::media_stream::{MediaStream, VideoFrame, VideoSink, VideoSource};
 use crate::webrtc::peer_connection::PeerConnection;
+use crate::webrtc::peer_connection_observer::IceGatheringState;
 use crate::webrtc::sdp_observer::{
     create_csd_observer,
     create_ssd_observer,
     SessionDescriptionInterface,
 };
+use crate::webrtc::stats_observer::{create_stats_observer, StatsReport};
+
+/// Gain that leaves the outgoing audio unchanged.
+pub const UNITY_OUTGOING_AUDIO_GAIN: f32 = 1.0;
+
+/// Largest gain applied to the outgoing audio.
+pub const MAX_OUTGOING_AUDIO_GAIN: f32 = 4.0;
+
+/// Longest minimum delay of the audio jitter buffer, the cap WebRTC
+/// applies itself.
+pub const MAX_JITTER_BUFFER_MIN_DELAY: Duration = Duration::from_secs(10);
+
+/// The tones accepted by the WebRTC DtmfSender, where `,` is a pause.
+const DTMF_TONES: &str = ",0123456789*#ABCDabcd";
+
+/// Shortest and longest duration of a DTMF tone, in milliseconds, as
+/// accepted by the WebRTC DtmfSender.
+const DTMF_DURATION_RANGE_MS: (i32, i32) = (40, 6000);
+
+/// Shortest gap between DTMF tones, in milliseconds.
+const MIN_DTMF_GAP_MS: i32 = 30;
+
+/// Times the delay before an automatic recovery attempt doubles, at
+/// most.
+const MAX_RECOVERY_BACKOFF_DOUBLINGS: u32 = 6;
+
+/// Most control messages queued while the DataChannel is backed up.
+pub const MAX_QUEUED_CONTROL_MESSAGES: usize = 32;
 
 /// Connection observer status notification types
 ///
@@ -51,8 +132,28 @@ pub enum ObserverEvent {
     /// The remote video status.
     RemoteVideoStatus(bool),
 
+    /// The remote audio status, `false` when muted.
+    RemoteAudioStatus(bool),
+
     /// The remote side has hungup.
-    RemoteHangup,
+    RemoteHangup(HangupType),
+
+    /// The remote hold status, `true` when on hold.
+    RemoteHold(bool),
+
+    /// The remote recording status, `true` while recording.
+    RemoteRecordingStatus(bool),
+
+    /// The remote screen share status, `true` while sharing.
+    RemoteScreenShareStatus(bool),
+
+    /// No media arrived from the remote peer for the media stall
+    /// timeout, or media arrives again.
+    RemoteMediaFlow(MediaFlow),
+
+    /// The remote peer closed its media, e.g. because its client
+    /// exited without hanging up.
+    RemoteMediaClosed,
 
     /// The call failed to connect during ICE negotiation.
     ConnectionFailed,
@@ -62,6 +163,47 @@ pub enum ObserverEvent {
 
     /// The call dropped while connected and is now reconnected.
     ConnectionReconnected,
+
+    /// The remote offer contained none of the preferred codecs, so
+    /// the answer fell back to a common codec.
+    CodecFallback,
+
+    /// ICE recovery failed and the PeerConnection is being rebuilt
+    /// from scratch.
+    ConnectionHardReset,
+
+    /// The renegotiation offer sent by a hard reset went unanswered.
+    RenegotiationTimeout,
+
+    /// The rotation of the remote video changed, in degrees.
+    RemoteVideoOrientation(u16),
+
+    /// A DTMF tone was received from the remote peer.
+    ReceivedDtmf(char),
+
+    /// The remote peer asked to transfer the call to another peer.
+    TransferRequested,
+
+    /// The remote peer accepted the transfer of the call.
+    TransferAccepted,
+
+    /// The remote peer declined the transfer of the call.
+    TransferDeclined,
+
+    /// A DataChannel send was refused and the send buffer has since
+    /// drained.
+    DataChannelBufferedAmountLow,
+
+    /// The ICE route changed between direct and relayed, and held
+    /// for the debounce period.
+    IceRouteChanged,
+
+    /// ICE selected a new candidate pair, possibly over another
+    /// network.
+    NetworkRouteChanged,
+
+    /// The ICE candidate gathering state changed.
+    IceGatheringStateChanged(IceGatheringState),
 }
 
 impl Clone for ObserverEvent {
@@ -76,6 +218,73 @@ impl fmt::Display for ObserverEvent {
     }
 }
 
+/// A control message for the remote peer, queued while the
+/// DataChannel is backed up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ControlMessage {
+    Hangup(HangupType),
+    Connected,
+    VideoStatus(bool),
+    AudioStatus(bool),
+    Hold(bool),
+    Transfer(TransferMessage),
+    VideoLayerRequest(usize),
+    RecordingStatus(bool),
+    ScreenShareStatus(bool),
+    Negotiation(NegotiationMessage),
+}
+
+impl ControlMessage {
+    /// Send the message via `data_channel`.
+    fn send(&self, data_channel: &DataChannel, call_id: CallId) -> Result<()> {
+        match self {
+            ControlMessage::Hangup(hangup_type) => data_channel.send_hang_up(call_id, *hangup_type),
+            ControlMessage::Connected => data_channel.send_connected(call_id),
+            ControlMessage::VideoStatus(enabled) => {
+                data_channel.send_video_status(call_id, *enabled)
+            }
+            ControlMessage::AudioStatus(enabled) => {
+                data_channel.send_audio_status(call_id, *enabled)
+            }
+            ControlMessage::Hold(held) => data_channel.send_hold(call_id, *held),
+            ControlMessage::Transfer(message) => data_channel.send_transfer(call_id, message),
+            ControlMessage::VideoLayerRequest(layer) => {
+                data_channel.send_video_layer_request(call_id, *layer)
+            }
+            ControlMessage::RecordingStatus(recording) => {
+                data_channel.send_recording_status(call_id, *recording)
+            }
+            ControlMessage::ScreenShareStatus(sharing) => {
+                data_channel.send_screen_share_status(call_id, *sharing)
+            }
+            ControlMessage::Negotiation(message) => data_channel.send_negotiation(call_id, message),
+        }
+    }
+
+    /// Returns `true` if the message makes a queued `other` message
+    /// moot, as the remote peer only needs the latest status.
+    fn supersedes(&self, other: &ControlMessage) -> bool {
+        matches!(
+            (self, other),
+            (Self::VideoStatus(_), Self::VideoStatus(_))
+                | (Self::AudioStatus(_), Self::AudioStatus(_))
+                | (Self::Hold(_), Self::Hold(_))
+                | (Self::VideoLayerRequest(_), Self::VideoLayerRequest(_))
+                | (Self::RecordingStatus(_), Self::RecordingStatus(_))
+                | (Self::ScreenShareStatus(_), Self::ScreenShareStatus(_))
+        )
+    }
+}
+
+/// Returns `true` if a DataChannel send failed only because the
+/// DataChannel is backed up.
+fn would_block(error: &failure::Error) -> bool {
+    matches!(
+        error.downcast_ref::<RingRtcError>(),
+        Some(RingRtcError::DataChannelWouldBlock(_, _))
+    )
+}
+
 /// Encapsulates several WebRTC objects associated with the
 /// Connection object.
 struct WebRtcData<T>
@@ -94,6 +303,8 @@ where
     app_media_stream:      Option<<T as Platform>::AppMediaStream>,
     /// Application specific peer connection
     app_connection:        Option<<T as Platform>::AppConnection>,
+    /// Frame cryptor attached to the PeerConnection
+    frame_cryptor:         Option<Box<FrameCryptor>>,
 }
 
 // Send and Sync needed to share *const pointer types across threads.
@@ -148,23 +359,499 @@ where
             .into()),
         }
     }
+
+    /// Free up the webrtc related resources.
+    fn release(&mut self) -> Result<()> {
+        // dispose of the media stream
+        let _ = self.app_media_stream.take();
+
+        // unregister the data channel observer
+        if let Some(data_channel) = self.data_channel.take().as_mut() {
+            if let Some(dc_observer) = self.data_channel_observer.take().as_mut() {
+                unsafe { data_channel.unregister_observer(dc_observer.rffi_interface()) };
+            }
+            data_channel.dispose();
+        }
+
+        // Free the application connection object, which is in essence
+        // the PeerConnection object.  It is important to dispose of
+        // the app_connection before the connection_ptr.  The
+        // app_connection refers to the real PeerConnection object,
+        // whose observer is using the connection_ptr.  Once the
+        // PeerConnection is completely shutdown it is safe to free up
+        // the connection_ptr.
+        let _ = self.app_connection.take();
+        let _ = self.pc_interface.take();
+
+        // The frame encryptors and decryptors went away with the
+        // PeerConnection, so free the frame cryptor they called.
+        let _ = self.frame_cryptor.take();
+
+        // Free the connection object previously used by the
+        // PeerConnectionObserver.  Convert the pointer back into a
+        // Box and let it go out of scope.
+        match self.connection_ptr.take() {
+            Some(v) => {
+                let _ = unsafe { ptr_as_box(v)? };
+                Ok(())
+            }
+            None => Err(RingRtcError::OptionValueNotSet(
+                String::from("release()"),
+                String::from("connection_ptr"),
+            )
+            .into()),
+        }
+    }
+}
+
+/// How many times the media flow is sampled per media stall timeout.
+const MEDIA_FLOW_SAMPLES_PER_TIMEOUT: u32 = 4;
+
+/// A change in the flow of media from the remote peer, see
+/// `MediaFlowMonitor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MediaFlow {
+    /// No RTP packet arrived for the media stall timeout.
+    Stalled,
+    /// RTP packets arrive again after a stall.
+    Resumed,
+}
+
+/// Watches the RTP packets received while the call is connected.
+///
+/// ICE only reports a connection lost once its consent checks go
+/// unanswered, so media that stops arriving over a path whose consent
+/// is still fresh, e.g. from a remote peer whose audio device died,
+/// goes unnoticed.  The monitor reports the media stalled once no
+/// packet arrived for the media stall timeout, and resumed once
+/// packets arrive again.
+#[derive(Debug, Default)]
+pub struct MediaFlowMonitor {
+    /// How long without a packet until the media is stalled, once
+    /// monitoring started.
+    timeout:           Option<Duration>,
+    /// Packets received as of the last sample.
+    packets_received:  u64,
+    /// Consent responses received as of the last sample.
+    consent_responses: u64,
+    /// When a packet last arrived, or the monitoring restarted.
+    last_packet_at:    Option<Instant>,
+    /// When a consent response last arrived.
+    last_consent_at:   Option<Instant>,
+    /// `true` while the remote peer holds the call.
+    remote_hold:       bool,
+    /// `true` once a stall was reported, until the media resumes.
+    stalled:           bool,
+}
+
+impl MediaFlowMonitor {
+    /// Start monitoring with `timeout`.
+    ///
+    /// Returns `false` if monitoring already started.
+    pub fn start(&mut self, timeout: Duration, now: Instant) -> bool {
+        if self.timeout.is_some() {
+            return false;
+        }
+        self.timeout = Some(timeout);
+        self.restart(now);
+        true
+    }
+
+    /// Returns how often the media flow is sampled, once monitoring
+    /// started.
+    pub fn sample_period(&self) -> Option<Duration> {
+        self.timeout
+            .map(|timeout| timeout / MEDIA_FLOW_SAMPLES_PER_TIMEOUT)
+    }
+
+    /// Give the media a full timeout to arrive again, e.g. once the
+    /// call resumes from hold or reconnects.
+    pub fn restart(&mut self, now: Instant) {
+        self.last_packet_at = Some(now);
+        self.last_consent_at = Some(now);
+    }
+
+    /// Record whether the remote peer holds the call, and so sends no
+    /// media.
+    pub fn set_remote_hold(&mut self, on_hold: bool, now: Instant) {
+        self.remote_hold = on_hold;
+        self.restart(now);
+    }
+
+    /// Record a sample of the packets received on all RTP streams and
+    /// of the consent responses received, returning a change in the
+    /// media flow, if any.
+    pub fn sample(
+        &mut self,
+        packets_received: u64,
+        consent_responses: u64,
+        now: Instant,
+    ) -> Option<MediaFlow> {
+        let timeout = self.timeout?;
+
+        if consent_responses != self.consent_responses {
+            self.consent_responses = consent_responses;
+            self.last_consent_at = Some(now);
+        }
+
+        // The counts start over when the PeerConnection is rebuilt.
+        if packets_received != self.packets_received {
+            self.packets_received = packets_received;
+            self.last_packet_at = Some(now);
+            if self.stalled {
+                self.stalled = false;
+                return Some(MediaFlow::Resumed);
+            }
+            return None;
+        }
+
+        if self.stalled || self.remote_hold {
+            return None;
+        }
+        let last_packet_at = *self.last_packet_at.get_or_insert(now);
+        if now.duration_since(last_packet_at) >= timeout {
+            self.stalled = true;
+            Some(MediaFlow::Stalled)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if a consent response arrived within the
+    /// timeout, i.e. the path to the remote peer still works.
+    pub fn consent_fresh(&self, now: Instant) -> bool {
+        match (self.timeout, self.last_consent_at) {
+            (Some(timeout), Some(last_consent_at)) => now.duration_since(last_consent_at) < timeout,
+            _ => false,
+        }
+    }
+}
+
+/// How to recover after ICE failed while reconnecting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IceRecovery {
+    /// Restart ICE, keeping the PeerConnection.
+    RestartIce,
+    /// Rebuild the PeerConnection from scratch.
+    HardReset,
+    /// Give up, failing the connection.
+    Fail,
+}
+
+/// Tracks ICE recovery attempts, deciding when to give up on ICE
+/// and rebuild the PeerConnection from scratch.
+#[derive(Debug, Default)]
+pub struct ReconnectionController {
+    /// ICE restarts that failed since the call last connected.
+    failed_restarts: u32,
+    /// Hard resets performed over the life of the connection.
+    hard_resets:     u32,
+    /// `true` while a hard reset is in progress.
+    resetting:       bool,
+    /// Renegotiation offers sent, identifying the outstanding offer.
+    offers:          u32,
+    /// Times the outstanding renegotiation offer has been re-sent.
+    retries:         u32,
+    /// `true` while an ICE restart, rather than a hard reset, is in
+    /// progress.
+    ice_restart:     bool,
+    /// Automatic recovery attempts scheduled, identifying the
+    /// outstanding one.
+    recoveries:      u32,
+    /// Automatic recovery attempts since the call last connected.
+    attempts:        u32,
+    /// Generation of the latest renegotiation offer received from
+    /// the remote peer.
+    remote_offer:    u32,
+}
+
+impl ReconnectionController {
+    /// Maximum number of hard resets before the connection fails.
+    pub const MAX_HARD_RESETS: u32 = 2;
+    /// Times an unanswered renegotiation offer is re-sent.
+    pub const MAX_RENEGOTIATION_RETRIES: u32 = 1;
+
+    /// Record that ICE failed while reconnecting.
+    ///
+    /// ICE is restarted until `max_restarts` ICE restarts have failed
+    /// since the call last connected, and only then is the connection
+    /// hard reset.  A failed hard reset is followed by another one.
+    pub fn ice_failed(&mut self, max_restarts: u32) -> IceRecovery {
+        if self.ice_restart {
+            self.failed_restarts += 1;
+        }
+        let reset_failed = self.resetting && !self.ice_restart;
+        if !reset_failed && self.failed_restarts < max_restarts {
+            IceRecovery::RestartIce
+        } else if self.hard_resets < Self::MAX_HARD_RESETS {
+            IceRecovery::HardReset
+        } else {
+            IceRecovery::Fail
+        }
+    }
+
+    /// Record the start of a hard reset.
+    pub fn start_hard_reset(&mut self) {
+        self.hard_resets += 1;
+        self.resetting = true;
+        self.ice_restart = false;
+        self.retries = 0;
+    }
+
+    /// Record the start of an ICE restart requested by the
+    /// application.
+    ///
+    /// An ICE restart is renegotiated like a hard reset, but keeps
+    /// the PeerConnection and does not count against the hard resets.
+    pub fn start_ice_restart(&mut self) {
+        self.resetting = true;
+        self.ice_restart = true;
+        self.retries = 0;
+    }
+
+    /// Returns `true` while an ICE restart is in progress.
+    pub fn restarting_ice(&self) -> bool {
+        self.ice_restart
+    }
+
+    /// Record a renegotiation offer, returning its identifier.
+    pub fn offer_sent(&mut self) -> u32 {
+        self.offers += 1;
+        self.offers
+    }
+
+    /// Returns `true` if `offer` is the outstanding renegotiation
+    /// offer.
+    pub fn offer_outstanding(&self, offer: u32) -> bool {
+        self.resetting && self.offers == offer
+    }
+
+    /// Returns the renegotiation the latest offer is, while a hard
+    /// reset or an ICE restart is in progress.
+    pub fn renegotiation(&self) -> Option<Renegotiation> {
+        if self.resetting {
+            Some(Renegotiation {
+                generation: self.offers,
+                reset:      !self.ice_restart,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Record a renegotiation offer received from the remote peer.
+    ///
+    /// Returns `false` if the offer is not newer than one already
+    /// received, e.g. a duplicate delivered again by the signaling
+    /// channel.
+    pub fn remote_offer_received(&mut self, renegotiation: Renegotiation) -> bool {
+        if renegotiation.generation > self.remote_offer {
+            self.remote_offer = renegotiation.generation;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record the re-sending of an unanswered renegotiation offer.
+    ///
+    /// Returns `false` if the retries are exhausted.
+    pub fn retry_offer(&mut self) -> bool {
+        if self.retries < Self::MAX_RENEGOTIATION_RETRIES {
+            self.retries += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record the scheduling of an automatic recovery attempt, if
+    /// fewer than `max_attempts` were made since the call last
+    /// connected.
+    ///
+    /// Returns the identifier of the attempt and how many attempts
+    /// were made, including this one.
+    pub fn schedule_recovery(&mut self, max_attempts: u32) -> Option<(u32, u32)> {
+        if self.attempts < max_attempts {
+            self.recoveries += 1;
+            self.attempts += 1;
+            Some((self.recoveries, self.attempts))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if `recovery` is the outstanding automatic
+    /// recovery attempt.
+    pub fn recovery_outstanding(&self, recovery: u32) -> bool {
+        self.recoveries == recovery
+    }
+
+    /// Record that ICE connected again, completing any hard reset.
+    pub fn connected(&mut self) {
+        self.failed_restarts = 0;
+        self.resetting = false;
+        self.ice_restart = false;
+        self.attempts = 0;
+    }
+
+    /// Returns `true` while a hard reset is in progress.
+    pub fn resetting(&self) -> bool {
+        self.resetting
+    }
+
+    /// Returns `true` if the PeerConnection has been hard reset.
+    pub fn has_reset(&self) -> bool {
+        self.hard_resets > 0
+    }
+}
+
+/// What to do with a negotiation offer received from the remote
+/// peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoteOfferAction {
+    /// Answer the offer.
+    Answer,
+    /// Roll back the local offer, then answer the offer.
+    RollbackAndAnswer,
+    /// Ignore the offer, the remote peer answers the local one.
+    Ignore,
+}
+
+/// Tracks the negotiations that add or remove tracks of a connected
+/// call, e.g. to add video to an audio only call, following the
+/// perfect negotiation pattern.
+///
+/// The session descriptions are exchanged over the DataChannel, so
+/// both peers may offer at once.  The callee is the polite peer: it
+/// rolls back its own offer, answers the remote one and then offers
+/// again.  The caller ignores the colliding offer.
+#[derive(Debug, Default)]
+pub struct PerfectNegotiation {
+    /// `true` while a local offer awaits its answer.
+    making_offer: bool,
+    /// `true` if the local tracks changed since the outstanding offer
+    /// was made, or before the call connected.
+    needed:       bool,
+    /// Negotiations completed, in either direction.
+    completed:    u32,
+}
+
+impl PerfectNegotiation {
+    /// Record that the local tracks changed.
+    ///
+    /// Returns `true` if an offer should be made now, rather than
+    /// once the outstanding one is answered.
+    pub fn negotiation_needed(&mut self) -> bool {
+        if self.making_offer {
+            self.needed = true;
+            false
+        } else {
+            self.needed = false;
+            self.making_offer = true;
+            true
+        }
+    }
+
+    /// Record that the local tracks changed before the call
+    /// connected, to be negotiated once it does.
+    pub fn defer(&mut self) {
+        self.needed = true;
+    }
+
+    /// Returns `true` if a negotiation is waiting to be offered.
+    pub fn deferred(&self) -> bool {
+        self.needed && !self.making_offer
+    }
+
+    /// Record an offer received from the remote peer.
+    pub fn offer_received(&mut self, polite: bool) -> RemoteOfferAction {
+        if !self.making_offer {
+            RemoteOfferAction::Answer
+        } else if polite {
+            self.making_offer = false;
+            self.needed = true;
+            RemoteOfferAction::RollbackAndAnswer
+        } else {
+            RemoteOfferAction::Ignore
+        }
+    }
+
+    /// Record an answer received from the remote peer.
+    ///
+    /// Returns `false` if no local offer awaits it.
+    pub fn answer_received(&mut self) -> bool {
+        let expected = self.making_offer;
+        self.making_offer = false;
+        expected
+    }
+
+    /// Record a completed negotiation.
+    ///
+    /// Returns `true` if another offer should be made now, for the
+    /// local tracks changed meanwhile.
+    pub fn completed(&mut self) -> bool {
+        self.completed += 1;
+        if self.deferred() {
+            self.negotiation_needed()
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` while a local offer awaits its answer.
+    pub fn making_offer(&self) -> bool {
+        self.making_offer
+    }
+
+    /// Returns the number of negotiations completed.
+    pub fn negotiations(&self) -> u32 {
+        self.completed
+    }
+
+    /// Forget any negotiation in progress, as a hard reset or an ICE
+    /// restart negotiates all the tracks anew.
+    pub fn reset(&mut self) {
+        self.making_offer = false;
+        self.needed = false;
+    }
 }
 
 /// Encapsulates the FSM and runtime upon which a Connection runs.
 struct Context {
     /// Runtime upon which the ConnectionStateMachine runs.
-    pub worker_runtime: runtime::Runtime,
+    pub worker_runtime:  runtime::Runtime,
+    /// Runtime that manages timeouts, e.g. for renegotiation offers
+    /// and debouncing ICE route changes.
+    pub timeout_runtime: Option<runtime::Runtime>,
 }
 
 impl Context {
     fn new() -> Result<Self> {
         Ok(Self {
-            worker_runtime: runtime::Builder::new()
+            worker_runtime:  runtime::Builder::new()
                 .core_threads(1)
                 .name_prefix("worker".to_string())
                 .build()?,
+            timeout_runtime: Some(
+                runtime::Builder::new()
+                    .core_threads(1)
+                    .name_prefix("timeout".to_string())
+                    .build()?,
+            ),
         })
     }
+
+    fn close(&mut self) {
+        info!("stopping timeout runtime");
+        if let Some(timeout_runtime) = self.timeout_runtime.take() {
+            let _ = timeout_runtime
+                .shutdown_now()
+                .wait()
+                .map_err(|_| warn!("Problems shutting down the timeout runtime"));
+        }
+        info!("stopping timeout runtime: complete");
+    }
 }
 
 /// A mpsc::Sender for injecting ConnectionEvents into the
@@ -212,6 +899,89 @@ where
     pending_inbound_ice_candidates:  Arc<CallMutex<Vec<IceCandidate>>>,
     /// Condition variable used at termination to quiesce and synchronize the FSM.
     terminate_condvar:               Arc<(Mutex<bool>, Condvar)>,
+    /// Bounded history of connection activity, for post-mortem debugging.
+    trace:                           Arc<CallMutex<TraceBuffer>>,
+    /// Maximum and capture resolutions used to scale the sent video.
+    video_scaling:                   Arc<CallMutex<VideoScaling>>,
+    /// Maximum framerate of the sent video, 0 when uncapped.
+    video_max_framerate:             Arc<CallMutex<u32>>,
+    /// How the sent video degrades when bandwidth is constrained.
+    degradation_preference:          Arc<CallMutex<DegradationPreference>>,
+    /// The simulcast layers of the sent video, kept across rebuilds
+    /// of the PeerConnection.
+    simulcast:                       Arc<CallMutex<Simulcast>>,
+    /// How much bandwidth the connection may use.
+    bandwidth_mode:                  Arc<CallMutex<BandwidthMode>>,
+    /// ICE recovery and hard reset tracking.
+    reconnection:                    Arc<CallMutex<ReconnectionController>>,
+    /// Negotiations adding or removing tracks mid-call.
+    negotiation:                     Arc<CallMutex<PerfectNegotiation>>,
+    /// Watches the media received from the remote peer.
+    media_flow:                      Arc<CallMutex<MediaFlowMonitor>>,
+    /// The last video status sent to the remote peer.
+    local_video_status:              Arc<CallMutex<Option<bool>>>,
+    /// The last screen share status sent to the remote peer.
+    local_screen_share_status:       Arc<CallMutex<Option<bool>>>,
+    /// Rotation of the received video.
+    remote_video_orientation:        Arc<CallMutex<VideoOrientation>>,
+    /// Audio levels reported by the audio level RTP header extension.
+    rtp_audio_levels:                Arc<CallMutex<AudioLevels>>,
+    /// Receives the decoded frames of the incoming video, if set.
+    incoming_video_sink:             Arc<CallMutex<Option<Box<dyn VideoSink>>>>,
+    /// Receives the frames of the captured and received audio, if
+    /// set.
+    audio_tap:                       Arc<CallMutex<Option<Box<dyn AudioTap>>>>,
+    /// Records the audio and incoming video, if started.
+    recorder:                        Arc<CallMutex<Option<Recorder>>>,
+    /// Stops the thread polling the outgoing video source, if set.
+    outgoing_video_source:           Arc<CallMutex<Option<CancellationToken>>>,
+    /// Encrypts the media frames end-to-end, if set.
+    media_crypto_provider:           Arc<CallMutex<Option<Arc<dyn MediaCryptoProvider>>>>,
+    /// Whether the outgoing audio is enabled, kept until the audio
+    /// sender exists.
+    outgoing_audio_enabled:          Arc<CallMutex<bool>>,
+    /// Gain applied to the outgoing audio, kept across rebuilds of
+    /// the PeerConnection.
+    outgoing_audio_gain:             Arc<CallMutex<f32>>,
+    /// Processing of the outgoing audio, kept across rebuilds of the
+    /// PeerConnection.
+    audio_config:                    Arc<CallMutex<AudioConfig>>,
+    /// Minimum delay of the audio jitter buffer, if set, kept across
+    /// rebuilds of the PeerConnection.
+    jitter_buffer_min_delay:         Arc<CallMutex<Option<Duration>>>,
+    /// Whether the call is on hold locally, kept across rebuilds of
+    /// the PeerConnection.
+    on_hold:                         Arc<CallMutex<bool>>,
+    /// Target of the transfer requested of the remote peer, while
+    /// waiting for its answer.
+    transfer_target:                 Arc<CallMutex<Option<String>>>,
+    /// Target of the transfer requested by the remote peer, while
+    /// the application decides.
+    remote_transfer_target:          Arc<CallMutex<Option<String>>>,
+    /// What the remote peer supports, once negotiated.
+    remote_capabilities:             Arc<CallMutex<Option<RemoteCapabilities>>>,
+    /// The DTLS fingerprints of the local and remote descriptions.
+    fingerprints:                    Arc<CallMutex<Fingerprints>>,
+    /// The signaling protocol version agreed with the remote peer.
+    protocol_version:                Arc<CallMutex<u32>>,
+    /// The sequence number of the last opaque signaling message sent.
+    signaling_sequence:              Arc<CallMutex<u64>>,
+    /// Debounces changes of the selected ICE route.
+    route_monitor:                   Arc<CallMutex<RouteMonitor>>,
+    /// Limits the rate of ICE candidates accepted from the remote
+    /// peer.
+    received_ice_limiter:            Arc<CallMutex<RateLimiter>>,
+    /// Number of bytes the DataChannel may buffer for sending.
+    data_channel_high_water_mark:    Arc<CallMutex<u64>>,
+    /// Control messages waiting for the DataChannel to drain.
+    control_queue:                   Arc<CallMutex<VecDeque<ControlMessage>>>,
+    /// ICE servers set by the application, replacing those the
+    /// PeerConnection was created with, kept across rebuilds.
+    ice_servers:                     Arc<CallMutex<Option<Vec<IceServerConfig>>>>,
+    /// Which ICE candidates are exchanged with the remote peer.
+    ice_candidate_policy:            Arc<CallMutex<IceCandidatePolicy>>,
+    /// The previous stats report, the baseline of the next one.
+    last_stats_report:               Arc<CallMutex<Option<StatsReport>>>,
 }
 
 impl<T> fmt::Display for Connection<T>
@@ -286,6 +1056,42 @@ where
             pending_outbound_ice_candidates: Arc::clone(&self.pending_outbound_ice_candidates),
             pending_inbound_ice_candidates:  Arc::clone(&self.pending_inbound_ice_candidates),
             terminate_condvar:               Arc::clone(&self.terminate_condvar),
+            trace:                           Arc::clone(&self.trace),
+            video_scaling:                   Arc::clone(&self.video_scaling),
+            video_max_framerate:             Arc::clone(&self.video_max_framerate),
+            degradation_preference:          Arc::clone(&self.degradation_preference),
+            simulcast:                       Arc::clone(&self.simulcast),
+            bandwidth_mode:                  Arc::clone(&self.bandwidth_mode),
+            reconnection:                    Arc::clone(&self.reconnection),
+            negotiation:                     Arc::clone(&self.negotiation),
+            media_flow:                      Arc::clone(&self.media_flow),
+            local_video_status:              Arc::clone(&self.local_video_status),
+            local_screen_share_status:       Arc::clone(&self.local_screen_share_status),
+            remote_video_orientation:        Arc::clone(&self.remote_video_orientation),
+            rtp_audio_levels:                Arc::clone(&self.rtp_audio_levels),
+            incoming_video_sink:             Arc::clone(&self.incoming_video_sink),
+            audio_tap:                       Arc::clone(&self.audio_tap),
+            recorder:                        Arc::clone(&self.recorder),
+            outgoing_video_source:           Arc::clone(&self.outgoing_video_source),
+            media_crypto_provider:           Arc::clone(&self.media_crypto_provider),
+            outgoing_audio_enabled:          Arc::clone(&self.outgoing_audio_enabled),
+            outgoing_audio_gain:             Arc::clone(&self.outgoing_audio_gain),
+            audio_config:                    Arc::clone(&self.audio_config),
+            jitter_buffer_min_delay:         Arc::clone(&self.jitter_buffer_min_delay),
+            on_hold:                         Arc::clone(&self.on_hold),
+            transfer_target:                 Arc::clone(&self.transfer_target),
+            remote_transfer_target:          Arc::clone(&self.remote_transfer_target),
+            remote_capabilities:             Arc::clone(&self.remote_capabilities),
+            fingerprints:                    Arc::clone(&self.fingerprints),
+            protocol_version:                Arc::clone(&self.protocol_version),
+            signaling_sequence:              Arc::clone(&self.signaling_sequence),
+            route_monitor:                   Arc::clone(&self.route_monitor),
+            received_ice_limiter:            Arc::clone(&self.received_ice_limiter),
+            data_channel_high_water_mark:    Arc::clone(&self.data_channel_high_water_mark),
+            control_queue:                   Arc::clone(&self.control_queue),
+            ice_servers:                     Arc::clone(&self.ice_servers),
+            ice_candidate_policy:            Arc::clone(&self.ice_candidate_policy),
+            last_stats_report:               Arc::clone(&self.last_stats_report),
         }
     }
 }
@@ -306,6 +1112,7 @@ where
 
         let call_id = call.call_id();
         let direction = call.direction();
+        let tracing = call.tracing()?;
 
         let webrtc = WebRtcData {
             pc_interface:          None,
@@ -314,6 +1121,7 @@ where
             connection_ptr:        None,
             app_media_stream:      None,
             app_connection:        None,
+            frame_cryptor:         None,
         };
 
         let connection = Self {
@@ -335,6 +1143,66 @@ where
                 "pending_inbound_ice_candidates",
             )),
             terminate_condvar: Arc::new((Mutex::new(false), Condvar::new())),
+            trace: Arc::new(CallMutex::new(
+                TraceBuffer::new(call_id, Some(remote_device), tracing),
+                "trace",
+            )),
+            video_scaling: Arc::new(CallMutex::new(VideoScaling::default(), "video_scaling")),
+            video_max_framerate: Arc::new(CallMutex::new(0, "video_max_framerate")),
+            degradation_preference: Arc::new(CallMutex::new(
+                DegradationPreference::default(),
+                "degradation_preference",
+            )),
+            simulcast: Arc::new(CallMutex::new(Simulcast::default(), "simulcast")),
+            bandwidth_mode: Arc::new(CallMutex::new(BandwidthMode::default(), "bandwidth_mode")),
+            reconnection: Arc::new(CallMutex::new(
+                ReconnectionController::default(),
+                "reconnection",
+            )),
+            negotiation: Arc::new(CallMutex::new(PerfectNegotiation::default(), "negotiation")),
+            media_flow: Arc::new(CallMutex::new(MediaFlowMonitor::default(), "media_flow")),
+            local_video_status: Arc::new(CallMutex::new(None, "local_video_status")),
+            local_screen_share_status: Arc::new(CallMutex::new(None, "local_screen_share_status")),
+            remote_video_orientation: Arc::new(CallMutex::new(
+                VideoOrientation::default(),
+                "remote_video_orientation",
+            )),
+            rtp_audio_levels: Arc::new(CallMutex::new(AudioLevels::default(), "rtp_audio_levels")),
+            incoming_video_sink: Arc::new(CallMutex::new(None, "incoming_video_sink")),
+            audio_tap: Arc::new(CallMutex::new(None, "audio_tap")),
+            recorder: Arc::new(CallMutex::new(None, "recorder")),
+            outgoing_video_source: Arc::new(CallMutex::new(None, "outgoing_video_source")),
+            media_crypto_provider: Arc::new(CallMutex::new(None, "media_crypto_provider")),
+            outgoing_audio_enabled: Arc::new(CallMutex::new(true, "outgoing_audio_enabled")),
+            outgoing_audio_gain: Arc::new(CallMutex::new(
+                UNITY_OUTGOING_AUDIO_GAIN,
+                "outgoing_audio_gain",
+            )),
+            audio_config: Arc::new(CallMutex::new(AudioConfig::default(), "audio_config")),
+            jitter_buffer_min_delay: Arc::new(CallMutex::new(None, "jitter_buffer_min_delay")),
+            on_hold: Arc::new(CallMutex::new(false, "on_hold")),
+            transfer_target: Arc::new(CallMutex::new(None, "transfer_target")),
+            remote_transfer_target: Arc::new(CallMutex::new(None, "remote_transfer_target")),
+            remote_capabilities: Arc::new(CallMutex::new(None, "remote_capabilities")),
+            fingerprints: Arc::new(CallMutex::new(Fingerprints::default(), "fingerprints")),
+            protocol_version: Arc::new(CallMutex::new(LEGACY_PROTOCOL_VERSION, "protocol_version")),
+            signaling_sequence: Arc::new(CallMutex::new(UNSEQUENCED, "signaling_sequence")),
+            route_monitor: Arc::new(CallMutex::new(RouteMonitor::default(), "route_monitor")),
+            received_ice_limiter: Arc::new(CallMutex::new(
+                RateLimiter::for_received_ice_candidates(),
+                "received_ice_limiter",
+            )),
+            data_channel_high_water_mark: Arc::new(CallMutex::new(
+                DEFAULT_HIGH_WATER_MARK,
+                "data_channel_high_water_mark",
+            )),
+            control_queue: Arc::new(CallMutex::new(VecDeque::new(), "control_queue")),
+            ice_servers: Arc::new(CallMutex::new(None, "ice_servers")),
+            ice_candidate_policy: Arc::new(CallMutex::new(
+                IceCandidatePolicy::default(),
+                "ice_candidate_policy",
+            )),
+            last_stats_report: Arc::new(CallMutex::new(None, "last_stats_report")),
         };
 
         connection.init_connection_ptr()?;
@@ -376,10 +1244,65 @@ where
     /// Update the current Call state.
     pub fn set_state(&self, new_state: ConnectionState) -> Result<()> {
         let mut state = self.state.lock()?;
+        if *state != new_state {
+            self.record_trace(
+                TraceKind::StateChange,
+                format!("{} -> {}", *state, new_state),
+            );
+        }
         *state = new_state;
         Ok(())
     }
 
+    /// Record an event in the connection trace.
+    ///
+    /// Tracing is best effort, so failures are ignored.
+    pub fn record_trace(&self, kind: TraceKind, description: String) {
+        if let Ok(mut trace) = self.trace.lock() {
+            trace.record(kind, description);
+        }
+    }
+
+    /// Return the trace of this connection, merged with the trace of
+    /// the parent call, ordered by timestamp.
+    ///
+    /// The trace is retained until the last reference to the
+    /// Connection is dropped, so this is safe to call after the call
+    /// has concluded.
+    pub fn export_trace(&self) -> Result<Vec<TraceEvent>> {
+        let mut events = self.call()?.export_trace()?;
+        events.append(&mut self.export_connection_trace()?);
+        events.sort_by_key(|e| e.timestamp_ms);
+        Ok(events)
+    }
+
+    /// Return a copy of the connection level trace events, oldest
+    /// first.
+    pub fn export_connection_trace(&self) -> Result<Vec<TraceEvent>> {
+        Ok(self.trace.lock()?.events())
+    }
+
+    /// Return the reconnection controller, under a locked mutex.
+    pub fn reconnection(&self) -> Result<MutexGuard<'_, ReconnectionController>> {
+        self.reconnection.lock()
+    }
+
+    /// Return the tracker of the negotiations adding or removing
+    /// tracks, under a locked mutex.
+    pub fn negotiation(&self) -> Result<MutexGuard<'_, PerfectNegotiation>> {
+        self.negotiation.lock()
+    }
+
+    /// Return the media flow monitor, under a locked mutex.
+    pub fn media_flow(&self) -> Result<MutexGuard<'_, MediaFlowMonitor>> {
+        self.media_flow.lock()
+    }
+
+    /// Return the ICE route monitor, under a locked mutex.
+    pub fn route_monitor(&self) -> Result<MutexGuard<'_, RouteMonitor>> {
+        self.route_monitor.lock()
+    }
+
     /// Update the webrtc::PeerConnection interface.
     pub fn set_pc_interface(&self, pc_interface: PeerConnection) -> Result<()> {
         let mut webrtc = self.webrtc.lock()?;
@@ -387,265 +1310,1429 @@ where
         Ok(())
     }
 
-    /// Update the webrtc::DataChannel interface.
-    pub fn set_data_channel(&self, data_channel: DataChannel) -> Result<()> {
-        let mut webrtc = self.webrtc.lock()?;
-        webrtc.data_channel = Some(data_channel);
-        Ok(())
+    /// Cap the resolution of the sent video.
+    ///
+    /// The video is scaled down, preserving the aspect ratio of the
+    /// capture, to fit within `width` x `height`.  A `(0, 0)` maximum
+    /// removes the cap.
+    pub fn set_video_max_resolution(&self, width: u32, height: u32) -> Result<()> {
+        let scaling = {
+            let mut video_scaling = self.video_scaling.lock()?;
+            video_scaling.max_resolution = VideoResolution::new(width, height);
+            *video_scaling
+        };
+        self.apply_video_scaling(scaling)
     }
 
-    /// Update the webrtc::DataChannelObserver interface.
-    pub fn set_data_channel_observer(&self, observer: DataChannelObserver<T>) -> Result<()> {
-        let mut webrtc = self.webrtc.lock()?;
-        webrtc.data_channel_observer = Some(observer);
-        Ok(())
+    /// Return the maximum resolution of the sent video, `(0, 0)` when
+    /// uncapped.
+    pub fn video_max_resolution(&self) -> Result<VideoResolution> {
+        Ok(self.video_scaling.lock()?.max_resolution)
     }
 
-    /// Update the media stream.
-    pub fn set_app_media_stream(
-        &self,
-        app_media_stream: <T as Platform>::AppMediaStream,
-    ) -> Result<()> {
-        // In the current application we only expect one media stream
-        // per connection.
-        let mut webrtc = self.webrtc.lock()?;
-        match webrtc.app_media_stream {
-            Some(_) => Err(RingRtcError::ActiveMediaStreamAlreadySet(self.remote_device).into()),
-            None => {
-                webrtc.app_media_stream = Some(app_media_stream);
-                Ok(())
-            }
-        }
+    /// Update the resolution of the video capture, recomputing the
+    /// scale of the sent video.
+    pub fn set_video_capture_resolution(&self, width: u32, height: u32) -> Result<()> {
+        let scaling = {
+            let mut video_scaling = self.video_scaling.lock()?;
+            video_scaling.capture_resolution = Some(VideoResolution::new(width, height));
+            *video_scaling
+        };
+        self.apply_video_scaling(scaling)
     }
 
-    /// Set the application peer connection.
-    pub fn set_app_connection(&self, app_connection: <T as Platform>::AppConnection) -> Result<()> {
-        let mut webrtc = self.webrtc.lock()?;
-        match webrtc.app_connection {
-            Some(_) => Err(RingRtcError::AppConnectionAlreadySet(self.remote_device).into()),
+    /// Apply the video scale factor to the PeerConnection.
+    ///
+    /// Skipped until both the PeerConnection and the capture
+    /// resolution are known.
+    fn apply_video_scaling(&self, scaling: VideoScaling) -> Result<()> {
+        let capture_resolution = match scaling.capture_resolution {
+            Some(v) => v,
             None => {
-                webrtc.app_connection = Some(app_connection);
-                Ok(())
+                info!(
+                    "id: {}, skipping video scaling, capture resolution unknown",
+                    self.id()
+                );
+                return Ok(());
             }
-        }
-    }
+        };
 
-    /// Return a clone of the application peer connection.
-    pub fn app_connection(&self) -> Result<<T as Platform>::AppConnection> {
         let webrtc = self.webrtc.lock()?;
-        match webrtc.app_connection.as_ref() {
-            Some(v) => Ok(v.clone()),
-            None => Err(RingRtcError::OptionValueNotSet(
-                String::from("app_connection()"),
-                String::from("app_connection"),
-            )
-            .into()),
-        }
+        let pc_interface = match webrtc.pc_interface.as_ref() {
+            Some(v) => v,
+            None => {
+                info!("id: {}, skipping video scaling, no pc_interface", self.id());
+                return Ok(());
+            }
+        };
+
+        let scale = scaling.scale_resolution_down_by();
+        info!(
+            "id: {}, capture: {}, max: {}, scale_resolution_down_by: {}",
+            self.id(),
+            capture_resolution,
+            scaling.max_resolution,
+            scale
+        );
+        pc_interface.set_video_scale_resolution_down_by(scale)
     }
 
-    /// Returns `true` if the call is terminating.
-    pub fn terminating(&self) -> Result<bool> {
-        if let ConnectionState::Terminating = self.state()? {
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+    /// Cap the framerate of the sent video, where 0 removes the cap.
+    ///
+    /// The cap is recorded even when the PeerConnection does not
+    /// exist yet, and is applied to its video senders once created.
+    pub fn set_video_max_framerate(&self, max_framerate: u32) -> Result<()> {
+        *self.video_max_framerate.lock()? = max_framerate;
+        self.apply_video_max_framerate(max_framerate)
     }
 
-    /// Clone the Connection, Box it and return a raw pointer to the Box.
-    pub fn create_connection_ptr(&self) -> *mut Connection<T> {
-        let connection_box = Box::new(self.clone());
-        Box::into_raw(connection_box)
+    /// Return the maximum framerate of the sent video, 0 when
+    /// uncapped.
+    pub fn video_max_framerate(&self) -> Result<u32> {
+        Ok(*self.video_max_framerate.lock()?)
     }
 
-    /// Return the internally tracked connection object pointer, for
-    /// use by the PeerConnectionObserver call backs.
-    pub fn get_connection_ptr(&self) -> Result<*mut Connection<T>> {
+    /// Apply the maximum video framerate to the PeerConnection.
+    fn apply_video_max_framerate(&self, max_framerate: u32) -> Result<()> {
         let webrtc = self.webrtc.lock()?;
-        match webrtc.connection_ptr.as_ref() {
-            Some(v) => Ok(*v),
-            None => Err(RingRtcError::OptionValueNotSet(
-                String::from("connection_ptr()"),
-                String::from("connection_ptr"),
-            )
-            .into()),
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, video max framerate: {}", self.id(), max_framerate);
+                pc_interface.set_video_max_framerate(max_framerate)
+            }
+            None => {
+                info!(
+                    "id: {}, deferring video max framerate: {}, no pc_interface",
+                    self.id(),
+                    max_framerate
+                );
+                Ok(())
+            }
         }
     }
 
-    /// Create a connection object on the heap, for use by the
-    /// PeerConnectionObserver call backs.  Track it, as it needs to
-    /// be freed after closing down the PeerConnection.
-    fn init_connection_ptr(&self) -> Result<()> {
-        let mut webrtc = self.webrtc.lock()?;
-        webrtc.connection_ptr = Some(self.create_connection_ptr());
+    /// Apply the media constraints the call started with, before the
+    /// media starts flowing.
+    ///
+    /// Muting the outgoing audio is left to the call, which keeps the
+    /// audio status of all its connections.
+    pub fn apply_media_constraints(&self, constraints: MediaConstraints) -> Result<()> {
+        info!("id: {}, media constraints: {}", self.id(), constraints);
+
+        let max_resolution = constraints.max_resolution;
+        if max_resolution != VideoResolution::default() {
+            self.set_video_max_resolution(max_resolution.width, max_resolution.height)?;
+        }
+        if constraints.max_framerate > 0 {
+            self.set_video_max_framerate(constraints.max_framerate)?;
+        }
+        if constraints.start_video_muted {
+            self.set_local_video_status(false)?;
+        }
         Ok(())
     }
 
-    /// Return the strong reference count on the webrtc `Arc<Mutex<>>`.
-    fn ref_count(&self) -> usize {
-        Arc::strong_count(&self.webrtc)
+    /// Set how the sent video degrades when bandwidth is constrained.
+    ///
+    /// The preference is recorded even when the PeerConnection does
+    /// not exist yet, and is applied to its video senders once
+    /// created.
+    pub fn set_degradation_preference(&self, preference: DegradationPreference) -> Result<()> {
+        *self.degradation_preference.lock()? = preference;
+        self.apply_degradation_preference(preference)
     }
 
-    /// Create an SDP offer message.
-    fn create_offer(&self) -> Result<SessionDescriptionInterface> {
-        let csd_observer = create_csd_observer();
+    /// Return how the sent video degrades when bandwidth is
+    /// constrained.
+    pub fn degradation_preference(&self) -> Result<DegradationPreference> {
+        Ok(*self.degradation_preference.lock()?)
+    }
 
+    /// Apply the degradation preference to the PeerConnection.
+    fn apply_degradation_preference(&self, preference: DegradationPreference) -> Result<()> {
         let webrtc = self.webrtc.lock()?;
-        webrtc.pc_interface()?.create_offer(csd_observer.as_ref());
-        csd_observer.get_result()
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, degradation preference: {}", self.id(), preference);
+                pc_interface.set_video_degradation_preference(preference as i32)
+            }
+            None => {
+                info!(
+                    "id: {}, deferring degradation preference: {}, no pc_interface",
+                    self.id(),
+                    preference
+                );
+                Ok(())
+            }
+        }
     }
 
-    /// Create an SDP answer message.
-    fn create_answer(&self) -> Result<SessionDescriptionInterface> {
-        let csd_observer = create_csd_observer();
+    /// Simulcast the sent video as `layers`, from the lowest quality
+    /// up, each capped in resolution and bitrate.  No layers go back
+    /// to sending a single encoding.
+    ///
+    /// At most `MAX_SIMULCAST_LAYERS` layers are sent.  The layers
+    /// are recorded even when the PeerConnection does not exist yet,
+    /// and are applied to its video sender once created.
+    pub fn set_simulcast_layers(&self, layers: Vec<SimulcastLayer>) -> Result<()> {
+        let simulcast = {
+            let mut simulcast = self.simulcast.lock()?;
+            simulcast.set_layers(layers)?;
+            simulcast.clone()
+        };
+        self.apply_simulcast(&simulcast)
+    }
 
-        let webrtc = self.webrtc.lock()?;
-        webrtc.pc_interface()?.create_answer(csd_observer.as_ref());
-        csd_observer.get_result()
+    /// Return the simulcast layers of the sent video, and the layer
+    /// requested by the remote peer.
+    pub fn simulcast(&self) -> Result<Simulcast> {
+        Ok(self.simulcast.lock()?.clone())
     }
 
-    /// Set the local SPD decription in the PeerConnection interface.
-    fn set_local_description(&self, desc: &SessionDescriptionInterface) -> Result<()> {
-        let ssd_observer = create_ssd_observer();
+    /// Ask the remote peer to send only the simulcast `layer` of its
+    /// video, where 0 is the lowest quality, via the PeerConnection
+    /// DataChannel.
+    pub fn request_video_layer(&self, layer: usize) -> Result<()> {
+        if layer >= MAX_SIMULCAST_LAYERS {
+            return Err(RingRtcError::InvalidVideoLayer(layer).into());
+        }
 
-        let webrtc = self.webrtc.lock()?;
-        webrtc
-            .pc_interface()?
-            .set_local_description(ssd_observer.as_ref(), desc);
-        ssd_observer.get_result()
+        info!("id: {}, request_video_layer(): {}", self.id(), layer);
+        self.send_control_message(ControlMessage::VideoLayerRequest(layer))
     }
 
-    /// Set the remote SPD decription in the PeerConnection interface.
-    fn set_remote_description(&self, desc: &SessionDescriptionInterface) -> Result<()> {
-        let ssd_observer = create_ssd_observer();
+    /// Send only the simulcast `layer` requested by the remote peer.
+    pub fn remote_video_layer_requested(&self, layer: usize) -> Result<()> {
+        let simulcast = {
+            let mut simulcast = self.simulcast.lock()?;
+            simulcast.request_layer(layer)?;
+            simulcast.clone()
+        };
+        self.apply_simulcast(&simulcast)
+    }
 
+    /// Apply the simulcast layers to the PeerConnection.
+    fn apply_simulcast(&self, simulcast: &Simulcast) -> Result<()> {
         let webrtc = self.webrtc.lock()?;
-        webrtc
-            .pc_interface()?
-            .set_remote_description(ssd_observer.as_ref(), desc);
-        ssd_observer.get_result()
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!(
+                    "id: {}, simulcast layers: {}, active: {:?}",
+                    self.id(),
+                    simulcast.layers().len(),
+                    simulcast.active_layers()
+                );
+                pc_interface.set_simulcast(simulcast)
+            }
+            None => {
+                info!(
+                    "id: {}, deferring simulcast layers: {}, no pc_interface",
+                    self.id(),
+                    simulcast.layers().len()
+                );
+                Ok(())
+            }
+        }
     }
 
-    /// Send an SDP offer message to the remote peer via the signaling
-    /// channel.
-    pub fn send_offer(&self) -> Result<()> {
-        let offer = self.create_offer()?;
-        self.set_local_description(&offer)?;
-
-        info!(
-            "id: {}, TX SDP offer:\n{}",
-            self.id(),
-            redact_string(&offer.get_description()?)
-        );
+    /// Set how much bandwidth the connection may use.
+    ///
+    /// The maximum bitrate is applied to the PeerConnection right
+    /// away, or once created, while the limits asked of the remote
+    /// peer take effect with the next local description.
+    pub fn set_bandwidth_mode(&self, mode: BandwidthMode) -> Result<()> {
+        *self.bandwidth_mode.lock()? = mode;
+        self.apply_bandwidth_mode(mode)
+    }
 
-        let call = self.call()?;
-        call.send_offer(self.clone(), offer)
+    /// Return how much bandwidth the connection may use.
+    pub fn bandwidth_mode(&self) -> Result<BandwidthMode> {
+        Ok(*self.bandwidth_mode.lock()?)
     }
 
-    /// Check to see if this Connection is able to send messages.
-    /// Once it is terminated it shouldn't be able to.
-    pub fn can_send_messages(&self) -> bool {
-        let state_result = self.state();
+    /// Apply the maximum bitrate of the bandwidth mode to the
+    /// PeerConnection.
+    fn apply_bandwidth_mode(&self, mode: BandwidthMode) -> Result<()> {
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, bandwidth mode: {}", self.id(), mode);
+                pc_interface.set_max_bitrate(mode.max_bitrate_bps())
+            }
+            None => {
+                info!(
+                    "id: {}, deferring bandwidth mode: {}, no pc_interface",
+                    self.id(),
+                    mode
+                );
+                Ok(())
+            }
+        }
+    }
 
-        match state_result {
-            Ok(state) => match state {
-                ConnectionState::Terminating | ConnectionState::Closed => false,
-                _ => true,
-            },
-            Err(_) => false,
+    /// Limit the bandwidth asked of the remote peer in a local
+    /// description, according to the bandwidth mode.
+    fn limit_bandwidth(
+        &self,
+        desc: SessionDescriptionInterface,
+        create: fn(String) -> Result<SessionDescriptionInterface>,
+    ) -> Result<SessionDescriptionInterface> {
+        let mode = *self.bandwidth_mode.lock()?;
+        match mode.limit_sdp(&desc.get_description()?) {
+            Some(description) => create(description),
+            None => Ok(desc),
         }
     }
 
-    /// Handle an incoming SDP answer message.
-    pub fn handle_answer(&mut self, answer: String) -> Result<()> {
-        let desc = SessionDescriptionInterface::create_sdp_answer(answer)?;
-        self.set_remote_description(&desc)?;
-        self.inject_have_local_remote_sdp()
+    /// Move the codecs preferred by the call to the front of a local
+    /// description, see `CallConfig::preferred_video_codec()`.
+    fn prefer_call_codecs(
+        &self,
+        desc: SessionDescriptionInterface,
+        create: fn(String) -> Result<SessionDescriptionInterface>,
+    ) -> Result<SessionDescriptionInterface> {
+        let codecs = match self.call()?.config() {
+            Some(config) => config.preferred_codecs(),
+            None => Vec::new(),
+        };
+        if codecs.is_empty() {
+            return Ok(desc);
+        }
+
+        info!("id: {}, preferring call codecs: {:?}", self.id(), codecs);
+        create(prefer_codecs(&desc.get_description()?, &codecs))
     }
 
-    /// Handle an incoming SDP offer message.
-    pub fn handle_offer(&mut self, offer: String) -> Result<()> {
-        let desc = SessionDescriptionInterface::create_sdp_offer(offer)?;
-        self.set_remote_description(&desc)?;
+    /// Offer the audio level RTP header extension, so the audio
+    /// levels are known without polling the stats.
+    fn offer_audio_level(
+        &self,
+        offer: SessionDescriptionInterface,
+    ) -> Result<SessionDescriptionInterface> {
+        let description = offer.get_description()?;
+        let offered = add_audio_level_extension(&description);
+        if offered == description {
+            return Ok(offer);
+        }
 
-        let answer = self.create_answer()?;
-        self.set_local_description(&answer)?;
-        self.inject_have_local_remote_sdp()?;
+        info!("id: {}, offering audio levels", self.id());
+        SessionDescriptionInterface::create_sdp_offer(offered)
+    }
 
-        info!(
-            "id: {}, TX SDP answer:\n{}",
-            self.id(),
-            redact_string(&answer.get_description()?)
-        );
+    /// Let the application transform a local description, see
+    /// `Platform::transform_local_description()`.
+    fn transform_local_description(
+        &self,
+        desc: SessionDescriptionInterface,
+        create: fn(String) -> Result<SessionDescriptionInterface>,
+    ) -> Result<SessionDescriptionInterface> {
+        let description = desc.get_description()?;
+        let transformed = {
+            let call = self.call()?;
+            let call_manager = call.call_manager()?;
+            call_manager.transform_local_description(self.id(), description.clone())?
+        };
+        if transformed == description {
+            Ok(desc)
+        } else {
+            info!("id: {}, local description transformed", self.id());
+            create(transformed)
+        }
+    }
 
+    /// Let the application transform a remote description, see
+    /// `Platform::transform_remote_description()`.
+    fn transform_remote_description(&self, sdp: String) -> Result<String> {
         let call = self.call()?;
-        call.send_answer(self.clone(), answer)
+        let call_manager = call.call_manager()?;
+        call_manager.transform_remote_description(self.id(), sdp)
     }
 
-    /// Buffer local ICE candidates.
-    pub fn buffer_local_ice_candidate(&self, candidate: IceCandidate) -> Result<()> {
-        info!("Local ICE candidate: {}", candidate);
-
-        let num_ice_candidates = {
-            let mut ice_candidates = self.pending_outbound_ice_candidates.lock()?;
-            ice_candidates.push(candidate);
-            ice_candidates.len()
-        };
+    /// Enable or disable the outgoing audio.
+    ///
+    /// The setting is recorded even when the PeerConnection does not
+    /// exist yet, and is applied to its audio senders once created.
+    /// While the call is on hold the audio stays paused, the setting
+    /// applies when the call is resumed.
+    pub fn set_outgoing_audio_enabled(&self, enabled: bool) -> Result<()> {
+        *self.outgoing_audio_enabled.lock()? = enabled;
+        let on_hold = self.on_hold()?;
 
-        // Only when we transition from no candidates to one do we
-        // need to signal the message queue that there is something
-        // to send for this Connection.
-        if num_ice_candidates == 1 {
-            let call = self.call()?;
-            call.send_ice_candidates(self.clone())?
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, outgoing audio enabled: {}", self.id(), enabled);
+                pc_interface.set_outgoing_audio_enabled(enabled && !on_hold)
+            }
+            None => {
+                info!(
+                    "id: {}, deferring outgoing audio enabled: {}, no pc_interface",
+                    self.id(),
+                    enabled
+                );
+                Ok(())
+            }
         }
+    }
 
-        Ok(())
+    /// Return whether the outgoing audio is enabled.
+    pub fn outgoing_audio_enabled(&self) -> Result<bool> {
+        Ok(*self.outgoing_audio_enabled.lock()?)
     }
 
-    /// Buffer remote ICE candidates.
-    pub fn buffer_remote_ice_candidates(&self, ice_candidates: Vec<IceCandidate>) -> Result<()> {
-        let mut pending_ice_candidates = self.pending_inbound_ice_candidates.lock()?;
-        for ice_candidate in ice_candidates {
-            info!("Remote ICE candidates: {}", ice_candidate);
-            pending_ice_candidates.push(ice_candidate);
-        }
+    /// Mute or unmute the outgoing audio, and let the remote peer
+    /// know via the DataChannel.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `false` to mute the local audio track.
+    pub fn set_audio_enabled(&mut self, enabled: bool) -> Result<()> {
+        self.set_outgoing_audio_enabled(enabled)?;
+        self.inject_local_audio_status(enabled)
+    }
+
+    /// Put the call on hold or resume it, and let the remote peer
+    /// know via the DataChannel.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_hold` - `true` to pause the outgoing media.
+    pub fn set_on_hold(&mut self, on_hold: bool) -> Result<()> {
+        self.inject_local_hold(on_hold)
+    }
+
+    /// Record whether the call is on hold locally.
+    pub fn set_hold_status(&self, on_hold: bool) -> Result<()> {
+        *self.on_hold.lock()? = on_hold;
         Ok(())
     }
 
-    /// Get the current local ICE candidates to send to the remote peer.
-    pub fn get_pending_ice_updates(&self) -> Result<Vec<IceCandidate>> {
-        info!("get_pending_ice_updates():");
+    /// Return whether the call is on hold locally.
+    pub fn on_hold(&self) -> Result<bool> {
+        Ok(*self.on_hold.lock()?)
+    }
 
-        let mut ice_candidates = self.pending_outbound_ice_candidates.lock()?;
+    /// Ask the remote peer to call `target` in place of the local
+    /// peer, via the DataChannel.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The peer to transfer the call to.
+    pub fn transfer(&mut self, target: String) -> Result<()> {
+        self.inject_local_transfer(target)
+    }
 
-        let copy_candidates = ice_candidates.clone();
-        ice_candidates.clear();
+    /// Record the target of the transfer requested of the remote
+    /// peer, `None` once it answered.
+    pub fn set_transfer_target(&self, target: Option<String>) -> Result<Option<String>> {
+        let mut transfer_target = self.transfer_target.lock()?;
+        Ok(std::mem::replace(&mut *transfer_target, target))
+    }
 
-        info!(
-            "get_pending_ice_updates(): Local ICE candidates length: {}",
-            copy_candidates.len()
-        );
+    /// Return the target of the transfer requested of the remote
+    /// peer, if it has not answered yet.
+    pub fn transfer_target(&self) -> Result<Option<String>> {
+        Ok(self.transfer_target.lock()?.clone())
+    }
 
-        Ok(copy_candidates)
+    /// Record the target of the transfer requested by the remote
+    /// peer, `None` once answered.
+    pub fn set_remote_transfer_target(&self, target: Option<String>) -> Result<Option<String>> {
+        let mut remote_transfer_target = self.remote_transfer_target.lock()?;
+        Ok(std::mem::replace(&mut *remote_transfer_target, target))
     }
 
-    /// Add any remote ICE candidates to the PeerConnection interface.
-    pub fn handle_remote_ice_updates(&self) -> Result<()> {
-        let mut ice_candidates = self.pending_inbound_ice_candidates.lock()?;
+    /// Return the target of the transfer requested by the remote
+    /// peer, if it was not answered yet.
+    pub fn remote_transfer_target(&self) -> Result<Option<String>> {
+        Ok(self.remote_transfer_target.lock()?.clone())
+    }
 
-        if ice_candidates.is_empty() {
-            return Ok(());
+    /// Return the state of the connected call, `OnHold` while the
+    /// call is on hold locally.
+    pub fn connected_state(&self) -> Result<ConnectionState> {
+        if self.on_hold()? {
+            Ok(ConnectionState::OnHold)
+        } else {
+            Ok(ConnectionState::CallConnected)
         }
+    }
+
+    /// Pause the outgoing audio and video while the call is on hold,
+    /// otherwise restore them.
+    ///
+    /// Resuming leaves muted audio muted, and only restores video and
+    /// screen share the remote peer was told are streaming.
+    pub fn apply_hold(&self) -> Result<()> {
+        let on_hold = self.on_hold()?;
+        let audio_enabled = !on_hold && self.outgoing_audio_enabled()?;
+        let video_enabled = !on_hold && self.local_video_status.lock()?.unwrap_or(false);
+        let screen_share_enabled = !on_hold && self.screen_sharing()?;
 
-        info!(
-            "handle_remote_ice_updates(): Remote ICE candidates length: {}",
-            ice_candidates.len()
-        );
         let webrtc = self.webrtc.lock()?;
-        for candidate in &(*ice_candidates) {
-            webrtc.pc_interface()?.add_ice_candidate(candidate)?;
-        }
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, on hold: {}", self.id(), on_hold);
+                pc_interface.set_outgoing_audio_enabled(audio_enabled)?;
+                pc_interface.set_outgoing_screen_share_enabled(screen_share_enabled)?;
+                pc_interface.set_outgoing_video_enabled(video_enabled)
+            }
+            None => {
+                info!(
+                    "id: {}, deferring on hold: {}, no pc_interface",
+                    self.id(),
+                    on_hold
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Set the gain applied to the outgoing audio, where 1.0 leaves
+    /// the audio unchanged.
+    ///
+    /// The gain is clamped to between 0.0 and
+    /// `MAX_OUTGOING_AUDIO_GAIN`.  A gain above 1.0 amplifies the
+    /// microphone signal and may clip loud input.  The gain is
+    /// recorded even when the PeerConnection does not exist yet, and
+    /// is applied to its audio senders once created.
+    pub fn set_outgoing_audio_gain(&self, gain: f32) -> Result<()> {
+        let gain = gain.clamp(0.0, MAX_OUTGOING_AUDIO_GAIN);
+        *self.outgoing_audio_gain.lock()? = gain;
+        self.apply_outgoing_audio_gain(gain)
+    }
+
+    /// Send `tones` as DTMF on the outgoing audio.
+    ///
+    /// Tones are the characters 0-9, A-D, `#` and `*`, where `,`
+    /// inserts a two second pause.  Each tone plays for `duration_ms`
+    /// followed by `gap_ms` of silence.  Tones queue behind any tones
+    /// still being played.
+    pub fn send_dtmf(&self, tones: &str, duration_ms: i32, gap_ms: i32) -> Result<()> {
+        if let Some(tone) = tones.chars().find(|tone| !DTMF_TONES.contains(*tone)) {
+            return Err(RingRtcError::InvalidDtmfTone(tone).into());
+        }
+        let (min_duration_ms, max_duration_ms) = DTMF_DURATION_RANGE_MS;
+        if duration_ms < min_duration_ms
+            || duration_ms > max_duration_ms
+            || gap_ms < MIN_DTMF_GAP_MS
+        {
+            return Err(RingRtcError::InvalidDtmfTiming(duration_ms, gap_ms).into());
+        }
+
+        info!(
+            "id: {}, send_dtmf(): tones: {}, duration_ms: {}, gap_ms: {}",
+            self.id(),
+            tones,
+            duration_ms,
+            gap_ms
+        );
+        let webrtc = self.webrtc.lock()?;
+        webrtc
+            .pc_interface()?
+            .insert_dtmf(tones, duration_ms, gap_ms)
+    }
+
+    /// Return the gain applied to the outgoing audio.
+    pub fn outgoing_audio_gain(&self) -> Result<f32> {
+        Ok(*self.outgoing_audio_gain.lock()?)
+    }
+
+    /// Apply the outgoing audio gain to the PeerConnection.
+    fn apply_outgoing_audio_gain(&self, gain: f32) -> Result<()> {
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, outgoing audio gain: {}", self.id(), gain);
+                pc_interface.set_outgoing_audio_gain(gain)
+            }
+            None => {
+                info!(
+                    "id: {}, deferring outgoing audio gain: {}, no pc_interface",
+                    self.id(),
+                    gain
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Set the processing of the outgoing audio, applied to the
+    /// PeerConnection right away, or once created.
+    pub fn set_audio_config(&self, config: AudioConfig) -> Result<()> {
+        *self.audio_config.lock()? = config;
+        self.apply_audio_config(config)
+    }
+
+    /// Return the processing of the outgoing audio.
+    pub fn audio_config(&self) -> Result<AudioConfig> {
+        Ok(*self.audio_config.lock()?)
+    }
+
+    /// Apply the audio processing to the PeerConnection.
+    fn apply_audio_config(&self, config: AudioConfig) -> Result<()> {
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, audio config: {}", self.id(), config);
+                pc_interface.set_audio_config(config)
+            }
+            None => {
+                info!(
+                    "id: {}, deferring audio config: {}, no pc_interface",
+                    self.id(),
+                    config
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Set the minimum delay of the audio jitter buffer.
+    ///
+    /// WebRTC adapts the delay of the received audio to the jitter of
+    /// the network.  A minimum delay trades latency for robustness,
+    /// e.g. for push to talk, where a late start matters less than
+    /// gaps.  `None` leaves the delay to WebRTC again.  The delay is
+    /// recorded even when the PeerConnection does not exist yet, and
+    /// is applied to its audio receivers once created.
+    pub fn set_jitter_buffer_min_delay(&self, delay: Option<Duration>) -> Result<()> {
+        if let Some(delay) = delay {
+            if delay > MAX_JITTER_BUFFER_MIN_DELAY {
+                return Err(RingRtcError::InvalidJitterBufferMinDelay(delay).into());
+            }
+        }
+        *self.jitter_buffer_min_delay.lock()? = delay;
+        self.apply_jitter_buffer_min_delay(delay)
+    }
+
+    /// Return the minimum delay of the audio jitter buffer, if set.
+    pub fn jitter_buffer_min_delay(&self) -> Result<Option<Duration>> {
+        Ok(*self.jitter_buffer_min_delay.lock()?)
+    }
+
+    /// Apply the minimum jitter buffer delay to the PeerConnection.
+    fn apply_jitter_buffer_min_delay(&self, delay: Option<Duration>) -> Result<()> {
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, jitter buffer min delay: {:?}", self.id(), delay);
+                pc_interface.set_audio_jitter_buffer_min_delay(delay)
+            }
+            None => {
+                info!(
+                    "id: {}, deferring jitter buffer min delay: {:?}, no pc_interface",
+                    self.id(),
+                    delay
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Replace the ICE servers of the PeerConnection.
+    ///
+    /// The servers are used for candidates gathered from now on, and
+    /// by any PeerConnection rebuilt later.  With `ice_restart`, the
+    /// caller also restarts ICE so the current connection moves to the
+    /// new servers.  The callee instead waits for the caller to
+    /// restart.
+    pub fn set_ice_servers(
+        &mut self,
+        ice_servers: Vec<IceServerConfig>,
+        ice_restart: bool,
+    ) -> Result<()> {
+        self.apply_ice_servers(&ice_servers)?;
+        *self.ice_servers.lock()? = Some(ice_servers);
+
+        if ice_restart {
+            self.inject_restart_ice()?;
+        }
+        Ok(())
+    }
+
+    /// Record the ICE servers the PeerConnection was created with,
+    /// which the platform already applied.
+    pub fn init_ice_servers(&self, ice_servers: Vec<IceServerConfig>) -> Result<()> {
+        *self.ice_servers.lock()? = Some(ice_servers);
+        Ok(())
+    }
+
+    /// Return the ICE servers set by the application, if any.
+    pub fn ice_servers(&self) -> Result<Option<Vec<IceServerConfig>>> {
+        Ok(self.ice_servers.lock()?.clone())
+    }
+
+    /// Set which ICE candidates are exchanged with the remote peer.
+    ///
+    /// Applies to candidates gathered or received from now on.
+    pub fn set_ice_candidate_policy(&self, policy: IceCandidatePolicy) -> Result<()> {
+        info!("id: {}, ice candidate policy: {}", self.id(), policy);
+        *self.ice_candidate_policy.lock()? = policy;
+        Ok(())
+    }
+
+    /// Return which ICE candidates are exchanged with the remote peer.
+    pub fn ice_candidate_policy(&self) -> Result<IceCandidatePolicy> {
+        Ok(*self.ice_candidate_policy.lock()?)
+    }
+
+    /// Apply the ICE servers to the PeerConnection.
+    fn apply_ice_servers(&self, ice_servers: &[IceServerConfig]) -> Result<()> {
+        info!("id: {}, ice servers: {}", self.id(), ice_servers.len());
+
+        let webrtc = self.webrtc.lock()?;
+        webrtc.pc_interface()?.set_ice_servers(ice_servers)
+    }
+
+    /// Update the webrtc::DataChannel interface.
+    ///
+    /// Control messages queued for the previous DataChannel are sent
+    /// on the new one.
+    pub fn set_data_channel(&self, data_channel: DataChannel) -> Result<()> {
+        data_channel.set_high_water_mark(*self.data_channel_high_water_mark.lock()?);
+
+        {
+            let mut webrtc = self.webrtc.lock()?;
+            webrtc.data_channel = Some(data_channel);
+        }
+        self.send_queued_control_messages()
+    }
+
+    /// Set the number of bytes the DataChannel may buffer for sending.
+    ///
+    /// Application messages that would buffer past the mark fail with
+    /// `RingRtcError::DataChannelWouldBlock`, while control messages
+    /// are queued.  Once the buffer drains to half the mark the
+    /// observer is notified, so the sender can resume.
+    pub fn set_data_channel_high_water_mark(&self, mark: u64) -> Result<()> {
+        info!("id: {}, data channel high water mark: {}", self.id(), mark);
+        *self.data_channel_high_water_mark.lock()? = mark;
+
+        let webrtc = self.webrtc.lock()?;
+        if let Some(data_channel) = webrtc.data_channel.as_ref() {
+            data_channel.set_high_water_mark(mark);
+        }
+        Ok(())
+    }
+
+    /// Return the number of bytes the DataChannel has buffered for
+    /// sending.
+    pub fn data_channel_buffered_amount(&self) -> Result<u64> {
+        let webrtc = self.webrtc.lock()?;
+        Ok(webrtc.data_channel()?.buffered_amount())
+    }
+
+    /// Collect the stats of the PeerConnection, covering the interval
+    /// since the previous call.
+    pub fn call_stats(&self) -> Result<CallStats> {
+        let stats_observer = create_stats_observer();
+
+        let report = {
+            let webrtc = self.webrtc.lock()?;
+            webrtc.pc_interface()?.get_stats(stats_observer.as_ref());
+            stats_observer.get_result()?
+        };
+        debug!("id: {}, stats report: {}", self.id(), report);
+
+        let mut last_report = self.last_stats_report.lock()?;
+        let stats = CallStats::new(last_report.as_ref(), &report);
+        *last_report = Some(report);
+        Ok(stats)
+    }
+
+    /// Export the SRTP keys of the PeerConnection.
+    #[cfg(feature = "srtp_key_export")]
+    pub fn export_srtp_keys(&self) -> Result<SrtpKeys> {
+        let webrtc = self.webrtc.lock()?;
+        webrtc.pc_interface()?.export_srtp_keys()
+    }
+
+    /// Sample the levels of the captured and received audio, from 0.0
+    /// to 1.0.
+    ///
+    /// The levels last reported by the audio level RTP header
+    /// extension are used if it was negotiated, otherwise the stats
+    /// of the PeerConnection are polled.  Unlike `call_stats()`, this
+    /// leaves the last stats report alone.
+    pub fn audio_levels(&self) -> Result<(f64, f64)> {
+        if let Some(levels) = self.rtp_audio_levels.lock()?.levels() {
+            return Ok(levels);
+        }
+
+        let stats_observer = create_stats_observer();
+
+        let report = {
+            let webrtc = self.webrtc.lock()?;
+            webrtc.pc_interface()?.get_stats(stats_observer.as_ref());
+            stats_observer.get_result()?
+        };
+        Ok((report.captured_audio_level(), report.received_audio_level()))
+    }
+
+    /// Sample the bandwidth available for sending and for receiving,
+    /// as estimated by congestion control, in bits per second.
+    ///
+    /// Like `audio_levels()`, this leaves the last stats report alone.
+    pub fn bandwidth_estimate(&self) -> Result<(u64, u64)> {
+        let stats_observer = create_stats_observer();
+
+        let report = {
+            let webrtc = self.webrtc.lock()?;
+            webrtc.pc_interface()?.get_stats(stats_observer.as_ref());
+            stats_observer.get_result()?
+        };
+        Ok((
+            report.available_outgoing_bitrate().max(0.0) as u64,
+            report.available_incoming_bitrate().max(0.0) as u64,
+        ))
+    }
+
+    /// Sample the round trip time of the nominated ICE candidate
+    /// pair.
+    pub fn round_trip_time(&self) -> Result<Duration> {
+        let stats_observer = create_stats_observer();
+
+        let report = {
+            let webrtc = self.webrtc.lock()?;
+            webrtc.pc_interface()?.get_stats(stats_observer.as_ref());
+            stats_observer.get_result()?
+        };
+        Ok(Duration::from_secs_f64(
+            report.current_round_trip_time().max(0.0),
+        ))
+    }
+
+    /// Return the last stats report collected by `call_stats()`, if
+    /// any.
+    pub fn last_stats_report(&self) -> Result<Option<StatsReport>> {
+        Ok(self.last_stats_report.lock()?.clone())
+    }
+
+    /// Send the remote peer a control message via the PeerConnection
+    /// DataChannel.
+    ///
+    /// While the DataChannel is backed up the message is queued, after
+    /// those queued before it, and sent once the DataChannel drains.
+    /// A queued status the message makes moot is dropped.
+    fn send_control_message(&self, message: ControlMessage) -> Result<()> {
+        let webrtc = self.webrtc.lock()?;
+        let data_channel = webrtc.data_channel()?;
+        let mut queue = self.control_queue.lock()?;
+
+        if queue.is_empty() {
+            match message.send(data_channel, self.call_id) {
+                Err(e) if would_block(&e) => {}
+                result => return result,
+            }
+        }
+
+        queue.retain(|queued| !message.supersedes(queued));
+        if queue.len() >= MAX_QUEUED_CONTROL_MESSAGES {
+            return Err(RingRtcError::ControlQueueFull(queue.len()).into());
+        }
+        info!("id: {}, queueing control message: {:?}", self.id(), message);
+        queue.push_back(message);
+        Ok(())
+    }
+
+    /// Send the queued control messages, in order, until the
+    /// DataChannel backs up again.
+    pub fn send_queued_control_messages(&self) -> Result<()> {
+        let webrtc = self.webrtc.lock()?;
+        let data_channel = match webrtc.data_channel.as_ref() {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let mut queue = self.control_queue.lock()?;
+        while let Some(message) = queue.front() {
+            match message.send(data_channel, self.call_id) {
+                Ok(()) => {
+                    let _ = queue.pop_front();
+                }
+                Err(e) if would_block(&e) => break,
+                Err(e) => {
+                    let _ = queue.pop_front();
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the number of control messages waiting for the
+    /// DataChannel to drain.
+    pub fn queued_control_messages(&self) -> Result<usize> {
+        Ok(self.control_queue.lock()?.len())
+    }
+
+    /// Returns `true`, once, when a refused DataChannel send has since
+    /// drained.
+    pub fn data_channel_buffered_amount_low(&self) -> Result<bool> {
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.data_channel.as_ref() {
+            Some(data_channel) => Ok(data_channel.buffered_amount_low()),
+            None => Ok(false),
+        }
+    }
+
+    /// Update the webrtc::DataChannelObserver interface.
+    pub fn set_data_channel_observer(&self, observer: DataChannelObserver<T>) -> Result<()> {
+        let mut webrtc = self.webrtc.lock()?;
+        webrtc.data_channel_observer = Some(observer);
+        Ok(())
+    }
+
+    /// Update the media stream.
+    pub fn set_app_media_stream(
+        &self,
+        app_media_stream: <T as Platform>::AppMediaStream,
+    ) -> Result<()> {
+        // In the current application we only expect one media stream
+        // per connection.
+        let mut webrtc = self.webrtc.lock()?;
+        match webrtc.app_media_stream {
+            Some(_) => Err(RingRtcError::ActiveMediaStreamAlreadySet(self.remote_device).into()),
+            None => {
+                webrtc.app_media_stream = Some(app_media_stream);
+                Ok(())
+            }
+        }
+    }
+
+    /// Set the application peer connection.
+    pub fn set_app_connection(&self, app_connection: <T as Platform>::AppConnection) -> Result<()> {
+        let mut webrtc = self.webrtc.lock()?;
+        match webrtc.app_connection {
+            Some(_) => Err(RingRtcError::AppConnectionAlreadySet(self.remote_device).into()),
+            None => {
+                webrtc.app_connection = Some(app_connection);
+                Ok(())
+            }
+        }
+    }
+
+    /// Return a clone of the application peer connection.
+    pub fn app_connection(&self) -> Result<<T as Platform>::AppConnection> {
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.app_connection.as_ref() {
+            Some(v) => Ok(v.clone()),
+            None => Err(RingRtcError::OptionValueNotSet(
+                String::from("app_connection()"),
+                String::from("app_connection"),
+            )
+            .into()),
+        }
+    }
+
+    /// Returns `true` if the call is terminating.
+    pub fn terminating(&self) -> Result<bool> {
+        if let ConnectionState::Terminating = self.state()? {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Clone the Connection, Box it and return a raw pointer to the Box.
+    pub fn create_connection_ptr(&self) -> *mut Connection<T> {
+        let connection_box = Box::new(self.clone());
+        Box::into_raw(connection_box)
+    }
+
+    /// Return the internally tracked connection object pointer, for
+    /// use by the PeerConnectionObserver call backs.
+    pub fn get_connection_ptr(&self) -> Result<*mut Connection<T>> {
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.connection_ptr.as_ref() {
+            Some(v) => Ok(*v),
+            None => Err(RingRtcError::OptionValueNotSet(
+                String::from("connection_ptr()"),
+                String::from("connection_ptr"),
+            )
+            .into()),
+        }
+    }
+
+    /// Create a connection object on the heap, for use by the
+    /// PeerConnectionObserver call backs.  Track it, as it needs to
+    /// be freed after closing down the PeerConnection.
+    fn init_connection_ptr(&self) -> Result<()> {
+        let mut webrtc = self.webrtc.lock()?;
+        webrtc.connection_ptr = Some(self.create_connection_ptr());
+        Ok(())
+    }
+
+    /// Return the strong reference count on the webrtc `Arc<Mutex<>>`.
+    fn ref_count(&self) -> usize {
+        Arc::strong_count(&self.webrtc)
+    }
+
+    /// Create an SDP offer message.
+    fn create_offer(&self) -> Result<SessionDescriptionInterface> {
+        let csd_observer = create_csd_observer();
+
+        let webrtc = self.webrtc.lock()?;
+        webrtc.pc_interface()?.create_offer(csd_observer.as_ref());
+        csd_observer.get_result()
+    }
+
+    /// Create an SDP answer message.
+    fn create_answer(&self) -> Result<SessionDescriptionInterface> {
+        let csd_observer = create_csd_observer();
+
+        let webrtc = self.webrtc.lock()?;
+        webrtc.pc_interface()?.create_answer(csd_observer.as_ref());
+        csd_observer.get_result()
+    }
+
+    /// Set the local SPD decription in the PeerConnection interface.
+    fn set_local_description(&self, desc: &SessionDescriptionInterface) -> Result<()> {
+        let ssd_observer = create_ssd_observer();
+
+        let webrtc = self.webrtc.lock()?;
+        webrtc
+            .pc_interface()?
+            .set_local_description(ssd_observer.as_ref(), desc);
+        ssd_observer.get_result()
+    }
+
+    /// Set the remote SPD decription in the PeerConnection interface.
+    fn set_remote_description(&self, desc: &SessionDescriptionInterface) -> Result<()> {
+        let ssd_observer = create_ssd_observer();
+
+        let webrtc = self.webrtc.lock()?;
+        webrtc
+            .pc_interface()?
+            .set_remote_description(ssd_observer.as_ref(), desc);
+        ssd_observer.get_result()
+    }
+
+    /// Send an SDP offer message to the remote peer via the signaling
+    /// channel.
+    pub fn send_offer(&self) -> Result<()> {
+        let offer = self.create_local_offer()?;
+        self.set_local_description(&offer)?;
+        let description = offer.get_description()?;
+        self.fingerprints.lock()?.set_local(&description);
+
+        info!(
+            "id: {}, TX SDP offer:\n{}",
+            self.id(),
+            redact_string(&description)
+        );
+
+        let call = self.call()?;
+        call.send_offer(self.clone(), offer)
+    }
+
+    /// Create an offer of the local tracks, with the call codecs
+    /// preferred, the bandwidth limited and the application
+    /// transforms applied.
+    fn create_local_offer(&self) -> Result<SessionDescriptionInterface> {
+        let offer = self.create_offer()?;
+        let offer =
+            self.prefer_call_codecs(offer, SessionDescriptionInterface::create_sdp_offer)?;
+        let offer = self.offer_audio_level(offer)?;
+        let offer = self.limit_bandwidth(offer, SessionDescriptionInterface::create_sdp_offer)?;
+        self.transform_local_description(offer, SessionDescriptionInterface::create_sdp_offer)
+    }
+
+    /// Offer the remote peer the changed local tracks, via the
+    /// DataChannel.
+    pub fn send_negotiation_offer(&self) -> Result<()> {
+        let offer = self.create_local_offer()?;
+        self.set_local_description(&offer)?;
+        let description = offer.get_description()?;
+
+        info!(
+            "id: {}, TX negotiation offer:\n{}",
+            self.id(),
+            redact_string(&description)
+        );
+
+        self.send_control_message(ControlMessage::Negotiation(NegotiationMessage::Offer(
+            description,
+        )))
+    }
+
+    /// Answer a negotiation offer received from the remote peer via
+    /// the DataChannel, first rolling back the local offer if the two
+    /// collided.
+    pub fn handle_negotiation_offer(&self, offer: String, rollback: bool) -> Result<()> {
+        if rollback {
+            self.rollback_local_description()?;
+        }
+
+        let offer = self.transform_remote_description(offer)?;
+        let desc = SessionDescriptionInterface::create_sdp_offer(offer)?;
+        self.set_remote_description(&desc)?;
+
+        let answer = self.create_answer()?;
+        let answer =
+            self.prefer_call_codecs(answer, SessionDescriptionInterface::create_sdp_answer)?;
+        let answer =
+            self.limit_bandwidth(answer, SessionDescriptionInterface::create_sdp_answer)?;
+        let answer = self
+            .transform_local_description(answer, SessionDescriptionInterface::create_sdp_answer)?;
+        self.set_local_description(&answer)?;
+        let description = answer.get_description()?;
+
+        info!(
+            "id: {}, TX negotiation answer:\n{}",
+            self.id(),
+            redact_string(&description)
+        );
+
+        self.send_control_message(ControlMessage::Negotiation(NegotiationMessage::Answer(
+            description,
+        )))?;
+        self.negotiation_completed()
+    }
+
+    /// Apply the answer of the remote peer to the local negotiation
+    /// offer.
+    pub fn handle_negotiation_answer(&self, answer: String) -> Result<()> {
+        let answer = self.transform_remote_description(answer)?;
+        let desc = SessionDescriptionInterface::create_sdp_answer(answer)?;
+        self.set_remote_description(&desc)?;
+        self.negotiation_completed()
+    }
+
+    /// Offer the local tracks changed before the call connected, if
+    /// any.
+    pub fn send_deferred_negotiation_offer(&self) -> Result<()> {
+        let deferred = {
+            let mut negotiation = self.negotiation()?;
+            negotiation.deferred() && negotiation.negotiation_needed()
+        };
+        if deferred {
+            self.send_negotiation_offer()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Record a completed negotiation, then offer the local tracks
+    /// changed meanwhile, if any.
+    fn negotiation_completed(&self) -> Result<()> {
+        let again = self.negotiation()?.completed();
+        info!("id: {}, negotiation completed", self.id());
+        if again {
+            self.send_negotiation_offer()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check to see if this Connection is able to send messages.
+    /// Once it is terminated it shouldn't be able to.
+    pub fn can_send_messages(&self) -> bool {
+        let state_result = self.state();
+
+        match state_result {
+            Ok(state) => match state {
+                ConnectionState::Terminating | ConnectionState::Closed => false,
+                _ => true,
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Handle an incoming SDP answer message.
+    pub fn handle_answer(&mut self, answer: String) -> Result<()> {
+        let answer = Answer::from_wire(&answer);
+        self.set_protocol_version(negotiate(answer.version))?;
+        if let Some(metadata) = &answer.metadata {
+            let call = self.call()?;
+            call.received_answer_metadata(self.id(), metadata)?;
+        }
+        let answer = self.transform_remote_description(answer.sdp)?;
+
+        self.remote_video_orientation.lock()?.negotiated = video_orientation_negotiated(&answer);
+        self.rtp_audio_levels
+            .lock()?
+            .set_negotiated(audio_level_negotiated(&answer));
+        let capabilities = RemoteCapabilities::from_sdp(&answer);
+        self.fingerprints.lock()?.set_remote(&answer);
+        let desc = SessionDescriptionInterface::create_sdp_answer(answer)?;
+        self.set_remote_description(&desc)?;
+        self.set_remote_capabilities(capabilities)?;
+        self.inject_have_local_remote_sdp()
+    }
+
+    /// Handle an incoming SDP offer message.
+    ///
+    /// The codec preferences are advisory: a preferred codec present
+    /// in the offer is moved to the front of the answer, otherwise the
+    /// answer is left to negotiate any common codec and the observer
+    /// is notified of the fallback.  Without any preferences, the
+    /// hardware accelerated codecs are moved to the front instead.
+    pub fn handle_offer(&mut self, offer: String) -> Result<()> {
+        let offer = Offer::from_wire(&offer);
+        self.set_protocol_version(negotiate(offer.version))?;
+        let offer = self.transform_remote_description(offer.sdp)?;
+
+        let offered = offered_codecs(&offer);
+        let kinds = offered_kinds(&offer);
+        self.remote_video_orientation.lock()?.negotiated = video_orientation_negotiated(&offer);
+        self.rtp_audio_levels
+            .lock()?
+            .set_negotiated(audio_level_negotiated(&offer));
+        let offered_capabilities = RemoteCapabilities::from_sdp(&offer);
+        self.fingerprints.lock()?.set_remote(&offer);
+        let desc = SessionDescriptionInterface::create_sdp_offer(offer)?;
+        self.set_remote_description(&desc)?;
+
+        let (preferences, supported) = {
+            let call = self.call()?;
+            let call_manager = call.call_manager()?;
+            (
+                call_manager.codec_preferences()?,
+                call_manager.supported_codecs()?,
+            )
+        };
+
+        let mut answer = self.create_answer()?;
+        match select_codec(&preferences, &offered, &kinds) {
+            CodecSelection::Preferred(codec) => {
+                info!("id: {}, preferring codec: {}", self.id(), codec);
+                let description = prefer_codec(&answer.get_description()?, &codec);
+                answer = SessionDescriptionInterface::create_sdp_answer(description)?;
+            }
+            CodecSelection::Fallback => {
+                warn!(
+                    "id: {}, no preferred codec offered, falling back to one of: {:?}",
+                    self.id(),
+                    offered
+                );
+                self.notify_observer(ObserverEvent::CodecFallback)?;
+            }
+            CodecSelection::Unconstrained => {
+                let accelerated = hardware_accelerated_codecs(&supported);
+                if !accelerated.is_empty() {
+                    info!(
+                        "id: {}, preferring hardware codecs: {:?}",
+                        self.id(),
+                        accelerated
+                    );
+                    let description = prefer_codecs(&answer.get_description()?, &accelerated);
+                    answer = SessionDescriptionInterface::create_sdp_answer(description)?;
+                }
+            }
+        }
+        let answer =
+            self.prefer_call_codecs(answer, SessionDescriptionInterface::create_sdp_answer)?;
+        let answer =
+            self.limit_bandwidth(answer, SessionDescriptionInterface::create_sdp_answer)?;
+        let answer = self
+            .transform_local_description(answer, SessionDescriptionInterface::create_sdp_answer)?;
+        self.set_local_description(&answer)?;
+
+        let description = answer.get_description()?;
+        self.fingerprints.lock()?.set_local(&description);
+        let capabilities =
+            offered_capabilities.intersect(&RemoteCapabilities::from_sdp(&description));
+        self.set_remote_capabilities(capabilities)?;
+        self.inject_have_local_remote_sdp()?;
+
+        info!(
+            "id: {}, TX SDP answer:\n{}",
+            self.id(),
+            redact_string(&description)
+        );
+
+        let call = self.call()?;
+        call.send_answer(self.clone(), answer)
+    }
+
+    /// Record the capabilities of the remote peer, negotiated by the
+    /// latest offer/answer exchange.
+    fn set_remote_capabilities(&self, capabilities: RemoteCapabilities) -> Result<()> {
+        info!("id: {}, remote capabilities: {}", self.id(), capabilities);
+        *self.remote_capabilities.lock()? = Some(capabilities);
+        Ok(())
+    }
+
+    /// Return the capabilities of the remote peer.
+    ///
+    /// Returns `None` until the offer/answer exchange completes, and
+    /// is updated by every later renegotiation.
+    pub fn remote_capabilities(&self) -> Result<Option<RemoteCapabilities>> {
+        Ok(self.remote_capabilities.lock()?.clone())
+    }
+
+    /// Return the safety numbers of the connection, which the users
+    /// of both sides read aloud to verify that no one substituted
+    /// the DTLS fingerprints exchanged in the offer and answer.
+    ///
+    /// Returns `None` until the offer/answer exchange completes, or
+    /// if either description lacks a fingerprint, and is updated by
+    /// every later renegotiation.
+    pub fn get_safety_numbers(&self) -> Result<Option<String>> {
+        Ok(self.fingerprints.lock()?.safety_numbers())
+    }
+
+    /// Record the signaling protocol version agreed with the remote
+    /// peer by the latest offer/answer exchange.
+    fn set_protocol_version(&self, version: u32) -> Result<()> {
+        info!("id: {}, protocol version: {}", self.id(), version);
+        *self.protocol_version.lock()? = version;
+        Ok(())
+    }
+
+    /// Return the signaling protocol version agreed with the remote
+    /// peer.
+    ///
+    /// The version is legacy until the remote peer sends an offer or
+    /// answer, and is updated by every later renegotiation.
+    pub fn protocol_version(&self) -> Result<u32> {
+        Ok(*self.protocol_version.lock()?)
+    }
+
+    /// Return the sequence number of the next opaque signaling
+    /// message sent on the connection.
+    pub fn next_signaling_sequence(&self) -> Result<u64> {
+        let mut sequence = self.signaling_sequence.lock()?;
+        *sequence += 1;
+        Ok(*sequence)
+    }
+
+    /// Buffer local ICE candidates.
+    ///
+    /// Candidates the ICE candidate policy does not allow are dropped.
+    /// Candidates are sent after the ICE candidate batch period, see
+    /// `CallManager::set_ice_candidate_batch_period()`.
+    pub fn buffer_local_ice_candidate(&self, candidate: IceCandidate) -> Result<()> {
+        info!("Local ICE candidate: {}", candidate);
+
+        if !self.ice_candidate_policy.lock()?.allows(&candidate) {
+            info!(
+                "id: {}, dropping local ICE candidate disallowed by policy",
+                self.connection_id
+            );
+            return Ok(());
+        }
+
+        let num_ice_candidates = {
+            let mut ice_candidates = self.pending_outbound_ice_candidates.lock()?;
+            ice_candidates.push(candidate);
+            ice_candidates.len()
+        };
+
+        // Only when we transition from no candidates to one do we
+        // need to signal the message queue that there is something
+        // to send for this Connection.
+        if num_ice_candidates == 1 {
+            let call = self.call()?;
+            let period = call.call_manager()?.ice_candidate_batch_period()?;
+            if period == Duration::from_secs(0) {
+                call.send_ice_candidates(self.clone())?
+            } else {
+                self.batch_local_ice_candidates(call.clone(), period)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send the buffered local ICE candidates after `period`, along
+    /// with any gathered in the meantime.
+    fn batch_local_ice_candidates(&self, call: Call<T>, period: Duration) -> Result<()> {
+        info!(
+            "batch_local_ice_candidates(): id: {}, period: {:?}",
+            self.connection_id, period
+        );
+
+        let connection = self.clone();
+        let batch_future = Delay::new(Instant::now() + period)
+            .map_err(|e| error!("ICE candidate batch Delay failed: {:?}", e))
+            .and_then(move |_| {
+                call.send_ice_candidates(connection)
+                    .map_err(|e| error!("Send batched ICE candidates failed: {:?}", e))
+            });
+
+        let mut context = self.context.lock()?;
+        if let Some(timeout_runtime) = &mut context.timeout_runtime {
+            timeout_runtime.spawn(batch_future);
+        }
+        Ok(())
+    }
+
+    /// Buffer remote ICE candidates.
+    ///
+    /// Candidates beyond the rate limit, or that the ICE candidate
+    /// policy does not allow, are dropped.
+    pub fn buffer_remote_ice_candidates(&self, ice_candidates: Vec<IceCandidate>) -> Result<()> {
+        let now = Instant::now();
+        let policy = *self.ice_candidate_policy.lock()?;
+        let mut limiter = self.received_ice_limiter.lock()?;
+        let mut pending_ice_candidates = self.pending_inbound_ice_candidates.lock()?;
+        for ice_candidate in ice_candidates {
+            if !policy.allows(&ice_candidate) {
+                info!(
+                    "id: {}, dropping remote ICE candidate disallowed by policy: {}",
+                    self.connection_id, ice_candidate
+                );
+            } else if limiter.admit(now) {
+                info!("Remote ICE candidates: {}", ice_candidate);
+                pending_ice_candidates.push(ice_candidate);
+            } else {
+                warn!(
+                    "id: {}, dropping remote ICE candidate over the rate limit: {}",
+                    self.connection_id, ice_candidate
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the number of remote ICE candidates dropped for
+    /// exceeding the rate limit.
+    pub fn dropped_remote_ice_candidates(&self) -> Result<usize> {
+        Ok(self.received_ice_limiter.lock()?.dropped())
+    }
+
+    /// Get the current local ICE candidates to send to the remote peer.
+    pub fn get_pending_ice_updates(&self) -> Result<Vec<IceCandidate>> {
+        info!("get_pending_ice_updates():");
+
+        let mut ice_candidates = self.pending_outbound_ice_candidates.lock()?;
+
+        let copy_candidates = ice_candidates.clone();
+        ice_candidates.clear();
+
+        info!(
+            "get_pending_ice_updates(): Local ICE candidates length: {}",
+            copy_candidates.len()
+        );
+
+        Ok(copy_candidates)
+    }
+
+    /// Return the local ICE candidates waiting to be sent, leaving
+    /// them pending.
+    pub fn peek_pending_ice_updates(&self) -> Result<Vec<IceCandidate>> {
+        Ok(self.pending_outbound_ice_candidates.lock()?.clone())
+    }
+
+    /// Add any remote ICE candidates to the PeerConnection interface.
+    pub fn handle_remote_ice_updates(&self) -> Result<()> {
+        let mut ice_candidates = self.pending_inbound_ice_candidates.lock()?;
+
+        if ice_candidates.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "handle_remote_ice_updates(): Remote ICE candidates length: {}",
+            ice_candidates.len()
+        );
+        let webrtc = self.webrtc.lock()?;
+        for candidate in &(*ice_candidates) {
+            webrtc.pc_interface()?.add_ice_candidate(candidate)?;
+        }
         ice_candidates.clear();
 
         Ok(())
@@ -653,44 +2740,155 @@ where
 
     /// Send a hangup message to the remote peer via the
     /// PeerConnection DataChannel.
-    pub fn send_hangup(&self) -> Result<()> {
-        info!("send_hangup(): id: {}", self.connection_id);
+    pub fn send_hangup(&self, hangup_type: HangupType) -> Result<()> {
+        info!(
+            "send_hangup(): id: {}, type: {}",
+            self.connection_id, hangup_type
+        );
+        if let Err(e) = self.send_control_message(ControlMessage::Hangup(hangup_type)) {
+            info!("send_hangup(): id: {}, skipping: {}", self.connection_id, e);
+        }
+        Ok(())
+    }
+
+    /// Send a call connected message to the remote peer via the
+    /// PeerConnection DataChannel.
+    pub fn send_connected(&self) -> Result<()> {
+        self.send_control_message(ControlMessage::Connected)
+    }
+
+    /// Send the remote peer the current video status via the
+    /// PeerConnection DataChannel.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `true` when the local side is streaming video,
+    /// otherwise `false`.
+    pub fn send_video_status(&self, enabled: bool) -> Result<()> {
+        self.set_local_video_status(enabled)?;
+
+        self.send_control_message(ControlMessage::VideoStatus(enabled))
+    }
+
+    /// Record the local video status, enabling the video track while
+    /// enabled and not on hold, without telling the remote peer.
+    pub fn set_local_video_status(&self, enabled: bool) -> Result<()> {
+        *self.local_video_status.lock()? = Some(enabled);
+        self.apply_outgoing_video()
+    }
+
+    /// Return the local video status, if one was set.
+    pub fn local_video_status(&self) -> Result<Option<bool>> {
+        Ok(*self.local_video_status.lock()?)
+    }
+
+    /// Enable the video track while the local video is enabled and
+    /// not on hold, otherwise disable it.
+    ///
+    /// The track is left as created until a video status is set, as
+    /// applications may enable it themselves.
+    pub fn apply_outgoing_video(&self) -> Result<()> {
+        let enabled = match *self.local_video_status.lock()? {
+            Some(v) => v && !self.on_hold()?,
+            None => return Ok(()),
+        };
+
         let webrtc = self.webrtc.lock()?;
-        if let Ok(data_channel) = webrtc.data_channel() {
-            if let Err(e) = data_channel.send_hang_up(self.call_id) {
-                info!("data_channel.send_hang_up() failed: {}", e);
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, outgoing video enabled: {}", self.id(), enabled);
+                pc_interface.set_outgoing_video_enabled(enabled)
             }
-        } else {
-            info!(
-                "send_hangup(): id: {}, skipping, data_channel not present",
-                self.connection_id
-            );
+            None => Ok(()),
+        }
+    }
+
+    /// Start or stop sending the screen share track, and tell the
+    /// remote peer via the PeerConnection DataChannel.
+    ///
+    /// The screen share track is separate from the camera track, so
+    /// both can stream at once.  While the call is on hold the track
+    /// stays paused, the status applies when the call is resumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `sharing` - `true` when the local side is sharing its screen,
+    ///   otherwise `false`.
+    pub fn send_screen_share_status(&self, sharing: bool) -> Result<()> {
+        *self.local_screen_share_status.lock()? = Some(sharing);
+        self.apply_screen_share()?;
+
+        self.send_control_message(ControlMessage::ScreenShareStatus(sharing))
+    }
+
+    /// Returns `true` if the local side is sharing its screen.
+    pub fn screen_sharing(&self) -> Result<bool> {
+        Ok(self.local_screen_share_status.lock()?.unwrap_or(false))
+    }
+
+    /// Enable the screen share track while sharing and not on hold,
+    /// otherwise disable it.
+    pub fn apply_screen_share(&self) -> Result<()> {
+        let enabled = !self.on_hold()? && self.screen_sharing()?;
+
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                info!("id: {}, screen share enabled: {}", self.id(), enabled);
+                pc_interface.set_outgoing_screen_share_enabled(enabled)
+            }
+            None => Ok(()),
         }
-        Ok(())
     }
 
-    /// Send a call connected message to the remote peer via the
+    /// Send the remote peer the current audio status via the
     /// PeerConnection DataChannel.
-    pub fn send_connected(&self) -> Result<()> {
-        let webrtc = self.webrtc.lock()?;
-
-        webrtc.data_channel()?.send_connected(self.call_id)?;
-        Ok(())
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `true` when the local audio is unmuted,
+    ///   otherwise `false`.
+    pub fn send_audio_status(&self, enabled: bool) -> Result<()> {
+        self.send_control_message(ControlMessage::AudioStatus(enabled))
     }
 
-    /// Send the remote peer the current video status via the
+    /// Send the remote peer the current hold status via the
     /// PeerConnection DataChannel.
     ///
     /// # Arguments
     ///
-    /// * `enabled` - `true` when the local side is streaming video,
-    /// otherwise `false`.
-    pub fn send_video_status(&self, enabled: bool) -> Result<()> {
+    /// * `on_hold` - `true` when the local side put the call on hold,
+    ///   otherwise `false`.
+    pub fn send_hold(&self, on_hold: bool) -> Result<()> {
+        self.send_control_message(ControlMessage::Hold(on_hold))
+    }
+
+    /// Send the remote peer a step of a call transfer via the
+    /// PeerConnection DataChannel.
+    pub fn send_transfer(&self, message: &TransferMessage) -> Result<()> {
+        self.send_control_message(ControlMessage::Transfer(message.clone()))
+    }
+
+    /// Send the remote peer an application message, e.g. a reaction
+    /// or a caption, via the PeerConnection DataChannel.
+    ///
+    /// The remote peer receives the payload with
+    /// `Platform::on_app_message_received()`.  Fails with
+    /// `RingRtcError::DataChannelWouldBlock` while the DataChannel
+    /// is backed up.
+    pub fn send_app_message(&self, payload: &[u8]) -> Result<()> {
         let webrtc = self.webrtc.lock()?;
 
         webrtc
             .data_channel()?
-            .send_video_status(self.call_id, enabled)
+            .send_app_message(self.call_id, payload)
+    }
+
+    /// Hand an application message received from the remote peer to
+    /// the application.
+    pub fn app_message_received(&self, payload: &[u8]) -> Result<()> {
+        let call = self.call()?;
+        call.app_message_received(self.remote_device, payload)
     }
 
     /// A notification of an available DataChannel.
@@ -710,46 +2908,695 @@ where
         Ok(())
     }
 
-    /// Notify the parent call observer about an event.
-    pub fn notify_observer(&self, event: ObserverEvent) -> Result<()> {
-        let mut call = self.call.lock()?;
-        call.on_connection_event(self.connection_id, event)
-    }
+    /// Notify the parent call observer about an event.
+    pub fn notify_observer(&self, event: ObserverEvent) -> Result<()> {
+        let mut call = self.call.lock()?;
+        call.on_connection_event(self.connection_id, event)
+    }
+
+    /// Notify the parent call observer about an internal error.
+    pub fn internal_error(&self, error: failure::Error) -> Result<()> {
+        let mut call = self.call.lock()?;
+        call.on_connection_error(self.connection_id, error)
+    }
+
+    /// Set the sink receiving the decoded frames of the incoming
+    /// video, e.g. to record them, or `None` to stop handing them
+    /// over.
+    ///
+    /// The sink is kept across rebuilds of the PeerConnection, and
+    /// receives frames in addition to the MediaStream of the
+    /// application.
+    pub fn set_incoming_video_sink(&self, sink: Option<Box<dyn VideoSink>>) -> Result<()> {
+        info!(
+            "id: {}, set_incoming_video_sink(): {}",
+            self.id(),
+            sink.is_some()
+        );
+
+        *self.incoming_video_sink.lock()? = sink;
+        Ok(())
+    }
+
+    /// Hand a decoded frame of the incoming video to the sink, if
+    /// any.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` back thread.
+    pub fn on_incoming_video_frame(&self, frame: VideoFrame) -> Result<()> {
+        let recorded = match &mut *self.recorder.lock()? {
+            Some(recorder) => recorder.on_video_frame(&frame),
+            None => Ok(()),
+        };
+        if let Err(e) = recorded {
+            self.abort_recording(e)?;
+        }
+        if let Some(sink) = &*self.incoming_video_sink.lock()? {
+            sink.on_video_frame(frame);
+        }
+        Ok(())
+    }
+
+    /// Set the tap receiving the PCM frames of the captured and
+    /// received audio, or remove it if `None`.
+    ///
+    /// The tap is kept across rebuilds of the PeerConnection.
+    pub fn set_audio_tap(&self, tap: Option<Box<dyn AudioTap>>) -> Result<()> {
+        info!("id: {}, set_audio_tap(): {}", self.id(), tap.is_some());
+
+        *self.audio_tap.lock()? = tap;
+        Ok(())
+    }
+
+    /// Hand a frame of the captured or received audio to the tap, if
+    /// any.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` back thread.
+    pub fn on_audio_frame(&self, frame: AudioFrame) -> Result<()> {
+        let recorded = match &mut *self.recorder.lock()? {
+            Some(recorder) => recorder.on_audio_frame(&frame),
+            None => Ok(()),
+        };
+        if let Err(e) = recorded {
+            self.abort_recording(e)?;
+        }
+        if let Some(tap) = &*self.audio_tap.lock()? {
+            tap.on_audio_frame(frame);
+        }
+        Ok(())
+    }
+
+    /// Start recording the captured and received audio, and the
+    /// incoming video if configured, to `sink`, and let the remote
+    /// peer know via the DataChannel.
+    ///
+    /// The recording is kept across rebuilds of the PeerConnection,
+    /// and finished when the connection closes.
+    pub fn start_recording(
+        &self,
+        sink: Box<dyn RecordingSink>,
+        config: RecordingConfig,
+    ) -> Result<()> {
+        info!("id: {}, start_recording(): {}", self.id(), config);
+
+        let mut recorder = self.recorder.lock()?;
+        if recorder.is_some() {
+            return Err(RingRtcError::RecordingInProgress(self.call_id).into());
+        }
+        *recorder = Some(Recorder::new(sink, config)?);
+        drop(recorder);
+
+        self.send_recording_status(true)
+    }
+
+    /// Finish the recording, and let the remote peer know via the
+    /// DataChannel.
+    pub fn stop_recording(&self) -> Result<()> {
+        info!("id: {}, stop_recording()", self.id());
+
+        let recorder = self.recorder.lock()?.take();
+        let mut recorder = match recorder {
+            Some(v) => v,
+            None => return Err(RingRtcError::RecordingNotInProgress(self.call_id).into()),
+        };
+        let finished = recorder.finish();
+
+        self.send_recording_status(false)?;
+        finished
+    }
+
+    /// Return whether the call is being recorded locally.
+    pub fn recording(&self) -> Result<bool> {
+        Ok(self.recorder.lock()?.is_some())
+    }
+
+    /// Stop a recording whose sink failed.
+    fn abort_recording(&self, error: failure::Error) -> Result<()> {
+        warn!("id: {}, recording failed: {}", self.id(), error);
+
+        if self.recorder.lock()?.take().is_some() {
+            self.send_recording_status(false)?;
+        }
+        Ok(())
+    }
+
+    /// Send the remote peer the current recording status via the
+    /// PeerConnection DataChannel.
+    ///
+    /// # Arguments
+    ///
+    /// * `recording` - `true` while the call is recorded locally,
+    ///   otherwise `false`.
+    pub fn send_recording_status(&self, recording: bool) -> Result<()> {
+        self.send_control_message(ControlMessage::RecordingStatus(recording))
+    }
+
+    /// Tell the remote peer the call is being recorded, once a new
+    /// DataChannel is available.
+    pub fn restore_recording_status(&self) -> Result<()> {
+        if !self.recording()? {
+            return Ok(());
+        }
+        if let Err(e) = self.send_recording_status(true) {
+            warn!(
+                "id: {}, unable to restore recording status: {}",
+                self.connection_id, e
+            );
+        }
+        Ok(())
+    }
+
+    /// Encrypt the media frames of the connection end-to-end with
+    /// `provider`, on top of SRTP, or stop if `None`.
+    ///
+    /// The provider is applied to the PeerConnection right away, or
+    /// once created, and kept across rebuilds of the PeerConnection.
+    pub fn set_media_crypto_provider(
+        &self,
+        provider: Option<Arc<dyn MediaCryptoProvider>>,
+    ) -> Result<()> {
+        info!(
+            "id: {}, set_media_crypto_provider(): {}",
+            self.id(),
+            provider.is_some()
+        );
+
+        *self.media_crypto_provider.lock()? = provider.clone();
+        self.apply_media_crypto_provider(provider)
+    }
+
+    /// Return true if the media frames are encrypted end-to-end.
+    pub fn frame_encryption(&self) -> Result<bool> {
+        Ok(self.media_crypto_provider.lock()?.is_some())
+    }
+
+    /// Attach a frame cryptor calling `provider` to the
+    /// PeerConnection, replacing the previous one.
+    fn apply_media_crypto_provider(
+        &self,
+        provider: Option<Arc<dyn MediaCryptoProvider>>,
+    ) -> Result<()> {
+        let mut webrtc = self.webrtc.lock()?;
+        let webrtc = &mut *webrtc;
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => {
+                let frame_cryptor = provider
+                    .map(|provider| Box::new(FrameCryptor::new(self.connection_id, provider)));
+                pc_interface.set_frame_cryptor(frame_cryptor.as_deref())?;
+
+                // The previous frame cryptor is detached, and may go.
+                webrtc.frame_cryptor = frame_cryptor;
+                Ok(())
+            }
+            None => {
+                info!(
+                    "id: {}, deferring media crypto provider, no pc_interface",
+                    self.id()
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Record an audio level, in -dBov, carried by the audio level
+    /// RTP header extension of a sent or received packet.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` back thread.
+    pub fn on_audio_level(&self, direction: AudioDirection, dbov: u8) -> Result<()> {
+        self.rtp_audio_levels.lock()?.update(direction, dbov);
+        Ok(())
+    }
+
+    /// Produce the outgoing video with `source`, in place of the
+    /// platform's capturer, or stop the current source if `None`.
+    ///
+    /// The source is polled on a thread of its own until it ends, is
+    /// replaced, or the connection closes.  Frames produced while
+    /// there is no PeerConnection are dropped.
+    pub fn set_outgoing_video_source(&self, source: Option<Box<dyn VideoSource>>) -> Result<()> {
+        info!(
+            "id: {}, set_outgoing_video_source(): {}",
+            self.id(),
+            source.is_some()
+        );
+
+        let mut current = self.outgoing_video_source.lock()?;
+        if let Some(token) = current.take() {
+            token.cancel();
+        }
+
+        if let Some(mut source) = source {
+            let token = CancellationToken::new();
+            *current = Some(token.clone());
+            let connection = self.clone();
+            thread::Builder::new()
+                .name("video_source".to_owned())
+                .spawn(move || {
+                    while let Some(frame) = source.next_frame() {
+                        if token.is_cancelled() {
+                            break;
+                        }
+                        connection
+                            .push_outgoing_video_frame(&frame)
+                            .unwrap_or_else(|e| warn!("Problems pushing video frame: {}", e));
+                    }
+                    info!("id: {}, outgoing video source stopped", connection.id());
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Hand a frame of the outgoing video source to the
+    /// PeerConnection, if any.
+    fn push_outgoing_video_frame(&self, frame: &VideoFrame) -> Result<()> {
+        let webrtc = self.webrtc.lock()?;
+        match webrtc.pc_interface.as_ref() {
+            Some(pc_interface) => pc_interface.push_outgoing_video_frame(frame),
+            None => Ok(()),
+        }
+    }
+
+    /// Create an application specific MediaStream object and store it
+    /// for later.
+    pub fn on_add_stream(&mut self, stream: MediaStream) -> Result<()> {
+        info!("on_add_stream(): id: {}", self.connection_id);
+
+        let app_media_stream = {
+            let call = self.call.lock()?;
+            call.create_media_stream(self, stream)?
+        };
+        self.set_app_media_stream(app_media_stream)?;
+
+        // The media of the PeerConnection being replaced was already
+        // connected, so connect the media of its replacement.
+        if self.reconnection()?.has_reset() {
+            self.connect_media()?;
+        }
+        Ok(())
+    }
+
+    /// Record the rotation of the received video, returning the
+    /// rotation in degrees if it changed.
+    pub fn update_remote_video_orientation(&self, rotation: i32) -> Result<Option<u16>> {
+        Ok(self.remote_video_orientation.lock()?.update(rotation))
+    }
+
+    /// Record a newly selected ICE route, settling it after the route
+    /// change debounce period if it may need reporting.
+    pub fn update_ice_route(&self, route: IceRoute) -> Result<()> {
+        let generation = match self.route_monitor()?.route_changed(route) {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let period = {
+            let call = self.call()?;
+            let call_manager = call.call_manager()?;
+            call_manager.route_change_debounce()?
+        };
+
+        let mut connection = self.clone();
+        let route_settled_future = Delay::new(Instant::now() + period)
+            .map_err(|e| error!("Route change debounce Delay failed: {:?}", e))
+            .and_then(move |_| {
+                connection
+                    .inject_ice_route_settled(generation)
+                    .map_err(|e| error!("Inject ICE route settled failed: {:?}", e))
+            });
+
+        let mut context = self.context.lock()?;
+        if let Some(timeout_runtime) = &mut context.timeout_runtime {
+            timeout_runtime.spawn(route_settled_future);
+        }
+        Ok(())
+    }
+
+    /// Connect our media stream to the application connection
+    pub fn connect_media(&self) -> Result<()> {
+        info!("connect_media(): id: {}", self.connection_id);
+
+        let webrtc = self.webrtc.lock()?;
+        let app_media_stream = match webrtc.app_media_stream.as_ref() {
+            Some(v) => v,
+            None => {
+                return Err(RingRtcError::OptionValueNotSet(
+                    String::from("connect_media()"),
+                    String::from("app_media_stream"),
+                )
+                .into())
+            }
+        };
+
+        let call = self.call()?;
+        call.connect_media(app_media_stream)?;
+        if let Some(timeout) = call.call_manager()?.media_stall_timeout()? {
+            self.start_media_flow_ticker(timeout)?;
+        }
+
+        // Report the rotation of any video received before the media
+        // was connected.
+        let degrees = self.remote_video_orientation.lock()?.degrees();
+        match degrees {
+            Some(degrees) => call.remote_video_orientation(self.remote_device, degrees),
+            None => Ok(()),
+        }
+    }
+
+    /// Inject a `MediaFlowTick` a few times per media stall `timeout`
+    /// for as long as the connection is open.
+    ///
+    /// Only the first time the media is connected starts the ticker.
+    fn start_media_flow_ticker(&self, timeout: Duration) -> Result<()> {
+        let period = {
+            let mut media_flow = self.media_flow()?;
+            if !media_flow.start(timeout, Instant::now()) {
+                return Ok(());
+            }
+            media_flow.sample_period().unwrap_or(timeout)
+        };
+
+        let connection = self.clone();
+        let mut tick_connection = self.clone();
+        let ticker = Interval::new(Instant::now() + period, period)
+            .map_err(|e| error!("Media flow Interval failed: {:?}", e))
+            .take_while(move |_| match connection.terminating() {
+                Ok(false) => Ok(true),
+                _ => Ok(false),
+            })
+            .for_each(move |_| {
+                tick_connection
+                    .inject_media_flow_tick()
+                    .map_err(|e| error!("Inject media flow tick failed: {:?}", e))
+            });
+
+        debug!("start_media_flow_ticker(): spawning media flow task");
+        let mut context = self.context.lock()?;
+        match &mut context.timeout_runtime {
+            Some(timeout_runtime) => {
+                timeout_runtime.spawn(ticker);
+            }
+            None => warn!("start_media_flow_ticker(): no timeout runtime, not monitoring"),
+        }
+        Ok(())
+    }
+
+    /// Sample the packets received from the remote peer, notifying
+    /// the observer if the media stalled or resumed.
+    pub fn sample_media_flow(&self) -> Result<()> {
+        let stats_observer = create_stats_observer();
+
+        let report = {
+            let webrtc = self.webrtc.lock()?;
+            webrtc.pc_interface()?.get_stats(stats_observer.as_ref());
+            stats_observer.get_result()?
+        };
+
+        let now = Instant::now();
+        let (flow, consent_fresh) = {
+            let mut media_flow = self.media_flow()?;
+            let flow = media_flow.sample(
+                report.packets_received(),
+                report.consent_responses_received(),
+                now,
+            );
+            (flow, media_flow.consent_fresh(now))
+        };
+
+        match flow {
+            Some(flow) => {
+                info!(
+                    "id: {}, media flow: {:?}, consent fresh: {}",
+                    self.connection_id, flow, consent_fresh
+                );
+                self.notify_observer(ObserverEvent::RemoteMediaFlow(flow))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Release the PeerConnection and replace it with a fresh one,
+    /// keeping the Connection, and its parent Call, alive.
+    fn rebuild_peer_connection(&self) -> Result<()> {
+        info!("rebuild_peer_connection(): id: {}", self.connection_id);
+
+        {
+            let mut webrtc = self.webrtc.lock()?;
+            webrtc.release()?;
+            webrtc.connection_ptr = Some(self.create_connection_ptr());
+        }
+
+        {
+            let call = self.call()?;
+            let call_manager = call.call_manager()?;
+            call_manager.recreate_peer_connection(&*call, self)?;
+        }
+
+        let scaling = *self.video_scaling.lock()?;
+        self.apply_video_scaling(scaling)?;
+
+        let max_framerate = *self.video_max_framerate.lock()?;
+        if max_framerate > 0 {
+            self.apply_video_max_framerate(max_framerate)?;
+        }
+
+        let preference = *self.degradation_preference.lock()?;
+        self.apply_degradation_preference(preference)?;
+
+        let simulcast = self.simulcast.lock()?.clone();
+        if !simulcast.layers().is_empty() {
+            self.apply_simulcast(&simulcast)?;
+        }
+
+        let mode = *self.bandwidth_mode.lock()?;
+        self.apply_bandwidth_mode(mode)?;
+
+        let gain = *self.outgoing_audio_gain.lock()?;
+        self.apply_outgoing_audio_gain(gain)?;
+
+        let config = *self.audio_config.lock()?;
+        self.apply_audio_config(config)?;
+
+        let delay = *self.jitter_buffer_min_delay.lock()?;
+        if delay.is_some() {
+            self.apply_jitter_buffer_min_delay(delay)?;
+        }
+
+        let provider = self.media_crypto_provider.lock()?.clone();
+        if provider.is_some() {
+            self.apply_media_crypto_provider(provider)?;
+        }
+        Ok(())
+    }
+
+    /// Record that ICE failed while reconnecting, returning how to
+    /// recover, see `ReconnectionController::ice_failed()`.
+    pub fn ice_failed(&self) -> Result<IceRecovery> {
+        let max_restarts = {
+            let call = self.call()?;
+            let call_manager = call.call_manager()?;
+            call_manager.ice_restarts_before_hard_reset()?
+        };
+        Ok(self.reconnection()?.ice_failed(max_restarts))
+    }
+
+    /// Rebuild the PeerConnection and send the remote peer a new
+    /// offer (caller only).
+    pub fn hard_reset(&self) -> Result<()> {
+        self.rebuild_peer_connection()?;
+        self.send_renegotiation_offer()
+    }
+
+    /// Restart ICE on the current PeerConnection and send the remote
+    /// peer a new offer (caller only).
+    ///
+    /// Fresh ICE servers are fetched from the application first, as
+    /// the TURN credentials may have expired since the connection was
+    /// created.
+    pub fn restart_ice(&self) -> Result<()> {
+        info!("restart_ice(): id: {}", self.connection_id);
+
+        let ice_servers = self.call()?.turn_servers()?;
+        if !ice_servers.is_empty() {
+            self.apply_ice_servers(&ice_servers)?;
+            *self.ice_servers.lock()? = Some(ice_servers);
+        }
+
+        {
+            let webrtc = self.webrtc.lock()?;
+            webrtc.pc_interface()?.restart_ice();
+        }
+        self.send_renegotiation_offer()
+    }
+
+    /// Send a renegotiation offer and time out waiting for the answer.
+    fn send_renegotiation_offer(&self) -> Result<()> {
+        // The offer is marked with its identifier, see
+        // `ReconnectionController::renegotiation()`.
+        let offer = self.reconnection()?.offer_sent();
+        self.send_offer()?;
+
+        let timeout = {
+            let call = self.call()?;
+            let call_manager = call.call_manager()?;
+            call_manager.renegotiation_timeout()?
+        };
+
+        let mut connection = self.clone();
+        let renegotiation_timeout_future = Delay::new(Instant::now() + timeout)
+            .map_err(|e| error!("Renegotiation timeout Delay failed: {:?}", e))
+            .and_then(move |_| {
+                connection
+                    .inject_renegotiation_timeout(offer)
+                    .map_err(|e| error!("Inject renegotiation timeout failed: {:?}", e))
+            });
+
+        let mut context = self.context.lock()?;
+        if let Some(timeout_runtime) = &mut context.timeout_runtime {
+            timeout_runtime.spawn(renegotiation_timeout_future);
+        }
+        Ok(())
+    }
+
+    /// Schedule an automatic ICE restart while reconnecting (caller
+    /// only).
+    ///
+    /// The delay doubles with each attempt since the call last
+    /// connected, and no attempt is scheduled once the attempts set
+    /// with `CallManager::set_auto_recovery()` are exhausted.
+    pub fn schedule_recovery(&self) -> Result<()> {
+        if let CallDirection::InComing = self.direction() {
+            return Ok(());
+        }
+
+        let (max_attempts, backoff) = {
+            let call = self.call()?;
+            let call_manager = call.call_manager()?;
+            call_manager.auto_recovery()?
+        };
+        let (recovery, attempt) = match self.reconnection()?.schedule_recovery(max_attempts) {
+            Some(v) => v,
+            None => {
+                info!("id: {}, automatic recovery attempts exhausted", self.id());
+                return Ok(());
+            }
+        };
+
+        let delay = backoff
+            .checked_mul(1 << (attempt - 1).min(MAX_RECOVERY_BACKOFF_DOUBLINGS))
+            .unwrap_or(backoff);
+        info!(
+            "id: {}, automatic recovery attempt: {} in {:?}",
+            self.id(),
+            attempt,
+            delay
+        );
+
+        let mut connection = self.clone();
+        let recovery_future = Delay::new(Instant::now() + delay)
+            .map_err(|e| error!("Recovery Delay failed: {:?}", e))
+            .and_then(move |_| {
+                connection
+                    .inject_recovery_timeout(recovery)
+                    .map_err(|e| error!("Inject recovery timeout failed: {:?}", e))
+            });
+
+        let mut context = self.context.lock()?;
+        if let Some(timeout_runtime) = &mut context.timeout_runtime {
+            timeout_runtime.spawn(recovery_future);
+        }
+        Ok(())
+    }
+
+    /// Roll back the unanswered local offer, returning the
+    /// PeerConnection to the stable signaling state.
+    pub fn rollback_local_description(&self) -> Result<()> {
+        info!("rollback_local_description(): id: {}", self.connection_id);
 
-    /// Notify the parent call observer about an internal error.
-    pub fn internal_error(&self, error: failure::Error) -> Result<()> {
-        let mut call = self.call.lock()?;
-        call.on_connection_error(self.connection_id, error)
+        let rollback = SessionDescriptionInterface::create_sdp_rollback()?;
+        self.set_local_description(&rollback)
     }
 
-    /// Create an application specific MediaStream object and store it
-    /// for later.
-    pub fn on_add_stream(&mut self, stream: MediaStream) -> Result<()> {
-        info!("on_add_stream(): id: {}", self.connection_id);
+    /// Roll back the unanswered renegotiation offer and send it again.
+    pub fn retry_renegotiation_offer(&self) -> Result<()> {
+        self.rollback_local_description()?;
+        self.send_renegotiation_offer()
+    }
 
-        let call = self.call.lock()?;
-        let app_media_stream = call.create_media_stream(self, stream)?;
-        self.set_app_media_stream(app_media_stream)
+    /// Rebuild the PeerConnection and answer the new offer sent by a
+    /// remote peer that hard reset its connection (callee only).
+    pub fn handle_reset_offer(&mut self, offer: String) -> Result<()> {
+        self.rebuild_peer_connection()?;
+        self.handle_offer(offer)
     }
 
-    /// Connect our media stream to the application connection
-    pub fn connect_media(&self) -> Result<()> {
-        info!("connect_media(): id: {}", self.connection_id);
+    /// Restore the video status of the replaced PeerConnection,
+    /// once a new DataChannel is available.
+    pub fn restore_video_status(&self) -> Result<()> {
+        let enabled = match *self.local_video_status.lock()? {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        if let Err(e) = self.send_video_status(enabled) {
+            warn!(
+                "id: {}, unable to restore video status: {}",
+                self.connection_id, e
+            );
+        }
+        Ok(())
+    }
 
-        let webrtc = self.webrtc.lock()?;
-        let app_media_stream = match webrtc.app_media_stream.as_ref() {
+    /// Restore the screen share status of the replaced
+    /// PeerConnection, once a new DataChannel is available.
+    pub fn restore_screen_share_status(&self) -> Result<()> {
+        let sharing = match *self.local_screen_share_status.lock()? {
             Some(v) => v,
-            None => {
-                return Err(RingRtcError::OptionValueNotSet(
-                    String::from("connect_media()"),
-                    String::from("app_media_stream"),
-                )
-                .into())
-            }
+            None => return Ok(()),
         };
+        if let Err(e) = self.send_screen_share_status(sharing) {
+            warn!(
+                "id: {}, unable to restore screen share status: {}",
+                self.connection_id, e
+            );
+        }
+        Ok(())
+    }
 
-        let call = self.call()?;
-        call.connect_media(app_media_stream)
+    /// Tell the remote peer the local audio is muted, once a
+    /// DataChannel is connected.
+    ///
+    /// The remote peer assumes unmuted audio, so nothing is sent
+    /// while the audio is enabled.
+    pub fn restore_audio_status(&self) -> Result<()> {
+        if self.outgoing_audio_enabled()? {
+            return Ok(());
+        }
+        if let Err(e) = self.send_audio_status(false) {
+            warn!(
+                "id: {}, unable to restore audio status: {}",
+                self.connection_id, e
+            );
+        }
+        Ok(())
+    }
+
+    /// Tell the remote peer the call is on hold, once a new
+    /// DataChannel is available.
+    pub fn restore_hold_status(&self) -> Result<()> {
+        if !self.on_hold()? {
+            return Ok(());
+        }
+        if let Err(e) = self.send_hold(true) {
+            warn!(
+                "id: {}, unable to restore hold status: {}",
+                self.connection_id, e
+            );
+        }
+        Ok(())
+    }
+
+    /// Discard the ICE candidates of the replaced PeerConnection.
+    pub fn clear_pending_ice_candidates(&self) -> Result<()> {
+        self.pending_outbound_ice_candidates.lock()?.clear();
+        self.pending_inbound_ice_candidates.lock()?.clear();
+        Ok(())
     }
 
     /// Send a ConnectionEvent to the internal FSM.
@@ -764,6 +3611,7 @@ where
             );
             return Ok(());
         }
+        self.record_trace(TraceKind::FsmEvent, format!("{}", event));
         self.event_pump.try_send((self.clone(), event))?;
         Ok(())
     }
@@ -784,43 +3632,21 @@ where
 
         self.set_state(ConnectionState::Closed)?;
 
-        // Free up webrtc related resources.
-        let mut webrtc = self.webrtc.lock()?;
-
-        // dispose of the media stream
-        let _ = webrtc.app_media_stream.take();
+        self.context.lock()?.close();
 
-        // unregister the data channel observer
-        if let Some(data_channel) = webrtc.data_channel.take().as_mut() {
-            if let Some(dc_observer) = webrtc.data_channel_observer.take().as_mut() {
-                unsafe { data_channel.unregister_observer(dc_observer.rffi_interface()) };
-            }
-            data_channel.dispose();
+        if let Some(token) = self.outgoing_video_source.lock()?.take() {
+            token.cancel();
         }
 
-        // Free the application connection object, which is in essence
-        // the PeerConnection object.  It is important to dispose of
-        // the app_connection before the connection_ptr.  The
-        // app_connection refers to the real PeerConnection object,
-        // whose observer is using the connection_ptr.  Once the
-        // PeerConnection is completely shutdown it is safe to free up
-        // the connection_ptr.
-        let _ = webrtc.app_connection.take();
-
-        // Free the connection object previously used by the
-        // PeerConnectionObserver.  Convert the pointer back into a
-        // Box and let it go out of scope.
-        match webrtc.connection_ptr.take() {
-            Some(v) => {
-                let _ = unsafe { ptr_as_box(v)? };
-                Ok(())
+        if let Some(mut recorder) = self.recorder.lock()?.take() {
+            if let Err(e) = recorder.finish() {
+                warn!("close(): unable to finish recording: {}", e);
             }
-            None => Err(RingRtcError::OptionValueNotSet(
-                String::from("close()"),
-                String::from("connection_ptr"),
-            )
-            .into()),
         }
+
+        // Free up webrtc related resources.
+        let mut webrtc = self.webrtc.lock()?;
+        webrtc.release()
     }
 
     /// Bottom half of `close()`
@@ -876,6 +3702,29 @@ where
         self.inject_event(event)
     }
 
+    /// Inject a `RestartIce` event into the FSM.
+    ///
+    /// `Called By:` Local application.
+    pub fn inject_restart_ice(&mut self) -> Result<()> {
+        self.inject_event(ConnectionEvent::RestartIce)
+    }
+
+    /// Inject a `RenegotiationTimeout` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    pub fn inject_renegotiation_timeout(&mut self, offer: u32) -> Result<()> {
+        let event = ConnectionEvent::RenegotiationTimeout(offer);
+        self.inject_event(event)
+    }
+
+    /// Inject a `RecoveryTimeout` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    pub fn inject_recovery_timeout(&mut self, recovery: u32) -> Result<()> {
+        let event = ConnectionEvent::RecoveryTimeout(recovery);
+        self.inject_event(event)
+    }
+
     /// Inject a `HandleAnswer` event into the FSM
     ///
     /// `Called By:` Local application.
@@ -983,8 +3832,9 @@ where
     /// # Arguments
     ///
     /// * `call_id` - Call ID from the remote peer.
-    pub fn inject_remote_hangup(&mut self, call_id: CallId) -> Result<()> {
-        self.inject_event(ConnectionEvent::RemoteHangup(call_id))
+    /// * `hangup_type` - Why the remote peer hung up.
+    pub fn inject_remote_hangup(&mut self, call_id: CallId, hangup_type: HangupType) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteHangup(call_id, hangup_type))
     }
 
     /// Inject a `RemoteVideoStatus` event into the FSM.
@@ -999,12 +3849,216 @@ where
         self.inject_event(ConnectionEvent::RemoteVideoStatus(call_id, enabled))
     }
 
+    /// Inject a `RemoteAudioStatus` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `enabled` - `false` if the remote peer muted its audio.
+    pub fn inject_remote_audio_status(&mut self, call_id: CallId, enabled: bool) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteAudioStatus(call_id, enabled))
+    }
+
+    /// Inject a `RemoteHold` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `on_hold` - `true` if the remote peer put the call on hold.
+    pub fn inject_remote_hold(&mut self, call_id: CallId, on_hold: bool) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteHold(call_id, on_hold))
+    }
+
+    /// Inject a `RemoteTransfer` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `message` - The transfer step sent by the remote peer.
+    pub fn inject_remote_transfer(
+        &mut self,
+        call_id: CallId,
+        message: TransferMessage,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteTransfer(call_id, message))
+    }
+
+    /// Inject a `RemoteNegotiation` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `message` - The session description sent by the remote peer.
+    pub fn inject_remote_negotiation(
+        &mut self,
+        call_id: CallId,
+        message: NegotiationMessage,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteNegotiation(call_id, message))
+    }
+
+    /// Inject a `RemoteAppMessage` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `payload` - The application message sent by the remote peer.
+    pub fn inject_remote_app_message(&mut self, call_id: CallId, payload: Vec<u8>) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteAppMessage(call_id, payload))
+    }
+
+    /// Inject a `RemoteVideoLayerRequest` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `layer` - The simulcast layer requested by the remote peer.
+    pub fn inject_remote_video_layer_request(
+        &mut self,
+        call_id: CallId,
+        layer: usize,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteVideoLayerRequest(call_id, layer))
+    }
+
+    /// Inject a `RemoteRecordingStatus` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `recording` - `true` if the remote peer started recording.
+    pub fn inject_remote_recording_status(
+        &mut self,
+        call_id: CallId,
+        recording: bool,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteRecordingStatus(call_id, recording))
+    }
+
+    /// Inject a `RemoteScreenShareStatus` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `sharing` - `true` if the remote peer is sharing its screen.
+    pub fn inject_remote_screen_share_status(
+        &mut self,
+        call_id: CallId,
+        sharing: bool,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteScreenShareStatus(call_id, sharing))
+    }
+
+    /// Inject a `RemoteVideoOrientation` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `rotation` - Rotation of the received video frames, in degrees.
+    pub fn inject_remote_video_orientation(&mut self, rotation: i32) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteVideoOrientation(rotation))
+    }
+
+    /// Inject a `ReceivedDtmf` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `tone` - The DTMF tone received from the remote peer.
+    pub fn inject_received_dtmf(&mut self, tone: char) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedDtmf(tone))
+    }
+
+    /// Inject a `RemoteMediaClosed` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    pub fn inject_remote_media_closed(&mut self) -> Result<()> {
+        self.inject_event(ConnectionEvent::RemoteMediaClosed)
+    }
+
+    /// Inject a `NegotiationNeeded` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    pub fn inject_negotiation_needed(&mut self) -> Result<()> {
+        self.inject_event(ConnectionEvent::NegotiationNeeded)
+    }
+
+    /// Inject an `IceRouteChanged` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The newly selected ICE candidate pair.
+    pub fn inject_ice_route_changed(&mut self, route: IceRoute) -> Result<()> {
+        self.inject_event(ConnectionEvent::IceRouteChanged(route))
+    }
+
+    /// Inject an `IceGatheringStateChanged` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The new ICE candidate gathering state.
+    pub fn inject_ice_gathering_state_changed(&mut self, state: IceGatheringState) -> Result<()> {
+        self.inject_event(ConnectionEvent::IceGatheringStateChanged(state))
+    }
+
+    /// Inject a `MediaFlowTick` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    pub fn inject_media_flow_tick(&mut self) -> Result<()> {
+        self.inject_event(ConnectionEvent::MediaFlowTick)
+    }
+
+    /// Inject an `IceRouteSettled` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    pub fn inject_ice_route_settled(&mut self, generation: u32) -> Result<()> {
+        self.inject_event(ConnectionEvent::IceRouteSettled(generation))
+    }
+
+    /// Inject a `DataChannelBufferedAmountChange` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `DataChannelObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_amount` - Number of bytes buffered before the change.
+    pub fn inject_data_channel_buffered_amount_change(
+        &mut self,
+        previous_amount: u64,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::DataChannelBufferedAmountChange(
+            previous_amount,
+        ))
+    }
+
     /// Inject a local `HangUp` event into the FSM.
     ///
     /// `Called By:` Local application.
-    pub fn inject_hangup(&mut self) -> Result<()> {
+    pub fn inject_hangup(&mut self, hangup_type: HangupType) -> Result<()> {
         self.set_state(ConnectionState::Terminating)?;
-        self.inject_event(ConnectionEvent::LocalHangup)
+        self.inject_event(ConnectionEvent::LocalHangup(hangup_type))
     }
 
     /// Inject a local `AcceptCall` event into the FSM.
@@ -1023,6 +4077,52 @@ where
         self.inject_event(ConnectionEvent::LocalVideoStatus(enabled))
     }
 
+    /// Inject a `LocalScreenShareStatus` event into the FSM.
+    ///
+    /// `Called By:` Local application.
+    ///
+    /// * `sharing` - `true` if the local peer is sharing its screen.
+    pub fn inject_local_screen_share_status(&mut self, sharing: bool) -> Result<()> {
+        self.inject_event(ConnectionEvent::LocalScreenShareStatus(sharing))
+    }
+
+    /// Inject a `LocalAudioStatus` event into the FSM.
+    ///
+    /// `Called By:` Local application.
+    ///
+    /// * `enabled` - `false` if the local audio is muted.
+    pub fn inject_local_audio_status(&mut self, enabled: bool) -> Result<()> {
+        self.inject_event(ConnectionEvent::LocalAudioStatus(enabled))
+    }
+
+    /// Inject a `LocalHold` event into the FSM.
+    ///
+    /// `Called By:` Local application.
+    ///
+    /// * `on_hold` - `true` if the local peer put the call on hold.
+    pub fn inject_local_hold(&mut self, on_hold: bool) -> Result<()> {
+        self.inject_event(ConnectionEvent::LocalHold(on_hold))
+    }
+
+    /// Inject a `LocalTransfer` event into the FSM.
+    ///
+    /// `Called By:` Local application.
+    ///
+    /// * `target` - The peer to transfer the call to.
+    pub fn inject_local_transfer(&mut self, target: String) -> Result<()> {
+        self.inject_event(ConnectionEvent::LocalTransfer(target))
+    }
+
+    /// Inject a `TransferResponse` event into the FSM.
+    ///
+    /// `Called By:` Call object.
+    ///
+    /// * `accepted` - `true` if the application accepted the transfer
+    ///   requested by the remote peer.
+    pub fn inject_transfer_response(&mut self, accepted: bool) -> Result<()> {
+        self.inject_event(ConnectionEvent::TransferResponse(accepted))
+    }
+
     /// Inject a `RemoteIceCandidates` event into the FSM.
     ///
     /// `Called By:` Call object.
@@ -0,0 +1,136 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! PeerConnection configuration chosen by the application.
+//!
+//! The defaults, max-bundle with rtcp-mux required, suit current
+//! endpoints.  Older endpoints may need BUNDLE or rtcp-mux disabled,
+//! in which case each media section of the offer gets its own
+//! transport.
+//!
+//! The ICE servers are those of the call when the PeerConnection is
+//! created, ordered by transport.
+
+use std::fmt;
+
+use crate::common::Result;
+use crate::core::ice_server::IceServerConfig;
+use crate::error::RingRtcError;
+
+/// How media sections are bundled onto transports, matching the
+/// WebRTC `BundlePolicy`.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BundlePolicy {
+    /// Bundle per media type if the remote peer does not support
+    /// BUNDLE.
+    Balanced = 0,
+    /// Bundle all media sections onto one transport.
+    #[default]
+    MaxBundle,
+    /// Give each media section its own transport.
+    MaxCompat,
+}
+
+impl fmt::Display for BundlePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl BundlePolicy {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(BundlePolicy::Balanced),
+            1 => Ok(BundlePolicy::MaxBundle),
+            2 => Ok(BundlePolicy::MaxCompat),
+            _ => Err(RingRtcError::UnknownBundlePolicy(value).into()),
+        }
+    }
+}
+
+/// Whether RTP and RTCP share a port, matching the WebRTC
+/// `RtcpMuxPolicy`.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RtcpMuxPolicy {
+    /// Gather separate RTCP candidates, used if the remote peer does
+    /// not support rtcp-mux.
+    Negotiate = 0,
+    /// Multiplex RTP and RTCP, failing if the remote peer does not
+    /// support rtcp-mux.
+    #[default]
+    Require,
+}
+
+impl fmt::Display for RtcpMuxPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl RtcpMuxPolicy {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(RtcpMuxPolicy::Negotiate),
+            1 => Ok(RtcpMuxPolicy::Require),
+            _ => Err(RingRtcError::UnknownRtcpMuxPolicy(value).into()),
+        }
+    }
+}
+
+/// The configuration a platform applies when creating a
+/// PeerConnection.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionConfig {
+    pub bundle_policy:   BundlePolicy,
+    pub rtcp_mux_policy: RtcpMuxPolicy,
+    /// STUN and TURN servers, in the order they are used.
+    pub ice_servers:     Vec<IceServerConfig>,
+}
+
+impl fmt::Display for ConnectionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "bundle_policy: {}, rtcp_mux_policy: {}, ice_servers: {}",
+            self.bundle_policy,
+            self.rtcp_mux_policy,
+            self.ice_servers.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_defaults() {
+        let config = ConnectionConfig::default();
+        assert_eq!(config.bundle_policy, BundlePolicy::MaxBundle);
+        assert_eq!(config.rtcp_mux_policy, RtcpMuxPolicy::Require);
+        assert!(config.ice_servers.is_empty());
+    }
+
+    #[test]
+    fn check_from_i32() {
+        for policy in &[
+            BundlePolicy::Balanced,
+            BundlePolicy::MaxBundle,
+            BundlePolicy::MaxCompat,
+        ] {
+            assert_eq!(BundlePolicy::from_i32(*policy as i32).unwrap(), *policy);
+        }
+        assert!(BundlePolicy::from_i32(3).is_err());
+
+        for policy in &[RtcpMuxPolicy::Negotiate, RtcpMuxPolicy::Require] {
+            assert_eq!(RtcpMuxPolicy::from_i32(*policy as i32).unwrap(), *policy);
+        }
+        assert!(RtcpMuxPolicy::from_i32(2).is_err());
+    }
+}
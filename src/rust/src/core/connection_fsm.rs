@@ -22,6 +22,11 @@
 //! - AnswerCall
 //! - LocalHangup
 //! - LocalVideoStatus
+//! - LocalScreenShareStatus
+//! - LocalAudioStatus
+//! - LocalHold
+//! - LocalTransfer
+//! - TransferResponse
 //! - SendBusy
 //! - RemoteIceCandidate
 //! - RemoteHangup
@@ -34,9 +39,31 @@
 //! - IceConnectionDisconnected
 //! - OnAddStream
 //! - OnDataChannel
+//! - RemoteVideoOrientation
+//! - ReceivedDtmf
+//! - RemoteMediaClosed
+//! - NegotiationNeeded
+//! - IceRouteChanged
+//! - IceGatheringStateChanged
 //! - RemoteConnected
 //! - RemoteVideoStatus
+//! - RemoteAudioStatus
+//! - RemoteHold
+//! - RemoteRecordingStatus
+//! - RemoteScreenShareStatus
+//! - RemoteTransfer
+//! - RemoteNegotiation
+//! - RemoteAppMessage
+//! - RemoteVideoLayerRequest
 //! - RemoteHangup
+//! - DataChannelBufferedAmountChange
+//!
+//! ## From Internal runtime
+//!
+//! - RenegotiationTimeout
+//! - RecoveryTimeout
+//! - IceRouteSettled
+//! - MediaFlowTick
 //!
 //! # Asynchronous Outputs:
 //!
@@ -50,19 +77,36 @@ extern crate tokio;
 use std::fmt;
 use std::sync::{mpsc, Arc, Condvar, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use futures::future::lazy;
 use futures::{Async, Future, Poll, Stream};
 use tokio::runtime;
 
-use crate::common::{CallDirection, CallId, ConnectionState, Result};
-use crate::core::connection::{Connection, EventStream, ObserverEvent};
+use crate::common::{
+    CallDirection,
+    CallId,
+    ConnectionState,
+    HangupType,
+    NegotiationMessage,
+    Result,
+    TransferMessage,
+};
+use crate::core::connection::{
+    Connection,
+    EventStream,
+    IceRecovery,
+    ObserverEvent,
+    RemoteOfferAction,
+};
 use crate::core::platform::Platform;
+use crate::core::signaling::Offer;
+use crate::core::video::MAX_SIMULCAST_LAYERS;
 use crate::error::RingRtcError;
 use crate::webrtc::data_channel::DataChannel;
-use crate::webrtc::ice_candidate::IceCandidate;
+use crate::webrtc::ice_candidate::{IceCandidate, IceRoute};
 use crate::webrtc::media_stream::MediaStream;
+use crate::webrtc::peer_connection_observer::IceGatheringState;
 
 /// The different types of Connection Events.
 pub enum ConnectionEvent {
@@ -77,17 +121,45 @@ pub enum ConnectionEvent {
     /// Accept incoming call (callee only).
     AcceptCall,
     /// Receive hangup from remote peer.
-    RemoteHangup(CallId),
+    RemoteHangup(CallId, HangupType),
     /// Receive call connected from remote peer.
     RemoteConnected(CallId),
     /// Receive video streaming status change from remote peer.
     RemoteVideoStatus(CallId, bool),
+    /// Receive audio mute status change from remote peer.
+    RemoteAudioStatus(CallId, bool),
+    /// Receive hold status change from remote peer.
+    RemoteHold(CallId, bool),
+    /// Receive recording status change from remote peer.
+    RemoteRecordingStatus(CallId, bool),
+    /// Receive screen share status change from remote peer.
+    RemoteScreenShareStatus(CallId, bool),
+    /// Receive a call transfer step from remote peer.
+    RemoteTransfer(CallId, TransferMessage),
+    /// Receive a session description adding or removing tracks from
+    /// remote peer.
+    RemoteNegotiation(CallId, NegotiationMessage),
+    /// Receive an application message from remote peer.
+    RemoteAppMessage(CallId, Vec<u8>),
+    /// Receive a request for a simulcast layer from remote peer.
+    RemoteVideoLayerRequest(CallId, usize),
     /// Receive ICE candidate message from remote peer.
     ReceivedIceCandidates(Vec<IceCandidate>),
     /// Local hangup event from client application.
-    LocalHangup,
+    LocalHangup(HangupType),
     /// Local video streaming status change from client application.
     LocalVideoStatus(bool),
+    /// Local screen share status change from client application.
+    LocalScreenShareStatus(bool),
+    /// Local audio mute status change from client application.
+    LocalAudioStatus(bool),
+    /// Local hold status change from client application.
+    LocalHold(bool),
+    /// Transfer the call to another peer, from client application.
+    LocalTransfer(String),
+    /// Answer to the transfer requested by the remote peer, from
+    /// client application.
+    TransferResponse(bool),
     /// Local ICE candidate ready, from WebRTC observer.
     LocalIceCandidate(IceCandidate),
     /// Local ICE status is connected, from WebRTC observer.
@@ -96,12 +168,40 @@ pub enum ConnectionEvent {
     IceConnectionFailed,
     /// Local ICE connection disconnected, from WebRTC observer.
     IceConnectionDisconnected,
+    /// The renegotiation offer went unanswered, from the timeout thread.
+    RenegotiationTimeout(u32),
+    /// The backoff of an automatic recovery attempt elapsed, from the
+    /// timeout thread.
+    RecoveryTimeout(u32),
+    /// Restart ICE with new ICE servers, from client application.
+    RestartIce,
     /// Send the observer an internal error message.
     InternalError(failure::Error),
     /// Receive local media stream from WebRTC observer.
     OnAddStream(MediaStream),
     /// Receive new available data channel from WebRTC observer (callee).
     OnDataChannel(DataChannel),
+    /// Rotation of the received video, from WebRTC observer.
+    RemoteVideoOrientation(i32),
+    /// A DTMF tone was received, from WebRTC observer.
+    ReceivedDtmf(char),
+    /// The remote peer sent an RTCP BYE or closed the DTLS transport,
+    /// from WebRTC observer.
+    RemoteMediaClosed,
+    /// The local tracks changed and need negotiating, from WebRTC
+    /// observer.
+    NegotiationNeeded,
+    /// The selected ICE candidate pair changed, from WebRTC observer.
+    IceRouteChanged(IceRoute),
+    /// The ICE route change debounce period expired, from the timeout
+    /// thread.
+    IceRouteSettled(u32),
+    /// Time to sample the media received, from the media flow ticker.
+    MediaFlowTick,
+    /// The ICE candidate gathering state changed, from WebRTC observer.
+    IceGatheringStateChanged(IceGatheringState),
+    /// The DataChannel send buffer changed, from DataChannel observer.
+    DataChannelBufferedAmountChange(u64),
     /// Synchronize the FSM.
     Synchronize(Arc<(Mutex<bool>, Condvar)>),
     /// Shutdown the call.
@@ -116,23 +216,87 @@ impl fmt::Display for ConnectionEvent {
             ConnectionEvent::HandleOffer(_) => "HandleOffer".to_string(),
             ConnectionEvent::HaveLocalRemoteSdp => "HaveLocalRemoteSdp".to_string(),
             ConnectionEvent::AcceptCall => "AcceptCall".to_string(),
-            ConnectionEvent::RemoteHangup(id) => format!("RemoteHangup, call_id: {}", id),
+            ConnectionEvent::RemoteHangup(id, t) => {
+                format!("RemoteHangup, call_id: {}, type: {}", id, t)
+            }
             ConnectionEvent::RemoteConnected(id) => format!("RemoteConnected, call_id: {}", id),
             ConnectionEvent::RemoteVideoStatus(id, enabled) => {
                 format!("RemoteVideoStatus, call_id: {}, enabled: {}", id, enabled)
             }
             ConnectionEvent::ReceivedIceCandidates(_) => "RemoteIceCandidates".to_string(),
-            ConnectionEvent::LocalHangup => "LocalHangup".to_string(),
+            ConnectionEvent::LocalHangup(t) => format!("LocalHangup, type: {}", t),
             ConnectionEvent::LocalVideoStatus(enabled) => {
                 format!("LocalVideoStatus, enabled: {}", enabled)
             }
+            ConnectionEvent::LocalScreenShareStatus(sharing) => {
+                format!("LocalScreenShareStatus, sharing: {}", sharing)
+            }
+            ConnectionEvent::RemoteAudioStatus(id, enabled) => {
+                format!("RemoteAudioStatus, call_id: {}, enabled: {}", id, enabled)
+            }
+            ConnectionEvent::LocalAudioStatus(enabled) => {
+                format!("LocalAudioStatus, enabled: {}", enabled)
+            }
+            ConnectionEvent::RemoteHold(id, on_hold) => {
+                format!("RemoteHold, call_id: {}, on_hold: {}", id, on_hold)
+            }
+            ConnectionEvent::RemoteRecordingStatus(id, recording) => format!(
+                "RemoteRecordingStatus, call_id: {}, recording: {}",
+                id, recording
+            ),
+            ConnectionEvent::RemoteScreenShareStatus(id, sharing) => format!(
+                "RemoteScreenShareStatus, call_id: {}, sharing: {}",
+                id, sharing
+            ),
+            ConnectionEvent::LocalHold(on_hold) => format!("LocalHold, on_hold: {}", on_hold),
+            ConnectionEvent::RemoteTransfer(id, message) => {
+                format!("RemoteTransfer, call_id: {}, message: {}", id, message)
+            }
+            ConnectionEvent::RemoteNegotiation(id, message) => {
+                format!("RemoteNegotiation, call_id: {}, message: {}", id, message)
+            }
+            ConnectionEvent::RemoteAppMessage(id, payload) => {
+                format!("RemoteAppMessage, call_id: {}, size: {}", id, payload.len())
+            }
+            ConnectionEvent::RemoteVideoLayerRequest(id, layer) => {
+                format!("RemoteVideoLayerRequest, call_id: {}, layer: {}", id, layer)
+            }
+            ConnectionEvent::LocalTransfer(target) => format!("LocalTransfer, target: {}", target),
+            ConnectionEvent::TransferResponse(accepted) => {
+                format!("TransferResponse, accepted: {}", accepted)
+            }
             ConnectionEvent::LocalIceCandidate(_) => "LocalIceCandidate".to_string(),
             ConnectionEvent::IceConnected => "IceConnected".to_string(),
             ConnectionEvent::IceConnectionFailed => "IceConnectionFailed".to_string(),
             ConnectionEvent::IceConnectionDisconnected => "IceConnectionDisconnected".to_string(),
+            ConnectionEvent::RenegotiationTimeout(offer) => {
+                format!("RenegotiationTimeout, offer: {}", offer)
+            }
+            ConnectionEvent::RecoveryTimeout(recovery) => {
+                format!("RecoveryTimeout, recovery: {}", recovery)
+            }
+            ConnectionEvent::RestartIce => "RestartIce".to_string(),
             ConnectionEvent::InternalError(e) => format!("InternalError: {}", e),
             ConnectionEvent::OnAddStream(stream) => format!("OnAddStream, stream: {:}", stream),
             ConnectionEvent::OnDataChannel(dc) => format!("OnDataChannel, dc: {:?}", dc),
+            ConnectionEvent::RemoteVideoOrientation(rotation) => {
+                format!("RemoteVideoOrientation, rotation: {}", rotation)
+            }
+            ConnectionEvent::ReceivedDtmf(tone) => format!("ReceivedDtmf, tone: {}", tone),
+            ConnectionEvent::RemoteMediaClosed => "RemoteMediaClosed".to_string(),
+            ConnectionEvent::NegotiationNeeded => "NegotiationNeeded".to_string(),
+            ConnectionEvent::IceRouteChanged(route) => format!("IceRouteChanged, {}", route),
+            ConnectionEvent::IceRouteSettled(generation) => {
+                format!("IceRouteSettled, generation: {}", generation)
+            }
+            ConnectionEvent::MediaFlowTick => "MediaFlowTick".to_string(),
+            ConnectionEvent::IceGatheringStateChanged(state) => {
+                format!("IceGatheringStateChanged, state: {}", state)
+            }
+            ConnectionEvent::DataChannelBufferedAmountChange(previous_amount) => format!(
+                "DataChannelBufferedAmountChange, previous_amount: {}",
+                previous_amount
+            ),
             ConnectionEvent::Synchronize(_) => "Synchronize".to_string(),
             ConnectionEvent::EndCall => "EndCall".to_string(),
         };
@@ -314,7 +478,9 @@ where
         // Handle these events even while terminating, as the remote
         // side needs to be informed.
         match event {
-            ConnectionEvent::LocalHangup => return self.handle_local_hangup(connection, state),
+            ConnectionEvent::LocalHangup(hangup_type) => {
+                return self.handle_local_hangup(connection, state, hangup_type)
+            }
             ConnectionEvent::EndCall => return self.handle_end_call(connection),
             ConnectionEvent::Synchronize(sync) => return self.handle_synchronize(sync),
             _ => {}
@@ -338,19 +504,60 @@ where
                 self.handle_have_local_remote_sdp(connection, state)
             }
             ConnectionEvent::AcceptCall => self.handle_accept_call(connection, state),
-            ConnectionEvent::RemoteHangup(id) => self.handle_remote_hangup(connection, state, id),
+            ConnectionEvent::RemoteHangup(id, hangup_type) => {
+                self.handle_remote_hangup(connection, state, id, hangup_type)
+            }
             ConnectionEvent::RemoteConnected(id) => {
                 self.handle_remote_connected(connection, state, id)
             }
             ConnectionEvent::RemoteVideoStatus(id, enable) => {
                 self.handle_remote_video_status(connection, state, id, enable)
             }
+            ConnectionEvent::RemoteAudioStatus(id, enable) => {
+                self.handle_remote_audio_status(connection, state, id, enable)
+            }
+            ConnectionEvent::RemoteHold(id, on_hold) => {
+                self.handle_remote_hold(connection, state, id, on_hold)
+            }
+            ConnectionEvent::RemoteRecordingStatus(id, recording) => {
+                self.handle_remote_recording_status(connection, state, id, recording)
+            }
+            ConnectionEvent::RemoteScreenShareStatus(id, sharing) => {
+                self.handle_remote_screen_share_status(connection, state, id, sharing)
+            }
             ConnectionEvent::ReceivedIceCandidates(candidates) => {
                 self.handle_received_ice_candidates(connection, state, candidates)
             }
             ConnectionEvent::LocalVideoStatus(enabled) => {
                 self.handle_local_video_status(connection, state, enabled)
             }
+            ConnectionEvent::LocalScreenShareStatus(sharing) => {
+                self.handle_local_screen_share_status(connection, state, sharing)
+            }
+            ConnectionEvent::LocalAudioStatus(enabled) => {
+                self.handle_local_audio_status(connection, state, enabled)
+            }
+            ConnectionEvent::LocalHold(on_hold) => {
+                self.handle_local_hold(connection, state, on_hold)
+            }
+            ConnectionEvent::RemoteTransfer(id, message) => {
+                self.handle_remote_transfer(connection, state, id, message)
+            }
+            ConnectionEvent::RemoteNegotiation(id, message) => {
+                self.handle_remote_negotiation(connection, state, id, message)
+            }
+            ConnectionEvent::RemoteAppMessage(id, payload) => {
+                self.handle_remote_app_message(connection, state, id, payload)
+            }
+            ConnectionEvent::RemoteVideoLayerRequest(id, layer) => {
+                self.handle_remote_video_layer_request(connection, state, id, layer)
+            }
+            ConnectionEvent::LocalTransfer(target) => {
+                self.handle_local_transfer(connection, state, target)
+            }
+            ConnectionEvent::TransferResponse(accepted) => {
+                self.handle_transfer_response(connection, state, accepted)
+            }
             ConnectionEvent::LocalIceCandidate(candidate) => {
                 self.handle_local_ice_candidate(connection, state, candidate)
             }
@@ -361,6 +568,13 @@ where
             ConnectionEvent::IceConnectionDisconnected => {
                 self.handle_ice_connection_disconnected(connection, state)
             }
+            ConnectionEvent::RenegotiationTimeout(offer) => {
+                self.handle_renegotiation_timeout(connection, state, offer)
+            }
+            ConnectionEvent::RecoveryTimeout(recovery) => {
+                self.handle_recovery_timeout(connection, state, recovery)
+            }
+            ConnectionEvent::RestartIce => self.handle_restart_ice(connection, state),
             ConnectionEvent::InternalError(error) => self.handle_internal_error(connection, error),
             ConnectionEvent::OnAddStream(stream) => {
                 self.handle_on_add_stream(connection, state, stream)
@@ -368,7 +582,30 @@ where
             ConnectionEvent::OnDataChannel(dc) => {
                 self.handle_on_data_channel(connection, state, dc)
             }
-            ConnectionEvent::LocalHangup => Ok(()),
+            ConnectionEvent::RemoteVideoOrientation(rotation) => {
+                self.handle_remote_video_orientation(connection, state, rotation)
+            }
+            ConnectionEvent::ReceivedDtmf(tone) => {
+                self.handle_received_dtmf(connection, state, tone)
+            }
+            ConnectionEvent::RemoteMediaClosed => {
+                self.handle_remote_media_closed(connection, state)
+            }
+            ConnectionEvent::NegotiationNeeded => self.handle_negotiation_needed(connection, state),
+            ConnectionEvent::IceRouteChanged(route) => {
+                self.handle_ice_route_changed(connection, state, route)
+            }
+            ConnectionEvent::IceRouteSettled(generation) => {
+                self.handle_ice_route_settled(connection, state, generation)
+            }
+            ConnectionEvent::MediaFlowTick => self.handle_media_flow_tick(connection, state),
+            ConnectionEvent::IceGatheringStateChanged(gathering_state) => {
+                self.handle_ice_gathering_state_changed(connection, state, gathering_state)
+            }
+            ConnectionEvent::DataChannelBufferedAmountChange(_) => {
+                self.handle_data_channel_buffered_amount_change(connection)
+            }
+            ConnectionEvent::LocalHangup(_) => Ok(()),
             ConnectionEvent::Synchronize(_) => Ok(()),
             ConnectionEvent::EndCall => Ok(()),
         }
@@ -407,7 +644,7 @@ where
         state: ConnectionState,
         answer: String,
     ) -> Result<()> {
-        if let ConnectionState::SendingOffer = state {
+        if let ConnectionState::SendingOffer | ConnectionState::IceRestarting = state {
             connection.set_state(ConnectionState::IceConnecting(false))?;
 
             let mut err_connection = connection.clone();
@@ -434,27 +671,95 @@ where
         state: ConnectionState,
         offer: String,
     ) -> Result<()> {
-        if let ConnectionState::Idle = state {
-            connection.set_state(ConnectionState::IceConnecting(false))?;
+        match state {
+            ConnectionState::Idle => {
+                connection.set_state(ConnectionState::IceConnecting(false))?;
 
-            let mut err_connection = connection.clone();
-            let handle_offer_future = lazy(move || {
-                if connection.terminating()? {
-                    return Ok(());
+                let mut err_connection = connection.clone();
+                let handle_offer_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.handle_offer(offer)
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "HandleOfferFuture failed")
+                });
+
+                self.worker_spawn(handle_offer_future);
+            }
+            ConnectionState::IceConnecting(_)
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                // Only a newer renegotiation of the established call
+                // is answered, not a duplicate of an earlier offer.
+                let renegotiation = match Offer::from_wire(&offer).renegotiation {
+                    Some(v) if connection.reconnection()?.remote_offer_received(v) => v,
+                    _ => {
+                        info!(
+                            "id: {}, ignoring offer, not a newer renegotiation",
+                            connection.id()
+                        );
+                        return Ok(());
+                    }
+                };
+                if !renegotiation.reset {
+                    return self.handle_ice_restart_offer(connection, offer);
                 }
-                connection.handle_offer(offer)
-            })
-            .map_err(move |err| {
-                err_connection.inject_internal_error(err, "HandleOfferFuture failed")
-            });
 
-            self.worker_spawn(handle_offer_future);
-        } else {
-            self.unexpected_state(state, "HandleOffer");
+                // The caller hard reset its connection, so rebuild
+                // ours to answer the new offer.
+                connection.reconnection()?.start_hard_reset();
+                connection.negotiation()?.reset();
+                connection.clear_pending_ice_candidates()?;
+                connection.set_state(ConnectionState::IceConnecting(false))?;
+                self.notify_observer(connection.clone(), ObserverEvent::ConnectionHardReset);
+
+                let mut err_connection = connection.clone();
+                let handle_reset_offer_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.handle_reset_offer(offer)
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "HandleResetOfferFuture failed")
+                });
+
+                self.worker_spawn(handle_reset_offer_future);
+            }
+            _ => self.unexpected_state(state, "HandleOffer"),
         }
         Ok(())
     }
 
+    /// The caller restarted ICE, so answer its offer on the current
+    /// PeerConnection.
+    fn handle_ice_restart_offer(
+        &mut self,
+        mut connection: Connection<T>,
+        offer: String,
+    ) -> Result<()> {
+        connection.reconnection()?.start_ice_restart();
+        connection.set_state(ConnectionState::IceConnecting(false))?;
+
+        let mut err_connection = connection.clone();
+        let handle_offer_future = lazy(move || {
+            if connection.terminating()? {
+                return Ok(());
+            }
+            connection.handle_offer(offer)
+        })
+        .map_err(move |err| {
+            err_connection.inject_internal_error(err, "HandleIceRestartOfferFuture failed")
+        });
+
+        self.worker_spawn(handle_offer_future);
+        Ok(())
+    }
+
     fn handle_have_local_remote_sdp(
         &mut self,
         connection: Connection<T>,
@@ -500,6 +805,7 @@ where
         connection: Connection<T>,
         state: ConnectionState,
         call_id: CallId,
+        hangup_type: HangupType,
     ) -> Result<()> {
         if connection.call_id() != call_id {
             warn!("Remote hangup for non-active call");
@@ -508,9 +814,11 @@ where
         match state {
             ConnectionState::IceConnecting(_)
             | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
             | ConnectionState::IceConnected
-            | ConnectionState::CallConnected => {
-                self.notify_observer(connection, ObserverEvent::RemoteHangup)
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                self.notify_observer(connection, ObserverEvent::RemoteHangup(hangup_type))
             }
             _ => self.unexpected_state(state, "RemoteHangup"),
         };
@@ -530,6 +838,8 @@ where
         match state {
             ConnectionState::IceConnecting(_) | ConnectionState::IceConnected => {
                 connection.set_state(ConnectionState::CallConnected)?;
+                self.restore_audio_status(connection.clone());
+                self.send_deferred_negotiation_offer(connection.clone());
                 self.notify_observer(connection, ObserverEvent::RemoteConnected);
             }
             _ => self.unexpected_state(state, "RemoteConnected"),
@@ -552,8 +862,10 @@ where
         match state {
             ConnectionState::IceConnecting(_)
             | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
             | ConnectionState::IceConnected
-            | ConnectionState::CallConnected => {
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
                 self.notify_observer(connection, ObserverEvent::RemoteVideoStatus(enable))
             }
             _ => self.unexpected_state(state, "RemoteVideoStatus"),
@@ -561,212 +873,1166 @@ where
         Ok(())
     }
 
-    fn handle_received_ice_candidates(
+    fn handle_remote_audio_status(
         &mut self,
         connection: Connection<T>,
         state: ConnectionState,
-        ice_candidates: Vec<IceCandidate>,
+        call_id: CallId,
+        enable: bool,
     ) -> Result<()> {
-        if let ConnectionState::Idle = state {
-            warn!("State is now idle, ignoring remote ICE candidates...");
+        if connection.call_id() != call_id {
+            warn!("Remote audio status change for non-active call");
             return Ok(());
         }
 
-        connection.buffer_remote_ice_candidates(ice_candidates)?;
-
         match state {
-            ConnectionState::IceConnecting(false) => {}
-            ConnectionState::IceConnecting(true)
+            ConnectionState::IceConnecting(_)
             | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
             | ConnectionState::IceConnected
-            | ConnectionState::CallConnected => connection.handle_remote_ice_updates()?,
-            _ => self.unexpected_state(state, "RemoteIceCandidate"),
-        }
-
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                self.notify_observer(connection, ObserverEvent::RemoteAudioStatus(enable))
+            }
+            _ => self.unexpected_state(state, "RemoteAudioStatus"),
+        };
         Ok(())
     }
 
-    fn handle_accept_call(
+    fn handle_remote_hold(
         &mut self,
         connection: Connection<T>,
         state: ConnectionState,
+        call_id: CallId,
+        on_hold: bool,
     ) -> Result<()> {
-        match state {
-            ConnectionState::IceConnecting(_)
-            | ConnectionState::IceReconnecting
-            | ConnectionState::IceConnected => {
-                // notify the peer via a data channel message.
-                let mut err_connection = connection.clone();
-                let connected_future = lazy(move || {
-                    if connection.terminating()? {
-                        return Ok(());
-                    }
-                    connection.send_connected()?;
-                    connection.set_state(ConnectionState::CallConnected)
-                })
-                .map_err(move |err| {
-                    err_connection.inject_internal_error(err, "Sending Connected failed")
-                });
+        if connection.call_id() != call_id {
+            warn!("Remote hold status change for non-active call");
+            return Ok(());
+        }
 
-                self.worker_spawn(connected_future);
+        match state {
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                connection
+                    .media_flow()?
+                    .set_remote_hold(on_hold, Instant::now());
+                self.notify_observer(connection, ObserverEvent::RemoteHold(on_hold))
             }
-            _ => self.unexpected_state(state, "AcceptCall"),
-        }
+            _ => self.unexpected_state(state, "RemoteHold"),
+        };
         Ok(())
     }
 
-    fn handle_local_hangup(
+    fn handle_remote_recording_status(
         &mut self,
         connection: Connection<T>,
         state: ConnectionState,
+        call_id: CallId,
+        recording: bool,
     ) -> Result<()> {
-        match state {
-            ConnectionState::Idle => self.unexpected_state(state, "LocalHangup"),
-            _ => {
-                let mut err_connection = connection.clone();
-                let hang_up_future = lazy(move || connection.send_hangup()).map_err(move |err| {
-                    err_connection.inject_internal_error(err, "Sending Hangup failed")
-                });
+        if connection.call_id() != call_id {
+            warn!("Remote recording status change for non-active call");
+            return Ok(());
+        }
 
-                self.worker_spawn(hang_up_future);
+        match state {
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                self.notify_observer(connection, ObserverEvent::RemoteRecordingStatus(recording))
             }
-        }
+            _ => self.unexpected_state(state, "RemoteRecordingStatus"),
+        };
         Ok(())
     }
 
-    fn handle_local_video_status(
+    fn handle_remote_screen_share_status(
         &mut self,
         connection: Connection<T>,
         state: ConnectionState,
-        enabled: bool,
+        call_id: CallId,
+        sharing: bool,
     ) -> Result<()> {
+        if connection.call_id() != call_id {
+            warn!("Remote screen share status change for non-active call");
+            return Ok(());
+        }
+
         match state {
             ConnectionState::IceConnecting(_)
             | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
             | ConnectionState::IceConnected
-            | ConnectionState::CallConnected => {
-                // notify the peer via a data channel message.
-                let mut err_connection = connection.clone();
-                let local_video_status_future = lazy(move || {
-                    if connection.terminating()? {
-                        return Ok(());
-                    }
-                    connection.send_video_status(enabled)
-                })
-                .map_err(move |err| {
-                    err_connection.inject_internal_error(err, "Sending local video status failed")
-                });
-
-                self.worker_spawn(local_video_status_future);
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                self.notify_observer(connection, ObserverEvent::RemoteScreenShareStatus(sharing))
             }
-            _ => self.unexpected_state(state, "LocalVideoStatus"),
+            _ => self.unexpected_state(state, "RemoteScreenShareStatus"),
         };
         Ok(())
     }
 
-    fn handle_local_ice_candidate(
+    fn handle_remote_app_message(
         &mut self,
         connection: Connection<T>,
         state: ConnectionState,
-        candidate: IceCandidate,
+        call_id: CallId,
+        payload: Vec<u8>,
     ) -> Result<()> {
-        info!("handle_local_ice_candidate()");
+        if connection.call_id() != call_id {
+            warn!("Remote app message for non-active call");
+            return Ok(());
+        }
 
         match state {
-            ConnectionState::Idle | ConnectionState::Terminating | ConnectionState::Closed => {
-                warn!("State is now idle or terminating, ignoring local ICE candidate...");
-            }
-            _ => {
-                // send signal message to the other side with the ICE
-                // candidate.
-                let mut err_connection = connection.clone();
-                let ice_update_future = lazy(move || {
-                    if connection.terminating()? {
-                        return Ok(());
-                    }
-                    connection.buffer_local_ice_candidate(candidate)
-                })
-                .map_err(move |err| {
-                    err_connection.inject_internal_error(err, "IceUpdateFuture failed")
-                });
-
-                self.worker_spawn(ice_update_future);
-            }
-        }
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => connection.app_message_received(&payload)?,
+            _ => self.unexpected_state(state, "RemoteAppMessage"),
+        };
         Ok(())
     }
 
-    fn handle_ice_connected(
+    fn handle_remote_video_layer_request(
         &mut self,
         connection: Connection<T>,
         state: ConnectionState,
+        call_id: CallId,
+        layer: usize,
     ) -> Result<()> {
-        match state {
-            ConnectionState::IceConnecting(_) => {
-                connection.set_state(ConnectionState::IceConnected)?;
-                // When ICE connects for the first time (or
-                // reconnects before the call was completely
-                // connected), notify only the *caller* about the
-                // ringing event.
-                if let CallDirection::OutGoing = connection.direction() {
-                    self.notify_observer(connection, ObserverEvent::ConnectionRinging);
-                }
-            }
-            ConnectionState::IceReconnecting => {
-                // ICE has reconnected after the call was
-                // previously connected.  Return to that state
-                // now.
-                connection.set_state(ConnectionState::CallConnected)?;
-                self.notify_observer(connection, ObserverEvent::ConnectionReconnected);
-            }
-            _ => (),
+        if connection.call_id() != call_id {
+            warn!("Remote video layer request for non-active call");
+            return Ok(());
+        }
+        if layer >= MAX_SIMULCAST_LAYERS {
+            warn!("Remote video layer request for unknown layer: {}", layer);
+            return Ok(());
         }
+
+        match state {
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => connection.remote_video_layer_requested(layer)?,
+            _ => self.unexpected_state(state, "RemoteVideoLayerRequest"),
+        };
         Ok(())
     }
 
-    fn handle_ice_connection_failed(
+    fn handle_remote_transfer(
         &mut self,
         connection: Connection<T>,
         state: ConnectionState,
+        call_id: CallId,
+        message: TransferMessage,
     ) -> Result<()> {
+        if connection.call_id() != call_id {
+            warn!("Remote transfer for non-active call");
+            return Ok(());
+        }
+
         match state {
-            ConnectionState::IceConnecting(_)
-            | ConnectionState::IceReconnecting
-            | ConnectionState::IceConnected
-            | ConnectionState::CallConnected => {
-                connection.set_state(ConnectionState::IceConnectionFailed)?;
-                // For callee -- the call was disconnected while answering/local_ringing
-                // For caller -- the recipient was unreachable
-                self.notify_observer(connection, ObserverEvent::ConnectionFailed);
-            }
-            _ => self.unexpected_state(state, "IceConnectionFailed"),
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => match message {
+                TransferMessage::Request(target) => {
+                    if connection
+                        .set_remote_transfer_target(Some(target))?
+                        .is_some()
+                    {
+                        info!("Remote transfer request replaces a pending request");
+                    }
+                    self.notify_observer(connection, ObserverEvent::TransferRequested)
+                }
+                TransferMessage::Accepted | TransferMessage::Declined => {
+                    if connection.set_transfer_target(None)?.is_none() {
+                        warn!("Remote transfer answer without a pending request");
+                        return Ok(());
+                    }
+                    if message == TransferMessage::Accepted {
+                        self.notify_observer(connection, ObserverEvent::TransferAccepted)
+                    } else {
+                        self.notify_observer(connection, ObserverEvent::TransferDeclined)
+                    }
+                }
+            },
+            _ => self.unexpected_state(state, "RemoteTransfer"),
         };
         Ok(())
     }
 
-    fn handle_ice_connection_disconnected(
+    fn handle_remote_negotiation(
         &mut self,
         connection: Connection<T>,
         state: ConnectionState,
+        call_id: CallId,
+        message: NegotiationMessage,
     ) -> Result<()> {
+        if connection.call_id() != call_id {
+            warn!("Remote negotiation for non-active call");
+            return Ok(());
+        }
+
         match state {
-            ConnectionState::IceConnecting(_) | ConnectionState::IceConnected => {
-                // ICE disconnected *before* the call was
+            ConnectionState::IceReconnecting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                let mut rollback = false;
+                match message {
+                    NegotiationMessage::Offer(_) => {
+                        let polite = connection.direction() == CallDirection::InComing;
+                        match connection.negotiation()?.offer_received(polite) {
+                            RemoteOfferAction::Answer => {}
+                            RemoteOfferAction::RollbackAndAnswer => {
+                                info!("Negotiation offers collided, rolling back ours");
+                                rollback = true;
+                            }
+                            RemoteOfferAction::Ignore => {
+                                info!("Negotiation offers collided, ignoring theirs");
+                                return Ok(());
+                            }
+                        }
+                    }
+                    NegotiationMessage::Answer(_) => {
+                        if !connection.negotiation()?.answer_received() {
+                            warn!("Remote negotiation answer without a pending offer");
+                            return Ok(());
+                        }
+                    }
+                }
+
+                let mut err_connection = connection.clone();
+                let negotiation_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    match message {
+                        NegotiationMessage::Offer(offer) => {
+                            connection.handle_negotiation_offer(offer, rollback)
+                        }
+                        NegotiationMessage::Answer(answer) => {
+                            connection.handle_negotiation_answer(answer)
+                        }
+                    }
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Negotiation failed")
+                });
+
+                self.worker_spawn(negotiation_future);
+            }
+            // A hard reset or an ICE restart negotiates all the tracks
+            // anew.
+            _ => info!("handle_remote_negotiation(): ignoring in state {}", state),
+        };
+        Ok(())
+    }
+
+    fn handle_remote_video_orientation(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        rotation: i32,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceConnecting(_)
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                if let Some(degrees) = connection.update_remote_video_orientation(rotation)? {
+                    self.notify_observer(connection, ObserverEvent::RemoteVideoOrientation(degrees))
+                }
+            }
+            _ => self.unexpected_state(state, "RemoteVideoOrientation"),
+        };
+        Ok(())
+    }
+
+    fn handle_received_dtmf(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        tone: char,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::OnHold => {
+                self.notify_observer(connection, ObserverEvent::ReceivedDtmf(tone))
+            }
+            _ => self.unexpected_state(state, "ReceivedDtmf"),
+        };
+        Ok(())
+    }
+
+    /// The remote peer closed its media, which it only does when the
+    /// call ends, so there is no point waiting for ICE to time out.
+    fn handle_remote_media_closed(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::OnHold => {
+                self.notify_observer(connection, ObserverEvent::RemoteMediaClosed)
+            }
+            // The hangup of the remote peer may arrive first.
+            _ => info!("handle_remote_media_closed(): ignoring in state {}", state),
+        };
+        Ok(())
+    }
+
+    fn handle_negotiation_needed(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceReconnecting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                if !connection.negotiation()?.negotiation_needed() {
+                    info!("handle_negotiation_needed(): offering once answered");
+                    return Ok(());
+                }
+
+                let mut err_connection = connection.clone();
+                let negotiation_offer_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.send_negotiation_offer()
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Sending negotiation offer failed")
+                });
+
+                self.worker_spawn(negotiation_offer_future);
+            }
+            ConnectionState::IceConnecting(_) | ConnectionState::IceConnected => {
+                info!("handle_negotiation_needed(): offering once connected");
+                connection.negotiation()?.defer();
+            }
+            // Covered by the initial offer, or by the offer of a hard
+            // reset or an ICE restart.
+            _ => info!("handle_negotiation_needed(): ignoring in state {}", state),
+        };
+        Ok(())
+    }
+
+    fn handle_ice_route_changed(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        route: IceRoute,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceConnecting(_)
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                connection.update_ice_route(route)?;
+                self.notify_observer(connection, ObserverEvent::NetworkRouteChanged);
+            }
+            _ => self.unexpected_state(state, "IceRouteChanged"),
+        };
+        Ok(())
+    }
+
+    fn handle_ice_route_settled(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        generation: u32,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                let settled = connection.route_monitor()?.settled(generation);
+                if settled.is_some() {
+                    self.notify_observer(connection, ObserverEvent::IceRouteChanged);
+                }
+            }
+            _ => debug!("Ignoring ICE route settled, generation: {}", generation),
+        };
+        Ok(())
+    }
+
+    fn handle_media_flow_tick(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::CallConnected => {
+                let mut err_connection = connection.clone();
+                let sample_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.sample_media_flow()
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Sampling media flow failed")
+                });
+
+                self.worker_spawn(sample_future);
+            }
+            // No media is expected while on hold or reconnecting, so
+            // the media gets a full timeout once the call goes on.
+            _ => connection.media_flow()?.restart(Instant::now()),
+        };
+        Ok(())
+    }
+
+    fn handle_ice_gathering_state_changed(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        gathering_state: IceGatheringState,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::Idle | ConnectionState::Terminating | ConnectionState::Closed => {
+                self.unexpected_state(state, "IceGatheringStateChanged")
+            }
+            // Gathering completes regardless of whether ICE connected
+            // or failed, e.g. with only host candidates when no STUN
+            // or TURN server is reachable.
+            _ => self.notify_observer(
+                connection,
+                ObserverEvent::IceGatheringStateChanged(gathering_state),
+            ),
+        }
+        Ok(())
+    }
+
+    fn handle_data_channel_buffered_amount_change(
+        &mut self,
+        connection: Connection<T>,
+    ) -> Result<()> {
+        // Control messages go out before the application resumes.
+        connection.send_queued_control_messages()?;
+        if connection.data_channel_buffered_amount_low()? {
+            self.notify_observer(connection, ObserverEvent::DataChannelBufferedAmountLow);
+        }
+        Ok(())
+    }
+
+    fn handle_received_ice_candidates(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        ice_candidates: Vec<IceCandidate>,
+    ) -> Result<()> {
+        if let ConnectionState::Idle = state {
+            warn!("State is now idle, ignoring remote ICE candidates...");
+            return Ok(());
+        }
+
+        connection.buffer_remote_ice_candidates(ice_candidates)?;
+
+        match state {
+            ConnectionState::IceConnecting(false) => {}
+            ConnectionState::IceConnecting(true)
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => connection.handle_remote_ice_updates()?,
+            _ => self.unexpected_state(state, "RemoteIceCandidate"),
+        }
+
+        Ok(())
+    }
+
+    fn handle_accept_call(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceConnecting(_)
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceConnected => {
+                // notify the peer via a data channel message.
+                let mut err_connection = connection.clone();
+                let connected_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.send_connected()?;
+                    connection.set_state(ConnectionState::CallConnected)?;
+                    connection.restore_audio_status()?;
+                    connection.send_deferred_negotiation_offer()
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Sending Connected failed")
+                });
+
+                self.worker_spawn(connected_future);
+            }
+            _ => self.unexpected_state(state, "AcceptCall"),
+        }
+        Ok(())
+    }
+
+    fn handle_local_hangup(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::Idle => self.unexpected_state(state, "LocalHangup"),
+            _ => {
+                let mut err_connection = connection.clone();
+                let hang_up_future =
+                    lazy(move || connection.send_hangup(hangup_type)).map_err(move |err| {
+                        err_connection.inject_internal_error(err, "Sending Hangup failed")
+                    });
+
+                self.worker_spawn(hang_up_future);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_local_video_status(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        enabled: bool,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceConnecting(_)
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                // notify the peer via a data channel message.
+                let mut err_connection = connection.clone();
+                let local_video_status_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.send_video_status(enabled)
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Sending local video status failed")
+                });
+
+                self.worker_spawn(local_video_status_future);
+            }
+            _ => self.unexpected_state(state, "LocalVideoStatus"),
+        };
+        Ok(())
+    }
+
+    fn handle_local_screen_share_status(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        sharing: bool,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceConnecting(_)
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                // enable the screen share track and notify the peer
+                // via a data channel message.
+                let mut err_connection = connection.clone();
+                let local_screen_share_status_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.send_screen_share_status(sharing)
+                })
+                .map_err(move |err| {
+                    err_connection
+                        .inject_internal_error(err, "Sending local screen share status failed")
+                });
+
+                self.worker_spawn(local_screen_share_status_future);
+            }
+            _ => self.unexpected_state(state, "LocalScreenShareStatus"),
+        };
+        Ok(())
+    }
+
+    fn handle_local_audio_status(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        enabled: bool,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                // notify the peer via a data channel message.
+                let mut err_connection = connection.clone();
+                let local_audio_status_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.send_audio_status(enabled)
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Sending local audio status failed")
+                });
+
+                self.worker_spawn(local_audio_status_future);
+            }
+            ConnectionState::Idle | ConnectionState::Terminating | ConnectionState::Closed => {
+                self.unexpected_state(state, "LocalAudioStatus")
+            }
+            _ => {
+                // The remote peer assumes unmuted audio, the status
+                // is sent once the call connects.
+                debug!("Deferring local audio status, state: {}", state);
+            }
+        };
+        Ok(())
+    }
+
+    fn handle_local_hold(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        on_hold: bool,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                connection.set_hold_status(on_hold)?;
+                // While reconnecting, the state is updated once ICE
+                // reconnects.
+                if let ConnectionState::CallConnected | ConnectionState::OnHold = state {
+                    connection.set_state(connection.connected_state()?)?;
+                }
+
+                // pause or resume the media, and notify the peer via
+                // a data channel message.
+                let mut err_connection = connection.clone();
+                let local_hold_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.apply_hold()?;
+                    connection.send_hold(on_hold)
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Sending local hold status failed")
+                });
+
+                self.worker_spawn(local_hold_future);
+            }
+            _ => self.unexpected_state(state, "LocalHold"),
+        };
+        Ok(())
+    }
+
+    fn handle_local_transfer(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        target: String,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::CallConnected | ConnectionState::OnHold => {
+                if connection.transfer_target()?.is_some() {
+                    warn!("Transfer already pending, ignoring transfer to: {}", target);
+                    return Ok(());
+                }
+                connection.set_transfer_target(Some(target.clone()))?;
+
+                // send the transfer target to the peer, which answers
+                // via a data channel message.
+                let mut err_connection = connection.clone();
+                let local_transfer_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.send_transfer(&TransferMessage::Request(target))
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Sending transfer request failed")
+                });
+
+                self.worker_spawn(local_transfer_future);
+            }
+            _ => self.unexpected_state(state, "LocalTransfer"),
+        };
+        Ok(())
+    }
+
+    fn handle_transfer_response(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        accepted: bool,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                if connection.set_remote_transfer_target(None)?.is_none() {
+                    warn!("Transfer response without a pending request");
+                    return Ok(());
+                }
+
+                let message = if accepted {
+                    TransferMessage::Accepted
+                } else {
+                    TransferMessage::Declined
+                };
+                let mut err_connection = connection.clone();
+                let transfer_response_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.send_transfer(&message)
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "Sending transfer response failed")
+                });
+
+                self.worker_spawn(transfer_response_future);
+            }
+            _ => self.unexpected_state(state, "TransferResponse"),
+        };
+        Ok(())
+    }
+
+    fn handle_local_ice_candidate(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        candidate: IceCandidate,
+    ) -> Result<()> {
+        info!("handle_local_ice_candidate()");
+
+        match state {
+            ConnectionState::Idle | ConnectionState::Terminating | ConnectionState::Closed => {
+                warn!("State is now idle or terminating, ignoring local ICE candidate...");
+            }
+            _ => {
+                // send signal message to the other side with the ICE
+                // candidate.
+                let mut err_connection = connection.clone();
+                let ice_update_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.buffer_local_ice_candidate(candidate)
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "IceUpdateFuture failed")
+                });
+
+                self.worker_spawn(ice_update_future);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_ice_connected(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        let resetting = connection.reconnection()?.resetting();
+        match state {
+            ConnectionState::IceConnecting(_) if resetting => {
+                // ICE has connected on the PeerConnection created by
+                // a hard reset, or after an ICE restart.  Return to
+                // the connected state.
+                let ice_restart = connection.reconnection()?.restarting_ice();
+                connection.reconnection()?.connected();
+                connection.set_state(connection.connected_state()?)?;
+                if let CallDirection::OutGoing = connection.direction() {
+                    // An ICE restart keeps the DataChannel.
+                    if !ice_restart {
+                        self.restore_media_status(connection.clone());
+                    }
+                }
+                self.notify_observer(connection, ObserverEvent::ConnectionReconnected);
+            }
+            ConnectionState::IceConnecting(_) => {
+                connection.set_state(ConnectionState::IceConnected)?;
+                // The route selected when ICE first connects is the
+                // baseline for reporting route changes.
+                connection.route_monitor()?.connected();
+                // When ICE connects for the first time (or
+                // reconnects before the call was completely
+                // connected), notify only the *caller* about the
+                // ringing event.
+                if let CallDirection::OutGoing = connection.direction() {
+                    self.notify_observer(connection, ObserverEvent::ConnectionRinging);
+                }
+            }
+            ConnectionState::IceReconnecting => {
+                // ICE has reconnected after the call was
+                // previously connected.  Return to that state
+                // now.
+                connection.reconnection()?.connected();
+                connection.set_state(connection.connected_state()?)?;
+                self.notify_observer(connection, ObserverEvent::ConnectionReconnected);
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn handle_ice_connection_failed(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        let recovering = match state {
+            ConnectionState::IceReconnecting => true,
+            _ => connection.reconnection()?.resetting(),
+        };
+        if recovering {
+            match connection.ice_failed()? {
+                IceRecovery::RestartIce => {
+                    return self.handle_ice_failed_restart(connection, state)
+                }
+                IceRecovery::HardReset => return self.handle_hard_reset(connection, state),
+                IceRecovery::Fail => {}
+            }
+        }
+
+        match state {
+            ConnectionState::IceConnecting(_)
+            | ConnectionState::IceReconnecting
+            | ConnectionState::IceConnected
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
+                connection.set_state(ConnectionState::IceConnectionFailed)?;
+                // For callee -- the call was disconnected while answering/local_ringing
+                // For caller -- the recipient was unreachable
+                self.notify_observer(connection, ObserverEvent::ConnectionFailed);
+            }
+            _ => self.unexpected_state(state, "IceConnectionFailed"),
+        };
+        Ok(())
+    }
+
+    /// ICE failed while reconnecting, so restart ICE before
+    /// resorting to a hard reset.
+    ///
+    /// The callee waits for the caller to restart, as it would for a
+    /// hard reset.
+    fn handle_ice_failed_restart(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        if let CallDirection::InComing = connection.direction() {
+            return self.handle_hard_reset(connection, state);
+        }
+
+        info!(
+            "id: {}, ice failed while reconnecting, restarting ice",
+            connection.id()
+        );
+        if let ConnectionState::IceConnecting(_) = state {
+            // The ICE restart or hard reset under way failed.
+            connection.set_state(ConnectionState::IceReconnecting)?;
+            return self.handle_restart_ice(connection, ConnectionState::IceReconnecting);
+        }
+        self.handle_restart_ice(connection, state)
+    }
+
+    /// ICE recovery failed, so rebuild the PeerConnection from
+    /// scratch.
+    ///
+    /// The caller drives the hard reset by sending a new offer.  The
+    /// callee waits for that offer, see `handle_offer()`.
+    fn handle_hard_reset(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        if let CallDirection::InComing = connection.direction() {
+            info!("handle_hard_reset(): waiting for the caller to reset");
+            if let ConnectionState::CallConnected | ConnectionState::OnHold = state {
+                connection.set_state(ConnectionState::IceReconnecting)?;
+                self.notify_observer(connection, ObserverEvent::ConnectionReconnecting);
+            }
+            return Ok(());
+        }
+
+        connection.reconnection()?.start_hard_reset();
+        connection.negotiation()?.reset();
+        connection.clear_pending_ice_candidates()?;
+        connection.set_state(ConnectionState::SendingOffer)?;
+        self.notify_observer(connection.clone(), ObserverEvent::ConnectionHardReset);
+
+        let mut err_connection = connection.clone();
+        let hard_reset_future = lazy(move || {
+            if connection.terminating()? {
+                return Ok(());
+            }
+            connection.hard_reset()
+        })
+        .map_err(move |err| err_connection.inject_internal_error(err, "HardResetFuture failed"));
+
+        self.worker_spawn(hard_reset_future);
+        Ok(())
+    }
+
+    /// The application asked for an ICE restart, after a network
+    /// change or along with new ICE servers.
+    ///
+    /// The caller renegotiates with new ICE credentials, keeping the
+    /// PeerConnection.  The callee waits for that offer, which it
+    /// answers on its current PeerConnection.
+    fn handle_restart_ice(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        if let CallDirection::InComing = connection.direction() {
+            info!("handle_restart_ice(): waiting for the caller to restart");
+            return Ok(());
+        }
+
+        match state {
+            ConnectionState::CallConnected
+            | ConnectionState::OnHold
+            | ConnectionState::IceReconnecting => {
+                connection.reconnection()?.start_ice_restart();
+                connection.negotiation()?.reset();
+                connection.clear_pending_ice_candidates()?;
+                connection.set_state(ConnectionState::IceRestarting)?;
+
+                let mut err_connection = connection.clone();
+                let restart_ice_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.restart_ice()
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "RestartIceFuture failed")
+                });
+
+                self.worker_spawn(restart_ice_future);
+            }
+            // Before the call connects, or while it is already
+            // renegotiating, the new ICE servers apply without a
+            // restart.
+            _ => info!("handle_restart_ice(): ignoring in state: {}", state),
+        }
+        Ok(())
+    }
+
+    /// The renegotiation offer sent by a hard reset went unanswered.
+    ///
+    /// Roll back the offer and send it again.  Once the retries are
+    /// exhausted, roll back and wait for the remote peer or the
+    /// application to act, rather than leaving the offer outstanding.
+    fn handle_renegotiation_timeout(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        offer: u32,
+    ) -> Result<()> {
+        let mut reconnection = connection.reconnection()?;
+        match state {
+            ConnectionState::SendingOffer | ConnectionState::IceRestarting
+                if reconnection.offer_outstanding(offer) =>
+            {
+                let retry = reconnection.retry_offer();
+                drop(reconnection);
+                warn!(
+                    "id: {}, renegotiation offer unanswered, retry: {}",
+                    connection.id(),
+                    retry
+                );
+
+                if !retry {
+                    connection.set_state(ConnectionState::IceReconnecting)?;
+                    self.notify_observer(connection.clone(), ObserverEvent::RenegotiationTimeout);
+                    self.schedule_recovery(connection.clone());
+                }
+
+                let mut err_connection = connection.clone();
+                let renegotiation_timeout_future = lazy(move || {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    if retry {
+                        connection.retry_renegotiation_offer()
+                    } else {
+                        connection.rollback_local_description()
+                    }
+                })
+                .map_err(move |err| {
+                    err_connection.inject_internal_error(err, "RenegotiationTimeoutFuture failed")
+                });
+
+                self.worker_spawn(renegotiation_timeout_future);
+            }
+            _ => debug!("Ignoring renegotiation timeout, offer: {}", offer),
+        }
+        Ok(())
+    }
+
+    /// Re-send the video, screen share, audio, hold and recording
+    /// status of the PeerConnection replaced by a hard reset.
+    fn restore_media_status(&mut self, connection: Connection<T>) {
+        let mut err_connection = connection.clone();
+        let restore_future = lazy(move || {
+            if connection.terminating()? {
+                return Ok(());
+            }
+            connection.restore_video_status()?;
+            connection.restore_screen_share_status()?;
+            connection.restore_audio_status()?;
+            connection.restore_hold_status()?;
+            connection.restore_recording_status()
+        })
+        .map_err(move |err| {
+            err_connection.inject_internal_error(err, "Restoring media status failed")
+        });
+
+        self.worker_spawn(restore_future);
+    }
+
+    /// Send a muted audio status once the call connects, as the
+    /// remote peer assumes unmuted audio.
+    fn restore_audio_status(&mut self, connection: Connection<T>) {
+        let mut err_connection = connection.clone();
+        let restore_future = lazy(move || {
+            if connection.terminating()? {
+                return Ok(());
+            }
+            connection.restore_audio_status()
+        })
+        .map_err(move |err| {
+            err_connection.inject_internal_error(err, "Restoring audio status failed")
+        });
+
+        self.worker_spawn(restore_future);
+    }
+
+    fn send_deferred_negotiation_offer(&mut self, connection: Connection<T>) {
+        let mut err_connection = connection.clone();
+        let negotiation_offer_future = lazy(move || {
+            if connection.terminating()? {
+                return Ok(());
+            }
+            connection.send_deferred_negotiation_offer()
+        })
+        .map_err(move |err| {
+            err_connection.inject_internal_error(err, "Sending negotiation offer failed")
+        });
+
+        self.worker_spawn(negotiation_offer_future);
+    }
+
+    fn handle_ice_connection_disconnected(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceConnecting(_) | ConnectionState::IceConnected => {
+                // ICE disconnected *before* the call was
                 // connected, so simply go back to the
                 // IceConnecting state.
                 connection.set_state(ConnectionState::IceConnecting(true))?;
             }
-            ConnectionState::CallConnected => {
+            ConnectionState::CallConnected | ConnectionState::OnHold => {
                 // ICE disconnected *after* the call was
-                // connected, go to IceReconnecting state.
+                // connected, go to IceReconnecting state and try to
+                // recover.
                 connection.set_state(ConnectionState::IceReconnecting)?;
-                self.notify_observer(connection, ObserverEvent::ConnectionReconnecting);
+                self.notify_observer(connection.clone(), ObserverEvent::ConnectionReconnecting);
+                self.schedule_recovery(connection);
+            }
+            ConnectionState::IceRestarting => {
+                // The ICE restart under way replaces the lost route.
+                info!("ICE disconnected while restarting ICE");
             }
             _ => self.unexpected_state(state, "IceConnectionDisconnected"),
         };
         Ok(())
     }
 
+    /// Schedule an automatic recovery attempt, see
+    /// `Connection::schedule_recovery()`.
+    fn schedule_recovery(&mut self, connection: Connection<T>) {
+        let mut err_connection = connection.clone();
+        let schedule_recovery_future = lazy(move || {
+            if connection.terminating()? {
+                return Ok(());
+            }
+            connection.schedule_recovery()
+        })
+        .map_err(move |err| {
+            err_connection.inject_internal_error(err, "ScheduleRecoveryFuture failed")
+        });
+
+        self.worker_spawn(schedule_recovery_future);
+    }
+
+    /// The backoff of an automatic recovery attempt elapsed.
+    ///
+    /// If still reconnecting, restart ICE as the application would
+    /// with `CallManager::restart_ice()`.
+    fn handle_recovery_timeout(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        recovery: u32,
+    ) -> Result<()> {
+        match state {
+            ConnectionState::IceReconnecting
+                if connection.reconnection()?.recovery_outstanding(recovery) =>
+            {
+                info!(
+                    "id: {}, automatic recovery, restarting ice",
+                    connection.id()
+                );
+                self.handle_restart_ice(connection, state)
+            }
+            _ => {
+                info!("handle_recovery_timeout(): ignoring in state: {}", state);
+                Ok(())
+            }
+        }
+    }
+
     fn handle_internal_error(
         &mut self,
         connection: Connection<T>,
@@ -796,8 +2062,10 @@ where
         match state {
             ConnectionState::IceConnecting(_)
             | ConnectionState::IceReconnecting
+            | ConnectionState::IceRestarting
             | ConnectionState::IceConnected
-            | ConnectionState::CallConnected => {
+            | ConnectionState::CallConnected
+            | ConnectionState::OnHold => {
                 let mut err_connection = connection.clone();
                 let add_stream_future = lazy(move || {
                     if connection.terminating()? {
@@ -832,7 +2100,13 @@ where
                     "onDataChannel should only happen for incoming calls"
                 );
                 connection.on_data_channel(data_channel, dc_observer_handle)?;
-                self.notify_observer(notify_handle, ObserverEvent::ConnectionRinging);
+                if connection.reconnection()?.has_reset() {
+                    // The DataChannel replaces the one released by a
+                    // hard reset, the call is already ringing.
+                    self.restore_media_status(notify_handle);
+                } else {
+                    self.notify_observer(notify_handle, ObserverEvent::ConnectionRinging);
+                }
             }
             _ => self.unexpected_state(state, "OnDataChannel"),
         }
@@ -0,0 +1,252 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! A call between the local client and many remote peers.
+//!
+//! Unlike a direct call, where the devices of a single remote peer
+//! race to answer, every participant of a group call stays in the
+//! call.  Each participant tracks its own connection state and media
+//! stream, all under the one CallId of the group call.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::common::{CallId, Result};
+use crate::core::call_mutex::CallMutex;
+use crate::core::platform::Platform;
+use crate::error::RingRtcError;
+
+/// Identifies a participant within a group call, assigned by the
+/// application.
+pub type ParticipantId = u32;
+
+/// Tracks the connection state of a group call participant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticipantState {
+    /// Added to the call, not yet connected.
+    Joining,
+    /// Connected, with media routed to the application.
+    Joined,
+    /// Disconnected from the call.
+    Left,
+}
+
+impl fmt::Display for ParticipantState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A remote peer taking part in a group call.
+struct Participant<T>
+where
+    T: Platform,
+{
+    /// The application specific remote peer of the participant.
+    remote_peer:  <T as Platform>::AppRemotePeer,
+    /// The connection state of the participant.
+    state:        ParticipantState,
+    /// The media stream received from the participant, if any.
+    media_stream: Option<<T as Platform>::AppMediaStream>,
+}
+
+/// Represents the participants of a group call.
+pub struct GroupCall<T>
+where
+    T: Platform,
+{
+    /// Unique 64-bit number identifying the call.
+    call_id:      CallId,
+    /// The participants of the call, by participant ID.
+    participants: Arc<CallMutex<HashMap<ParticipantId, Participant<T>>>>,
+}
+
+impl<T> fmt::Display for GroupCall<T>
+where
+    T: Platform,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let participants = match self.participants.lock() {
+            Ok(v) => format!("{}", v.len()),
+            Err(_) => "unavailable".to_string(),
+        };
+        write!(
+            f,
+            "call_id: {}, participants: {}",
+            self.call_id, participants
+        )
+    }
+}
+
+impl<T> fmt::Debug for GroupCall<T>
+where
+    T: Platform,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl<T> Clone for GroupCall<T>
+where
+    T: Platform,
+{
+    fn clone(&self) -> Self {
+        Self {
+            call_id:      self.call_id,
+            participants: Arc::clone(&self.participants),
+        }
+    }
+}
+
+impl<T> GroupCall<T>
+where
+    T: Platform,
+{
+    /// Create a new GroupCall without participants.
+    pub fn new(call_id: CallId) -> Self {
+        Self {
+            call_id,
+            participants: Arc::new(CallMutex::new(HashMap::new(), "participants")),
+        }
+    }
+
+    /// Return the Call identifier.
+    pub fn call_id(&self) -> CallId {
+        self.call_id
+    }
+
+    /// Add a participant in the `Joining` state.
+    ///
+    /// A participant that left may be added again.
+    pub fn add_participant(
+        &self,
+        participant_id: ParticipantId,
+        remote_peer: <T as Platform>::AppRemotePeer,
+    ) -> Result<()> {
+        let mut participants = self.participants.lock()?;
+        if let Some(participant) = participants.get(&participant_id) {
+            if participant.state != ParticipantState::Left {
+                return Err(
+                    RingRtcError::ParticipantAlreadyAdded(self.call_id, participant_id).into(),
+                );
+            }
+        }
+
+        let participant = Participant {
+            remote_peer,
+            state: ParticipantState::Joining,
+            media_stream: None,
+        };
+        participants.insert(participant_id, participant);
+        Ok(())
+    }
+
+    /// Return the IDs of the participants, in ascending order.
+    pub fn participant_ids(&self) -> Result<Vec<ParticipantId>> {
+        let participants = self.participants.lock()?;
+        let mut participant_ids: Vec<ParticipantId> = participants.keys().cloned().collect();
+        participant_ids.sort();
+        Ok(participant_ids)
+    }
+
+    /// Return the state of a participant.
+    pub fn participant_state(&self, participant_id: ParticipantId) -> Result<ParticipantState> {
+        let participants = self.participants.lock()?;
+        match participants.get(&participant_id) {
+            Some(participant) => Ok(participant.state),
+            None => Err(RingRtcError::ParticipantNotFound(self.call_id, participant_id).into()),
+        }
+    }
+
+    /// Return the remote peer of a participant.
+    pub fn remote_peer(
+        &self,
+        participant_id: ParticipantId,
+    ) -> Result<<T as Platform>::AppRemotePeer> {
+        let participants = self.participants.lock()?;
+        match participants.get(&participant_id) {
+            Some(participant) => Ok(participant.remote_peer.clone()),
+            None => Err(RingRtcError::ParticipantNotFound(self.call_id, participant_id).into()),
+        }
+    }
+
+    /// Returns `true` if a media stream is routed for the participant.
+    pub fn has_media_stream(&self, participant_id: ParticipantId) -> Result<bool> {
+        let participants = self.participants.lock()?;
+        match participants.get(&participant_id) {
+            Some(participant) => Ok(participant.media_stream.is_some()),
+            None => Err(RingRtcError::ParticipantNotFound(self.call_id, participant_id).into()),
+        }
+    }
+
+    /// Move a participant to `new_state`, returning the previous
+    /// state.
+    ///
+    /// Leaving releases the participant's media stream.
+    pub fn set_participant_state(
+        &self,
+        participant_id: ParticipantId,
+        new_state: ParticipantState,
+    ) -> Result<ParticipantState> {
+        let mut participants = self.participants.lock()?;
+        match participants.get_mut(&participant_id) {
+            Some(participant) => {
+                let old_state = participant.state;
+                participant.state = new_state;
+                if new_state == ParticipantState::Left {
+                    participant.media_stream = None;
+                }
+                Ok(old_state)
+            }
+            None => Err(RingRtcError::ParticipantNotFound(self.call_id, participant_id).into()),
+        }
+    }
+
+    /// Store the media stream received from a participant, replacing
+    /// any previous one.
+    ///
+    /// Media from a participant that left is dropped.
+    pub fn set_media_stream(
+        &self,
+        participant_id: ParticipantId,
+        media_stream: <T as Platform>::AppMediaStream,
+    ) -> Result<()> {
+        let mut participants = self.participants.lock()?;
+        match participants.get_mut(&participant_id) {
+            Some(participant) => {
+                if participant.state != ParticipantState::Left {
+                    participant.media_stream = Some(media_stream);
+                }
+                Ok(())
+            }
+            None => Err(RingRtcError::ParticipantNotFound(self.call_id, participant_id).into()),
+        }
+    }
+
+    /// Call `f` with the remote peer and media stream of a joined
+    /// participant.
+    ///
+    /// Does nothing until the participant has joined and its media
+    /// stream has arrived, whichever comes last.
+    pub fn with_joined_media<F>(&self, participant_id: ParticipantId, f: F) -> Result<()>
+    where
+        F: FnOnce(&<T as Platform>::AppRemotePeer, &<T as Platform>::AppMediaStream) -> Result<()>,
+    {
+        let participants = self.participants.lock()?;
+        match participants.get(&participant_id) {
+            Some(Participant {
+                remote_peer,
+                state: ParticipantState::Joined,
+                media_stream: Some(media_stream),
+            }) => f(remote_peer, media_stream),
+            Some(_) => Ok(()),
+            None => Err(RingRtcError::ParticipantNotFound(self.call_id, participant_id).into()),
+        }
+    }
+}
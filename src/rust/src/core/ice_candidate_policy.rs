@@ -0,0 +1,133 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Which ICE candidates a connection exchanges with the remote peer.
+//!
+//! Host candidates carry the local IP addresses, and server
+//! reflexive ones the public address, so privacy-conscious
+//! deployments may keep only relay candidates, forcing the media
+//! through TURN.  The policy filters both the local candidates sent
+//! to the remote peer and the remote candidates added to the
+//! PeerConnection.
+
+use std::fmt;
+
+use crate::webrtc::ice_candidate::IceCandidate;
+
+/// The ICE candidates a connection exchanges, by default all of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IceCandidatePolicy {
+    /// Only exchange relay candidates.
+    pub relay_only: bool,
+    /// Do not exchange IPv6 candidates.
+    pub no_ipv6:    bool,
+    /// Do not exchange host candidates.
+    pub no_host:    bool,
+}
+
+impl fmt::Display for IceCandidatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "relay_only: {}, no_ipv6: {}, no_host: {}",
+            self.relay_only, self.no_ipv6, self.no_host
+        )
+    }
+}
+
+impl IceCandidatePolicy {
+    /// Returns `true` if the policy lets any candidate through.
+    pub fn allows_all(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns `true` if the policy lets `candidate` through.
+    ///
+    /// A restrictive policy drops candidates it cannot parse.
+    pub fn allows(&self, candidate: &IceCandidate) -> bool {
+        if self.allows_all() {
+            return true;
+        }
+
+        let (address, candidate_type) = match parse_candidate(&candidate.sdp) {
+            Some(parsed) => parsed,
+            None => return false,
+        };
+
+        !((self.relay_only && candidate_type != "relay")
+            || (self.no_host && candidate_type == "host")
+            || (self.no_ipv6 && address.contains(':')))
+    }
+}
+
+/// Returns the connection address and type of a `candidate` attribute,
+/// e.g. `candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host`.
+fn parse_candidate(sdp: &str) -> Option<(&str, &str)> {
+    let sdp = sdp.trim_start_matches("a=");
+    if !sdp.starts_with("candidate:") {
+        return None;
+    }
+
+    let mut fields = sdp.split_whitespace();
+    let address = fields.nth(4)?;
+    let _port = fields.next()?;
+    match fields.next()? {
+        "typ" => Some((address, fields.next()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(sdp: &str) -> IceCandidate {
+        IceCandidate::new("0".to_owned(), 0, sdp.to_owned())
+    }
+
+    #[test]
+    fn check_allows() {
+        let host = candidate("candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host");
+        let host_ipv6 = candidate("a=candidate:2 1 udp 2122262783 2001:db8::1 50001 typ host");
+        let srflx = candidate(
+            "candidate:3 1 udp 1686052607 198.51.100.1 50002 typ srflx raddr 192.0.2.1 rport 50000",
+        );
+        let relay = candidate("candidate:4 1 udp 41885439 203.0.113.1 3478 typ relay");
+        let fake = candidate("ICE-1");
+
+        let policy = IceCandidatePolicy::default();
+        for c in &[&host, &host_ipv6, &srflx, &relay, &fake] {
+            assert!(policy.allows(c));
+        }
+
+        let policy = IceCandidatePolicy {
+            relay_only: true,
+            ..Default::default()
+        };
+        assert!(!policy.allows(&host));
+        assert!(!policy.allows(&srflx));
+        assert!(policy.allows(&relay));
+        assert!(!policy.allows(&fake));
+
+        let policy = IceCandidatePolicy {
+            no_host: true,
+            ..Default::default()
+        };
+        assert!(!policy.allows(&host));
+        assert!(!policy.allows(&host_ipv6));
+        assert!(policy.allows(&srflx));
+        assert!(policy.allows(&relay));
+
+        let policy = IceCandidatePolicy {
+            no_ipv6: true,
+            ..Default::default()
+        };
+        assert!(policy.allows(&host));
+        assert!(!policy.allows(&host_ipv6));
+        assert!(policy.allows(&relay));
+    }
+}
@@ -0,0 +1,279 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Compact binary encoding of ICE candidates for opaque signaling.
+//!
+//! A candidate attribute spells out its address, port, type and
+//! ICE username fragment as text.  With opaque signaling, see
+//! `CallManager::set_opaque_signaling()`, batches of candidates are
+//! instead handed to the application as an `IceCandidates` message
+//! of `protobuf/signaling.proto`, with the fields of each attribute
+//! in binary, and turned back into the same attribute on receipt.
+//! Attributes that do not fit the fields, e.g. with an mDNS hostname
+//! or unknown extensions, are carried as they are.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use prost::Message;
+
+use crate::common::Result;
+use crate::core::sequencing::UNSEQUENCED;
+use crate::error::RingRtcError;
+use crate::protobuf::signaling as protobuf;
+use crate::protobuf::signaling::ice_candidate::{Protocol, Type};
+use crate::webrtc::ice_candidate::IceCandidate;
+
+/// The start of a candidate attribute.
+const CANDIDATE_PREFIX: &str = "candidate:";
+
+/// Encode `candidates` as an opaque message with the `sequence`
+/// number, see `core::sequencing`.
+pub fn encode_ice_candidates(sequence: u64, candidates: &[IceCandidate]) -> Vec<u8> {
+    let message = protobuf::IceCandidates {
+        candidates: candidates.iter().map(to_compact).collect(),
+        sequence:   Some(sequence),
+    };
+    let mut bytes = Vec::with_capacity(message.encoded_len());
+    // Encoding only fails if the buffer is too small.
+    let _ = message.encode(&mut bytes);
+    bytes
+}
+
+/// Decode the sequence number and candidates of an opaque message.
+pub fn decode_ice_candidates(opaque: &[u8]) -> Result<(u64, Vec<IceCandidate>)> {
+    let message = protobuf::IceCandidates::decode(opaque)
+        .map_err(|e| RingRtcError::InvalidOpaqueMessage(e.to_string()))?;
+    let candidates = message
+        .candidates
+        .iter()
+        .map(from_compact)
+        .collect::<Result<_>>()?;
+    Ok((message.sequence.unwrap_or(UNSEQUENCED), candidates))
+}
+
+/// Return the compact form of `candidate`, falling back to its
+/// attribute if the fields would not restore it exactly.
+fn to_compact(candidate: &IceCandidate) -> protobuf::IceCandidate {
+    let fields = parse_attribute(&candidate.sdp)
+        .filter(|fields| to_attribute(fields).as_ref() == Some(&candidate.sdp));
+    let mut compact = fields.unwrap_or_else(|| protobuf::IceCandidate {
+        sdp: Some(candidate.sdp.clone()),
+        ..Default::default()
+    });
+    compact.sdp_mid = Some(candidate.sdp_mid.clone());
+    compact.sdp_mline_index = Some(candidate.sdp_mline_index);
+    compact
+}
+
+/// Restore a candidate from its compact form.
+fn from_compact(compact: &protobuf::IceCandidate) -> Result<IceCandidate> {
+    let sdp = match &compact.sdp {
+        Some(sdp) => sdp.clone(),
+        None => to_attribute(compact).ok_or_else(|| {
+            RingRtcError::InvalidOpaqueMessage("incomplete ICE candidate".to_owned())
+        })?,
+    };
+    Ok(IceCandidate::new(
+        compact.sdp_mid.clone().unwrap_or_default(),
+        compact.sdp_mline_index.unwrap_or_default(),
+        sdp,
+    ))
+}
+
+/// Parse the fields of a candidate attribute, e.g.
+/// `candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host generation 0`.
+fn parse_attribute(sdp: &str) -> Option<protobuf::IceCandidate> {
+    if !sdp.starts_with(CANDIDATE_PREFIX) {
+        return None;
+    }
+
+    let mut fields = sdp[CANDIDATE_PREFIX.len()..].split(' ');
+    let mut compact = protobuf::IceCandidate {
+        foundation: Some(fields.next()?.to_owned()),
+        component: Some(fields.next()?.parse().ok()?),
+        ..Default::default()
+    };
+    compact.set_protocol(match fields.next()? {
+        "udp" => Protocol::Udp,
+        "tcp" => Protocol::Tcp,
+        _ => return None,
+    });
+    compact.priority = Some(fields.next()?.parse().ok()?);
+    compact.ip = Some(ip_to_bytes(fields.next()?.parse().ok()?));
+    compact.port = Some(fields.next()?.parse::<u16>().ok()?.into());
+    if fields.next()? != "typ" {
+        return None;
+    }
+    compact.set_type(match fields.next()? {
+        "host" => Type::Host,
+        "srflx" => Type::Srflx,
+        "prflx" => Type::Prflx,
+        "relay" => Type::Relay,
+        _ => return None,
+    });
+
+    while let Some(key) = fields.next() {
+        let value = fields.next()?;
+        match key {
+            "raddr" => compact.related_ip = Some(ip_to_bytes(value.parse().ok()?)),
+            "rport" => compact.related_port = Some(value.parse::<u16>().ok()?.into()),
+            "tcptype" => compact.tcp_type = Some(value.to_owned()),
+            "generation" => compact.generation = Some(value.parse().ok()?),
+            "ufrag" => compact.ufrag = Some(value.to_owned()),
+            "network-id" => compact.network_id = Some(value.parse().ok()?),
+            "network-cost" => compact.network_cost = Some(value.parse().ok()?),
+            _ => return None,
+        }
+    }
+    Some(compact)
+}
+
+/// Return the candidate attribute of the fields, in the order WebRTC
+/// writes them.
+fn to_attribute(compact: &protobuf::IceCandidate) -> Option<String> {
+    let protocol = match Protocol::from_i32(compact.protocol?)? {
+        Protocol::Udp => "udp",
+        Protocol::Tcp => "tcp",
+    };
+    let candidate_type = match Type::from_i32(compact.r#type?)? {
+        Type::Host => "host",
+        Type::Srflx => "srflx",
+        Type::Prflx => "prflx",
+        Type::Relay => "relay",
+    };
+
+    let mut sdp = format!(
+        "{}{} {} {} {} {} {} typ {}",
+        CANDIDATE_PREFIX,
+        compact.foundation.as_ref()?,
+        compact.component?,
+        protocol,
+        compact.priority?,
+        ip_from_bytes(compact.ip.as_ref()?)?,
+        compact.port?,
+        candidate_type
+    );
+    if let Some(related_ip) = &compact.related_ip {
+        sdp.push_str(&format!(" raddr {}", ip_from_bytes(related_ip)?));
+    }
+    if let Some(related_port) = compact.related_port {
+        sdp.push_str(&format!(" rport {}", related_port));
+    }
+    if let Some(tcp_type) = &compact.tcp_type {
+        sdp.push_str(&format!(" tcptype {}", tcp_type));
+    }
+    if let Some(generation) = compact.generation {
+        sdp.push_str(&format!(" generation {}", generation));
+    }
+    if let Some(ufrag) = &compact.ufrag {
+        sdp.push_str(&format!(" ufrag {}", ufrag));
+    }
+    if let Some(network_id) = compact.network_id {
+        sdp.push_str(&format!(" network-id {}", network_id));
+    }
+    if let Some(network_cost) = compact.network_cost {
+        sdp.push_str(&format!(" network-cost {}", network_cost));
+    }
+    Some(sdp)
+}
+
+fn ip_to_bytes(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+fn ip_from_bytes(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => {
+            let mut octets = [0u8; 4];
+            octets.copy_from_slice(bytes);
+            Some(Ipv4Addr::from(octets).into())
+        }
+        16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(bytes);
+            Some(Ipv6Addr::from(octets).into())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(sdp: &str) -> IceCandidate {
+        IceCandidate::new("audio".to_owned(), 0, sdp.to_owned())
+    }
+
+    #[test]
+    fn check_round_trip() {
+        let candidates = vec![
+            candidate(
+                "candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host generation 0 ufrag \
+                 abcd network-id 1 network-cost 10",
+            ),
+            candidate("candidate:2 1 tcp 1518280447 2001:db8::1 9 typ host tcptype active"),
+            candidate(
+                "candidate:3 1 udp 1686052607 198.51.100.1 50002 typ srflx raddr 192.0.2.1 \
+                 rport 50000 generation 0",
+            ),
+            candidate("candidate:4 1 udp 41885439 203.0.113.1 3478 typ relay"),
+            // Carried as they are.
+            candidate("candidate:5 1 udp 2122260223 abcd.local 50000 typ host"),
+            candidate("a=candidate:6 1 udp 2122260223 192.0.2.1 50000 typ host"),
+            candidate("candidate:7 1 udp 2122260223 192.0.2.1 50000 typ host unknown 1"),
+            candidate("ICE-1"),
+        ];
+
+        let opaque = encode_ice_candidates(7, &candidates);
+        let (sequence, decoded) = decode_ice_candidates(&opaque).unwrap();
+        assert_eq!(sequence, 7);
+        assert_eq!(decoded.len(), candidates.len());
+        for (decoded, candidate) in decoded.iter().zip(&candidates) {
+            assert_eq!(decoded.sdp_mid, candidate.sdp_mid);
+            assert_eq!(decoded.sdp_mline_index, candidate.sdp_mline_index);
+            assert_eq!(decoded.sdp, candidate.sdp);
+        }
+
+        let compact = to_compact(&candidates[0]);
+        assert_eq!(compact.sdp, None);
+        assert_eq!(compact.ip, Some(vec![192, 0, 2, 1]));
+        assert_eq!(
+            to_compact(&candidates[4]).sdp,
+            Some(candidates[4].sdp.clone())
+        );
+    }
+
+    #[test]
+    fn check_smaller() {
+        let srflx = candidate(
+            "candidate:842163049 1 udp 1677729535 198.51.100.1 61231 typ srflx raddr 192.0.2.1 \
+             rport 61231 generation 0 ufrag Q3sa network-id 1 network-cost 10",
+        );
+        let opaque = encode_ice_candidates(1, std::slice::from_ref(&srflx));
+        assert!(opaque.len() < srflx.sdp.len() / 2);
+    }
+
+    #[test]
+    fn check_invalid() {
+        assert!(decode_ice_candidates(&[0x0a, 0x05]).is_err());
+
+        let message = protobuf::IceCandidates {
+            candidates: vec![protobuf::IceCandidate {
+                foundation: Some("1".to_owned()),
+                ..Default::default()
+            }],
+            sequence:   None,
+        };
+        let mut bytes = Vec::new();
+        message.encode(&mut bytes).unwrap();
+        assert!(decode_ice_candidates(&bytes).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! ICE server configuration.
+//!
+//! Each TURN URL carries its transport, either from the scheme, where
+//! `turns:` always means TLS, or from a `?transport=` parameter.  The
+//! servers handed to the RTC configuration are ordered by a transport
+//! preference, so cheaper transports are tried first.
+//!
+//! TURN credentials are usually short lived.  Servers configured with
+//! a time to live are refreshed before the credentials expire: the
+//! application is asked for new ones with
+//! `ApplicationEvent::IceServersExpiring`, and hands them to
+//! `CallManager::update_ice_servers()`.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::common::Result;
+use crate::error::RingRtcError;
+
+/// The default order in which TURN transports are tried.
+pub const DEFAULT_TURN_TRANSPORT_ORDER: [IceTransport; 3] =
+    [IceTransport::Udp, IceTransport::Tcp, IceTransport::Tls];
+
+/// The transport used to reach a TURN server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IceTransport {
+    Udp,
+    Tcp,
+    /// TCP wrapped in TLS, for `turns:` URLs.
+    Tls,
+}
+
+impl fmt::Display for IceTransport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let display = match self {
+            IceTransport::Udp => "udp",
+            IceTransport::Tcp => "tcp",
+            IceTransport::Tls => "tls",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+/// A single STUN or TURN server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IceServerConfig {
+    /// The server URL, without scheme or parameters, e.g.
+    /// `turn.example.org:3478`.
+    host:          String,
+    /// The transport of a TURN server, `None` for a STUN server.
+    pub transport: Option<IceTransport>,
+    /// The TURN username.
+    pub username:  String,
+    /// The TURN password.
+    pub password:  String,
+    /// How long the TURN credentials are valid, `None` if they do not
+    /// expire.
+    pub ttl:       Option<Duration>,
+}
+
+impl fmt::Display for IceServerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.rtc_url())
+    }
+}
+
+impl IceServerConfig {
+    /// Parse a `stun:`, `turn:` or `turns:` URL.
+    ///
+    /// A `turn:` URL defaults to UDP, and may select TCP with a
+    /// `?transport=tcp` parameter.  A `turns:` URL is always TLS, so
+    /// a `?transport=udp` parameter is rejected.
+    pub fn new(url: &str, username: &str, password: &str) -> Result<Self> {
+        let invalid = || RingRtcError::InvalidIceServerUrl(url.to_string());
+
+        let mut parts = url.splitn(2, ':');
+        let scheme = parts.next().ok_or_else(invalid)?.to_ascii_lowercase();
+        let rest = parts.next().ok_or_else(invalid)?;
+
+        let mut parts = rest.splitn(2, '?');
+        let host = parts.next().unwrap_or("");
+        if host.is_empty() {
+            return Err(invalid().into());
+        }
+        let requested = match parts.next() {
+            Some(query) => match query.to_ascii_lowercase().as_str() {
+                "transport=udp" => Some(IceTransport::Udp),
+                "transport=tcp" => Some(IceTransport::Tcp),
+                _ => return Err(invalid().into()),
+            },
+            None => None,
+        };
+
+        let transport = match (scheme.as_str(), requested) {
+            ("stun", None) => None,
+            ("turn", requested) => Some(requested.unwrap_or(IceTransport::Udp)),
+            ("turns", None) | ("turns", Some(IceTransport::Tcp)) => Some(IceTransport::Tls),
+            _ => return Err(invalid().into()),
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            transport,
+            username: username.to_string(),
+            password: password.to_string(),
+            ttl: None,
+        })
+    }
+
+    /// Set how long the TURN credentials are valid.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Returns the URL in the form the RTC configuration expects,
+    /// with the transport explicit.
+    pub fn rtc_url(&self) -> String {
+        match self.transport {
+            None => format!("stun:{}", self.host),
+            Some(IceTransport::Udp) => format!("turn:{}?transport=udp", self.host),
+            Some(IceTransport::Tcp) => format!("turn:{}?transport=tcp", self.host),
+            Some(IceTransport::Tls) => format!("turns:{}?transport=tcp", self.host),
+        }
+    }
+}
+
+/// Order ICE servers for the RTC configuration.
+///
+/// STUN servers come first, then TURN servers by the position of
+/// their transport in `order`.  TURN servers whose transport is not
+/// in `order` are left out.  Otherwise the given order is kept.
+pub fn order_by_transport(
+    servers: &[IceServerConfig],
+    order: &[IceTransport],
+) -> Vec<IceServerConfig> {
+    let rank = |server: &IceServerConfig| match server.transport {
+        None => Some(0),
+        Some(transport) => order.iter().position(|t| *t == transport).map(|p| p + 1),
+    };
+
+    let mut ordered: Vec<(usize, &IceServerConfig)> = servers
+        .iter()
+        .filter_map(|server| rank(server).map(|rank| (rank, server)))
+        .collect();
+    ordered.sort_by_key(|(rank, _)| *rank);
+    ordered
+        .into_iter()
+        .map(|(_, server)| server.clone())
+        .collect()
+}
+
+/// Returns how long until the credentials of `servers` should be
+/// refreshed, leaving a tenth of the shortest time to live to fetch
+/// new ones, or `None` if no credentials expire.
+pub fn refresh_period(servers: &[IceServerConfig]) -> Option<Duration> {
+    servers
+        .iter()
+        .filter_map(|server| server.ttl)
+        .min()
+        .map(|ttl| ttl - ttl / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(url: &str) -> IceServerConfig {
+        IceServerConfig::new(url, "user", "pass").unwrap()
+    }
+
+    #[test]
+    fn check_turns_is_tls() {
+        let tls = server("turns:turn.example.org:443");
+        assert_eq!(tls.transport, Some(IceTransport::Tls));
+        assert_eq!(tls.rtc_url(), "turns:turn.example.org:443?transport=tcp");
+        assert_eq!(tls.username, "user");
+        assert_eq!(tls.password, "pass");
+
+        assert_eq!(server("TURNS:turn.example.org:443?transport=tcp"), tls);
+        assert!(IceServerConfig::new("turns:turn.example.org:443?transport=udp", "", "").is_err());
+    }
+
+    #[test]
+    fn check_parse() {
+        let udp = server("turn:turn.example.org:3478");
+        assert_eq!(udp.transport, Some(IceTransport::Udp));
+        assert_eq!(udp.rtc_url(), "turn:turn.example.org:3478?transport=udp");
+
+        let tcp = server("turn:turn.example.org:3478?transport=tcp");
+        assert_eq!(tcp.transport, Some(IceTransport::Tcp));
+        assert_eq!(tcp.rtc_url(), "turn:turn.example.org:3478?transport=tcp");
+
+        let stun = server("stun:stun.example.org");
+        assert_eq!(stun.transport, None);
+        assert_eq!(stun.rtc_url(), "stun:stun.example.org");
+
+        for url in &[
+            "http://turn.example.org",
+            "turn.example.org",
+            "turn:",
+            "turn:turn.example.org?transport=sctp",
+            "stun:stun.example.org?transport=udp",
+        ] {
+            assert!(IceServerConfig::new(url, "", "").is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn check_refresh_period() {
+        assert_eq!(refresh_period(&[]), None);
+        assert_eq!(refresh_period(&[server("stun:stun.example.org")]), None);
+
+        let servers = vec![
+            server("stun:stun.example.org"),
+            server("turn:turn.example.org").with_ttl(Duration::from_secs(3600)),
+            server("turns:turn.example.org").with_ttl(Duration::from_secs(600)),
+        ];
+        assert_eq!(refresh_period(&servers), Some(Duration::from_secs(540)));
+    }
+
+    #[test]
+    fn check_order_by_transport() {
+        let servers = vec![
+            server("turns:tls.example.org"),
+            server("turn:tcp.example.org?transport=tcp"),
+            server("turn:udp.example.org"),
+            server("stun:stun.example.org"),
+            server("turn:udp2.example.org"),
+        ];
+
+        let urls = |order: &[IceTransport]| -> Vec<String> {
+            order_by_transport(&servers, order)
+                .iter()
+                .map(|s| s.rtc_url())
+                .collect()
+        };
+
+        assert_eq!(
+            urls(&DEFAULT_TURN_TRANSPORT_ORDER),
+            vec![
+                "stun:stun.example.org",
+                "turn:udp.example.org?transport=udp",
+                "turn:udp2.example.org?transport=udp",
+                "turn:tcp.example.org?transport=tcp",
+                "turns:tls.example.org?transport=tcp",
+            ]
+        );
+        assert_eq!(
+            urls(&[IceTransport::Tls, IceTransport::Tcp]),
+            vec![
+                "stun:stun.example.org",
+                "turns:tls.example.org?transport=tcp",
+                "turn:tcp.example.org?transport=tcp",
+            ]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Limits on the outgoing media, chosen by the application before
+//! the call starts.
+//!
+//! Each connection of the call applies the constraints as soon as its
+//! PeerConnection is created, so the encoders never start at full
+//! quality only to be turned down once the call is set up, e.g. for a
+//! call that starts with the camera off or on a slow device.
+
+use std::fmt;
+
+use crate::core::video::VideoResolution;
+
+/// Constraints on the outgoing audio and video tracks of a call.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MediaConstraints {
+    /// The maximum sent video resolution, `(0, 0)` when uncapped.
+    pub max_resolution:    VideoResolution,
+    /// The maximum sent video framerate, 0 when uncapped.
+    pub max_framerate:     u32,
+    /// Start the call with the outgoing audio muted.
+    pub start_audio_muted: bool,
+    /// Start the call with the outgoing video disabled.
+    pub start_video_muted: bool,
+}
+
+impl fmt::Display for MediaConstraints {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "max_resolution: {}, max_framerate: {}, start_audio_muted: {}, start_video_muted: {}",
+            self.max_resolution, self.max_framerate, self.start_audio_muted, self.start_video_muted
+        )
+    }
+}
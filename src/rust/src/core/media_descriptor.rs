@@ -0,0 +1,112 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! A summary of a local offer or answer, for the application.
+//!
+//! The descriptor is derived from the generated SDP and handed to the
+//! application next to the SDP itself.  It is informational only, the
+//! SDP remains what is sent to the remote peer.
+
+use std::fmt;
+
+use crate::core::capabilities::RemoteCapabilities;
+use crate::core::codec::primary_codec;
+
+/// The media profile of a local offer or answer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MediaDescriptor {
+    /// An audio section is offered or accepted.
+    pub audio:            bool,
+    /// A video section is offered or accepted.
+    pub video:            bool,
+    /// A data channel section is offered or accepted.
+    pub data:             bool,
+    /// The preferred audio codec, e.g. opus.
+    pub audio_codec:      Option<String>,
+    /// The preferred video codec, e.g. VP8.
+    pub video_codec:      Option<String>,
+    /// Media frames are end-to-end encrypted on top of SRTP, see
+    /// `CallManager::set_media_crypto_provider()`.
+    ///
+    /// Not known from the SDP alone, so false unless set by the
+    /// caller.
+    pub frame_encryption: bool,
+}
+
+impl fmt::Display for MediaDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "audio: {}, video: {}, data: {}, audio_codec: {:?}, video_codec: {:?}, frame_encryption: {}",
+            self.audio,
+            self.video,
+            self.data,
+            self.audio_codec,
+            self.video_codec,
+            self.frame_encryption
+        )
+    }
+}
+
+impl MediaDescriptor {
+    /// Describe a local SDP.
+    ///
+    /// Media sections rejected with a zero port are ignored.
+    pub fn from_sdp(sdp: &str) -> Self {
+        let capabilities = RemoteCapabilities::from_sdp(sdp);
+        Self {
+            audio:            capabilities.audio,
+            video:            capabilities.video,
+            data:             capabilities.data,
+            audio_codec:      primary_codec(sdp, "audio"),
+            video_codec:      primary_codec(sdp, "video"),
+            frame_encryption: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFER: &str = "v=0\r\n\
+                         m=audio 9 UDP/TLS/RTP/SAVPF 111 0\r\n\
+                         a=rtpmap:111 opus/48000/2\r\n\
+                         a=rtpmap:0 PCMU/8000\r\n\
+                         m=video 9 UDP/TLS/RTP/SAVPF 98 96\r\n\
+                         a=rtpmap:96 VP8/90000\r\n\
+                         a=rtpmap:98 VP9/90000\r\n\
+                         m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n";
+
+    #[test]
+    fn check_audio_video_offer() {
+        assert_eq!(
+            MediaDescriptor::from_sdp(OFFER),
+            MediaDescriptor {
+                audio:            true,
+                video:            true,
+                data:             true,
+                audio_codec:      Some("opus".to_string()),
+                video_codec:      Some("VP9".to_string()),
+                frame_encryption: false,
+            }
+        );
+    }
+
+    #[test]
+    fn check_rejected_video() {
+        let descriptor = MediaDescriptor::from_sdp(&OFFER.replace("m=video 9", "m=video 0"));
+        assert!(descriptor.audio);
+        assert!(!descriptor.video);
+        assert_eq!(descriptor.video_codec, None);
+
+        assert_eq!(
+            MediaDescriptor::from_sdp("FAKE SDP"),
+            MediaDescriptor::default()
+        );
+    }
+}
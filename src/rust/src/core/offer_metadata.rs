@@ -0,0 +1,75 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! What the application knows about an incoming call before it rings.
+//!
+//! The metadata is taken from the received offer and handed to
+//! `Platform::should_ring()`, so the application can screen the call,
+//! e.g. against a blocklist or do-not-disturb, once the connection is
+//! established but before the user is alerted.
+
+use std::fmt;
+
+use crate::common::{CallId, ConnectionId, DeviceId};
+use crate::core::capabilities::RemoteCapabilities;
+
+/// The metadata of a received offer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OfferMetadata {
+    /// The call the offer starts.
+    pub call_id:       CallId,
+    /// The remote device that sent the offer.
+    pub remote_device: DeviceId,
+    /// When the offer was sent, in milliseconds since the UNIX epoch.
+    pub timestamp:     u64,
+    /// The offer includes video, i.e. this is a video call.
+    pub video:         bool,
+}
+
+impl fmt::Display for OfferMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "call_id: {}, remote_device: {}, timestamp: {}, video: {}",
+            self.call_id, self.remote_device, self.timestamp, self.video
+        )
+    }
+}
+
+impl OfferMetadata {
+    /// Describe an offer received on `connection_id`.
+    pub fn new(connection_id: ConnectionId, offer: &str, timestamp: u64) -> Self {
+        Self {
+            call_id: connection_id.call_id(),
+            remote_device: connection_id.remote_device(),
+            timestamp,
+            video: RemoteCapabilities::from_sdp(offer).video,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_offer_metadata() {
+        let connection_id = ConnectionId::new(CallId::new(42), 2);
+        let offer = "v=0\r\n\
+                     m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                     m=video 9 UDP/TLS/RTP/SAVPF 96\r\n";
+
+        let metadata = OfferMetadata::new(connection_id, offer, 1000);
+        assert_eq!(metadata.call_id, CallId::new(42));
+        assert_eq!(metadata.remote_device, 2);
+        assert_eq!(metadata.timestamp, 1000);
+        assert!(metadata.video);
+
+        let offer = offer.replace("m=video 9", "m=video 0");
+        assert!(!OfferMetadata::new(connection_id, &offer, 1000).video);
+    }
+}
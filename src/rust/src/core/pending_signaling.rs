@@ -0,0 +1,143 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Signaling messages waiting to be sent, for the application to
+//! persist.
+//!
+//! Signaling messages wait in a queue until the application confirms
+//! the previous one was sent, so while the transport is offline they
+//! pile up, and are lost if the process dies.  Whenever the queue
+//! changes, the unsent offers, answers and ICE candidates are handed
+//! to `Platform::persist_pending_signaling()`, and after a restart
+//! `CallManager::restore_pending_signaling()` sends whatever
+//! `Platform::restore_pending_signaling()` returns.
+
+use std::fmt;
+
+use crate::common::{ConnectionId, Result};
+use crate::core::util::redact_string;
+use crate::error::RingRtcError;
+use crate::webrtc::ice_candidate::IceCandidate;
+
+/// The content of a pending signaling message.
+#[derive(Clone, Debug)]
+pub enum PendingMessage {
+    /// An SDP offer, renegotiating with new ICE credentials if
+    /// `ice_restart` is set.
+    Offer {
+        description: String,
+        ice_restart: bool,
+    },
+    /// An SDP answer.
+    Answer { description: String },
+    /// A batch of ICE candidates.
+    IceCandidates(Vec<IceCandidate>),
+}
+
+impl PendingMessage {
+    /// The kind of the message, as passed through the platform APIs:
+    /// 0 for an offer, 1 for an ICE restart offer, 2 for an answer
+    /// and 3 for ICE candidates.
+    pub fn kind(&self) -> i32 {
+        match self {
+            PendingMessage::Offer {
+                ice_restart: false, ..
+            } => 0,
+            PendingMessage::Offer {
+                ice_restart: true, ..
+            } => 1,
+            PendingMessage::Answer { .. } => 2,
+            PendingMessage::IceCandidates(_) => 3,
+        }
+    }
+
+    /// The SDP of an offer or answer.
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            PendingMessage::Offer { description, .. } | PendingMessage::Answer { description } => {
+                Some(description)
+            }
+            PendingMessage::IceCandidates(_) => None,
+        }
+    }
+
+    /// The ICE candidates of an `IceCandidates` message, empty for
+    /// the other kinds.
+    pub fn ice_candidates(&self) -> &[IceCandidate] {
+        match self {
+            PendingMessage::IceCandidates(candidates) => candidates,
+            _ => &[],
+        }
+    }
+
+    /// Rebuild a message restored by a platform from its `kind()` and
+    /// content.
+    pub fn from_kind(
+        kind: i32,
+        description: Option<String>,
+        ice_candidates: Vec<IceCandidate>,
+    ) -> Result<Self> {
+        let description = || {
+            description.ok_or_else(|| {
+                RingRtcError::OptionValueNotSet(
+                    "PendingMessage::from_kind()".to_owned(),
+                    "description".to_owned(),
+                )
+            })
+        };
+        match kind {
+            0 | 1 => Ok(PendingMessage::Offer {
+                description: description()?,
+                ice_restart: kind == 1,
+            }),
+            2 => Ok(PendingMessage::Answer {
+                description: description()?,
+            }),
+            3 => Ok(PendingMessage::IceCandidates(ice_candidates)),
+            _ => Err(RingRtcError::UnknownPendingMessageKind(kind).into()),
+        }
+    }
+}
+
+impl fmt::Display for PendingMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PendingMessage::Offer {
+                description,
+                ice_restart,
+            } => write!(
+                f,
+                "Offer(ice_restart: {}): {}",
+                ice_restart,
+                redact_string(description)
+            ),
+            PendingMessage::Answer { description } => {
+                write!(f, "Answer: {}", redact_string(description))
+            }
+            PendingMessage::IceCandidates(candidates) => {
+                write!(f, "IceCandidates: {}", candidates.len())
+            }
+        }
+    }
+}
+
+/// A signaling message waiting to be sent to `remote_peer`.
+#[derive(Clone, Debug)]
+pub struct PendingSignaling<P> {
+    /// The remote peer the message is for.
+    pub remote_peer:   P,
+    /// The connection the message belongs to.
+    pub connection_id: ConnectionId,
+    /// The message itself.
+    pub message:       PendingMessage,
+}
+
+impl<P> fmt::Display for PendingSignaling<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "id: {}, {}", self.connection_id, self.message)
+    }
+}
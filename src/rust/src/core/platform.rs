@@ -8,14 +8,37 @@
 //! Platform trait describing the interface an operating system platform must
 /// implement for calling.
 use std::fmt;
+use std::time::Duration;
 
-use crate::common::{ApplicationEvent, CallDirection, CallId, ConnectionId, DeviceId, Result};
+use crate::common::{
+    ApplicationEvent,
+    CallDirection,
+    CallId,
+    ConnectionId,
+    DeviceId,
+    HangupType,
+    Result,
+};
 
+use crate::core::audio_route::AudioRoute;
 use crate::core::call::Call;
+use crate::core::call_failure::CallFailureReason;
+use crate::core::call_record::CallRecord;
+use crate::core::call_stats::CallStats;
+use crate::core::codec::CodecCapability;
 use crate::core::connection::Connection;
+use crate::core::connection_config::ConnectionConfig;
+use crate::core::ice_encoding::decode_ice_candidates;
+use crate::core::ice_server::IceServerConfig;
+use crate::core::media_descriptor::MediaDescriptor;
+use crate::core::offer_metadata::OfferMetadata;
+use crate::core::pending_signaling::PendingSignaling;
+use crate::core::signaling::{Answer, Offer};
+use crate::error::RingRtcError;
 
-use crate::webrtc::ice_candidate::IceCandidate;
+use crate::webrtc::ice_candidate::{IceCandidate, IceRoute, NetworkAdapterType};
 use crate::webrtc::media_stream::MediaStream;
+use crate::webrtc::peer_connection_observer::IceGatheringState;
 
 /// A trait encompassing the traits the platform associated types must
 /// implement.
@@ -36,13 +59,26 @@ pub trait Platform: fmt::Debug + fmt::Display + Sync + Send + Sized + 'static {
     /// Opaque application specific call context.
     type AppCallContext: PlatformItem + Clone;
 
-    /// Create platform specific Connection object.
+    /// Create platform specific Connection object, with its
+    /// PeerConnection configured by `config`.
     fn create_connection(
         &mut self,
         call: &Call<Self>,
         device_id: DeviceId,
+        config: ConnectionConfig,
     ) -> Result<Connection<Self>>;
 
+    /// Create a new PeerConnection for an existing Connection object,
+    /// replacing the one released by a hard reset.
+    fn recreate_peer_connection(
+        &mut self,
+        _call: &Call<Self>,
+        _connection: &Connection<Self>,
+        _config: ConnectionConfig,
+    ) -> Result<()> {
+        Err(RingRtcError::HardResetUnsupported.into())
+    }
+
     /// Inform the client application that a call should be started.
     fn on_start_call(
         &self,
@@ -51,33 +87,433 @@ pub trait Platform: fmt::Debug + fmt::Display + Sync + Send + Sized + 'static {
         direction: CallDirection,
     ) -> Result<()>;
 
-    /// Notify the client application about an event.
-    fn on_event(&self, remote_peer: &Self::AppRemotePeer, event: ApplicationEvent) -> Result<()>;
+    /// Notify the client application about an event of the call
+    /// identified by `call_id`.
+    fn on_event(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        event: ApplicationEvent,
+    ) -> Result<()>;
+
+    /// Notify the client application that the rotation of the remote
+    /// video changed, in degrees (0, 90, 180 or 270).
+    ///
+    /// Only reported when the video orientation RTP header extension
+    /// was negotiated.  Platforms that ignore rotation metadata can
+    /// rely on the default, which does nothing.
+    fn on_remote_video_orientation(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _degrees: u16,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of a message the remote peer
+    /// sent with `Connection::send_app_message()`, e.g. a reaction or
+    /// a caption.
+    ///
+    /// The messages travel on the DataChannel of the call, reliably
+    /// and in order.  The default ignores them.
+    fn on_app_message_received(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _payload: &[u8],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that a DTMF tone was received
+    /// from the remote peer.
+    ///
+    /// Tones are the characters 0-9, A-D, `#` and `*`.  Platforms
+    /// without a use for DTMF can rely on the default, which does
+    /// nothing.
+    fn on_received_dtmf(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _tone: char,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that the ICE route of the
+    /// connected call changed from direct to relayed through a TURN
+    /// server.
+    ///
+    /// Only reported once the new route has held for the route
+    /// change debounce period.
+    fn on_route_downgraded_to_relay(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _route: &IceRoute,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that the ICE route of the
+    /// connected call changed from relayed to direct.
+    ///
+    /// Only reported once the new route has held for the route
+    /// change debounce period.
+    fn on_route_upgraded_to_direct(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _route: &IceRoute,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of the network the ICE route of
+    /// the connected call runs over, e.g. for "connecting via relay"
+    /// or "switched to cellular" indicators.
+    ///
+    /// Reported once the call connects, then whenever ICE selects a
+    /// candidate pair over another kind of network interface, or
+    /// switches between a direct and a relayed route, without
+    /// waiting for the route change debounce period.
+    fn on_network_route_changed(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _local_adapter_type: NetworkAdapterType,
+        _relayed: bool,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of the safety numbers of the
+    /// connected call, for the users to read aloud and compare.
+    ///
+    /// Matching numbers show that no one substituted the DTLS
+    /// fingerprints on the signaling path.  Only reported when both
+    /// sides sent a fingerprint, see
+    /// `Connection::get_safety_numbers()`.
+    fn on_safety_numbers(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _safety_numbers: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that the DataChannel send buffer
+    /// drained to half the high water mark, after a send was refused
+    /// for exceeding it.
+    fn on_data_channel_buffered_amount_low(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of how long the call has been
+    /// connected, every second while it is connected or
+    /// reconnecting.
+    ///
+    /// Only reported when enabled with
+    /// `CallManager::set_call_duration_ticks()`.
+    fn on_call_duration_tick(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _duration: Duration,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of the quality of the active
+    /// call, e.g. for a call quality indicator.
+    ///
+    /// Only reported when enabled with
+    /// `CallManager::set_stats_interval()`.
+    fn on_stats_report(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _stats: &CallStats,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of the levels of the captured
+    /// and received audio of the active call, from 0.0 to 1.0, e.g. to
+    /// animate speaking indicators.
+    ///
+    /// Only reported when enabled with
+    /// `CallManager::set_audio_levels_interval()`.
+    fn on_audio_levels(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _captured: f64,
+        _received: f64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of the bandwidth available for
+    /// sending and receiving the media of the active call, in bits
+    /// per second, as estimated by WebRTC congestion control.
+    ///
+    /// An application may e.g. suggest turning video off when the
+    /// estimate drops below what video needs.  Only reported when
+    /// enabled with `CallManager::set_bandwidth_estimate_interval()`.
+    fn on_bandwidth_estimate(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _send_bps: u64,
+        _receive_bps: u64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of the ICE candidate gathering
+    /// state of a connection, e.g. for a "finding network path"
+    /// indicator.
+    ///
+    /// Gathering starts over whenever the connection restarts ICE or
+    /// is rebuilt.
+    fn on_ice_gathering_state_changed(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _state: IceGatheringState,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Ask the client application whether an incoming call may ring,
+    /// e.g. to apply a blocklist or do-not-disturb.
+    ///
+    /// Called once the connection is established, just before the
+    /// call would ring.  If `false`, the call is hung up and ends
+    /// with `EndedRingRefused`.  Platforms can rely on the default,
+    /// which always rings.
+    fn should_ring(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _offer_metadata: &OfferMetadata,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// Ask the client application whether to accept the remote
+    /// peer's request to transfer the call to `target`.
+    ///
+    /// An application that accepts places the call to `target`
+    /// itself, and the remote peer then hangs up.  The default
+    /// declines every transfer.
+    fn should_transfer(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _target: &str,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Ask the client application for fresh STUN and TURN servers,
+    /// e.g. short-lived TURN credentials fetched from its server.
+    ///
+    /// Called whenever a connection is created and again whenever it
+    /// restarts ICE, so the credentials need not outlive the time it
+    /// takes to connect.  An empty list keeps the servers passed to
+    /// `proceed()`, which is what the default returns.
+    fn get_turn_servers(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+    ) -> Result<Vec<IceServerConfig>> {
+        Ok(Vec::new())
+    }
+
+    /// Ask the client application which codecs the device encodes or
+    /// decodes in hardware, e.g. from Android's `MediaCodecList`.
+    ///
+    /// The result is merged with the software codecs of WebRTC, see
+    /// `CallManager::supported_codecs()`.  The default reports no
+    /// hardware codecs.
+    fn get_codec_capabilities(&self) -> Result<Vec<CodecCapability>> {
+        Ok(Vec::new())
+    }
+
+    /// Transform a local SDP offer or answer before it is set on the
+    /// PeerConnection and sent to the remote peer, e.g. to reorder
+    /// codecs, add extmap lines or strip attributes.
+    ///
+    /// WebRTC must accept the transformed SDP, or the call fails.  The
+    /// default returns the SDP unchanged.
+    fn transform_local_description(
+        &self,
+        _connection_id: ConnectionId,
+        sdp: String,
+    ) -> Result<String> {
+        Ok(sdp)
+    }
+
+    /// Transform a remote SDP offer or answer before it is set on the
+    /// PeerConnection, see `transform_local_description()`.
+    ///
+    /// The default returns the SDP unchanged.
+    fn transform_remote_description(
+        &self,
+        _connection_id: ConnectionId,
+        sdp: String,
+    ) -> Result<String> {
+        Ok(sdp)
+    }
+
+    /// Notify the client application of the metadata the callee
+    /// attached to its answer, see `CallManager::set_answer_metadata()`.
+    ///
+    /// Called before the answer is applied, so the application can
+    /// negotiate features before the media connects.  The default
+    /// ignores the metadata.
+    fn on_received_answer_metadata(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _metadata: &[u8],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Send a pre-offer to all the devices of a remote peer using the
+    /// signaling channel, as soon as an outgoing call starts, see
+    /// `CallManager::set_pre_offer()`.
+    ///
+    /// The pre-offer only identifies the call, the remote peer passes
+    /// it to `CallManager::received_pre_offer()`.  The default sends
+    /// nothing.
+    fn on_send_pre_offer(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that a remote peer is about to
+    /// offer a call, so it can warm up, e.g. initialize the audio
+    /// device and allocate TURN candidates, while the offer is still
+    /// in flight.
+    ///
+    /// The offer may never arrive.  The default does nothing.
+    fn on_received_pre_offer(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+    ) -> Result<()> {
+        Ok(())
+    }
 
     /// Send an SDP offer to a remote peer using the signaling
     /// channel.
     ///
-    /// If broadcast is true, then send to all remote peers.
+    /// If broadcast is true, then send to all remote peers.  The
+    /// descriptor summarizes the SDP for the application, the SDP
+    /// itself is what gets sent.
     fn on_send_offer(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
         broadcast: bool,
         description: &str,
+        descriptor: &MediaDescriptor,
     ) -> Result<()>;
 
+    /// Send an SDP offer that restarts ICE on a connected call to a
+    /// remote peer using the signaling channel.
+    ///
+    /// The remote peer answers it like any other offer, so platforms
+    /// that do not distinguish restart offers can rely on the
+    /// default, which calls `on_send_offer()`.
+    fn on_send_restart_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        self.on_send_offer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            description,
+            descriptor,
+        )
+    }
+
+    /// Send an offer encoded as opaque bytes to a remote peer using
+    /// the signaling channel, see `CallManager::set_opaque_signaling()`.
+    ///
+    /// The default decodes the offer again and calls
+    /// `on_send_offer()`.
+    fn on_send_opaque_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        self.on_send_offer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            &Offer::from_opaque(opaque)?.to_wire(),
+            descriptor,
+        )
+    }
+
     /// Send an SDP answer to a remote peer using the signaling
     /// channel.
     ///
-    /// If broadcast is true, then send to all remote peers.
+    /// If broadcast is true, then send to all remote peers.  The
+    /// descriptor summarizes the SDP for the application, the SDP
+    /// itself is what gets sent.
     fn on_send_answer(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
         broadcast: bool,
         description: &str,
+        descriptor: &MediaDescriptor,
     ) -> Result<()>;
 
+    /// Send an answer encoded as opaque bytes to a remote peer using
+    /// the signaling channel, see `CallManager::set_opaque_signaling()`.
+    ///
+    /// The default decodes the answer again and calls
+    /// `on_send_answer()`.
+    fn on_send_opaque_answer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        self.on_send_answer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            &Answer::from_opaque(opaque)?.to_wire(),
+            descriptor,
+        )
+    }
+
     /// Send ICE Candidates to a remote peer using the signaling
     /// channel.
     ///
@@ -90,17 +526,59 @@ pub trait Platform: fmt::Debug + fmt::Display + Sync + Send + Sized + 'static {
         candidates: &[IceCandidate],
     ) -> Result<()>;
 
+    /// Send ICE Candidates encoded as opaque bytes to a remote peer
+    /// using the signaling channel, see
+    /// `CallManager::set_opaque_signaling()`.
+    ///
+    /// The default decodes the candidates again and calls
+    /// `on_send_ice_candidates()`, dropping the sequence number.
+    fn on_send_opaque_ice_candidates(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+    ) -> Result<()> {
+        self.on_send_ice_candidates(
+            remote_peer,
+            connection_id,
+            broadcast,
+            &decode_ice_candidates(opaque)?.1,
+        )
+    }
+
     /// Send a call hangup message to a remote peer using the
     /// signaling channel.
     ///
-    /// If broadcast is true, then send to all remote peers.
+    /// If broadcast is true, then send to all remote peers.  The
+    /// `hangup_type` tells the remote peer why the call ended.  For an
+    /// `Accepted` hangup the device of `connection_id` is the device
+    /// that accepted the call.
     fn on_send_hangup(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
         broadcast: bool,
+        hangup_type: HangupType,
     ) -> Result<()>;
 
+    /// Send a call hangup message to several devices of a remote peer
+    /// at once using the signaling channel.
+    ///
+    /// Used for the broadcast hangups, with the remote devices the
+    /// call reached, so the application can address all of them in a
+    /// single message.  The default ignores the devices and calls
+    /// `on_send_hangup()` as a broadcast.
+    fn on_send_hangup_to_devices(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        _remote_devices: &[DeviceId],
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        self.on_send_hangup(remote_peer, connection_id, true, hangup_type)
+    }
+
     /// Send a call busy message to a remote peer using the
     /// signaling channel.
     ///
@@ -112,6 +590,45 @@ pub trait Platform: fmt::Debug + fmt::Display + Sync + Send + Sized + 'static {
         broadcast: bool,
     ) -> Result<()>;
 
+    /// Send a ringing acknowledgment to the caller using the
+    /// signaling channel, once an incoming call rings.
+    ///
+    /// Platforms whose signaling has no such message can rely on the
+    /// default, which sends nothing and reports the message sent.
+    fn on_send_ringing(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Seal an opaque signaling message for a remote peer, e.g.
+    /// encrypt and authenticate it with a key shared with the remote
+    /// peer, see `signaling::seal()`.
+    ///
+    /// Returns `None`, like the default, to send the message as is.
+    /// Platforms that seal messages send the opaque messages as given,
+    /// without decoding them.
+    fn seal(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        _opaque: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Open a sealed opaque signaling message received on a
+    /// connection, returning the message as it was before
+    /// `seal()`.
+    ///
+    /// Fails if the message is not authentic, in which case it is
+    /// dropped.  The default opens nothing.
+    fn open(&self, _connection_id: ConnectionId, _sealed: &[u8]) -> Result<Vec<u8>> {
+        Err(RingRtcError::OpenSealedMessage("sealing not supported".to_owned()).into())
+    }
+
     /// Create a platform dependent media stream from the base WebRTC
     /// MediaStream.
     fn create_media_stream(
@@ -128,6 +645,83 @@ pub trait Platform: fmt::Debug + fmt::Display + Sync + Send + Sized + 'static {
         app_media_stream: &Self::AppMediaStream,
     ) -> Result<()>;
 
+    /// Connect the media stream of a group call participant to the
+    /// application.
+    ///
+    /// Platforms without group calls can rely on the default, which
+    /// does nothing.
+    fn on_connect_participant_media(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _app_media_stream: &Self::AppMediaStream,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Suggest the application to play the audio of the call on
+    /// `route`, e.g. the speaker once the remote peer turns its video
+    /// on, see `AudioRoute::for_video()`.
+    ///
+    /// The application applies the route to its audio devices, or
+    /// tells the call manager otherwise with
+    /// `CallManager::set_audio_output()`.  Platforms whose application
+    /// routes the audio by itself can rely on the default, which does
+    /// nothing.
+    fn on_audio_route_suggested(
+        &self,
+        _app_call_context: &Self::AppCallContext,
+        _route: AudioRoute,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that the call manager now
+    /// treats the call as managed by the calling service of the
+    /// system, after `CallManager::call_added_to_system()`.
+    ///
+    /// Platforms without such a service can rely on the default,
+    /// which does nothing.
+    fn on_call_added_to_system(&self, _app_call_context: &Self::AppCallContext) -> Result<()> {
+        Ok(())
+    }
+
+    /// Ask the client application to show a call managed by the
+    /// system as on hold, or active again, after the call manager
+    /// held or resumed it by itself, e.g. to accept a waiting call.
+    ///
+    /// Holds requested by the system with
+    /// `CallManager::hold_requested_by_system()` are not echoed back.
+    /// Called like `on_call_added_to_system()`, with the same default.
+    fn on_request_system_hold(
+        &self,
+        _app_call_context: &Self::AppCallContext,
+        _on_hold: bool,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Turn the camera of the call on or off.
+    ///
+    /// Called from the worker of the call, never after
+    /// `on_close_media()`.  Platforms whose application controls the
+    /// camera itself can rely on the default, which does nothing.
+    fn on_set_camera_enabled(
+        &self,
+        _app_call_context: &Self::AppCallContext,
+        _enabled: bool,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Switch to the next camera of the call, e.g. from the front to
+    /// the back camera.
+    ///
+    /// Called like `on_set_camera_enabled()`, with the same default.
+    fn on_switch_camera(&self, _app_call_context: &Self::AppCallContext) -> Result<()> {
+        Ok(())
+    }
+
     /// Close the media associated with the call.
     fn on_close_media(&self, _app_call_context: &Self::AppCallContext) -> Result<()> {
         Ok(())
@@ -141,6 +735,65 @@ pub trait Platform: fmt::Debug + fmt::Display + Sync + Send + Sized + 'static {
         remote_peer2: &Self::AppRemotePeer,
     ) -> Result<bool>;
 
+    /// Hand the application the record of a concluded call, e.g. for
+    /// its call history.
+    ///
+    /// Called just before `on_call_concluded()`.  Platforms without a
+    /// call history can rely on the default, which does nothing.
+    fn on_call_record(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _record: &CallRecord,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Tell the application why a call failed, e.g. to show an
+    /// actionable error message.
+    ///
+    /// Called just before the `Ended*` event of the failed call.
+    /// Platforms that only show the event can rely on the default,
+    /// which does nothing.
+    fn on_call_failed(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _reason: CallFailureReason,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Notify the application that the call is completely concluded
-    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer) -> Result<()>;
+    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer, call_id: CallId) -> Result<()>;
+
+    /// Notify the application that `CallManager::shutdown()` is
+    /// complete, all calls are torn down and the call manager is
+    /// closed.
+    ///
+    /// Platforms that never shut down gracefully can rely on the
+    /// default, which does nothing.
+    fn on_shutdown_complete(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Persist the signaling messages waiting to be sent, replacing
+    /// any persisted before.
+    ///
+    /// Called whenever the queue of signaling messages changes, with
+    /// an empty `pending` once it drains.  Platforms that do not
+    /// survive a restart can rely on the default, which does nothing.
+    fn persist_pending_signaling(
+        &self,
+        _pending: &[PendingSignaling<Self::AppRemotePeer>],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Return the signaling messages last persisted, for
+    /// `CallManager::restore_pending_signaling()` to send.
+    ///
+    /// The default has nothing to restore.
+    fn restore_pending_signaling(&self) -> Result<Vec<PendingSignaling<Self::AppRemotePeer>>> {
+        Ok(Vec::new())
+    }
 }
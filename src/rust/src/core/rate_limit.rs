@@ -0,0 +1,117 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Rate limiting of events received from the remote peer.
+//!
+//! A misbehaving peer can send far more ICE candidates than a call
+//! needs, each of which costs connectivity checks and, on Android,
+//! JNI round trips.  Events beyond the limit of a window are dropped.
+
+use std::time::{Duration, Instant};
+
+/// Maximum number of remote ICE candidates accepted per window.
+pub const MAX_RECEIVED_ICE_CANDIDATES: usize = 64;
+
+/// Window over which remote ICE candidates are counted.
+pub const RECEIVED_ICE_CANDIDATES_WINDOW: Duration = Duration::from_secs(10);
+
+/// Admits at most a fixed number of events per fixed window.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Maximum number of events admitted per window.
+    limit:        usize,
+    /// Length of a window.
+    window:       Duration,
+    /// Start of the current window, if any event was seen.
+    window_start: Option<Instant>,
+    /// Number of events admitted in the current window.
+    admitted:     usize,
+    /// Total number of events dropped.
+    dropped:      usize,
+}
+
+impl RateLimiter {
+    /// Create a limiter admitting `limit` events per `window`.
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            window_start: None,
+            admitted: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Create a limiter for remote ICE candidates.
+    pub fn for_received_ice_candidates() -> Self {
+        Self::new(MAX_RECEIVED_ICE_CANDIDATES, RECEIVED_ICE_CANDIDATES_WINDOW)
+    }
+
+    /// Returns `true` if an event seen at `now` is within the limit,
+    /// otherwise counts it as dropped.
+    pub fn admit(&mut self, now: Instant) -> bool {
+        match self.window_start {
+            Some(start) if now.duration_since(start) < self.window => {}
+            _ => {
+                self.window_start = Some(now);
+                self.admitted = 0;
+            }
+        }
+
+        if self.admitted < self.limit {
+            self.admitted += 1;
+            true
+        } else {
+            self.dropped += 1;
+            false
+        }
+    }
+
+    /// Returns the total number of events dropped.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_limit() {
+        let mut limiter = RateLimiter::new(3, Duration::from_secs(1));
+        let now = Instant::now();
+
+        assert!(limiter.admit(now));
+        assert!(limiter.admit(now));
+        assert!(limiter.admit(now + Duration::from_millis(500)));
+        assert!(!limiter.admit(now + Duration::from_millis(500)));
+        assert!(!limiter.admit(now + Duration::from_millis(999)));
+        assert_eq!(limiter.dropped(), 2);
+    }
+
+    #[test]
+    fn check_window() {
+        let mut limiter = RateLimiter::new(2, Duration::from_secs(1));
+        let now = Instant::now();
+
+        // A steady rate at the limit is never dropped.
+        for second in 0..10 {
+            let start = now + Duration::from_secs(second);
+            assert!(limiter.admit(start));
+            assert!(limiter.admit(start + Duration::from_millis(900)));
+        }
+        assert_eq!(limiter.dropped(), 0);
+
+        let start = now + Duration::from_secs(20);
+        assert!(limiter.admit(start));
+        assert!(limiter.admit(start));
+        assert!(!limiter.admit(start));
+        assert!(limiter.admit(start + Duration::from_secs(1)));
+        assert_eq!(limiter.dropped(), 1);
+    }
+}
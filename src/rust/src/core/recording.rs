@@ -0,0 +1,615 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Recording of a call to a Matroska stream.
+//!
+//! A recording started with `CallManager::start_recording()` taps the
+//! captured and received audio of the active connection, mixes both
+//! into one mono track and, if configured, adds the frames of the
+//! incoming video as a second track.  The tracks are written
+//! uncompressed to a `RecordingSink`, e.g. a file, as Matroska
+//! clusters of about a second, so a recording cut short by a crash is
+//! still playable up to the last cluster.
+//!
+//! The remote peer is told over the DataChannel whenever a recording
+//! starts or stops, and reports it to its application as
+//! `RemoteRecordingStarted` or `RemoteRecordingStopped`.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::common::Result;
+use crate::webrtc::media::{AudioDirection, AudioFrame};
+use crate::webrtc::media_stream::VideoFrame;
+
+/// Sample rate of the recorded audio, in Hz.
+pub const RECORDING_SAMPLE_RATE_HZ: u32 = 48000;
+
+/// Samples of the recorded audio in each block, 10 ms worth.
+const BLOCK_SAMPLES: usize = (RECORDING_SAMPLE_RATE_HZ / 100) as usize;
+
+/// Most samples one direction of the audio may get ahead of the
+/// other, 200 ms worth, before the other is padded with silence,
+/// e.g. while the remote peer holds the call.
+const MAX_LAG_SAMPLES: usize = 20 * BLOCK_SAMPLES;
+
+/// Length of a cluster, in ms.
+const CLUSTER_DURATION_MS: u64 = 1000;
+
+/// Track number of the audio.
+const AUDIO_TRACK: u8 = 1;
+
+/// Track number of the video.
+const VIDEO_TRACK: u8 = 2;
+
+// Matroska element IDs, with their length marker.
+const EBML: u32 = 0x1A45_DFA3;
+const EBML_VERSION: u32 = 0x4286;
+const EBML_READ_VERSION: u32 = 0x42F7;
+const EBML_MAX_ID_LENGTH: u32 = 0x42F2;
+const EBML_MAX_SIZE_LENGTH: u32 = 0x42F3;
+const DOC_TYPE: u32 = 0x4282;
+const DOC_TYPE_VERSION: u32 = 0x4287;
+const DOC_TYPE_READ_VERSION: u32 = 0x4285;
+const SEGMENT: u32 = 0x1853_8067;
+const INFO: u32 = 0x1549_A966;
+const TIMECODE_SCALE: u32 = 0x2A_D7B1;
+const MUXING_APP: u32 = 0x4D80;
+const WRITING_APP: u32 = 0x5741;
+const TRACKS: u32 = 0x1654_AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_NUMBER: u32 = 0xD7;
+const TRACK_UID: u32 = 0x73C5;
+const TRACK_TYPE: u32 = 0x83;
+const FLAG_LACING: u32 = 0x9C;
+const CODEC_ID: u32 = 0x86;
+const AUDIO: u32 = 0xE1;
+const SAMPLING_FREQUENCY: u32 = 0xB5;
+const CHANNELS: u32 = 0x9F;
+const BIT_DEPTH: u32 = 0x6264;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const COLOUR_SPACE: u32 = 0x2E_B524;
+const CLUSTER: u32 = 0x1F43_B675;
+const TIMECODE: u32 = 0xE7;
+const SIMPLE_BLOCK: u32 = 0xA3;
+
+/// The size of an element written before its body is known.
+const UNKNOWN_SIZE: [u8; 8] = [0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+
+/// Receives the Matroska stream of a recording.
+///
+/// The stream is written on the WebRTC audio and video threads, so a
+/// sink should not block for long.
+pub trait RecordingSink: Send + 'static {
+    /// Append the next bytes of the stream.
+    fn write(&mut self, data: &[u8]) -> Result<()>;
+
+    /// The stream is complete.  The default does nothing.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// What a recording includes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RecordingConfig {
+    /// The width and height of the incoming video to record, or `None`
+    /// to record the audio only.
+    ///
+    /// The size of an uncompressed Matroska video track is fixed, so
+    /// frames of any other size are skipped.
+    pub video_size: Option<(u32, u32)>,
+}
+
+impl RecordingConfig {
+    /// Record the incoming video frames of `width` by `height` too.
+    pub fn with_video(mut self, width: u32, height: u32) -> Self {
+        self.video_size = Some((width, height));
+        self
+    }
+}
+
+impl fmt::Display for RecordingConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.video_size {
+            Some((width, height)) => write!(f, "video: {}x{}", width, height),
+            None => write!(f, "video: none"),
+        }
+    }
+}
+
+/// Writes the variable length size of an element.
+fn write_size(out: &mut Vec<u8>, size: u64) {
+    // All ones is reserved for an unknown size.
+    let length = (1..8).find(|n| size < (1 << (7 * n)) - 1).unwrap_or(8);
+    let marked = size | (1 << (7 * length));
+    out.extend_from_slice(&marked.to_be_bytes()[8 - length as usize..]);
+}
+
+/// Writes an element ID, which already carries its length marker.
+fn write_id(out: &mut Vec<u8>, id: u32) {
+    let skip = (id.leading_zeros() / 8) as usize;
+    out.extend_from_slice(&id.to_be_bytes()[skip..]);
+}
+
+/// Writes an element with `body`.
+fn write_element(out: &mut Vec<u8>, id: u32, body: &[u8]) {
+    write_id(out, id);
+    write_size(out, body.len() as u64);
+    out.extend_from_slice(body);
+}
+
+/// Writes an unsigned integer element, in as few bytes as it takes.
+fn write_uint(out: &mut Vec<u8>, id: u32, value: u64) {
+    let skip = ((value.leading_zeros() / 8) as usize).min(7);
+    write_element(out, id, &value.to_be_bytes()[skip..]);
+}
+
+/// Writes a float element.
+fn write_float(out: &mut Vec<u8>, id: u32, value: f64) {
+    write_element(out, id, &value.to_bits().to_be_bytes());
+}
+
+/// Writes a string element.
+fn write_string(out: &mut Vec<u8>, id: u32, value: &str) {
+    write_element(out, id, value.as_bytes());
+}
+
+/// Writes the EBML header, and the start of the segment with the
+/// info and tracks of a recording.
+fn write_header(out: &mut Vec<u8>, config: &RecordingConfig) {
+    let mut header = Vec::new();
+    write_uint(&mut header, EBML_VERSION, 1);
+    write_uint(&mut header, EBML_READ_VERSION, 1);
+    write_uint(&mut header, EBML_MAX_ID_LENGTH, 4);
+    write_uint(&mut header, EBML_MAX_SIZE_LENGTH, 8);
+    write_string(&mut header, DOC_TYPE, "matroska");
+    write_uint(&mut header, DOC_TYPE_VERSION, 4);
+    write_uint(&mut header, DOC_TYPE_READ_VERSION, 2);
+    write_element(out, EBML, &header);
+
+    // The segment grows until the recording stops.
+    write_id(out, SEGMENT);
+    out.extend_from_slice(&UNKNOWN_SIZE);
+
+    let mut info = Vec::new();
+    write_uint(&mut info, TIMECODE_SCALE, 1_000_000);
+    write_string(&mut info, MUXING_APP, "ringrtc");
+    write_string(&mut info, WRITING_APP, "ringrtc");
+    write_element(out, INFO, &info);
+
+    let mut audio = Vec::new();
+    write_float(
+        &mut audio,
+        SAMPLING_FREQUENCY,
+        f64::from(RECORDING_SAMPLE_RATE_HZ),
+    );
+    write_uint(&mut audio, CHANNELS, 1);
+    write_uint(&mut audio, BIT_DEPTH, 16);
+
+    let mut audio_track = Vec::new();
+    write_uint(&mut audio_track, TRACK_NUMBER, u64::from(AUDIO_TRACK));
+    write_uint(&mut audio_track, TRACK_UID, u64::from(AUDIO_TRACK));
+    write_uint(&mut audio_track, TRACK_TYPE, 2);
+    write_uint(&mut audio_track, FLAG_LACING, 0);
+    write_string(&mut audio_track, CODEC_ID, "A_PCM/INT/LIT");
+    write_element(&mut audio_track, AUDIO, &audio);
+
+    let mut tracks = Vec::new();
+    write_element(&mut tracks, TRACK_ENTRY, &audio_track);
+
+    if let Some((width, height)) = config.video_size {
+        let mut video = Vec::new();
+        write_uint(&mut video, PIXEL_WIDTH, u64::from(width));
+        write_uint(&mut video, PIXEL_HEIGHT, u64::from(height));
+        write_element(&mut video, COLOUR_SPACE, b"I420");
+
+        let mut video_track = Vec::new();
+        write_uint(&mut video_track, TRACK_NUMBER, u64::from(VIDEO_TRACK));
+        write_uint(&mut video_track, TRACK_UID, u64::from(VIDEO_TRACK));
+        write_uint(&mut video_track, TRACK_TYPE, 1);
+        write_uint(&mut video_track, FLAG_LACING, 0);
+        write_string(&mut video_track, CODEC_ID, "V_UNCOMPRESSED");
+        write_element(&mut video_track, VIDEO, &video);
+
+        write_element(&mut tracks, TRACK_ENTRY, &video_track);
+    }
+    write_element(out, TRACKS, &tracks);
+}
+
+/// Downmixes a frame to mono and resamples it to the rate of the
+/// recording, by linear interpolation.
+fn to_recording_format(frame: &AudioFrame) -> Vec<i16> {
+    let channels = frame.channels.max(1) as usize;
+    let mono: Vec<i16> = frame
+        .samples
+        .chunks(channels)
+        .map(|samples| {
+            let sum: i32 = samples.iter().map(|s| i32::from(*s)).sum();
+            (sum / samples.len() as i32) as i16
+        })
+        .collect();
+
+    if frame.sample_rate_hz == RECORDING_SAMPLE_RATE_HZ || frame.sample_rate_hz == 0 {
+        return mono;
+    }
+
+    let count = (mono.len() as u64 * u64::from(RECORDING_SAMPLE_RATE_HZ)
+        / u64::from(frame.sample_rate_hz)) as usize;
+    let step = f64::from(frame.sample_rate_hz) / f64::from(RECORDING_SAMPLE_RATE_HZ);
+    (0..count)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position as usize;
+            let fraction = position - index as f64;
+            let current = f64::from(mono[index]);
+            let next = f64::from(*mono.get(index + 1).unwrap_or(&mono[index]));
+            (current + (next - current) * fraction).round() as i16
+        })
+        .collect()
+}
+
+/// Mixes the audio, and writes it along with the incoming video to
+/// a `RecordingSink`.
+pub struct Recorder {
+    /// Receives the Matroska stream.
+    sink:             Box<dyn RecordingSink>,
+    /// What is recorded.
+    config:           RecordingConfig,
+    /// Captured audio not mixed yet.
+    captured:         VecDeque<i16>,
+    /// Received audio not mixed yet.
+    received:         VecDeque<i16>,
+    /// Samples of mixed audio written so far, the clock of the
+    /// recording.
+    mixed_samples:    u64,
+    /// Timestamp of the first video frame, and the recording time it
+    /// was received at, in ms.
+    video_start:      Option<(i64, u64)>,
+    /// Timecode of the cluster being filled, in ms.
+    cluster_timecode: u64,
+    /// Blocks of the cluster being filled.
+    cluster:          Vec<u8>,
+    /// The stream is complete.
+    finished:         bool,
+}
+
+impl fmt::Display for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, recorded_ms: {}, finished: {}",
+            self.config,
+            self.time_ms(),
+            self.finished
+        )
+    }
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            warn!("Recorder: unable to finish on drop: {}", e);
+        }
+    }
+}
+
+impl Recorder {
+    /// Create a new Recorder, writing the header of the stream to
+    /// `sink`.
+    pub fn new(mut sink: Box<dyn RecordingSink>, config: RecordingConfig) -> Result<Self> {
+        let mut header = Vec::new();
+        write_header(&mut header, &config);
+        sink.write(&header)?;
+
+        Ok(Self {
+            sink,
+            config,
+            captured: VecDeque::new(),
+            received: VecDeque::new(),
+            mixed_samples: 0,
+            video_start: None,
+            cluster_timecode: 0,
+            cluster: Vec::new(),
+            finished: false,
+        })
+    }
+
+    /// Returns the length of the recording so far, in ms.
+    pub fn time_ms(&self) -> u64 {
+        self.mixed_samples * 1000 / u64::from(RECORDING_SAMPLE_RATE_HZ)
+    }
+
+    /// Add a frame of the captured or received audio, writing the
+    /// audio mixed so far.
+    pub fn on_audio_frame(&mut self, frame: &AudioFrame) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+
+        let samples = to_recording_format(frame);
+        match frame.direction {
+            AudioDirection::Captured => self.captured.extend(samples),
+            AudioDirection::Received => self.received.extend(samples),
+        }
+
+        loop {
+            let ready = self.captured.len().min(self.received.len());
+            let lagging = self.captured.len().max(self.received.len()) > MAX_LAG_SAMPLES;
+            if ready < BLOCK_SAMPLES && !lagging {
+                return Ok(());
+            }
+            self.mix_block()?;
+        }
+    }
+
+    /// Add a frame of the incoming video.
+    ///
+    /// Frames are skipped if the video is not recorded, or of another
+    /// size than configured.
+    pub fn on_video_frame(&mut self, frame: &VideoFrame) -> Result<()> {
+        if self.finished || self.config.video_size != Some((frame.width, frame.height)) {
+            return Ok(());
+        }
+
+        let time_ms = self.time_ms();
+        let (start_us, start_ms) = *self
+            .video_start
+            .get_or_insert((frame.timestamp_us, time_ms));
+        let timecode = start_ms + ((frame.timestamp_us - start_us).max(0) / 1000) as u64;
+        self.write_block(VIDEO_TRACK, timecode, &frame.data)
+    }
+
+    /// Mix the next block of audio, padding a lagging direction with
+    /// silence.
+    fn mix_block(&mut self) -> Result<()> {
+        let mut block = Vec::with_capacity(2 * BLOCK_SAMPLES);
+        for _ in 0..BLOCK_SAMPLES {
+            let captured = self.captured.pop_front().unwrap_or(0);
+            let received = self.received.pop_front().unwrap_or(0);
+            block.extend_from_slice(&captured.saturating_add(received).to_le_bytes());
+        }
+
+        let timecode = self.time_ms();
+        self.mixed_samples += BLOCK_SAMPLES as u64;
+        self.write_block(AUDIO_TRACK, timecode, &block)
+    }
+
+    /// Add a block to the cluster, first writing the cluster if the
+    /// block does not belong in it.
+    fn write_block(&mut self, track: u8, timecode: u64, frame: &[u8]) -> Result<()> {
+        let relative = timecode as i64 - self.cluster_timecode as i64;
+        if self.cluster.is_empty()
+            || relative >= CLUSTER_DURATION_MS as i64
+            || relative < i64::from(i16::MIN)
+        {
+            self.write_cluster()?;
+            self.cluster_timecode = timecode;
+            write_uint(&mut self.cluster, TIMECODE, timecode);
+        }
+
+        let relative = (timecode as i64 - self.cluster_timecode as i64) as i16;
+        let mut block = Vec::with_capacity(frame.len() + 4);
+        block.push(0x80 | track);
+        block.extend_from_slice(&relative.to_be_bytes());
+        // Every frame is a keyframe.
+        block.push(0x80);
+        block.extend_from_slice(frame);
+        write_element(&mut self.cluster, SIMPLE_BLOCK, &block);
+        Ok(())
+    }
+
+    /// Write the cluster being filled, if any.
+    fn write_cluster(&mut self) -> Result<()> {
+        if self.cluster.is_empty() {
+            return Ok(());
+        }
+
+        let mut cluster = Vec::with_capacity(self.cluster.len() + 12);
+        write_element(&mut cluster, CLUSTER, &self.cluster);
+        self.cluster.clear();
+        self.sink.write(&cluster)
+    }
+
+    /// Mix the remaining audio, write the last cluster and finish the
+    /// stream.
+    pub fn finish(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+
+        while !self.captured.is_empty() || !self.received.is_empty() {
+            self.mix_block()?;
+        }
+        self.write_cluster()?;
+        self.sink.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    /// Collects the stream.
+    struct TestSink {
+        stream:   Arc<Mutex<Vec<u8>>>,
+        finished: Arc<Mutex<bool>>,
+    }
+
+    impl RecordingSink for TestSink {
+        fn write(&mut self, data: &[u8]) -> Result<()> {
+            self.stream.lock().unwrap().extend_from_slice(data);
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            *self.finished.lock().unwrap() = true;
+            Ok(())
+        }
+    }
+
+    /// Reads the element at `offset`, returning its ID, and the start
+    /// and size of its body.
+    fn read_element(stream: &[u8], offset: usize) -> (u32, usize, u64) {
+        let id_length = stream[offset].leading_zeros() as usize + 1;
+        let id = stream[offset..offset + id_length]
+            .iter()
+            .fold(0u32, |id, b| (id << 8) | u32::from(*b));
+
+        let start = offset + id_length;
+        let size_length = stream[start].leading_zeros() as usize + 1;
+        let size = stream[start..start + size_length]
+            .iter()
+            .fold(0u64, |size, b| (size << 8) | u64::from(*b))
+            & ((1 << (7 * size_length)) - 1);
+        (id, start + size_length, size)
+    }
+
+    /// Returns the track, relative timecode, size and first sample of
+    /// every block in the clusters of the stream, with the cluster
+    /// timecodes.
+    fn read_blocks(stream: &[u8]) -> (Vec<u64>, Vec<(u8, i16, usize, i16)>) {
+        let mut clusters = Vec::new();
+        let mut blocks = Vec::new();
+
+        let (id, start, size) = read_element(stream, 0);
+        assert_eq!(id, EBML);
+        let (id, mut offset, _) = read_element(stream, start + size as usize);
+        assert_eq!(id, SEGMENT);
+
+        while offset < stream.len() {
+            let (id, start, size) = read_element(stream, offset);
+            let end = start + size as usize;
+            if id == CLUSTER {
+                let mut inner = start;
+                while inner < end {
+                    let (id, start, size) = read_element(stream, inner);
+                    let body = &stream[start..start + size as usize];
+                    if id == TIMECODE {
+                        clusters.push(body.iter().fold(0u64, |t, b| (t << 8) | u64::from(*b)));
+                    } else if id == SIMPLE_BLOCK {
+                        let relative = i16::from_be_bytes([body[1], body[2]]);
+                        let first = i16::from_le_bytes([body[4], body[5]]);
+                        blocks.push((body[0] & 0x7F, relative, body.len() - 4, first));
+                    }
+                    inner = start + size as usize;
+                }
+            }
+            offset = end;
+        }
+        (clusters, blocks)
+    }
+
+    fn audio(direction: AudioDirection, sample_rate_hz: u32, value: i16) -> AudioFrame {
+        AudioFrame {
+            direction,
+            sample_rate_hz,
+            channels: 1,
+            samples: vec![value; (sample_rate_hz / 100) as usize],
+        }
+    }
+
+    #[test]
+    fn check_sizes() {
+        let mut out = Vec::new();
+        write_size(&mut out, 0);
+        write_size(&mut out, 126);
+        write_size(&mut out, 127);
+        write_size(&mut out, 300);
+        assert_eq!(out, vec![0x80, 0xFE, 0x40, 0x7F, 0x41, 0x2C]);
+
+        let mut out = Vec::new();
+        write_id(&mut out, SEGMENT);
+        write_id(&mut out, SIMPLE_BLOCK);
+        assert_eq!(out, vec![0x18, 0x53, 0x80, 0x67, 0xA3]);
+    }
+
+    #[test]
+    fn check_to_recording_format() {
+        let frame = AudioFrame {
+            direction:      AudioDirection::Received,
+            sample_rate_hz: 16000,
+            channels:       2,
+            samples:        vec![100, 300, 400, 400],
+        };
+        assert_eq!(
+            to_recording_format(&frame),
+            vec![200, 267, 333, 400, 400, 400]
+        );
+    }
+
+    #[test]
+    fn check_mixing() {
+        let stream = Arc::new(Mutex::new(Vec::new()));
+        let finished = Arc::new(Mutex::new(false));
+        let sink = TestSink {
+            stream:   Arc::clone(&stream),
+            finished: Arc::clone(&finished),
+        };
+        let mut recorder =
+            Recorder::new(Box::new(sink), RecordingConfig::default().with_video(2, 2)).unwrap();
+
+        // Nothing is mixed until both directions are in.
+        recorder
+            .on_audio_frame(&audio(AudioDirection::Captured, 48000, 1000))
+            .unwrap();
+        assert_eq!(recorder.time_ms(), 0);
+        recorder
+            .on_audio_frame(&audio(AudioDirection::Received, 16000, i16::MAX))
+            .unwrap();
+        assert_eq!(recorder.time_ms(), 10);
+
+        // Video of another size is skipped.
+        let frame = VideoFrame {
+            width:        2,
+            height:       2,
+            timestamp_us: 5_000_000,
+            data:         vec![0; 6],
+        };
+        recorder.on_video_frame(&frame).unwrap();
+        let mut large = frame.clone();
+        large.width = 4;
+        recorder.on_video_frame(&large).unwrap();
+
+        // A silent direction is padded once the other is far ahead.
+        for _ in 0..120 {
+            recorder
+                .on_audio_frame(&audio(AudioDirection::Captured, 48000, 1))
+                .unwrap();
+        }
+        assert_eq!(recorder.time_ms(), 1010);
+
+        let mut later = frame;
+        later.timestamp_us += 1_000_000;
+        recorder.on_video_frame(&later).unwrap();
+        recorder.finish().unwrap();
+        assert!(*finished.lock().unwrap());
+        assert_eq!(recorder.time_ms(), 1210);
+
+        let stream = stream.lock().unwrap();
+        let (clusters, blocks) = read_blocks(&stream);
+        assert_eq!(clusters, vec![0, 1000]);
+        assert_eq!(blocks.len(), 123);
+        // The mix saturates.
+        assert_eq!(blocks[0], (AUDIO_TRACK, 0, 2 * BLOCK_SAMPLES, i16::MAX));
+        assert_eq!(blocks[1], (VIDEO_TRACK, 10, 6, 0));
+        assert_eq!(blocks[101], (AUDIO_TRACK, 0, 2 * BLOCK_SAMPLES, 1));
+        assert_eq!(blocks[102], (VIDEO_TRACK, 10, 6, 0));
+        assert_eq!(blocks[122], (AUDIO_TRACK, 200, 2 * BLOCK_SAMPLES, 1));
+    }
+}
@@ -0,0 +1,273 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Tracking of the ICE route of a connected call.
+//!
+//! Once ICE first connects, the selected candidate pair becomes the
+//! baseline route.  Later changes between a direct and a relayed
+//! route are only reported after the new route has held for a
+//! debounce period, so brief flaps between candidate pairs are not
+//! reported at all.
+//!
+//! The network the route runs over, i.e. the kind of local network
+//! interface and whether it is relayed, is reported as soon as it
+//! changes, for "connecting via relay" or "switched to cellular"
+//! indicators.
+
+use crate::core::call_record::ConnectionType;
+use crate::webrtc::ice_candidate::{IceRoute, NetworkAdapterType};
+
+/// The network an ICE route runs over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetworkRoute {
+    /// The kind of network interface of the local candidate.
+    pub local_adapter_type: NetworkAdapterType,
+    /// Either end of the route is a TURN relay.
+    pub relayed:            bool,
+}
+
+impl From<&IceRoute> for NetworkRoute {
+    fn from(route: &IceRoute) -> Self {
+        Self {
+            local_adapter_type: route.local_adapter_type,
+            relayed:            route.relayed(),
+        }
+    }
+}
+
+/// Debounces changes of the selected ICE route.
+#[derive(Debug, Default)]
+pub struct RouteMonitor {
+    /// The currently selected route.
+    route:      Option<IceRoute>,
+    /// The route last reported, or the baseline route.
+    reported:   Option<IceRoute>,
+    /// The best kind of route reported so far.
+    best:       Option<ConnectionType>,
+    /// The network route last returned by `network_route_changed()`.
+    network:    Option<NetworkRoute>,
+    /// ICE has connected at least once.
+    connected:  bool,
+    /// Incremented for each route change, identifying the pending
+    /// debounce period.
+    generation: u32,
+}
+
+impl RouteMonitor {
+    /// Record a newly selected route.
+    ///
+    /// Returns the generation to settle after the debounce period if
+    /// the route may need reporting, i.e. if it is relayed when the
+    /// reported route is direct or vice versa.
+    pub fn route_changed(&mut self, route: IceRoute) -> Option<u32> {
+        self.generation = self.generation.wrapping_add(1);
+        self.route = Some(route);
+
+        if !self.connected {
+            return None;
+        }
+        if self.reported.is_none() {
+            self.report_route();
+            return None;
+        }
+        if self.relay_changed() {
+            Some(self.generation)
+        } else {
+            None
+        }
+    }
+
+    /// ICE connected for the first time, so the current route becomes
+    /// the baseline.
+    pub fn connected(&mut self) {
+        if !self.connected {
+            self.connected = true;
+            self.report_route();
+        }
+    }
+
+    /// The debounce period for `generation` expired.
+    ///
+    /// Returns the route to report if no other route was selected
+    /// in the meantime and the relayed-ness still differs from the
+    /// reported route.
+    pub fn settled(&mut self, generation: u32) -> Option<IceRoute> {
+        if generation != self.generation || !self.relay_changed() {
+            return None;
+        }
+        self.report_route();
+        self.reported.clone()
+    }
+
+    /// Returns the network route of the current route if it differs
+    /// from the one last returned.
+    ///
+    /// Nothing is returned before ICE connects.
+    pub fn network_route_changed(&mut self) -> Option<NetworkRoute> {
+        if !self.connected {
+            return None;
+        }
+        let network = self.route.as_ref().map(NetworkRoute::from);
+        if network.is_none() || network == self.network {
+            return None;
+        }
+        self.network = network;
+        network
+    }
+
+    /// Returns the route last reported, or the baseline route.
+    pub fn reported(&self) -> Option<IceRoute> {
+        self.reported.clone()
+    }
+
+    /// Returns the best kind of route reported so far, direct being
+    /// better than relayed.
+    pub fn best_connection_type(&self) -> Option<ConnectionType> {
+        self.best
+    }
+
+    fn report_route(&mut self) {
+        self.reported = self.route.clone();
+        if let Some(route) = &self.reported {
+            self.best = self.best.max(Some(ConnectionType::from(route)));
+        }
+    }
+
+    fn relay_changed(&self) -> bool {
+        match (&self.route, &self.reported) {
+            (Some(route), Some(reported)) => route.relayed() != reported.relayed(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn direct() -> IceRoute {
+        IceRoute::new("local", "stun", "udp")
+    }
+
+    fn relay() -> IceRoute {
+        IceRoute::new("relay", "local", "udp")
+    }
+
+    #[test]
+    fn check_baseline() {
+        let mut monitor = RouteMonitor::default();
+        assert_eq!(monitor.route_changed(direct()), None);
+        assert_eq!(monitor.route_changed(relay()), None);
+        assert_eq!(monitor.reported(), None);
+
+        monitor.connected();
+        assert_eq!(monitor.reported(), Some(relay()));
+        assert_eq!(monitor.route_changed(relay()), None);
+    }
+
+    #[test]
+    fn check_baseline_after_connected() {
+        let mut monitor = RouteMonitor::default();
+        monitor.connected();
+        assert_eq!(monitor.reported(), None);
+
+        assert_eq!(monitor.route_changed(direct()), None);
+        assert_eq!(monitor.reported(), Some(direct()));
+    }
+
+    #[test]
+    fn check_settled() {
+        let mut monitor = RouteMonitor::default();
+        monitor.route_changed(direct());
+        monitor.connected();
+
+        let generation = monitor.route_changed(relay()).unwrap();
+        assert_eq!(monitor.settled(generation), Some(relay()));
+        assert_eq!(monitor.settled(generation), None);
+        assert_eq!(monitor.reported(), Some(relay()));
+
+        let generation = monitor.route_changed(direct()).unwrap();
+        assert_eq!(monitor.settled(generation), Some(direct()));
+    }
+
+    #[test]
+    fn check_best_connection_type() {
+        let mut monitor = RouteMonitor::default();
+        monitor.route_changed(relay());
+        assert_eq!(monitor.best_connection_type(), None);
+
+        monitor.connected();
+        assert_eq!(
+            monitor.best_connection_type(),
+            Some(ConnectionType::Relayed)
+        );
+
+        let generation = monitor.route_changed(direct()).unwrap();
+        monitor.settled(generation);
+        let generation = monitor.route_changed(relay()).unwrap();
+        monitor.settled(generation);
+        assert_eq!(monitor.reported(), Some(relay()));
+        assert_eq!(monitor.best_connection_type(), Some(ConnectionType::Direct));
+    }
+
+    #[test]
+    fn check_flap() {
+        let mut monitor = RouteMonitor::default();
+        monitor.route_changed(direct());
+        monitor.connected();
+
+        let first = monitor.route_changed(relay()).unwrap();
+        assert_eq!(monitor.route_changed(direct()), None);
+        assert_eq!(monitor.settled(first), None);
+
+        let second = monitor.route_changed(relay()).unwrap();
+        let third = monitor
+            .route_changed(IceRoute::new("relay", "relay", "tcp"))
+            .unwrap();
+        assert_eq!(monitor.settled(second), None);
+        assert_eq!(
+            monitor.settled(third),
+            Some(IceRoute::new("relay", "relay", "tcp"))
+        );
+    }
+
+    #[test]
+    fn check_network_route_changed() {
+        let wifi = |route: IceRoute| route.with_local_adapter_type(NetworkAdapterType::Wifi);
+        let cellular =
+            |route: IceRoute| route.with_local_adapter_type(NetworkAdapterType::Cellular);
+
+        let mut monitor = RouteMonitor::default();
+        assert_eq!(monitor.network_route_changed(), None);
+        monitor.route_changed(wifi(direct()));
+        assert_eq!(monitor.network_route_changed(), None);
+
+        monitor.connected();
+        assert_eq!(
+            monitor.network_route_changed(),
+            Some(NetworkRoute {
+                local_adapter_type: NetworkAdapterType::Wifi,
+                relayed:            false,
+            })
+        );
+        assert_eq!(monitor.network_route_changed(), None);
+
+        // Another candidate pair over the same network.
+        monitor.route_changed(wifi(IceRoute::new("prflx", "local", "udp")));
+        assert_eq!(monitor.network_route_changed(), None);
+
+        // Reported without waiting for the debounce period.
+        monitor.route_changed(cellular(relay()));
+        assert_eq!(
+            monitor.network_route_changed(),
+            Some(NetworkRoute {
+                local_adapter_type: NetworkAdapterType::Cellular,
+                relayed:            true,
+            })
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Safety numbers, a short authentication string users compare to
+//! verify a call.
+//!
+//! The media is encrypted with keys negotiated over DTLS, whose
+//! certificates are only authenticated by the fingerprints exchanged
+//! in the offer and answer.  Whoever can rewrite the signaling
+//! messages can thus substitute their own certificates.  Both sides
+//! derive the safety numbers from the two fingerprints they used, so
+//! the numbers only match, when read aloud, if the fingerprints were
+//! not substituted.
+
+use sha2::{Digest, Sha256};
+
+/// Prefix of the SDP attribute carrying the DTLS fingerprint.
+const FINGERPRINT_ATTRIBUTE: &str = "a=fingerprint:";
+
+/// Domain separation for the hash of the fingerprints.
+const SAFETY_NUMBERS_LABEL: &[u8] = b"RingRTC Safety Numbers";
+
+/// Number of groups of digits in the safety numbers.
+const SAFETY_NUMBERS_GROUPS: usize = 3;
+
+/// Number of digits in each group, read aloud together.
+const SAFETY_NUMBERS_GROUP_DIGITS: usize = 5;
+
+/// Returns the DTLS fingerprint of `sdp`, e.g.
+/// `sha-256 4A:AD:B9:...`, with the hash function in lowercase and
+/// the digest in uppercase, as both sides may write them either way.
+pub fn fingerprint(sdp: &str) -> Option<String> {
+    sdp.lines()
+        .map(str::trim)
+        .find(|line| line.starts_with(FINGERPRINT_ATTRIBUTE))
+        .and_then(|line| {
+            let mut fields = line[FINGERPRINT_ATTRIBUTE.len()..].split_whitespace();
+            let hash_function = fields.next()?;
+            let digest = fields.next()?;
+            Some(format!(
+                "{} {}",
+                hash_function.to_ascii_lowercase(),
+                digest.to_ascii_uppercase()
+            ))
+        })
+}
+
+/// The DTLS fingerprints of both sides of a connection.
+#[derive(Clone, Debug, Default)]
+pub struct Fingerprints {
+    /// The fingerprint of the local description.
+    local:  Option<String>,
+    /// The fingerprint of the remote description.
+    remote: Option<String>,
+}
+
+impl Fingerprints {
+    /// Records the fingerprint of the local description `sdp`.
+    pub fn set_local(&mut self, sdp: &str) {
+        self.local = fingerprint(sdp);
+    }
+
+    /// Records the fingerprint of the remote description `sdp`.
+    pub fn set_remote(&mut self, sdp: &str) {
+        self.remote = fingerprint(sdp);
+    }
+
+    /// Returns the safety numbers derived from both fingerprints, or
+    /// `None` until both are known.
+    pub fn safety_numbers(&self) -> Option<String> {
+        match (&self.local, &self.remote) {
+            (Some(local), Some(remote)) => Some(safety_numbers(local, remote)),
+            _ => None,
+        }
+    }
+}
+
+/// Derives the safety numbers of two fingerprints, e.g.
+/// `01234 56789 01234`.
+///
+/// The fingerprints are hashed in sorted order, so both sides derive
+/// the same numbers from their own local and remote fingerprints.
+pub fn safety_numbers(local: &str, remote: &str) -> String {
+    let (first, second) = if local <= remote {
+        (local, remote)
+    } else {
+        (remote, local)
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.input(SAFETY_NUMBERS_LABEL);
+    hasher.input([0]);
+    hasher.input(first.as_bytes());
+    hasher.input([0]);
+    hasher.input(second.as_bytes());
+    let hash = hasher.result();
+
+    // Each group takes the next 40 bits of the hash, whose bias when
+    // reduced to five digits is negligible.
+    let modulus = 10u64.pow(SAFETY_NUMBERS_GROUP_DIGITS as u32);
+    hash.chunks(5)
+        .take(SAFETY_NUMBERS_GROUPS)
+        .map(|chunk| {
+            let value = chunk
+                .iter()
+                .fold(0u64, |value, byte| (value << 8) | u64::from(*byte));
+            format!(
+                "{:0width$}",
+                value % modulus,
+                width = SAFETY_NUMBERS_GROUP_DIGITS
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCAL: &str = "sha-256 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:AB:3C:A0:5E:B4:E6:A8:5B:1C:D8:47:E2:BB";
+    const REMOTE: &str = "sha-256 2E:7C:B3:01:0A:48:1C:95:D3:36:58:5B:71:AA:2F:C4:93:E0:6D:44:B8:10:97:CE:0F:6A:D2:85:3C:19:E7:66";
+
+    #[test]
+    fn check_fingerprint() {
+        let sdp = format!(
+            "v=0\r\ns=-\r\nm=audio 9\r\na=fingerprint:{}\r\na=setup:actpass\r\n",
+            LOCAL
+        );
+        assert_eq!(fingerprint(&sdp), Some(LOCAL.to_owned()));
+
+        let sdp = "v=0\r\na=fingerprint:SHA-256 4a:ad:b9\r\n";
+        assert_eq!(fingerprint(sdp), Some("sha-256 4A:AD:B9".to_owned()));
+
+        assert_eq!(fingerprint("FAKE SDP"), None);
+        assert_eq!(fingerprint("a=fingerprint:sha-256"), None);
+    }
+
+    #[test]
+    fn check_safety_numbers() {
+        let numbers = safety_numbers(LOCAL, REMOTE);
+        assert_eq!(numbers.len(), 17);
+        assert!(numbers
+            .split(' ')
+            .all(|group| group.len() == 5 && group.chars().all(|c| c.is_ascii_digit())));
+
+        // Both sides derive the same numbers.
+        assert_eq!(safety_numbers(REMOTE, LOCAL), numbers);
+        assert_ne!(safety_numbers(LOCAL, LOCAL), numbers);
+    }
+
+    #[test]
+    fn check_fingerprints() {
+        let mut fingerprints = Fingerprints::default();
+        assert_eq!(fingerprints.safety_numbers(), None);
+
+        fingerprints.set_local(&format!("a=fingerprint:{}", LOCAL));
+        assert_eq!(fingerprints.safety_numbers(), None);
+
+        fingerprints.set_remote(&format!("a=fingerprint:{}", REMOTE));
+        assert_eq!(
+            fingerprints.safety_numbers(),
+            Some(safety_numbers(LOCAL, REMOTE))
+        );
+
+        fingerprints.set_remote("FAKE SDP");
+        assert_eq!(fingerprints.safety_numbers(), None);
+    }
+}
@@ -0,0 +1,242 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Ordering of received signaling messages.
+//!
+//! Push based transports may deliver a signaling message twice, or
+//! deliver ICE candidates before the offer they belong to.  With
+//! opaque signaling, see `CallManager::set_opaque_signaling()`, every
+//! offer, answer and batch of ICE candidates carries a sequence
+//! number, counting from 1 for each connection, so duplicates are
+//! dropped on receipt.  ICE candidates arriving before the offer of
+//! their call, sequenced or not, are held until the offer arrives.
+//!
+//! Independently of sequencing, the offers and hangups recently
+//! received from each device are remembered, so an old offer or
+//! hangup delivered again, e.g. by a buggy or malicious transport,
+//! is rejected instead of ringing a call that already ended.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::common::{ConnectionId, HangupType};
+use crate::core::rate_limit::MAX_RECEIVED_ICE_CANDIDATES;
+use crate::webrtc::ice_candidate::IceCandidate;
+
+/// The sequence number of unsequenced messages.
+pub const UNSEQUENCED: u64 = 0;
+
+/// Maximum number of connections whose received sequence numbers
+/// are remembered.
+const MAX_SEQUENCED_CONNECTIONS: usize = 32;
+
+/// Maximum number of connections whose early ICE candidates are
+/// held.
+const MAX_EARLY_ICE_CONNECTIONS: usize = 8;
+
+/// Maximum number of received offers and hangups remembered.
+const MAX_RECEIVED_MESSAGES: usize = 64;
+
+/// The sequence numbers received on recent connections.
+#[derive(Default)]
+pub struct ReceivedSequences {
+    connections: VecDeque<(ConnectionId, HashSet<u64>)>,
+}
+
+impl ReceivedSequences {
+    /// Record `sequence` as received on `connection_id`, returning
+    /// `false` if it was received before.  Unsequenced messages are
+    /// never duplicates.
+    pub fn record(&mut self, connection_id: ConnectionId, sequence: u64) -> bool {
+        if sequence == UNSEQUENCED {
+            return true;
+        }
+
+        if let Some((_, received)) = self
+            .connections
+            .iter_mut()
+            .find(|(id, _)| *id == connection_id)
+        {
+            return received.insert(sequence);
+        }
+
+        if self.connections.len() >= MAX_SEQUENCED_CONNECTIONS {
+            let _ = self.connections.pop_front();
+        }
+        let mut received = HashSet::new();
+        received.insert(sequence);
+        self.connections.push_back((connection_id, received));
+        true
+    }
+}
+
+/// A received message remembered to detect replays.  Each type of
+/// hangup is a message of its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReceivedMessage {
+    Offer,
+    Hangup(HangupType),
+}
+
+/// The offers and hangups recently received, by connection, i.e. by
+/// CallId and sender device.
+#[derive(Default)]
+pub struct ReceivedMessages {
+    messages: VecDeque<(ConnectionId, ReceivedMessage)>,
+}
+
+impl ReceivedMessages {
+    /// Record `message` as received on `connection_id`, returning
+    /// `false` if it was received before.
+    ///
+    /// Only the most recent messages are remembered.
+    pub fn record(&mut self, connection_id: ConnectionId, message: ReceivedMessage) -> bool {
+        if self.messages.contains(&(connection_id, message)) {
+            return false;
+        }
+
+        if self.messages.len() >= MAX_RECEIVED_MESSAGES {
+            let _ = self.messages.pop_front();
+        }
+        self.messages.push_back((connection_id, message));
+        true
+    }
+}
+
+/// ICE candidates received before the offer of their call.
+#[derive(Default)]
+pub struct EarlyIceCandidates {
+    connections: VecDeque<(ConnectionId, Vec<IceCandidate>)>,
+}
+
+impl EarlyIceCandidates {
+    /// Hold `candidates` until the offer of `connection_id` arrives.
+    ///
+    /// Only the candidates of the most recent connections are held,
+    /// and at most `MAX_RECEIVED_ICE_CANDIDATES` of each.
+    pub fn hold(&mut self, connection_id: ConnectionId, candidates: Vec<IceCandidate>) {
+        let held = match self
+            .connections
+            .iter_mut()
+            .find(|(id, _)| *id == connection_id)
+        {
+            Some((_, held)) => held,
+            None => {
+                if self.connections.len() >= MAX_EARLY_ICE_CONNECTIONS {
+                    let _ = self.connections.pop_front();
+                }
+                self.connections.push_back((connection_id, Vec::new()));
+                &mut self.connections.back_mut().unwrap().1
+            }
+        };
+
+        let room = MAX_RECEIVED_ICE_CANDIDATES.saturating_sub(held.len());
+        held.extend(candidates.into_iter().take(room));
+    }
+
+    /// Take the candidates held for `connection_id`, if any.
+    pub fn take(&mut self, connection_id: ConnectionId) -> Option<Vec<IceCandidate>> {
+        let index = self
+            .connections
+            .iter()
+            .position(|(id, _)| *id == connection_id)?;
+        self.connections
+            .remove(index)
+            .map(|(_, candidates)| candidates)
+    }
+
+    /// Drop all the held candidates.
+    pub fn clear(&mut self) {
+        self.connections.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::CallId;
+
+    fn connection_id(call_id: u64) -> ConnectionId {
+        ConnectionId::new(CallId::new(call_id), 1)
+    }
+
+    fn candidate(sdp: &str) -> IceCandidate {
+        IceCandidate::new("0".to_owned(), 0, sdp.to_owned())
+    }
+
+    #[test]
+    fn check_received_sequences() {
+        let mut sequences = ReceivedSequences::default();
+        assert!(sequences.record(connection_id(1), 1));
+        assert!(sequences.record(connection_id(1), 3));
+        assert!(sequences.record(connection_id(1), 2));
+        assert!(!sequences.record(connection_id(1), 3));
+        assert!(sequences.record(connection_id(2), 3));
+
+        // Unsequenced messages are never duplicates.
+        assert!(sequences.record(connection_id(1), UNSEQUENCED));
+        assert!(sequences.record(connection_id(1), UNSEQUENCED));
+
+        // The oldest connections are forgotten.
+        for id in 3..(MAX_SEQUENCED_CONNECTIONS as u64 + 2) {
+            assert!(sequences.record(connection_id(id), 1));
+        }
+        assert!(sequences.record(connection_id(1), 1));
+    }
+
+    #[test]
+    fn check_received_messages() {
+        let hangup = ReceivedMessage::Hangup(HangupType::Normal);
+        let declined = ReceivedMessage::Hangup(HangupType::Declined);
+
+        let mut messages = ReceivedMessages::default();
+        assert!(messages.record(connection_id(1), ReceivedMessage::Offer));
+        assert!(messages.record(connection_id(1), hangup));
+        assert!(messages.record(connection_id(2), ReceivedMessage::Offer));
+        assert!(!messages.record(connection_id(1), ReceivedMessage::Offer));
+        assert!(!messages.record(connection_id(1), hangup));
+        assert!(messages.record(connection_id(1), declined));
+
+        let other_device = ConnectionId::new(CallId::new(1), 2);
+        assert!(messages.record(other_device, ReceivedMessage::Offer));
+
+        // The oldest messages are forgotten.
+        for id in 3..(MAX_RECEIVED_MESSAGES as u64 + 3) {
+            assert!(messages.record(connection_id(id), ReceivedMessage::Offer));
+        }
+        assert!(messages.record(connection_id(1), ReceivedMessage::Offer));
+    }
+
+    #[test]
+    fn check_early_ice_candidates() {
+        let mut early = EarlyIceCandidates::default();
+        early.hold(connection_id(1), vec![candidate("ICE-1")]);
+        early.hold(connection_id(1), vec![candidate("ICE-2")]);
+        early.hold(connection_id(2), vec![candidate("ICE-3")]);
+
+        let held = early.take(connection_id(1)).unwrap();
+        assert_eq!(
+            held.iter().map(|c| c.sdp.as_str()).collect::<Vec<_>>(),
+            vec!["ICE-1", "ICE-2"]
+        );
+        assert!(early.take(connection_id(1)).is_none());
+
+        let many = vec![candidate("ICE"); MAX_RECEIVED_ICE_CANDIDATES + 1];
+        early.hold(connection_id(2), many);
+        assert_eq!(
+            early.take(connection_id(2)).unwrap().len(),
+            MAX_RECEIVED_ICE_CANDIDATES
+        );
+
+        for id in 0..(MAX_EARLY_ICE_CONNECTIONS as u64 + 1) {
+            early.hold(connection_id(id), vec![candidate("ICE")]);
+        }
+        assert!(early.take(connection_id(0)).is_none());
+        early.clear();
+        assert!(early.take(connection_id(1)).is_none());
+    }
+}
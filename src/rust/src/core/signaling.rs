@@ -0,0 +1,726 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Versions of the signaling protocol, negotiated with each offer and
+//! answer.
+//!
+//! Offers and answers travel as SDP strings.  Versioned clients add a
+//! session level `a=x-ringrtc-version:` attribute, which legacy
+//! clients, like WebRTC itself, ignore.  The callee answers with the
+//! lowest version both sides know, and answers an offer without the
+//! attribute with the plain legacy SDP, so future changes, e.g. new
+//! SDP formats or opaque messages, can be gated on the negotiated
+//! version while old clients keep working.
+//!
+//! The callee may attach opaque metadata to its answer, e.g. the
+//! features it supports, in a session level `a=x-ringrtc-metadata:`
+//! attribute holding the metadata in hex, see
+//! `CallManager::set_answer_metadata()`.
+//!
+//! The caller marks the offers that renegotiate an established call,
+//! i.e. hard reset the connection or restart ICE, with a session
+//! level `a=x-ringrtc-renegotiation:` attribute holding the number of
+//! renegotiation offers sent and the kind of renegotiation, see
+//! `Renegotiation`.  The callee only acts on an offer for an active
+//! call if it is marked as a newer renegotiation than any it already
+//! answered, so a duplicated offer does not disturb the call.
+//!
+//! Since version 2 clients accept compressed SDP, see `decompress()`.
+//! Common SDP lines and tokens are replaced from a fixed dictionary,
+//! keeping the message printable, so it still fits SMS or push
+//! payloads that would reject binary data.  Answers are compressed
+//! once the caller's version is known, offers only if the application
+//! knows the callee accepts them, see
+//! `CallManager::set_sdp_compression()`.
+//!
+//! Applications that carry signaling in their own protobuf messages
+//! can instead exchange offers and answers as opaque bytes, see
+//! `CallManager::set_opaque_signaling()`.  The bytes are an `Opaque`
+//! message of `protobuf/signaling.proto`, so the application may
+//! embed, compress or re-encode them, as long as the remote peer gets
+//! the same bytes back.
+//!
+//! Deployments whose signaling channel is not end-to-end encrypted
+//! can have the opaque messages sealed before they leave the library,
+//! see `seal()`.  The application encrypts and authenticates them with
+//! a key of its own in `Platform::seal()`, and the remote peer opens
+//! them again in `Platform::open()`.
+
+use std::fmt;
+
+use prost::Message;
+
+use crate::common::{ConnectionId, Result};
+use crate::core::platform::Platform;
+use crate::core::sequencing::UNSEQUENCED;
+use crate::error::RingRtcError;
+use crate::protobuf::signaling as protobuf;
+
+/// Version of the clients that send plain SDP strings.
+pub const LEGACY_PROTOCOL_VERSION: u32 = 0;
+
+/// First version of the clients that accept compressed SDP.
+pub const COMPRESSION_PROTOCOL_VERSION: u32 = 2;
+
+/// Version of the signaling protocol spoken by this library.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// The SDP attribute carrying the version, up to the version itself.
+const VERSION_ATTRIBUTE: &str = "a=x-ringrtc-version:";
+
+/// The SDP attribute carrying the answer metadata, up to the metadata
+/// itself, in hex.
+const METADATA_ATTRIBUTE: &str = "a=x-ringrtc-metadata:";
+
+/// The SDP attribute marking a renegotiation offer, up to its
+/// generation and kind.
+const RENEGOTIATION_ATTRIBUTE: &str = "a=x-ringrtc-renegotiation:";
+
+/// The kind of a renegotiation offer that hard resets the
+/// connection.
+const RESET_RENEGOTIATION: &str = "reset";
+
+/// The kind of a renegotiation offer that restarts ICE.
+const ICE_RESTART_RENEGOTIATION: &str = "ice-restart";
+
+/// Character starting a dictionary token in compressed SDP.  SDP
+/// containing it is never compressed.
+const ESCAPE: char = '`';
+
+/// The start of a compressed SDP.
+const COMPRESSED_MARKER: &str = "`!";
+
+/// The first byte of a sealed opaque message.  Unsealed opaque
+/// messages never start with it, as it is not a valid protobuf field
+/// key.
+const ENVELOPE_MARKER: u8 = 0xff;
+
+/// The dictionary of compressed SDP, each entry replaced by `ESCAPE`
+/// and its token.  Entries are replaced in order, so an entry comes
+/// before any entry it contains.  Only append to it, as the remote
+/// peer must expand the same tokens.
+const DICTIONARY: &[(char, &str)] = &[
+    ('a', "\r\na=rtcp-fb:"),
+    ('b', "\r\na=rtpmap:"),
+    ('c', "\r\na=fmtp:"),
+    ('d', "\r\na=extmap:"),
+    ('e', "\r\na=ssrc:"),
+    ('f', "\r\na=candidate:"),
+    ('g', "\r\na=ice-ufrag:"),
+    ('h', "\r\na=ice-pwd:"),
+    ('i', "\r\na=fingerprint:sha-256 "),
+    ('j', "\r\na=setup:"),
+    ('k', "\r\na=mid:"),
+    ('l', "\r\na=rtcp-mux"),
+    ('m', "\r\na=rtcp-rsize"),
+    ('n', "\r\na=sendrecv"),
+    ('o', "\r\na=msid:"),
+    ('p', "\r\na=ssrc-group:FID "),
+    ('q', "urn:ietf:params:rtp-hdrext:"),
+    ('r', "http://www.webrtc.org/experiments/rtp-hdrext/"),
+    ('s', "UDP/TLS/RTP/SAVPF"),
+    ('t', " IN IP4 "),
+    ('u', " transport-cc"),
+    ('v', " goog-remb"),
+    ('w', " nack pli"),
+    ('x', " ccm fir"),
+    ('y', " cname:"),
+    ('z', " generation 0"),
+    ('A', " typ host"),
+    ('B', "/90000"),
+    ('C', "/48000/2"),
+    ('D', "\r\nm="),
+    ('E', "\r\na="),
+    ('F', "\r\n"),
+];
+
+/// Returns the version both sides speak, given the version of the
+/// remote peer.
+pub fn negotiate(remote_version: u32) -> u32 {
+    remote_version.min(PROTOCOL_VERSION)
+}
+
+/// A renegotiation of an established call, sent by the caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Renegotiation {
+    /// Renegotiation offers sent on the connection, this one
+    /// included, telling a newer offer from a duplicate.
+    pub generation: u32,
+    /// `true` if the caller rebuilt its PeerConnection, i.e. hard
+    /// reset the connection, rather than restarting ICE.
+    pub reset:      bool,
+}
+
+impl fmt::Display for Renegotiation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = if self.reset {
+            RESET_RENEGOTIATION
+        } else {
+            ICE_RESTART_RENEGOTIATION
+        };
+        write!(f, "{} {}", self.generation, kind)
+    }
+}
+
+impl Renegotiation {
+    /// Parse the value of the renegotiation attribute.
+    fn parse(value: &str) -> Option<Self> {
+        let mut fields = value.trim().split(' ');
+        let generation = fields.next()?.parse().ok()?;
+        let reset = match fields.next()? {
+            RESET_RENEGOTIATION => true,
+            ICE_RESTART_RENEGOTIATION => false,
+            _ => return None,
+        };
+        Some(Self { generation, reset })
+    }
+}
+
+/// An SDP offer and the protocol version of its sender.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Offer {
+    /// The protocol version of the sender.
+    pub version:       u32,
+    /// The SDP, without the version and renegotiation attributes.
+    pub sdp:           String,
+    /// The sequence number of the opaque message carrying the offer,
+    /// see `core::sequencing`.
+    pub sequence:      u64,
+    /// The renegotiation the offer is, if it renegotiates an
+    /// established call.
+    pub renegotiation: Option<Renegotiation>,
+}
+
+impl fmt::Display for Offer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "version: {}, sdp length: {}",
+            self.version,
+            self.sdp.len()
+        )
+    }
+}
+
+impl Offer {
+    /// Create an offer of the current protocol version.
+    pub fn new(sdp: String) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            sdp,
+            sequence: UNSEQUENCED,
+            renegotiation: None,
+        }
+    }
+
+    /// Parse an offer received from the remote peer.
+    pub fn from_wire(message: &str) -> Self {
+        let (version, sdp) = split_version(message);
+        let (renegotiation, sdp) = split_renegotiation(&sdp);
+        Self {
+            version,
+            sdp,
+            sequence: UNSEQUENCED,
+            renegotiation,
+        }
+    }
+
+    /// Return the offer as sent to the remote peer.
+    pub fn to_wire(&self) -> String {
+        add_version(&self.marked_sdp(), self.version)
+    }
+
+    /// Return the offer as sent to a remote peer that accepts
+    /// compressed SDP.
+    pub fn to_compressed_wire(&self) -> String {
+        compress(&self.to_wire())
+    }
+
+    /// Parse an opaque offer received from the remote peer.
+    pub fn from_opaque(opaque: &[u8]) -> Result<Self> {
+        let (version, sdp, sequence, _) = decode_opaque(opaque)?;
+        let (renegotiation, sdp) = split_renegotiation(&sdp);
+        Ok(Self {
+            version,
+            sdp,
+            sequence,
+            renegotiation,
+        })
+    }
+
+    /// Return the offer as opaque bytes for the remote peer.
+    pub fn to_opaque(&self) -> Vec<u8> {
+        encode_opaque(&self.marked_sdp(), self.version, self.sequence, None)
+    }
+
+    /// Return the SDP with the renegotiation attribute, if any.
+    fn marked_sdp(&self) -> String {
+        match &self.renegotiation {
+            Some(renegotiation) => add_attribute(
+                &self.sdp,
+                &format!("{}{}", RENEGOTIATION_ATTRIBUTE, renegotiation),
+            ),
+            None => self.sdp.clone(),
+        }
+    }
+}
+
+/// An SDP answer and the protocol version negotiated by its sender.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    /// The negotiated protocol version.
+    pub version:  u32,
+    /// The SDP, without the version and metadata attributes.
+    pub sdp:      String,
+    /// The sequence number of the opaque message carrying the answer,
+    /// see `core::sequencing`.
+    pub sequence: u64,
+    /// The metadata the callee attached to the answer, if any.
+    pub metadata: Option<Vec<u8>>,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "version: {}, sdp length: {}",
+            self.version,
+            self.sdp.len()
+        )
+    }
+}
+
+impl Answer {
+    /// Create an answer of the negotiated protocol `version`.
+    pub fn new(sdp: String, version: u32) -> Self {
+        Self {
+            version,
+            sdp,
+            sequence: UNSEQUENCED,
+            metadata: None,
+        }
+    }
+
+    /// Parse an answer received from the remote peer.
+    pub fn from_wire(message: &str) -> Self {
+        let (version, sdp) = split_version(message);
+        let (metadata, sdp) = split_attribute(&sdp, METADATA_ATTRIBUTE);
+        Self {
+            version,
+            sdp,
+            sequence: UNSEQUENCED,
+            metadata: metadata.and_then(|metadata| decode_hex(metadata.trim())),
+        }
+    }
+
+    /// Return the answer as sent to the remote peer, a plain SDP for
+    /// a legacy one without metadata.
+    pub fn to_wire(&self) -> String {
+        match &self.metadata {
+            Some(metadata) => {
+                let attribute = format!("{}{}", METADATA_ATTRIBUTE, encode_hex(metadata));
+                add_version(&add_attribute(&self.sdp, &attribute), self.version)
+            }
+            None => add_version(&self.sdp, self.version),
+        }
+    }
+
+    /// Return the answer as sent to a remote peer that accepts
+    /// compressed SDP.
+    pub fn to_compressed_wire(&self) -> String {
+        compress(&self.to_wire())
+    }
+
+    /// Parse an opaque answer received from the remote peer.
+    pub fn from_opaque(opaque: &[u8]) -> Result<Self> {
+        let (version, sdp, sequence, metadata) = decode_opaque(opaque)?;
+        Ok(Self {
+            version,
+            sdp,
+            sequence,
+            metadata,
+        })
+    }
+
+    /// Return the answer as opaque bytes for the remote peer.
+    pub fn to_opaque(&self) -> Vec<u8> {
+        encode_opaque(
+            &self.sdp,
+            self.version,
+            self.sequence,
+            self.metadata.clone(),
+        )
+    }
+}
+
+/// Add the version attribute to the session section of `sdp`, unless
+/// the version is the legacy one.
+fn add_version(sdp: &str, version: u32) -> String {
+    if version == LEGACY_PROTOCOL_VERSION {
+        return sdp.to_owned();
+    }
+
+    add_attribute(sdp, &format!("{}{}", VERSION_ATTRIBUTE, version))
+}
+
+/// Add a session level `attribute` line to `sdp`, before the first
+/// media section.
+fn add_attribute(sdp: &str, attribute: &str) -> String {
+    match sdp.find("\r\nm=") {
+        Some(pos) => format!("{}{}\r\n{}", &sdp[..pos + 2], attribute, &sdp[pos + 2..]),
+        None if sdp.ends_with("\r\n") => format!("{}{}\r\n", sdp, attribute),
+        None => format!("{}\r\n{}", sdp, attribute),
+    }
+}
+
+/// Split a received message into the version of its sender and the
+/// SDP without the version attribute, exactly as it was before
+/// `add_version()`.
+fn split_version(message: &str) -> (u32, String) {
+    let (version, sdp) = split_attribute(message, VERSION_ATTRIBUTE);
+    let version = version
+        .and_then(|version| version.trim().parse().ok())
+        .unwrap_or(LEGACY_PROTOCOL_VERSION);
+    (version, sdp)
+}
+
+/// Split a received offer into the renegotiation it is marked as, if
+/// any, and the SDP without the renegotiation attribute.
+///
+/// A malformed attribute is dropped, leaving the offer unmarked.
+fn split_renegotiation(message: &str) -> (Option<Renegotiation>, String) {
+    let (renegotiation, sdp) = split_attribute(message, RENEGOTIATION_ATTRIBUTE);
+    (
+        renegotiation.and_then(|renegotiation| Renegotiation::parse(&renegotiation)),
+        sdp,
+    )
+}
+
+/// Split a received message into the value of the `attribute` line,
+/// if any, and the SDP without that line, exactly as it was before
+/// `add_attribute()`.
+fn split_attribute(message: &str, attribute: &str) -> (Option<String>, String) {
+    let start = if message.starts_with(attribute) {
+        0
+    } else {
+        match message.find(&format!("\r\n{}", attribute)) {
+            Some(pos) => pos + 2,
+            None => return (None, message.to_owned()),
+        }
+    };
+
+    let value_start = start + attribute.len();
+    let (value_end, line_end) = match message[value_start..].find("\r\n") {
+        Some(pos) => (value_start + pos, value_start + pos + 2),
+        None => (message.len(), message.len()),
+    };
+    let value = message[value_start..value_end].to_owned();
+
+    let sdp = if line_end == message.len() && value_end == line_end && start >= 2 {
+        // The attribute was appended as the last line.
+        message[..start - 2].to_owned()
+    } else {
+        format!("{}{}", &message[..start], &message[line_end..])
+    };
+    (Some(value), sdp)
+}
+
+/// Compress a message, unless that does not make it shorter.
+fn compress(message: &str) -> String {
+    if message.contains(ESCAPE) {
+        return message.to_owned();
+    }
+
+    let mut compressed = message.to_owned();
+    for (token, entry) in DICTIONARY {
+        if compressed.contains(entry) {
+            compressed = compressed.replace(entry, &format!("{}{}", ESCAPE, token));
+        }
+    }
+
+    if COMPRESSED_MARKER.len() + compressed.len() < message.len() {
+        format!("{}{}", COMPRESSED_MARKER, compressed)
+    } else {
+        message.to_owned()
+    }
+}
+
+/// Return a received offer or answer as it was before compression,
+/// or unchanged if it was not compressed.
+pub fn decompress(message: String) -> Result<String> {
+    if !message.starts_with(COMPRESSED_MARKER) {
+        return Ok(message);
+    }
+
+    let mut decompressed = String::with_capacity(message.len() * 2);
+    let mut chars = message[COMPRESSED_MARKER.len()..].chars();
+    while let Some(c) = chars.next() {
+        if c != ESCAPE {
+            decompressed.push(c);
+            continue;
+        }
+        let token = chars.next();
+        match DICTIONARY.iter().find(|(t, _)| Some(*t) == token) {
+            Some((_, entry)) => decompressed.push_str(entry),
+            None => {
+                return Err(RingRtcError::InvalidCompressedSdp(format!(
+                    "unknown token {:?}",
+                    token
+                ))
+                .into())
+            }
+        }
+    }
+    Ok(decompressed)
+}
+
+/// Encode the version, SDP, sequence number and metadata as an
+/// opaque message.
+fn encode_opaque(sdp: &str, version: u32, sequence: u64, metadata: Option<Vec<u8>>) -> Vec<u8> {
+    let opaque = protobuf::Opaque {
+        version: Some(version),
+        sdp: Some(sdp.as_bytes().to_vec()),
+        sequence: Some(sequence),
+        metadata,
+    };
+    let mut bytes = Vec::with_capacity(opaque.encoded_len());
+    // Encoding only fails if the buffer is too small.
+    let _ = opaque.encode(&mut bytes);
+    bytes
+}
+
+/// Decode an opaque message into the version of its sender, the SDP,
+/// the sequence number and the metadata.
+fn decode_opaque(opaque: &[u8]) -> Result<(u32, String, u64, Option<Vec<u8>>)> {
+    let opaque = protobuf::Opaque::decode(opaque)
+        .map_err(|e| RingRtcError::InvalidOpaqueMessage(e.to_string()))?;
+    let sdp = match opaque.sdp {
+        Some(sdp) => String::from_utf8(sdp)
+            .map_err(|_| RingRtcError::InvalidOpaqueMessage("sdp is not UTF-8".to_owned()))?,
+        None => return Err(RingRtcError::InvalidOpaqueMessage("missing sdp".to_owned()).into()),
+    };
+    Ok((
+        opaque.version.unwrap_or(LEGACY_PROTOCOL_VERSION),
+        sdp,
+        opaque.sequence.unwrap_or(UNSEQUENCED),
+        opaque.metadata,
+    ))
+}
+
+/// Encode bytes as lowercase hex.
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decode hex, or return `None` if it is not valid hex.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Seal an opaque message for `remote_peer` with `Platform::seal()`,
+/// or return it unchanged if the application does not seal messages.
+pub fn seal<T>(
+    platform: &T,
+    remote_peer: &<T as Platform>::AppRemotePeer,
+    connection_id: ConnectionId,
+    opaque: Vec<u8>,
+) -> Result<Vec<u8>>
+where
+    T: Platform,
+{
+    match platform.seal(remote_peer, connection_id, &opaque)? {
+        Some(sealed) => {
+            let mut envelope = Vec::with_capacity(sealed.len() + 1);
+            envelope.push(ENVELOPE_MARKER);
+            envelope.extend_from_slice(&sealed);
+            Ok(envelope)
+        }
+        None => Ok(opaque),
+    }
+}
+
+/// Open an opaque message received on `connection_id` with
+/// `Platform::open()`, or return it unchanged if it is not sealed.
+pub fn open<T>(platform: &T, connection_id: ConnectionId, opaque: &[u8]) -> Result<Vec<u8>>
+where
+    T: Platform,
+{
+    if is_sealed(opaque) {
+        platform.open(connection_id, &opaque[1..])
+    } else {
+        Ok(opaque.to_vec())
+    }
+}
+
+/// Returns `true` if the opaque message is sealed, see `seal()`.
+pub fn is_sealed(opaque: &[u8]) -> bool {
+    opaque.first() == Some(&ENVELOPE_MARKER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ice_encoding::encode_ice_candidates;
+
+    const SDP: &str = "v=0\r\n\
+                       o=- 1 2 IN IP4 127.0.0.1\r\n\
+                       s=-\r\n\
+                       t=0 0\r\n\
+                       m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                       a=rtpmap:111 opus/48000/2\r\n";
+
+    #[test]
+    fn check_round_trip() {
+        let offer = Offer::new(SDP.to_owned());
+        let wire = offer.to_wire();
+        assert!(wire.contains(&format!(
+            "t=0 0\r\na=x-ringrtc-version:{}\r\nm=audio",
+            PROTOCOL_VERSION
+        )));
+        assert_eq!(Offer::from_wire(&wire), offer);
+
+        for sdp in &["FAKE SDP OFFER", "FAKE SDP OFFER\r\n", ""] {
+            let offer = Offer::new((*sdp).to_owned());
+            assert_eq!(Offer::from_wire(&offer.to_wire()), offer);
+        }
+    }
+
+    #[test]
+    fn check_legacy() {
+        let offer = Offer::from_wire(SDP);
+        assert_eq!(offer.version, LEGACY_PROTOCOL_VERSION);
+        assert_eq!(offer.sdp, SDP);
+
+        // A legacy peer gets the plain SDP back.
+        let answer = Answer::new(SDP.to_owned(), negotiate(offer.version));
+        assert_eq!(answer.to_wire(), SDP);
+    }
+
+    #[test]
+    fn check_negotiate() {
+        assert_eq!(negotiate(LEGACY_PROTOCOL_VERSION), LEGACY_PROTOCOL_VERSION);
+        assert_eq!(negotiate(PROTOCOL_VERSION), PROTOCOL_VERSION);
+        assert_eq!(negotiate(PROTOCOL_VERSION + 1), PROTOCOL_VERSION);
+
+        let answer = Answer::from_wire(&Answer::new(SDP.to_owned(), 7).to_wire());
+        assert_eq!(answer.version, 7);
+        assert_eq!(answer.sdp, SDP);
+    }
+
+    #[test]
+    fn check_compression() {
+        let offer = Offer::new(SDP.to_owned());
+        let compressed = offer.to_compressed_wire();
+        assert!(compressed.starts_with(COMPRESSED_MARKER));
+        assert!(compressed.len() < offer.to_wire().len());
+        assert_eq!(decompress(compressed).unwrap(), offer.to_wire());
+
+        // Messages that would not shrink, or contain the escape, are
+        // left as they are.
+        for sdp in &["FAKE SDP OFFER", "v=0\r\ns=`\r\nm=audio"] {
+            let answer = Answer::new((*sdp).to_owned(), LEGACY_PROTOCOL_VERSION);
+            assert_eq!(answer.to_compressed_wire(), *sdp);
+            assert_eq!(decompress(answer.to_compressed_wire()).unwrap(), *sdp);
+        }
+
+        assert!(decompress("`!v=0`?".to_owned()).is_err());
+        assert!(decompress("`!v=0`".to_owned()).is_err());
+    }
+
+    #[test]
+    fn check_opaque() {
+        let offer = Offer::new(SDP.to_owned());
+        let opaque = offer.to_opaque();
+        assert_eq!(
+            &opaque[..4],
+            &[0x08, PROTOCOL_VERSION as u8, 0x12, SDP.len() as u8]
+        );
+        assert_eq!(Offer::from_opaque(&opaque).unwrap(), offer);
+
+        // Unknown fields are skipped, and a missing version is the
+        // legacy one.
+        let answer = Answer::new(SDP.to_owned(), LEGACY_PROTOCOL_VERSION);
+        let mut opaque = vec![0x28, 0x96, 0x01, 0x2a, 0x01, 0xff];
+        opaque.extend(&answer.to_opaque()[2..]);
+        assert_eq!(Answer::from_opaque(&opaque).unwrap(), answer);
+
+        assert!(Offer::from_opaque(&[]).is_err());
+        assert!(Offer::from_opaque(&[0x12, 0x05, b'v']).is_err());
+        assert!(Offer::from_opaque(&[0x12, 0x01, 0xff]).is_err());
+        assert!(Offer::from_opaque(&[0x08, 0x81]).is_err());
+    }
+
+    #[test]
+    fn check_answer_metadata() {
+        let answer = Answer {
+            metadata: Some(vec![0x00, 0x2a, 0xff]),
+            ..Answer::new(SDP.to_owned(), PROTOCOL_VERSION)
+        };
+        let wire = answer.to_wire();
+        assert!(wire.contains("t=0 0\r\na=x-ringrtc-metadata:002aff\r\n"));
+        assert_eq!(Answer::from_wire(&wire), answer);
+        assert_eq!(Answer::from_opaque(&answer.to_opaque()).unwrap(), answer);
+
+        // Offers carry no metadata, and malformed metadata is dropped.
+        assert_eq!(
+            Offer::from_opaque(&answer.to_opaque()).unwrap().sdp,
+            answer.sdp
+        );
+        let answer = Answer::from_wire(&wire.replace("002aff", "02a"));
+        assert_eq!(answer.metadata, None);
+        assert_eq!(answer.sdp, SDP);
+    }
+
+    #[test]
+    fn check_renegotiation() {
+        let offer = Offer {
+            renegotiation: Some(Renegotiation {
+                generation: 3,
+                reset:      true,
+            }),
+            ..Offer::new(SDP.to_owned())
+        };
+        let wire = offer.to_wire();
+        assert!(wire.contains("t=0 0\r\na=x-ringrtc-renegotiation:3 reset\r\n"));
+        assert_eq!(Offer::from_wire(&wire), offer);
+        assert_eq!(Offer::from_opaque(&offer.to_opaque()).unwrap(), offer);
+
+        let restart = Offer {
+            renegotiation: Some(Renegotiation {
+                generation: 1,
+                reset:      false,
+            }),
+            ..Offer::new(SDP.to_owned())
+        };
+        assert!(restart
+            .to_wire()
+            .contains("a=x-ringrtc-renegotiation:1 ice-restart\r\n"));
+        assert_eq!(Offer::from_wire(&restart.to_wire()), restart);
+
+        // A malformed attribute leaves the offer unmarked.
+        let offer = Offer::from_wire(&wire.replace("3 reset", "3 rebuild"));
+        assert_eq!(offer.renegotiation, None);
+        assert_eq!(offer.sdp, SDP);
+    }
+
+    #[test]
+    fn check_is_sealed() {
+        assert!(!is_sealed(&Offer::new(SDP.to_owned()).to_opaque()));
+        assert!(!is_sealed(&Answer::new(SDP.to_owned(), 0).to_opaque()));
+        assert!(!is_sealed(&encode_ice_candidates(1, &[])));
+        assert!(!is_sealed(&[]));
+
+        assert!(is_sealed(&[ENVELOPE_MARKER, 0x08, 0x02]));
+        assert!(Offer::from_opaque(&[ENVELOPE_MARKER, 0x08, 0x02]).is_err());
+    }
+}
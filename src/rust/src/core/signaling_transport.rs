@@ -0,0 +1,368 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! The transport carrying signaling messages to the remote peer.
+//!
+//! By default every offer, answer, ICE candidate and hangup message
+//! is handed to the `Platform::on_send_*()` callbacks, and the
+//! application sends it.  An embedder with a transport the Rust core
+//! can drive directly, e.g. a WebSocket, plugs it in with
+//! `CallManager::set_signaling_transport()` instead.
+//!
+//! Either way the messages leave the queue one at a time: a transport
+//! calls `CallManager::message_sent()` once a message is sent, or
+//! `CallManager::message_send_failure()` if it is not, just as the
+//! application does.
+
+use crate::common::{ConnectionId, DeviceId, HangupType, Result};
+use crate::core::ice_encoding::decode_ice_candidates;
+use crate::core::media_descriptor::MediaDescriptor;
+use crate::core::platform::Platform;
+use crate::core::signaling::{Answer, Offer};
+use crate::webrtc::ice_candidate::IceCandidate;
+
+/// Sends signaling messages to remote peers.
+///
+/// The methods mirror the `Platform::on_send_*()` callbacks, and are
+/// handed the platform too, for transports that fall back to it.  If
+/// broadcast is true, the message goes to all devices of the remote
+/// peer.
+///
+/// Set with `CallManager::set_signaling_transport()`.
+pub trait SignalingTransport<T>: Send + 'static
+where
+    T: Platform,
+{
+    /// Send a pre-offer, see `CallManager::set_pre_offer()`.  The
+    /// default sends nothing.
+    fn send_pre_offer(
+        &self,
+        _platform: &T,
+        _remote_peer: &<T as Platform>::AppRemotePeer,
+        _connection_id: ConnectionId,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Send an SDP offer.
+    fn send_offer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()>;
+
+    /// Send an SDP offer that restarts ICE on a connected call.  The
+    /// default calls `send_offer()`.
+    fn send_restart_offer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        self.send_offer(
+            platform,
+            remote_peer,
+            connection_id,
+            broadcast,
+            description,
+            descriptor,
+        )
+    }
+
+    /// Send an offer encoded as opaque bytes.  The default decodes the
+    /// offer again and calls `send_offer()`.
+    fn send_opaque_offer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        self.send_offer(
+            platform,
+            remote_peer,
+            connection_id,
+            broadcast,
+            &Offer::from_opaque(opaque)?.to_wire(),
+            descriptor,
+        )
+    }
+
+    /// Send an SDP answer.
+    fn send_answer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()>;
+
+    /// Send an answer encoded as opaque bytes.  The default decodes
+    /// the answer again and calls `send_answer()`.
+    fn send_opaque_answer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        self.send_answer(
+            platform,
+            remote_peer,
+            connection_id,
+            broadcast,
+            &Answer::from_opaque(opaque)?.to_wire(),
+            descriptor,
+        )
+    }
+
+    /// Send ICE candidates.
+    fn send_ice_candidates(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        candidates: &[IceCandidate],
+    ) -> Result<()>;
+
+    /// Send ICE candidates encoded as opaque bytes.  The default
+    /// decodes the candidates again and calls `send_ice_candidates()`.
+    fn send_opaque_ice_candidates(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+    ) -> Result<()> {
+        self.send_ice_candidates(
+            platform,
+            remote_peer,
+            connection_id,
+            broadcast,
+            &decode_ice_candidates(opaque)?.1,
+        )
+    }
+
+    /// Send a hangup message.
+    fn send_hangup(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        hangup_type: HangupType,
+    ) -> Result<()>;
+
+    /// Send a hangup message to several devices of the remote peer at
+    /// once.  The default ignores the devices and calls
+    /// `send_hangup()` as a broadcast.
+    fn send_hangup_to_devices(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        _remote_devices: &[DeviceId],
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        self.send_hangup(platform, remote_peer, connection_id, true, hangup_type)
+    }
+
+    /// Send a busy message.
+    fn send_busy(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+    ) -> Result<()>;
+
+    /// Send a ringing acknowledgment to the caller.  The default sends
+    /// nothing and reports the message sent.
+    fn send_ringing(
+        &self,
+        _platform: &T,
+        _remote_peer: &<T as Platform>::AppRemotePeer,
+        _connection_id: ConnectionId,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The default signaling transport, which hands every message to the
+/// `Platform::on_send_*()` callbacks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlatformTransport;
+
+impl<T> SignalingTransport<T> for PlatformTransport
+where
+    T: Platform,
+{
+    fn send_pre_offer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        platform.on_send_pre_offer(remote_peer, connection_id)
+    }
+
+    fn send_offer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        platform.on_send_offer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            description,
+            descriptor,
+        )
+    }
+
+    fn send_restart_offer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        platform.on_send_restart_offer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            description,
+            descriptor,
+        )
+    }
+
+    fn send_opaque_offer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        platform.on_send_opaque_offer(remote_peer, connection_id, broadcast, opaque, descriptor)
+    }
+
+    fn send_answer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        platform.on_send_answer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            description,
+            descriptor,
+        )
+    }
+
+    fn send_opaque_answer(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        platform.on_send_opaque_answer(remote_peer, connection_id, broadcast, opaque, descriptor)
+    }
+
+    fn send_ice_candidates(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        candidates: &[IceCandidate],
+    ) -> Result<()> {
+        platform.on_send_ice_candidates(remote_peer, connection_id, broadcast, candidates)
+    }
+
+    fn send_opaque_ice_candidates(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+    ) -> Result<()> {
+        platform.on_send_opaque_ice_candidates(remote_peer, connection_id, broadcast, opaque)
+    }
+
+    fn send_hangup(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        platform.on_send_hangup(remote_peer, connection_id, broadcast, hangup_type)
+    }
+
+    fn send_hangup_to_devices(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        remote_devices: &[DeviceId],
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        platform.on_send_hangup_to_devices(remote_peer, connection_id, remote_devices, hangup_type)
+    }
+
+    fn send_busy(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+    ) -> Result<()> {
+        platform.on_send_busy(remote_peer, connection_id, broadcast)
+    }
+
+    fn send_ringing(
+        &self,
+        platform: &T,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        platform.on_send_ringing(remote_peer, connection_id)
+    }
+}
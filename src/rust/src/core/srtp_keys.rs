@@ -0,0 +1,170 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! SRTP key export, for compliance recording gateways.
+//!
+//! Some deployments must by law feed their calls into a recorder
+//! outside the call.  Rather than relaying the media, the recorder
+//! can be given the SRTP keys negotiated by DTLS, RFC 5764, and
+//! decrypt a copy of the packets itself.
+//!
+//! Anyone holding the keys can decrypt the media of the call, so the
+//! export is only built with the `srtp_key_export` feature, and only
+//! allowed for calls opted in with
+//! `CallConfig::with_srtp_key_export()`.  The keys are never logged.
+
+use std::fmt;
+
+use crate::common::Result;
+use crate::error::RingRtcError;
+
+/// Largest keying material exported, for `AeadAes256Gcm`.
+pub const MAX_SRTP_KEYING_MATERIAL: usize = 2 * (32 + 12);
+
+/// The SRTP protection profile negotiated by DTLS, with the IDs of
+/// RFC 5764 and RFC 7714.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SrtpProtectionProfile {
+    Aes128CmSha1_80 = 1,
+    Aes128CmSha1_32 = 2,
+    AeadAes128Gcm   = 7,
+    AeadAes256Gcm   = 8,
+}
+
+impl fmt::Display for SrtpProtectionProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl SrtpProtectionProfile {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            1 => Ok(SrtpProtectionProfile::Aes128CmSha1_80),
+            2 => Ok(SrtpProtectionProfile::Aes128CmSha1_32),
+            7 => Ok(SrtpProtectionProfile::AeadAes128Gcm),
+            8 => Ok(SrtpProtectionProfile::AeadAes256Gcm),
+            _ => Err(RingRtcError::UnknownSrtpProtectionProfile(value).into()),
+        }
+    }
+
+    /// Returns the (key, salt) lengths of the profile, in bytes.
+    pub fn key_and_salt_len(self) -> (usize, usize) {
+        match self {
+            SrtpProtectionProfile::Aes128CmSha1_80 | SrtpProtectionProfile::Aes128CmSha1_32 => {
+                (16, 14)
+            }
+            SrtpProtectionProfile::AeadAes128Gcm => (16, 12),
+            SrtpProtectionProfile::AeadAes256Gcm => (32, 12),
+        }
+    }
+}
+
+/// The SRTP master keys and salts of both directions of a call.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SrtpKeys {
+    pub profile:     SrtpProtectionProfile,
+    /// Protects the media sent by the local side.
+    pub local_key:   Vec<u8>,
+    pub local_salt:  Vec<u8>,
+    /// Protects the media sent by the remote side.
+    pub remote_key:  Vec<u8>,
+    pub remote_salt: Vec<u8>,
+}
+
+/// Never shows the keys.
+impl fmt::Debug for SrtpKeys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SrtpKeys {{ profile: {} }}", self.profile)
+    }
+}
+
+impl SrtpKeys {
+    /// Split the keying material exported with the
+    /// `EXTRACTOR-dtls_srtp` label, laid out as the client key, the
+    /// server key, the client salt and the server salt.
+    ///
+    /// `dtls_client` tells whether the local side was the DTLS
+    /// client, i.e. whose keys are the local ones.
+    pub fn from_keying_material(
+        profile: SrtpProtectionProfile,
+        dtls_client: bool,
+        material: &[u8],
+    ) -> Result<Self> {
+        let (key_len, salt_len) = profile.key_and_salt_len();
+        if material.len() != 2 * (key_len + salt_len) {
+            return Err(RingRtcError::InvalidSrtpKeyingMaterial(material.len()).into());
+        }
+
+        let (keys, salts) = material.split_at(2 * key_len);
+        let (client_key, server_key) = keys.split_at(key_len);
+        let (client_salt, server_salt) = salts.split_at(salt_len);
+
+        let (local_key, local_salt, remote_key, remote_salt) = if dtls_client {
+            (client_key, client_salt, server_key, server_salt)
+        } else {
+            (server_key, server_salt, client_key, client_salt)
+        };
+        Ok(Self {
+            profile,
+            local_key: local_key.to_vec(),
+            local_salt: local_salt.to_vec(),
+            remote_key: remote_key.to_vec(),
+            remote_salt: remote_salt.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_from_keying_material() {
+        let material: Vec<u8> = (0..60).collect();
+        let profile = SrtpProtectionProfile::Aes128CmSha1_80;
+        let keys = SrtpKeys::from_keying_material(profile, true, &material).unwrap();
+        assert_eq!(keys.local_key, material[0..16].to_vec());
+        assert_eq!(keys.remote_key, material[16..32].to_vec());
+        assert_eq!(keys.local_salt, material[32..46].to_vec());
+        assert_eq!(keys.remote_salt, material[46..60].to_vec());
+
+        // The DTLS server gets the other half.
+        let server_keys = SrtpKeys::from_keying_material(profile, false, &material).unwrap();
+        assert_eq!(server_keys.local_key, keys.remote_key);
+        assert_eq!(server_keys.remote_salt, keys.local_salt);
+
+        // Too short for the profile.
+        let profile = SrtpProtectionProfile::AeadAes256Gcm;
+        assert!(SrtpKeys::from_keying_material(profile, true, &material).is_err());
+
+        // The keys are never shown.
+        assert_eq!(
+            format!("{:?}", keys),
+            "SrtpKeys { profile: Aes128CmSha1_80 }"
+        );
+    }
+
+    #[test]
+    fn check_protection_profile() {
+        for profile in &[
+            SrtpProtectionProfile::Aes128CmSha1_80,
+            SrtpProtectionProfile::Aes128CmSha1_32,
+            SrtpProtectionProfile::AeadAes128Gcm,
+            SrtpProtectionProfile::AeadAes256Gcm,
+        ] {
+            assert_eq!(
+                SrtpProtectionProfile::from_i32(*profile as i32).unwrap(),
+                *profile
+            );
+            let (key_len, salt_len) = profile.key_and_salt_len();
+            assert!(2 * (key_len + salt_len) <= MAX_SRTP_KEYING_MATERIAL);
+        }
+        assert!(SrtpProtectionProfile::from_i32(3).is_err());
+    }
+}
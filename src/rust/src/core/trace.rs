@@ -0,0 +1,131 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Structured trace of Call and Connection activity.
+//!
+//! A bounded history of state transitions, FSM events and signaling
+//! traffic, retained for post-mortem debugging.  Tracing is enabled
+//! by default, and can be disabled with `CallManager::set_tracing()`.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::SystemTime;
+
+use crate::common::{CallId, DeviceId};
+
+/// Maximum number of events retained in a single trace buffer.
+pub const MAX_TRACE_EVENTS: usize = 256;
+
+/// The category of a traced event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceKind {
+    /// A Call or Connection state transition.
+    StateChange,
+    /// An event injected into a Call or Connection FSM.
+    FsmEvent,
+    /// A signaling message sent to or received from the remote peer.
+    Signaling,
+}
+
+impl fmt::Display for TraceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A single timestamped trace entry.
+#[derive(Clone, Debug)]
+pub struct TraceEvent {
+    /// Milliseconds since the UNIX_EPOCH when the event was recorded.
+    pub timestamp_ms:  u64,
+    /// The call the event belongs to.
+    pub call_id:       CallId,
+    /// The remote device, for Connection level events.
+    pub remote_device: Option<DeviceId>,
+    /// The category of the event.
+    pub kind:          TraceKind,
+    /// Human readable description of the event.
+    pub description:   String,
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let id = match self.remote_device {
+            Some(d) => self.call_id.format(d),
+            None => format!("{}", self.call_id),
+        };
+        write!(
+            f,
+            "{} id: {}, {}: {}",
+            self.timestamp_ms, id, self.kind, self.description
+        )
+    }
+}
+
+/// Ring buffer of trace events.
+///
+/// Once `MAX_TRACE_EVENTS` is reached the oldest event is discarded
+/// for each new event recorded.
+#[derive(Debug)]
+pub struct TraceBuffer {
+    /// Unique 64-bit number identifying the call.
+    call_id:       CallId,
+    /// Remote device, for Connection level buffers.
+    remote_device: Option<DeviceId>,
+    /// The retained events, oldest first.
+    events:        VecDeque<TraceEvent>,
+    /// Whether new events are recorded.
+    enabled:       bool,
+}
+
+impl TraceBuffer {
+    /// Create a new, empty, TraceBuffer.
+    ///
+    /// A disabled buffer records nothing.
+    pub fn new(call_id: CallId, remote_device: Option<DeviceId>, enabled: bool) -> Self {
+        Self {
+            call_id,
+            remote_device,
+            events: VecDeque::with_capacity(if enabled { MAX_TRACE_EVENTS } else { 0 }),
+            enabled,
+        }
+    }
+
+    /// Return whether new events are recorded.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record a new event, discarding the oldest one if full.
+    pub fn record(&mut self, kind: TraceKind, description: String) {
+        if !self.enabled {
+            return;
+        }
+
+        if self.events.len() >= MAX_TRACE_EVENTS {
+            let _ = self.events.pop_front();
+        }
+
+        let timestamp_ms = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(v) => v.as_millis() as u64,
+            Err(_) => 0,
+        };
+
+        self.events.push_back(TraceEvent {
+            timestamp_ms,
+            call_id: self.call_id,
+            remote_device: self.remote_device,
+            kind,
+            description,
+        });
+    }
+
+    /// Return a copy of the retained events, oldest first.
+    pub fn events(&self) -> Vec<TraceEvent> {
+        self.events.iter().cloned().collect()
+    }
+}
@@ -17,6 +17,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 
 use crate::common::Result;
+use crate::core::call_mutex::CallMutex;
 use crate::error::RingRtcError;
 
 /// Generic Mutex/Condvar pair for signaling async event completion.
@@ -83,6 +84,43 @@ impl<T> Drop for ArcPtr<T> {
     }
 }
 
+/// A value shared by all clones of its owner that can be replaced in
+/// place, e.g. a reference to an application object the application
+/// recreates.
+///
+/// Users take a clone of the current value, so each use sees either
+/// the old or the new value throughout.
+pub struct Swappable<T: Clone> {
+    value: Arc<CallMutex<T>>,
+}
+
+impl<T: Clone> Clone for Swappable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: Arc::clone(&self.value),
+        }
+    }
+}
+
+impl<T: Clone> Swappable<T> {
+    /// Creates a new Swappable holding `value`.
+    pub fn new(value: T, label: &str) -> Self {
+        Self {
+            value: Arc::new(CallMutex::new(value, label)),
+        }
+    }
+
+    /// Returns a clone of the current value.
+    pub fn get(&self) -> Result<T> {
+        Ok(self.value.lock()?.clone())
+    }
+
+    /// Replaces the value for all clones, returning the old value.
+    pub fn replace(&self, value: T) -> Result<T> {
+        Ok(mem::replace(&mut *self.value.lock()?, value))
+    }
+}
+
 /// # Safety
 ///
 /// Dereferences raw *mut T into an ArcPtr<T>.
@@ -263,6 +301,44 @@ pub fn redact_string(text: &str) -> String {
 mod tests {
     use super::*;
 
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Stands in for an application object receiving callbacks.
+    #[derive(Default)]
+    struct FakeTarget {
+        callbacks: AtomicUsize,
+    }
+
+    #[test]
+    fn check_swappable() {
+        let old_target = Arc::new(FakeTarget::default());
+        let new_target = Arc::new(FakeTarget::default());
+
+        let target = Swappable::new(Arc::clone(&old_target), "target");
+        let platform = target.clone();
+        let callback = |target: &Swappable<Arc<FakeTarget>>| {
+            target
+                .get()
+                .unwrap()
+                .callbacks
+                .fetch_add(1, Ordering::AcqRel);
+        };
+
+        callback(&platform);
+
+        // A callback in flight keeps the target it started with.
+        let in_flight = platform.get().unwrap();
+        let replaced = target.replace(Arc::clone(&new_target)).unwrap();
+        assert!(Arc::ptr_eq(&replaced, &old_target));
+        in_flight.callbacks.fetch_add(1, Ordering::AcqRel);
+
+        callback(&platform);
+        callback(&target);
+
+        assert_eq!(old_target.callbacks.load(Ordering::Acquire), 2);
+        assert_eq!(new_target.callbacks.load(Ordering::Acquire), 2);
+    }
+
     #[test]
     fn check_ipv6() {
         let addrs = [
@@ -0,0 +1,467 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Sent video resolution scaling.
+//!
+//! The sent video can be capped to a maximum resolution, for
+//! bandwidth or thermal control.  The cap is applied as a single
+//! `scaleResolutionDownBy` factor on the video encodings, so the
+//! aspect ratio of the capture is preserved.
+//!
+//! When bandwidth or CPU is constrained, the degradation preference
+//! decides whether the sent video keeps its resolution or its
+//! framerate.  The stats report how the sent video is adapted, e.g.
+//! CPU-limited at 360p, so the application can tell whether the
+//! preference suits its content.
+//!
+//! The sent video can also be simulcast as up to three layers, each
+//! capped in resolution and bitrate.  The receiver may ask for one of
+//! the layers, after which only that layer is sent, as an SFU would
+//! forward only the layer each receiver can take.
+//!
+//! The rotation of the received video is carried by the video
+//! orientation RTP header extension and is only reported when that
+//! extension was negotiated.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::common::Result;
+use crate::error::RingRtcError;
+use crate::webrtc::stats_observer::QualityLimitationReason;
+
+/// A video resolution in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VideoResolution {
+    pub width:  u32,
+    pub height: u32,
+}
+
+impl VideoResolution {
+    /// Create a new VideoResolution.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// Create a new VideoResolution from signed sides, e.g. as
+    /// passed in from Java, rejecting negative values.
+    pub fn from_i32(width: i32, height: i32) -> Result<Self> {
+        match (u32::try_from(width), u32::try_from(height)) {
+            (Ok(width), Ok(height)) => Ok(Self::new(width, height)),
+            _ => Err(RingRtcError::InvalidVideoResolution(width, height).into()),
+        }
+    }
+
+    /// Returns the (long, short) sides of the resolution.
+    fn sides(self) -> (u32, u32) {
+        (self.width.max(self.height), self.width.min(self.height))
+    }
+}
+
+impl fmt::Display for VideoResolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// The inputs needed to scale the sent video.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VideoScaling {
+    /// The maximum sent resolution, `(0, 0)` when uncapped.
+    pub max_resolution:     VideoResolution,
+    /// The current capture resolution, if known.
+    pub capture_resolution: Option<VideoResolution>,
+}
+
+impl VideoScaling {
+    /// Returns the `scaleResolutionDownBy` factor that fits the
+    /// capture resolution within the maximum resolution.
+    ///
+    /// The sides are compared long to long and short to short, so
+    /// the cap applies regardless of the capture orientation.  A
+    /// zero maximum side leaves that side uncapped.  The factor is
+    /// never less than 1.0, i.e. the video is never scaled up.
+    pub fn scale_resolution_down_by(&self) -> f64 {
+        let capture = match self.capture_resolution {
+            Some(v) => v,
+            None => return 1.0,
+        };
+
+        let ratio = |captured: u32, max: u32| {
+            if max == 0 {
+                1.0
+            } else {
+                f64::from(captured) / f64::from(max)
+            }
+        };
+
+        let (capture_long, capture_short) = capture.sides();
+        let (max_long, max_short) = self.max_resolution.sides();
+
+        ratio(capture_long, max_long)
+            .max(ratio(capture_short, max_short))
+            .max(1.0)
+    }
+}
+
+/// How the sent video degrades when bandwidth or CPU is constrained.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DegradationPreference {
+    /// Lower the resolution, e.g. for camera video.
+    MaintainFramerate = 0,
+    /// Lower the framerate, e.g. for screen sharing.
+    MaintainResolution,
+    /// Lower both the resolution and the framerate.
+    #[default]
+    Balanced,
+}
+
+impl fmt::Display for DegradationPreference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl DegradationPreference {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(DegradationPreference::MaintainFramerate),
+            1 => Ok(DegradationPreference::MaintainResolution),
+            2 => Ok(DegradationPreference::Balanced),
+            _ => Err(RingRtcError::UnknownDegradationPreference(value).into()),
+        }
+    }
+}
+
+/// How the sent video is currently adapted, as reported by the stats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VideoAdaptation {
+    /// Why the video is below the captured resolution or framerate,
+    /// if it is.
+    pub limitation:        QualityLimitationReason,
+    /// The sent resolution.
+    pub resolution:        VideoResolution,
+    /// The sent framerate.
+    pub frames_per_second: f64,
+}
+
+impl VideoAdaptation {
+    /// Returns true if the video is degraded by a constraint.
+    pub fn is_limited(&self) -> bool {
+        self.limitation != QualityLimitationReason::None
+    }
+}
+
+impl fmt::Display for VideoAdaptation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = match self.limitation {
+            QualityLimitationReason::None => "sending",
+            QualityLimitationReason::Cpu => "CPU-limited at",
+            QualityLimitationReason::Bandwidth => "bandwidth-limited at",
+            QualityLimitationReason::Other => "limited at",
+        };
+        // Resolutions are named by their short side, e.g. 720p.
+        let (_, short) = self.resolution.sides();
+        write!(f, "{} {}p, {:.0} fps", state, short, self.frames_per_second)
+    }
+}
+
+/// The most layers the sent video can be simulcast as.
+pub const MAX_SIMULCAST_LAYERS: usize = 3;
+
+/// A simulcast layer of the sent video.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulcastLayer {
+    /// The maximum resolution of the layer, `(0, 0)` when uncapped.
+    pub max_resolution:  VideoResolution,
+    /// The maximum bitrate of the layer, in bits per second.
+    pub max_bitrate_bps: u32,
+}
+
+impl SimulcastLayer {
+    /// Create a new SimulcastLayer.
+    pub fn new(max_resolution: VideoResolution, max_bitrate_bps: u32) -> Self {
+        Self {
+            max_resolution,
+            max_bitrate_bps,
+        }
+    }
+}
+
+impl fmt::Display for SimulcastLayer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "max_resolution: {}, max_bitrate_bps: {}",
+            self.max_resolution, self.max_bitrate_bps
+        )
+    }
+}
+
+/// The simulcast layers of the sent video, and the layer the
+/// receiver asked for.
+#[derive(Clone, Debug, Default)]
+pub struct Simulcast {
+    /// The layers, from the lowest quality up.  Empty when the video
+    /// is not simulcast.
+    layers:          Vec<SimulcastLayer>,
+    /// The layer the receiver asked for, if any.
+    requested_layer: Option<usize>,
+}
+
+impl Simulcast {
+    /// Returns the layers, from the lowest quality up.
+    pub fn layers(&self) -> &[SimulcastLayer] {
+        &self.layers
+    }
+
+    /// Replace the layers, from the lowest quality up.  No layers
+    /// stops simulcasting.
+    pub fn set_layers(&mut self, layers: Vec<SimulcastLayer>) -> Result<()> {
+        if layers.len() > MAX_SIMULCAST_LAYERS {
+            return Err(RingRtcError::InvalidSimulcastLayers(layers.len()).into());
+        }
+        self.layers = layers;
+        Ok(())
+    }
+
+    /// Returns the layer the receiver asked for, if any.
+    pub fn requested_layer(&self) -> Option<usize> {
+        self.requested_layer
+    }
+
+    /// Record the layer the receiver asked for.
+    pub fn request_layer(&mut self, layer: usize) -> Result<()> {
+        if layer >= MAX_SIMULCAST_LAYERS {
+            return Err(RingRtcError::InvalidVideoLayer(layer).into());
+        }
+        self.requested_layer = Some(layer);
+        Ok(())
+    }
+
+    /// Returns whether each layer is sent.
+    ///
+    /// All layers are sent until the receiver asks for one.  After
+    /// that only the requested layer is sent, or the highest layer if
+    /// fewer layers are configured.
+    pub fn active_layers(&self) -> Vec<bool> {
+        let last = self.layers.len().saturating_sub(1);
+        match self.requested_layer {
+            Some(requested) => (0..self.layers.len())
+                .map(|layer| layer == requested.min(last))
+                .collect(),
+            None => vec![true; self.layers.len()],
+        }
+    }
+}
+
+/// The URI of the video orientation RTP header extension.
+const VIDEO_ORIENTATION_EXTENSION: &str = "urn:3gpp:video-orientation";
+
+/// Returns true if an SDP carries the video orientation RTP header
+/// extension.
+pub fn video_orientation_negotiated(sdp: &str) -> bool {
+    sdp.lines().any(|line| {
+        line.starts_with("a=extmap:")
+            && line.split_whitespace().nth(1) == Some(VIDEO_ORIENTATION_EXTENSION)
+    })
+}
+
+/// Tracks the rotation of the received video.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VideoOrientation {
+    /// The video orientation RTP header extension was negotiated.
+    pub negotiated: bool,
+    /// The last rotation reported, in degrees.
+    degrees:        Option<u16>,
+}
+
+impl VideoOrientation {
+    /// Returns the last rotation recorded, in degrees.
+    pub fn degrees(&self) -> Option<u16> {
+        self.degrees
+    }
+
+    /// Record a rotation received from WebRTC, returning the rotation
+    /// in degrees if it changed.
+    ///
+    /// Nothing is recorded when the extension was not negotiated, or
+    /// for rotations other than 0, 90, 180 or 270.
+    pub fn update(&mut self, rotation: i32) -> Option<u16> {
+        if !self.negotiated {
+            return None;
+        }
+
+        let degrees = match rotation {
+            0 | 90 | 180 | 270 => rotation as u16,
+            _ => return None,
+        };
+
+        if self.degrees == Some(degrees) {
+            return None;
+        }
+        self.degrees = Some(degrees);
+        Some(degrees)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scale(capture: (u32, u32), max: (u32, u32)) -> f64 {
+        VideoScaling {
+            max_resolution:     VideoResolution::new(max.0, max.1),
+            capture_resolution: Some(VideoResolution::new(capture.0, capture.1)),
+        }
+        .scale_resolution_down_by()
+    }
+
+    #[test]
+    fn check_scale_resolution_down_by() {
+        // uncapped
+        assert_eq!(scale((1280, 720), (0, 0)), 1.0);
+        // already within the cap
+        assert_eq!(scale((640, 480), (1280, 720)), 1.0);
+        assert_eq!(scale((1280, 720), (1280, 720)), 1.0);
+        // same aspect ratio
+        assert_eq!(scale((1280, 720), (640, 360)), 2.0);
+        // portrait capture, landscape cap
+        assert_eq!(scale((720, 1280), (640, 360)), 2.0);
+        // the long side is the constraint
+        assert_eq!(scale((1280, 720), (640, 480)), 2.0);
+        // the short side is the constraint
+        assert_eq!(scale((1920, 1080), (1280, 1280)), 1.5);
+        assert_eq!(scale((1280, 960), (1280, 480)), 2.0);
+        // a single capped side
+        assert_eq!(scale((1920, 1080), (960, 0)), 2.0);
+    }
+
+    #[test]
+    fn check_resolution_from_i32() {
+        assert_eq!(
+            VideoResolution::from_i32(1280, 720).unwrap(),
+            VideoResolution::new(1280, 720)
+        );
+        assert_eq!(
+            VideoResolution::from_i32(0, 0).unwrap(),
+            VideoResolution::default()
+        );
+        assert!(VideoResolution::from_i32(-1, 720).is_err());
+        assert!(VideoResolution::from_i32(1280, -720).is_err());
+    }
+
+    #[test]
+    fn check_unknown_capture_resolution() {
+        let scaling = VideoScaling {
+            max_resolution:     VideoResolution::new(640, 360),
+            capture_resolution: None,
+        };
+        assert_eq!(scaling.scale_resolution_down_by(), 1.0);
+    }
+
+    #[test]
+    fn check_degradation_preference() {
+        assert_eq!(
+            DegradationPreference::default(),
+            DegradationPreference::Balanced
+        );
+        for preference in &[
+            DegradationPreference::MaintainFramerate,
+            DegradationPreference::MaintainResolution,
+            DegradationPreference::Balanced,
+        ] {
+            assert_eq!(
+                DegradationPreference::from_i32(*preference as i32).unwrap(),
+                *preference
+            );
+        }
+        assert!(DegradationPreference::from_i32(3).is_err());
+    }
+
+    #[test]
+    fn check_video_adaptation() {
+        let adaptation = |limitation, width, height| VideoAdaptation {
+            limitation,
+            resolution: VideoResolution::new(width, height),
+            frames_per_second: 14.8,
+        };
+        let limited = adaptation(QualityLimitationReason::Cpu, 640, 360);
+        assert!(limited.is_limited());
+        assert_eq!(limited.to_string(), "CPU-limited at 360p, 15 fps");
+        assert_eq!(
+            adaptation(QualityLimitationReason::Bandwidth, 360, 640).to_string(),
+            "bandwidth-limited at 360p, 15 fps"
+        );
+        let unlimited = adaptation(QualityLimitationReason::None, 1280, 720);
+        assert!(!unlimited.is_limited());
+        assert_eq!(unlimited.to_string(), "sending 720p, 15 fps");
+    }
+
+    #[test]
+    fn check_simulcast() {
+        let layer = |width, height, max_bitrate_bps| {
+            SimulcastLayer::new(VideoResolution::new(width, height), max_bitrate_bps)
+        };
+        let mut simulcast = Simulcast::default();
+        assert!(simulcast.active_layers().is_empty());
+
+        simulcast
+            .set_layers(vec![
+                layer(320, 180, 150_000),
+                layer(640, 360, 500_000),
+                layer(1280, 720, 1_500_000),
+            ])
+            .unwrap();
+        assert_eq!(simulcast.active_layers(), vec![true, true, true]);
+
+        simulcast.request_layer(1).unwrap();
+        assert_eq!(simulcast.requested_layer(), Some(1));
+        assert_eq!(simulcast.active_layers(), vec![false, true, false]);
+
+        // Fewer layers than the one requested
+        simulcast.request_layer(2).unwrap();
+        simulcast
+            .set_layers(vec![layer(320, 180, 150_000), layer(640, 360, 500_000)])
+            .unwrap();
+        assert_eq!(simulcast.active_layers(), vec![false, true]);
+
+        assert!(simulcast.request_layer(MAX_SIMULCAST_LAYERS).is_err());
+        assert!(simulcast
+            .set_layers(vec![layer(0, 0, 100_000); MAX_SIMULCAST_LAYERS + 1])
+            .is_err());
+        assert_eq!(simulcast.layers().len(), 2);
+    }
+
+    #[test]
+    fn check_video_orientation_negotiated() {
+        let sdp = "m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                   a=extmap:2 urn:ietf:params:rtp-hdrext:toffset\r\n\
+                   a=extmap:4 urn:3gpp:video-orientation\r\n";
+        assert!(video_orientation_negotiated(sdp));
+        assert!(!video_orientation_negotiated(
+            "a=extmap:2 urn:ietf:params:rtp-hdrext:toffset\r\n"
+        ));
+        assert!(!video_orientation_negotiated("FAKE SDP"));
+    }
+
+    #[test]
+    fn check_video_orientation_update() {
+        let mut orientation = VideoOrientation::default();
+        assert_eq!(orientation.update(90), None);
+        assert_eq!(orientation.degrees(), None);
+
+        orientation.negotiated = true;
+        assert_eq!(orientation.update(0), Some(0));
+        assert_eq!(orientation.update(0), None);
+        assert_eq!(orientation.update(45), None);
+        assert_eq!(orientation.update(270), Some(270));
+        assert_eq!(orientation.update(90), Some(90));
+        assert_eq!(orientation.degrees(), Some(90));
+    }
+}
@@ -7,7 +7,11 @@
 
 //! Common error codes.
 
+use std::time::Duration;
+
 use crate::common::{CallId, DeviceId};
+use crate::core::audio_config::AudioConfig;
+use crate::core::group_call::ParticipantId;
 
 /// Platform independent error conditions.
 #[derive(Fail, Debug)]
@@ -23,8 +27,19 @@ pub enum RingRtcError {
     // Call Manager error codes
     #[fail(display = "Active call already in progress, id: {}", _0)]
     CallAlreadyInProgress(CallId),
+    #[fail(display = "CallId already belongs to a live call, id: {}", _0)]
+    CallIdCollision(CallId),
+    #[fail(
+        display = "Replayed signaling message, call_id: {}, remote_device: {}, message: {}",
+        _0, _1, _2
+    )]
+    ReplayedSignalingMessage(CallId, DeviceId, String),
     #[fail(display = "No active call found")]
     NoActiveCall,
+    #[fail(display = "Call is not active, id: {}", _0)]
+    CallNotActive(CallId),
+    #[fail(display = "Call is not connected, id: {}", _0)]
+    CallNotConnected(CallId),
     #[fail(display = "CallID not found in call_map: {}", _0)]
     CallIdNotFound(CallId),
     #[fail(display = "Connection not found in connection_map: {}", _0)]
@@ -38,6 +53,8 @@ pub enum RingRtcError {
         _0, _1
     )]
     PendingCallAlreadySet(DeviceId, String),
+    #[fail(display = "Hard reset of the connection is not supported by the platform")]
+    HardResetUnsupported,
     #[fail(
         display = "Application Connection is already set, remote_device: {}",
         _0
@@ -45,8 +62,31 @@ pub enum RingRtcError {
     AppConnectionAlreadySet(DeviceId),
     #[fail(display = "Application Call Context is already set, call_id: {}", _0)]
     AppCallContextAlreadySet(CallId),
+    #[fail(display = "CallID not found in group_calls: {}", _0)]
+    GroupCallNotFound(CallId),
+    #[fail(
+        display = "Participant not found, call_id: {}, participant: {}",
+        _0, _1
+    )]
+    ParticipantNotFound(CallId, ParticipantId),
+    #[fail(
+        display = "Participant is already in the call, call_id: {}, participant: {}",
+        _0, _1
+    )]
+    ParticipantAlreadyAdded(CallId, ParticipantId),
+    #[fail(display = "Recording already in progress, call_id: {}", _0)]
+    RecordingInProgress(CallId),
+    #[fail(display = "No recording in progress, call_id: {}", _0)]
+    RecordingNotInProgress(CallId),
+    #[fail(display = "Screen sharing was not negotiated, call_id: {}", _0)]
+    ScreenShareNotNegotiated(CallId),
+    #[cfg(feature = "srtp_key_export")]
+    #[fail(display = "SRTP key export not enabled, call_id: {}", _0)]
+    SrtpKeyExportNotEnabled(CallId),
 
     // WebRTC / C++ error codes
+    #[fail(display = "Unable to create PeerConnection: {}", _0)]
+    CreatePeerConnection(String),
     #[fail(display = "Unable to create C++ PeerConnectionObserver")]
     CreatePeerConnectionObserver,
     #[fail(display = "Unable to create C++ DataChannel with label: {}", _0)]
@@ -77,6 +117,73 @@ pub enum RingRtcError {
     SetSessionDescriptionObserverResult(String),
     #[fail(display = "AddIceCandidate failure")]
     AddIceCandidate,
+    #[fail(display = "SetVideoScaleResolutionDownBy failure, scale: {}", _0)]
+    SetVideoScaleResolutionDownBy(f64),
+    #[fail(display = "SetOutgoingAudioEnabled failure, enabled: {}", _0)]
+    SetOutgoingAudioEnabled(bool),
+    #[fail(display = "SetOutgoingVideoEnabled failure, enabled: {}", _0)]
+    SetOutgoingVideoEnabled(bool),
+    #[fail(display = "SetOutgoingScreenShareEnabled failure, enabled: {}", _0)]
+    SetOutgoingScreenShareEnabled(bool),
+    #[fail(display = "SetOutgoingAudioGain failure, gain: {}", _0)]
+    SetOutgoingAudioGain(f32),
+    #[fail(display = "SetAudioConfig failure, config: {}", _0)]
+    SetAudioConfig(AudioConfig),
+    #[fail(display = "SetAudioJitterBufferMinimumDelay failure, delay: {:?}", _0)]
+    SetAudioJitterBufferMinimumDelay(Option<Duration>),
+    #[fail(display = "Invalid jitter buffer minimum delay: {:?}", _0)]
+    InvalidJitterBufferMinDelay(Duration),
+    #[fail(display = "PushOutgoingVideoFrame failure, size: {}x{}", _0, _1)]
+    PushOutgoingVideoFrame(u32, u32),
+    #[fail(display = "InsertDtmf failure, tones: {}", _0)]
+    InsertDtmf(String),
+    #[fail(display = "Invalid DTMF tone: {}", _0)]
+    InvalidDtmfTone(char),
+    #[fail(display = "Invalid DTMF timing, duration_ms: {}, gap_ms: {}", _0, _1)]
+    InvalidDtmfTiming(i32, i32),
+    #[fail(display = "SetMaxBitrate failure, bitrate_bps: {}", _0)]
+    SetMaxBitrate(u32),
+    #[fail(display = "SetIceServers failure, servers: {}", _0)]
+    SetIceServers(usize),
+    #[fail(display = "SetVideoMaxFramerate failure, max_framerate: {}", _0)]
+    SetVideoMaxFramerate(u32),
+    #[fail(display = "SetVideoDegradationPreference failure, preference: {}", _0)]
+    SetVideoDegradationPreference(i32),
+    #[fail(display = "SetFrameCryptor failure, attached: {}", _0)]
+    SetFrameCryptor(bool),
+    #[cfg(feature = "srtp_key_export")]
+    #[fail(display = "ExportSrtpKeyingMaterial failure")]
+    ExportSrtpKeyingMaterial,
+    #[cfg(feature = "srtp_key_export")]
+    #[fail(display = "Invalid SRTP keying material, length: {}", _0)]
+    InvalidSrtpKeyingMaterial(usize),
+    #[fail(display = "SetSimulcastLayers failure, count: {}", _0)]
+    SetSimulcastLayers(usize),
+    #[fail(display = "Invalid simulcast layers, count: {}", _0)]
+    InvalidSimulcastLayers(usize),
+    #[fail(display = "Invalid video layer: {}", _0)]
+    InvalidVideoLayer(usize),
+    #[fail(display = "Invalid video resolution: {}x{}", _0, _1)]
+    InvalidVideoResolution(i32, i32),
+    #[fail(display = "Unknown video degradation preference: {}", _0)]
+    UnknownDegradationPreference(i32),
+    #[cfg(feature = "srtp_key_export")]
+    #[fail(display = "Unknown SRTP protection profile: {}", _0)]
+    UnknownSrtpProtectionProfile(i32),
+    #[fail(display = "Unknown bandwidth mode: {}", _0)]
+    UnknownBandwidthMode(i32),
+    #[fail(display = "Unknown audio route: {}", _0)]
+    UnknownAudioRoute(i32),
+    #[fail(display = "Unknown audio device error: {}", _0)]
+    UnknownAudioDeviceError(i32),
+    #[fail(display = "Unknown hangup type: {}", _0)]
+    UnknownHangupType(i32),
+    #[fail(display = "Unknown bundle policy: {}", _0)]
+    UnknownBundlePolicy(i32),
+    #[fail(display = "Unknown rtcp-mux policy: {}", _0)]
+    UnknownRtcpMuxPolicy(i32),
+    #[fail(display = "Unknown pending signaling message kind: {}", _0)]
+    UnknownPendingMessageKind(i32),
 
     // WebRTC / C++ offer / answer error codes
     #[fail(display = "Unable to retrieve sdp description from offer")]
@@ -85,10 +192,29 @@ pub enum RingRtcError {
     ConvertSdpAnswer,
     #[fail(display = "Unable to convert sdp offer string to SessionDescriptionInterface object")]
     ConvertSdpOffer,
+    #[fail(display = "Unable to create sdp rollback SessionDescriptionInterface object")]
+    CreateSdpRollback,
+    #[fail(display = "SDP length: {} exceeds the maximum length: {}", _0, _1)]
+    SdpTooLong(usize, usize),
+    #[fail(display = "Invalid opaque signaling message: {}", _0)]
+    InvalidOpaqueMessage(String),
+    #[fail(display = "Unable to open sealed signaling message: {}", _0)]
+    OpenSealedMessage(String),
+    #[fail(display = "Invalid compressed SDP: {}", _0)]
+    InvalidCompressedSdp(String),
+    #[fail(display = "Invalid ICE server URL: {}", _0)]
+    InvalidIceServerUrl(String),
 
     // DataChannel error codes
     #[fail(display = "Unable to send data channel message")]
     DataChannelSend,
+    #[fail(
+        display = "DataChannel send would block, buffered amount: {}, high water mark: {}",
+        _0, _1
+    )]
+    DataChannelWouldBlock(u64, u64),
+    #[fail(display = "Too many control messages queued: {}", _0)]
+    ControlQueueFull(usize),
     #[fail(display = "Data channel protocol error: {}", _0)]
     DataChannelProtocol(String),
 
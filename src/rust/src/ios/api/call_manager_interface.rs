@@ -90,6 +90,175 @@ pub struct AppIceCandidateArray {
     pub count:      size_t,
 }
 
+/// Structure for passing an ICE server from Swift, one per URL.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct AppIceServer {
+    pub url:      AppByteSlice,
+    pub username: AppByteSlice,
+    pub password: AppByteSlice,
+}
+
+/// Structure for passing multiple ICE servers from Swift.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct AppIceServerArray {
+    pub servers: *const AppIceServer,
+    pub count:   size_t,
+    /// Swift object owning the servers.
+    pub object:  *mut c_void,
+    /// Swift object clean up method.
+    pub destroy: extern "C" fn(object: *mut c_void),
+}
+
+impl AppIceServerArray {
+    /// Return the servers, valid until the array is dropped.
+    pub fn servers(&self) -> &[AppIceServer] {
+        if self.servers.is_null() || self.count == 0 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.servers, self.count) }
+    }
+}
+
+// Rust owns the array from Swift. Drop it when it goes out of scope.
+impl Drop for AppIceServerArray {
+    fn drop(&mut self) {
+        (self.destroy)(self.object);
+    }
+}
+
+/// Structure for passing a hardware codec from Swift.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct AppCodecCapability {
+    pub name:   AppByteSlice,
+    pub encode: bool,
+    pub decode: bool,
+}
+
+/// Structure for passing multiple hardware codecs from Swift.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct AppCodecCapabilityArray {
+    pub codecs:  *const AppCodecCapability,
+    pub count:   size_t,
+    /// Swift object owning the codecs.
+    pub object:  *mut c_void,
+    /// Swift object clean up method.
+    pub destroy: extern "C" fn(object: *mut c_void),
+}
+
+impl AppCodecCapabilityArray {
+    /// Return the codecs, valid until the array is dropped.
+    pub fn codecs(&self) -> &[AppCodecCapability] {
+        if self.codecs.is_null() || self.count == 0 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.codecs, self.count) }
+    }
+}
+
+// Rust owns the array from Swift. Drop it when it goes out of scope.
+impl Drop for AppCodecCapabilityArray {
+    fn drop(&mut self) {
+        (self.destroy)(self.object);
+    }
+}
+
+/// Structure for passing an optional byte buffer from Swift.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct AppByteArray {
+    /// Null if there are no bytes, e.g. a message was not sealed.
+    pub bytes:   *const u8,
+    pub len:     size_t,
+    /// Swift object owning the bytes.
+    pub object:  *mut c_void,
+    /// Swift object clean up method.
+    pub destroy: extern "C" fn(object: *mut c_void),
+}
+
+impl AppByteArray {
+    /// Return a copy of the bytes, or `None` if there are none.
+    pub fn to_vec(&self) -> Option<Vec<u8>> {
+        if self.bytes.is_null() {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(self.bytes, self.len) }.to_vec())
+    }
+}
+
+// Rust owns the buffer from Swift. Drop it when it goes out of scope.
+impl Drop for AppByteArray {
+    fn drop(&mut self) {
+        (self.destroy)(self.object);
+    }
+}
+
+/// Structure for passing a pending signaling message to and from
+/// Swift.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct AppPendingSignaling {
+    pub remote:          *const c_void,
+    pub callId:          u64,
+    pub remoteDevice:    u32,
+    /// The kind of the message, see `PendingMessage::kind()`.
+    pub kind:            i32,
+    /// The SDP of an offer or answer, null bytes for ICE candidates.
+    pub sdp:             AppByteSlice,
+    pub candidates:      *const AppIceCandidate,
+    pub candidatesCount: size_t,
+}
+
+impl AppPendingSignaling {
+    /// Return the ICE candidates of the message.
+    pub fn candidates(&self) -> &[AppIceCandidate] {
+        if self.candidates.is_null() || self.candidatesCount == 0 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.candidates, self.candidatesCount) }
+    }
+}
+
+/// Structure for passing multiple pending signaling messages from
+/// Swift.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct AppPendingSignalingArray {
+    pub pending: *const AppPendingSignaling,
+    pub count:   size_t,
+    /// Swift object owning the messages.
+    pub object:  *mut c_void,
+    /// Swift object clean up method.
+    pub destroy: extern "C" fn(object: *mut c_void),
+}
+
+impl AppPendingSignalingArray {
+    /// Return the messages, valid until the array is dropped.
+    pub fn pending(&self) -> &[AppPendingSignaling] {
+        if self.pending.is_null() || self.count == 0 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.pending, self.count) }
+    }
+}
+
+// Rust owns the array from Swift. Drop it when it goes out of scope.
+impl Drop for AppPendingSignalingArray {
+    fn drop(&mut self) {
+        (self.destroy)(self.object);
+    }
+}
+
 /// Structure for passing connection details from the application.
 #[repr(C)]
 #[derive(Clone, Debug)]
@@ -197,7 +366,7 @@ pub struct AppInterface {
     pub onStartCall:
         extern "C" fn(object: *mut c_void, remote: *const c_void, callId: u64, isOutgoing: bool),
     /// Swift event callback method.
-    pub onEvent: extern "C" fn(object: *mut c_void, remote: *const c_void, event: i32),
+    pub onEvent: extern "C" fn(object: *mut c_void, remote: *const c_void, callId: u64, event: i32),
     ///
     pub onSendOffer: extern "C" fn(
         object: *mut c_void,
@@ -232,6 +401,7 @@ pub struct AppInterface {
         remote: *const c_void,
         deviceId: u32,
         broadcast: bool,
+        hangupType: i32,
     ),
     ///
     pub onSendBusy: extern "C" fn(
@@ -263,7 +433,79 @@ pub struct AppInterface {
     pub onCompareRemotes:
         extern "C" fn(object: *mut c_void, remote1: *const c_void, remote2: *const c_void) -> bool,
     ///
-    pub onCallConcluded:              extern "C" fn(object: *mut c_void, remote: *const c_void),
+    pub onCallConcluded: extern "C" fn(object: *mut c_void, remote: *const c_void, callId: u64),
+    /// Request fresh STUN and TURN servers for the call.
+    pub getTurnServers:
+        extern "C" fn(object: *mut c_void, remote: *const c_void, callId: u64) -> AppIceServerArray,
+    /// Request the codecs the device supports in hardware.
+    pub getCodecCapabilities:         extern "C" fn(object: *mut c_void) -> AppCodecCapabilityArray,
+    /// Notify the application of the estimated bandwidth of the active call.
+    pub onBandwidthEstimate: extern "C" fn(
+        object: *mut c_void,
+        remote: *const c_void,
+        callId: u64,
+        sendBps: u64,
+        receiveBps: u64,
+    ),
+    /// Notify the application of the network the ICE route of the call runs over.
+    pub onNetworkRouteChanged: extern "C" fn(
+        object: *mut c_void,
+        remote: *const c_void,
+        callId: u64,
+        localAdapterType: i32,
+        relayed: bool,
+    ),
+    /// Notify the application of the safety numbers of the connected call.
+    pub onSafetyNumbers: extern "C" fn(
+        object: *mut c_void,
+        remote: *const c_void,
+        callId: u64,
+        remoteDevice: u32,
+        safetyNumbers: AppByteSlice,
+    ),
+    /// Notify the application of the metadata the callee attached to its answer.
+    pub onReceivedAnswerMetadata: extern "C" fn(
+        object: *mut c_void,
+        remote: *const c_void,
+        callId: u64,
+        remoteDevice: u32,
+        metadata: AppByteSlice,
+    ),
+    /// Request to seal an opaque signaling message for a remote peer.
+    pub seal: extern "C" fn(
+        object: *mut c_void,
+        remote: *const c_void,
+        callId: u64,
+        remoteDevice: u32,
+        opaque: AppByteSlice,
+    ) -> AppByteArray,
+    /// Request to open a sealed signaling message, null bytes if not authentic.
+    pub open: extern "C" fn(
+        object: *mut c_void,
+        callId: u64,
+        remoteDevice: u32,
+        sealed: AppByteSlice,
+    ) -> AppByteArray,
+    /// Request to persist the signaling messages waiting to be sent.
+    pub persistPendingSignaling:
+        extern "C" fn(object: *mut c_void, pending: *const AppPendingSignaling, count: size_t),
+    /// Request the signaling messages last persisted.
+    pub restorePendingSignaling: extern "C" fn(object: *mut c_void) -> AppPendingSignalingArray,
+    /// Request that a pre-offer be sent to all the devices of the remote.
+    pub onSendPreOffer:
+        extern "C" fn(object: *mut c_void, callId: u64, remote: *const c_void, deviceId: u32),
+    /// Notification that a remote peer is about to offer a call.
+    pub onReceivedPreOffer:
+        extern "C" fn(object: *mut c_void, callId: u64, remote: *const c_void, deviceId: u32),
+    /// Notify the application of a message the remote peer sent with
+    /// ringrtcSendAppMessage().
+    pub onAppMessageReceived: extern "C" fn(
+        object: *mut c_void,
+        remote: *const c_void,
+        callId: u64,
+        remoteDevice: u32,
+        payload: AppByteSlice,
+    ),
 }
 
 // Add an empty Send trait to allow transfer of ownership between threads.
@@ -322,17 +564,21 @@ pub extern "C" fn ringrtcProceed(
     callManager: *mut c_void,
     callId: u64,
     appCallContext: AppCallContext,
+    appIceServers: *const AppIceServer,
+    appIceServersLen: size_t,
     appRemoteDevices: *const u32,
     appRemoteDevicesLen: size_t,
 ) -> *mut c_void {
     // Convert the remoteDevices list from a u32 array to a vector.
     let device_slice =
         unsafe { slice::from_raw_parts(appRemoteDevices, appRemoteDevicesLen as usize) };
+    let ice_server_slice = unsafe { slice::from_raw_parts(appIceServers, appIceServersLen) };
 
     match call_manager::proceed(
         callManager as *mut IOSCallManager,
         callId,
         appCallContext,
+        ice_server_slice,
         device_slice.to_vec(),
     ) {
         Ok(_v) => {
@@ -409,6 +655,28 @@ pub extern "C" fn ringrtcReceivedAnswer(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcReceivedPreOffer(
+    callManager: *mut c_void,
+    callId: u64,
+    appRemote: *const c_void,
+    remoteDevice: u32,
+) -> *mut c_void {
+    match call_manager::received_pre_offer(
+        callManager as *mut IOSCallManager,
+        callId,
+        appRemote,
+        remoteDevice as DeviceId,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcReceivedOffer(
@@ -418,6 +686,7 @@ pub extern "C" fn ringrtcReceivedOffer(
     remoteDevice: u32,
     offer: AppByteSlice,
     timestamp: u64,
+    ageSec: u64,
 ) -> *mut c_void {
     // Build the Rust string.
     let offer_bytes = unsafe { slice::from_raw_parts(offer.bytes, offer.len as usize) };
@@ -431,6 +700,7 @@ pub extern "C" fn ringrtcReceivedOffer(
                 remoteDevice as DeviceId,
                 session_desc,
                 timestamp,
+                ageSec,
             ) {
                 Ok(_v) => {
                     // Return the object reference back as indication of success.
@@ -506,11 +776,33 @@ pub extern "C" fn ringrtcReceivedHangup(
     callManager: *mut c_void,
     callId: u64,
     remoteDevice: u32,
+    hangupType: i32,
 ) -> *mut c_void {
     match call_manager::received_hangup(
         callManager as *mut IOSCallManager,
         callId,
         remoteDevice as DeviceId,
+        hangupType,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcReceivedDeclined(
+    callManager: *mut c_void,
+    callId: u64,
+    remoteDevice: u32,
+) -> *mut c_void {
+    match call_manager::received_declined(
+        callManager as *mut IOSCallManager,
+        callId,
+        remoteDevice as DeviceId,
     ) {
         Ok(_v) => {
             // Return the object reference back as indication of success.
@@ -582,6 +874,230 @@ pub extern "C" fn ringrtcSetVideoEnable(callManager: *mut c_void, enable: bool)
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetScreenShareEnable(
+    callManager: *mut c_void,
+    callId: u64,
+    enable: bool,
+) -> *mut c_void {
+    match call_manager::set_screen_share_enable(callManager as *mut IOSCallManager, callId, enable)
+    {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetAnswerMetadata(
+    callManager: *mut c_void,
+    callId: u64,
+    metadata: AppByteSlice,
+) -> *mut c_void {
+    let metadata_slice = unsafe { slice::from_raw_parts(metadata.bytes, metadata.len) };
+
+    match call_manager::set_answer_metadata(
+        callManager as *mut IOSCallManager,
+        callId,
+        metadata_slice.to_vec(),
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcUpdateIceServers(
+    callManager: *mut c_void,
+    callId: u64,
+    appIceServers: *const AppIceServer,
+    appIceServersLen: size_t,
+) -> *mut c_void {
+    let ice_server_slice = unsafe { slice::from_raw_parts(appIceServers, appIceServersLen) };
+
+    match call_manager::update_ice_servers(
+        callManager as *mut IOSCallManager,
+        callId,
+        ice_server_slice,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcRestartIce(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::restart_ice(callManager as *mut IOSCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetOnHold(
+    callManager: *mut c_void,
+    callId: u64,
+    onHold: bool,
+) -> *mut c_void {
+    match call_manager::set_on_hold(callManager as *mut IOSCallManager, callId, onHold) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetVideoDegradationPreference(
+    callManager: *mut c_void,
+    preference: i32,
+) -> *mut c_void {
+    match call_manager::set_video_degradation_preference(
+        callManager as *mut IOSCallManager,
+        preference,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetBandwidthMode(
+    callManager: *mut c_void,
+    callId: u64,
+    mode: i32,
+) -> *mut c_void {
+    match call_manager::set_bandwidth_mode(callManager as *mut IOSCallManager, callId, mode) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcTransferCall(
+    callManager: *mut c_void,
+    callId: u64,
+    newRemote: AppByteSlice,
+) -> *mut c_void {
+    // Build the Rust string.
+    let new_remote_bytes =
+        unsafe { slice::from_raw_parts(newRemote.bytes, newRemote.len as usize) };
+
+    match str::from_utf8(new_remote_bytes) {
+        Ok(new_remote) => {
+            match call_manager::transfer_call(
+                callManager as *mut IOSCallManager,
+                callId,
+                new_remote,
+            ) {
+                Ok(_v) => {
+                    // Return the object reference back as indication of success.
+                    callManager
+                }
+                Err(_e) => ptr::null_mut(),
+            }
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetCallWaiting(callManager: *mut c_void, enable: bool) -> *mut c_void {
+    match call_manager::set_call_waiting(callManager as *mut IOSCallManager, enable) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetBandwidthEstimateInterval(
+    callManager: *mut c_void,
+    intervalMillis: u64,
+) -> *mut c_void {
+    match call_manager::set_bandwidth_estimate_interval(
+        callManager as *mut IOSCallManager,
+        intervalMillis,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetPreOffer(callManager: *mut c_void, enable: bool) -> *mut c_void {
+    match call_manager::set_pre_offer(callManager as *mut IOSCallManager, enable) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSendAppMessage(
+    callManager: *mut c_void,
+    payload: AppByteSlice,
+) -> *mut c_void {
+    let payload_slice = unsafe { slice::from_raw_parts(payload.bytes, payload.len) };
+
+    match call_manager::send_app_message(callManager as *mut IOSCallManager, payload_slice) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcCancelSetup(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::cancel_setup(callManager as *mut IOSCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcDrop(callManager: *mut c_void, callId: u64) -> *mut c_void {
@@ -594,6 +1110,18 @@ pub extern "C" fn ringrtcDrop(callManager: *mut c_void, callId: u64) -> *mut c_v
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcRestorePendingSignaling(callManager: *mut c_void) -> *mut c_void {
+    match call_manager::restore_pending_signaling(callManager as *mut IOSCallManager) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcReset(callManager: *mut c_void) -> *mut c_void {
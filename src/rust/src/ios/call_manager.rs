@@ -10,15 +10,28 @@
 use std::ffi::c_void;
 use std::panic;
 use std::sync::Arc;
+use std::time::Duration;
+use std::{slice, str};
 
 use crate::ios::logging::{init_logging, IOSLogger};
 
-use crate::ios::api::call_manager_interface::{AppCallContext, AppInterface, AppObject};
+use crate::ios::api::call_manager_interface::{
+    AppCallContext,
+    AppIceCandidate,
+    AppIceServer,
+    AppInterface,
+    AppObject,
+};
 use crate::ios::ios_platform::IOSPlatform;
+use crate::ios::ios_util::AppByteSlice;
 
-use crate::common::{CallId, ConnectionId, DeviceId, Result};
+use crate::common::{CallId, ConnectionId, DeviceId, HangupType, Result};
 
+use crate::core::bandwidth_mode::BandwidthMode;
+use crate::core::call_config::CallConfig;
+use crate::core::ice_server::IceServerConfig;
 use crate::core::util::{ptr_as_box, ptr_as_mut};
+use crate::core::video::DegradationPreference;
 
 use crate::core::call_manager::CallManager;
 
@@ -59,7 +72,7 @@ pub fn call(call_manager: *mut IOSCallManager, app_remote: *const c_void) -> Res
 
     info!("call():");
 
-    call_manager.call(AppObject::from(app_remote))
+    call_manager.call(AppObject::from(app_remote), CallConfig::default())
 }
 
 /// Application notification to proceed with a new call
@@ -67,6 +80,7 @@ pub fn proceed(
     call_manager: *mut IOSCallManager,
     call_id: u64,
     app_call_context: AppCallContext,
+    app_ice_servers: &[AppIceServer],
     app_remote_devices: Vec<u32>,
 ) -> Result<()> {
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
@@ -90,11 +104,90 @@ pub fn proceed(
         info!("proceed(): device id: {}", device);
     }
 
-    call_manager.proceed(
+    let ice_servers = ice_servers_from_app(app_ice_servers)?;
+    info!("proceed(): ice_servers size: {}", ice_servers.len());
+
+    let _ = call_manager.proceed(
         CallId::from(call_id),
         Arc::new(app_call_context),
         remote_devices,
-    )
+        ice_servers,
+    )?;
+    Ok(())
+}
+
+/// Convert the Swift ICE servers, one per URL, into a Rust Vector of
+/// IceServerConfig.
+pub fn ice_servers_from_app(app_ice_servers: &[AppIceServer]) -> Result<Vec<IceServerConfig>> {
+    let string = |slice: &AppByteSlice| -> Result<String> {
+        let bytes = unsafe { slice::from_raw_parts(slice.bytes, slice.len) };
+        Ok(str::from_utf8(bytes)?.to_string())
+    };
+
+    let mut ice_servers = Vec::new();
+    for app_ice_server in app_ice_servers {
+        ice_servers.push(IceServerConfig::new(
+            &string(&app_ice_server.url)?,
+            &string(&app_ice_server.username)?,
+            &string(&app_ice_server.password)?,
+        )?);
+    }
+    Ok(ice_servers)
+}
+
+/// Application request to attach opaque metadata to the answer of an
+/// incoming call
+pub fn set_answer_metadata(
+    call_manager: *mut IOSCallManager,
+    call_id: u64,
+    metadata: Vec<u8>,
+) -> Result<()> {
+    info!("set_answer_metadata(): size: {}", metadata.len());
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_answer_metadata(CallId::from(call_id), metadata)
+}
+
+/// Convert AppIceCandidates into IceCandidates.
+pub fn ice_candidates_from_app(
+    app_ice_candidates: &[AppIceCandidate],
+) -> Result<Vec<IceCandidate>> {
+    let string = |slice: &AppByteSlice| -> Result<String> {
+        let bytes = unsafe { slice::from_raw_parts(slice.bytes, slice.len) };
+        Ok(str::from_utf8(bytes)?.to_string())
+    };
+
+    let mut ice_candidates = Vec::new();
+    for app_ice_candidate in app_ice_candidates {
+        ice_candidates.push(IceCandidate::new(
+            string(&app_ice_candidate.sdpMid)?,
+            app_ice_candidate.sdpMLineIndex,
+            string(&app_ice_candidate.sdp)?,
+        ));
+    }
+    Ok(ice_candidates)
+}
+
+/// Application request to send the signaling messages persisted
+/// before a restart
+pub fn restore_pending_signaling(call_manager: *mut IOSCallManager) -> Result<()> {
+    info!("restore_pending_signaling():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.restore_pending_signaling()
+}
+
+/// Application request to replace the ICE servers of the call
+pub fn update_ice_servers(
+    call_manager: *mut IOSCallManager,
+    call_id: u64,
+    app_ice_servers: &[AppIceServer],
+) -> Result<()> {
+    let ice_servers = ice_servers_from_app(app_ice_servers)?;
+    info!("update_ice_servers(): size: {}", ice_servers.len());
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.update_ice_servers(CallId::from(call_id), ice_servers)
 }
 
 /// Application notification that the sending of the previous message was a success.
@@ -135,6 +228,21 @@ pub fn received_answer(
     call_manager.received_answer(connection_id, app_answer.to_string())
 }
 
+/// Application notification of received pre-offer message
+pub fn received_pre_offer(
+    call_manager: *mut IOSCallManager,
+    call_id: u64,
+    app_remote: *const c_void,
+    remote_device: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
+
+    info!("received_pre_offer(): id: {}", connection_id);
+
+    call_manager.received_pre_offer(AppObject::from(app_remote), connection_id)
+}
+
 /// Application notification of received SDP offer message
 pub fn received_offer(
     call_manager: *mut IOSCallManager,
@@ -143,17 +251,20 @@ pub fn received_offer(
     remote_device: DeviceId,
     app_offer: &str,
     timestamp: u64,
+    age_sec: u64,
 ) -> Result<()> {
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
     let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
 
-    info!("received_offer(): id: {}", connection_id);
+    info!("received_offer(): id: {}, age: {}s", connection_id, age_sec);
 
     call_manager.received_offer(
         AppObject::from(app_remote),
         connection_id,
         app_offer.to_string(),
         timestamp,
+        Duration::from_secs(age_sec),
+        CallConfig::default(),
     )
 }
 
@@ -178,7 +289,7 @@ pub fn received_ice_candidates(
     }
 
     info!("ice_candidate size: {}", ice_candidates.len());
-    call_manager.received_ice_candidates(connection_id, &ice_candidates)?;
+    call_manager.received_ice_candidates(connection_id, &ice_candidates, Duration::from_secs(0))?;
 
     Ok(())
 }
@@ -188,13 +299,32 @@ pub fn received_hangup(
     call_manager: *mut IOSCallManager,
     call_id: u64,
     remote_device: DeviceId,
+    hangup_type: i32,
 ) -> Result<()> {
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
     let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
+    let hangup_type = HangupType::from_i32(hangup_type)?;
 
-    info!("received_hangup(): id: {}", connection_id);
+    info!(
+        "received_hangup(): id: {}, type: {}",
+        connection_id, hangup_type
+    );
 
-    call_manager.received_hangup(connection_id)
+    call_manager.received_hangup(connection_id, hangup_type)
+}
+
+/// Application notification of received declined Hangup message
+pub fn received_declined(
+    call_manager: *mut IOSCallManager,
+    call_id: u64,
+    remote_device: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let connection_id = ConnectionId::new(CallId::from(call_id), remote_device);
+
+    info!("received_declined(): id: {}", connection_id);
+
+    call_manager.received_declined(connection_id)
 }
 
 /// Application notification of received Busy message
@@ -216,7 +346,8 @@ pub fn accept_call(call_manager: *mut IOSCallManager, call_id: u64) -> Result<()
     info!("accept_call():");
 
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
-    call_manager.accept_call(CallId::from(call_id))
+    let _ = call_manager.accept_call(CallId::from(call_id))?;
+    Ok(())
 }
 
 /// CMI request for the active Connection object
@@ -246,8 +377,126 @@ pub fn set_video_enable(call_manager: *mut IOSCallManager, enable: bool) -> Resu
     info!("set_video_enable():");
 
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
-    let mut active_connection = call_manager.active_connection()?;
-    active_connection.inject_local_video_status(enable)
+    let call_id = call_manager.active_call()?.call_id();
+    call_manager.set_outgoing_video_enabled(call_id, enable)
+}
+
+/// CMI request to start or stop sharing the screen
+pub fn set_screen_share_enable(
+    call_manager: *mut IOSCallManager,
+    call_id: u64,
+    enable: bool,
+) -> Result<()> {
+    info!("set_screen_share_enable(): enable: {}", enable);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_outgoing_screen_share_enabled(CallId::from(call_id), enable)
+}
+
+/// CMI request to restart ICE on the active connection
+pub fn restart_ice(call_manager: *mut IOSCallManager, call_id: u64) -> Result<()> {
+    info!("restart_ice():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.restart_ice(CallId::from(call_id))
+}
+
+/// CMI request to put the active call on hold, or resume it
+pub fn set_on_hold(call_manager: *mut IOSCallManager, call_id: u64, on_hold: bool) -> Result<()> {
+    info!("set_on_hold(): on_hold: {}", on_hold);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_on_hold(CallId::from(call_id), on_hold)
+}
+
+/// CMI request to set how the sent video degrades
+pub fn set_video_degradation_preference(
+    call_manager: *mut IOSCallManager,
+    preference: i32,
+) -> Result<()> {
+    let preference = DegradationPreference::from_i32(preference)?;
+    info!("set_video_degradation_preference(): {}", preference);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    active_connection.set_degradation_preference(preference)
+}
+
+/// CMI request to set how much bandwidth the active call may use
+pub fn set_bandwidth_mode(
+    call_manager: *mut IOSCallManager,
+    call_id: u64,
+    mode: i32,
+) -> Result<()> {
+    let mode = BandwidthMode::from_i32(mode)?;
+    info!("set_bandwidth_mode(): {}", mode);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_bandwidth_mode(CallId::from(call_id), mode)
+}
+
+/// CMI request to transfer the active call to another peer
+pub fn transfer_call(
+    call_manager: *mut IOSCallManager,
+    call_id: u64,
+    new_remote: &str,
+) -> Result<()> {
+    info!("transfer_call(): new_remote: {}", new_remote);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.transfer_call(CallId::from(call_id), new_remote.to_string())
+}
+
+/// CMI request to enable or disable call waiting
+pub fn set_call_waiting(call_manager: *mut IOSCallManager, enable: bool) -> Result<()> {
+    info!("set_call_waiting(): enable: {}", enable);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_call_waiting(enable)
+}
+
+/// CMI request to set how often the bandwidth estimate of the active
+/// call is reported, zero to disable
+pub fn set_bandwidth_estimate_interval(
+    call_manager: *mut IOSCallManager,
+    interval_millis: u64,
+) -> Result<()> {
+    info!("set_bandwidth_estimate_interval(): {}", interval_millis);
+
+    let interval = if interval_millis > 0 {
+        Some(Duration::from_millis(interval_millis))
+    } else {
+        None
+    };
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_bandwidth_estimate_interval(interval)
+}
+
+/// CMI request to enable or disable pre-offers
+pub fn set_pre_offer(call_manager: *mut IOSCallManager, enable: bool) -> Result<()> {
+    info!("set_pre_offer(): enable: {}", enable);
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_pre_offer(enable)
+}
+
+/// CMI request to send the remote peer of the active connection an
+/// application message
+pub fn send_app_message(call_manager: *mut IOSCallManager, payload: &[u8]) -> Result<()> {
+    info!("send_app_message(): size: {}", payload.len());
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let active_connection = call_manager.active_connection()?;
+    active_connection.send_app_message(payload)
+}
+
+/// CMI request to cancel the setup of an active call
+pub fn cancel_setup(call_manager: *mut IOSCallManager, call_id: u64) -> Result<()> {
+    info!("cancel_setup():");
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.cancel_setup(CallId::from(call_id))
 }
 
 /// CMI request to drop the active call
@@ -9,6 +9,9 @@
 
 use std::ffi::c_void;
 use std::fmt;
+use std::ptr;
+use std::slice;
+use std::str;
 use std::sync::Arc;
 
 use libc::size_t;
@@ -19,26 +22,36 @@ use crate::common::{
     CallId,
     ConnectionId,
     DeviceId,
+    HangupType,
     Result,
     DATA_CHANNEL_NAME,
 };
 use crate::core::call::Call;
+use crate::core::codec::CodecCapability;
 use crate::core::connection::Connection;
+use crate::core::connection_config::ConnectionConfig;
+use crate::core::ice_server::IceServerConfig;
+use crate::core::media_descriptor::MediaDescriptor;
+use crate::core::pending_signaling::{PendingMessage, PendingSignaling};
 use crate::core::platform::{Platform, PlatformItem};
+use crate::error::RingRtcError;
 use crate::ios::api::call_manager_interface::{
     AppCallContext,
+    AppCodecCapabilityArray,
     AppConnectionInterface,
     AppIceCandidate,
     AppIceCandidateArray,
     AppInterface,
     AppObject,
+    AppPendingSignaling,
 };
+use crate::ios::call_manager::{ice_candidates_from_app, ice_servers_from_app};
 use crate::ios::error::IOSError;
 use crate::ios::ios_media_stream::IOSMediaStream;
 use crate::ios::ios_util::*;
 
 use crate::webrtc::data_channel_observer::DataChannelObserver;
-use crate::webrtc::ice_candidate::IceCandidate;
+use crate::webrtc::ice_candidate::{IceCandidate, NetworkAdapterType};
 use crate::webrtc::media_stream::MediaStream;
 use crate::webrtc::peer_connection::{PeerConnection, RffiPeerConnectionInterface};
 use crate::webrtc::peer_connection_observer::PeerConnectionObserver;
@@ -102,61 +115,35 @@ impl Platform for IOSPlatform {
         &mut self,
         call: &Call<Self>,
         remote_device: DeviceId,
+        config: ConnectionConfig,
     ) -> Result<Connection<Self>> {
         let connection_id = ConnectionId::new(call.call_id(), remote_device);
 
-        info!("create_connection(): {}", connection_id);
+        // The application configures the PeerConnection itself, only
+        // the ICE servers are applied once it exists.
+        info!("create_connection(): {}, {}", connection_id, config);
 
         let connection = Connection::new(call.clone(), remote_device)?;
+        self.create_app_connection(call, &connection, &config.ice_servers)?;
 
-        let connection_ptr = connection.get_connection_ptr()?;
+        debug!("Done with create_connection!");
 
-        // Get the observer because we will need it when creating the
-        // PeerConnection in Swift.
-        let pc_observer = PeerConnectionObserver::new(connection_ptr)?;
+        Ok(connection)
+    }
 
-        let app_connection_interface = (self.app_interface.onCreateConnectionInterface)(
-            self.app_interface.object,
-            pc_observer.rffi_interface() as *mut c_void,
-            remote_device,
-            call.call_context()?.object,
+    fn recreate_peer_connection(
+        &mut self,
+        call: &Call<Self>,
+        connection: &Connection<Self>,
+        config: ConnectionConfig,
+    ) -> Result<()> {
+        info!(
+            "recreate_peer_connection(): {}, {}",
+            connection.id(),
+            config
         );
 
-        if app_connection_interface.object.is_null() || app_connection_interface.pc.is_null() {
-            return Err(IOSError::CreateAppPeerConnection.into());
-        }
-
-        debug!("app_connection_interface: {}", app_connection_interface);
-
-        // Finish up the connection creation...
-
-        // Retrieve the underlying PeerConnectionInterface object from the
-        // application owned RTCPeerConnection object.
-        let rffi_pc_interface = app_connection_interface.pc as *const RffiPeerConnectionInterface;
-        if rffi_pc_interface.is_null() {
-            return Err(IOSError::ExtractNativePeerConnectionInterface.into());
-        }
-
-        let pc_interface = PeerConnection::new(rffi_pc_interface);
-
-        if let CallDirection::OutGoing = connection.direction() {
-            // Create data channel observer and data channel.
-            let dc_observer = DataChannelObserver::new(connection.clone())?;
-            let data_channel = pc_interface.create_data_channel(DATA_CHANNEL_NAME.to_string())?;
-            unsafe { data_channel.register_observer(dc_observer.rffi_interface())? };
-            connection.set_data_channel(data_channel)?;
-            connection.set_data_channel_observer(dc_observer)?;
-        }
-
-        connection.set_pc_interface(pc_interface)?;
-
-        info!("connection: {:?}", connection);
-
-        connection.set_app_connection(Arc::new(app_connection_interface))?;
-
-        debug!("Done with create_connection!");
-
-        Ok(connection)
+        self.create_app_connection(call, connection, &config.ice_servers)
     }
 
     fn on_start_call(
@@ -183,10 +170,110 @@ impl Platform for IOSPlatform {
         Ok(())
     }
 
-    fn on_event(&self, remote_peer: &Self::AppRemotePeer, event: ApplicationEvent) -> Result<()> {
-        info!("on_event(): {}", event);
+    fn on_event(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        event: ApplicationEvent,
+    ) -> Result<()> {
+        info!("on_event(): call_id: {}, event: {}", call_id, event);
+
+        (self.app_interface.onEvent)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(call_id),
+            event as i32,
+        );
+
+        Ok(())
+    }
+
+    fn on_received_answer_metadata(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        metadata: &[u8],
+    ) -> Result<()> {
+        info!(
+            "on_received_answer_metadata(): id: {}, size: {}",
+            connection_id,
+            metadata.len()
+        );
+
+        let metadata = AppByteSlice {
+            bytes: metadata.as_ptr(),
+            len:   metadata.len(),
+        };
 
-        (self.app_interface.onEvent)(self.app_interface.object, remote_peer.ptr, event as i32);
+        (self.app_interface.onReceivedAnswerMetadata)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(connection_id.call_id()),
+            connection_id.remote_device(),
+            metadata,
+        );
+
+        Ok(())
+    }
+
+    fn on_app_message_received(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        payload: &[u8],
+    ) -> Result<()> {
+        info!(
+            "on_app_message_received(): id: {}, size: {}",
+            connection_id,
+            payload.len()
+        );
+
+        let payload = AppByteSlice {
+            bytes: payload.as_ptr(),
+            len:   payload.len(),
+        };
+
+        (self.app_interface.onAppMessageReceived)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(connection_id.call_id()),
+            connection_id.remote_device(),
+            payload,
+        );
+
+        Ok(())
+    }
+
+    fn on_send_pre_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!("on_send_pre_offer(): id: {}", connection_id);
+
+        (self.app_interface.onSendPreOffer)(
+            self.app_interface.object,
+            u64::from(connection_id.call_id()),
+            remote_peer.ptr,
+            connection_id.remote_device(),
+        );
+
+        Ok(())
+    }
+
+    fn on_received_pre_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!("on_received_pre_offer(): id: {}", connection_id);
+
+        (self.app_interface.onReceivedPreOffer)(
+            self.app_interface.object,
+            u64::from(connection_id.call_id()),
+            remote_peer.ptr,
+            connection_id.remote_device(),
+        );
 
         Ok(())
     }
@@ -197,10 +284,12 @@ impl Platform for IOSPlatform {
         connection_id: ConnectionId,
         broadcast: bool,
         description: &str,
+        descriptor: &MediaDescriptor,
     ) -> Result<()> {
+        // The application interface only carries the SDP.
         info!(
-            "on_send_offer(): id: {}, broadcast: {}",
-            connection_id, broadcast
+            "on_send_offer(): id: {}, broadcast: {}, descriptor: {}",
+            connection_id, broadcast, descriptor
         );
 
         let string_slice = AppByteSlice {
@@ -226,10 +315,12 @@ impl Platform for IOSPlatform {
         connection_id: ConnectionId,
         broadcast: bool,
         description: &str,
+        descriptor: &MediaDescriptor,
     ) -> Result<()> {
+        // The application interface only carries the SDP.
         info!(
-            "on_send_answer(): id: {}, broadcast: {}",
-            connection_id, broadcast
+            "on_send_answer(): id: {}, broadcast: {}, descriptor: {}",
+            connection_id, broadcast, descriptor
         );
 
         let string_slice = AppByteSlice {
@@ -267,27 +358,7 @@ impl Platform for IOSPlatform {
 
         // The format of the IceCandidate structure is not enough for iOS,
         // so we will convert to a more appropriate structure.
-        let mut v: Vec<AppIceCandidate> = Vec::new();
-
-        for candidate in ice_candidates {
-            let sdp_mid_slice = AppByteSlice {
-                bytes: candidate.sdp_mid.as_ptr(),
-                len:   candidate.sdp_mid.len() as size_t,
-            };
-
-            let sdp_slice = AppByteSlice {
-                bytes: candidate.sdp.as_ptr(),
-                len:   candidate.sdp.len() as size_t,
-            };
-
-            let ice_candidate = AppIceCandidate {
-                sdpMid:        sdp_mid_slice,
-                sdpMLineIndex: candidate.sdp_mline_index,
-                sdp:           sdp_slice,
-            };
-
-            v.push(ice_candidate);
-        }
+        let v = Self::app_ice_candidates(ice_candidates);
 
         let ice_candidates = AppIceCandidateArray {
             candidates: v.as_ptr(),
@@ -313,10 +384,11 @@ impl Platform for IOSPlatform {
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
         broadcast: bool,
+        hangup_type: HangupType,
     ) -> Result<()> {
         info!(
-            "on_send_hangup(): id: {}, broadcast: {}",
-            connection_id, broadcast
+            "on_send_hangup(): id: {}, broadcast: {}, type: {}",
+            connection_id, broadcast, hangup_type
         );
 
         (self.app_interface.onSendHangup)(
@@ -325,6 +397,7 @@ impl Platform for IOSPlatform {
             remote_peer.ptr,
             connection_id.remote_device(),
             broadcast,
+            hangup_type as i32,
         );
 
         Ok(())
@@ -352,6 +425,51 @@ impl Platform for IOSPlatform {
         Ok(())
     }
 
+    fn seal(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        opaque: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        debug!("seal(): id: {}, size: {}", connection_id, opaque.len());
+
+        let opaque = AppByteSlice {
+            bytes: opaque.as_ptr(),
+            len:   opaque.len(),
+        };
+
+        let app_sealed = (self.app_interface.seal)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(connection_id.call_id()),
+            connection_id.remote_device(),
+            opaque,
+        );
+
+        Ok(app_sealed.to_vec())
+    }
+
+    fn open(&self, connection_id: ConnectionId, sealed: &[u8]) -> Result<Vec<u8>> {
+        debug!("open(): id: {}, size: {}", connection_id, sealed.len());
+
+        let sealed = AppByteSlice {
+            bytes: sealed.as_ptr(),
+            len:   sealed.len(),
+        };
+
+        let app_opened = (self.app_interface.open)(
+            self.app_interface.object,
+            u64::from(connection_id.call_id()),
+            connection_id.remote_device(),
+            sealed,
+        );
+
+        match app_opened.to_vec() {
+            Some(opened) => Ok(opened),
+            None => Err(RingRtcError::OpenSealedMessage("not authentic".to_owned()).into()),
+        }
+    }
+
     fn create_media_stream(
         &self,
         connection: &Connection<Self>,
@@ -413,16 +531,231 @@ impl Platform for IOSPlatform {
         Ok(result)
     }
 
-    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer) -> Result<()> {
-        info!("on_call_concluded():");
+    fn get_turn_servers(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+    ) -> Result<Vec<IceServerConfig>> {
+        info!("get_turn_servers(): call_id: {}", call_id);
+
+        let app_ice_servers = (self.app_interface.getTurnServers)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(call_id),
+        );
+
+        ice_servers_from_app(app_ice_servers.servers())
+    }
+
+    fn get_codec_capabilities(&self) -> Result<Vec<CodecCapability>> {
+        info!("get_codec_capabilities():");
+
+        let app_codecs: AppCodecCapabilityArray =
+            (self.app_interface.getCodecCapabilities)(self.app_interface.object);
+
+        let mut codecs = Vec::new();
+        for app_codec in app_codecs.codecs() {
+            let name = unsafe { slice::from_raw_parts(app_codec.name.bytes, app_codec.name.len) };
+            codecs.push(CodecCapability::hardware(
+                str::from_utf8(name)?,
+                app_codec.encode,
+                app_codec.decode,
+            ));
+        }
+        Ok(codecs)
+    }
+
+    fn on_safety_numbers(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        safety_numbers: &str,
+    ) -> Result<()> {
+        info!("on_safety_numbers(): id: {}", connection_id);
+
+        let safety_numbers = AppByteSlice {
+            bytes: safety_numbers.as_ptr(),
+            len:   safety_numbers.len(),
+        };
+
+        (self.app_interface.onSafetyNumbers)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(connection_id.call_id()),
+            connection_id.remote_device(),
+            safety_numbers,
+        );
+
+        Ok(())
+    }
+
+    fn on_network_route_changed(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        local_adapter_type: NetworkAdapterType,
+        relayed: bool,
+    ) -> Result<()> {
+        info!(
+            "on_network_route_changed(): call_id: {}, adapter: {}, relayed: {}",
+            call_id, local_adapter_type, relayed
+        );
+
+        (self.app_interface.onNetworkRouteChanged)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(call_id),
+            local_adapter_type as i32,
+            relayed,
+        );
+
+        Ok(())
+    }
+
+    fn on_bandwidth_estimate(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        send_bps: u64,
+        receive_bps: u64,
+    ) -> Result<()> {
+        debug!(
+            "on_bandwidth_estimate(): call_id: {}, send: {}, receive: {}",
+            call_id, send_bps, receive_bps
+        );
+
+        (self.app_interface.onBandwidthEstimate)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(call_id),
+            send_bps,
+            receive_bps,
+        );
+
+        Ok(())
+    }
+
+    fn persist_pending_signaling(
+        &self,
+        pending: &[PendingSignaling<Self::AppRemotePeer>],
+    ) -> Result<()> {
+        info!("persist_pending_signaling(): len: {}", pending.len());
+
+        // The candidates of each message must outlive the call below.
+        let candidates: Vec<Vec<AppIceCandidate>> = pending
+            .iter()
+            .map(|signaling| Self::app_ice_candidates(signaling.message.ice_candidates()))
+            .collect();
+
+        let mut v: Vec<AppPendingSignaling> = Vec::new();
+        for (signaling, candidates) in pending.iter().zip(candidates.iter()) {
+            let sdp = match signaling.message.description() {
+                Some(description) => AppByteSlice {
+                    bytes: description.as_ptr(),
+                    len:   description.len(),
+                },
+                None => AppByteSlice {
+                    bytes: ptr::null(),
+                    len:   0,
+                },
+            };
+
+            v.push(AppPendingSignaling {
+                remote: signaling.remote_peer.ptr,
+                callId: u64::from(signaling.connection_id.call_id()),
+                remoteDevice: signaling.connection_id.remote_device(),
+                kind: signaling.message.kind(),
+                sdp,
+                candidates: candidates.as_ptr(),
+                candidatesCount: candidates.len(),
+            });
+        }
 
-        (self.app_interface.onCallConcluded)(self.app_interface.object, remote_peer.ptr);
+        // The messages are passed up by reference and must be
+        // consumed by the integration layer before returning.
+        (self.app_interface.persistPendingSignaling)(
+            self.app_interface.object,
+            v.as_ptr(),
+            v.len(),
+        );
+
+        Ok(())
+    }
+
+    fn restore_pending_signaling(&self) -> Result<Vec<PendingSignaling<Self::AppRemotePeer>>> {
+        info!("restore_pending_signaling():");
+
+        let app_pending = (self.app_interface.restorePendingSignaling)(self.app_interface.object);
+
+        let mut pending = Vec::new();
+        for app_signaling in app_pending.pending() {
+            let description = if app_signaling.sdp.bytes.is_null() {
+                None
+            } else {
+                let bytes = unsafe {
+                    slice::from_raw_parts(app_signaling.sdp.bytes, app_signaling.sdp.len)
+                };
+                Some(str::from_utf8(bytes)?.to_string())
+            };
+            let ice_candidates = ice_candidates_from_app(app_signaling.candidates())?;
+
+            pending.push(PendingSignaling {
+                remote_peer:   AppObject::new(app_signaling.remote as *mut c_void),
+                connection_id: ConnectionId::new(
+                    CallId::from(app_signaling.callId),
+                    app_signaling.remoteDevice,
+                ),
+                message:       PendingMessage::from_kind(
+                    app_signaling.kind,
+                    description,
+                    ice_candidates,
+                )?,
+            });
+        }
+        Ok(pending)
+    }
+
+    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer, call_id: CallId) -> Result<()> {
+        info!("on_call_concluded(): call_id: {}", call_id);
+
+        (self.app_interface.onCallConcluded)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(call_id),
+        );
 
         Ok(())
     }
 }
 
 impl IOSPlatform {
+    /// Convert IceCandidates into AppIceCandidates, which refer to
+    /// the strings of the IceCandidates.
+    fn app_ice_candidates(ice_candidates: &[IceCandidate]) -> Vec<AppIceCandidate> {
+        let mut v: Vec<AppIceCandidate> = Vec::new();
+
+        for candidate in ice_candidates {
+            let sdp_mid_slice = AppByteSlice {
+                bytes: candidate.sdp_mid.as_ptr(),
+                len:   candidate.sdp_mid.len() as size_t,
+            };
+
+            let sdp_slice = AppByteSlice {
+                bytes: candidate.sdp.as_ptr(),
+                len:   candidate.sdp.len() as size_t,
+            };
+
+            let ice_candidate = AppIceCandidate {
+                sdpMid:        sdp_mid_slice,
+                sdpMLineIndex: candidate.sdp_mline_index,
+                sdp:           sdp_slice,
+            };
+
+            v.push(ice_candidate);
+        }
+        v
+    }
+
     /// Create a new IOSPlatform object.
     pub fn new(
         app_call_manager_interface: *mut c_void,
@@ -435,4 +768,62 @@ impl IOSPlatform {
 
         Ok(Self { app_interface })
     }
+
+    /// Create the application's PeerConnection for the Connection
+    /// and attach it.
+    fn create_app_connection(
+        &self,
+        call: &Call<Self>,
+        connection: &Connection<Self>,
+        ice_servers: &[IceServerConfig],
+    ) -> Result<()> {
+        let remote_device = connection.remote_device();
+        let connection_ptr = connection.get_connection_ptr()?;
+
+        // Get the observer because we will need it when creating the
+        // PeerConnection in Swift.
+        let pc_observer = PeerConnectionObserver::new(connection_ptr)?;
+
+        let app_connection_interface = (self.app_interface.onCreateConnectionInterface)(
+            self.app_interface.object,
+            pc_observer.rffi_interface() as *mut c_void,
+            remote_device,
+            call.call_context()?.object,
+        );
+
+        if app_connection_interface.object.is_null() || app_connection_interface.pc.is_null() {
+            return Err(IOSError::CreateAppPeerConnection.into());
+        }
+
+        debug!("app_connection_interface: {}", app_connection_interface);
+
+        // Finish up the connection creation...
+
+        // Retrieve the underlying PeerConnectionInterface object from the
+        // application owned RTCPeerConnection object.
+        let rffi_pc_interface = app_connection_interface.pc as *const RffiPeerConnectionInterface;
+        if rffi_pc_interface.is_null() {
+            return Err(IOSError::ExtractNativePeerConnectionInterface.into());
+        }
+
+        let pc_interface = PeerConnection::new(rffi_pc_interface);
+        if !ice_servers.is_empty() {
+            pc_interface.set_ice_servers(ice_servers)?;
+        }
+
+        if let CallDirection::OutGoing = connection.direction() {
+            // Create data channel observer and data channel.
+            let dc_observer = DataChannelObserver::new(connection.clone())?;
+            let data_channel = pc_interface.create_data_channel(DATA_CHANNEL_NAME.to_string())?;
+            unsafe { data_channel.register_observer(dc_observer.rffi_interface())? };
+            connection.set_data_channel(data_channel)?;
+            connection.set_data_channel_observer(dc_observer)?;
+        }
+
+        connection.set_pc_interface(pc_interface)?;
+
+        info!("connection: {:?}", connection);
+
+        connection.set_app_connection(Arc::new(app_connection_interface))
+    }
 }
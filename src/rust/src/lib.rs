@@ -30,19 +30,51 @@ mod error;
 
 /// Core, platform independent functionality.
 pub mod core {
+    pub mod audio_config;
+    pub mod audio_level;
+    pub mod audio_route;
+    pub mod bandwidth_mode;
     pub mod call;
+    pub mod call_config;
+    pub mod call_failure;
     pub mod call_fsm;
     pub mod call_manager;
     pub mod call_mutex;
+    pub mod call_record;
+    pub mod call_stats;
+    pub mod cancellation;
+    pub mod capabilities;
+    pub mod codec;
     pub mod connection;
+    pub mod connection_config;
     pub mod connection_fsm;
+    pub mod group_call;
+    pub mod ice_candidate_policy;
+    pub mod ice_encoding;
+    pub mod ice_server;
+    pub mod media_constraints;
+    pub mod media_descriptor;
+    pub mod offer_metadata;
+    pub mod pending_signaling;
     pub mod platform;
+    pub mod rate_limit;
+    pub mod recording;
+    pub mod route;
+    pub mod safety_numbers;
+    pub mod sequencing;
+    pub mod signaling;
+    pub mod signaling_transport;
+    #[cfg(feature = "srtp_key_export")]
+    pub mod srtp_keys;
+    pub mod trace;
     pub mod util;
+    pub mod video;
 }
 
 /// Protobuf Definitions.
 mod protobuf {
     pub mod data_channel;
+    pub mod signaling;
 }
 
 #[cfg(target_os = "android")]
@@ -80,11 +112,14 @@ mod ios {
 pub mod webrtc {
     pub mod data_channel;
     pub mod data_channel_observer;
+    pub mod frame_crypto;
     pub mod ice_candidate;
+    pub mod media;
     pub mod media_stream;
     pub mod peer_connection;
     pub mod peer_connection_observer;
     pub mod sdp_observer;
+    pub mod stats_observer;
     #[cfg(not(feature = "sim"))]
     mod ffi {
         pub mod data_channel;
@@ -93,6 +128,7 @@ pub mod webrtc {
         pub mod peer_connection_observer;
         pub mod ref_count;
         pub mod sdp_observer;
+        pub mod stats_observer;
     }
     #[cfg(feature = "sim")]
     mod sim {
@@ -102,6 +138,7 @@ pub mod webrtc {
         pub mod peer_connection_observer;
         pub mod ref_count;
         pub mod sdp_observer;
+        pub mod stats_observer;
     }
 }
 
@@ -14,6 +14,19 @@ pub struct Connected {
 pub struct Hangup {
     #[prost(uint64, optional, tag="1")]
     pub id: ::std::option::Option<u64>,
+    #[prost(enumeration="hangup::Type", optional, tag="2")]
+    pub r#type: ::std::option::Option<i32>,
+}
+pub mod hangup {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Type {
+        Normal = 0,
+        Accepted = 1,
+        Declined = 2,
+        Busy = 3,
+        NeedPermission = 4,
+    }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct VideoStreamingStatus {
@@ -23,6 +36,83 @@ pub struct VideoStreamingStatus {
     pub enabled: ::std::option::Option<bool>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AudioStatus {
+    #[prost(uint64, optional, tag="1")]
+    pub id: ::std::option::Option<u64>,
+    #[prost(bool, optional, tag="2")]
+    pub enabled: ::std::option::Option<bool>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Hold {
+    #[prost(uint64, optional, tag="1")]
+    pub id: ::std::option::Option<u64>,
+    #[prost(bool, optional, tag="2")]
+    pub held: ::std::option::Option<bool>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Transfer {
+    #[prost(uint64, optional, tag="1")]
+    pub id: ::std::option::Option<u64>,
+    #[prost(enumeration="transfer::Type", optional, tag="2")]
+    pub r#type: ::std::option::Option<i32>,
+    #[prost(string, optional, tag="3")]
+    pub target: ::std::option::Option<std::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AppMessage {
+    #[prost(uint64, optional, tag="1")]
+    pub id: ::std::option::Option<u64>,
+    #[prost(bytes, optional, tag="2")]
+    pub payload: ::std::option::Option<std::vec::Vec<u8>>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VideoLayerRequest {
+    #[prost(uint64, optional, tag="1")]
+    pub id: ::std::option::Option<u64>,
+    #[prost(uint32, optional, tag="2")]
+    pub layer: ::std::option::Option<u32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordingStatus {
+    #[prost(uint64, optional, tag="1")]
+    pub id: ::std::option::Option<u64>,
+    #[prost(bool, optional, tag="2")]
+    pub recording: ::std::option::Option<bool>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScreenShareStatus {
+    #[prost(uint64, optional, tag="1")]
+    pub id: ::std::option::Option<u64>,
+    #[prost(bool, optional, tag="2")]
+    pub sharing: ::std::option::Option<bool>,
+}
+pub mod transfer {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Type {
+        Request = 0,
+        Accepted = 1,
+        Declined = 2,
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Negotiation {
+    #[prost(uint64, optional, tag="1")]
+    pub id: ::std::option::Option<u64>,
+    #[prost(enumeration="negotiation::Type", optional, tag="2")]
+    pub r#type: ::std::option::Option<i32>,
+    #[prost(string, optional, tag="3")]
+    pub sdp: ::std::option::Option<std::string::String>,
+}
+pub mod negotiation {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Type {
+        Offer = 0,
+        Answer = 1,
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Data {
     #[prost(message, optional, tag="1")]
     pub connected: ::std::option::Option<Connected>,
@@ -30,4 +120,20 @@ pub struct Data {
     pub hangup: ::std::option::Option<Hangup>,
     #[prost(message, optional, tag="3")]
     pub video_streaming_status: ::std::option::Option<VideoStreamingStatus>,
+    #[prost(message, optional, tag="4")]
+    pub audio_status: ::std::option::Option<AudioStatus>,
+    #[prost(message, optional, tag="5")]
+    pub hold: ::std::option::Option<Hold>,
+    #[prost(message, optional, tag="6")]
+    pub transfer: ::std::option::Option<Transfer>,
+    #[prost(message, optional, tag="7")]
+    pub app_message: ::std::option::Option<AppMessage>,
+    #[prost(message, optional, tag="8")]
+    pub video_layer_request: ::std::option::Option<VideoLayerRequest>,
+    #[prost(message, optional, tag="9")]
+    pub recording_status: ::std::option::Option<RecordingStatus>,
+    #[prost(message, optional, tag="10")]
+    pub screen_share_status: ::std::option::Option<ScreenShareStatus>,
+    #[prost(message, optional, tag="11")]
+    pub negotiation: ::std::option::Option<Negotiation>,
 }
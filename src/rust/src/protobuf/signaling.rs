@@ -0,0 +1,81 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Opaque {
+    #[prost(uint32, optional, tag="1")]
+    pub version: ::std::option::Option<u32>,
+    #[prost(bytes, optional, tag="2")]
+    pub sdp: ::std::option::Option<std::vec::Vec<u8>>,
+    #[prost(uint64, optional, tag="3")]
+    pub sequence: ::std::option::Option<u64>,
+    #[prost(bytes, optional, tag="4")]
+    pub metadata: ::std::option::Option<std::vec::Vec<u8>>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IceCandidate {
+    #[prost(string, optional, tag="1")]
+    pub sdp_mid: ::std::option::Option<std::string::String>,
+    #[prost(int32, optional, tag="2")]
+    pub sdp_mline_index: ::std::option::Option<i32>,
+    /// The candidate attribute, only set if it does not fit the fields
+    /// below.
+    #[prost(string, optional, tag="3")]
+    pub sdp: ::std::option::Option<std::string::String>,
+    #[prost(string, optional, tag="4")]
+    pub foundation: ::std::option::Option<std::string::String>,
+    #[prost(uint32, optional, tag="5")]
+    pub component: ::std::option::Option<u32>,
+    #[prost(enumeration="ice_candidate::Protocol", optional, tag="6")]
+    pub protocol: ::std::option::Option<i32>,
+    #[prost(uint32, optional, tag="7")]
+    pub priority: ::std::option::Option<u32>,
+    /// The IPv4 or IPv6 address, in network byte order.
+    #[prost(bytes, optional, tag="8")]
+    pub ip: ::std::option::Option<std::vec::Vec<u8>>,
+    #[prost(uint32, optional, tag="9")]
+    pub port: ::std::option::Option<u32>,
+    #[prost(enumeration="ice_candidate::Type", optional, tag="10")]
+    pub r#type: ::std::option::Option<i32>,
+    #[prost(bytes, optional, tag="11")]
+    pub related_ip: ::std::option::Option<std::vec::Vec<u8>>,
+    #[prost(uint32, optional, tag="12")]
+    pub related_port: ::std::option::Option<u32>,
+    #[prost(string, optional, tag="13")]
+    pub tcp_type: ::std::option::Option<std::string::String>,
+    #[prost(uint32, optional, tag="14")]
+    pub generation: ::std::option::Option<u32>,
+    #[prost(string, optional, tag="15")]
+    pub ufrag: ::std::option::Option<std::string::String>,
+    #[prost(uint32, optional, tag="16")]
+    pub network_id: ::std::option::Option<u32>,
+    #[prost(uint32, optional, tag="17")]
+    pub network_cost: ::std::option::Option<u32>,
+}
+pub mod ice_candidate {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Protocol {
+        Udp = 0,
+        Tcp = 1,
+    }
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Type {
+        Host = 0,
+        Srflx = 1,
+        Prflx = 2,
+        Relay = 3,
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IceCandidates {
+    #[prost(message, repeated, tag="1")]
+    pub candidates: ::std::vec::Vec<IceCandidate>,
+    #[prost(uint64, optional, tag="2")]
+    pub sequence: ::std::option::Option<u64>,
+}
@@ -30,4 +30,6 @@ pub enum SimError {
     StartCallError,
     #[fail(display = "Simulation: Intentional: Call Concluded failed")]
     CallConcludedError,
+    #[fail(display = "Simulation: Intentional: Create PeerConnection failed")]
+    CreatePeerConnectionError,
 }
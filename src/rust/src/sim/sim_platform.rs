@@ -11,25 +11,42 @@ use std::collections::HashMap;
 use std::fmt;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use crate::common::{
     ApplicationEvent,
+    AudioDeviceError,
     CallDirection,
     CallId,
     ConnectionId,
     DeviceId,
+    HangupType,
     Result,
     DATA_CHANNEL_NAME,
 };
+use crate::core::audio_route::AudioRoute;
 use crate::core::call::Call;
+use crate::core::call_failure::CallFailureReason;
 use crate::core::call_manager::CallManager;
+use crate::core::call_record::CallRecord;
+use crate::core::call_stats::CallStats;
+use crate::core::codec::CodecCapability;
 use crate::core::connection::Connection;
+use crate::core::connection_config::ConnectionConfig;
+use crate::core::ice_encoding::decode_ice_candidates;
+use crate::core::ice_server::IceServerConfig;
+use crate::core::media_descriptor::MediaDescriptor;
+use crate::core::offer_metadata::OfferMetadata;
+use crate::core::pending_signaling::PendingSignaling;
 use crate::core::platform::{Platform, PlatformItem};
+use crate::core::signaling::{self, Answer, Offer};
+use crate::error::RingRtcError;
 use crate::sim::error::SimError;
 use crate::webrtc::data_channel_observer::DataChannelObserver;
-use crate::webrtc::ice_candidate::IceCandidate;
+use crate::webrtc::ice_candidate::{IceCandidate, IceRoute, NetworkAdapterType};
 use crate::webrtc::media_stream::MediaStream;
 use crate::webrtc::peer_connection::PeerConnection;
+use crate::webrtc::peer_connection_observer::IceGatheringState;
 
 /// Simulation implmentation for platform::Platform::{AppMediaStream,
 /// AppRemotePeer, AppCallContext}
@@ -40,14 +57,30 @@ impl PlatformItem for SimPlatformItem {}
 struct SimStats {
     /// Number of offers sent
     offers_sent:         AtomicUsize,
+    /// Number of the offers sent that restart ICE
+    restart_offers_sent: AtomicUsize,
+    /// Number of the offers sent as opaque bytes
+    opaque_offers_sent:  AtomicUsize,
     /// Number of answers sent
     answers_sent:        AtomicUsize,
+    /// Number of the answers sent as opaque bytes
+    opaque_answers_sent: AtomicUsize,
     /// Number of ICE candidates sent
     ice_candidates_sent: AtomicUsize,
+    /// Number of ICE candidate messages sent
+    ice_messages_sent:   AtomicUsize,
+    /// Number of the ICE candidate messages sent as opaque bytes
+    opaque_ice_sent:     AtomicUsize,
+    /// Number of the opaque messages sent sealed
+    sealed_sent:         AtomicUsize,
     /// Number of hang ups sent
     hangups_sent:        AtomicUsize,
     /// Number of busy messages sent
     busys_sent:          AtomicUsize,
+    /// Number of ringing acknowledgments sent
+    ringings_sent:       AtomicUsize,
+    pre_offers_sent:     AtomicUsize,
+    pre_offers_received: AtomicUsize,
     /// Number of start outgoing call events
     start_outgoing:      AtomicUsize,
     /// Number of start incoming call events
@@ -56,6 +89,26 @@ struct SimStats {
     call_concluded:      AtomicUsize,
     /// Track stream counts
     stream_count:        AtomicUsize,
+    /// Number of remote video orientation changes
+    orientations:        AtomicUsize,
+    /// Last remote video rotation, in degrees
+    rotation:            AtomicUsize,
+    /// Number of route downgrades to relay
+    route_downgrades:    AtomicUsize,
+    /// Number of route upgrades to direct
+    route_upgrades:      AtomicUsize,
+    /// Number of data channel buffered amount low events
+    buffered_amount_low: AtomicUsize,
+    /// Number of call duration ticks
+    duration_ticks:      AtomicUsize,
+    /// Number of shutdown complete events
+    shutdown_complete:   AtomicUsize,
+    /// Number of requests for fresh ICE servers
+    turn_requests:       AtomicUsize,
+    /// Number of camera switches
+    camera_switches:     AtomicUsize,
+    /// Number of calls added to the system
+    system_calls:        AtomicUsize,
 }
 
 /// Simulation implementation of platform::Platform.
@@ -68,8 +121,87 @@ pub struct SimPlatform {
     /// True if the signaling functions should indicate a signaling
     /// failure to the call manager.
     force_signaling_fault: Arc<AtomicBool>,
+    /// True if the signaling functions should never report a message
+    /// as sent, as when the transport is offline.
+    signaling_offline:     Arc<AtomicBool>,
+    /// If set, opaque messages are sealed by XORing them with this
+    /// key, followed by the key as the authentication tag.
+    sealing_key:           Arc<Mutex<Option<u8>>>,
+    /// If set, creating a connection reports this audio device
+    /// failure to the call manager.
+    force_audio_fault:     Arc<Mutex<Option<AudioDeviceError>>>,
+    /// True if creating a PeerConnection should fail.
+    force_pc_fault:        Arc<AtomicBool>,
+    /// True if incoming calls should be refused before they ring.
+    force_ring_refusal:    Arc<AtomicBool>,
+    /// True if transfers requested by the remote peer should be
+    /// accepted.
+    force_transfer_accept: Arc<AtomicBool>,
+    /// ICE servers handed out when fresh ones are requested
+    turn_servers:          Arc<Mutex<Vec<IceServerConfig>>>,
+    /// Hardware codecs reported to the call manager
+    codec_capabilities:    Arc<Mutex<Vec<CodecCapability>>>,
+    /// If set, this attribute line is appended to the local
+    /// descriptions and stripped from the remote ones.
+    sdp_attribute:         Arc<Mutex<Option<String>>>,
+    /// Descriptions as transformed, local and remote, in order
+    transformed_sdp:       Arc<Mutex<Vec<String>>>,
+    /// Metadata attached to the received answers, in order
+    answer_metadata:       Arc<Mutex<Vec<Vec<u8>>>>,
+    /// Metadata attached to the sent answers, if any, in order
+    sent_answer_metadata:  Arc<Mutex<Vec<Option<Vec<u8>>>>>,
+    /// Application messages received, in order
+    app_messages:          Arc<Mutex<Vec<Vec<u8>>>>,
     /// Track event frequencies
     event_map:             Arc<Mutex<HashMap<ApplicationEvent, usize>>>,
+    /// Events reported, as (CallId, event), in order
+    call_events:           Arc<Mutex<Vec<(CallId, ApplicationEvent)>>>,
+    /// ICE gathering states reported, in order
+    gathering_states:      Arc<Mutex<Vec<IceGatheringState>>>,
+    /// Network routes reported, as (local adapter type, relayed), in
+    /// order
+    network_routes:        Arc<Mutex<Vec<(NetworkAdapterType, bool)>>>,
+    /// Configurations of the PeerConnections created, in order
+    connection_configs:    Arc<Mutex<Vec<ConnectionConfig>>>,
+    /// Descriptors of the offers and answers sent, in order
+    media_descriptors:     Arc<Mutex<Vec<MediaDescriptor>>>,
+    /// Types of the hangups sent, in order
+    hangup_types:          Arc<Mutex<Vec<HangupType>>>,
+    /// Remote devices addressed by the hangups sent to several
+    /// devices at once, in order
+    hangup_devices:        Arc<Mutex<Vec<Vec<DeviceId>>>>,
+    /// Metadata of the incoming calls asked to ring, in order
+    offer_metadata:        Arc<Mutex<Vec<OfferMetadata>>>,
+    /// Stats reports of the active call, in order
+    stats_reports:         Arc<Mutex<Vec<CallStats>>>,
+    /// Audio levels of the active call, as (captured, received), in
+    /// order
+    audio_levels:          Arc<Mutex<Vec<(f64, f64)>>>,
+    /// Bandwidth estimates of the active call, as (send, receive), in
+    /// order
+    bandwidth_estimates:   Arc<Mutex<Vec<(u64, u64)>>>,
+    /// Records of the concluded calls, in order
+    call_records:          Arc<Mutex<Vec<CallRecord>>>,
+    /// Reasons of the failed calls, in order
+    call_failures:         Arc<Mutex<Vec<CallFailureReason>>>,
+    /// DTMF tones received, in order
+    received_dtmf:         Arc<Mutex<String>>,
+    /// Safety numbers reported, in order
+    safety_numbers:        Arc<Mutex<Vec<String>>>,
+    /// Targets of the transfers requested by the remote peer, in
+    /// order
+    transfer_requests:     Arc<Mutex<Vec<String>>>,
+    /// Group call participant media connected, as (remote peer,
+    /// media stream), in order
+    participant_media:     Arc<Mutex<Vec<(SimPlatformItem, SimPlatformItem)>>>,
+    /// Signaling messages last persisted
+    pending_signaling:     Arc<Mutex<Vec<PendingSignaling<SimPlatformItem>>>>,
+    /// Audio routes suggested, in order
+    audio_routes:          Arc<Mutex<Vec<AudioRoute>>>,
+    /// Camera states set, in order
+    camera_states:         Arc<Mutex<Vec<bool>>>,
+    /// System holds requested, in order
+    system_holds:          Arc<Mutex<Vec<bool>>>,
     /// Track whether close media happened
     close_media:           Arc<AtomicBool>,
     /// Call Manager
@@ -104,33 +236,37 @@ impl Platform for SimPlatform {
         &mut self,
         call: &Call<Self>,
         remote_device: DeviceId,
+        config: ConnectionConfig,
     ) -> Result<Connection<Self>> {
         let connection_id = ConnectionId::new(call.call_id(), remote_device);
 
-        info!("create_connection(): {}", connection_id);
+        info!("create_connection(): {}, {}", connection_id, config);
 
         let connection = Connection::new(call.clone(), remote_device).unwrap();
-        connection
-            .set_app_connection("Simulation".to_owned())
-            .unwrap();
+        self.create_peer_connection(&connection, config)?;
 
-        let fake_pc_interface: u32 = 1;
-        let pc_interface = PeerConnection::new(&fake_pc_interface);
-
-        if let CallDirection::OutGoing = connection.direction() {
-            // Create data channel observer and data channel
-            let dc_observer = DataChannelObserver::new(connection.clone())?;
-            let data_channel = pc_interface.create_data_channel(DATA_CHANNEL_NAME.to_string())?;
-            unsafe { data_channel.register_observer(dc_observer.rffi_interface())? };
-            connection.set_data_channel(data_channel)?;
-            connection.set_data_channel_observer(dc_observer)?;
+        if let Some(error) = *self.force_audio_fault.lock().unwrap() {
+            self.audio_device_failure(error)?;
         }
 
-        connection.set_pc_interface(pc_interface).unwrap();
-
         Ok(connection)
     }
 
+    fn recreate_peer_connection(
+        &mut self,
+        _call: &Call<Self>,
+        connection: &Connection<Self>,
+        config: ConnectionConfig,
+    ) -> Result<()> {
+        info!(
+            "recreate_peer_connection(): {}, {}",
+            connection.id(),
+            config
+        );
+
+        self.create_peer_connection(connection, config)
+    }
+
     fn on_start_call(
         &self,
         remote_peer: &Self::AppRemotePeer,
@@ -153,27 +289,373 @@ impl Platform for SimPlatform {
         }
     }
 
-    fn on_event(&self, remote_peer: &Self::AppRemotePeer, event: ApplicationEvent) -> Result<()> {
-        info!("on_event(): {}, remote_peer: {}", event, remote_peer);
+    fn on_event(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        event: ApplicationEvent,
+    ) -> Result<()> {
+        info!(
+            "on_event(): {}, remote_peer: {}, call_id: {}",
+            event, remote_peer, call_id
+        );
 
         let mut map = self.event_map.lock().unwrap();
         map.entry(event).and_modify(|e| *e += 1).or_insert(1);
+        self.call_events.lock().unwrap().push((call_id, event));
+
+        Ok(())
+    }
+
+    fn on_remote_video_orientation(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        degrees: u16,
+    ) -> Result<()> {
+        info!(
+            "on_remote_video_orientation(): remote_peer: {}, id: {}, degrees: {}",
+            remote_peer, connection_id, degrees
+        );
+
+        self.stats
+            .rotation
+            .store(usize::from(degrees), Ordering::Release);
+        let _ = self.stats.orientations.fetch_add(1, Ordering::AcqRel);
+
+        Ok(())
+    }
+
+    fn on_received_dtmf(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        tone: char,
+    ) -> Result<()> {
+        info!(
+            "on_received_dtmf(): remote_peer: {}, id: {}, tone: {}",
+            remote_peer, connection_id, tone
+        );
+
+        self.received_dtmf.lock().unwrap().push(tone);
+
+        Ok(())
+    }
+
+    fn on_app_message_received(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        payload: &[u8],
+    ) -> Result<()> {
+        info!(
+            "on_app_message_received(): remote_peer: {}, id: {}, size: {}",
+            remote_peer,
+            connection_id,
+            payload.len()
+        );
+
+        self.app_messages.lock().unwrap().push(payload.to_vec());
+
+        Ok(())
+    }
+
+    fn on_safety_numbers(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        safety_numbers: &str,
+    ) -> Result<()> {
+        info!(
+            "on_safety_numbers(): remote_peer: {}, id: {}",
+            remote_peer, connection_id
+        );
+
+        self.safety_numbers
+            .lock()
+            .unwrap()
+            .push(safety_numbers.to_owned());
+
+        Ok(())
+    }
+
+    fn on_route_downgraded_to_relay(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        route: &IceRoute,
+    ) -> Result<()> {
+        info!(
+            "on_route_downgraded_to_relay(): remote_peer: {}, id: {}, route: {}",
+            remote_peer, connection_id, route
+        );
+
+        let _ = self.stats.route_downgrades.fetch_add(1, Ordering::AcqRel);
+
+        Ok(())
+    }
+
+    fn on_route_upgraded_to_direct(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        route: &IceRoute,
+    ) -> Result<()> {
+        info!(
+            "on_route_upgraded_to_direct(): remote_peer: {}, id: {}, route: {}",
+            remote_peer, connection_id, route
+        );
+
+        let _ = self.stats.route_upgrades.fetch_add(1, Ordering::AcqRel);
+
+        Ok(())
+    }
+
+    fn on_data_channel_buffered_amount_low(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!(
+            "on_data_channel_buffered_amount_low(): remote_peer: {}, id: {}",
+            remote_peer, connection_id
+        );
+
+        let _ = self
+            .stats
+            .buffered_amount_low
+            .fetch_add(1, Ordering::AcqRel);
+
+        Ok(())
+    }
+
+    fn on_call_duration_tick(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        duration: Duration,
+    ) -> Result<()> {
+        info!(
+            "on_call_duration_tick(): remote_peer: {}, call_id: {}, duration: {:?}",
+            remote_peer, call_id, duration
+        );
+
+        let _ = self.stats.duration_ticks.fetch_add(1, Ordering::AcqRel);
+
+        Ok(())
+    }
+
+    fn on_stats_report(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        stats: &CallStats,
+    ) -> Result<()> {
+        info!(
+            "on_stats_report(): remote_peer: {}, call_id: {}, stats: {}",
+            remote_peer, call_id, stats
+        );
+
+        self.stats_reports.lock().unwrap().push(stats.clone());
+
+        Ok(())
+    }
+
+    fn on_audio_levels(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        captured: f64,
+        received: f64,
+    ) -> Result<()> {
+        info!(
+            "on_audio_levels(): remote_peer: {}, call_id: {}, captured: {}, received: {}",
+            remote_peer, call_id, captured, received
+        );
+
+        self.audio_levels.lock().unwrap().push((captured, received));
+
+        Ok(())
+    }
+
+    fn on_bandwidth_estimate(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        send_bps: u64,
+        receive_bps: u64,
+    ) -> Result<()> {
+        info!(
+            "on_bandwidth_estimate(): remote_peer: {}, call_id: {}, send_bps: {}, receive_bps: {}",
+            remote_peer, call_id, send_bps, receive_bps
+        );
+
+        self.bandwidth_estimates
+            .lock()
+            .unwrap()
+            .push((send_bps, receive_bps));
+
+        Ok(())
+    }
+
+    fn on_ice_gathering_state_changed(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        state: IceGatheringState,
+    ) -> Result<()> {
+        info!(
+            "on_ice_gathering_state_changed(): remote_peer: {}, id: {}, state: {}",
+            remote_peer, connection_id, state
+        );
+
+        self.gathering_states.lock().unwrap().push(state);
+
+        Ok(())
+    }
+
+    fn on_network_route_changed(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        local_adapter_type: NetworkAdapterType,
+        relayed: bool,
+    ) -> Result<()> {
+        info!(
+            "on_network_route_changed(): remote_peer: {}, call_id: {}, adapter: {}, relayed: {}",
+            remote_peer, call_id, local_adapter_type, relayed
+        );
+
+        self.network_routes
+            .lock()
+            .unwrap()
+            .push((local_adapter_type, relayed));
 
         Ok(())
     }
 
+    fn should_ring(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        offer_metadata: &OfferMetadata,
+    ) -> Result<bool> {
+        info!(
+            "should_ring(): remote_peer: {}, {}",
+            remote_peer, offer_metadata
+        );
+
+        self.offer_metadata
+            .lock()
+            .unwrap()
+            .push(offer_metadata.clone());
+
+        Ok(!self.force_ring_refusal.load(Ordering::Acquire))
+    }
+
+    fn should_transfer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        target: &str,
+    ) -> Result<bool> {
+        info!(
+            "should_transfer(): remote_peer: {}, call_id: {}, target: {}",
+            remote_peer, call_id, target
+        );
+
+        self.transfer_requests
+            .lock()
+            .unwrap()
+            .push(target.to_string());
+
+        Ok(self.force_transfer_accept.load(Ordering::Acquire))
+    }
+
+    fn get_turn_servers(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+    ) -> Result<Vec<IceServerConfig>> {
+        info!(
+            "get_turn_servers(): remote_peer: {}, call_id: {}",
+            remote_peer, call_id
+        );
+
+        let _ = self.stats.turn_requests.fetch_add(1, Ordering::AcqRel);
+        Ok(self.turn_servers.lock().unwrap().clone())
+    }
+
+    fn get_codec_capabilities(&self) -> Result<Vec<CodecCapability>> {
+        info!("get_codec_capabilities():");
+
+        Ok(self.codec_capabilities.lock().unwrap().clone())
+    }
+
+    fn transform_local_description(
+        &self,
+        connection_id: ConnectionId,
+        sdp: String,
+    ) -> Result<String> {
+        info!("transform_local_description(): id: {}", connection_id);
+
+        let sdp = match &*self.sdp_attribute.lock().unwrap() {
+            Some(attribute) => format!("{}\r\n{}", sdp, attribute),
+            None => sdp,
+        };
+        self.transformed_sdp.lock().unwrap().push(sdp.clone());
+        Ok(sdp)
+    }
+
+    fn transform_remote_description(
+        &self,
+        connection_id: ConnectionId,
+        sdp: String,
+    ) -> Result<String> {
+        info!("transform_remote_description(): id: {}", connection_id);
+
+        let sdp = match &*self.sdp_attribute.lock().unwrap() {
+            Some(attribute) => sdp.replace(&format!("\r\n{}", attribute), ""),
+            None => sdp,
+        };
+        self.transformed_sdp.lock().unwrap().push(sdp.clone());
+        Ok(sdp)
+    }
+
+    fn on_received_answer_metadata(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        metadata: &[u8],
+    ) -> Result<()> {
+        info!(
+            "on_received_answer_metadata(): remote_peer: {}, id: {}, size: {}",
+            remote_peer,
+            connection_id,
+            metadata.len()
+        );
+
+        self.answer_metadata.lock().unwrap().push(metadata.to_vec());
+        Ok(())
+    }
+
     fn on_send_offer(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
         broadcast: bool,
         description: &str,
+        descriptor: &MediaDescriptor,
     ) -> Result<()> {
         info!(
-            "on_send_offer(): remote_peer: {}, id: {}, broadcast: {}, offer: {}",
-            remote_peer, connection_id, broadcast, description
+            "on_send_offer(): remote_peer: {}, id: {}, broadcast: {}, offer: {}, descriptor: {}",
+            remote_peer, connection_id, broadcast, description, descriptor
         );
 
+        self.media_descriptors
+            .lock()
+            .unwrap()
+            .push(descriptor.clone());
+
         if self.force_internal_fault.load(Ordering::Acquire) {
             Err(SimError::SendOfferError.into())
         } else {
@@ -187,18 +669,80 @@ impl Platform for SimPlatform {
         }
     }
 
+    fn on_send_restart_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        description: &str,
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        info!("on_send_restart_offer(): id: {}", connection_id);
+
+        let _ = self
+            .stats
+            .restart_offers_sent
+            .fetch_add(1, Ordering::AcqRel);
+        self.on_send_offer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            description,
+            descriptor,
+        )
+    }
+
+    fn on_send_opaque_offer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        info!(
+            "on_send_opaque_offer(): id: {}, length: {}",
+            connection_id,
+            opaque.len()
+        );
+
+        if signaling::is_sealed(opaque) {
+            let _ = self.stats.sealed_sent.fetch_add(1, Ordering::AcqRel);
+        }
+        let opaque = &signaling::open(self, connection_id, opaque)?;
+        let _ = self.stats.opaque_offers_sent.fetch_add(1, Ordering::AcqRel);
+        self.on_send_offer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            &Offer::from_opaque(opaque)?.to_wire(),
+            descriptor,
+        )
+    }
+
     fn on_send_answer(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
         broadcast: bool,
         description: &str,
+        descriptor: &MediaDescriptor,
     ) -> Result<()> {
         info!(
-            "on_send_answer(): remote_peer: {}, id: {}, broadcast: {}, answer: {}",
-            remote_peer, connection_id, broadcast, description
+            "on_send_answer(): remote_peer: {}, id: {}, broadcast: {}, answer: {}, descriptor: {}",
+            remote_peer, connection_id, broadcast, description, descriptor
         );
 
+        self.media_descriptors
+            .lock()
+            .unwrap()
+            .push(descriptor.clone());
+        let answer = Answer::from_wire(&signaling::decompress(description.to_owned())?);
+        self.sent_answer_metadata
+            .lock()
+            .unwrap()
+            .push(answer.metadata);
+
         if self.force_internal_fault.load(Ordering::Acquire) {
             Err(SimError::SendAnswerError.into())
         } else {
@@ -212,6 +756,37 @@ impl Platform for SimPlatform {
         }
     }
 
+    fn on_send_opaque_answer(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+        descriptor: &MediaDescriptor,
+    ) -> Result<()> {
+        info!(
+            "on_send_opaque_answer(): id: {}, length: {}",
+            connection_id,
+            opaque.len()
+        );
+
+        if signaling::is_sealed(opaque) {
+            let _ = self.stats.sealed_sent.fetch_add(1, Ordering::AcqRel);
+        }
+        let opaque = &signaling::open(self, connection_id, opaque)?;
+        let _ = self
+            .stats
+            .opaque_answers_sent
+            .fetch_add(1, Ordering::AcqRel);
+        self.on_send_answer(
+            remote_peer,
+            connection_id,
+            broadcast,
+            &Answer::from_opaque(opaque)?.to_wire(),
+            descriptor,
+        )
+    }
+
     fn on_send_ice_candidates(
         &self,
         remote_peer: &Self::AppRemotePeer,
@@ -220,17 +795,139 @@ impl Platform for SimPlatform {
         ice_candidates: &[IceCandidate],
     ) -> Result<()> {
         info!(
-            "on_send_ice_candidates(): remote_peer: {}, id: {}, broadcast: {}",
+            "on_send_ice_candidates(): remote_peer: {}, id: {}, broadcast: {}",
+            remote_peer, connection_id, broadcast
+        );
+
+        if self.force_internal_fault.load(Ordering::Acquire) {
+            Err(SimError::SendIceCandidateError.into())
+        } else {
+            let _ = self
+                .stats
+                .ice_candidates_sent
+                .fetch_add(ice_candidates.len(), Ordering::AcqRel);
+            let _ = self.stats.ice_messages_sent.fetch_add(1, Ordering::AcqRel);
+            if self.force_internal_fault.load(Ordering::Acquire) {
+                self.message_send_failure(connection_id.call_id()).unwrap();
+            } else {
+                self.message_sent(connection_id.call_id()).unwrap();
+            }
+            Ok(())
+        }
+    }
+
+    fn on_send_opaque_ice_candidates(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        opaque: &[u8],
+    ) -> Result<()> {
+        info!(
+            "on_send_opaque_ice_candidates(): id: {}, length: {}",
+            connection_id,
+            opaque.len()
+        );
+
+        if signaling::is_sealed(opaque) {
+            let _ = self.stats.sealed_sent.fetch_add(1, Ordering::AcqRel);
+        }
+        let opaque = &signaling::open(self, connection_id, opaque)?;
+        let _ = self.stats.opaque_ice_sent.fetch_add(1, Ordering::AcqRel);
+        self.on_send_ice_candidates(
+            remote_peer,
+            connection_id,
+            broadcast,
+            &decode_ice_candidates(opaque)?.1,
+        )
+    }
+
+    fn seal(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _connection_id: ConnectionId,
+        opaque: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        Ok((*self.sealing_key.lock().unwrap()).map(|key| {
+            let mut sealed: Vec<u8> = opaque.iter().map(|byte| byte ^ key).collect();
+            sealed.push(key);
+            sealed
+        }))
+    }
+
+    fn open(&self, connection_id: ConnectionId, sealed: &[u8]) -> Result<Vec<u8>> {
+        info!("open(): id: {}, length: {}", connection_id, sealed.len());
+
+        let key = *self.sealing_key.lock().unwrap();
+        match (key, sealed.split_last()) {
+            (Some(key), Some((tag, opaque))) if *tag == key => {
+                Ok(opaque.iter().map(|byte| byte ^ key).collect())
+            }
+            _ => Err(RingRtcError::OpenSealedMessage("bad tag".to_owned()).into()),
+        }
+    }
+
+    fn on_send_hangup(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        info!(
+            "on_send_hangup(): remote_peer: {}, id: {}, broadcast: {}, type: {}",
+            remote_peer, connection_id, broadcast, hangup_type
+        );
+
+        self.hangup_types.lock().unwrap().push(hangup_type);
+
+        if self.force_internal_fault.load(Ordering::Acquire) {
+            Err(SimError::SendHangupError.into())
+        } else {
+            let _ = self.stats.hangups_sent.fetch_add(1, Ordering::AcqRel);
+            if self.force_internal_fault.load(Ordering::Acquire) {
+                self.message_send_failure(connection_id.call_id()).unwrap();
+            } else {
+                self.message_sent(connection_id.call_id()).unwrap();
+            }
+            Ok(())
+        }
+    }
+
+    fn on_send_hangup_to_devices(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        remote_devices: &[DeviceId],
+        hangup_type: HangupType,
+    ) -> Result<()> {
+        info!(
+            "on_send_hangup_to_devices(): id: {}, devices: {:?}",
+            connection_id, remote_devices
+        );
+
+        self.hangup_devices
+            .lock()
+            .unwrap()
+            .push(remote_devices.to_vec());
+        self.on_send_hangup(remote_peer, connection_id, true, hangup_type)
+    }
+
+    fn on_send_busy(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+        broadcast: bool,
+    ) -> Result<()> {
+        info!(
+            "on_send_busy(): remote_peer: {}, id: {}, broadcast: {}",
             remote_peer, connection_id, broadcast
         );
 
         if self.force_internal_fault.load(Ordering::Acquire) {
-            Err(SimError::SendIceCandidateError.into())
+            Err(SimError::SendBusyError.into())
         } else {
-            let _ = self
-                .stats
-                .ice_candidates_sent
-                .fetch_add(ice_candidates.len(), Ordering::AcqRel);
+            let _ = self.stats.busys_sent.fetch_add(1, Ordering::AcqRel);
             if self.force_internal_fault.load(Ordering::Acquire) {
                 self.message_send_failure(connection_id.call_id()).unwrap();
             } else {
@@ -240,52 +937,59 @@ impl Platform for SimPlatform {
         }
     }
 
-    fn on_send_hangup(
+    fn on_send_pre_offer(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
-        broadcast: bool,
     ) -> Result<()> {
         info!(
-            "on_send_hangup(): remote_peer: {}, id: {}, broadcast: {}",
-            remote_peer, connection_id, broadcast
+            "on_send_pre_offer(): remote_peer: {}, id: {}",
+            remote_peer, connection_id
         );
 
+        let _ = self.stats.pre_offers_sent.fetch_add(1, Ordering::AcqRel);
         if self.force_internal_fault.load(Ordering::Acquire) {
-            Err(SimError::SendHangupError.into())
+            self.message_send_failure(connection_id.call_id()).unwrap();
         } else {
-            let _ = self.stats.hangups_sent.fetch_add(1, Ordering::AcqRel);
-            if self.force_internal_fault.load(Ordering::Acquire) {
-                self.message_send_failure(connection_id.call_id()).unwrap();
-            } else {
-                self.message_sent(connection_id.call_id()).unwrap();
-            }
-            Ok(())
+            self.message_sent(connection_id.call_id()).unwrap();
         }
+        Ok(())
     }
 
-    fn on_send_busy(
+    fn on_received_pre_offer(
         &self,
         remote_peer: &Self::AppRemotePeer,
         connection_id: ConnectionId,
-        broadcast: bool,
     ) -> Result<()> {
         info!(
-            "on_send_busy(): remote_peer: {}, id: {}, broadcast: {}",
-            remote_peer, connection_id, broadcast
+            "on_received_pre_offer(): remote_peer: {}, id: {}",
+            remote_peer, connection_id
+        );
+
+        let _ = self
+            .stats
+            .pre_offers_received
+            .fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
+    fn on_send_ringing(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        connection_id: ConnectionId,
+    ) -> Result<()> {
+        info!(
+            "on_send_ringing(): remote_peer: {}, id: {}",
+            remote_peer, connection_id
         );
 
+        let _ = self.stats.ringings_sent.fetch_add(1, Ordering::AcqRel);
         if self.force_internal_fault.load(Ordering::Acquire) {
-            Err(SimError::SendBusyError.into())
+            self.message_send_failure(connection_id.call_id()).unwrap();
         } else {
-            let _ = self.stats.busys_sent.fetch_add(1, Ordering::AcqRel);
-            if self.force_internal_fault.load(Ordering::Acquire) {
-                self.message_send_failure(connection_id.call_id()).unwrap();
-            } else {
-                self.message_sent(connection_id.call_id()).unwrap();
-            }
-            Ok(())
+            self.message_sent(connection_id.call_id()).unwrap();
         }
+        Ok(())
     }
 
     fn create_media_stream(
@@ -315,6 +1019,84 @@ impl Platform for SimPlatform {
         }
     }
 
+    fn on_connect_participant_media(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        media_stream: &Self::AppMediaStream,
+    ) -> Result<()> {
+        info!(
+            "on_connect_participant_media(): remote_peer: {}, call_id: {}, media_stream: {}",
+            remote_peer, call_id, media_stream
+        );
+
+        self.participant_media
+            .lock()
+            .unwrap()
+            .push((remote_peer.clone(), media_stream.clone()));
+
+        Ok(())
+    }
+
+    fn on_audio_route_suggested(
+        &self,
+        app_call_context: &Self::AppCallContext,
+        route: AudioRoute,
+    ) -> Result<()> {
+        info!(
+            "on_audio_route_suggested(): call_context: {}, route: {}",
+            app_call_context, route
+        );
+
+        self.audio_routes.lock().unwrap().push(route);
+        Ok(())
+    }
+
+    fn on_call_added_to_system(&self, app_call_context: &Self::AppCallContext) -> Result<()> {
+        info!(
+            "on_call_added_to_system(): call_context: {}",
+            app_call_context
+        );
+
+        let _ = self.stats.system_calls.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
+    fn on_request_system_hold(
+        &self,
+        app_call_context: &Self::AppCallContext,
+        on_hold: bool,
+    ) -> Result<()> {
+        info!(
+            "on_request_system_hold(): call_context: {}, on_hold: {}",
+            app_call_context, on_hold
+        );
+
+        self.system_holds.lock().unwrap().push(on_hold);
+        Ok(())
+    }
+
+    fn on_set_camera_enabled(
+        &self,
+        app_call_context: &Self::AppCallContext,
+        enabled: bool,
+    ) -> Result<()> {
+        info!(
+            "on_set_camera_enabled(): call_context: {}, enabled: {}",
+            app_call_context, enabled
+        );
+
+        self.camera_states.lock().unwrap().push(enabled);
+        Ok(())
+    }
+
+    fn on_switch_camera(&self, app_call_context: &Self::AppCallContext) -> Result<()> {
+        info!("on_switch_camera(): call_context: {}", app_call_context);
+
+        let _ = self.stats.camera_switches.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
     fn on_close_media(&self, app_call_context: &Self::AppCallContext) -> Result<()> {
         info!("on_close_media(): call_context: {}", app_call_context);
 
@@ -339,8 +1121,32 @@ impl Platform for SimPlatform {
         Ok(remote_peer1 == remote_peer2)
     }
 
-    fn on_call_concluded(&self, _remote_peer: &Self::AppRemotePeer) -> Result<()> {
-        info!("on_call_concluded():");
+    fn on_call_record(&self, remote_peer: &Self::AppRemotePeer, record: &CallRecord) -> Result<()> {
+        info!(
+            "on_call_record(): remote_peer: {}, record: {}",
+            remote_peer, record
+        );
+
+        self.call_records.lock().unwrap().push(record.clone());
+
+        Ok(())
+    }
+
+    fn on_call_failed(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        reason: CallFailureReason,
+    ) -> Result<()> {
+        info!("on_call_failed(): call_id: {}, reason: {}", call_id, reason);
+
+        self.call_failures.lock().unwrap().push(reason);
+
+        Ok(())
+    }
+
+    fn on_call_concluded(&self, _remote_peer: &Self::AppRemotePeer, call_id: CallId) -> Result<()> {
+        info!("on_call_concluded(): call_id: {}", call_id);
         if self.force_internal_fault.load(Ordering::Acquire) {
             Err(SimError::CallConcludedError.into())
         } else {
@@ -348,6 +1154,26 @@ impl Platform for SimPlatform {
             Ok(())
         }
     }
+
+    fn on_shutdown_complete(&self) -> Result<()> {
+        info!("on_shutdown_complete():");
+        let _ = self.stats.shutdown_complete.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
+    fn persist_pending_signaling(
+        &self,
+        pending: &[PendingSignaling<Self::AppRemotePeer>],
+    ) -> Result<()> {
+        info!("persist_pending_signaling(): len: {}", pending.len());
+        *self.pending_signaling.lock().unwrap() = pending.to_vec();
+        Ok(())
+    }
+
+    fn restore_pending_signaling(&self) -> Result<Vec<PendingSignaling<Self::AppRemotePeer>>> {
+        info!("restore_pending_signaling():");
+        Ok(self.pending_signaling.lock().unwrap().clone())
+    }
 }
 
 impl SimPlatform {
@@ -367,12 +1193,54 @@ impl SimPlatform {
         *cm = Some(call_manager);
     }
 
+    fn create_peer_connection(
+        &self,
+        connection: &Connection<Self>,
+        config: ConnectionConfig,
+    ) -> Result<()> {
+        if self.force_pc_fault.load(Ordering::Acquire) {
+            return Err(SimError::CreatePeerConnectionError.into());
+        }
+
+        connection
+            .set_app_connection("Simulation".to_owned())
+            .unwrap();
+
+        self.connection_configs.lock().unwrap().push(config);
+
+        let fake_pc_interface: u32 = 1;
+        let pc_interface = PeerConnection::new(&fake_pc_interface);
+
+        if let CallDirection::OutGoing = connection.direction() {
+            // Create data channel observer and data channel
+            let dc_observer = DataChannelObserver::new(connection.clone())?;
+            let data_channel = pc_interface.create_data_channel(DATA_CHANNEL_NAME.to_string())?;
+            unsafe { data_channel.register_observer(dc_observer.rffi_interface())? };
+            connection.set_data_channel(data_channel)?;
+            connection.set_data_channel_observer(dc_observer)?;
+        }
+
+        connection.set_pc_interface(pc_interface).unwrap();
+
+        Ok(())
+    }
+
     fn message_sent(&self, call_id: CallId) -> Result<()> {
+        if self.signaling_offline.load(Ordering::Acquire) {
+            info!("message_sent(): offline, call_id: {}", call_id);
+            return Ok(());
+        }
         let mut cm = self.call_manager.lock().unwrap();
         cm.as_mut().unwrap().message_sent(call_id).unwrap();
         Ok(())
     }
 
+    fn audio_device_failure(&self, error: AudioDeviceError) -> Result<()> {
+        let mut cm = self.call_manager.lock().unwrap();
+        cm.as_mut().unwrap().audio_device_failure(error).unwrap();
+        Ok(())
+    }
+
     fn message_send_failure(&self, call_id: CallId) -> Result<()> {
         let mut cm = self.call_manager.lock().unwrap();
         cm.as_mut().unwrap().message_send_failure(call_id).unwrap();
@@ -387,6 +1255,42 @@ impl SimPlatform {
         self.force_signaling_fault.store(enable, Ordering::Release);
     }
 
+    pub fn force_signaling_offline(&mut self, enable: bool) {
+        self.signaling_offline.store(enable, Ordering::Release);
+    }
+
+    pub fn set_sealing_key(&mut self, key: Option<u8>) {
+        *self.sealing_key.lock().unwrap() = key;
+    }
+
+    pub fn force_audio_device_fault(&mut self, error: Option<AudioDeviceError>) {
+        *self.force_audio_fault.lock().unwrap() = error;
+    }
+
+    pub fn force_peer_connection_fault(&mut self, enable: bool) {
+        self.force_pc_fault.store(enable, Ordering::Release);
+    }
+
+    pub fn force_ring_refusal(&mut self, enable: bool) {
+        self.force_ring_refusal.store(enable, Ordering::Release);
+    }
+
+    pub fn force_transfer_accept(&mut self, enable: bool) {
+        self.force_transfer_accept.store(enable, Ordering::Release);
+    }
+
+    pub fn set_turn_servers(&mut self, ice_servers: Vec<IceServerConfig>) {
+        *self.turn_servers.lock().unwrap() = ice_servers;
+    }
+
+    pub fn set_codec_capabilities(&mut self, capabilities: Vec<CodecCapability>) {
+        *self.codec_capabilities.lock().unwrap() = capabilities;
+    }
+
+    pub fn set_sdp_attribute(&mut self, attribute: Option<String>) {
+        *self.sdp_attribute.lock().unwrap() = attribute;
+    }
+
     pub fn event_count(&self, event: ApplicationEvent) -> usize {
         let mut errors = 0;
         let map = self.event_map.lock().unwrap();
@@ -398,6 +1302,10 @@ impl SimPlatform {
         errors
     }
 
+    pub fn call_events(&self) -> Vec<(CallId, ApplicationEvent)> {
+        self.call_events.lock().unwrap().clone()
+    }
+
     pub fn error_count(&self) -> usize {
         self.event_count(ApplicationEvent::EndedInternalFailure)
     }
@@ -414,10 +1322,20 @@ impl SimPlatform {
             ApplicationEvent::EndedLocalHangup,
             ApplicationEvent::EndedRemoteHangup,
             ApplicationEvent::EndedRemoteBusy,
+            ApplicationEvent::EndedRemoteBusyRetry,
             ApplicationEvent::EndedTimeout,
             ApplicationEvent::EndedInternalFailure,
             ApplicationEvent::EndedConnectionFailure,
             ApplicationEvent::EndedAppDroppedCall,
+            ApplicationEvent::EndedAudioDeviceFailure,
+            ApplicationEvent::EndedRemoteDeclined,
+            ApplicationEvent::EndedRemoteHangupAccepted,
+            ApplicationEvent::EndedRemoteHangupBusy,
+            ApplicationEvent::EndedRemoteHangupNeedPermission,
+            ApplicationEvent::EndedRingRefused,
+            ApplicationEvent::EndedSetupCancelled,
+            ApplicationEvent::EndedTransferred,
+            ApplicationEvent::EndedRemoteMediaClosed,
         ];
         for event in ended_events {
             ends += self.event_count(event);
@@ -430,14 +1348,38 @@ impl SimPlatform {
         self.stats.offers_sent.load(Ordering::Acquire)
     }
 
+    pub fn restart_offers_sent(&self) -> usize {
+        self.stats.restart_offers_sent.load(Ordering::Acquire)
+    }
+
     pub fn answers_sent(&self) -> usize {
         self.stats.answers_sent.load(Ordering::Acquire)
     }
 
+    pub fn opaque_offers_sent(&self) -> usize {
+        self.stats.opaque_offers_sent.load(Ordering::Acquire)
+    }
+
+    pub fn opaque_answers_sent(&self) -> usize {
+        self.stats.opaque_answers_sent.load(Ordering::Acquire)
+    }
+
     pub fn ice_candidates_sent(&self) -> usize {
         self.stats.ice_candidates_sent.load(Ordering::Acquire)
     }
 
+    pub fn ice_messages_sent(&self) -> usize {
+        self.stats.ice_messages_sent.load(Ordering::Acquire)
+    }
+
+    pub fn opaque_ice_sent(&self) -> usize {
+        self.stats.opaque_ice_sent.load(Ordering::Acquire)
+    }
+
+    pub fn sealed_sent(&self) -> usize {
+        self.stats.sealed_sent.load(Ordering::Acquire)
+    }
+
     pub fn hangups_sent(&self) -> usize {
         self.stats.hangups_sent.load(Ordering::Acquire)
     }
@@ -446,10 +1388,158 @@ impl SimPlatform {
         self.stats.busys_sent.load(Ordering::Acquire)
     }
 
+    pub fn ringings_sent(&self) -> usize {
+        self.stats.ringings_sent.load(Ordering::Acquire)
+    }
+
+    pub fn pre_offers_sent(&self) -> usize {
+        self.stats.pre_offers_sent.load(Ordering::Acquire)
+    }
+
+    pub fn pre_offers_received(&self) -> usize {
+        self.stats.pre_offers_received.load(Ordering::Acquire)
+    }
+
     pub fn stream_count(&self) -> usize {
         self.stats.stream_count.load(Ordering::Acquire)
     }
 
+    pub fn remote_video_orientations(&self) -> usize {
+        self.stats.orientations.load(Ordering::Acquire)
+    }
+
+    pub fn remote_video_rotation(&self) -> usize {
+        self.stats.rotation.load(Ordering::Acquire)
+    }
+
+    pub fn route_downgrades(&self) -> usize {
+        self.stats.route_downgrades.load(Ordering::Acquire)
+    }
+
+    pub fn route_upgrades(&self) -> usize {
+        self.stats.route_upgrades.load(Ordering::Acquire)
+    }
+
+    pub fn buffered_amount_low_count(&self) -> usize {
+        self.stats.buffered_amount_low.load(Ordering::Acquire)
+    }
+
+    pub fn duration_ticks(&self) -> usize {
+        self.stats.duration_ticks.load(Ordering::Acquire)
+    }
+
+    pub fn turn_requests(&self) -> usize {
+        self.stats.turn_requests.load(Ordering::Acquire)
+    }
+
+    pub fn ice_gathering_states(&self) -> Vec<IceGatheringState> {
+        self.gathering_states.lock().unwrap().clone()
+    }
+
+    pub fn network_routes(&self) -> Vec<(NetworkAdapterType, bool)> {
+        self.network_routes.lock().unwrap().clone()
+    }
+
+    pub fn connection_configs(&self) -> Vec<ConnectionConfig> {
+        self.connection_configs.lock().unwrap().clone()
+    }
+
+    pub fn media_descriptors(&self) -> Vec<MediaDescriptor> {
+        self.media_descriptors.lock().unwrap().clone()
+    }
+
+    pub fn offer_metadata(&self) -> Vec<OfferMetadata> {
+        self.offer_metadata.lock().unwrap().clone()
+    }
+
+    pub fn hangup_types(&self) -> Vec<HangupType> {
+        self.hangup_types.lock().unwrap().clone()
+    }
+
+    pub fn hangup_devices(&self) -> Vec<Vec<DeviceId>> {
+        self.hangup_devices.lock().unwrap().clone()
+    }
+
+    pub fn transformed_sdp(&self) -> Vec<String> {
+        self.transformed_sdp.lock().unwrap().clone()
+    }
+
+    pub fn answer_metadata(&self) -> Vec<Vec<u8>> {
+        self.answer_metadata.lock().unwrap().clone()
+    }
+
+    pub fn sent_answer_metadata(&self) -> Vec<Option<Vec<u8>>> {
+        self.sent_answer_metadata.lock().unwrap().clone()
+    }
+
+    pub fn app_messages(&self) -> Vec<Vec<u8>> {
+        self.app_messages.lock().unwrap().clone()
+    }
+
+    pub fn stats_reports(&self) -> Vec<CallStats> {
+        self.stats_reports.lock().unwrap().clone()
+    }
+
+    pub fn audio_levels(&self) -> Vec<(f64, f64)> {
+        self.audio_levels.lock().unwrap().clone()
+    }
+
+    pub fn bandwidth_estimates(&self) -> Vec<(u64, u64)> {
+        self.bandwidth_estimates.lock().unwrap().clone()
+    }
+
+    pub fn call_records(&self) -> Vec<CallRecord> {
+        self.call_records.lock().unwrap().clone()
+    }
+
+    pub fn call_failures(&self) -> Vec<CallFailureReason> {
+        self.call_failures.lock().unwrap().clone()
+    }
+
+    pub fn received_dtmf(&self) -> String {
+        self.received_dtmf.lock().unwrap().clone()
+    }
+
+    pub fn safety_numbers(&self) -> Vec<String> {
+        self.safety_numbers.lock().unwrap().clone()
+    }
+
+    pub fn transfer_requests(&self) -> Vec<String> {
+        self.transfer_requests.lock().unwrap().clone()
+    }
+
+    pub fn pending_signaling(&self) -> Vec<PendingSignaling<SimPlatformItem>> {
+        self.pending_signaling.lock().unwrap().clone()
+    }
+
+    pub fn set_pending_signaling(&self, pending: Vec<PendingSignaling<SimPlatformItem>>) {
+        *self.pending_signaling.lock().unwrap() = pending;
+    }
+
+    pub fn participant_media(&self) -> Vec<(String, String)> {
+        self.participant_media.lock().unwrap().clone()
+    }
+
+    pub fn audio_routes(&self) -> Vec<AudioRoute> {
+        self.audio_routes.lock().unwrap().clone()
+    }
+
+    pub fn camera_states(&self) -> Vec<bool> {
+        self.camera_states.lock().unwrap().clone()
+    }
+
+    pub fn camera_switches(&self) -> usize {
+        self.stats.camera_switches.load(Ordering::Acquire)
+    }
+
+    pub fn system_calls(&self) -> usize {
+        self.stats.system_calls.load(Ordering::Acquire)
+    }
+
+    pub fn system_holds(&self) -> Vec<bool> {
+        self.system_holds.lock().unwrap().clone()
+    }
+
     pub fn media_closed(&self) -> bool {
         self.close_media.load(Ordering::Acquire)
     }
@@ -465,4 +1555,8 @@ impl SimPlatform {
     pub fn call_concluded_count(&self) -> usize {
         self.stats.call_concluded.load(Ordering::Acquire)
     }
+
+    pub fn shutdown_complete_count(&self) -> usize {
+        self.stats.shutdown_complete.load(Ordering::Acquire)
+    }
 }
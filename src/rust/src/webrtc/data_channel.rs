@@ -10,14 +10,30 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int};
 use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use bytes::BytesMut;
 use prost::Message;
 
-use crate::common::{CallId, Result};
+use crate::common::{CallId, HangupType, NegotiationMessage, Result, TransferMessage};
 use crate::core::util::CppObject;
 use crate::error::RingRtcError;
-use crate::protobuf::data_channel::{Connected, Data, Hangup, VideoStreamingStatus};
+use crate::protobuf::data_channel::negotiation::Type as NegotiationType;
+use crate::protobuf::data_channel::transfer::Type as TransferType;
+use crate::protobuf::data_channel::{
+    AppMessage,
+    AudioStatus,
+    Connected,
+    Data,
+    Hangup,
+    Hold,
+    Negotiation,
+    RecordingStatus,
+    ScreenShareStatus,
+    Transfer,
+    VideoLayerRequest,
+    VideoStreamingStatus,
+};
 use crate::webrtc::data_channel_observer::RffiDataChannelObserverInterface;
 
 #[cfg(not(feature = "sim"))]
@@ -88,10 +104,18 @@ impl RffiDataChannelInit {
     }
 }
 
+/// Default number of bytes that may be buffered for sending before
+/// sends are refused.
+pub const DEFAULT_HIGH_WATER_MARK: u64 = 1024 * 1024;
+
 /// Rust wrapper around WebRTC C++ DataChannel object.
 #[derive(Debug)]
 pub struct DataChannel {
-    dc_interface: *const RffiDataChannelInterface,
+    dc_interface:    *const RffiDataChannelInterface,
+    /// Sends are refused while this many bytes would be buffered.
+    high_water_mark: AtomicU64,
+    /// A send was refused and the buffer has not drained since.
+    blocked:         AtomicBool,
 }
 
 // Implementing Sync and Sync required to share raw *const pointer
@@ -108,7 +132,11 @@ impl Drop for DataChannel {
 impl DataChannel {
     /// Create a new Rust DataChannel object from a WebRTC C++ DataChannel object.
     pub fn new(dc_interface: *const RffiDataChannelInterface) -> Self {
-        Self { dc_interface }
+        Self {
+            dc_interface,
+            high_water_mark: AtomicU64::new(DEFAULT_HIGH_WATER_MARK),
+            blocked: AtomicBool::new(false),
+        }
     }
 
     /// Free resources related to the DataChannel object.
@@ -164,11 +192,45 @@ impl DataChannel {
         }
     }
 
+    /// Set the number of buffered bytes at which sends are refused.
+    pub fn set_high_water_mark(&self, mark: u64) {
+        self.high_water_mark.store(mark, Ordering::Release);
+    }
+
+    /// Return the number of bytes buffered for sending.
+    pub fn buffered_amount(&self) -> u64 {
+        unsafe { dc::Rust_dataChannelBufferedAmount(self.dc_interface) }
+    }
+
+    /// Returns `true`, once, when a send was refused and the buffered
+    /// amount has since drained to half the high water mark or less.
+    pub fn buffered_amount_low(&self) -> bool {
+        if !self.blocked.load(Ordering::Acquire) {
+            return false;
+        }
+        if self.buffered_amount() > self.high_water_mark.load(Ordering::Acquire) / 2 {
+            return false;
+        }
+        self.blocked.swap(false, Ordering::AcqRel)
+    }
+
     /// Send data via the DataChannel.
+    ///
+    /// Fails with `RingRtcError::DataChannelWouldBlock` rather than
+    /// buffering past the high water mark.
     fn send_data(&self, data: &Data) -> Result<()> {
         let mut bytes = BytesMut::with_capacity(data.encoded_len());
         data.encode(&mut bytes)?;
 
+        let buffered_amount = self.buffered_amount();
+        let high_water_mark = self.high_water_mark.load(Ordering::Acquire);
+        if buffered_amount + bytes.len() as u64 > high_water_mark {
+            self.blocked.store(true, Ordering::Release);
+            return Err(
+                RingRtcError::DataChannelWouldBlock(buffered_amount, high_water_mark).into(),
+            );
+        }
+
         let buffer: *const u8 = bytes.as_ptr();
 
         let result =
@@ -182,9 +244,10 @@ impl DataChannel {
     }
 
     /// Send `HangUp` message via the DataChannel.
-    pub fn send_hang_up(&self, call_id: CallId) -> Result<()> {
+    pub fn send_hang_up(&self, call_id: CallId, hangup_type: HangupType) -> Result<()> {
         let mut hangup = Hangup::default();
         hangup.id = Some(u64::from(call_id));
+        hangup.r#type = Some(hangup_type as i32);
         let mut data = Data::default();
         data.hangup = Some(hangup);
 
@@ -212,4 +275,115 @@ impl DataChannel {
 
         self.send_data(&data)
     }
+
+    /// Send `AudioStatus` message via the DataChannel.
+    pub fn send_audio_status(&self, call_id: CallId, enabled: bool) -> Result<()> {
+        let mut audio_status = AudioStatus::default();
+        audio_status.id = Some(u64::from(call_id));
+        audio_status.enabled = Some(enabled);
+
+        let mut data = Data::default();
+        data.audio_status = Some(audio_status);
+
+        self.send_data(&data)
+    }
+
+    /// Send `Hold` message via the DataChannel.
+    pub fn send_hold(&self, call_id: CallId, held: bool) -> Result<()> {
+        let mut hold = Hold::default();
+        hold.id = Some(u64::from(call_id));
+        hold.held = Some(held);
+
+        let mut data = Data::default();
+        data.hold = Some(hold);
+
+        self.send_data(&data)
+    }
+
+    /// Send `Transfer` message via the DataChannel.
+    pub fn send_transfer(&self, call_id: CallId, message: &TransferMessage) -> Result<()> {
+        let mut transfer = Transfer::default();
+        transfer.id = Some(u64::from(call_id));
+        match message {
+            TransferMessage::Request(target) => {
+                transfer.r#type = Some(TransferType::Request as i32);
+                transfer.target = Some(target.clone());
+            }
+            TransferMessage::Accepted => transfer.r#type = Some(TransferType::Accepted as i32),
+            TransferMessage::Declined => transfer.r#type = Some(TransferType::Declined as i32),
+        }
+
+        let mut data = Data::default();
+        data.transfer = Some(transfer);
+
+        self.send_data(&data)
+    }
+
+    /// Send `AppMessage` message via the DataChannel.
+    pub fn send_app_message(&self, call_id: CallId, payload: &[u8]) -> Result<()> {
+        let mut app_message = AppMessage::default();
+        app_message.id = Some(u64::from(call_id));
+        app_message.payload = Some(payload.to_vec());
+
+        let mut data = Data::default();
+        data.app_message = Some(app_message);
+
+        self.send_data(&data)
+    }
+
+    /// Send `VideoLayerRequest` message via the DataChannel.
+    pub fn send_video_layer_request(&self, call_id: CallId, layer: usize) -> Result<()> {
+        let mut request = VideoLayerRequest::default();
+        request.id = Some(u64::from(call_id));
+        request.layer = Some(layer as u32);
+
+        let mut data = Data::default();
+        data.video_layer_request = Some(request);
+
+        self.send_data(&data)
+    }
+
+    /// Send `RecordingStatus` message via the DataChannel.
+    pub fn send_recording_status(&self, call_id: CallId, recording: bool) -> Result<()> {
+        let mut recording_status = RecordingStatus::default();
+        recording_status.id = Some(u64::from(call_id));
+        recording_status.recording = Some(recording);
+
+        let mut data = Data::default();
+        data.recording_status = Some(recording_status);
+
+        self.send_data(&data)
+    }
+
+    /// Send `ScreenShareStatus` message via the DataChannel.
+    pub fn send_screen_share_status(&self, call_id: CallId, sharing: bool) -> Result<()> {
+        let mut screen_share_status = ScreenShareStatus::default();
+        screen_share_status.id = Some(u64::from(call_id));
+        screen_share_status.sharing = Some(sharing);
+
+        let mut data = Data::default();
+        data.screen_share_status = Some(screen_share_status);
+
+        self.send_data(&data)
+    }
+
+    /// Send `Negotiation` message via the DataChannel.
+    pub fn send_negotiation(&self, call_id: CallId, message: &NegotiationMessage) -> Result<()> {
+        let (negotiation_type, sdp) = match message {
+            NegotiationMessage::Offer(sdp) => (NegotiationType::Offer, sdp),
+            NegotiationMessage::Answer(sdp) => (NegotiationType::Answer, sdp),
+        };
+        let negotiation = Negotiation {
+            id:     Some(u64::from(call_id)),
+            r#type: Some(negotiation_type as i32),
+            sdp:    Some(sdp.clone()),
+        };
+
+        let data = Data {
+            negotiation: Some(negotiation),
+            ..Default::default()
+        };
+
+        self.send_data(&data)
+    }
 }
@@ -7,21 +7,33 @@
 
 //! WebRTC Data Channel Observer Interface.
 
-use std::mem;
 use std::slice;
 
 use bytes::Bytes;
 use libc::size_t;
 use prost::Message;
 
-use crate::common::{CallDirection, CallId, Result};
+use crate::common::{
+    CallDirection,
+    CallId,
+    HangupType,
+    NegotiationMessage,
+    Result,
+    TransferMessage,
+};
 use crate::core::connection::Connection;
 use crate::core::platform::Platform;
 
 use crate::core::util::{ptr_as_mut, CppObject, RustObject};
 use crate::error::RingRtcError;
+use crate::protobuf::data_channel::negotiation::Type as NegotiationType;
+use crate::protobuf::data_channel::transfer::Type as TransferType;
 use crate::protobuf::data_channel::Data;
 
+/// Largest message accepted, which must hold the session description
+/// of a negotiation.
+const MAX_MESSAGE_LENGTH: usize = 64 * 1024;
+
 /// DataChannelObserver callback function pointers.
 ///
 /// A structure containing function pointers for each
@@ -49,15 +61,22 @@ where
 /// DataChannelObserver OnBufferedAmountChange() callback.
 #[allow(non_snake_case)]
 extern "C" fn dc_observer_OnBufferedAmountChange<T>(
-    _connection: *mut Connection<T>,
+    connection: *mut Connection<T>,
     previous_amount: u64,
 ) where
     T: Platform,
 {
-    info!(
+    debug!(
         "dc_observer_OnBufferedAmountChange(): previous_amount: {}",
         previous_amount
     );
+
+    match unsafe { ptr_as_mut(connection) } {
+        Ok(cc) => cc
+            .inject_data_channel_buffered_amount_change(previous_amount)
+            .unwrap_or_else(|e| warn!("unable to inject buffered amount change event: {}", e)),
+        Err(e) => warn!("unable to translate cc ptr: {}", e),
+    }
 }
 
 /// DataChannelObserver OnMessage() callback.
@@ -75,7 +94,7 @@ extern "C" fn dc_observer_OnMessage<T>(
         return;
     }
 
-    if length > (mem::size_of::<Data>() * 2) {
+    if length > MAX_MESSAGE_LENGTH {
         warn!("rx protobuf is excessively large: {}", length);
         return;
     }
@@ -128,11 +147,69 @@ extern "C" fn dc_observer_OnMessage<T>(
             );
         }
     } else if let Some(hangup) = message.hangup {
-        cc.inject_remote_hangup(CallId::new(hangup.id()))
+        let hangup_type =
+            HangupType::from_i32(hangup.r#type.unwrap_or_default()).unwrap_or_else(|e| {
+                warn!("{}, treating as a normal hangup", e);
+                HangupType::Normal
+            });
+        cc.inject_remote_hangup(CallId::new(hangup.id()), hangup_type)
             .unwrap_or_else(|e| warn!("unable to inject remote hangup event: {}", e));
     } else if let Some(video_status) = message.video_streaming_status {
         cc.inject_remote_video_status(CallId::new(video_status.id()), video_status.enabled())
             .unwrap_or_else(|e| warn!("unable to inject remote video status event: {}", e));
+    } else if let Some(audio_status) = message.audio_status {
+        cc.inject_remote_audio_status(CallId::new(audio_status.id()), audio_status.enabled())
+            .unwrap_or_else(|e| warn!("unable to inject remote audio status event: {}", e));
+    } else if let Some(hold) = message.hold {
+        cc.inject_remote_hold(CallId::new(hold.id()), hold.held())
+            .unwrap_or_else(|e| warn!("unable to inject remote hold event: {}", e));
+    } else if let Some(transfer) = message.transfer {
+        let transfer_message = match TransferType::from_i32(transfer.r#type.unwrap_or_default()) {
+            Some(TransferType::Request) => TransferMessage::Request(transfer.target().to_string()),
+            Some(TransferType::Accepted) => TransferMessage::Accepted,
+            Some(TransferType::Declined) => TransferMessage::Declined,
+            None => {
+                warn!("Unknown transfer type: {:?}", transfer.r#type);
+                return;
+            }
+        };
+        cc.inject_remote_transfer(CallId::new(transfer.id()), transfer_message)
+            .unwrap_or_else(|e| warn!("unable to inject remote transfer event: {}", e));
+    } else if let Some(app_message) = message.app_message {
+        cc.inject_remote_app_message(
+            CallId::new(app_message.id()),
+            app_message.payload.unwrap_or_default(),
+        )
+        .unwrap_or_else(|e| warn!("unable to inject remote app message event: {}", e));
+    } else if let Some(request) = message.video_layer_request {
+        cc.inject_remote_video_layer_request(CallId::new(request.id()), request.layer() as usize)
+            .unwrap_or_else(|e| warn!("unable to inject remote video layer request event: {}", e));
+    } else if let Some(recording_status) = message.recording_status {
+        cc.inject_remote_recording_status(
+            CallId::new(recording_status.id()),
+            recording_status.recording(),
+        )
+        .unwrap_or_else(|e| warn!("unable to inject remote recording status event: {}", e));
+    } else if let Some(screen_share_status) = message.screen_share_status {
+        cc.inject_remote_screen_share_status(
+            CallId::new(screen_share_status.id()),
+            screen_share_status.sharing(),
+        )
+        .unwrap_or_else(|e| warn!("unable to inject remote screen share status event: {}", e));
+    } else if let Some(negotiation) = message.negotiation {
+        let call_id = CallId::new(negotiation.id());
+        let sdp = negotiation.sdp.unwrap_or_default();
+        let negotiation_message =
+            match NegotiationType::from_i32(negotiation.r#type.unwrap_or_default()) {
+                Some(NegotiationType::Offer) => NegotiationMessage::Offer(sdp),
+                Some(NegotiationType::Answer) => NegotiationMessage::Answer(sdp),
+                None => {
+                    warn!("Unknown negotiation type: {:?}", negotiation.r#type);
+                    return;
+                }
+            };
+        cc.inject_remote_negotiation(call_id, negotiation_message)
+            .unwrap_or_else(|e| warn!("unable to inject remote negotiation event: {}", e));
     } else {
         info!("Unhandled data channel message: {:?}", message);
     }
@@ -21,6 +21,8 @@ extern "C" {
         binary: bool,
     ) -> bool;
 
+    pub fn Rust_dataChannelBufferedAmount(dc_interface: *const RffiDataChannelInterface) -> u64;
+
     pub fn Rust_registerDataChannelObserver(
         dc_interface: *const RffiDataChannelInterface,
         dc_observer: *const RffiDataChannelObserverInterface,
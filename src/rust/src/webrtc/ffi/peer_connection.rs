@@ -7,15 +7,24 @@
 
 //! WebRTC FFI Peer Connection Interface
 
+use std::ffi::c_void;
 use std::os::raw::c_char;
 
+use libc::size_t;
+
+use crate::core::audio_config::AudioConfig;
+use crate::core::util::RustObject;
 use crate::webrtc::data_channel::RffiDataChannelInit;
+#[cfg(feature = "srtp_key_export")]
+use crate::webrtc::peer_connection::RffiSrtpKeyingMaterial;
+use crate::webrtc::peer_connection::{RffiIceServer, RffiSimulcastLayer};
 
 use crate::webrtc::sdp_observer::{
     RffiCreateSessionDescriptionObserver,
     RffiSessionDescriptionInterface,
     RffiSetSessionDescriptionObserver,
 };
+use crate::webrtc::stats_observer::RffiStatsObserver;
 
 /// Incomplete type for C++ PeerConnectionInterface.
 #[repr(C)]
@@ -64,4 +73,101 @@ extern "C" {
         sdp_mline_index: i32,
         sdp: *const c_char,
     ) -> bool;
+
+    pub fn Rust_setVideoScaleResolutionDownBy(
+        pc_interface: *const RffiPeerConnectionInterface,
+        scale: f64,
+    ) -> bool;
+
+    pub fn Rust_setVideoMaxFramerate(
+        pc_interface: *const RffiPeerConnectionInterface,
+        max_framerate: u32,
+    ) -> bool;
+
+    pub fn Rust_setVideoDegradationPreference(
+        pc_interface: *const RffiPeerConnectionInterface,
+        preference: i32,
+    ) -> bool;
+
+    pub fn Rust_setSimulcastLayers(
+        pc_interface: *const RffiPeerConnectionInterface,
+        layers: *const RffiSimulcastLayer,
+        layers_size: usize,
+    ) -> bool;
+
+    pub fn Rust_setFrameCryptor(
+        pc_interface: *const RffiPeerConnectionInterface,
+        frame_cryptor: RustObject,
+        frame_cryptor_cbs: *const c_void,
+    ) -> bool;
+
+    pub fn Rust_setOutgoingAudioEnabled(
+        pc_interface: *const RffiPeerConnectionInterface,
+        enabled: bool,
+    ) -> bool;
+
+    pub fn Rust_setOutgoingVideoEnabled(
+        pc_interface: *const RffiPeerConnectionInterface,
+        enabled: bool,
+    ) -> bool;
+
+    pub fn Rust_setOutgoingScreenShareEnabled(
+        pc_interface: *const RffiPeerConnectionInterface,
+        enabled: bool,
+    ) -> bool;
+
+    pub fn Rust_setOutgoingAudioGain(
+        pc_interface: *const RffiPeerConnectionInterface,
+        gain: f64,
+    ) -> bool;
+
+    pub fn Rust_setAudioConfig(
+        pc_interface: *const RffiPeerConnectionInterface,
+        config: AudioConfig,
+    ) -> bool;
+
+    pub fn Rust_setAudioJitterBufferMinimumDelay(
+        pc_interface: *const RffiPeerConnectionInterface,
+        delay_seconds: f64,
+    ) -> bool;
+
+    pub fn Rust_insertDtmf(
+        pc_interface: *const RffiPeerConnectionInterface,
+        tones: *const c_char,
+        duration_ms: i32,
+        gap_ms: i32,
+    ) -> bool;
+
+    pub fn Rust_pushOutgoingVideoFrame(
+        pc_interface: *const RffiPeerConnectionInterface,
+        width: u32,
+        height: u32,
+        timestamp_us: i64,
+        buffer: *const u8,
+        length: size_t,
+    ) -> bool;
+
+    pub fn Rust_setMaxBitrate(
+        pc_interface: *const RffiPeerConnectionInterface,
+        bitrate_bps: i32,
+    ) -> bool;
+
+    pub fn Rust_setIceServers(
+        pc_interface: *const RffiPeerConnectionInterface,
+        ice_servers: *const RffiIceServer,
+        ice_servers_size: usize,
+    ) -> bool;
+
+    pub fn Rust_restartIce(pc_interface: *const RffiPeerConnectionInterface);
+
+    #[cfg(feature = "srtp_key_export")]
+    pub fn Rust_exportSrtpKeyingMaterial(
+        pc_interface: *const RffiPeerConnectionInterface,
+        material: *mut RffiSrtpKeyingMaterial,
+    ) -> bool;
+
+    pub fn Rust_getStats(
+        pc_interface: *const RffiPeerConnectionInterface,
+        stats_observer: *const RffiStatsObserver,
+    );
 }
@@ -51,4 +51,6 @@ extern "C" {
     pub fn Rust_createSessionDescriptionOffer(
         description: *const c_char,
     ) -> *const RffiSessionDescriptionInterface;
+
+    pub fn Rust_createSessionDescriptionRollback() -> *const RffiSessionDescriptionInterface;
 }
@@ -0,0 +1,334 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! WebRTC Frame Encryptor / Decryptor Interface.
+//!
+//! DTLS-SRTP only encrypts the media from one hop to the next, so a
+//! server relaying it, such as the SFU of a group call, sees the
+//! media in the clear.  A `MediaCryptoProvider` set with
+//! `CallManager::set_media_crypto_provider()` encrypts each encoded
+//! frame before it is packetized, and decrypts each received frame
+//! before it is decoded, with keys only the embedders know.
+//!
+//! C++ attaches a frame encryptor to every sender and a frame
+//! decryptor to every receiver of the PeerConnection, each calling
+//! back into the `FrameCryptor` of the connection.
+
+use std::ffi::c_void;
+use std::fmt;
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use libc::size_t;
+
+use crate::common::ConnectionId;
+use crate::core::util::{ptr_as_ref, RustObject};
+use crate::webrtc::media::MediaKind;
+
+/// Encrypts and decrypts the media frames of the connections.
+///
+/// Frames are handed over on the WebRTC encoder and decoder threads,
+/// so the keys of a connection must be available without blocking.
+pub trait MediaCryptoProvider: Send + Sync + 'static {
+    /// Encrypt an encoded frame sent on the connection, returning the
+    /// ciphertext, or `None` to drop the frame.
+    fn encrypt(
+        &self,
+        connection_id: ConnectionId,
+        kind: MediaKind,
+        frame: &[u8],
+    ) -> Option<Vec<u8>>;
+
+    /// Decrypt an encrypted frame received on the connection,
+    /// returning the encoded frame, or `None` to drop the frame.
+    fn decrypt(
+        &self,
+        connection_id: ConnectionId,
+        kind: MediaKind,
+        frame: &[u8],
+    ) -> Option<Vec<u8>>;
+
+    /// The most bytes `encrypt()` adds to a frame, e.g. for a nonce
+    /// and an authentication tag.
+    fn max_overhead(&self, kind: MediaKind) -> usize;
+}
+
+/// The `MediaCryptoProvider` of one connection, called back by the
+/// frame encryptors and decryptors of its PeerConnection.
+pub struct FrameCryptor {
+    /// The connection whose frames are encrypted.
+    connection_id: ConnectionId,
+    /// Encrypts and decrypts the frames.
+    provider:      Arc<dyn MediaCryptoProvider>,
+}
+
+impl fmt::Display for FrameCryptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "connection_id: {}", self.connection_id)
+    }
+}
+
+impl fmt::Debug for FrameCryptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl FrameCryptor {
+    /// Create a new FrameCryptor, encrypting the frames of the
+    /// connection with `provider`.
+    pub fn new(connection_id: ConnectionId, provider: Arc<dyn MediaCryptoProvider>) -> Self {
+        Self {
+            connection_id,
+            provider,
+        }
+    }
+
+    /// Return the object handed to C++ along with the callbacks.
+    pub fn rust_object(&self) -> RustObject {
+        self as *const FrameCryptor as RustObject
+    }
+
+    /// Return the callbacks handed to C++.
+    pub fn callbacks() -> *const c_void {
+        FRAME_CRYPTOR_CBS_PTR as *const c_void
+    }
+}
+
+/// Copy `output` into the C++ buffer, failing if it does not fit.
+///
+/// # Safety
+///
+/// `buffer` must point to `capacity` writable bytes and `size` to a
+/// writable size.
+unsafe fn copy_frame(
+    output: Option<Vec<u8>>,
+    buffer: *mut u8,
+    capacity: size_t,
+    size: *mut size_t,
+) -> bool {
+    match output {
+        Some(output) if output.len() <= capacity && !size.is_null() => {
+            ptr::copy_nonoverlapping(output.as_ptr(), buffer, output.len());
+            *size = output.len() as size_t;
+            true
+        }
+        Some(output) => {
+            warn!(
+                "copy_frame(): {} bytes do not fit in {}",
+                output.len(),
+                capacity
+            );
+            false
+        }
+        None => false,
+    }
+}
+
+/// FrameEncryptor Encrypt() callback.
+#[allow(non_snake_case)]
+extern "C" fn frame_cryptor_Encrypt(
+    frame_cryptor: *mut FrameCryptor,
+    kind: MediaKind,
+    plaintext: *const u8,
+    plaintext_size: size_t,
+    ciphertext: *mut u8,
+    ciphertext_capacity: size_t,
+    ciphertext_size: *mut size_t,
+) -> bool {
+    match unsafe { ptr_as_ref(frame_cryptor) } {
+        Ok(v) if !plaintext.is_null() && !ciphertext.is_null() => {
+            let plaintext = unsafe { slice::from_raw_parts(plaintext, plaintext_size) };
+            let output = v.provider.encrypt(v.connection_id, kind, plaintext);
+            unsafe { copy_frame(output, ciphertext, ciphertext_capacity, ciphertext_size) }
+        }
+        Ok(_) => {
+            warn!("frame_cryptor_Encrypt(): null frame");
+            false
+        }
+        Err(e) => {
+            error!("frame_cryptor_Encrypt(): {}", e);
+            false
+        }
+    }
+}
+
+/// FrameDecryptor Decrypt() callback.
+#[allow(non_snake_case)]
+extern "C" fn frame_cryptor_Decrypt(
+    frame_cryptor: *mut FrameCryptor,
+    kind: MediaKind,
+    ciphertext: *const u8,
+    ciphertext_size: size_t,
+    plaintext: *mut u8,
+    plaintext_capacity: size_t,
+    plaintext_size: *mut size_t,
+) -> bool {
+    match unsafe { ptr_as_ref(frame_cryptor) } {
+        Ok(v) if !ciphertext.is_null() && !plaintext.is_null() => {
+            let ciphertext = unsafe { slice::from_raw_parts(ciphertext, ciphertext_size) };
+            let output = v.provider.decrypt(v.connection_id, kind, ciphertext);
+            unsafe { copy_frame(output, plaintext, plaintext_capacity, plaintext_size) }
+        }
+        Ok(_) => {
+            warn!("frame_cryptor_Decrypt(): null frame");
+            false
+        }
+        Err(e) => {
+            error!("frame_cryptor_Decrypt(): {}", e);
+            false
+        }
+    }
+}
+
+/// FrameEncryptor GetMaxCiphertextByteSize() callback.
+#[allow(non_snake_case)]
+extern "C" fn frame_cryptor_GetMaxCiphertextSize(
+    frame_cryptor: *mut FrameCryptor,
+    kind: MediaKind,
+    plaintext_size: size_t,
+) -> size_t {
+    match unsafe { ptr_as_ref(frame_cryptor) } {
+        Ok(v) => plaintext_size + v.provider.max_overhead(kind) as size_t,
+        Err(e) => {
+            error!("frame_cryptor_GetMaxCiphertextSize(): {}", e);
+            plaintext_size
+        }
+    }
+}
+
+/// Frame cryptor callback function pointers.
+#[repr(C)]
+#[allow(non_snake_case)]
+pub struct FrameCryptorCallbacks {
+    pub encrypt: extern "C" fn(
+        frame_cryptor: *mut FrameCryptor,
+        kind: MediaKind,
+        plaintext: *const u8,
+        plaintext_size: size_t,
+        ciphertext: *mut u8,
+        ciphertext_capacity: size_t,
+        ciphertext_size: *mut size_t,
+    ) -> bool,
+    pub decrypt: extern "C" fn(
+        frame_cryptor: *mut FrameCryptor,
+        kind: MediaKind,
+        ciphertext: *const u8,
+        ciphertext_size: size_t,
+        plaintext: *mut u8,
+        plaintext_capacity: size_t,
+        plaintext_size: *mut size_t,
+    ) -> bool,
+    pub getMaxCiphertextSize: extern "C" fn(
+        frame_cryptor: *mut FrameCryptor,
+        kind: MediaKind,
+        plaintext_size: size_t,
+    ) -> size_t,
+}
+
+const FRAME_CRYPTOR_CBS: FrameCryptorCallbacks = FrameCryptorCallbacks {
+    encrypt:              frame_cryptor_Encrypt,
+    decrypt:              frame_cryptor_Decrypt,
+    getMaxCiphertextSize: frame_cryptor_GetMaxCiphertextSize,
+};
+const FRAME_CRYPTOR_CBS_PTR: *const FrameCryptorCallbacks = &FRAME_CRYPTOR_CBS;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::CallId;
+
+    /// Appends a checksum byte and flips the bits of the frame.
+    struct TestProvider;
+
+    impl MediaCryptoProvider for TestProvider {
+        fn encrypt(&self, _: ConnectionId, _: MediaKind, frame: &[u8]) -> Option<Vec<u8>> {
+            let checksum = frame.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+            let mut output: Vec<u8> = frame.iter().map(|b| !b).collect();
+            output.push(checksum);
+            Some(output)
+        }
+
+        fn decrypt(&self, _: ConnectionId, _: MediaKind, frame: &[u8]) -> Option<Vec<u8>> {
+            let (checksum, frame) = frame.split_last()?;
+            let output: Vec<u8> = frame.iter().map(|b| !b).collect();
+            if output.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) == *checksum {
+                Some(output)
+            } else {
+                None
+            }
+        }
+
+        fn max_overhead(&self, _: MediaKind) -> usize {
+            1
+        }
+    }
+
+    fn crypt(
+        callback: extern "C" fn(
+            *mut FrameCryptor,
+            MediaKind,
+            *const u8,
+            size_t,
+            *mut u8,
+            size_t,
+            *mut size_t,
+        ) -> bool,
+        cryptor: &FrameCryptor,
+        input: &[u8],
+        capacity: usize,
+    ) -> Option<Vec<u8>> {
+        let mut output = vec![0u8; capacity];
+        let mut size: size_t = 0;
+        let ok = callback(
+            cryptor.rust_object() as *mut FrameCryptor,
+            MediaKind::Video,
+            input.as_ptr(),
+            input.len() as size_t,
+            output.as_mut_ptr(),
+            output.len() as size_t,
+            &mut size,
+        );
+        if ok {
+            output.truncate(size as usize);
+            Some(output)
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn check_round_trip() {
+        let connection_id = ConnectionId::new(CallId::new(1), 2);
+        let cryptor = FrameCryptor::new(connection_id, Arc::new(TestProvider));
+        let frame = [1u8, 2, 3, 250];
+
+        let max = frame_cryptor_GetMaxCiphertextSize(
+            cryptor.rust_object() as *mut FrameCryptor,
+            MediaKind::Video,
+            frame.len() as size_t,
+        );
+        assert_eq!(max, 5);
+
+        let ciphertext = crypt(frame_cryptor_Encrypt, &cryptor, &frame, max as usize).unwrap();
+        assert_eq!(ciphertext.len(), 5);
+        assert_ne!(&ciphertext[..4], &frame[..]);
+
+        let plaintext = crypt(frame_cryptor_Decrypt, &cryptor, &ciphertext, 5).unwrap();
+        assert_eq!(plaintext, frame);
+
+        // A tampered frame is dropped.
+        let mut tampered = ciphertext.clone();
+        tampered[0] ^= 1;
+        assert_eq!(crypt(frame_cryptor_Decrypt, &cryptor, &tampered, 5), None);
+
+        // So is a ciphertext larger than the buffer.
+        assert_eq!(crypt(frame_cryptor_Encrypt, &cryptor, &frame, 4), None);
+    }
+}
@@ -13,6 +13,9 @@ use std::os::raw::c_char;
 
 use crate::core::util::redact_string;
 
+/// The WebRTC name of a TURN relay candidate type.
+const RELAY_CANDIDATE_TYPE: &str = "relay";
+
 /// Ice Candidate structure passed between Rust and C++.
 #[repr(C)]
 #[derive(Debug)]
@@ -22,6 +25,36 @@ pub struct CppIceCandidate {
     sdp:             *const c_char,
 }
 
+/// Selected ICE candidate pair structure passed from C++ to Rust.
+#[repr(C)]
+#[derive(Debug)]
+pub struct CppIceRoute {
+    local_candidate_type:  *const c_char,
+    remote_candidate_type: *const c_char,
+    protocol:              *const c_char,
+    local_adapter_type:    NetworkAdapterType,
+}
+
+/// Rust version of the WebRTC rtc::AdapterType enum, the kind of
+/// network interface a candidate was gathered on.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum NetworkAdapterType {
+    #[default]
+    Unknown  = 0,
+    Ethernet = 1,
+    Wifi     = 2,
+    Cellular = 4,
+    Vpn      = 8,
+    Loopback = 16,
+}
+
+impl fmt::Display for NetworkAdapterType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// Ice Candiate structure passed around within Rust only.
 #[derive(Clone)]
 pub struct IceCandidate {
@@ -57,6 +90,77 @@ impl IceCandidate {
     }
 }
 
+/// The candidate pair selected by ICE, passed around within Rust
+/// only.
+///
+/// Candidate types are as named by WebRTC: `local`, `stun`, `prflx`
+/// or `relay`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IceRoute {
+    pub local_candidate_type:  String,
+    pub remote_candidate_type: String,
+    pub protocol:              String,
+    pub local_adapter_type:    NetworkAdapterType,
+}
+
+impl fmt::Display for IceRoute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "local: {}, remote: {}, protocol: {}, adapter: {}",
+            self.local_candidate_type,
+            self.remote_candidate_type,
+            self.protocol,
+            self.local_adapter_type
+        )
+    }
+}
+
+impl IceRoute {
+    /// Create a new IceRoute.
+    pub fn new(local_candidate_type: &str, remote_candidate_type: &str, protocol: &str) -> Self {
+        Self {
+            local_candidate_type:  local_candidate_type.to_string(),
+            remote_candidate_type: remote_candidate_type.to_string(),
+            protocol:              protocol.to_string(),
+            local_adapter_type:    NetworkAdapterType::Unknown,
+        }
+    }
+
+    /// Set the kind of network interface of the local candidate.
+    pub fn with_local_adapter_type(mut self, local_adapter_type: NetworkAdapterType) -> Self {
+        self.local_adapter_type = local_adapter_type;
+        self
+    }
+
+    /// Returns true if either end of the route is a TURN relay.
+    pub fn relayed(&self) -> bool {
+        self.local_candidate_type == RELAY_CANDIDATE_TYPE
+            || self.remote_candidate_type == RELAY_CANDIDATE_TYPE
+    }
+}
+
+impl From<&CppIceRoute> for IceRoute {
+    fn from(item: &CppIceRoute) -> Self {
+        IceRoute {
+            local_candidate_type:  unsafe {
+                CStr::from_ptr(item.local_candidate_type)
+                    .to_string_lossy()
+                    .into_owned()
+            },
+            remote_candidate_type: unsafe {
+                CStr::from_ptr(item.remote_candidate_type)
+                    .to_string_lossy()
+                    .into_owned()
+            },
+            protocol:              unsafe {
+                CStr::from_ptr(item.protocol).to_string_lossy().into_owned()
+            },
+            local_adapter_type:    item.local_adapter_type,
+        }
+    }
+}
+
 impl From<&CppIceCandidate> for IceCandidate {
     fn from(item: &CppIceCandidate) -> Self {
         IceCandidate::new(
@@ -0,0 +1,79 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! Media of a call.
+//!
+//! An `AudioTap` set with `Connection::set_audio_tap()` receives the
+//! PCM frames of the captured and of the received audio, e.g. to
+//! record or transcribe the call, or to meter its loudness.
+
+use std::fmt;
+
+/// Rust version of the WebRTC media kind of a track or frame.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MediaKind {
+    Audio,
+    Video,
+}
+
+impl fmt::Display for MediaKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Which audio of the call a frame belongs to.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AudioDirection {
+    /// Audio captured locally, before it is encoded.
+    Captured,
+    /// Audio received from the remote peer, once decoded.
+    Received,
+}
+
+impl fmt::Display for AudioDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A frame of PCM audio, usually 10 ms long.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioFrame {
+    /// Which audio of the call the frame belongs to.
+    pub direction:      AudioDirection,
+    /// The sample rate, in Hz.
+    pub sample_rate_hz: u32,
+    /// The number of channels.
+    pub channels:       u32,
+    /// The 16-bit samples, interleaved by channel.
+    pub samples:        Vec<i16>,
+}
+
+impl fmt::Display for AudioFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, sample_rate_hz: {}, channels: {}, samples: {}",
+            self.direction,
+            self.sample_rate_hz,
+            self.channels,
+            self.samples.len()
+        )
+    }
+}
+
+/// Receives the frames of the captured and received audio.
+///
+/// Frames are handed over on the WebRTC audio threads, so a tap
+/// should not block.
+pub trait AudioTap: Send + 'static {
+    /// Receive the next frame.
+    fn on_audio_frame(&self, frame: AudioFrame);
+}
@@ -6,6 +6,12 @@
 //
 
 //! WebRTC Media Stream Interface.
+//!
+//! Besides the MediaStream handed to the application, the decoded
+//! frames of the incoming video can be handed to a Rust `VideoSink`,
+//! see `Connection::set_incoming_video_sink()`, and the outgoing video
+//! can be produced by a Rust `VideoSource` instead of the platform's
+//! camera capturer, see `Connection::set_outgoing_video_source()`.
 
 use std::fmt;
 use std::marker::Send;
@@ -81,3 +87,49 @@ impl MediaStream {
         rffi_ms_interface
     }
 }
+
+/// A raw video frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VideoFrame {
+    /// The width of the frame, in pixels.
+    pub width:        u32,
+    /// The height of the frame, in pixels.
+    pub height:       u32,
+    /// The capture time of the frame, in microseconds.
+    pub timestamp_us: i64,
+    /// The pixels of the frame, in I420.
+    pub data:         Vec<u8>,
+}
+
+impl fmt::Display for VideoFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}x{}, timestamp_us: {}, size: {}",
+            self.width,
+            self.height,
+            self.timestamp_us,
+            self.data.len()
+        )
+    }
+}
+
+/// Receives the decoded frames of a video track, e.g. to record or
+/// analyze the incoming video.
+///
+/// Frames are handed over on the WebRTC decoder thread, so a sink
+/// should not block.
+pub trait VideoSink: Send + 'static {
+    /// Receive the next frame.
+    fn on_video_frame(&self, frame: VideoFrame);
+}
+
+/// Produces the frames of the outgoing video, e.g. from a screen
+/// capture, a file or a test pattern.
+///
+/// The source is polled on a thread of its own, so `next_frame()` may
+/// block until the next frame is due.
+pub trait VideoSource: Send + 'static {
+    /// Return the next frame, or `None` once the source has ended.
+    fn next_frame(&mut self) -> Option<VideoFrame>;
+}
@@ -8,16 +8,27 @@
 //! WebRTC Peer Connection Interface
 use std::ffi::CString;
 use std::fmt;
+use std::os::raw::c_char;
+use std::ptr;
+use std::time::Duration;
 
 use crate::common::Result;
+use crate::core::audio_config::AudioConfig;
+use crate::core::ice_server::IceServerConfig;
+#[cfg(feature = "srtp_key_export")]
+use crate::core::srtp_keys::{SrtpKeys, SrtpProtectionProfile, MAX_SRTP_KEYING_MATERIAL};
+use crate::core::video::Simulcast;
 use crate::error::RingRtcError;
 use crate::webrtc::data_channel::{DataChannel, RffiDataChannelInit};
+use crate::webrtc::frame_crypto::FrameCryptor;
 use crate::webrtc::ice_candidate::IceCandidate;
+use crate::webrtc::media_stream::VideoFrame;
 use crate::webrtc::sdp_observer::{
     CreateSessionDescriptionObserver,
     SessionDescriptionInterface,
     SetSessionDescriptionObserver,
 };
+use crate::webrtc::stats_observer::StatsObserver;
 
 #[cfg(not(feature = "sim"))]
 use crate::webrtc::ffi::peer_connection as pc;
@@ -35,6 +46,35 @@ pub use crate::webrtc::sim::peer_connection::{
     RffiPeerConnectionInterface,
 };
 
+/// A STUN or TURN server passed to C++, see `IceServerConfig`.
+#[repr(C)]
+pub struct RffiIceServer {
+    pub url:      *const c_char,
+    pub username: *const c_char,
+    pub password: *const c_char,
+}
+
+/// A simulcast layer passed to C++, see `Simulcast`.
+#[repr(C)]
+pub struct RffiSimulcastLayer {
+    pub max_width:       u32,
+    pub max_height:      u32,
+    pub max_bitrate_bps: u32,
+    pub active:          bool,
+}
+
+/// The SRTP keying material exported by C++, see `SrtpKeys`.
+#[cfg(feature = "srtp_key_export")]
+#[repr(C)]
+pub struct RffiSrtpKeyingMaterial {
+    /// The SRTP protection profile, see `SrtpProtectionProfile`.
+    pub crypto_suite:  i32,
+    /// Whether the local side was the DTLS client.
+    pub dtls_client:   bool,
+    pub material:      [u8; MAX_SRTP_KEYING_MATERIAL],
+    pub material_size: usize,
+}
+
 /// Rust wrapper around WebRTC C++ PeerConnectionInterface object.
 pub struct PeerConnection {
     /// Pointer to C++ PeerConnectionInterface.
@@ -143,4 +183,281 @@ impl PeerConnection {
             Err(RingRtcError::AddIceCandidate.into())
         }
     }
+
+    /// Rust wrapper around C++ RtpSenderInterface::SetParameters(),
+    /// setting `scale_resolution_down_by` on all video encodings.
+    pub fn set_video_scale_resolution_down_by(&self, scale: f64) -> Result<()> {
+        let set_ok =
+            unsafe { pc::Rust_setVideoScaleResolutionDownBy(self.rffi_pc_interface, scale) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetVideoScaleResolutionDownBy(scale).into())
+        }
+    }
+
+    /// Rust wrapper around C++ RtpSenderInterface::SetParameters(),
+    /// setting `max_framerate` on all video encodings, where 0 removes
+    /// the cap.
+    pub fn set_video_max_framerate(&self, max_framerate: u32) -> Result<()> {
+        let set_ok =
+            unsafe { pc::Rust_setVideoMaxFramerate(self.rffi_pc_interface, max_framerate) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetVideoMaxFramerate(max_framerate).into())
+        }
+    }
+
+    /// Rust wrapper around C++ RtpSenderInterface::SetParameters(),
+    /// setting `degradation_preference` on all video senders.
+    pub fn set_video_degradation_preference(&self, preference: i32) -> Result<()> {
+        let set_ok =
+            unsafe { pc::Rust_setVideoDegradationPreference(self.rffi_pc_interface, preference) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetVideoDegradationPreference(preference).into())
+        }
+    }
+
+    /// Rust wrapper around C++ RtpSenderInterface::SetFrameEncryptor()
+    /// and RtpReceiverInterface::SetFrameDecryptor(), for all senders
+    /// and receivers.  `None` detaches them again.
+    ///
+    /// The frame cryptor must outlive the PeerConnection, or be
+    /// detached first.
+    pub fn set_frame_cryptor(&self, frame_cryptor: Option<&FrameCryptor>) -> Result<()> {
+        let (rust_object, callbacks) = match frame_cryptor {
+            Some(v) => (v.rust_object(), FrameCryptor::callbacks()),
+            None => (ptr::null(), ptr::null()),
+        };
+
+        let set_ok =
+            unsafe { pc::Rust_setFrameCryptor(self.rffi_pc_interface, rust_object, callbacks) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetFrameCryptor(frame_cryptor.is_some()).into())
+        }
+    }
+
+    /// Rust wrapper around C++ RtpSenderInterface::SetParameters(),
+    /// replacing the encodings of the video sender with the simulcast
+    /// layers.  No layers go back to a single encoding.
+    pub fn set_simulcast(&self, simulcast: &Simulcast) -> Result<()> {
+        let rffi_layers: Vec<RffiSimulcastLayer> = simulcast
+            .layers()
+            .iter()
+            .zip(simulcast.active_layers())
+            .map(|(layer, active)| RffiSimulcastLayer {
+                max_width: layer.max_resolution.width,
+                max_height: layer.max_resolution.height,
+                max_bitrate_bps: layer.max_bitrate_bps,
+                active,
+            })
+            .collect();
+
+        let set_ok = unsafe {
+            pc::Rust_setSimulcastLayers(
+                self.rffi_pc_interface,
+                rffi_layers.as_ptr(),
+                rffi_layers.len(),
+            )
+        };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetSimulcastLayers(rffi_layers.len()).into())
+        }
+    }
+
+    /// Rust wrapper around C++ MediaStreamTrackInterface::set_enabled(),
+    /// enabling or disabling the tracks of all audio senders.
+    pub fn set_outgoing_audio_enabled(&self, enabled: bool) -> Result<()> {
+        let set_ok = unsafe { pc::Rust_setOutgoingAudioEnabled(self.rffi_pc_interface, enabled) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetOutgoingAudioEnabled(enabled).into())
+        }
+    }
+
+    /// Rust wrapper around C++ MediaStreamTrackInterface::set_enabled(),
+    /// enabling or disabling the tracks of all video senders.
+    pub fn set_outgoing_video_enabled(&self, enabled: bool) -> Result<()> {
+        let set_ok = unsafe { pc::Rust_setOutgoingVideoEnabled(self.rffi_pc_interface, enabled) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetOutgoingVideoEnabled(enabled).into())
+        }
+    }
+
+    /// Rust wrapper around C++ MediaStreamTrackInterface::set_enabled(),
+    /// enabling or disabling the tracks of the screen share senders.
+    pub fn set_outgoing_screen_share_enabled(&self, enabled: bool) -> Result<()> {
+        let set_ok =
+            unsafe { pc::Rust_setOutgoingScreenShareEnabled(self.rffi_pc_interface, enabled) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetOutgoingScreenShareEnabled(enabled).into())
+        }
+    }
+
+    /// Rust wrapper around C++ AudioSourceInterface::SetVolume(),
+    /// setting the gain of the sources of all audio senders.
+    pub fn set_outgoing_audio_gain(&self, gain: f32) -> Result<()> {
+        let set_ok =
+            unsafe { pc::Rust_setOutgoingAudioGain(self.rffi_pc_interface, f64::from(gain)) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetOutgoingAudioGain(gain).into())
+        }
+    }
+
+    /// Rust wrapper around C++ AudioSourceInterface::options(),
+    /// setting the audio processing of the sources of all audio
+    /// senders.
+    pub fn set_audio_config(&self, config: AudioConfig) -> Result<()> {
+        let set_ok = unsafe { pc::Rust_setAudioConfig(self.rffi_pc_interface, config) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetAudioConfig(config).into())
+        }
+    }
+
+    /// Rust wrapper around C++
+    /// RtpReceiverInterface::SetJitterBufferMinimumDelay(), for all
+    /// audio receivers.  `None` lets WebRTC choose the delay again.
+    pub fn set_audio_jitter_buffer_min_delay(&self, delay: Option<Duration>) -> Result<()> {
+        // C++ takes a negative delay as none.
+        let delay_seconds = delay.map_or(-1.0, |delay| delay.as_secs_f64());
+        let set_ok = unsafe {
+            pc::Rust_setAudioJitterBufferMinimumDelay(self.rffi_pc_interface, delay_seconds)
+        };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetAudioJitterBufferMinimumDelay(delay).into())
+        }
+    }
+
+    /// Rust wrapper around C++ DtmfSenderInterface::InsertDtmf(),
+    /// queueing `tones` on the DTMF sender of the audio sender.
+    pub fn insert_dtmf(&self, tones: &str, duration_ms: i32, gap_ms: i32) -> Result<()> {
+        let tones = CString::new(tones)?;
+        let insert_ok = unsafe {
+            pc::Rust_insertDtmf(self.rffi_pc_interface, tones.as_ptr(), duration_ms, gap_ms)
+        };
+        if insert_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::InsertDtmf(tones.to_string_lossy().into_owned()).into())
+        }
+    }
+
+    /// Hand `frame` to the video track source of the video sender,
+    /// in place of the frames of the platform's capturer.
+    pub fn push_outgoing_video_frame(&self, frame: &VideoFrame) -> Result<()> {
+        let push_ok = unsafe {
+            pc::Rust_pushOutgoingVideoFrame(
+                self.rffi_pc_interface,
+                frame.width,
+                frame.height,
+                frame.timestamp_us,
+                frame.data.as_ptr(),
+                frame.data.len(),
+            )
+        };
+        if push_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::PushOutgoingVideoFrame(frame.width, frame.height).into())
+        }
+    }
+
+    /// Rust wrapper around C++ PeerConnectionInterface::SetBitrate(),
+    /// capping the bitrate of all media sent.
+    pub fn set_max_bitrate(&self, bitrate_bps: u32) -> Result<()> {
+        let set_ok = unsafe { pc::Rust_setMaxBitrate(self.rffi_pc_interface, bitrate_bps as i32) };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetMaxBitrate(bitrate_bps).into())
+        }
+    }
+
+    /// Rust wrapper around C++ PeerConnectionInterface::SetConfiguration(),
+    /// replacing the ICE servers.
+    pub fn set_ice_servers(&self, ice_servers: &[IceServerConfig]) -> Result<()> {
+        // The strings must outlive the call into C++.
+        let mut strings = Vec::with_capacity(ice_servers.len());
+        for ice_server in ice_servers {
+            strings.push((
+                CString::new(ice_server.rtc_url())?,
+                CString::new(ice_server.username.as_str())?,
+                CString::new(ice_server.password.as_str())?,
+            ));
+        }
+        let rffi_ice_servers: Vec<RffiIceServer> = strings
+            .iter()
+            .map(|(url, username, password)| RffiIceServer {
+                url:      url.as_ptr(),
+                username: username.as_ptr(),
+                password: password.as_ptr(),
+            })
+            .collect();
+
+        let set_ok = unsafe {
+            pc::Rust_setIceServers(
+                self.rffi_pc_interface,
+                rffi_ice_servers.as_ptr(),
+                rffi_ice_servers.len(),
+            )
+        };
+        if set_ok {
+            Ok(())
+        } else {
+            Err(RingRtcError::SetIceServers(ice_servers.len()).into())
+        }
+    }
+
+    /// Rust wrapper around C++ PeerConnectionInterface::RestartIce().
+    pub fn restart_ice(&self) {
+        unsafe { pc::Rust_restartIce(self.rffi_pc_interface) };
+    }
+
+    /// Export the SRTP keys negotiated by the DTLS transport of the
+    /// media, with the keying material exporter of RFC 5705.
+    #[cfg(feature = "srtp_key_export")]
+    pub fn export_srtp_keys(&self) -> Result<SrtpKeys> {
+        let mut rffi_material = RffiSrtpKeyingMaterial {
+            crypto_suite:  0,
+            dtls_client:   false,
+            material:      [0; MAX_SRTP_KEYING_MATERIAL],
+            material_size: 0,
+        };
+
+        let export_ok = unsafe {
+            pc::Rust_exportSrtpKeyingMaterial(self.rffi_pc_interface, &mut rffi_material)
+        };
+        if !export_ok || rffi_material.material_size > MAX_SRTP_KEYING_MATERIAL {
+            return Err(RingRtcError::ExportSrtpKeyingMaterial.into());
+        }
+
+        SrtpKeys::from_keying_material(
+            SrtpProtectionProfile::from_i32(rffi_material.crypto_suite)?,
+            rffi_material.dtls_client,
+            &rffi_material.material[..rffi_material.material_size],
+        )
+    }
+
+    /// Rust wrapper around C++ PeerConnectionInterface::GetStats().
+    pub fn get_stats(&self, stats_observer: &StatsObserver) {
+        unsafe { pc::Rust_getStats(self.rffi_pc_interface, stats_observer.rffi_observer()) };
+    }
 }
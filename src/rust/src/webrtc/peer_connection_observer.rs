@@ -9,7 +9,11 @@
 
 use std::fmt;
 use std::marker::PhantomData;
+use std::os::raw::c_char;
 use std::ptr;
+use std::slice;
+
+use libc::size_t;
 
 use crate::common::{Result, DATA_CHANNEL_NAME};
 use crate::core::connection::Connection;
@@ -17,8 +21,9 @@ use crate::core::platform::Platform;
 use crate::core::util::{ptr_as_mut, CppObject, RustObject};
 use crate::error::RingRtcError;
 use crate::webrtc::data_channel::DataChannel;
-use crate::webrtc::ice_candidate::{CppIceCandidate, IceCandidate};
-use crate::webrtc::media_stream::{MediaStream, RffiMediaStreamInterface};
+use crate::webrtc::ice_candidate::{CppIceCandidate, CppIceRoute, IceCandidate, IceRoute};
+use crate::webrtc::media::{AudioDirection, AudioFrame};
+use crate::webrtc::media_stream::{MediaStream, RffiMediaStreamInterface, VideoFrame};
 use crate::webrtc::peer_connection::RffiDataChannelInterface;
 
 /// Rust version of WebRTC RTCSignalingState enum
@@ -42,14 +47,19 @@ enum SignalingState {
 /// See [WebRTC
 /// RTCIceGatheringState](https://www.w3.org/TR/webrtc/#rtcicegatheringstate-enum)
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
-#[allow(dead_code)]
-enum IceGatheringState {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IceGatheringState {
     New,
     Gathering,
     Complete,
 }
 
+impl fmt::Display for IceGatheringState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// Rust version of WebRTC RTCPeerConnectionState enum
 ///
 /// See [WebRTC
@@ -236,11 +246,42 @@ extern "C" fn pc_observer_OnIceGatheringChange<T>(
             connection.id(),
             new_state
         );
+        connection
+            .inject_ice_gathering_state_changed(new_state)
+            .unwrap_or_else(|e| error!("Problems adding ice gathering event to fsm: {}", e));
     } else {
         warn!("pc_observer_OnIceGatheringChange(): ptr_as_mut() failed.");
     }
 }
 
+/// PeerConnectionObserver OnIceSelectedCandidatePairChanged() callback.
+#[allow(non_snake_case)]
+extern "C" fn pc_observer_OnIceSelectedCandidatePairChanged<T>(
+    connection_ptr: *mut Connection<T>,
+    route: *const CppIceRoute,
+) where
+    T: Platform,
+{
+    let object = unsafe { ptr_as_mut(connection_ptr) };
+    if let Ok(connection) = object {
+        if route.is_null() {
+            warn!("pc_observer_OnIceSelectedCandidatePairChanged(): null route");
+            return;
+        }
+        let route = IceRoute::from(unsafe { &*route });
+        info!(
+            "pc_observer_OnIceSelectedCandidatePairChanged(): {}, route: {}",
+            connection.id(),
+            route
+        );
+        connection
+            .inject_ice_route_changed(route)
+            .unwrap_or_else(|e| error!("Problems adding ice route event to fsm: {}", e));
+    } else {
+        warn!("pc_observer_OnIceSelectedCandidatePairChanged(): ptr_as_mut() failed.");
+    }
+}
+
 /// PeerConnectionObserver OnAddStream() callback.
 #[allow(non_snake_case)]
 extern "C" fn pc_observer_OnAddStream<T>(
@@ -316,6 +357,9 @@ where
     let object = unsafe { ptr_as_mut(connection_ptr) };
     if let Ok(connection) = object {
         info!("pc_observer_OnRenegotiationNeeded(): {}", connection.id());
+        connection
+            .inject_negotiation_needed()
+            .unwrap_or_else(|e| error!("Problems adding negotiation_needed to fsm: {}", e));
     } else {
         warn!("pc_observer_OnRenegotiationNeeded(): ptr_as_mut() failed.");
     }
@@ -349,6 +393,167 @@ where
     }
 }
 
+/// PeerConnectionObserver OnVideoRotationChanged() callback.
+///
+/// Not part of the WebRTC PeerConnectionObserver interface, reported
+/// by the sink attached to the remote video track.
+#[allow(non_snake_case)]
+extern "C" fn pc_observer_OnVideoRotationChanged<T>(
+    connection_ptr: *mut Connection<T>,
+    rotation: i32,
+) where
+    T: Platform,
+{
+    let object = unsafe { ptr_as_mut(connection_ptr) };
+    if let Ok(connection) = object {
+        info!(
+            "pc_observer_OnVideoRotationChanged(): {}, rotation: {}",
+            connection.id(),
+            rotation
+        );
+        connection
+            .inject_remote_video_orientation(rotation)
+            .unwrap_or_else(|e| error!("Problems adding video rotation event to fsm: {}", e));
+    } else {
+        warn!("pc_observer_OnVideoRotationChanged(): ptr_as_mut() failed.");
+    }
+}
+
+/// PeerConnectionObserver OnDtmfReceived() callback.
+///
+/// Not part of the WebRTC PeerConnectionObserver interface, reported
+/// when a telephone-event is received on the remote audio track.
+#[allow(non_snake_case)]
+extern "C" fn pc_observer_OnDtmfReceived<T>(connection_ptr: *mut Connection<T>, tone: c_char)
+where
+    T: Platform,
+{
+    let object = unsafe { ptr_as_mut(connection_ptr) };
+    if let Ok(connection) = object {
+        let tone = tone as u8 as char;
+        info!(
+            "pc_observer_OnDtmfReceived(): {}, tone: {}",
+            connection.id(),
+            tone
+        );
+        connection
+            .inject_received_dtmf(tone)
+            .unwrap_or_else(|e| error!("Problems adding received DTMF event to fsm: {}", e));
+    } else {
+        warn!("pc_observer_OnDtmfReceived(): ptr_as_mut() failed.");
+    }
+}
+
+/// PeerConnectionObserver OnRemoteMediaClosed() callback.
+///
+/// Not part of the WebRTC PeerConnectionObserver interface, reported
+/// when the remote peer closes its media, with an RTCP BYE or by
+/// closing the DTLS transport.
+#[allow(non_snake_case)]
+extern "C" fn pc_observer_OnRemoteMediaClosed<T>(connection_ptr: *mut Connection<T>)
+where
+    T: Platform,
+{
+    let object = unsafe { ptr_as_mut(connection_ptr) };
+    if let Ok(connection) = object {
+        info!("pc_observer_OnRemoteMediaClosed(): {}", connection.id());
+        connection
+            .inject_remote_media_closed()
+            .unwrap_or_else(|e| error!("Problems adding remote_media_closed to fsm: {}", e));
+    } else {
+        warn!("pc_observer_OnRemoteMediaClosed(): ptr_as_mut() failed.");
+    }
+}
+
+/// PeerConnectionObserver OnVideoFrame() callback.
+///
+/// Not part of the WebRTC PeerConnectionObserver interface, reported
+/// by the sink attached to the remote video track for each decoded
+/// frame, as I420.
+#[allow(non_snake_case)]
+extern "C" fn pc_observer_OnVideoFrame<T>(
+    connection_ptr: *mut Connection<T>,
+    width: u32,
+    height: u32,
+    timestamp_us: i64,
+    buffer: *const u8,
+    length: size_t,
+) where
+    T: Platform,
+{
+    let object = unsafe { ptr_as_mut(connection_ptr) };
+    if let Ok(connection) = object {
+        if buffer.is_null() {
+            warn!("pc_observer_OnVideoFrame(): null frame buffer");
+            return;
+        }
+        let data = unsafe { slice::from_raw_parts(buffer, length) };
+        let frame = VideoFrame {
+            width,
+            height,
+            timestamp_us,
+            data: data.to_vec(),
+        };
+        connection
+            .on_incoming_video_frame(frame)
+            .unwrap_or_else(|e| error!("Problems handing over video frame: {}", e));
+    } else {
+        warn!("pc_observer_OnVideoFrame(): ptr_as_mut() failed.");
+    }
+}
+
+/// PeerConnectionObserver OnAudioFrame() callback.
+#[allow(non_snake_case)]
+extern "C" fn pc_observer_OnAudioFrame<T>(
+    connection_ptr: *mut Connection<T>,
+    direction: AudioDirection,
+    sample_rate_hz: u32,
+    channels: u32,
+    samples: *const i16,
+    count: size_t,
+) where
+    T: Platform,
+{
+    let object = unsafe { ptr_as_mut(connection_ptr) };
+    if let Ok(connection) = object {
+        if samples.is_null() {
+            warn!("pc_observer_OnAudioFrame(): null samples");
+            return;
+        }
+        let samples = unsafe { slice::from_raw_parts(samples, count) };
+        let frame = AudioFrame {
+            direction,
+            sample_rate_hz,
+            channels,
+            samples: samples.to_vec(),
+        };
+        connection
+            .on_audio_frame(frame)
+            .unwrap_or_else(|e| error!("Problems handing over audio frame: {}", e));
+    } else {
+        warn!("pc_observer_OnAudioFrame(): ptr_as_mut() failed.");
+    }
+}
+
+/// PeerConnectionObserver OnAudioLevel() callback.
+#[allow(non_snake_case)]
+extern "C" fn pc_observer_OnAudioLevel<T>(
+    connection_ptr: *mut Connection<T>,
+    direction: AudioDirection,
+    level_dbov: u8,
+) where
+    T: Platform,
+{
+    let object = unsafe { ptr_as_mut(connection_ptr) };
+    if let Ok(connection) = object {
+        connection
+            .on_audio_level(direction, level_dbov)
+            .unwrap_or_else(|e| error!("Problems recording audio level: {}", e));
+    } else {
+        warn!("pc_observer_OnAudioLevel(): ptr_as_mut() failed.");
+    }
+}
+
 /// PeerConnectionObserver callback function pointers.
 ///
 /// A structure containing function pointers for each
@@ -359,19 +564,26 @@ pub struct PeerConnectionObserverCallbacks<T>
 where
     T: Platform,
 {
-    onIceCandidate:                 extern "C" fn(*mut Connection<T>, *const CppIceCandidate),
-    onIceCandidatesRemoved:         extern "C" fn(*mut Connection<T>),
-    onSignalingChange:              extern "C" fn(*mut Connection<T>, SignalingState),
-    onIceConnectionChange:          extern "C" fn(*mut Connection<T>, IceConnectionState),
-    onConnectionChange:             extern "C" fn(*mut Connection<T>, PeerConnectionState),
-    onIceConnectionReceivingChange: extern "C" fn(*mut Connection<T>),
-    onIceGatheringChange:           extern "C" fn(*mut Connection<T>, IceGatheringState),
+    onIceCandidate:                    extern "C" fn(*mut Connection<T>, *const CppIceCandidate),
+    onIceCandidatesRemoved:            extern "C" fn(*mut Connection<T>),
+    onSignalingChange:                 extern "C" fn(*mut Connection<T>, SignalingState),
+    onIceConnectionChange:             extern "C" fn(*mut Connection<T>, IceConnectionState),
+    onConnectionChange:                extern "C" fn(*mut Connection<T>, PeerConnectionState),
+    onIceConnectionReceivingChange:    extern "C" fn(*mut Connection<T>),
+    onIceGatheringChange:              extern "C" fn(*mut Connection<T>, IceGatheringState),
     onAddStream: extern "C" fn(*mut Connection<T>, *const RffiMediaStreamInterface),
-    onRemoveStream:                 extern "C" fn(*mut Connection<T>),
+    onRemoveStream:                    extern "C" fn(*mut Connection<T>),
     onDataChannel: extern "C" fn(*mut Connection<T>, *const RffiDataChannelInterface),
-    onRenegotiationNeeded:          extern "C" fn(*mut Connection<T>),
-    onAddTrack:                     extern "C" fn(*mut Connection<T>),
-    onTrack:                        extern "C" fn(*mut Connection<T>),
+    onRenegotiationNeeded:             extern "C" fn(*mut Connection<T>),
+    onAddTrack:                        extern "C" fn(*mut Connection<T>),
+    onTrack:                           extern "C" fn(*mut Connection<T>),
+    onVideoRotationChanged:            extern "C" fn(*mut Connection<T>, i32),
+    onIceSelectedCandidatePairChanged: extern "C" fn(*mut Connection<T>, *const CppIceRoute),
+    onDtmfReceived:                    extern "C" fn(*mut Connection<T>, c_char),
+    onRemoteMediaClosed:               extern "C" fn(*mut Connection<T>),
+    onVideoFrame: extern "C" fn(*mut Connection<T>, u32, u32, i64, *const u8, size_t),
+    onAudioFrame: extern "C" fn(*mut Connection<T>, AudioDirection, u32, u32, *const i16, size_t),
+    onAudioLevel:                      extern "C" fn(*mut Connection<T>, AudioDirection, u8),
 }
 
 #[cfg(not(feature = "sim"))]
@@ -436,19 +648,26 @@ where
     pub fn new(connection_ptr: *mut Connection<T>) -> Result<Self> {
         debug!("create_pc_observer(): connection_ptr: {:p}", connection_ptr);
         let pc_observer_callbacks = PeerConnectionObserverCallbacks::<T> {
-            onIceCandidate:                 pc_observer_OnIceCandidate::<T>,
-            onIceCandidatesRemoved:         pc_observer_OnIceCandidatesRemoved::<T>,
-            onSignalingChange:              pc_observer_OnSignalingChange::<T>,
-            onIceConnectionChange:          pc_observer_OnIceConnectionChange::<T>,
-            onConnectionChange:             pc_observer_OnConnectionChange::<T>,
-            onIceConnectionReceivingChange: pc_observer_OnIceConnectionReceivingChange::<T>,
-            onIceGatheringChange:           pc_observer_OnIceGatheringChange::<T>,
-            onAddStream:                    pc_observer_OnAddStream::<T>,
-            onRemoveStream:                 pc_observer_OnRemoveStream::<T>,
-            onDataChannel:                  pc_observer_OnDataChannel::<T>,
-            onRenegotiationNeeded:          pc_observer_OnRenegotiationNeeded::<T>,
-            onAddTrack:                     pc_observer_OnAddTrack::<T>,
-            onTrack:                        pc_observer_OnTrack::<T>,
+            onIceCandidate:                    pc_observer_OnIceCandidate::<T>,
+            onIceCandidatesRemoved:            pc_observer_OnIceCandidatesRemoved::<T>,
+            onSignalingChange:                 pc_observer_OnSignalingChange::<T>,
+            onIceConnectionChange:             pc_observer_OnIceConnectionChange::<T>,
+            onConnectionChange:                pc_observer_OnConnectionChange::<T>,
+            onIceConnectionReceivingChange:    pc_observer_OnIceConnectionReceivingChange::<T>,
+            onIceGatheringChange:              pc_observer_OnIceGatheringChange::<T>,
+            onAddStream:                       pc_observer_OnAddStream::<T>,
+            onRemoveStream:                    pc_observer_OnRemoveStream::<T>,
+            onDataChannel:                     pc_observer_OnDataChannel::<T>,
+            onRenegotiationNeeded:             pc_observer_OnRenegotiationNeeded::<T>,
+            onAddTrack:                        pc_observer_OnAddTrack::<T>,
+            onTrack:                           pc_observer_OnTrack::<T>,
+            onVideoRotationChanged:            pc_observer_OnVideoRotationChanged::<T>,
+            onIceSelectedCandidatePairChanged: pc_observer_OnIceSelectedCandidatePairChanged::<T>,
+            onDtmfReceived:                    pc_observer_OnDtmfReceived::<T>,
+            onRemoteMediaClosed:               pc_observer_OnRemoteMediaClosed::<T>,
+            onVideoFrame:                      pc_observer_OnVideoFrame::<T>,
+            onAudioFrame:                      pc_observer_OnAudioFrame::<T>,
+            onAudioLevel:                      pc_observer_OnAudioLevel::<T>,
         };
         let pc_observer_callbacks_ptr: *const PeerConnectionObserverCallbacks<T> =
             &pc_observer_callbacks;
@@ -87,6 +87,15 @@ impl SessionDescriptionInterface {
         }
         Ok(SessionDescriptionInterface::new(offer))
     }
+
+    /// Create a SDP rollback, returning the signaling state to stable.
+    pub fn create_sdp_rollback() -> Result<Self> {
+        let rollback = unsafe { sdp::Rust_createSessionDescriptionRollback() };
+        if rollback.is_null() {
+            return Err(RingRtcError::CreateSdpRollback.into());
+        }
+        Ok(SessionDescriptionInterface::new(rollback))
+    }
 }
 
 #[cfg(not(feature = "sim"))]
@@ -10,6 +10,7 @@
 use std::ffi::CString;
 use std::os::raw::c_char;
 use std::ptr;
+use std::sync::atomic::Ordering;
 
 use libc::{size_t, strdup};
 
@@ -18,15 +19,27 @@ use crate::webrtc::peer_connection::RffiDataChannelInterface;
 
 #[allow(non_snake_case)]
 pub unsafe fn Rust_dataChannelSend(
-    _dc_interface: *const RffiDataChannelInterface,
+    dc_interface: *const RffiDataChannelInterface,
     _buffer: *const u8,
-    _len: size_t,
+    len: size_t,
     _binary: bool,
 ) -> bool {
     info!("Rust_dataChannelSend(): ");
+    if let Some(buffered_amount) = dc_interface.as_ref() {
+        let _ = buffered_amount.fetch_add(len as u64, Ordering::AcqRel);
+    }
     true
 }
 
+#[allow(non_snake_case)]
+pub unsafe fn Rust_dataChannelBufferedAmount(dc_interface: *const RffiDataChannelInterface) -> u64 {
+    info!("Rust_dataChannelBufferedAmount(): ");
+    match dc_interface.as_ref() {
+        Some(buffered_amount) => buffered_amount.load(Ordering::Acquire),
+        None => 0,
+    }
+}
+
 #[allow(non_snake_case)]
 pub unsafe fn Rust_registerDataChannelObserver(
     _dc_interface: *const RffiDataChannelInterface,
@@ -7,23 +7,32 @@
 
 //! WebRTC Simulation Peer Connection Interface
 
+use std::ffi::c_void;
 use std::os::raw::c_char;
+use std::sync::atomic::AtomicU64;
 
+use libc::size_t;
+
+use crate::core::audio_config::AudioConfig;
+use crate::core::util::RustObject;
 use crate::webrtc::data_channel::RffiDataChannelInit;
+#[cfg(feature = "srtp_key_export")]
+use crate::webrtc::peer_connection::RffiSrtpKeyingMaterial;
+use crate::webrtc::peer_connection::{RffiIceServer, RffiSimulcastLayer};
 
 use crate::webrtc::sdp_observer::{
     RffiCreateSessionDescriptionObserver,
     RffiSessionDescriptionInterface,
     RffiSetSessionDescriptionObserver,
 };
+use crate::webrtc::stats_observer::RffiStatsObserver;
 
 /// Simulation type for PeerConnectionInterface.
 pub type RffiPeerConnectionInterface = u32;
 
-/// Simulation type for DataChannelInterface.
-pub type RffiDataChannelInterface = u32;
-
-static FAKE_DC_INTERFACE: u32 = 9;
+/// Simulation type for DataChannelInterface, holding the number of
+/// bytes buffered for sending.
+pub type RffiDataChannelInterface = AtomicU64;
 
 #[allow(non_snake_case)]
 pub unsafe fn Rust_createOffer(
@@ -66,7 +75,9 @@ pub unsafe fn Rust_createDataChannel(
     _config: *const RffiDataChannelInit,
 ) -> *const RffiDataChannelInterface {
     info!("Rust_createDataChannel():");
-    &FAKE_DC_INTERFACE
+    // Each channel buffers separately.  The simulated ref counting
+    // never frees the channel.
+    Box::into_raw(Box::new(AtomicU64::new(0)))
 }
 
 #[allow(non_snake_case)]
@@ -79,3 +90,200 @@ pub unsafe fn Rust_addIceCandidate(
     info!("Rust_addIceCandidate():");
     true
 }
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setVideoScaleResolutionDownBy(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    scale: f64,
+) -> bool {
+    info!("Rust_setVideoScaleResolutionDownBy(): scale: {}", scale);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setVideoMaxFramerate(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    max_framerate: u32,
+) -> bool {
+    info!(
+        "Rust_setVideoMaxFramerate(): max_framerate: {}",
+        max_framerate
+    );
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setVideoDegradationPreference(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    preference: i32,
+) -> bool {
+    info!(
+        "Rust_setVideoDegradationPreference(): preference: {}",
+        preference
+    );
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setSimulcastLayers(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    _layers: *const RffiSimulcastLayer,
+    layers_size: usize,
+) -> bool {
+    info!("Rust_setSimulcastLayers(): size: {}", layers_size);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setFrameCryptor(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    frame_cryptor: RustObject,
+    _frame_cryptor_cbs: *const c_void,
+) -> bool {
+    info!(
+        "Rust_setFrameCryptor(): attached: {}",
+        !frame_cryptor.is_null()
+    );
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setOutgoingAudioEnabled(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    enabled: bool,
+) -> bool {
+    info!("Rust_setOutgoingAudioEnabled(): enabled: {}", enabled);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setOutgoingVideoEnabled(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    enabled: bool,
+) -> bool {
+    info!("Rust_setOutgoingVideoEnabled(): enabled: {}", enabled);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setOutgoingScreenShareEnabled(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    enabled: bool,
+) -> bool {
+    info!("Rust_setOutgoingScreenShareEnabled(): enabled: {}", enabled);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setOutgoingAudioGain(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    gain: f64,
+) -> bool {
+    info!("Rust_setOutgoingAudioGain(): gain: {}", gain);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setAudioJitterBufferMinimumDelay(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    delay_seconds: f64,
+) -> bool {
+    info!(
+        "Rust_setAudioJitterBufferMinimumDelay(): delay_seconds: {}",
+        delay_seconds
+    );
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setAudioConfig(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    config: AudioConfig,
+) -> bool {
+    info!("Rust_setAudioConfig(): {}", config);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_insertDtmf(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    _tones: *const c_char,
+    duration_ms: i32,
+    gap_ms: i32,
+) -> bool {
+    info!(
+        "Rust_insertDtmf(): duration_ms: {}, gap_ms: {}",
+        duration_ms, gap_ms
+    );
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_pushOutgoingVideoFrame(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    width: u32,
+    height: u32,
+    timestamp_us: i64,
+    _buffer: *const u8,
+    length: size_t,
+) -> bool {
+    info!(
+        "Rust_pushOutgoingVideoFrame(): {}x{}, timestamp_us: {}, length: {}",
+        width, height, timestamp_us, length
+    );
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setMaxBitrate(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    bitrate_bps: i32,
+) -> bool {
+    info!("Rust_setMaxBitrate(): bitrate_bps: {}", bitrate_bps);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_setIceServers(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    _ice_servers: *const RffiIceServer,
+    ice_servers_size: usize,
+) -> bool {
+    info!("Rust_setIceServers(): size: {}", ice_servers_size);
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_restartIce(_pc_interface: *const RffiPeerConnectionInterface) {
+    info!("Rust_restartIce():");
+}
+
+#[cfg(feature = "srtp_key_export")]
+#[allow(non_snake_case)]
+pub unsafe fn Rust_exportSrtpKeyingMaterial(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    material: *mut RffiSrtpKeyingMaterial,
+) -> bool {
+    info!("Rust_exportSrtpKeyingMaterial():");
+
+    // SRTP_AES128_CM_SHA1_80, as the local DTLS client.
+    let material = &mut *material;
+    material.crypto_suite = 1;
+    material.dtls_client = true;
+    material.material_size = 2 * (16 + 14);
+    for (i, byte) in material.material[..material.material_size]
+        .iter_mut()
+        .enumerate()
+    {
+        *byte = i as u8;
+    }
+    true
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_getStats(
+    _pc_interface: *const RffiPeerConnectionInterface,
+    _stats_observer: *const RffiStatsObserver,
+) {
+    info!("Rust_getStats():");
+}
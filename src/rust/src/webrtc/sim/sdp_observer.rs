@@ -24,9 +24,11 @@ use crate::webrtc::sdp_observer::{
 /// Simulation type for SessionDescriptionInterface.
 pub type RffiSessionDescriptionInterface = &'static str;
 
-static FAKE_SDP: &str = "FAKE SDP";
+static FAKE_SDP: &str =
+    "FAKE SDP\r\na=fingerprint:sha-256 00:11:22:33:44:55:66:77:88:99:AA:BB:CC:DD:EE:FF";
 static FAKE_SDP_OFFER: &str = "FAKE SDP OFFER";
 static FAKE_SDP_ANSWER: &str = "FAKE SDP ANSWER";
+static FAKE_SDP_ROLLBACK: &str = "FAKE SDP ROLLBACK";
 
 /// Simulation type for webrtc::rffi::CreateSessionDescriptionObserverRffi
 pub type RffiCreateSessionDescriptionObserver = u32;
@@ -95,3 +97,9 @@ pub unsafe fn Rust_createSessionDescriptionOffer(
     info!("Rust_createSessionDescriptionOffer(): ");
     &FAKE_SDP_OFFER
 }
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_createSessionDescriptionRollback() -> *const RffiSessionDescriptionInterface {
+    info!("Rust_createSessionDescriptionRollback(): ");
+    &FAKE_SDP_ROLLBACK
+}
@@ -0,0 +1,149 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! WebRTC Simulation Stats Collector Interface.
+
+use std::ffi::{c_void, CString};
+
+use crate::core::util::RustObject;
+use crate::webrtc::media::MediaKind;
+use crate::webrtc::stats_observer::{
+    QualityLimitationReason,
+    RffiIceCandidatePairStats,
+    RffiInboundRtpStats,
+    RffiOutboundRtpStats,
+    RffiStatsReport,
+    RffiTrackStats,
+    StatsObserver,
+    StatsObserverCallbacks,
+};
+
+/// Simulation type for webrtc::rffi::StatsObserverRffi
+pub type RffiStatsObserver = u32;
+
+static FAKE_STATS_OBSERVER: u32 = 17;
+
+#[allow(non_snake_case)]
+pub unsafe fn Rust_createStatsObserver(
+    stats_observer: RustObject,
+    stats_observer_cbs: *const c_void,
+) -> *const RffiStatsObserver {
+    info!("Rust_createStatsObserver():");
+
+    let audio_codec = CString::new("audio/opus").unwrap();
+    let video_codec = CString::new("video/VP8").unwrap();
+    let ice_candidate_pairs = [
+        RffiIceCandidatePairStats {
+            nominated:                  false,
+            current_round_trip_time:    0.2,
+            available_outgoing_bitrate: 0.0,
+            available_incoming_bitrate: 0.0,
+            bytes_sent:                 0,
+            bytes_received:             0,
+            responses_received:         0,
+        },
+        RffiIceCandidatePairStats {
+            nominated:                  true,
+            current_round_trip_time:    0.05,
+            available_outgoing_bitrate: 1_000_000.0,
+            available_incoming_bitrate: 1_500_000.0,
+            bytes_sent:                 100_000,
+            bytes_received:             100_000,
+            responses_received:         20,
+        },
+    ];
+    let outbound_rtp = [
+        RffiOutboundRtpStats {
+            ssrc:                      1,
+            kind:                      MediaKind::Audio,
+            mime_type:                 audio_codec.as_ptr(),
+            packets_sent:              500,
+            bytes_sent:                40_000,
+            frame_width:               0,
+            frame_height:              0,
+            frames_per_second:         0.0,
+            quality_limitation_reason: QualityLimitationReason::None,
+        },
+        RffiOutboundRtpStats {
+            ssrc:                      2,
+            kind:                      MediaKind::Video,
+            mime_type:                 video_codec.as_ptr(),
+            packets_sent:              500,
+            bytes_sent:                60_000,
+            frame_width:               640,
+            frame_height:              360,
+            frames_per_second:         15.0,
+            quality_limitation_reason: QualityLimitationReason::Cpu,
+        },
+    ];
+    let inbound_rtp = [
+        RffiInboundRtpStats {
+            ssrc:                        3,
+            kind:                        MediaKind::Audio,
+            mime_type:                   audio_codec.as_ptr(),
+            packets_received:            500,
+            packets_lost:                5,
+            bytes_received:              40_000,
+            jitter:                      0.002,
+            jitter_buffer_delay:         3000.0,
+            jitter_buffer_emitted_count: 48_000,
+        },
+        RffiInboundRtpStats {
+            ssrc:                        4,
+            kind:                        MediaKind::Video,
+            mime_type:                   video_codec.as_ptr(),
+            packets_received:            500,
+            packets_lost:                5,
+            bytes_received:              60_000,
+            jitter:                      0.01,
+            jitter_buffer_delay:         15.0,
+            jitter_buffer_emitted_count: 300,
+        },
+    ];
+    let tracks = [
+        RffiTrackStats {
+            kind:              MediaKind::Audio,
+            remote_source:     false,
+            audio_level:       0.5,
+            frame_width:       0,
+            frame_height:      0,
+            frames_per_second: 0.0,
+        },
+        RffiTrackStats {
+            kind:              MediaKind::Audio,
+            remote_source:     true,
+            audio_level:       0.25,
+            frame_width:       0,
+            frame_height:      0,
+            frames_per_second: 0.0,
+        },
+        RffiTrackStats {
+            kind:              MediaKind::Video,
+            remote_source:     true,
+            audio_level:       0.0,
+            frame_width:       640,
+            frame_height:      480,
+            frames_per_second: 30.0,
+        },
+    ];
+    let report = RffiStatsReport {
+        ice_candidate_pairs:      ice_candidate_pairs.as_ptr(),
+        ice_candidate_pairs_size: ice_candidate_pairs.len(),
+        outbound_rtp:             outbound_rtp.as_ptr(),
+        outbound_rtp_size:        outbound_rtp.len(),
+        inbound_rtp:              inbound_rtp.as_ptr(),
+        inbound_rtp_size:         inbound_rtp.len(),
+        tracks:                   tracks.as_ptr(),
+        tracks_size:              tracks.len(),
+    };
+
+    // Hit the onStatsComplete() callback
+    let call_backs = stats_observer_cbs as *const StatsObserverCallbacks;
+    ((*call_backs).onStatsComplete)(stats_observer as *mut StatsObserver, &report);
+
+    &FAKE_STATS_OBSERVER
+}
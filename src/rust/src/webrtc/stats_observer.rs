@@ -0,0 +1,722 @@
+//
+// Copyright (C) 2020 Signal Messenger, LLC.
+// All rights reserved.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+//
+
+//! WebRTC Stats Collector Interface.
+//!
+//! C++ delivers the stats objects of an RTCStatsReport the Rust side
+//! uses, which are copied into the typed structs of a `StatsReport`.
+//! The stats polling of the call derives `CallStats` from these.
+
+use std::ffi::{c_void, CStr};
+use std::fmt;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Instant;
+
+use libc::size_t;
+
+use crate::common::Result;
+use crate::core::util::{ptr_as_ref, FutureResult, RustObject};
+use crate::core::video::{VideoAdaptation, VideoResolution};
+use crate::error::RingRtcError;
+use crate::webrtc::media::MediaKind;
+
+#[cfg(not(feature = "sim"))]
+use crate::webrtc::ffi::stats_observer as stats;
+#[cfg(not(feature = "sim"))]
+pub use crate::webrtc::ffi::stats_observer::RffiStatsObserver;
+
+#[cfg(feature = "sim")]
+use crate::webrtc::sim::stats_observer as stats;
+#[cfg(feature = "sim")]
+pub use crate::webrtc::sim::stats_observer::RffiStatsObserver;
+
+/// RTCIceCandidatePairStats, as delivered by C++.
+#[repr(C)]
+pub struct RffiIceCandidatePairStats {
+    pub nominated:                  bool,
+    pub current_round_trip_time:    f64,
+    pub available_outgoing_bitrate: f64,
+    pub available_incoming_bitrate: f64,
+    pub bytes_sent:                 u64,
+    pub bytes_received:             u64,
+    pub responses_received:         u64,
+}
+
+/// Why a sent video stream is below the captured resolution or
+/// framerate, the `qualityLimitationReason` of
+/// RTCOutboundRtpStreamStats.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum QualityLimitationReason {
+    #[default]
+    None,
+    Cpu,
+    Bandwidth,
+    Other,
+}
+
+impl fmt::Display for QualityLimitationReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// RTCOutboundRtpStreamStats, as delivered by C++.
+#[repr(C)]
+pub struct RffiOutboundRtpStats {
+    pub ssrc:                      u32,
+    pub kind:                      MediaKind,
+    pub mime_type:                 *const c_char,
+    pub packets_sent:              u64,
+    pub bytes_sent:                u64,
+    pub frame_width:               u32,
+    pub frame_height:              u32,
+    pub frames_per_second:         f64,
+    pub quality_limitation_reason: QualityLimitationReason,
+}
+
+/// RTCInboundRtpStreamStats, as delivered by C++.
+#[repr(C)]
+pub struct RffiInboundRtpStats {
+    pub ssrc:                        u32,
+    pub kind:                        MediaKind,
+    pub mime_type:                   *const c_char,
+    pub packets_received:            u64,
+    pub packets_lost:                i64,
+    pub bytes_received:              u64,
+    pub jitter:                      f64,
+    pub jitter_buffer_delay:         f64,
+    pub jitter_buffer_emitted_count: u64,
+}
+
+/// RTCMediaStreamTrackStats, as delivered by C++.
+#[repr(C)]
+pub struct RffiTrackStats {
+    pub kind:              MediaKind,
+    pub remote_source:     bool,
+    pub audio_level:       f64,
+    pub frame_width:       u32,
+    pub frame_height:      u32,
+    pub frames_per_second: f64,
+}
+
+/// The stats objects of an RTCStatsReport, as arrays owned by C++,
+/// see `StatsReport`.
+#[repr(C)]
+pub struct RffiStatsReport {
+    pub ice_candidate_pairs:      *const RffiIceCandidatePairStats,
+    pub ice_candidate_pairs_size: size_t,
+    pub outbound_rtp:             *const RffiOutboundRtpStats,
+    pub outbound_rtp_size:        size_t,
+    pub inbound_rtp:              *const RffiInboundRtpStats,
+    pub inbound_rtp_size:         size_t,
+    pub tracks:                   *const RffiTrackStats,
+    pub tracks_size:              size_t,
+}
+
+/// The stats of an ICE candidate pair.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IceCandidatePairStats {
+    /// Whether the pair was nominated to carry the media.
+    pub nominated:                  bool,
+    /// Round trip time of the pair, in seconds.
+    pub current_round_trip_time:    f64,
+    /// Bitrate available for sending, estimated by congestion
+    /// control, in bits per second.
+    pub available_outgoing_bitrate: f64,
+    /// Bitrate available for receiving, estimated by the receive side
+    /// of congestion control, in bits per second.
+    pub available_incoming_bitrate: f64,
+    pub bytes_sent:                 u64,
+    pub bytes_received:             u64,
+    /// STUN binding responses received, i.e. answered connectivity
+    /// and consent checks.
+    pub responses_received:         u64,
+}
+
+/// The stats of a sent RTP stream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutboundRtpStats {
+    pub ssrc:                      u32,
+    pub kind:                      MediaKind,
+    /// MIME type of the codec, e.g. `audio/opus`.
+    pub mime_type:                 Option<String>,
+    pub packets_sent:              u64,
+    pub bytes_sent:                u64,
+    /// The sent resolution and framerate, zero for audio.
+    pub frame_width:               u32,
+    pub frame_height:              u32,
+    pub frames_per_second:         f64,
+    /// Why the sent video is degraded, if it is.
+    pub quality_limitation_reason: QualityLimitationReason,
+}
+
+/// The stats of a received RTP stream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InboundRtpStats {
+    pub ssrc:                        u32,
+    pub kind:                        MediaKind,
+    /// MIME type of the codec, e.g. `audio/opus`.
+    pub mime_type:                   Option<String>,
+    pub packets_received:            u64,
+    pub packets_lost:                i64,
+    pub bytes_received:              u64,
+    /// Jitter of the stream, in seconds.
+    pub jitter:                      f64,
+    /// Sum of the time each sample spent in the jitter buffer, in
+    /// seconds.
+    pub jitter_buffer_delay:         f64,
+    /// Samples that left the jitter buffer.
+    pub jitter_buffer_emitted_count: u64,
+}
+
+/// The stats of a local or remote media track.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackStats {
+    pub kind:              MediaKind,
+    /// Whether the track is received rather than captured.
+    pub remote_source:     bool,
+    /// Level of an audio track, from 0.0 to 1.0.
+    pub audio_level:       f64,
+    pub frame_width:       u32,
+    pub frame_height:      u32,
+    pub frames_per_second: f64,
+}
+
+/// A snapshot of the statistics of a PeerConnection.
+///
+/// The packet and byte counters are cumulative since the
+/// PeerConnection was created.
+#[derive(Clone, Debug)]
+pub struct StatsReport {
+    /// When the report was delivered.
+    pub timestamp:           Instant,
+    pub ice_candidate_pairs: Vec<IceCandidatePairStats>,
+    pub outbound_rtp:        Vec<OutboundRtpStats>,
+    pub inbound_rtp:         Vec<InboundRtpStats>,
+    pub tracks:              Vec<TrackStats>,
+}
+
+impl Default for StatsReport {
+    fn default() -> Self {
+        Self {
+            timestamp:           Instant::now(),
+            ice_candidate_pairs: Vec::new(),
+            outbound_rtp:        Vec::new(),
+            inbound_rtp:         Vec::new(),
+            tracks:              Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for StatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "rtt: {}, jitter: {}, packets_received: {}, packets_lost: {}, bytes_sent: {}, bytes_received: {}, audio_codec: {:?}, video_codec: {:?}, captured_audio_level: {}, received_audio_level: {}",
+            self.current_round_trip_time(),
+            self.jitter(),
+            self.packets_received(),
+            self.packets_lost(),
+            self.bytes_sent(),
+            self.bytes_received(),
+            self.audio_codec(),
+            self.video_codec(),
+            self.captured_audio_level(),
+            self.received_audio_level()
+        )
+    }
+}
+
+/// Copy an optional C string owned by C++.
+fn copy_c_string(string_ptr: *const c_char) -> Option<String> {
+    if string_ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(string_ptr).to_string_lossy().into_owned() })
+    }
+}
+
+/// Copy an array of stats objects owned by C++.
+fn copy_array<R, S>(array: *const R, size: size_t, copy: impl Fn(&R) -> S) -> Vec<S> {
+    if array.is_null() {
+        Vec::new()
+    } else {
+        unsafe { slice::from_raw_parts(array, size) }
+            .iter()
+            .map(copy)
+            .collect()
+    }
+}
+
+impl StatsReport {
+    /// Copy a report delivered by C++, which only lives for the
+    /// duration of the callback.
+    fn from_rffi(report: &RffiStatsReport) -> Self {
+        Self {
+            timestamp:           Instant::now(),
+            ice_candidate_pairs: copy_array(
+                report.ice_candidate_pairs,
+                report.ice_candidate_pairs_size,
+                |pair| IceCandidatePairStats {
+                    nominated:                  pair.nominated,
+                    current_round_trip_time:    pair.current_round_trip_time,
+                    available_outgoing_bitrate: pair.available_outgoing_bitrate,
+                    available_incoming_bitrate: pair.available_incoming_bitrate,
+                    bytes_sent:                 pair.bytes_sent,
+                    bytes_received:             pair.bytes_received,
+                    responses_received:         pair.responses_received,
+                },
+            ),
+            outbound_rtp:        copy_array(report.outbound_rtp, report.outbound_rtp_size, |rtp| {
+                OutboundRtpStats {
+                    ssrc:                      rtp.ssrc,
+                    kind:                      rtp.kind,
+                    mime_type:                 copy_c_string(rtp.mime_type),
+                    packets_sent:              rtp.packets_sent,
+                    bytes_sent:                rtp.bytes_sent,
+                    frame_width:               rtp.frame_width,
+                    frame_height:              rtp.frame_height,
+                    frames_per_second:         rtp.frames_per_second,
+                    quality_limitation_reason: rtp.quality_limitation_reason,
+                }
+            }),
+            inbound_rtp:         copy_array(report.inbound_rtp, report.inbound_rtp_size, |rtp| {
+                InboundRtpStats {
+                    ssrc:                        rtp.ssrc,
+                    kind:                        rtp.kind,
+                    mime_type:                   copy_c_string(rtp.mime_type),
+                    packets_received:            rtp.packets_received,
+                    packets_lost:                rtp.packets_lost,
+                    bytes_received:              rtp.bytes_received,
+                    jitter:                      rtp.jitter,
+                    jitter_buffer_delay:         rtp.jitter_buffer_delay,
+                    jitter_buffer_emitted_count: rtp.jitter_buffer_emitted_count,
+                }
+            }),
+            tracks:              copy_array(report.tracks, report.tracks_size, |track| {
+                TrackStats {
+                    kind:              track.kind,
+                    remote_source:     track.remote_source,
+                    audio_level:       track.audio_level,
+                    frame_width:       track.frame_width,
+                    frame_height:      track.frame_height,
+                    frames_per_second: track.frames_per_second,
+                }
+            }),
+        }
+    }
+
+    /// Return the stats of the nominated ICE candidate pair, if any.
+    pub fn nominated_candidate_pair(&self) -> Option<&IceCandidatePairStats> {
+        self.ice_candidate_pairs.iter().find(|pair| pair.nominated)
+    }
+
+    /// Bitrate available for sending on the nominated ICE candidate
+    /// pair, in bits per second.
+    pub fn available_outgoing_bitrate(&self) -> f64 {
+        self.nominated_candidate_pair()
+            .map_or(0.0, |pair| pair.available_outgoing_bitrate)
+    }
+
+    /// Bitrate available for receiving on the nominated ICE candidate
+    /// pair, in bits per second.
+    pub fn available_incoming_bitrate(&self) -> f64 {
+        self.nominated_candidate_pair()
+            .map_or(0.0, |pair| pair.available_incoming_bitrate)
+    }
+
+    /// STUN binding responses received on the nominated ICE
+    /// candidate pair, which keep its consent to send fresh.
+    pub fn consent_responses_received(&self) -> u64 {
+        self.nominated_candidate_pair()
+            .map_or(0, |pair| pair.responses_received)
+    }
+
+    /// Round trip time of the nominated ICE candidate pair, in
+    /// seconds.
+    pub fn current_round_trip_time(&self) -> f64 {
+        self.nominated_candidate_pair()
+            .map_or(0.0, |pair| pair.current_round_trip_time)
+    }
+
+    /// Return the stats of the received audio, if any.
+    fn received_audio(&self) -> Option<&InboundRtpStats> {
+        self.inbound_rtp
+            .iter()
+            .find(|rtp| rtp.kind == MediaKind::Audio)
+    }
+
+    /// Jitter of the received audio, in seconds.
+    pub fn jitter(&self) -> f64 {
+        self.received_audio().map_or(0.0, |rtp| rtp.jitter)
+    }
+
+    /// The (delay, emitted count) jitter buffer counters of the
+    /// received audio, see `InboundRtpStats`.
+    pub fn audio_jitter_buffer(&self) -> (f64, u64) {
+        self.received_audio().map_or((0.0, 0), |rtp| {
+            (rtp.jitter_buffer_delay, rtp.jitter_buffer_emitted_count)
+        })
+    }
+
+    /// Packets received on all RTP streams.
+    pub fn packets_received(&self) -> u64 {
+        self.inbound_rtp
+            .iter()
+            .map(|rtp| rtp.packets_received)
+            .sum()
+    }
+
+    /// Packets lost on all received RTP streams.
+    pub fn packets_lost(&self) -> i64 {
+        self.inbound_rtp.iter().map(|rtp| rtp.packets_lost).sum()
+    }
+
+    /// Bytes sent on all RTP streams.
+    pub fn bytes_sent(&self) -> u64 {
+        self.outbound_rtp.iter().map(|rtp| rtp.bytes_sent).sum()
+    }
+
+    /// Bytes received on all RTP streams.
+    pub fn bytes_received(&self) -> u64 {
+        self.inbound_rtp.iter().map(|rtp| rtp.bytes_received).sum()
+    }
+
+    /// MIME type of the sent codec of `kind`.
+    fn sent_codec(&self, kind: MediaKind) -> Option<String> {
+        self.outbound_rtp
+            .iter()
+            .find(|rtp| rtp.kind == kind)
+            .and_then(|rtp| rtp.mime_type.clone())
+    }
+
+    /// MIME type of the sent audio codec, e.g. `audio/opus`.
+    pub fn audio_codec(&self) -> Option<String> {
+        self.sent_codec(MediaKind::Audio)
+    }
+
+    /// MIME type of the sent video codec, e.g. `video/VP8`.
+    pub fn video_codec(&self) -> Option<String> {
+        self.sent_codec(MediaKind::Video)
+    }
+
+    /// The adaptation of the sent video, if video is sent.
+    ///
+    /// When the video is simulcast, the highest resolution layer
+    /// being sent is reported.
+    pub fn video_adaptation(&self) -> Option<VideoAdaptation> {
+        self.outbound_rtp
+            .iter()
+            .filter(|rtp| {
+                rtp.kind == MediaKind::Video && rtp.frame_width > 0 && rtp.frame_height > 0
+            })
+            .max_by_key(|rtp| rtp.frame_width * rtp.frame_height)
+            .map(|rtp| VideoAdaptation {
+                limitation:        rtp.quality_limitation_reason,
+                resolution:        VideoResolution::new(rtp.frame_width, rtp.frame_height),
+                frames_per_second: rtp.frames_per_second,
+            })
+    }
+
+    /// Level of the audio track captured, or received if
+    /// `remote_source`, from 0.0 to 1.0.
+    fn audio_level(&self, remote_source: bool) -> f64 {
+        self.tracks
+            .iter()
+            .find(|track| track.kind == MediaKind::Audio && track.remote_source == remote_source)
+            .map_or(0.0, |track| track.audio_level)
+    }
+
+    /// Level of the captured audio, from 0.0 to 1.0.
+    pub fn captured_audio_level(&self) -> f64 {
+        self.audio_level(false)
+    }
+
+    /// Level of the received audio, from 0.0 to 1.0.
+    pub fn received_audio_level(&self) -> f64 {
+        self.audio_level(true)
+    }
+}
+
+/// Observer object for collecting the statistics of a
+/// PeerConnection.
+#[derive(Debug)]
+pub struct StatsObserver {
+    /// condition variable used to signal the delivery of the stats
+    /// report.
+    condition:           FutureResult<StatsReport>,
+    /// Pointer to C++ webrtc::rffi::StatsObserverRffi object
+    rffi_stats_observer: *const RffiStatsObserver,
+}
+
+impl StatsObserver {
+    /// Create a new StatsObserver.
+    fn new() -> Self {
+        Self {
+            condition:           Arc::new((
+                Mutex::new((false, StatsReport::default())),
+                Condvar::new(),
+            )),
+            rffi_stats_observer: ptr::null(),
+        }
+    }
+
+    /// Called back when the stats report is delivered.
+    ///
+    /// This call signals the condition variable.
+    fn on_stats_complete(&self, report: StatsReport) {
+        info!("on_stats_complete()");
+        let (mtx, cvar) = &*self.condition;
+        if let Ok(mut guard) = mtx.lock() {
+            guard.1 = report;
+            guard.0 = true;
+            // We notify the condvar that the value has changed.
+            cvar.notify_one();
+        }
+    }
+
+    /// Retrieve the delivered stats report.
+    ///
+    /// This call blocks on the condition variable.
+    pub fn get_result(&self) -> Result<StatsReport> {
+        let (mtx, cvar) = &*self.condition;
+        if let Ok(mut guard) = mtx.lock() {
+            while !guard.0 {
+                guard = cvar.wait(guard).map_err(|_| {
+                    RingRtcError::MutexPoisoned("StatsObserver condvar mutex".to_string())
+                })?;
+            }
+            Ok(guard.1.clone())
+        } else {
+            Err(RingRtcError::MutexPoisoned("StatsObserver condvar mutex".to_string()).into())
+        }
+    }
+
+    /// Set the RFFI observer object.
+    pub fn set_rffi_observer(&mut self, observer: *const RffiStatsObserver) {
+        self.rffi_stats_observer = observer
+    }
+
+    /// Return the RFFI observer object.
+    pub fn rffi_observer(&self) -> *const RffiStatsObserver {
+        self.rffi_stats_observer
+    }
+}
+
+/// Stats observer OnStatsComplete() callback.
+#[no_mangle]
+#[allow(non_snake_case)]
+extern "C" fn stats_observer_OnStatsComplete(
+    stats_observer: *mut StatsObserver,
+    report: *const RffiStatsReport,
+) {
+    info!("stats_observer_OnStatsComplete()");
+    let report = match unsafe { ptr_as_ref(report as *mut RffiStatsReport) } {
+        Ok(v) => StatsReport::from_rffi(v),
+        Err(e) => {
+            error!("stats_observer_OnStatsComplete(): {}", e);
+            return;
+        }
+    };
+
+    match unsafe { ptr_as_ref(stats_observer) } {
+        Ok(v) => v.on_stats_complete(report),
+        Err(e) => error!("stats_observer_OnStatsComplete(): {}", e),
+    };
+}
+
+/// Stats observer callback function pointers.
+#[repr(C)]
+#[allow(non_snake_case)]
+pub struct StatsObserverCallbacks {
+    pub onStatsComplete:
+        extern "C" fn(stats_observer: *mut StatsObserver, report: *const RffiStatsReport),
+}
+
+const STATS_OBSERVER_CBS: StatsObserverCallbacks = StatsObserverCallbacks {
+    onStatsComplete: stats_observer_OnStatsComplete,
+};
+const STATS_OBSERVER_CBS_PTR: *const StatsObserverCallbacks = &STATS_OBSERVER_CBS;
+
+/// Create a new Rust StatsObserver object.
+///
+/// Creates a new WebRTC C++ StatsObserver object, registering the
+/// observer callbacks to this module, and wraps the result in a Rust
+/// StatsObserver object.
+pub fn create_stats_observer() -> Box<StatsObserver> {
+    let stats_observer = Box::new(StatsObserver::new());
+    let stats_observer_ptr = Box::into_raw(stats_observer);
+    let rffi_stats_observer = unsafe {
+        stats::Rust_createStatsObserver(
+            stats_observer_ptr as RustObject,
+            STATS_OBSERVER_CBS_PTR as *const c_void,
+        )
+    };
+    let mut stats_observer = unsafe { Box::from_raw(stats_observer_ptr) };
+
+    stats_observer.set_rffi_observer(rffi_stats_observer);
+    stats_observer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ffi::CString;
+
+    #[test]
+    fn check_from_rffi() {
+        let opus = CString::new("audio/opus").unwrap();
+        let ice_candidate_pairs = [
+            RffiIceCandidatePairStats {
+                nominated:                  false,
+                current_round_trip_time:    0.3,
+                available_outgoing_bitrate: 0.0,
+                available_incoming_bitrate: 0.0,
+                bytes_sent:                 0,
+                bytes_received:             0,
+                responses_received:         0,
+            },
+            RffiIceCandidatePairStats {
+                nominated:                  true,
+                current_round_trip_time:    0.1,
+                available_outgoing_bitrate: 500_000.0,
+                available_incoming_bitrate: 800_000.0,
+                bytes_sent:                 2000,
+                bytes_received:             1000,
+                responses_received:         12,
+            },
+        ];
+        let outbound_rtp = [RffiOutboundRtpStats {
+            ssrc:                      7,
+            kind:                      MediaKind::Audio,
+            mime_type:                 opus.as_ptr(),
+            packets_sent:              20,
+            bytes_sent:                2000,
+            frame_width:               0,
+            frame_height:              0,
+            frames_per_second:         0.0,
+            quality_limitation_reason: QualityLimitationReason::None,
+        }];
+        let report = RffiStatsReport {
+            ice_candidate_pairs:      ice_candidate_pairs.as_ptr(),
+            ice_candidate_pairs_size: ice_candidate_pairs.len(),
+            outbound_rtp:             outbound_rtp.as_ptr(),
+            outbound_rtp_size:        outbound_rtp.len(),
+            inbound_rtp:              ptr::null(),
+            inbound_rtp_size:         0,
+            tracks:                   ptr::null(),
+            tracks_size:              0,
+        };
+
+        let report = StatsReport::from_rffi(&report);
+        assert_eq!(report.ice_candidate_pairs.len(), 2);
+        assert_eq!(
+            report.nominated_candidate_pair().map(|p| p.bytes_sent),
+            Some(2000)
+        );
+        assert_eq!(report.current_round_trip_time(), 0.1);
+        assert_eq!(report.available_outgoing_bitrate(), 500_000.0);
+        assert_eq!(report.available_incoming_bitrate(), 800_000.0);
+        assert_eq!(report.consent_responses_received(), 12);
+        assert_eq!(
+            report.outbound_rtp,
+            vec![OutboundRtpStats {
+                ssrc:                      7,
+                kind:                      MediaKind::Audio,
+                mime_type:                 Some("audio/opus".to_string()),
+                packets_sent:              20,
+                bytes_sent:                2000,
+                frame_width:               0,
+                frame_height:              0,
+                frames_per_second:         0.0,
+                quality_limitation_reason: QualityLimitationReason::None,
+            }]
+        );
+        assert_eq!(report.audio_codec(), Some("audio/opus".to_string()));
+        assert_eq!(report.video_codec(), None);
+        assert_eq!(report.video_adaptation(), None);
+        assert!(report.inbound_rtp.is_empty());
+        assert_eq!(report.packets_received(), 0);
+        assert_eq!(report.received_audio_level(), 0.0);
+    }
+
+    #[test]
+    fn check_totals() {
+        let inbound = |kind, packets_received, packets_lost, jitter| InboundRtpStats {
+            ssrc: 1,
+            kind,
+            mime_type: None,
+            packets_received,
+            packets_lost,
+            bytes_received: packets_received * 100,
+            jitter,
+            jitter_buffer_delay: jitter * 100.0,
+            jitter_buffer_emitted_count: packets_received,
+        };
+        let report = StatsReport {
+            inbound_rtp: vec![
+                inbound(MediaKind::Video, 90, 10, 0.05),
+                inbound(MediaKind::Audio, 45, 5, 0.02),
+            ],
+            tracks: vec![TrackStats {
+                kind:              MediaKind::Audio,
+                remote_source:     true,
+                audio_level:       0.75,
+                frame_width:       0,
+                frame_height:      0,
+                frames_per_second: 0.0,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(report.packets_received(), 135);
+        assert_eq!(report.packets_lost(), 15);
+        assert_eq!(report.bytes_received(), 13_500);
+        assert_eq!(report.bytes_sent(), 0);
+        assert_eq!(report.jitter(), 0.02);
+        assert_eq!(report.audio_jitter_buffer(), (2.0, 45));
+        assert_eq!(report.received_audio_level(), 0.75);
+        assert_eq!(report.captured_audio_level(), 0.0);
+        assert_eq!(report.current_round_trip_time(), 0.0);
+    }
+
+    #[test]
+    fn check_video_adaptation() {
+        let outbound = |width, height, quality_limitation_reason| OutboundRtpStats {
+            ssrc: 1,
+            kind: MediaKind::Video,
+            mime_type: None,
+            packets_sent: 0,
+            bytes_sent: 0,
+            frame_width: width,
+            frame_height: height,
+            frames_per_second: 15.0,
+            quality_limitation_reason,
+        };
+        let report = StatsReport {
+            outbound_rtp: vec![
+                outbound(320, 180, QualityLimitationReason::None),
+                outbound(640, 360, QualityLimitationReason::Cpu),
+                // A simulcast layer that is not sent.
+                outbound(0, 0, QualityLimitationReason::Bandwidth),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            report.video_adaptation(),
+            Some(VideoAdaptation {
+                limitation:        QualityLimitationReason::Cpu,
+                resolution:        VideoResolution::new(640, 360),
+                frames_per_second: 15.0,
+            })
+        );
+    }
+}
@@ -21,11 +21,31 @@ use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha20Rng;
 use simplelog::{Config, ConfigBuilder, SimpleLogger};
 
-use ringrtc::common::{ApplicationEvent, DeviceId};
+use ringrtc::common::{
+    ApplicationEvent,
+    AudioDeviceError,
+    CallId,
+    ConnectionId,
+    DeviceId,
+    HangupType,
+};
+use ringrtc::core::audio_route::AudioRoute;
 use ringrtc::core::call::Call;
+use ringrtc::core::call_failure::CallFailureReason;
 use ringrtc::core::call_manager::CallManager;
+use ringrtc::core::call_record::CallRecord;
+use ringrtc::core::call_stats::CallStats;
+use ringrtc::core::codec::CodecCapability;
 use ringrtc::core::connection::Connection;
+use ringrtc::core::connection_config::ConnectionConfig;
+use ringrtc::core::ice_server::IceServerConfig;
+use ringrtc::core::media_descriptor::MediaDescriptor;
+use ringrtc::core::offer_metadata::OfferMetadata;
+use ringrtc::core::pending_signaling::PendingSignaling;
+use ringrtc::core::signaling;
 use ringrtc::sim::sim_platform::SimPlatform;
+use ringrtc::webrtc::ice_candidate::NetworkAdapterType;
+use ringrtc::webrtc::peer_connection_observer::IceGatheringState;
 
 /*
 use ringrtc::common::{CallDirection, CallId};
@@ -164,26 +184,141 @@ impl TestContext {
         platform.force_signaling_fault(enable);
     }
 
+    pub fn force_audio_device_fault(&self, error: Option<AudioDeviceError>) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.force_audio_device_fault(error);
+    }
+
+    pub fn force_peer_connection_fault(&self, enable: bool) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.force_peer_connection_fault(enable);
+    }
+
+    pub fn force_ring_refusal(&self, enable: bool) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.force_ring_refusal(enable);
+    }
+
+    pub fn force_transfer_accept(&self, enable: bool) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.force_transfer_accept(enable);
+    }
+
+    pub fn set_turn_servers(&self, ice_servers: Vec<IceServerConfig>) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.set_turn_servers(ice_servers);
+    }
+
+    pub fn set_codec_capabilities(&self, capabilities: Vec<CodecCapability>) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.set_codec_capabilities(capabilities);
+    }
+
+    pub fn set_sdp_attribute(&self, attribute: Option<String>) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.set_sdp_attribute(attribute);
+    }
+
+    pub fn force_signaling_offline(&self, enable: bool) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.force_signaling_offline(enable);
+    }
+
+    pub fn set_sealing_key(&self, key: Option<u8>) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.set_sealing_key(key);
+    }
+
+    pub fn seal(&self, remote_peer: &str, connection_id: ConnectionId, opaque: Vec<u8>) -> Vec<u8> {
+        let platform = self.call_manager.platform().unwrap();
+        signaling::seal(&*platform, &remote_peer.to_owned(), connection_id, opaque).unwrap()
+    }
+
     pub fn offers_sent(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.offers_sent()
     }
 
+    pub fn restart_offers_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.restart_offers_sent()
+    }
+
     pub fn answers_sent(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.answers_sent()
     }
 
+    pub fn opaque_offers_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.opaque_offers_sent()
+    }
+
+    pub fn opaque_answers_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.opaque_answers_sent()
+    }
+
     pub fn ice_candidates_sent(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.ice_candidates_sent()
     }
 
+    pub fn ice_messages_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.ice_messages_sent()
+    }
+
+    pub fn opaque_ice_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.opaque_ice_sent()
+    }
+
+    pub fn sealed_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.sealed_sent()
+    }
+
     pub fn hangups_sent(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.hangups_sent()
     }
 
+    pub fn offer_metadata(&self) -> Vec<OfferMetadata> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.offer_metadata()
+    }
+
+    pub fn hangup_types(&self) -> Vec<HangupType> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.hangup_types()
+    }
+
+    pub fn hangup_devices(&self) -> Vec<Vec<DeviceId>> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.hangup_devices()
+    }
+
+    pub fn transformed_sdp(&self) -> Vec<String> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.transformed_sdp()
+    }
+
+    pub fn answer_metadata(&self) -> Vec<Vec<u8>> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.answer_metadata()
+    }
+
+    pub fn sent_answer_metadata(&self) -> Vec<Option<Vec<u8>>> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.sent_answer_metadata()
+    }
+
+    pub fn app_messages(&self) -> Vec<Vec<u8>> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.app_messages()
+    }
+
     pub fn error_count(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.error_count()
@@ -204,16 +339,171 @@ impl TestContext {
         platform.event_count(event)
     }
 
+    pub fn call_events(&self) -> Vec<(CallId, ApplicationEvent)> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.call_events()
+    }
+
     pub fn busys_sent(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.busys_sent()
     }
 
+    pub fn ringings_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.ringings_sent()
+    }
+
+    pub fn pre_offers_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.pre_offers_sent()
+    }
+
+    pub fn pre_offers_received(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.pre_offers_received()
+    }
+
     pub fn stream_count(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.stream_count()
     }
 
+    pub fn remote_video_orientations(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.remote_video_orientations()
+    }
+
+    pub fn remote_video_rotation(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.remote_video_rotation()
+    }
+
+    pub fn route_downgrades(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.route_downgrades()
+    }
+
+    pub fn route_upgrades(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.route_upgrades()
+    }
+
+    pub fn buffered_amount_low_count(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.buffered_amount_low_count()
+    }
+
+    pub fn duration_ticks(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.duration_ticks()
+    }
+
+    pub fn turn_requests(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.turn_requests()
+    }
+
+    pub fn ice_gathering_states(&self) -> Vec<IceGatheringState> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.ice_gathering_states()
+    }
+
+    pub fn network_routes(&self) -> Vec<(NetworkAdapterType, bool)> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.network_routes()
+    }
+
+    pub fn connection_configs(&self) -> Vec<ConnectionConfig> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.connection_configs()
+    }
+
+    pub fn media_descriptors(&self) -> Vec<MediaDescriptor> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.media_descriptors()
+    }
+
+    pub fn stats_reports(&self) -> Vec<CallStats> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.stats_reports()
+    }
+
+    pub fn audio_levels(&self) -> Vec<(f64, f64)> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.audio_levels()
+    }
+
+    pub fn bandwidth_estimates(&self) -> Vec<(u64, u64)> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.bandwidth_estimates()
+    }
+
+    pub fn call_records(&self) -> Vec<CallRecord> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.call_records()
+    }
+
+    pub fn call_failures(&self) -> Vec<CallFailureReason> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.call_failures()
+    }
+
+    pub fn received_dtmf(&self) -> String {
+        let platform = self.call_manager.platform().unwrap();
+        platform.received_dtmf()
+    }
+
+    pub fn safety_numbers(&self) -> Vec<String> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.safety_numbers()
+    }
+
+    pub fn transfer_requests(&self) -> Vec<String> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.transfer_requests()
+    }
+
+    pub fn pending_signaling(&self) -> Vec<PendingSignaling<String>> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.pending_signaling()
+    }
+
+    pub fn set_pending_signaling(&self, pending: Vec<PendingSignaling<String>>) {
+        let platform = self.call_manager.platform().unwrap();
+        platform.set_pending_signaling(pending);
+    }
+
+    pub fn participant_media(&self) -> Vec<(String, String)> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.participant_media()
+    }
+
+    pub fn audio_routes(&self) -> Vec<AudioRoute> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.audio_routes()
+    }
+
+    pub fn camera_states(&self) -> Vec<bool> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.camera_states()
+    }
+
+    pub fn camera_switches(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.camera_switches()
+    }
+
+    pub fn system_calls(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.system_calls()
+    }
+
+    pub fn system_holds(&self) -> Vec<bool> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.system_holds()
+    }
+
     pub fn start_outgoing_count(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.start_outgoing_count()
@@ -228,4 +518,9 @@ impl TestContext {
         let platform = self.call_manager.platform().unwrap();
         platform.call_concluded_count()
     }
+
+    pub fn shutdown_complete_count(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.shutdown_complete_count()
+    }
 }
@@ -13,7 +13,8 @@ extern crate ringrtc;
 extern crate log;
 
 use std::ptr;
-use std::time::SystemTime;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
 
 use ringrtc::common::{
     ApplicationEvent,
@@ -22,9 +23,22 @@ use ringrtc::common::{
     ConnectionId,
     ConnectionState,
     DeviceId,
+    HangupType,
+    NegotiationMessage,
 };
 
+use ringrtc::core::call_config::CallConfig;
+use ringrtc::core::codec::{
+    hardware_accelerated_codecs,
+    merge_capabilities,
+    CodecCapability,
+    CodecImplementation,
+};
+use ringrtc::core::signaling::{Offer, Renegotiation, LEGACY_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use ringrtc::core::trace::TraceKind;
+
 use ringrtc::webrtc::ice_candidate::IceCandidate;
+use ringrtc::webrtc::media::AudioDirection;
 use ringrtc::webrtc::media_stream::MediaStream;
 
 use ringrtc::webrtc::data_channel::DataChannel;
@@ -46,6 +60,12 @@ use common::{test_init, TestContext, PRNG};
 // - check answer sent
 // Now in the Connecting state.
 fn start_inbound_call() -> TestContext {
+    start_inbound_call_with_offer(format!("OFFER-{}", PRNG.gen::<u16>()).to_owned())
+}
+
+// Create an inbound call session up to the IceConnecting state,
+// receiving the given offer.
+fn start_inbound_call_with_offer(offer: String) -> TestContext {
     let context = TestContext::new();
     let mut cm = context.cm();
 
@@ -54,11 +74,13 @@ fn start_inbound_call() -> TestContext {
     cm.received_offer(
         remote_peer,
         connection_id,
-        format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
+        offer,
         SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect(error_line!())
             .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
     )
     .expect(error_line!());
 
@@ -79,6 +101,7 @@ fn start_inbound_call() -> TestContext {
         active_call.call_id(),
         format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
         remote_devices,
+        Vec::new(),
     )
     .expect(error_line!());
 
@@ -95,7 +118,7 @@ fn start_inbound_call() -> TestContext {
         1,
         format!("ICE-{}", PRNG.gen::<u16>()).to_owned(),
     );
-    cm.received_ice_candidates(remote_id, &[ice_candidate])
+    cm.received_ice_candidates(remote_id, &[ice_candidate], Duration::from_secs(0))
         .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
@@ -132,7 +155,13 @@ fn inbound_ice_connecting() {
 // Now in the CallConnected state.
 
 fn connect_inbound_call() -> TestContext {
-    let context = start_inbound_call();
+    connect_inbound_call_with_offer(format!("OFFER-{}", PRNG.gen::<u16>()).to_owned())
+}
+
+// Create an inbound call session up to the CallConnected state,
+// receiving the given offer.
+fn connect_inbound_call_with_offer(offer: String) -> TestContext {
+    let context = start_inbound_call_with_offer(offer);
     let mut cm = context.cm();
     let active_call = context.active_call();
     let mut active_connection = context.active_connection();
@@ -202,6 +231,177 @@ fn inbound_call_connected() {
     let _ = connect_inbound_call();
 }
 
+#[test]
+fn inbound_call_acknowledges_ringing() {
+    test_init();
+
+    let context = connect_inbound_call();
+
+    assert_eq!(context.ringings_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn inbound_call_screened_before_ringing() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let active_call = context.active_call();
+
+    let offer_metadata = context.offer_metadata();
+    assert_eq!(offer_metadata.len(), 1);
+    assert_eq!(offer_metadata[0].call_id, active_call.call_id());
+    assert_eq!(offer_metadata[0].remote_device, 1 as DeviceId);
+}
+
+#[test]
+fn inbound_call_refused_before_ringing() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    context.force_ring_refusal(true);
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    active_connection
+        .inject_on_data_channel(DataChannel::new(ptr::null()))
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.offer_metadata().len(), 1);
+    assert_eq!(context.event_count(ApplicationEvent::LocalRinging), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedRingRefused), 1);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.hangup_types(), vec![HangupType::Normal]);
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+}
+
+#[test]
+fn decline_inbound_call() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(context.hangups_sent(), 1);
+    assert_eq!(context.hangup_types(), vec![HangupType::Declined]);
+}
+
+#[test]
+fn hangup_connected_inbound_call() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(context.hangup_types(), vec![HangupType::Normal]);
+}
+
+#[test]
+fn shutdown_declines_ringing_inbound_call() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+
+    cm.shutdown().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(context.hangup_types(), vec![HangupType::Declined]);
+    assert_eq!(context.shutdown_complete_count(), 1);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+}
+
+#[test]
+fn inbound_call_accepted_elsewhere() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_hangup(remote_id, HangupType::Accepted)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteHangupAccepted),
+        1
+    );
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.hangups_sent(), 0);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+}
+
+#[test]
+fn inbound_call_declined_elsewhere() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_hangup(remote_id, HangupType::Declined)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteDeclined),
+        1
+    );
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.hangups_sent(), 0);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+}
+
+#[test]
+fn connected_inbound_call_ignores_accepted_hangup() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    // The caller announces this device accepted the call.
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_hangup(remote_id, HangupType::Accepted)
+        .expect(error_line!());
+    cm.received_hangup(remote_id, HangupType::Declined)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(cm.call_active().expect(error_line!()), true);
+}
+
 #[test]
 fn start_inbound_call_with_error() {
     test_init();
@@ -219,6 +419,8 @@ fn start_inbound_call_with_error() {
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect(error_line!())
             .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
     )
     .expect(error_line!());
 
@@ -242,6 +444,7 @@ fn start_inbound_call_with_error() {
         active_call.call_id(),
         format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
         remote_devices,
+        Vec::new(),
     )
     .expect(error_line!());
 
@@ -283,6 +486,8 @@ fn receive_offer_while_active() {
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect(error_line!())
             .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
     )
     .expect(error_line!());
 
@@ -298,32 +503,1406 @@ fn receive_offer_while_active() {
 }
 
 #[test]
-fn receive_expired_offer() {
+fn receive_offer_with_call_waiting() {
     test_init();
 
-    let context = TestContext::new();
+    let context = connect_inbound_call();
     let mut cm = context.cm();
+    let held_call = context.active_call();
+    let held_connection = context.active_connection();
 
-    // create off way in the past
+    cm.set_call_waiting(true).expect(error_line!());
+
+    info!("test: receiving a second call");
     let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
-    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    let call_id = CallId::new(PRNG.gen::<u64>());
     cm.received_offer(
         remote_peer,
-        connection_id,
+        ConnectionId::new(call_id, 1 as DeviceId),
         format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
         SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect(error_line!())
-            .as_millis() as u64
-            - 1000000,
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(context.start_incoming_count(), 2);
+    assert_eq!(
+        cm.active_call_ids().expect(error_line!()),
+        vec![held_call.call_id(), call_id]
+    );
+
+    let waiting_call = cm.active_call_by_id(call_id).expect(error_line!());
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        Vec::<DeviceId>::new(),
+        Vec::new(),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
+    let mut waiting_connection = waiting_call
+        .get_connection(1 as DeviceId)
+        .expect(error_line!());
+    waiting_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    waiting_connection
+        .inject_on_data_channel(DataChannel::new(ptr::null()))
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        waiting_call.state().expect(error_line!()),
+        CallState::Ringing
+    );
+    assert_eq!(
+        context.call_events().last(),
+        Some(&(call_id, ApplicationEvent::LocalRinging))
+    );
+
+    info!("test: accepting the waiting call");
+    waiting_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    cm.accept_call(call_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        waiting_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.active_call().call_id(), call_id);
+    assert!(held_connection.on_hold().expect(error_line!()));
+    assert_eq!(
+        held_connection.state().expect(error_line!()),
+        ConnectionState::OnHold
+    );
+
+    info!("test: hanging up the foreground call");
+    cm.hangup().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.call_events().last(),
+        Some(&(call_id, ApplicationEvent::EndedLocalHangup))
+    );
+    assert_eq!(context.active_call().call_id(), held_call.call_id());
+    assert_eq!(
+        held_call.state().expect(error_line!()),
+        CallState::Connected
+    );
     assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 1);
+}
+
+#[test]
+fn system_managed_calls() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let held_call = context.active_call();
+    let held_connection = context.active_connection();
+
+    cm.set_call_waiting(true).expect(error_line!());
+
+    info!("test: adding the call to the system");
+    cm.call_added_to_system(CallId::new(PRNG.gen::<u64>()))
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.system_calls(), 0);
+
+    cm.call_added_to_system(held_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.system_calls(), 1);
+    assert!(held_call.system_managed());
+
+    info!("test: holding and resuming the call from the system");
+    cm.hold_requested_by_system(held_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(held_connection.on_hold().expect(error_line!()));
+
+    cm.hold_requested_by_system(held_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(!held_connection.on_hold().expect(error_line!()));
+    assert!(context.system_holds().is_empty());
+
+    info!("test: receiving and accepting a second call");
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let call_id = CallId::new(PRNG.gen::<u64>());
+    cm.received_offer(
+        remote_peer,
+        ConnectionId::new(call_id, 1 as DeviceId),
+        format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let waiting_call = cm.active_call_by_id(call_id).expect(error_line!());
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        Vec::<DeviceId>::new(),
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let mut waiting_connection = waiting_call
+        .get_connection(1 as DeviceId)
+        .expect(error_line!());
+    waiting_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    waiting_connection
+        .inject_on_data_channel(DataChannel::new(ptr::null()))
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The system can't hold a call still ringing.
+    cm.hold_requested_by_system(call_id, true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
     assert_eq!(
-        context.event_count(ApplicationEvent::EndedReceivedOfferExpired),
-        1
+        waiting_call.state().expect(error_line!()),
+        CallState::Ringing
     );
+
+    waiting_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    cm.accept_call(call_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.active_call().call_id(), call_id);
+    assert!(held_connection.on_hold().expect(error_line!()));
+    assert_eq!(context.system_holds(), vec![true]);
+
+    info!("test: resuming the held call from the system");
+    cm.hold_requested_by_system(held_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.active_call().call_id(), held_call.call_id());
+    assert!(!held_connection.on_hold().expect(error_line!()));
+    assert!(waiting_connection.on_hold().expect(error_line!()));
+    // Only calls added to the system are reported.
+    assert_eq!(context.system_holds(), vec![true]);
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn receive_hard_reset_offer() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    info!("test: injecting ice disconnected");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+
+    info!("test: injecting ice connection failed");
+    active_connection
+        .inject_ice_connection_failed()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The callee waits for the caller to hard reset the connection.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceReconnecting
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Reconnecting
+    );
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 1);
+    assert_eq!(context.ended_count(), 0);
+
+    info!("test: receiving hard reset offer");
+    let remote_peer = active_call.remote_peer().expect(error_line!()).to_owned();
+    let connection_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    let offer = Offer {
+        renegotiation: Some(Renegotiation {
+            generation: 1,
+            reset:      true,
+        }),
+        ..Offer::new(format!("OFFER-{}", PRNG.gen::<u16>()))
+    };
+    cm.received_offer(
+        remote_peer.clone(),
+        connection_id,
+        offer.to_wire(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceConnecting(true)
+    );
+    assert_eq!(context.answers_sent(), 2);
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 2);
+
+    info!("test: add media stream");
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+
+    info!("test: injecting ice connected");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+
+    info!("test: injecting data channel connected");
+    active_connection
+        .inject_on_data_channel(DataChannel::new(ptr::null()))
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.stream_count(), 2);
+    assert_eq!(context.event_count(ApplicationEvent::LocalRinging), 1);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnected), 1);
+
+    info!("test: receiving the hard reset offer again, and an unmarked offer");
+    for offer in &[offer.to_wire(), "OFFER".to_owned()] {
+        cm.received_offer(
+            remote_peer.clone(),
+            connection_id,
+            offer.clone(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .expect(error_line!())
+                .as_millis() as u64,
+            Duration::from_secs(0),
+            CallConfig::default(),
+        )
+        .expect(error_line!());
+    }
+
+    cm.synchronize().expect(error_line!());
+
+    // Neither disturbs the connected call.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(context.answers_sent(), 2);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 2);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn receive_ice_restart_offer() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    info!("test: receiving ice restart offer");
+    let remote_peer = active_call.remote_peer().expect(error_line!()).to_owned();
+    let connection_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    let offer = Offer {
+        renegotiation: Some(Renegotiation {
+            generation: 1,
+            reset:      false,
+        }),
+        ..Offer::new(format!("OFFER-{}", PRNG.gen::<u16>()))
+    };
+    cm.received_offer(
+        remote_peer,
+        connection_id,
+        offer.to_wire(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The offer is answered on the current PeerConnection.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceConnecting(true)
+    );
+    assert_eq!(context.answers_sent(), 2);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 0);
+
+    info!("test: injecting ice connected");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.stream_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn proceed_after_call_concluded() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    cm.received_offer(
+        "REMOTE_PEER".to_owned(),
+        connection_id,
+        "OFFER".to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.start_incoming_count(), 1);
+    let mut active_call = context.active_call();
+
+    info!("test: concluding the call before proceeding");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+
+    // The offer pending when the call concluded is handled late, but
+    // no connection is created for it.
+    active_call.proceed(vec![1]).expect(error_line!());
+
+    assert!(context.connection_configs().is_empty());
+    assert!(active_call.get_connection(1 as DeviceId).is_err());
+    assert_eq!(context.answers_sent(), 0);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn receive_expired_offer() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    // create off way in the past
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    cm.received_offer(
+        remote_peer,
+        connection_id,
+        format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64
+            - 1000000,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedReceivedOfferExpired),
+        1
+    );
+}
+
+// Receive a current offer that was delivered late, checked against
+// the given maximum offer age.
+fn receive_offer_with_age(age: Duration, max_offer_age: Option<Duration>) -> TestContext {
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    if let Some(max_offer_age) = max_offer_age {
+        cm.set_max_offer_age(max_offer_age).expect(error_line!());
+    }
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    cm.received_offer(
+        remote_peer,
+        connection_id,
+        format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        age,
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    context
+}
+
+#[test]
+fn receive_aged_offer() {
+    test_init();
+
+    // Past the default maximum of two minutes.
+    let context = receive_offer_with_age(Duration::from_secs(121), None);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedReceivedOfferExpired),
+        1
+    );
+    assert_eq!(context.start_incoming_count(), 0);
+
+    // Within a custom maximum.
+    let max_offer_age = Some(Duration::from_secs(300));
+    let context = receive_offer_with_age(Duration::from_secs(121), max_offer_age);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedReceivedOfferExpired),
+        0
+    );
+    assert_eq!(context.start_incoming_count(), 1);
+
+    // Past a custom maximum.
+    let max_offer_age = Some(Duration::from_secs(30));
+    let context = receive_offer_with_age(Duration::from_secs(31), max_offer_age);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedReceivedOfferExpired),
+        1
+    );
+}
+
+// Receive an offer and proceed with the given codec preferences,
+// returning once the answer is sent.
+fn answer_offer_with_codec_preferences(preferences: Vec<String>) -> TestContext {
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    cm.set_codec_preferences(preferences).expect(error_line!());
+
+    let offer = "v=0\r\n\
+                 m=audio 9 UDP/TLS/RTP/SAVPF 111 0\r\n\
+                 a=rtpmap:111 opus/48000/2\r\n\
+                 a=rtpmap:0 PCMU/8000\r\n\
+                 m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                 a=rtpmap:96 VP8/90000\r\n";
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    cm.received_offer(
+        remote_peer,
+        connection_id,
+        offer.to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        Vec::<DeviceId>::new(),
+        Vec::new(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connecting
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+
+    context
+}
+
+#[test]
+fn negotiate_protocol_version() {
+    test_init();
+
+    let context = connect_inbound_call_with_offer(Offer::new("OFFER".to_owned()).to_wire());
+    assert_eq!(
+        context
+            .active_connection()
+            .protocol_version()
+            .expect(error_line!()),
+        PROTOCOL_VERSION
+    );
+    assert_eq!(context.error_count(), 0);
+
+    // A legacy caller sends a plain SDP.
+    let context = connect_inbound_call_with_offer("OFFER".to_owned());
+    assert_eq!(
+        context
+            .active_connection()
+            .protocol_version()
+            .expect(error_line!()),
+        LEGACY_PROTOCOL_VERSION
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn receive_compressed_offer() {
+    test_init();
+
+    let offer = Offer::new(VIDEO_ORIENTATION_OFFER.to_owned()).to_compressed_wire();
+    assert!(offer.len() < VIDEO_ORIENTATION_OFFER.len());
+
+    let context = connect_inbound_call_with_offer(offer);
+    assert_eq!(
+        context
+            .active_connection()
+            .protocol_version()
+            .expect(error_line!()),
+        PROTOCOL_VERSION
+    );
+    assert_eq!(context.media_descriptors().len(), 1);
+    assert_eq!(context.error_count(), 0);
+
+    info!("test: a malformed compressed offer is rejected");
+    let mut cm = context.cm();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    assert!(cm
+        .received_offer(
+            "REMOTE_PEER".to_owned(),
+            connection_id,
+            "`!v=0`?".to_owned(),
+            0,
+            Duration::from_secs(0),
+            CallConfig::default(),
+        )
+        .is_err());
+}
+
+#[test]
+fn inbound_opaque_signaling() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_opaque_signaling(true).expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect(error_line!())
+        .as_millis() as u64;
+
+    info!("test: a malformed opaque offer is rejected");
+    assert!(cm
+        .received_opaque_offer(
+            remote_peer.clone(),
+            connection_id,
+            &[0x12, 0x05],
+            timestamp,
+            Duration::from_secs(0),
+            CallConfig::default(),
+        )
+        .is_err());
+
+    info!("test: injecting opaque offer, delivered twice");
+    let offer = Offer {
+        sequence: 1,
+        ..Offer::new("OFFER".to_owned())
+    };
+    for _ in 0..2 {
+        cm.received_opaque_offer(
+            remote_peer.clone(),
+            connection_id,
+            &offer.to_opaque(),
+            timestamp,
+            Duration::from_secs(0),
+            CallConfig::default(),
+        )
+        .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.start_incoming_count(), 1);
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        Vec::<DeviceId>::new(),
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The answer is handed over as opaque bytes only.
+    assert_eq!(context.opaque_answers_sent(), 1);
+    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(
+        context
+            .active_connection()
+            .protocol_version()
+            .expect(error_line!()),
+        PROTOCOL_VERSION
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn inbound_sealed_signaling() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_opaque_signaling(true).expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect(error_line!())
+        .as_millis() as u64;
+
+    info!("test: a sealed offer is rejected without a key");
+    context.set_sealing_key(Some(0x5a));
+    let sealed = context.seal(
+        &remote_peer,
+        connection_id,
+        Offer::new("OFFER".to_owned()).to_opaque(),
+    );
+    context.set_sealing_key(None);
+    assert!(cm
+        .received_opaque_offer(
+            remote_peer.clone(),
+            connection_id,
+            &sealed,
+            timestamp,
+            Duration::from_secs(0),
+            CallConfig::default(),
+        )
+        .is_err());
+
+    info!("test: injecting sealed offer");
+    context.set_sealing_key(Some(0x5a));
+    cm.received_opaque_offer(
+        remote_peer.clone(),
+        connection_id,
+        &sealed,
+        timestamp,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.start_incoming_count(), 1);
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        Vec::<DeviceId>::new(),
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The answer is sealed too.
+    assert_eq!(context.sealed_sent(), 1);
+    assert_eq!(context.opaque_answers_sent(), 1);
+    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn inbound_ice_candidates_before_offer() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    info!("test: injecting ICE candidates before the offer");
+    let ice_candidate = IceCandidate::new("0".to_owned(), 1, "ICE-1".to_owned());
+    cm.received_ice_candidates(connection_id, &[ice_candidate], Duration::from_secs(0))
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(cm.active_call().is_ok(), false);
+
+    cm.received_offer(
+        "REMOTE_PEER".to_owned(),
+        connection_id,
+        "OFFER".to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        Vec::<DeviceId>::new(),
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The held candidates reach the connection.
+    let trace = context
+        .active_connection()
+        .export_trace()
+        .expect(error_line!());
+    assert!(trace
+        .iter()
+        .any(|e| e.kind == TraceKind::FsmEvent && e.description == "(RemoteIceCandidates)"));
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn answer_with_preferred_codec() {
+    test_init();
+
+    let context = answer_offer_with_codec_preferences(vec!["H264".to_owned(), "VP8".to_owned()]);
+
+    assert_eq!(context.event_count(ApplicationEvent::CodecFallback), 0);
+}
+
+#[test]
+fn answer_falls_back_when_preferred_codec_not_offered() {
+    test_init();
+
+    let context = answer_offer_with_codec_preferences(vec!["H264".to_owned()]);
+
+    assert_eq!(context.event_count(ApplicationEvent::CodecFallback), 1);
+}
+
+#[test]
+fn supported_codecs() {
+    test_init();
+
+    let context = TestContext::new();
+    let cm = context.cm();
+
+    // Only the software codecs of WebRTC.
+    let codecs = cm.supported_codecs().expect(error_line!());
+    assert_eq!(codecs, merge_capabilities(&[]));
+    assert!(hardware_accelerated_codecs(&codecs).is_empty());
+
+    info!("test: reporting hardware codecs");
+    context.set_codec_capabilities(vec![
+        CodecCapability::hardware("H264", true, true),
+        CodecCapability::hardware("VP8", true, false),
+    ]);
+    let codecs = cm.supported_codecs().expect(error_line!());
+    assert!(codecs.contains(&CodecCapability::hardware("H264", true, true)));
+    assert!(codecs.contains(&CodecCapability {
+        name:    "VP8".to_owned(),
+        encoder: Some(CodecImplementation::Hardware),
+        decoder: Some(CodecImplementation::Software),
+    }));
+    assert_eq!(hardware_accelerated_codecs(&codecs), vec!["VP8", "H264"]);
+    assert_eq!(context.error_count(), 0);
+}
+
+// An offer negotiating the video orientation RTP header extension.
+const VIDEO_ORIENTATION_OFFER: &str = "v=0\r\n\
+                                       m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                                       a=rtpmap:96 VP8/90000\r\n\
+                                       a=extmap:4 urn:3gpp:video-orientation\r\n";
+
+#[test]
+fn remote_video_orientation() {
+    test_init();
+
+    let context = connect_inbound_call_with_offer(VIDEO_ORIENTATION_OFFER.to_owned());
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    // only changes to a valid rotation are reported
+    for (rotation, reported) in &[(90, 1), (90, 1), (45, 1), (270, 2), (0, 3)] {
+        active_connection
+            .inject_remote_video_orientation(*rotation)
+            .expect(error_line!());
+        cm.synchronize().expect(error_line!());
+
+        assert_eq!(context.remote_video_orientations(), *reported);
+    }
+    assert_eq!(context.remote_video_rotation(), 0);
+    assert_eq!(context.error_count(), 0);
+}
+
+// An offer negotiating the audio level RTP header extension.
+const AUDIO_LEVEL_OFFER: &str = "v=0\r\n\
+                                 m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                                 a=rtpmap:111 opus/48000/2\r\n\
+                                 a=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\n";
+
+#[test]
+fn audio_levels_from_rtp_header_extension() {
+    test_init();
+
+    let context = connect_inbound_call_with_offer(AUDIO_LEVEL_OFFER.to_owned());
+    let active_connection = context.active_connection();
+
+    // The stats are polled until both levels are reported.
+    active_connection
+        .on_audio_level(AudioDirection::Captured, 0)
+        .expect(error_line!());
+    assert_eq!(
+        active_connection.audio_levels().expect(error_line!()),
+        (0.5, 0.25)
+    );
+
+    active_connection
+        .on_audio_level(AudioDirection::Received, 20)
+        .expect(error_line!());
+    let (captured, received) = active_connection.audio_levels().expect(error_line!());
+    assert_eq!(captured, 1.0);
+    assert!((received - 0.1).abs() < 1e-9);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn audio_levels_not_negotiated() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let active_connection = context.active_connection();
+
+    active_connection
+        .on_audio_level(AudioDirection::Captured, 0)
+        .expect(error_line!());
+    active_connection
+        .on_audio_level(AudioDirection::Received, 20)
+        .expect(error_line!());
+    assert_eq!(
+        active_connection.audio_levels().expect(error_line!()),
+        (0.5, 0.25)
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+// An offer carrying the DTLS fingerprint of the remote peer.
+const FINGERPRINT_OFFER: &str = "v=0\r\n\
+                                 m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                                 a=fingerprint:sha-256 2E:7C:B3:01:0A:48:1C:95:D3:36:58:5B:71:AA:2F:C4\r\n";
+
+#[test]
+fn safety_numbers_reported() {
+    test_init();
+
+    let context = connect_inbound_call_with_offer(FINGERPRINT_OFFER.to_owned());
+    let active_connection = context.active_connection();
+
+    let safety_numbers = active_connection
+        .get_safety_numbers()
+        .expect(error_line!())
+        .expect(error_line!());
+    assert_eq!(context.safety_numbers(), vec![safety_numbers]);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn safety_numbers_without_remote_fingerprint() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let active_connection = context.active_connection();
+
+    assert_eq!(
+        active_connection.get_safety_numbers().expect(error_line!()),
+        None
+    );
+    assert!(context.safety_numbers().is_empty());
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn remote_video_orientation_not_negotiated() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_remote_video_orientation(90)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.remote_video_orientations(), 0);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn remote_video_orientation_before_connected() {
+    test_init();
+
+    let context = start_inbound_call_with_offer(VIDEO_ORIENTATION_OFFER.to_owned());
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    info!("test: injecting remote video orientation");
+    active_connection
+        .inject_remote_video_orientation(180)
+        .expect(error_line!());
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    active_connection
+        .inject_on_data_channel(DataChannel::new(ptr::null()))
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Ringing
+    );
+    assert_eq!(context.remote_video_orientations(), 0);
+
+    info!("test: accepting call");
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    cm.accept_call(active_call.call_id()).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.remote_video_orientations(), 1);
+    assert_eq!(context.remote_video_rotation(), 180);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn answer_exceeding_max_sdp_length() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    // Shorter than the simulated answer.
+    cm.set_max_sdp_length(4).expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    cm.received_offer(
+        remote_peer,
+        connection_id,
+        format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        Vec::<DeviceId>::new(),
+        Vec::new(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.answers_sent(), 0);
+    assert_eq!(context.error_count(), 1);
+}
+
+#[test]
+fn data_channel_backpressure() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+    active_connection
+        .set_data_channel_high_water_mark(16)
+        .expect(error_line!());
+
+    info!("test: filling the send buffer");
+    let mut sent = 0;
+    while active_connection.send_app_message(&[1]).is_ok() {
+        sent += 1;
+        assert!(sent < 16);
+    }
+    let error = active_connection
+        .send_app_message(&[1])
+        .expect_err(error_line!());
+    assert!(format!("{}", error).contains("would block"));
+
+    let full = active_connection
+        .data_channel_buffered_amount()
+        .expect(error_line!());
+    assert!(full > 8 && full <= 16);
+
+    info!("test: queueing control messages");
+    active_connection
+        .send_video_status(false)
+        .expect(error_line!());
+    active_connection.send_hold(true).expect(error_line!());
+    active_connection
+        .send_video_status(true)
+        .expect(error_line!());
+    assert_eq!(
+        active_connection
+            .queued_control_messages()
+            .expect(error_line!()),
+        2
+    );
+    assert_eq!(
+        active_connection
+            .data_channel_buffered_amount()
+            .expect(error_line!()),
+        full
+    );
+
+    info!("test: partially draining the send buffer");
+    buffered_amount.store(9, Ordering::Release);
+    active_connection
+        .inject_data_channel_buffered_amount_change(full)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.buffered_amount_low_count(), 0);
+
+    info!("test: draining the send buffer");
+    buffered_amount.store(0, Ordering::Release);
+    active_connection
+        .inject_data_channel_buffered_amount_change(9)
+        .expect(error_line!());
+    active_connection
+        .inject_data_channel_buffered_amount_change(0)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The queued control messages go out first, as far as they fit,
+    // before the application is told to resume.
+    assert_eq!(
+        active_connection
+            .queued_control_messages()
+            .expect(error_line!()),
+        1
+    );
+    assert!(buffered_amount.load(Ordering::Acquire) > 0);
+    assert_eq!(context.buffered_amount_low_count(), 0);
+
+    while active_connection
+        .queued_control_messages()
+        .expect(error_line!())
+        > 0
+    {
+        buffered_amount.store(0, Ordering::Release);
+        active_connection
+            .inject_data_channel_buffered_amount_change(9)
+            .expect(error_line!());
+        cm.synchronize().expect(error_line!());
+    }
+
+    buffered_amount.store(0, Ordering::Release);
+    active_connection
+        .inject_data_channel_buffered_amount_change(9)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.buffered_amount_low_count(), 1);
+
+    active_connection
+        .send_video_status(true)
+        .expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn negotiation_offers_collide_as_callee() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    active_connection
+        .inject_negotiation_needed()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let offered = buffered_amount.load(Ordering::Acquire);
+    assert!(offered > 0);
+
+    info!("test: the polite callee rolls back and answers the colliding offer");
+    active_connection
+        .inject_remote_negotiation(
+            active_call.call_id(),
+            NegotiationMessage::Offer("OFFER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Then offers its own tracks again.
+    {
+        let negotiation = active_connection.negotiation().expect(error_line!());
+        assert_eq!(negotiation.negotiations(), 1);
+        assert!(negotiation.making_offer());
+    }
+    assert!(buffered_amount.load(Ordering::Acquire) > offered);
+
+    active_connection
+        .inject_remote_negotiation(
+            active_call.call_id(),
+            NegotiationMessage::Answer("ANSWER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    {
+        let negotiation = active_connection.negotiation().expect(error_line!());
+        assert_eq!(negotiation.negotiations(), 2);
+        assert!(!negotiation.making_offer());
+    }
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn inbound_answer_metadata() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+    cm.received_offer(
+        remote_peer,
+        connection_id,
+        Offer::new("OFFER".to_owned()).to_wire(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: the metadata is attached to the answer");
+    let active_call = context.active_call();
+    cm.set_answer_metadata(active_call.call_id(), vec![0x00, 0x2a, 0xff])
+        .expect(error_line!());
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        Vec::<DeviceId>::new(),
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(
+        context.sent_answer_metadata(),
+        vec![Some(vec![0x00, 0x2a, 0xff])]
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn inbound_replayed_signaling() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let remote_peer = active_call.remote_peer().expect(error_line!()).to_owned();
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+
+    cm.received_hangup(remote_id, HangupType::Normal)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 1);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+
+    info!("test: a replayed hangup is rejected");
+    assert!(cm.received_hangup(remote_id, HangupType::Normal).is_err());
+
+    info!("test: a replayed offer is rejected");
+    assert!(cm
+        .received_offer(
+            remote_peer,
+            remote_id,
+            format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .expect(error_line!())
+                .as_millis() as u64,
+            Duration::from_secs(0),
+            CallConfig::default(),
+        )
+        .is_err());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.start_incoming_count(), 1);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+}
+
+#[test]
+fn inbound_pre_offer() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
+
+    info!("test: the application warms up on the pre-offer");
+    cm.received_pre_offer(remote_peer.clone(), connection_id)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.pre_offers_received(), 1);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+
+    cm.received_offer(
+        remote_peer.clone(),
+        connection_id,
+        format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.start_incoming_count(), 1);
+
+    info!("test: a pre-offer arriving after its offer is ignored");
+    cm.received_pre_offer(remote_peer, connection_id)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.pre_offers_received(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
 }
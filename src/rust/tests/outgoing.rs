@@ -13,22 +13,73 @@ extern crate ringrtc;
 extern crate log;
 
 use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 
 use ringrtc::common::{
     ApplicationEvent,
+    AudioDeviceError,
+    CallDirection,
     CallId,
     CallState,
     ConnectionId,
     ConnectionState,
     DeviceId,
+    HangupType,
+    NegotiationMessage,
+    TransferMessage,
+};
+
+use ringrtc::core::audio_config::AudioConfig;
+use ringrtc::core::audio_route::AudioRoute;
+use ringrtc::core::bandwidth_mode::BandwidthMode;
+use ringrtc::core::call::{DeviceSelectionPolicy, PreferDevice};
+use ringrtc::core::call_config::CallConfig;
+use ringrtc::core::call_failure::CallFailureReason;
+use ringrtc::core::call_record::ConnectionType;
+use ringrtc::core::codec::{AudioCodec, VideoCodec};
+use ringrtc::core::connection::{
+    MediaFlow,
+    MediaFlowMonitor,
+    ReconnectionController,
+    MAX_JITTER_BUFFER_MIN_DELAY,
+    MAX_OUTGOING_AUDIO_GAIN,
+    UNITY_OUTGOING_AUDIO_GAIN,
+};
+use ringrtc::core::connection_config::{BundlePolicy, ConnectionConfig, RtcpMuxPolicy};
+use ringrtc::core::group_call::ParticipantState;
+use ringrtc::core::ice_candidate_policy::IceCandidatePolicy;
+use ringrtc::core::ice_encoding::encode_ice_candidates;
+use ringrtc::core::ice_server::{IceServerConfig, IceTransport};
+use ringrtc::core::media_constraints::MediaConstraints;
+use ringrtc::core::media_descriptor::MediaDescriptor;
+use ringrtc::core::pending_signaling::PendingMessage;
+use ringrtc::core::rate_limit::MAX_RECEIVED_ICE_CANDIDATES;
+use ringrtc::core::recording::{RecordingConfig, RecordingSink};
+use ringrtc::core::signaling::{Answer, PROTOCOL_VERSION};
+use ringrtc::core::signaling_transport::SignalingTransport;
+#[cfg(feature = "srtp_key_export")]
+use ringrtc::core::srtp_keys::SrtpProtectionProfile;
+use ringrtc::core::trace::{TraceKind, MAX_TRACE_EVENTS};
+use ringrtc::core::video::{
+    DegradationPreference,
+    SimulcastLayer,
+    VideoResolution,
+    MAX_SIMULCAST_LAYERS,
 };
 
 use ringrtc::sim::error::SimError;
+use ringrtc::sim::sim_platform::SimPlatform;
 
-use ringrtc::webrtc::ice_candidate::IceCandidate;
-use ringrtc::webrtc::media_stream::MediaStream;
+use ringrtc::webrtc::data_channel::DataChannel;
+use ringrtc::webrtc::frame_crypto::MediaCryptoProvider;
+use ringrtc::webrtc::ice_candidate::{IceCandidate, IceRoute, NetworkAdapterType};
+use ringrtc::webrtc::media::{AudioDirection, AudioFrame, AudioTap, MediaKind};
+use ringrtc::webrtc::media_stream::{MediaStream, VideoFrame, VideoSink, VideoSource};
+use ringrtc::webrtc::peer_connection_observer::IceGatheringState;
 
 #[macro_use]
 mod common;
@@ -58,6 +109,12 @@ fn create_cm() {
 //
 // Now in the Connecting state.
 fn start_outbound_n_remote_call(n_remotes: u16) -> TestContext {
+    start_outbound_n_remote_call_with_config(n_remotes, CallConfig::default())
+}
+
+// Create an outbound N-remote call session with the timeouts of
+// `config`, up to the IceConnecting state.
+fn start_outbound_n_remote_call_with_config(n_remotes: u16, config: CallConfig) -> TestContext {
     let context = TestContext::new();
     let mut cm = context.cm();
 
@@ -65,7 +122,7 @@ fn start_outbound_n_remote_call(n_remotes: u16) -> TestContext {
     assert!(n_remotes < 20);
 
     let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
-    cm.call(remote_peer).expect(error_line!());
+    cm.call(remote_peer, config).expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
@@ -89,6 +146,7 @@ fn start_outbound_n_remote_call(n_remotes: u16) -> TestContext {
         active_call.call_id(),
         format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
         remote_devices,
+        Vec::new(),
     )
     .expect(error_line!());
 
@@ -113,7 +171,7 @@ fn start_outbound_n_remote_call(n_remotes: u16) -> TestContext {
             1,
             format!("ICE-{}", PRNG.gen::<u16>()).to_owned(),
         );
-        cm.received_ice_candidates(remote_id, &[ice_candidate])
+        cm.received_ice_candidates(remote_id, &[ice_candidate], Duration::from_secs(0))
             .expect(error_line!());
 
         cm.synchronize().expect(error_line!());
@@ -230,6 +288,50 @@ fn outbound_call_connected() {
     let _ = connect_outbound_call();
 }
 
+#[test]
+fn outbound_remote_ringing_before_ice_connected() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    info!("test: receiving ringing acknowledgment");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_ringing(remote_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connecting
+    );
+    assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 1);
+
+    info!("test: injecting ice connected");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Ringing
+    );
+    assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 1);
+
+    info!("test: receiving late ringing acknowledgment");
+    cm.received_ringing(remote_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
 #[test]
 fn outbound_local_hang_up() {
     test_init();
@@ -252,6 +354,75 @@ fn outbound_local_hang_up() {
     // TODO - verify that the data_channel sent a hangup message
 }
 
+#[test]
+fn outbound_cancel_setup() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    cm.call(remote_peer, CallConfig::default())
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    let mut handle = cm
+        .proceed(
+            active_call.call_id(),
+            format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+            vec![1],
+            Vec::new(),
+        )
+        .expect(error_line!());
+    assert_eq!(handle.call_id(), active_call.call_id());
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.offers_sent(), 1);
+
+    info!("test: cancel setup");
+    handle.cancel().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(cm.active_call().is_ok(), false);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedSetupCancelled),
+        1
+    );
+    assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 0);
+    assert_eq!(context.hangups_sent(), 1);
+}
+
+#[test]
+fn outbound_cancel_setup_after_connect() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    info!("test: cancel setup");
+    cm.cancel_setup(active_call.call_id()).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedSetupCancelled),
+        0
+    );
+}
+
 #[test]
 fn outbound_ice_failed() {
     test_init();
@@ -279,6 +450,7 @@ fn outbound_ice_failed() {
         context.event_count(ApplicationEvent::EndedConnectionFailure),
         1
     );
+    assert_eq!(context.call_failures(), vec![CallFailureReason::IceFailed]);
 }
 
 #[test]
@@ -423,6 +595,7 @@ fn outbound_call_connected_local_hangup() {
     assert_eq!(context.ended_count(), 1);
     assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
     assert_eq!(context.hangups_sent(), 1);
+    assert_eq!(context.hangup_types(), vec![HangupType::Normal]);
 
     // TODO - verify that the data_channel sent a hangup message
 }
@@ -516,499 +689,5508 @@ fn outbound_ice_disconnected_after_call_connected_and_local_hangup() {
 }
 
 #[test]
-fn inject_connection_error() {
+fn outbound_remote_media_closed_while_reconnecting() {
     test_init();
 
     let context = connect_outbound_call();
     let mut cm = context.cm();
+    let active_call = context.active_call();
     let mut active_connection = context.active_connection();
 
-    active_connection.inject_internal_error(
-        SimError::TestError("fake_error".to_string()).into(),
-        "testing connection error injection",
+    info!("test: injecting ice disconnected");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Reconnecting
     );
 
+    // The remote client went away, so the call ends without waiting
+    // for ICE to fail.
+    info!("test: injecting remote media closed");
+    active_connection
+        .inject_remote_media_closed()
+        .expect(error_line!());
+
     cm.synchronize().expect(error_line!());
-    assert_eq!(context.error_count(), 1);
+
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(context.error_count(), 0);
     assert_eq!(context.ended_count(), 1);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteMediaClosed),
+        1
+    );
+    assert_eq!(context.hangups_sent(), 1);
 }
 
 #[test]
-fn inject_call_error() {
+fn outbound_remote_media_closed_before_call_connected() {
     test_init();
 
-    let context = connect_outbound_call();
+    let context = start_outbound_call();
     let mut cm = context.cm();
-    let mut active_call = context.active_call();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
 
-    active_call.inject_internal_error(
-        SimError::TestError("fake_error".to_string()).into(),
-        "testing call error injection",
+    info!("test: injecting ice connected");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+
+    info!("test: injecting remote media closed");
+    active_connection
+        .inject_remote_media_closed()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // Only a connected call ends, the ringing one times out.
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Ringing
     );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
 
+    cm.hangup().expect(error_line!());
     cm.synchronize().expect(error_line!());
-    assert_eq!(context.error_count(), 1);
-    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
 }
 
-#[test]
-fn inject_local_video_status() {
-    test_init();
-
+// Disconnect a connected call and fail ICE recovery, triggering a
+// hard reset of the connection.
+//
+// Now in the SendingOffer state, with the call Reconnecting.
+fn hard_reset_outbound_call() -> TestContext {
     let context = connect_outbound_call();
     let mut cm = context.cm();
+    let active_call = context.active_call();
     let mut active_connection = context.active_connection();
 
+    // Hard reset as soon as ICE fails, without restarting ICE.
+    cm.set_ice_restarts_before_hard_reset(0)
+        .expect(error_line!());
+
+    info!("test: injecting ice disconnected");
     active_connection
-        .inject_local_video_status(false)
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 1);
+
+    info!("test: injecting ice connection failed");
+    active_connection
+        .inject_ice_connection_failed()
         .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
+
+    // Rather than ending the call, the connection is rebuilt and a
+    // new offer sent.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::SendingOffer
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Reconnecting
+    );
+    assert_eq!(context.offers_sent(), 2);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 2);
     assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
 
-    // TODO -- verify that the data channel object sent a message
+    context
 }
 
 #[test]
-fn inject_local_ice_candidate() {
+fn outbound_ice_failed_while_reconnecting_hard_reset() {
     test_init();
 
-    let context = connect_outbound_call();
+    let context = hard_reset_outbound_call();
     let mut cm = context.cm();
+    let active_call = context.active_call();
     let mut active_connection = context.active_connection();
 
-    let ice_candidate = IceCandidate::new("fake_spd_mid".to_string(), 0, "fake_spd".to_string());
+    info!("test: injecting answer");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(
+        remote_id,
+        format!("ANSWER-{}", PRNG.gen::<u16>()).to_owned(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceConnecting(true)
+    );
+
+    info!("test: add media stream");
     active_connection
-        .inject_local_ice_candidate(ice_candidate)
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+
+    info!("test: injecting ice connected");
+    active_connection
+        .inject_ice_connected()
         .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
+
+    // The media of the new PeerConnection is connected without
+    // ringing again.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.stream_count(), 2);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 1);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnected), 1);
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.ice_candidates_sent(), 1);
+    assert_eq!(context.ended_count(), 0);
 }
 
+const CAPABILITIES_ANSWER: &str = "v=0\r\n\
+                                   m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                                   a=rtpmap:111 opus/48000/2\r\n\
+                                   m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                                   a=rtpmap:96 VP8/90000\r\n";
+
 #[test]
-fn receive_remote_ice_candidate() {
+fn remote_capabilities() {
     test_init();
 
-    let context = connect_outbound_call();
+    let context = TestContext::new();
     let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    cm.call(remote_peer, CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
     let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
-    // add a received ICE candidate
-    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
-    let ice_candidate = IceCandidate::new(
-        "0".to_owned(),
-        1,
-        format!("ICE-{}", PRNG.gen::<u16>()).to_owned(),
-    );
-    cm.received_ice_candidates(remote_id, &[ice_candidate])
+    let connection = active_call
+        .get_connection(1 as DeviceId)
         .expect(error_line!());
 
+    // Unknown until the answer is applied.
+    assert_eq!(connection.remote_capabilities().expect(error_line!()), None);
+
+    info!("test: injecting answer");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(remote_id, CAPABILITIES_ANSWER.to_owned())
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
+    let capabilities = connection
+        .remote_capabilities()
+        .expect(error_line!())
+        .expect(error_line!());
+    assert!(capabilities.audio);
+    assert!(capabilities.video);
+    assert!(!capabilities.data);
+    assert!(!capabilities.simulcast);
+    assert_eq!(capabilities.codecs, vec!["opus", "VP8"]);
     assert_eq!(context.error_count(), 0);
-
-    // TODO -- verify the ice candidate was buffered
-    // TODO -- verify the ice candidate was applied to the peer_connection
 }
 
 #[test]
-fn received_remote_hangup() {
+fn remote_capabilities_after_hard_reset() {
     test_init();
 
-    let context = connect_outbound_call();
+    let context = hard_reset_outbound_call();
     let mut cm = context.cm();
     let active_call = context.active_call();
+    let active_connection = context.active_connection();
 
-    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
-    cm.received_hangup(remote_id).expect(error_line!());
+    let capabilities = active_connection
+        .remote_capabilities()
+        .expect(error_line!())
+        .expect(error_line!());
+    assert!(!capabilities.video);
 
+    info!("test: injecting renegotiation answer");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(remote_id, CAPABILITIES_ANSWER.to_owned())
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
+    let capabilities = active_connection
+        .remote_capabilities()
+        .expect(error_line!())
+        .expect(error_line!());
+    assert!(capabilities.video);
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 1);
 }
 
 #[test]
-fn received_remote_video_status() {
+fn outbound_hard_reset_exhausted() {
     test_init();
 
-    let context = connect_outbound_call();
+    let context = hard_reset_outbound_call();
     let mut cm = context.cm();
     let active_call = context.active_call();
     let mut active_connection = context.active_connection();
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
 
-    let mut enable_count = 0;
-    let mut disable_count = 0;
-    for _i in 0..20 {
-        let enable = PRNG.gen::<bool>();
+    for i in 1..(ReconnectionController::MAX_HARD_RESETS + 1) {
+        info!("test: injecting answer");
+        cm.received_answer(
+            remote_id,
+            format!("ANSWER-{}", PRNG.gen::<u16>()).to_owned(),
+        )
+        .expect(error_line!());
 
+        info!("test: injecting ice connection failed");
         active_connection
-            .inject_remote_video_status(active_call.call_id(), enable)
+            .inject_ice_connection_failed()
             .expect(error_line!());
+
         cm.synchronize().expect(error_line!());
 
-        if enable {
-            enable_count += 1;
-        } else {
-            disable_count += 1;
+        if i < ReconnectionController::MAX_HARD_RESETS {
+            assert_eq!(context.offers_sent(), (i + 2) as usize);
+            assert_eq!(context.ended_count(), 0);
         }
-
-        assert_eq!(context.error_count(), 0);
-        assert_eq!(
-            context.event_count(ApplicationEvent::RemoteVideoEnable),
-            enable_count
-        );
-        assert_eq!(
-            context.event_count(ApplicationEvent::RemoteVideoDisable),
-            disable_count
-        );
     }
+
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(
+        context.offers_sent(),
+        (ReconnectionController::MAX_HARD_RESETS + 1) as usize
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedConnectionFailure),
+        1
+    );
 }
 
 #[test]
-fn call_timeout_before_connect() {
+fn outbound_ice_failed_restart_before_hard_reset() {
     test_init();
 
-    let context = start_outbound_call();
+    let context = connect_outbound_call();
     let mut cm = context.cm();
-    let mut active_call = context.active_call();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
 
-    active_call.inject_call_timeout().expect(error_line!());
+    info!("test: injecting ice disconnected and failed");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    active_connection
+        .inject_ice_connection_failed()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // ICE is restarted first, keeping the PeerConnection.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceRestarting
+    );
+    assert_eq!(context.offers_sent(), 2);
+    assert_eq!(context.restart_offers_sent(), 1);
+
+    info!("test: injecting answer, then ice connection failed");
+    cm.received_answer(
+        remote_id,
+        format!("ANSWER-{}", PRNG.gen::<u16>()).to_owned(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    active_connection
+        .inject_ice_connection_failed()
+        .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
+    // Once the ICE restart failed, the connection is hard reset.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::SendingOffer
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Reconnecting
+    );
+    assert_eq!(context.offers_sent(), 3);
+    assert_eq!(context.restart_offers_sent(), 1);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 2);
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+    assert_eq!(context.ended_count(), 0);
 }
 
 #[test]
-fn call_timeout_after_connect() {
+fn outbound_hard_reset_renegotiation_timeout() {
     test_init();
 
     let context = connect_outbound_call();
     let mut cm = context.cm();
-    let mut active_call = context.active_call();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
 
-    active_call.inject_call_timeout().expect(error_line!());
+    // Hard reset as soon as ICE fails, without restarting ICE.
+    cm.set_ice_restarts_before_hard_reset(0)
+        .expect(error_line!());
 
-    cm.synchronize().expect(error_line!());
+    cm.set_renegotiation_timeout(Duration::from_millis(100))
+        .expect(error_line!());
 
-    // The call is already connected, so the timeout is ignored.
-    assert_eq!(context.error_count(), 0);
-    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 0);
-}
+    info!("test: injecting ice disconnected");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
 
-#[test]
-fn outbound_proceed_with_error() {
-    test_init();
+    info!("test: injecting ice connection failed");
+    active_connection
+        .inject_ice_connection_failed()
+        .expect(error_line!());
 
-    let context = TestContext::new();
-    let mut cm = context.cm();
+    cm.synchronize().expect(error_line!());
 
-    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
-    cm.call(remote_peer).expect(error_line!());
+    assert_eq!(context.offers_sent(), 2);
 
-    cm.synchronize().expect(error_line!());
+    // Never answer the renegotiation offer, it is retried and then
+    // rolled back.
+    thread::sleep(Duration::from_millis(
+        500 * u64::from(ReconnectionController::MAX_RENEGOTIATION_RETRIES + 1),
+    ));
 
-    assert_eq!(cm.active_call().is_ok(), true);
-    assert_eq!(context.start_outgoing_count(), 1);
-    assert_eq!(context.start_incoming_count(), 0);
+    cm.synchronize().expect(error_line!());
 
-    let active_call = context.active_call();
+    assert_eq!(
+        context.offers_sent(),
+        (2 + ReconnectionController::MAX_RENEGOTIATION_RETRIES) as usize
+    );
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceReconnecting
+    );
     assert_eq!(
         active_call.state().expect(error_line!()),
-        CallState::Starting
+        CallState::Reconnecting
     );
+    assert_eq!(
+        context.event_count(ApplicationEvent::RenegotiationTimeout),
+        1
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
 
-    // cause the sending of the offer to fail.
-    context.force_internal_fault(true);
-
-    let active_call = context.active_call();
-    let mut remote_devices = Vec::<DeviceId>::new();
-    remote_devices.push(1);
-    cm.proceed(
-        active_call.call_id(),
-        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
-        remote_devices,
+    // The connection is still usable, a later answer is ignored and
+    // the call can be hung up.
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(
+        remote_id,
+        format!("ANSWER-{}", PRNG.gen::<u16>()).to_owned(),
     )
     .expect(error_line!());
 
+    cm.hangup().expect(error_line!());
+
     cm.synchronize().expect(error_line!());
 
     assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
-
-    // Two errors -- one from the failed send_offer and another from
-    // the failed send_hangup, sent as part of the error clean up.
-    assert_eq!(context.error_count(), 2);
-    assert_eq!(
-        context.event_count(ApplicationEvent::EndedInternalFailure),
-        2
-    );
-    assert_eq!(context.offers_sent(), 0);
-
-    context.force_internal_fault(false);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
 }
 
 #[test]
-fn outbound_call_connected_local_hangup_with_error() {
+fn outbound_route_changes() {
     test_init();
 
     let context = connect_outbound_call();
     let mut cm = context.cm();
-    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
 
-    // cause the sending of the hangup to fail.
-    context.force_internal_fault(true);
+    cm.set_route_change_debounce(Duration::from_millis(100))
+        .expect(error_line!());
 
-    cm.hangup().expect(error_line!());
+    let direct = IceRoute::new("local", "stun", "udp");
+    let relay = IceRoute::new("relay", "local", "udp");
+    let settle = || thread::sleep(Duration::from_millis(300));
 
+    // The first route after connecting is the baseline.
+    active_connection
+        .inject_ice_route_changed(direct.clone())
+        .expect(error_line!());
+    settle();
     cm.synchronize().expect(error_line!());
 
-    assert_eq!(context.error_count(), 1);
-    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
-    assert_eq!(context.ended_count(), 2);
-    assert_eq!(
-        context.event_count(ApplicationEvent::EndedInternalFailure),
-        1
-    );
-    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
-    assert_eq!(context.hangups_sent(), 0);
+    assert_eq!(context.route_downgrades(), 0);
+    assert_eq!(context.route_upgrades(), 0);
+
+    info!("test: injecting relayed route");
+    active_connection
+        .inject_ice_route_changed(relay.clone())
+        .expect(error_line!());
+    settle();
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.route_downgrades(), 1);
+    assert_eq!(context.route_upgrades(), 0);
+
+    info!("test: injecting route flap");
+    active_connection
+        .inject_ice_route_changed(direct.clone())
+        .expect(error_line!());
+    active_connection
+        .inject_ice_route_changed(relay)
+        .expect(error_line!());
+    settle();
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.route_downgrades(), 1);
+    assert_eq!(context.route_upgrades(), 0);
+
+    info!("test: injecting direct route");
+    active_connection
+        .inject_ice_route_changed(direct)
+        .expect(error_line!());
+    settle();
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.route_downgrades(), 1);
+    assert_eq!(context.route_upgrades(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
 }
 
 #[test]
-fn local_ice_candidate_with_error() {
+fn outbound_network_route_changes() {
     test_init();
 
     let context = connect_outbound_call();
     let mut cm = context.cm();
     let mut active_connection = context.active_connection();
 
-    // cause the sending of the ICE candidate to fail.
-    context.force_internal_fault(true);
+    let wifi =
+        IceRoute::new("local", "stun", "udp").with_local_adapter_type(NetworkAdapterType::Wifi);
 
-    let ice_candidate = IceCandidate::new("fake_spd_mid".to_string(), 0, "fake_spd".to_string());
     active_connection
-        .inject_local_ice_candidate(ice_candidate)
+        .inject_ice_route_changed(wifi.clone())
         .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.network_routes(),
+        vec![(NetworkAdapterType::Wifi, false)]
+    );
 
+    info!("test: injecting another candidate pair over wifi");
+    active_connection
+        .inject_ice_route_changed(
+            IceRoute::new("prflx", "local", "udp")
+                .with_local_adapter_type(NetworkAdapterType::Wifi),
+        )
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    // Two errors -- one from the failed send_ice_candidates and another from
-    // the failed send_hangup, sent as part of the error clean up.
-    assert_eq!(context.error_count(), 2);
+    assert_eq!(context.network_routes().len(), 1);
 
+    info!("test: injecting relayed route over cellular");
+    active_connection
+        .inject_ice_route_changed(
+            IceRoute::new("relay", "local", "udp")
+                .with_local_adapter_type(NetworkAdapterType::Cellular),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Reported right away, without waiting for the route change
+    // debounce period.
     assert_eq!(
-        context.event_count(ApplicationEvent::EndedInternalFailure),
-        2
+        context.network_routes(),
+        vec![
+            (NetworkAdapterType::Wifi, false),
+            (NetworkAdapterType::Cellular, true)
+        ]
     );
-    // We should see that no ICE candidates were sent
-    assert_eq!(context.ice_candidates_sent(), 0);
+    assert_eq!(context.route_downgrades(), 0);
+
+    info!("test: injecting route back over wifi");
+    active_connection
+        .inject_ice_route_changed(wifi)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.network_routes().len(), 3);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
 }
 
-// Create outbound call to multiple remote devices.
-//
-// One of the remote devices accepts the call, which is followed by a
-// local hangup.
 #[test]
-fn outbound_multiple_remote_devices() {
+fn outbound_ice_gathering_host_candidates_only() {
     test_init();
 
-    let n_remotes: u16 = 5;
-    let context = start_outbound_n_remote_call(n_remotes);
+    let context = start_outbound_call();
     let mut cm = context.cm();
-    let active_call = context.active_call();
-
-    for i in 1..(n_remotes + 1) {
-        let mut connection = active_call
-            .get_connection(i as DeviceId)
-            .expect(error_line!());
+    let mut active_connection = context.active_connection();
+    let ice_candidates_sent = context.ice_candidates_sent();
 
-        info!("test:{}: injecting ice connected", i);
-        connection.inject_ice_connected().expect(error_line!());
+    info!("test: gathering a single host candidate");
+    active_connection
+        .inject_ice_gathering_state_changed(IceGatheringState::Gathering)
+        .expect(error_line!());
+    let ice_candidate = IceCandidate::new(
+        "audio".to_string(),
+        0,
+        "candidate:1 1 udp 2122260223 192.168.1.2 49152 typ host".to_string(),
+    );
+    active_connection
+        .inject_local_ice_candidate(ice_candidate)
+        .expect(error_line!());
 
-        cm.synchronize().expect(error_line!());
+    // No STUN or TURN server responded, gathering completes anyway.
+    active_connection
+        .inject_ice_gathering_state_changed(IceGatheringState::Complete)
+        .expect(error_line!());
 
-        assert_eq!(
-            connection.state().expect(error_line!()),
-            ConnectionState::IceConnected
-        );
-        assert_eq!(
-            active_call.state().expect(error_line!()),
-            CallState::Ringing
-        );
-        assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 1);
-        assert_eq!(context.error_count(), 0);
-        assert_eq!(context.ended_count(), 0);
+    cm.synchronize().expect(error_line!());
 
-        info!("test:{}: add media stream", i);
-        connection
-            .on_add_stream(MediaStream::new(ptr::null()))
-            .expect(error_line!());
-    }
+    assert_eq!(
+        context.ice_gathering_states(),
+        vec![IceGatheringState::Gathering, IceGatheringState::Complete]
+    );
+    assert_eq!(context.ice_candidates_sent(), ice_candidates_sent + 1);
 
-    // connect one of the remotes
-    let active_remote = (PRNG.gen::<u16>() % n_remotes) + 1;
+    info!("test: gathering restarts after the call connects");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(context.active_call().call_id())
+        .expect(error_line!());
+    active_connection
+        .inject_ice_gathering_state_changed(IceGatheringState::Gathering)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.ice_gathering_states(),
+        vec![
+            IceGatheringState::Gathering,
+            IceGatheringState::Complete,
+            IceGatheringState::Gathering
+        ]
+    );
+
+    info!("test: ignoring gathering once the call ends");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    active_connection
+        .inject_ice_gathering_state_changed(IceGatheringState::Complete)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.ice_gathering_states().len(), 3);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn video_degradation_preference() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let active_connection = context.active_connection();
+
+    assert_eq!(
+        active_connection
+            .degradation_preference()
+            .expect(error_line!()),
+        DegradationPreference::Balanced
+    );
+
+    info!("test: setting degradation preference");
+    active_connection
+        .set_degradation_preference(DegradationPreference::MaintainResolution)
+        .expect(error_line!());
+
+    assert_eq!(
+        active_connection
+            .degradation_preference()
+            .expect(error_line!()),
+        DegradationPreference::MaintainResolution
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn video_degradation_preference_after_hard_reset() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    // Hard reset as soon as ICE fails, without restarting ICE.
+    cm.set_ice_restarts_before_hard_reset(0)
+        .expect(error_line!());
+
+    active_connection
+        .set_degradation_preference(DegradationPreference::MaintainFramerate)
+        .expect(error_line!());
+
+    info!("test: injecting ice disconnected and failed");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    active_connection
+        .inject_ice_connection_failed()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The preference is applied to the rebuilt PeerConnection.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::SendingOffer
+    );
+    assert_eq!(
+        active_connection
+            .degradation_preference()
+            .expect(error_line!()),
+        DegradationPreference::MaintainFramerate
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn jitter_buffer_min_delay() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    // Hard reset as soon as ICE fails, without restarting ICE.
+    cm.set_ice_restarts_before_hard_reset(0)
+        .expect(error_line!());
+
+    assert_eq!(
+        active_connection
+            .jitter_buffer_min_delay()
+            .expect(error_line!()),
+        None
+    );
+
+    info!("test: setting the minimum delay");
+    let delay = Some(Duration::from_millis(500));
+    active_connection
+        .set_jitter_buffer_min_delay(delay)
+        .expect(error_line!());
+
+    info!("test: delays beyond WebRTC's cap are refused");
+    assert!(active_connection
+        .set_jitter_buffer_min_delay(Some(MAX_JITTER_BUFFER_MIN_DELAY * 2))
+        .is_err());
+
+    info!("test: the delay is kept across a hard reset");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    active_connection
+        .inject_ice_connection_failed()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::SendingOffer
+    );
+    assert_eq!(
+        active_connection
+            .jitter_buffer_min_delay()
+            .expect(error_line!()),
+        delay
+    );
+
+    active_connection
+        .set_jitter_buffer_min_delay(None)
+        .expect(error_line!());
+    assert_eq!(
+        active_connection
+            .jitter_buffer_min_delay()
+            .expect(error_line!()),
+        None
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn connection_bundle_policy() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_bundle_policy(BundlePolicy::MaxCompat)
+        .expect(error_line!());
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The offer is created from a PeerConnection with the configured
+    // policies.
+    assert_eq!(context.offers_sent(), 1);
+    assert_eq!(
+        context.connection_configs(),
+        vec![ConnectionConfig {
+            bundle_policy:   BundlePolicy::MaxCompat,
+            rtcp_mux_policy: RtcpMuxPolicy::Require,
+            ice_servers:     Vec::new(),
+        }]
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn connection_ice_servers() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let turns = IceServerConfig::new("turns:turn.example.org:443", "user", "pass").unwrap();
+    let turn = IceServerConfig::new("turn:turn.example.org:3478", "user", "pass").unwrap();
+    let stun = IceServerConfig::new("stun:stun.example.org", "", "").unwrap();
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1],
+        vec![turns.clone(), turn.clone(), stun.clone()],
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The PeerConnection is created with the servers ordered by
+    // transport, the `turns:` server last as TLS.
+    assert_eq!(context.offers_sent(), 1);
+    let configs = context.connection_configs();
+    assert_eq!(configs.len(), 1);
+    assert_eq!(configs[0].ice_servers, vec![stun, turn, turns]);
+    assert_eq!(configs[0].ice_servers[2].transport, Some(IceTransport::Tls));
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn max_connections() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_max_connections(Some(2)).expect(error_line!());
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1, 2, 3, 4],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.offers_sent(), 2);
+    assert!(active_call.get_connection(2).is_ok());
+    assert!(active_call.get_connection(3).is_err());
+
+    info!("test: answer from a deferred device");
+    let remote_id = ConnectionId::new(active_call.call_id(), 3);
+    cm.received_answer(remote_id, "ANSWER".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The device is sent an offer of its own to answer.
+    assert_eq!(context.offers_sent(), 3);
+    let connection = active_call.get_connection(3).expect(error_line!());
+
+    cm.received_answer(remote_id, "ANSWER".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.offers_sent(), 3);
+    assert_eq!(
+        connection.state().expect(error_line!()),
+        ConnectionState::IceConnecting(true)
+    );
+    assert!(active_call.get_connection(4).is_err());
+    assert_eq!(context.error_count(), 0);
+}
+
+struct LastDevices;
+
+impl DeviceSelectionPolicy for LastDevices {
+    fn select(&self, remote_devices: &[DeviceId], max: usize) -> Vec<DeviceId> {
+        remote_devices.iter().rev().take(max).cloned().collect()
+    }
+}
+
+#[test]
+fn device_selection_policy() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_max_connections(Some(1)).expect(error_line!());
+    cm.set_device_selection_policy(Box::new(LastDevices))
+        .expect(error_line!());
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1, 2, 3],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.offers_sent(), 1);
+    assert!(active_call.get_connection(3).is_ok());
+    assert!(active_call.get_connection(1).is_err());
+    assert_eq!(context.error_count(), 0);
+}
+
+// Create an outbound call to three remote devices, preferring remote
+// 2, with every connection ringing.
+fn start_outbound_call_preferring_device() -> TestContext {
+    let context = start_outbound_n_remote_call(3);
+    let mut cm = context.cm();
+    cm.set_forking_policy(Box::new(PreferDevice(2)))
+        .expect(error_line!());
+
+    let active_call = context.active_call();
+    for i in 1..4 {
+        let mut connection = active_call
+            .get_connection(i as DeviceId)
+            .expect(error_line!());
+        connection.inject_ice_connected().expect(error_line!());
+        connection
+            .on_add_stream(MediaStream::new(ptr::null()))
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Ringing
+    );
+    context
+}
+
+#[test]
+fn forking_policy_prefer_device() {
+    test_init();
+
+    let context = start_outbound_call_preferring_device();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    info!("test: remote 1 accepts, waiting for remote 2");
+    active_call
+        .get_connection(1 as DeviceId)
+        .expect(error_line!())
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Ringing
+    );
+    assert_eq!(context.event_count(ApplicationEvent::RemoteConnected), 0);
+
+    info!("test: remote 2 accepts and wins");
+    active_call
+        .get_connection(2 as DeviceId)
+        .expect(error_line!())
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(active_call.active_device_id().expect(error_line!()), 2);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteConnected), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn forking_policy_preferred_device_declines() {
+    test_init();
+
+    let context = start_outbound_call_preferring_device();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    info!("test: remote 3 accepts, waiting for remote 2");
+    active_call
+        .get_connection(3 as DeviceId)
+        .expect(error_line!())
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Ringing
+    );
+
+    info!("test: remote 2 declines, remote 3 wins");
+    let remote_id = ConnectionId::new(active_call.call_id(), 2 as DeviceId);
+    cm.received_declined(remote_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(active_call.active_device_id().expect(error_line!()), 3);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteConnected), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn offer_media_descriptor() {
+    test_init();
+
+    let context = start_outbound_call();
+
+    // The descriptor accompanies the offer.  The simulated SDP has no
+    // media sections, so nothing is described.
+    assert_eq!(context.offers_sent(), 1);
+    assert_eq!(
+        context.media_descriptors(),
+        vec![MediaDescriptor::default()]
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn replace_ice_servers() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    assert_eq!(active_connection.ice_servers().expect(error_line!()), None);
+
+    info!("test: replacing ice servers");
+    let ice_servers =
+        vec![IceServerConfig::new("turn:turn.example.org", "user", "pass").expect(error_line!())];
+    active_connection
+        .set_ice_servers(ice_servers.clone(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Without a restart, the connection carries on as is.
+    assert_eq!(
+        active_connection.ice_servers().expect(error_line!()),
+        Some(ice_servers)
+    );
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(context.offers_sent(), 1);
+
+    info!("test: replacing ice servers with an ice restart");
+    let ice_servers = vec![
+        IceServerConfig::new("turns:turn2.example.org:443", "user", "pass").expect(error_line!()),
+    ];
+    active_connection
+        .set_ice_servers(ice_servers.clone(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.ice_servers().expect(error_line!()),
+        Some(ice_servers)
+    );
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceRestarting
+    );
+    assert_eq!(context.offers_sent(), 2);
+    assert_eq!(context.restart_offers_sent(), 1);
+
+    info!("test: injecting answer");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(remote_id, "ANSWER".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: injecting ice connected");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The call stays connected throughout the restart.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 0);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnected), 0);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn proceed_with_expiring_ice_servers() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: proceeding with ice servers");
+    let active_call = context.active_call();
+    let turn = IceServerConfig::new("turn:turn.example.org", "user", "pass")
+        .expect(error_line!())
+        .with_ttl(Duration::from_millis(100));
+    let stun = IceServerConfig::new("stun:stun.example.org", "", "").expect(error_line!());
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1],
+        vec![turn.clone(), stun.clone()],
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // STUN servers come first.
+    let connection = active_call.get_connection(1).expect(error_line!());
+    assert_eq!(
+        connection.ice_servers().expect(error_line!()),
+        Some(vec![stun.clone(), turn])
+    );
+
+    info!("test: waiting for the turn credentials to expire");
+    thread::sleep(Duration::from_millis(200));
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.event_count(ApplicationEvent::IceServersExpiring), 1);
+
+    info!("test: rotating the turn credentials");
+    let turn = IceServerConfig::new("turn:turn.example.org", "user2", "pass2")
+        .expect(error_line!())
+        .with_ttl(Duration::from_millis(100));
+    cm.update_ice_servers(active_call.call_id(), vec![turn])
+        .expect(error_line!());
+    let turn = IceServerConfig::new("turn:turn.example.org", "user3", "pass3")
+        .expect(error_line!())
+        .with_ttl(Duration::from_secs(3600));
+    cm.update_ice_servers(active_call.call_id(), vec![stun, turn.clone()])
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The credentials replaced before expiring are not refreshed.
+    thread::sleep(Duration::from_millis(200));
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.event_count(ApplicationEvent::IceServersExpiring), 1);
+    assert_eq!(
+        connection.ice_servers().expect(error_line!()).unwrap()[1],
+        turn
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn proceed_with_fetched_turn_servers() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let turn = IceServerConfig::new("turn:turn.example.org", "user", "pass").expect(error_line!());
+    let stun = IceServerConfig::new("stun:stun.example.org", "", "").expect(error_line!());
+    context.set_turn_servers(vec![turn.clone(), stun.clone()]);
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: proceeding with stale ice servers");
+    let active_call = context.active_call();
+    let stale =
+        IceServerConfig::new("turn:turn.example.org", "stale", "stale").expect(error_line!());
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1],
+        vec![stale],
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The fetched servers replace the ones passed to proceed().
+    let connection = active_call.get_connection(1).expect(error_line!());
+    assert_eq!(context.turn_requests(), 1);
+    assert_eq!(
+        connection.ice_servers().expect(error_line!()),
+        Some(vec![stun, turn])
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn restart_ice_with_fetched_turn_servers() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    // Without servers from the application, the connection keeps
+    // the ones passed to proceed().
+    assert_eq!(context.turn_requests(), 1);
+    assert_eq!(active_connection.ice_servers().expect(error_line!()), None);
+
+    info!("test: restarting ice");
+    let turn = IceServerConfig::new("turn:turn.example.org", "user", "pass").expect(error_line!());
+    context.set_turn_servers(vec![turn.clone()]);
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    cm.restart_ice(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.turn_requests(), 2);
+    assert_eq!(context.restart_offers_sent(), 1);
+    assert_eq!(
+        active_connection.ice_servers().expect(error_line!()),
+        Some(vec![turn])
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn restart_ice_after_network_change() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    info!("test: restarting ice of another call");
+    cm.restart_ice(CallId::new(0)).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(context.offers_sent(), 1);
+
+    info!("test: network change disconnects ice");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceReconnecting
+    );
+
+    info!("test: restarting ice");
+    cm.restart_ice(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceRestarting
+    );
+    assert_eq!(context.offers_sent(), 2);
+    assert_eq!(context.restart_offers_sent(), 1);
+
+    info!("test: injecting answer");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(remote_id, "ANSWER".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: injecting ice connected");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The call survives the network change.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 1);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnected), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn expired_ice_candidates_restart_ice() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    let ice_candidate = IceCandidate::new("0".to_owned(), 1, "ICE-EXPIRED".to_owned());
+
+    info!("test: injecting ice candidates within the maximum age");
+    cm.received_ice_candidates(
+        remote_id,
+        std::slice::from_ref(&ice_candidate),
+        Duration::from_secs(59),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(context.restart_offers_sent(), 0);
+
+    info!("test: injecting expired ice candidates");
+    cm.received_ice_candidates(remote_id, &[ice_candidate], Duration::from_secs(61))
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceRestarting
+    );
+    assert_eq!(context.restart_offers_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn automatic_recovery_after_network_change() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    cm.set_auto_recovery(2, Duration::from_millis(100))
+        .expect(error_line!());
+
+    info!("test: network change disconnects ice");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceReconnecting
+    );
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 1);
+
+    info!("test: restarting ice after the backoff");
+    thread::sleep(Duration::from_millis(300));
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceRestarting
+    );
+    assert_eq!(context.restart_offers_sent(), 1);
+
+    info!("test: injecting answer and ice connected");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(remote_id, "ANSWER".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.event_count(ApplicationEvent::Reconnected), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn automatic_recovery_disabled() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    cm.set_auto_recovery(0, Duration::from_millis(100))
+        .expect(error_line!());
+
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    thread::sleep(Duration::from_millis(300));
+    cm.synchronize().expect(error_line!());
+
+    // The recovery is left to the application.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceReconnecting
+    );
+    assert_eq!(context.restart_offers_sent(), 0);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn bandwidth_mode_from_config() {
+    test_init();
+
+    let config = CallConfig {
+        bandwidth_mode: BandwidthMode::Low,
+        ..Default::default()
+    };
+    let context = start_outbound_n_remote_call_with_config(1, config);
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    assert_eq!(
+        active_call.bandwidth_mode().expect(error_line!()),
+        BandwidthMode::Low
+    );
+    assert_eq!(
+        active_connection.bandwidth_mode().expect(error_line!()),
+        BandwidthMode::Low
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn preferred_codecs_from_config() {
+    test_init();
+
+    let config = CallConfig::default()
+        .preferred_video_codec(VideoCodec::H264)
+        .preferred_audio_codec(AudioCodec::Opus);
+    assert_eq!(config.preferred_codecs(), vec!["H264", "opus"]);
+    assert!(CallConfig::default().preferred_codecs().is_empty());
+
+    let context = start_outbound_n_remote_call_with_config(1, config);
+    let active_call = context.active_call();
+
+    assert_eq!(
+        active_call.config().map(|c| c.video_codec),
+        Some(Some(VideoCodec::H264))
+    );
+    assert_eq!(context.offers_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn audio_config_from_config() {
+    test_init();
+
+    let audio_config = AudioConfig {
+        echo_cancellation: false,
+        ..Default::default()
+    };
+    let config = CallConfig::default().with_audio_config(audio_config);
+
+    let context = start_outbound_n_remote_call_with_config(1, config);
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    assert_eq!(
+        active_call.audio_config().expect(error_line!()),
+        Some(audio_config)
+    );
+    assert_eq!(
+        active_connection.audio_config().expect(error_line!()),
+        audio_config
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn media_constraints_from_config() {
+    test_init();
+
+    let constraints = MediaConstraints {
+        max_resolution:    VideoResolution::new(640, 360),
+        max_framerate:     15,
+        start_audio_muted: true,
+        start_video_muted: true,
+    };
+    let config = CallConfig::default().with_media_constraints(constraints);
+
+    let context = start_outbound_n_remote_call_with_config(1, config);
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    // Hard reset as soon as ICE fails, without restarting ICE.
+    cm.set_ice_restarts_before_hard_reset(0)
+        .expect(error_line!());
+
+    // The connection starts within the constraints.
+    assert_eq!(
+        active_connection
+            .video_max_resolution()
+            .expect(error_line!()),
+        VideoResolution::new(640, 360)
+    );
+    assert_eq!(
+        active_connection
+            .video_max_framerate()
+            .expect(error_line!()),
+        15
+    );
+    assert!(!active_call.outgoing_audio_enabled());
+    assert!(!active_connection
+        .outgoing_audio_enabled()
+        .expect(error_line!()));
+    assert_eq!(
+        active_connection.local_video_status().expect(error_line!()),
+        Some(false)
+    );
+
+    info!("test: unmuting the media once connected");
+    let call_id = active_call.call_id();
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(call_id)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+
+    cm.set_outgoing_audio_enabled(call_id, true)
+        .expect(error_line!());
+    cm.set_outgoing_video_enabled(call_id, true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(active_connection
+        .outgoing_audio_enabled()
+        .expect(error_line!()));
+    assert_eq!(
+        active_connection.local_video_status().expect(error_line!()),
+        Some(true)
+    );
+
+    info!("test: the framerate cap is kept across a hard reset");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    active_connection
+        .inject_ice_connection_failed()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection
+            .video_max_framerate()
+            .expect(error_line!()),
+        15
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn set_audio_config() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    assert_eq!(
+        active_connection.audio_config().expect(error_line!()),
+        AudioConfig::default()
+    );
+
+    let headset = AudioConfig {
+        echo_cancellation: false,
+        noise_suppression: false,
+        ..Default::default()
+    };
+
+    info!("test: setting the default audio config");
+    cm.set_default_audio_config(headset).expect(error_line!());
+    assert_eq!(cm.default_audio_config().expect(error_line!()), headset);
+    assert_eq!(
+        active_connection.audio_config().expect(error_line!()),
+        AudioConfig::default()
+    );
+
+    info!("test: setting the audio config of another call");
+    cm.set_audio_config(CallId::new(0), headset)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.audio_config().expect(error_line!()),
+        AudioConfig::default()
+    );
+
+    info!("test: setting the audio config of the call");
+    cm.set_audio_config(active_call.call_id(), headset)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.audio_config().expect(error_line!()),
+        headset
+    );
+    assert_eq!(
+        active_call.audio_config().expect(error_line!()),
+        Some(headset)
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn set_bandwidth_mode_renegotiates() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    assert_eq!(
+        active_connection.bandwidth_mode().expect(error_line!()),
+        BandwidthMode::Normal
+    );
+
+    info!("test: setting the bandwidth mode of another call");
+    cm.set_bandwidth_mode(CallId::new(0), BandwidthMode::Low)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.offers_sent(), 1);
+
+    info!("test: setting low bandwidth mode");
+    cm.set_bandwidth_mode(active_call.call_id(), BandwidthMode::Low)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_connection.bandwidth_mode().expect(error_line!()),
+        BandwidthMode::Low
+    );
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceRestarting
+    );
+    assert_eq!(context.offers_sent(), 2);
+
+    info!("test: injecting answer");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(remote_id, "ANSWER".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn camera_control() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+    let call_id = active_call.call_id();
+
+    info!("test: controlling the camera of another call");
+    cm.set_camera_enabled(CallId::new(0), true)
+        .expect(error_line!());
+    cm.switch_camera(CallId::new(0)).expect(error_line!());
+    cm.set_video_max_resolution(CallId::new(0), 640, 360)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(context.camera_states().is_empty());
+    assert_eq!(context.camera_switches(), 0);
+    assert_eq!(
+        active_connection
+            .video_max_resolution()
+            .expect(error_line!()),
+        VideoResolution::default()
+    );
+
+    info!("test: controlling the camera of the call");
+    cm.set_camera_enabled(call_id, true).expect(error_line!());
+    cm.switch_camera(call_id).expect(error_line!());
+    cm.set_camera_enabled(call_id, false).expect(error_line!());
+    cm.set_video_max_resolution(call_id, 640, 360)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.camera_states(), vec![true, false]);
+    assert_eq!(context.camera_switches(), 1);
+    assert_eq!(
+        active_connection
+            .video_max_resolution()
+            .expect(error_line!()),
+        VideoResolution::new(640, 360)
+    );
+
+    info!("test: controlling the camera after hangup");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    cm.set_camera_enabled(call_id, true).expect(error_line!());
+    cm.switch_camera(call_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.camera_states(), vec![true, false]);
+    assert_eq!(context.camera_switches(), 1);
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 1);
+}
+
+#[test]
+fn outgoing_audio_gain() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    // Hard reset as soon as ICE fails, without restarting ICE.
+    cm.set_ice_restarts_before_hard_reset(0)
+        .expect(error_line!());
+
+    assert_eq!(
+        active_connection
+            .outgoing_audio_gain()
+            .expect(error_line!()),
+        UNITY_OUTGOING_AUDIO_GAIN
+    );
+
+    active_connection
+        .set_outgoing_audio_gain(10.0)
+        .expect(error_line!());
+    assert_eq!(
+        active_connection
+            .outgoing_audio_gain()
+            .expect(error_line!()),
+        MAX_OUTGOING_AUDIO_GAIN
+    );
+
+    active_connection
+        .set_outgoing_audio_gain(-1.0)
+        .expect(error_line!());
+    assert_eq!(
+        active_connection
+            .outgoing_audio_gain()
+            .expect(error_line!()),
+        0.0
+    );
+
+    active_connection
+        .set_outgoing_audio_gain(2.5)
+        .expect(error_line!());
+
+    info!("test: injecting ice disconnected and failed");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    active_connection
+        .inject_ice_connection_failed()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The gain is applied to the rebuilt PeerConnection.
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::SendingOffer
+    );
+    assert_eq!(
+        active_connection
+            .outgoing_audio_gain()
+            .expect(error_line!()),
+        2.5
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn send_and_receive_dtmf() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .send_dtmf("123,#*", 100, 70)
+        .expect(error_line!());
+    assert!(active_connection.send_dtmf("12x", 100, 70).is_err());
+    assert!(active_connection.send_dtmf("1", 10, 70).is_err());
+    assert!(active_connection.send_dtmf("1", 100, 10).is_err());
+
+    for tone in "5#".chars() {
+        active_connection
+            .inject_received_dtmf(tone)
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.received_dtmf(), "5#");
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn inject_connection_error() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    active_connection.inject_internal_error(
+        SimError::TestError("fake_error".to_string()).into(),
+        "testing connection error injection",
+    );
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 1);
+    assert_eq!(context.ended_count(), 1);
+}
+
+#[test]
+fn inject_call_error() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_call = context.active_call();
+
+    active_call.inject_internal_error(
+        SimError::TestError("fake_error".to_string()).into(),
+        "testing call error injection",
+    );
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 1);
+    assert_eq!(context.ended_count(), 1);
+}
+
+#[test]
+fn inject_local_video_status() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_local_video_status(false)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+
+    // TODO -- verify that the data channel object sent a message
+}
+
+#[test]
+fn mute_before_connection_created() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    cm.call(remote_peer, CallConfig::default())
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Starting
+    );
+
+    info!("test: muting while starting");
+    cm.set_outgoing_audio_enabled(active_call.call_id(), false)
+        .expect(error_line!());
+
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The connection's audio starts disabled.
+    let connection = active_call
+        .get_connection(1 as DeviceId)
+        .expect(error_line!());
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connecting
+    );
+    assert!(!connection.outgoing_audio_enabled().expect(error_line!()));
+
+    info!("test: unmuting while connecting");
+    cm.set_outgoing_audio_enabled(active_call.call_id(), true)
+        .expect(error_line!());
+
+    assert!(connection.outgoing_audio_enabled().expect(error_line!()));
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn mute_signals_remote_peer() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    info!("test: muting an inactive call");
+    cm.set_outgoing_audio_enabled(CallId::new(PRNG.gen::<u64>()), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(active_connection
+        .outgoing_audio_enabled()
+        .expect(error_line!()));
+    assert_eq!(buffered_amount.load(Ordering::Acquire), 0);
+
+    info!("test: muting the active call");
+    cm.set_outgoing_audio_enabled(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(!active_connection
+        .outgoing_audio_enabled()
+        .expect(error_line!()));
+    let muted = buffered_amount.load(Ordering::Acquire);
+    assert!(muted > 0);
+
+    info!("test: unmuting the active call");
+    cm.set_outgoing_audio_enabled(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(active_connection
+        .outgoing_audio_enabled()
+        .expect(error_line!()));
+    assert!(buffered_amount.load(Ordering::Acquire) > muted);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn inject_local_ice_candidate() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    let ice_candidate = IceCandidate::new("fake_spd_mid".to_string(), 0, "fake_spd".to_string());
+    active_connection
+        .inject_local_ice_candidate(ice_candidate)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ice_candidates_sent(), 1);
+}
+
+#[test]
+fn batch_local_ice_candidates() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    cm.set_ice_candidate_batch_period(Duration::from_millis(200))
+        .expect(error_line!());
+    let ice_messages_sent = context.ice_messages_sent();
+
+    for i in 0..3 {
+        let ice_candidate = IceCandidate::new("0".to_owned(), 0, format!("ICE-{}", i));
+        active_connection
+            .inject_local_ice_candidate(ice_candidate)
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.ice_candidates_sent(), 0);
+
+    info!("test: batch period expires");
+    thread::sleep(Duration::from_millis(400));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.ice_candidates_sent(), 3);
+    assert_eq!(context.ice_messages_sent(), ice_messages_sent + 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn persist_and_restore_pending_signaling() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    context.force_signaling_offline(true);
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1 as DeviceId, 2 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: the second offer waits for the first to be sent");
+    assert_eq!(context.offers_sent(), 1);
+    let pending = context.pending_signaling();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].remote_peer, "REMOTE_PEER");
+    assert_eq!(pending[0].connection_id.remote_device(), 2 as DeviceId);
+    match &pending[0].message {
+        PendingMessage::Offer { ice_restart, .. } => assert!(!ice_restart),
+        message => panic!("unexpected pending message: {}", message),
+    }
+
+    info!("test: queueing local ICE candidates");
+    let mut connection = active_call
+        .get_connection(1 as DeviceId)
+        .expect(error_line!());
+    let ice_candidate = IceCandidate::new("0".to_owned(), 0, "ICE-1".to_owned());
+    connection
+        .inject_local_ice_candidate(ice_candidate)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let pending = context.pending_signaling();
+    assert_eq!(pending.len(), 2);
+    match &pending[1].message {
+        PendingMessage::IceCandidates(candidates) => assert_eq!(candidates.len(), 1),
+        message => panic!("unexpected pending message: {}", message),
+    }
+
+    info!("test: restoring after a restart");
+    let restarted = TestContext::new();
+    let mut restarted_cm = restarted.cm();
+    restarted.set_pending_signaling(pending);
+    restarted_cm
+        .restore_pending_signaling()
+        .expect(error_line!());
+    restarted_cm.synchronize().expect(error_line!());
+
+    assert_eq!(restarted.offers_sent(), 1);
+    assert_eq!(restarted.ice_candidates_sent(), 1);
+    assert!(restarted.pending_signaling().is_empty());
+    assert_eq!(restarted.error_count(), 0);
+}
+
+/// A signaling transport recording the messages it sends, instead
+/// of handing them to the platform.
+#[derive(Default)]
+struct DirectTransport {
+    sent: Arc<Mutex<Vec<String>>>,
+}
+
+impl DirectTransport {
+    fn record(&self, message: &str, connection_id: ConnectionId) -> ringrtc::common::Result<()> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", message, connection_id.remote_device()));
+        Ok(())
+    }
+}
+
+impl SignalingTransport<SimPlatform> for DirectTransport {
+    fn send_offer(
+        &self,
+        _platform: &SimPlatform,
+        _remote_peer: &String,
+        connection_id: ConnectionId,
+        _broadcast: bool,
+        _description: &str,
+        _descriptor: &MediaDescriptor,
+    ) -> ringrtc::common::Result<()> {
+        self.record("offer", connection_id)
+    }
+
+    fn send_answer(
+        &self,
+        _platform: &SimPlatform,
+        _remote_peer: &String,
+        connection_id: ConnectionId,
+        _broadcast: bool,
+        _description: &str,
+        _descriptor: &MediaDescriptor,
+    ) -> ringrtc::common::Result<()> {
+        self.record("answer", connection_id)
+    }
+
+    fn send_ice_candidates(
+        &self,
+        _platform: &SimPlatform,
+        _remote_peer: &String,
+        connection_id: ConnectionId,
+        _broadcast: bool,
+        _candidates: &[IceCandidate],
+    ) -> ringrtc::common::Result<()> {
+        self.record("ice", connection_id)
+    }
+
+    fn send_hangup(
+        &self,
+        _platform: &SimPlatform,
+        _remote_peer: &String,
+        connection_id: ConnectionId,
+        _broadcast: bool,
+        _hangup_type: HangupType,
+    ) -> ringrtc::common::Result<()> {
+        self.record("hangup", connection_id)
+    }
+
+    fn send_busy(
+        &self,
+        _platform: &SimPlatform,
+        _remote_peer: &String,
+        connection_id: ConnectionId,
+        _broadcast: bool,
+    ) -> ringrtc::common::Result<()> {
+        self.record("busy", connection_id)
+    }
+}
+
+#[test]
+fn direct_signaling_transport() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    let transport = DirectTransport::default();
+    let sent = Arc::clone(&transport.sent);
+    cm.set_signaling_transport(Box::new(transport))
+        .expect(error_line!());
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: sending the offer");
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(*sent.lock().unwrap(), vec!["offer 1"]);
+
+    info!("test: local ICE candidates wait for the offer to be sent");
+    let mut connection = active_call
+        .get_connection(1 as DeviceId)
+        .expect(error_line!());
+    let ice_candidate = IceCandidate::new("0".to_owned(), 0, "ICE-1".to_owned());
+    connection
+        .inject_local_ice_candidate(ice_candidate)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(*sent.lock().unwrap(), vec!["offer 1"]);
+
+    cm.message_sent(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(*sent.lock().unwrap(), vec!["offer 1", "ice 1"]);
+
+    info!("test: hanging up");
+    cm.message_sent(active_call.call_id()).expect(error_line!());
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(*sent.lock().unwrap(), vec!["offer 1", "ice 1", "hangup 0"]);
+
+    // Nothing went through the platform.
+    assert_eq!(context.offers_sent(), 0);
+    assert_eq!(context.ice_candidates_sent(), 0);
+    assert_eq!(context.hangups_sent(), 0);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn relay_only_ice_candidate_policy() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_ice_candidate_policy(IceCandidatePolicy {
+            relay_only: true,
+            ..Default::default()
+        })
+        .expect(error_line!());
+
+    info!("test: dropping a host candidate");
+    let host = IceCandidate::new(
+        "0".to_owned(),
+        0,
+        "candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host".to_owned(),
+    );
+    active_connection
+        .inject_local_ice_candidate(host)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.ice_candidates_sent(), 0);
+
+    info!("test: sending a relay candidate");
+    let relay = IceCandidate::new(
+        "0".to_owned(),
+        0,
+        "candidate:2 1 udp 41885439 203.0.113.1 3478 typ relay".to_owned(),
+    );
+    active_connection
+        .inject_local_ice_candidate(relay)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn receive_remote_ice_candidate() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    // add a received ICE candidate
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    let ice_candidate = IceCandidate::new(
+        "0".to_owned(),
+        1,
+        format!("ICE-{}", PRNG.gen::<u16>()).to_owned(),
+    );
+    cm.received_ice_candidates(remote_id, &[ice_candidate], Duration::from_secs(0))
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+
+    // TODO -- verify the ice candidate was buffered
+    // TODO -- verify the ice candidate was applied to the peer_connection
+}
+
+#[test]
+fn receive_remote_ice_candidate_flood() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    let ice_candidates = |count: usize| -> Vec<IceCandidate> {
+        (0..count)
+            .map(|i| IceCandidate::new("0".to_owned(), 1, format!("ICE-{}", i)))
+            .collect()
+    };
+
+    // One candidate was received while setting up the call.
+    info!("test: receiving candidates up to the limit");
+    cm.received_ice_candidates(
+        remote_id,
+        &ice_candidates(MAX_RECEIVED_ICE_CANDIDATES - 2),
+        Duration::from_secs(0),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection
+            .dropped_remote_ice_candidates()
+            .expect(error_line!()),
+        0
+    );
+
+    info!("test: receiving a burst of candidates");
+    cm.received_ice_candidates(remote_id, &ice_candidates(20), Duration::from_secs(0))
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection
+            .dropped_remote_ice_candidates()
+            .expect(error_line!()),
+        19
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn received_remote_hangup() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_hangup(remote_id, HangupType::Normal)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 1);
+}
+
+#[test]
+fn received_typed_remote_hangup() {
+    test_init();
+
+    for (hangup_type, event) in &[
+        (
+            HangupType::Accepted,
+            ApplicationEvent::EndedRemoteHangupAccepted,
+        ),
+        (HangupType::Busy, ApplicationEvent::EndedRemoteHangupBusy),
+        (
+            HangupType::NeedPermission,
+            ApplicationEvent::EndedRemoteHangupNeedPermission,
+        ),
+        (HangupType::Declined, ApplicationEvent::EndedRemoteDeclined),
+    ] {
+        info!("test: receiving hangup: {}", hangup_type);
+
+        let context = start_outbound_call();
+        let mut cm = context.cm();
+        let active_call = context.active_call();
+
+        let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+        cm.received_hangup(remote_id, *hangup_type)
+            .expect(error_line!());
+
+        cm.synchronize().expect(error_line!());
+
+        assert_eq!(context.error_count(), 0);
+        assert_eq!(context.event_count(*event), 1);
+        assert_eq!(context.ended_count(), 1);
+        assert_eq!(cm.call_active().expect(error_line!()), false);
+    }
+}
+
+#[test]
+fn received_typed_remote_hangup_via_data_channel() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_remote_hangup(active_call.call_id(), HangupType::Accepted)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteHangupAccepted),
+        1
+    );
+    assert_eq!(context.ended_count(), 1);
+}
+
+#[test]
+fn received_remote_video_status() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    let mut enable_count = 0;
+    let mut disable_count = 0;
+    for _i in 0..20 {
+        let enable = PRNG.gen::<bool>();
+
+        active_connection
+            .inject_remote_video_status(active_call.call_id(), enable)
+            .expect(error_line!());
+        cm.synchronize().expect(error_line!());
+
+        if enable {
+            enable_count += 1;
+        } else {
+            disable_count += 1;
+        }
+
+        assert_eq!(context.error_count(), 0);
+        assert_eq!(
+            context.event_count(ApplicationEvent::RemoteVideoEnable),
+            enable_count
+        );
+        assert_eq!(
+            context.event_count(ApplicationEvent::RemoteVideoDisable),
+            disable_count
+        );
+    }
+}
+
+#[test]
+fn audio_route_suggested_for_remote_video() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+    let call_id = active_call.call_id();
+
+    assert_eq!(
+        cm.audio_output().expect(error_line!()),
+        AudioRoute::Earpiece
+    );
+
+    info!("test: remote video enabled, from the earpiece");
+    active_connection
+        .inject_remote_video_status(call_id, true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.audio_routes(), vec![AudioRoute::Speaker]);
+    assert_eq!(cm.audio_output().expect(error_line!()), AudioRoute::Speaker);
+
+    info!("test: remote video enabled again, from the speaker");
+    active_connection
+        .inject_remote_video_status(call_id, false)
+        .expect(error_line!());
+    active_connection
+        .inject_remote_video_status(call_id, true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.audio_routes(), vec![AudioRoute::Speaker]);
+
+    info!("test: remote video enabled, from a headset");
+    cm.set_audio_output(AudioRoute::Bluetooth)
+        .expect(error_line!());
+    active_connection
+        .inject_remote_video_status(call_id, true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.audio_routes(), vec![AudioRoute::Speaker]);
+    assert_eq!(
+        cm.audio_output().expect(error_line!()),
+        AudioRoute::Bluetooth
+    );
+
+    info!("test: remote video enabled, back on the earpiece");
+    cm.set_audio_output(AudioRoute::Earpiece)
+        .expect(error_line!());
+    active_connection
+        .inject_remote_video_status(call_id, true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        context.audio_routes(),
+        vec![AudioRoute::Speaker, AudioRoute::Speaker]
+    );
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn video_status_signals_remote_peer() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    info!("test: enabling video of an inactive call");
+    cm.set_outgoing_video_enabled(CallId::new(PRNG.gen::<u64>()), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(buffered_amount.load(Ordering::Acquire), 0);
+
+    info!("test: toggling video of the active call");
+    cm.set_outgoing_video_enabled(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let enabled = buffered_amount.load(Ordering::Acquire);
+    assert!(enabled > 0);
+
+    cm.set_outgoing_video_enabled(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(buffered_amount.load(Ordering::Acquire) > enabled);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn received_remote_audio_status() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_remote_audio_status(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioMuted), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioUnmuted), 0);
+
+    info!("test: status for an inactive call");
+    active_connection
+        .inject_remote_audio_status(CallId::new(PRNG.gen::<u64>()), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioUnmuted), 0);
+
+    active_connection
+        .inject_remote_audio_status(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioMuted), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioUnmuted), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn hold_and_resume_call() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    info!("test: holding an inactive call");
+    cm.set_on_hold(CallId::new(PRNG.gen::<u64>()), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(!active_connection.on_hold().expect(error_line!()));
+    assert_eq!(buffered_amount.load(Ordering::Acquire), 0);
+
+    info!("test: holding the active call");
+    cm.set_on_hold(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(active_connection.on_hold().expect(error_line!()));
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::OnHold
+    );
+    let held = buffered_amount.load(Ordering::Acquire);
+    assert!(held > 0);
+
+    info!("test: reconnecting while on hold");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceReconnecting
+    );
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::OnHold
+    );
+
+    info!("test: resuming the active call");
+    cm.set_on_hold(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(!active_connection.on_hold().expect(error_line!()));
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert!(buffered_amount.load(Ordering::Acquire) > held);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn transfer_call() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    info!("test: transferring an inactive call");
+    cm.transfer_call(CallId::new(PRNG.gen::<u64>()), "target".to_string())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.transfer_target().expect(error_line!()),
+        None
+    );
+    assert_eq!(buffered_amount.load(Ordering::Acquire), 0);
+
+    info!("test: transfer declined");
+    cm.transfer_call(active_call.call_id(), "target".to_string())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.transfer_target().expect(error_line!()),
+        Some("target".to_string())
+    );
+    assert!(buffered_amount.load(Ordering::Acquire) > 0);
+
+    active_connection
+        .inject_remote_transfer(active_call.call_id(), TransferMessage::Declined)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.transfer_target().expect(error_line!()),
+        None
+    );
+    assert_eq!(context.event_count(ApplicationEvent::TransferDeclined), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+
+    info!("test: unsolicited transfer answer");
+    active_connection
+        .inject_remote_transfer(active_call.call_id(), TransferMessage::Accepted)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.ended_count(), 0);
+
+    info!("test: transfer accepted");
+    cm.transfer_call(active_call.call_id(), "target".to_string())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    active_connection
+        .inject_remote_transfer(active_call.call_id(), TransferMessage::Accepted)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedTransferred), 1);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.hangups_sent(), 1);
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn received_transfer_request() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    info!("test: transfer declined by default");
+    active_connection
+        .inject_remote_transfer(
+            active_call.call_id(),
+            TransferMessage::Request("first".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.transfer_requests(), vec!["first".to_string()]);
+    assert_eq!(
+        active_connection
+            .remote_transfer_target()
+            .expect(error_line!()),
+        None
+    );
+    let declined = buffered_amount.load(Ordering::Acquire);
+    assert!(declined > 0);
+
+    info!("test: transfer accepted");
+    context.force_transfer_accept(true);
+    active_connection
+        .inject_remote_transfer(
+            active_call.call_id(),
+            TransferMessage::Request("second".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.transfer_requests(),
+        vec!["first".to_string(), "second".to_string()]
+    );
+    assert!(buffered_amount.load(Ordering::Acquire) > declined);
+
+    // The call goes on until the remote peer hangs up.
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn negotiate_tracks_mid_call() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    info!("test: local tracks changed");
+    active_connection
+        .inject_negotiation_needed()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(active_connection
+        .negotiation()
+        .expect(error_line!())
+        .making_offer());
+    let offered = buffered_amount.load(Ordering::Acquire);
+    assert!(offered > 0);
+
+    info!("test: local tracks changed again before the answer");
+    active_connection
+        .inject_negotiation_needed()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(buffered_amount.load(Ordering::Acquire), offered);
+
+    info!("test: answered, offering the later change");
+    active_connection
+        .inject_remote_negotiation(
+            active_call.call_id(),
+            NegotiationMessage::Answer("ANSWER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    {
+        let negotiation = active_connection.negotiation().expect(error_line!());
+        assert_eq!(negotiation.negotiations(), 1);
+        assert!(negotiation.making_offer());
+    }
+    let offered_again = buffered_amount.load(Ordering::Acquire);
+    assert!(offered_again > offered);
+
+    active_connection
+        .inject_remote_negotiation(
+            active_call.call_id(),
+            NegotiationMessage::Answer("ANSWER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    {
+        let negotiation = active_connection.negotiation().expect(error_line!());
+        assert_eq!(negotiation.negotiations(), 2);
+        assert!(!negotiation.making_offer());
+    }
+
+    info!("test: answer without a pending offer");
+    active_connection
+        .inject_remote_negotiation(
+            active_call.call_id(),
+            NegotiationMessage::Answer("ANSWER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection
+            .negotiation()
+            .expect(error_line!())
+            .negotiations(),
+        2
+    );
+
+    info!("test: remote tracks changed");
+    active_connection
+        .inject_remote_negotiation(
+            active_call.call_id(),
+            NegotiationMessage::Offer("OFFER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection
+            .negotiation()
+            .expect(error_line!())
+            .negotiations(),
+        3
+    );
+    assert!(buffered_amount.load(Ordering::Acquire) > offered_again);
+
+    info!("test: negotiation for an inactive call");
+    let answered = buffered_amount.load(Ordering::Acquire);
+    active_connection
+        .inject_remote_negotiation(
+            CallId::new(PRNG.gen::<u64>()),
+            NegotiationMessage::Offer("OFFER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(buffered_amount.load(Ordering::Acquire), answered);
+
+    // The offers and answers never go over signaling.
+    assert_eq!(context.offers_sent(), 1);
+    assert_eq!(context.answers_sent(), 0);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn negotiation_offers_collide_as_caller() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    active_connection
+        .inject_negotiation_needed()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let offered = buffered_amount.load(Ordering::Acquire);
+
+    info!("test: the impolite caller ignores the colliding offer");
+    active_connection
+        .inject_remote_negotiation(
+            active_call.call_id(),
+            NegotiationMessage::Offer("OFFER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    {
+        let negotiation = active_connection.negotiation().expect(error_line!());
+        assert_eq!(negotiation.negotiations(), 0);
+        assert!(negotiation.making_offer());
+    }
+    assert_eq!(buffered_amount.load(Ordering::Acquire), offered);
+
+    info!("test: the polite callee answers ours instead");
+    active_connection
+        .inject_remote_negotiation(
+            active_call.call_id(),
+            NegotiationMessage::Answer("ANSWER".to_string()),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    {
+        let negotiation = active_connection.negotiation().expect(error_line!());
+        assert_eq!(negotiation.negotiations(), 1);
+        assert!(!negotiation.making_offer());
+    }
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn negotiation_deferred_until_connected() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: local tracks changed before the call connected");
+    active_connection
+        .inject_negotiation_needed()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(!active_connection
+        .negotiation()
+        .expect(error_line!())
+        .making_offer());
+    assert_eq!(buffered_amount.load(Ordering::Acquire), 0);
+
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert!(active_connection
+        .negotiation()
+        .expect(error_line!())
+        .making_offer());
+    assert!(buffered_amount.load(Ordering::Acquire) > 0);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn received_remote_hold() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_remote_hold(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteOnHold), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteResumed), 0);
+
+    info!("test: hold for an inactive call");
+    active_connection
+        .inject_remote_hold(CallId::new(PRNG.gen::<u64>()), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteResumed), 0);
+
+    active_connection
+        .inject_remote_hold(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteOnHold), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteResumed), 1);
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn export_trace() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_connection = context.active_connection();
+
+    let trace = active_connection.export_trace().expect(error_line!());
+    assert!(trace
+        .iter()
+        .any(|e| e.kind == TraceKind::StateChange && e.remote_device.is_none()));
+    assert!(trace
+        .iter()
+        .any(|e| e.kind == TraceKind::StateChange && e.remote_device == Some(1)));
+    assert!(trace.iter().any(|e| e.kind == TraceKind::Signaling));
+    assert!(trace
+        .windows(2)
+        .all(|w| w[0].timestamp_ms <= w[1].timestamp_ms));
+
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The trace remains available after the call concludes.
+    let concluded = active_connection.export_trace().expect(error_line!());
+    assert!(concluded.len() >= trace.len());
+    assert!(concluded
+        .iter()
+        .any(|e| e.description.contains("Terminating")));
+}
+
+#[test]
+fn get_call_trace() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    let trace = cm
+        .get_call_trace(active_call.call_id())
+        .expect(error_line!());
+    assert!(trace
+        .iter()
+        .any(|e| e.kind == TraceKind::StateChange && e.remote_device.is_none()));
+    assert!(trace
+        .iter()
+        .any(|e| e.kind == TraceKind::FsmEvent && e.remote_device == Some(1)));
+    assert!(trace
+        .windows(2)
+        .all(|w| w[0].timestamp_ms <= w[1].timestamp_ms));
+
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Concluded calls are no longer held by the call manager.
+    assert!(cm.get_call_trace(active_call.call_id()).is_err());
+}
+
+#[test]
+fn get_call_trace_disabled() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_tracing(false).expect(error_line!());
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        vec![1],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let trace = cm
+        .get_call_trace(active_call.call_id())
+        .expect(error_line!());
+    assert!(trace.is_empty());
+}
+
+#[test]
+fn export_trace_is_bounded() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    for i in 0..(MAX_TRACE_EVENTS * 2) {
+        active_connection
+            .inject_local_video_status(i % 2 == 0)
+            .expect(error_line!());
+        // don't overflow the FSM event queue
+        if i % 64 == 0 {
+            cm.synchronize().expect(error_line!());
+        }
+    }
+    cm.synchronize().expect(error_line!());
+
+    // Connection and Call each retain at most MAX_TRACE_EVENTS.
+    let trace = active_connection.export_trace().expect(error_line!());
+    let connection_events = trace.iter().filter(|e| e.remote_device.is_some()).count();
+    assert_eq!(connection_events, MAX_TRACE_EVENTS);
+    assert!(trace.len() <= MAX_TRACE_EVENTS * 2);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn call_timeout_before_connect() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_call = context.active_call();
+
+    active_call.inject_call_timeout().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+    assert_eq!(
+        context.call_failures(),
+        vec![CallFailureReason::SignalingTimeout]
+    );
+}
+
+#[test]
+fn call_timeout_after_connect() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_call = context.active_call();
+
+    active_call.inject_call_timeout().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The call is already connected, so the timeout is ignored.
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 0);
+}
+
+#[test]
+fn ice_gathering_timeout_before_ringing() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let config = CallConfig {
+        ice_gathering_timeout: Duration::from_millis(200),
+        ..Default::default()
+    };
+    cm.call("REMOTE_PEER".to_owned(), config)
+        .expect(error_line!());
+
+    thread::sleep(Duration::from_millis(500));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+}
+
+#[test]
+fn ring_timeout_while_ringing() {
+    test_init();
+
+    let config = CallConfig {
+        ring_timeout: Duration::from_millis(1000),
+        ice_gathering_timeout: Duration::from_millis(500),
+        ..Default::default()
+    };
+    let context = start_outbound_n_remote_call_with_config(1, config);
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Ringing
+    );
+
+    // The call rang before the ICE gathering timeout.
+    thread::sleep(Duration::from_millis(600));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 0);
+
+    thread::sleep(Duration::from_millis(600));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+}
+
+#[test]
+fn outbound_proceed_with_error() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    cm.call(remote_peer, CallConfig::default())
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(cm.active_call().is_ok(), true);
+    assert_eq!(context.start_outgoing_count(), 1);
+    assert_eq!(context.start_incoming_count(), 0);
+
+    let active_call = context.active_call();
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Starting
+    );
+
+    // cause the sending of the offer to fail.
+    context.force_internal_fault(true);
+
+    let active_call = context.active_call();
+    let mut remote_devices = Vec::<DeviceId>::new();
+    remote_devices.push(1);
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        remote_devices,
+        Vec::new(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+
+    // Two errors -- one from the failed send_offer and another from
+    // the failed send_hangup, sent as part of the error clean up.
+    assert_eq!(context.error_count(), 2);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedInternalFailure),
+        2
+    );
+    assert_eq!(context.offers_sent(), 0);
+    assert_eq!(
+        context.call_failures(),
+        vec![
+            CallFailureReason::InternalFsmError,
+            CallFailureReason::InternalFsmError
+        ]
+    );
+
+    context.force_internal_fault(false);
+}
+
+#[test]
+fn outbound_proceed_with_peer_connection_error() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    cm.call(remote_peer, CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // cause the creation of the PeerConnection to fail.
+    context.force_peer_connection_fault(true);
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        vec![1],
+        Vec::new(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedInternalFailure),
+        1
+    );
+    assert_eq!(
+        context.call_failures(),
+        vec![CallFailureReason::PeerConnectionCreateFailed]
+    );
+    assert_eq!(context.offers_sent(), 0);
+}
+
+#[test]
+fn outbound_call_connected_local_hangup_with_error() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    // cause the sending of the hangup to fail.
+    context.force_internal_fault(true);
+
+    cm.hangup().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 1);
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(context.ended_count(), 2);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedInternalFailure),
+        1
+    );
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(context.hangups_sent(), 0);
+}
+
+#[test]
+fn local_ice_candidate_with_error() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    // cause the sending of the ICE candidate to fail.
+    context.force_internal_fault(true);
+
+    let ice_candidate = IceCandidate::new("fake_spd_mid".to_string(), 0, "fake_spd".to_string());
+    active_connection
+        .inject_local_ice_candidate(ice_candidate)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // Two errors -- one from the failed send_ice_candidates and another from
+    // the failed send_hangup, sent as part of the error clean up.
+    assert_eq!(context.error_count(), 2);
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedInternalFailure),
+        2
+    );
+    // We should see that no ICE candidates were sent
+    assert_eq!(context.ice_candidates_sent(), 0);
+}
+
+// Create outbound call to multiple remote devices.
+//
+// One of the remote devices accepts the call, which is followed by a
+// local hangup.
+#[test]
+fn outbound_multiple_remote_devices() {
+    test_init();
+
+    let n_remotes: u16 = 5;
+    let context = start_outbound_n_remote_call(n_remotes);
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    for i in 1..(n_remotes + 1) {
+        let mut connection = active_call
+            .get_connection(i as DeviceId)
+            .expect(error_line!());
+
+        info!("test:{}: injecting ice connected", i);
+        connection.inject_ice_connected().expect(error_line!());
+
+        cm.synchronize().expect(error_line!());
+
+        assert_eq!(
+            connection.state().expect(error_line!()),
+            ConnectionState::IceConnected
+        );
+        assert_eq!(
+            active_call.state().expect(error_line!()),
+            CallState::Ringing
+        );
+        assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 1);
+        assert_eq!(context.error_count(), 0);
+        assert_eq!(context.ended_count(), 0);
+
+        info!("test:{}: add media stream", i);
+        connection
+            .on_add_stream(MediaStream::new(ptr::null()))
+            .expect(error_line!());
+    }
+
+    // connect one of the remotes
+    let active_remote = (PRNG.gen::<u16>() % n_remotes) + 1;
     let mut active_connection = active_call
         .get_connection(active_remote as DeviceId)
         .expect(error_line!());
 
-    info!(
-        "test:active_remote:{}: injecting call connected",
-        active_remote
+    info!(
+        "test:active_remote:{}: injecting call connected",
+        active_remote
+    );
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::CallConnected
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteConnected), 1);
+    assert_eq!(context.stream_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+
+    // the other remotes are told the call was accepted elsewhere
+    assert_eq!(context.hangups_sent(), 1);
+    assert_eq!(context.hangup_types(), vec![HangupType::Accepted]);
+    let all_devices: Vec<DeviceId> = (1..(n_remotes + 1)).map(|i| i as DeviceId).collect();
+    let other_devices: Vec<DeviceId> = all_devices
+        .iter()
+        .cloned()
+        .filter(|device| *device != active_remote as DeviceId)
+        .collect();
+    assert_eq!(context.hangup_devices(), vec![other_devices.clone()]);
+
+    cm.hangup().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(context.hangups_sent(), 2);
+    assert_eq!(
+        context.hangup_types(),
+        vec![HangupType::Accepted, HangupType::Normal]
+    );
+    assert_eq!(context.hangup_devices(), vec![other_devices, all_devices]);
+}
+
+// Create multiple call managers, each managing one outbound call.
+//
+// Each call is connected and then followed by a remote hangup.
+//
+// Each call manager is on a separate thread
+#[test]
+fn outbound_multiple_call_managers() {
+    test_init();
+
+    let n_call_manager = 5;
+
+    let mut thread_vec = Vec::new();
+    for i in 0..n_call_manager {
+        info!("test:{}: creating call manager", i);
+
+        let child = thread::spawn(move || {
+            outbound_multiple_remote_devices();
+        });
+
+        thread_vec.push(child);
+    }
+
+    info!("test: joinging threads");
+    for child in thread_vec {
+        info!("test: joinging thread...");
+        // Make sure no threads panicked
+        assert!(child.join().is_ok());
+    }
+}
+
+// Send the active remote peer's offer for the call `call_id`, as if
+// the two users called each other at the same time.
+fn receive_glare_offer(context: &TestContext, call_id: CallId) {
+    let mut cm = context.cm();
+
+    let remote_peer = {
+        let active_call = context.active_call();
+        let remote_peer = active_call.remote_peer().expect(error_line!());
+        remote_peer.to_owned()
+    };
+    info!("active remote_peer: {}", remote_peer);
+
+    let connection_id = ConnectionId::new(call_id, 1 as DeviceId);
+    cm.received_offer(
+        remote_peer,
+        connection_id,
+        format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect(error_line!())
+            .as_millis() as u64,
+        Duration::from_secs(0),
+        CallConfig::default(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+}
+
+// Two users call each other at the same time, the active call has
+// the larger CallId.
+#[test]
+fn glare_active_call_wins() {
+    test_init();
+
+    let context = start_outbound_call();
+    let active_call = context.active_call();
+
+    receive_glare_offer(&context, CallId::new(0));
+
+    // glare case should send a busy to the new caller and keep the
+    // current call.
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedReceivedOfferWithGlare),
+        1
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedReceivedOfferWhileActive),
+        0
+    );
+    assert_eq!(context.busys_sent(), 1);
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteGlare), 0);
+    assert_eq!(context.call_concluded_count(), 1);
+    assert_eq!(context.active_call().call_id(), active_call.call_id());
+    assert_eq!(context.ended_count(), 0);
+}
+
+// Two users call each other at the same time, the received offer has
+// the larger CallId.
+#[test]
+fn glare_received_offer_wins() {
+    test_init();
+
+    let context = start_outbound_call();
+    let call_id = CallId::new(u64::MAX);
+
+    receive_glare_offer(&context, call_id);
+
+    // glare case should hang up the current call and answer the new
+    // caller.
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteGlare), 1);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedReceivedOfferWithGlare),
+        0
+    );
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(context.hangups_sent(), 1);
+    assert_eq!(context.call_concluded_count(), 1);
+    assert_eq!(context.start_incoming_count(), 1);
+
+    let active_call = context.active_call();
+    assert_eq!(active_call.call_id(), call_id);
+    assert_eq!(active_call.direction(), CallDirection::InComing);
+}
+
+// Receive a busy message when trying to establish outbound call
+#[test]
+fn start_outbound_receive_busy() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    cm.call(remote_peer, CallConfig::default())
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(cm.active_call().is_ok(), true);
+    assert_eq!(context.start_outgoing_count(), 1);
+    assert_eq!(context.start_incoming_count(), 0);
+
+    let call_id = {
+        let active_call = context.active_call();
+        assert_eq!(
+            active_call.state().expect(error_line!()),
+            CallState::Starting
+        );
+        active_call.call_id()
+    };
+
+    let mut remote_devices = Vec::<DeviceId>::new();
+
+    remote_devices.push(1);
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        remote_devices,
+        Vec::new(),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // Receive a busy message
+    let remote_id = ConnectionId::new(call_id, 1 as DeviceId);
+    cm.received_busy(remote_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteBusy), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.call_concluded_count(), 1);
+}
+
+#[test]
+fn call_id_collision() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let call_id = active_call.call_id();
+
+    info!("test: calling with the CallId of the active call");
+    let error = cm
+        .call_with_id("REMOTE_PEER".to_owned(), call_id, CallConfig::default())
+        .expect_err(error_line!());
+    assert_eq!(
+        error.to_string(),
+        format!("CallId already belongs to a live call, id: {}", call_id)
+    );
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.start_outgoing_count(), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.error_count(), 0);
+
+    info!("test: regenerating the colliding CallId");
+    cm.set_regenerate_call_ids(true).expect(error_line!());
+    let new_call_id = cm
+        .call_with_id("REMOTE_PEER".to_owned(), call_id, CallConfig::default())
+        .expect(error_line!());
+    assert_ne!(new_call_id, call_id);
+
+    cm.synchronize().expect(error_line!());
+
+    // The new call fails on its own, as a call is in progress,
+    // leaving the active call alone.
+    assert!(context
+        .call_events()
+        .contains(&(new_call_id, ApplicationEvent::EndedInternalFailure)));
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(cm.active_call().expect(error_line!()).call_id(), call_id);
+}
+
+#[test]
+fn outbound_busy_retry() {
+    test_init();
+
+    let config = CallConfig::default().retry_on_busy(Duration::from_millis(100), 1);
+    let context = start_outbound_n_remote_call_with_config(1, config);
+    let mut cm = context.cm();
+    let busy_call_id = context.active_call().call_id();
+
+    info!("test: receiving busy");
+    let remote_id = ConnectionId::new(busy_call_id, 1 as DeviceId);
+    cm.received_busy(remote_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteBusyRetry),
+        1
+    );
+    assert_eq!(context.call_concluded_count(), 1);
+    assert_eq!(cm.active_call().is_ok(), false);
+
+    info!("test: calling again after the interval");
+    thread::sleep(Duration::from_millis(300));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.start_outgoing_count(), 2);
+    let active_call = context.active_call();
+    assert_ne!(active_call.call_id(), busy_call_id);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Starting
+    );
+
+    info!("test: receiving busy again, attempts exhausted");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_busy(remote_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteBusy), 1);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteBusyRetry),
+        1
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn outbound_busy_retry_cancelled_by_hangup() {
+    test_init();
+
+    let config = CallConfig::default().retry_on_busy(Duration::from_millis(100), 3);
+    let context = start_outbound_n_remote_call_with_config(1, config);
+    let mut cm = context.cm();
+    let busy_call_id = context.active_call().call_id();
+
+    let remote_id = ConnectionId::new(busy_call_id, 1 as DeviceId);
+    cm.received_busy(remote_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: hanging up before calling again");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::BusyRetryCancelled), 1);
+
+    thread::sleep(Duration::from_millis(300));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.start_outgoing_count(), 1);
+    assert_eq!(cm.active_call().is_ok(), false);
+    assert_eq!(context.error_count(), 0);
+}
+
+// Create outbound call to multiple remote devices, all of which
+// decline the call.
+#[test]
+fn outbound_all_remote_devices_declined() {
+    test_init();
+
+    let n_remotes: u16 = 3;
+    let context = start_outbound_n_remote_call(n_remotes);
+    let mut cm = context.cm();
+    let call_id = context.active_call().call_id();
+
+    for i in 1..n_remotes {
+        info!("test:{}: receiving declined", i);
+        let remote_id = ConnectionId::new(call_id, i as DeviceId);
+        cm.received_declined(remote_id).expect(error_line!());
+        // A repeated decline is rejected as a replay.
+        assert!(cm.received_declined(remote_id).is_err());
+        cm.synchronize().expect(error_line!());
+
+        assert_eq!(
+            context.active_call().state().expect(error_line!()),
+            CallState::Connecting
+        );
+        assert_eq!(context.ended_count(), 0);
+    }
+
+    info!("test: receiving declined from the last remote");
+    let remote_id = ConnectionId::new(call_id, n_remotes as DeviceId);
+    cm.received_declined(remote_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteDeclined),
+        1
+    );
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 0);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.call_concluded_count(), 1);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+}
+
+// Create outbound call to multiple remote devices, one of which
+// accepts the call while the others decline.
+#[test]
+fn outbound_accept_among_declines() {
+    test_init();
+
+    let n_remotes: u16 = 3;
+    let context = start_outbound_n_remote_call(n_remotes);
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    info!("test: receiving declined from remote 2");
+    let remote_id = ConnectionId::new(active_call.call_id(), 2 as DeviceId);
+    cm.received_declined(remote_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: connecting remote 1");
+    let mut connection = active_call
+        .get_connection(1 as DeviceId)
+        .expect(error_line!());
+    connection.inject_ice_connected().expect(error_line!());
+    connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+
+    info!("test: receiving declined from remotes 1 and 3");
+    for i in &[3, 1] {
+        let remote_id = ConnectionId::new(active_call.call_id(), *i as DeviceId);
+        cm.received_declined(remote_id).expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert_eq!(context.event_count(ApplicationEvent::RemoteConnected), 1);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteDeclined),
+        0
+    );
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(cm.call_active().expect(error_line!()), true);
+}
+
+#[test]
+fn shutdown_with_connected_call() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.shutdown().expect(error_line!());
+
+    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.hangups_sent(), 1);
+    assert_eq!(context.shutdown_complete_count(), 1);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+
+    // A second shutdown finds the call manager closed.
+    cm.shutdown().expect(error_line!());
+    assert_eq!(context.shutdown_complete_count(), 1);
+}
+
+#[test]
+fn call_duration_ticks() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let active_call = context.active_call();
+
+    cm.set_call_duration_ticks(true).expect(error_line!());
+
+    info!("test: no duration before the media connects");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_call.duration().expect(error_line!()),
+        Duration::default()
+    );
+
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+
+    thread::sleep(Duration::from_millis(2500));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Connected
+    );
+    assert!(context.duration_ticks() >= 2);
+    let duration = active_call.duration().expect(error_line!());
+    assert!(duration >= Duration::from_secs(2));
+
+    info!("test: duration keeps counting across a reconnection");
+    active_connection
+        .inject_ice_connection_disconnected()
+        .expect(error_line!());
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(active_call.duration().expect(error_line!()) >= duration);
+
+    info!("test: no ticks after hanging up");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let duration_ticks = context.duration_ticks();
+
+    thread::sleep(Duration::from_millis(1500));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.duration_ticks(), duration_ticks);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn call_stats_reports() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let active_call = context.active_call();
+
+    cm.set_stats_interval(Some(Duration::from_millis(500)))
+        .expect(error_line!());
+
+    info!("test: no stats before the media connects");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(context.stats_reports().is_empty());
+
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+
+    thread::sleep(Duration::from_millis(1200));
+    cm.synchronize().expect(error_line!());
+
+    let reports = context.stats_reports();
+    assert!(reports.len() >= 2);
+    assert_eq!(reports[0].rtt_ms, 50.0);
+    assert_eq!(reports[0].jitter_ms, 2.0);
+    assert_eq!(reports[0].jitter_buffer_ms, 62.5);
+    assert_eq!(reports[0].audio_codec, Some("opus".to_string()));
+    assert_eq!(reports[0].video_codec, Some("VP8".to_string()));
+    let video_adaptation = reports[0].video_adaptation.expect(error_line!());
+    assert!(video_adaptation.is_limited());
+    assert_eq!(video_adaptation.to_string(), "CPU-limited at 360p, 15 fps");
+    // The simulated counters do not move, so only the first report,
+    // covering the whole connection, sees any loss.
+    assert!(reports[0].packet_loss > 0.0);
+    assert_eq!(reports[1].packet_loss, 0.0);
+    assert_eq!(reports[1].send_bitrate_bps, 0);
+
+    info!("test: no stats after hanging up");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let report_count = context.stats_reports().len();
+
+    thread::sleep(Duration::from_millis(1000));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.stats_reports().len(), report_count);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn audio_levels() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let active_call = context.active_call();
+
+    cm.set_audio_levels_interval(Some(Duration::from_millis(200)))
+        .expect(error_line!());
+
+    info!("test: no audio levels before the media connects");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(context.audio_levels().is_empty());
+
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+
+    thread::sleep(Duration::from_millis(700));
+    cm.synchronize().expect(error_line!());
+
+    let levels = context.audio_levels();
+    assert!(levels.len() >= 2);
+    assert_eq!(levels[0], (0.5, 0.25));
+    // Sampling the audio levels leaves the stats reports alone.
+    assert!(active_connection
+        .last_stats_report()
+        .expect(error_line!())
+        .is_none());
+
+    info!("test: no audio levels after hanging up");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let level_count = context.audio_levels().len();
+
+    thread::sleep(Duration::from_millis(500));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.audio_levels().len(), level_count);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn media_flow_monitor() {
+    test_init();
+
+    let timeout = Duration::from_secs(4);
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    let mut monitor = MediaFlowMonitor::default();
+
+    info!("test: nothing is reported before monitoring starts");
+    assert_eq!(monitor.sample(0, 0, at(10)), None);
+    assert!(monitor.start(timeout, start));
+    assert!(!monitor.start(timeout, start));
+    assert_eq!(monitor.sample_period(), Some(Duration::from_secs(1)));
+
+    info!("test: the media stalls while consent stays fresh");
+    assert_eq!(monitor.sample(100, 5, at(1)), None);
+    assert_eq!(monitor.sample(100, 6, at(4)), None);
+    assert_eq!(monitor.sample(100, 7, at(5)), Some(MediaFlow::Stalled));
+    assert!(monitor.consent_fresh(at(5)));
+    assert_eq!(monitor.sample(100, 7, at(6)), None);
+
+    info!("test: the media resumes");
+    assert_eq!(monitor.sample(150, 7, at(7)), Some(MediaFlow::Resumed));
+    assert_eq!(monitor.sample(160, 7, at(8)), None);
+
+    info!("test: no stall while the remote peer holds the call");
+    monitor.set_remote_hold(true, at(8));
+    assert_eq!(monitor.sample(160, 7, at(20)), None);
+    assert!(!monitor.consent_fresh(at(20)));
+
+    monitor.set_remote_hold(false, at(20));
+    assert_eq!(monitor.sample(160, 7, at(23)), None);
+    assert_eq!(monitor.sample(160, 7, at(24)), Some(MediaFlow::Stalled));
+}
+
+#[test]
+fn remote_media_stalled() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let active_call = context.active_call();
+
+    cm.set_media_stall_timeout(Some(Duration::from_millis(200)))
+        .expect(error_line!());
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+
+    // The simulated stats report the same packet count every time.
+    thread::sleep(Duration::from_millis(700));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteMediaStalled), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteMediaResumed), 0);
+
+    info!("test: a stall is reported once");
+    thread::sleep(Duration::from_millis(300));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteMediaStalled), 1);
+    assert_eq!(context.error_count(), 0);
+
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+}
+
+#[test]
+fn bandwidth_estimates() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let active_call = context.active_call();
+
+    cm.set_bandwidth_estimate_interval(Some(Duration::from_millis(200)))
+        .expect(error_line!());
+
+    info!("test: no bandwidth estimates before the media connects");
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(context.bandwidth_estimates().is_empty());
+
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+
+    thread::sleep(Duration::from_millis(700));
+    cm.synchronize().expect(error_line!());
+
+    let estimates = context.bandwidth_estimates();
+    assert!(estimates.len() >= 2);
+    // The estimates of the nominated ICE candidate pair.
+    assert_eq!(estimates[0], (1_000_000, 1_500_000));
+    assert!(active_connection
+        .last_stats_report()
+        .expect(error_line!())
+        .is_none());
+
+    info!("test: no bandwidth estimates after hanging up");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let estimate_count = context.bandwidth_estimates().len();
+
+    thread::sleep(Duration::from_millis(500));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.bandwidth_estimates().len(), estimate_count);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn call_record() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+
+    cm.set_stats_interval(Some(Duration::from_millis(500)))
+        .expect(error_line!());
+
+    let call_id = {
+        let mut active_connection = context.active_connection();
+        let active_call = context.active_call();
+
+        active_connection
+            .inject_ice_route_changed(IceRoute::new("relay", "local", "udp"))
+            .expect(error_line!());
+        active_connection
+            .inject_ice_connected()
+            .expect(error_line!());
+        active_connection
+            .on_add_stream(MediaStream::new(ptr::null()))
+            .expect(error_line!());
+        active_connection
+            .inject_remote_connected(active_call.call_id())
+            .expect(error_line!());
+
+        active_call.call_id()
+    };
+
+    thread::sleep(Duration::from_millis(700));
+    cm.synchronize().expect(error_line!());
+    assert!(context.call_records().is_empty());
+
+    info!("test: local hangup");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let records = context.call_records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].call_id, call_id);
+    assert_eq!(records[0].direction, CallDirection::OutGoing);
+    assert!(records[0].duration >= Duration::from_millis(500));
+    assert_eq!(records[0].reason, Some(ApplicationEvent::EndedLocalHangup));
+    assert_eq!(records[0].connection_type, Some(ConnectionType::Relayed));
+    assert!(records[0].average_bitrate_bps > 0);
+    assert_eq!(context.call_concluded_count(), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn call_record_never_connected() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let call_id = context.active_call().call_id();
+
+    info!("test: local hangup");
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let records = context.call_records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].call_id, call_id);
+    assert_eq!(records[0].duration, Duration::default());
+    assert_eq!(records[0].reason, Some(ApplicationEvent::EndedLocalHangup));
+    assert_eq!(records[0].connection_type, None);
+    assert_eq!(records[0].average_bitrate_bps, 0);
+}
+
+fn proceed_with_audio_device_fault(context: &TestContext, error: AudioDeviceError) {
+    let mut cm = context.cm();
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    context.force_audio_device_fault(Some(error));
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+}
+
+#[test]
+fn audio_device_failure() {
+    test_init();
+
+    let context = TestContext::new();
+    let cm = context.cm();
+
+    proceed_with_audio_device_fault(&context, AudioDeviceError::PermissionDenied);
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::AudioDevicePermissionDenied),
+        1
+    );
+    assert_eq!(context.event_count(ApplicationEvent::AudioDeviceBusy), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(cm.call_active().expect(error_line!()), true);
+}
+
+#[test]
+fn audio_device_failure_ends_call() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_end_on_audio_device_failure(true)
+        .expect(error_line!());
+
+    proceed_with_audio_device_fault(&context, AudioDeviceError::Busy);
+
+    assert_eq!(context.event_count(ApplicationEvent::AudioDeviceBusy), 1);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedAudioDeviceFailure),
+        1
+    );
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+}
+
+#[test]
+fn group_call_participants() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let call_id = cm
+        .create_group_call(vec![
+            (1, "PEER-1".to_owned()),
+            (2, "PEER-2".to_owned()),
+            (3, "PEER-3".to_owned()),
+        ])
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // A group call is not a direct call.
+    assert_eq!(cm.call_active().expect(error_line!()), false);
+
+    let group_call = cm.group_call(call_id).expect(error_line!());
+    assert_eq!(
+        group_call.participant_ids().expect(error_line!()),
+        vec![1, 2, 3]
+    );
+
+    info!("test: participants 1 and 2 connect");
+    cm.group_participant_connected(call_id, 1)
+        .expect(error_line!());
+    cm.group_participant_connected(call_id, 2)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        group_call.participant_state(1).expect(error_line!()),
+        ParticipantState::Joined
+    );
+    assert_eq!(
+        group_call.participant_state(3).expect(error_line!()),
+        ParticipantState::Joining
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::GroupParticipantJoined),
+        2
+    );
+
+    info!("test: participant 2 disconnects");
+    cm.group_participant_disconnected(call_id, 2)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        group_call.participant_state(2).expect(error_line!()),
+        ParticipantState::Left
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::GroupParticipantLeft),
+        1
+    );
+
+    info!("test: participant 4 is added and connects");
+    cm.add_group_participant(call_id, 4, "PEER-4".to_owned())
+        .expect(error_line!());
+    cm.group_participant_connected(call_id, 4)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::GroupParticipantJoined),
+        3
+    );
+
+    info!("test: ending the group call");
+    cm.end_group_call(call_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Participants 1 and 4 were still joined, participant 3 never
+    // joined.
+    assert_eq!(
+        context.event_count(ApplicationEvent::GroupParticipantLeft),
+        3
+    );
+    assert!(cm.group_call(call_id).is_err());
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn group_call_participant_media() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let call_id = cm
+        .create_group_call(vec![(1, "PEER-1".to_owned()), (2, "PEER-2".to_owned())])
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: media arrives before participant 1 joins");
+    cm.group_participant_media(call_id, 1, "STREAM-1".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(context.participant_media().is_empty());
+
+    cm.group_participant_connected(call_id, 1)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        context.participant_media(),
+        vec![("PEER-1".to_owned(), "STREAM-1".to_owned())]
+    );
+
+    info!("test: media arrives after participant 2 joins");
+    cm.group_participant_connected(call_id, 2)
+        .expect(error_line!());
+    cm.group_participant_media(call_id, 2, "STREAM-2".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        context.participant_media(),
+        vec![
+            ("PEER-1".to_owned(), "STREAM-1".to_owned()),
+            ("PEER-2".to_owned(), "STREAM-2".to_owned()),
+        ]
+    );
+
+    info!("test: media from a participant that left is dropped");
+    cm.group_participant_disconnected(call_id, 2)
+        .expect(error_line!());
+    cm.group_participant_media(call_id, 2, "STREAM-3".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let group_call = cm.group_call(call_id).expect(error_line!());
+    assert!(!group_call.has_media_stream(2).expect(error_line!()));
+    assert_eq!(context.participant_media().len(), 2);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn outbound_opaque_signaling() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_opaque_signaling(true).expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    cm.call(remote_peer, CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The offer is handed over as opaque bytes only.
+    assert_eq!(context.opaque_offers_sent(), 1);
+    assert_eq!(context.offers_sent(), 1);
+
+    info!("test: a malformed opaque answer is rejected");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    assert!(cm.received_opaque_answer(remote_id, &[0x12, 0x05]).is_err());
+
+    info!("test: injecting opaque answer");
+    let answer = Answer::new("ANSWER".to_owned(), PROTOCOL_VERSION);
+    cm.received_opaque_answer(remote_id, &answer.to_opaque())
+        .expect(error_line!());
+    let ice_candidate = IceCandidate::new("0".to_owned(), 1, "ICE-1".to_owned());
+    cm.received_opaque_ice_candidates(
+        remote_id,
+        &encode_ice_candidates(1, &[ice_candidate]),
+        Duration::from_secs(0),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let mut active_connection = context.active_connection();
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceConnecting(true)
+    );
+    assert_eq!(
+        active_connection.protocol_version().expect(error_line!()),
+        PROTOCOL_VERSION
+    );
+
+    info!("test: local ICE candidates are sent as opaque bytes");
+    let ice_candidate = IceCandidate::new(
+        "0".to_owned(),
+        0,
+        "candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host".to_owned(),
+    );
+    active_connection
+        .inject_local_ice_candidate(ice_candidate)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.opaque_ice_sent(), 1);
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn outbound_sealed_signaling() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_opaque_signaling(true).expect(error_line!());
+    context.set_sealing_key(Some(0x5a));
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The offer is sealed, and opens again.
+    assert_eq!(context.sealed_sent(), 1);
+    assert_eq!(context.opaque_offers_sent(), 1);
+    assert_eq!(context.offers_sent(), 1);
+
+    info!("test: a tampered sealed answer is rejected");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    let answer = Answer::new("ANSWER".to_owned(), PROTOCOL_VERSION);
+    let mut sealed = context.seal("REMOTE_PEER", remote_id, answer.to_opaque());
+    let last = sealed.len() - 1;
+    sealed[last] ^= 0x01;
+    assert!(cm.received_opaque_answer(remote_id, &sealed).is_err());
+
+    info!("test: injecting sealed answer and ICE candidates");
+    cm.received_opaque_answer(
+        remote_id,
+        &context.seal("REMOTE_PEER", remote_id, answer.to_opaque()),
+    )
+    .expect(error_line!());
+    let ice_candidate = IceCandidate::new("0".to_owned(), 1, "ICE-1".to_owned());
+    cm.received_opaque_ice_candidates(
+        remote_id,
+        &context.seal(
+            "REMOTE_PEER",
+            remote_id,
+            encode_ice_candidates(1, &[ice_candidate]),
+        ),
+        Duration::from_secs(0),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let mut active_connection = context.active_connection();
+    assert_eq!(
+        active_connection.state().expect(error_line!()),
+        ConnectionState::IceConnecting(true)
+    );
+
+    info!("test: local ICE candidates are sealed");
+    let ice_candidate = IceCandidate::new(
+        "0".to_owned(),
+        0,
+        "candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host".to_owned(),
+    );
+    active_connection
+        .inject_local_ice_candidate(ice_candidate)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.sealed_sent(), 2);
+    assert_eq!(context.opaque_ice_sent(), 1);
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn transform_descriptions() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    context.set_sdp_attribute(Some("a=x-test".to_owned()));
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: the offer is transformed before it is sent");
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let transformed = context.transformed_sdp();
+    assert_eq!(transformed.len(), 1);
+    assert!(transformed[0].starts_with("FAKE SDP"));
+    assert!(transformed[0].ends_with("\r\na=x-test"));
+    assert_eq!(context.offers_sent(), 1);
+
+    info!("test: the answer is transformed before it is set");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(remote_id, "ANSWER\r\na=x-test".to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.transformed_sdp()[1..], ["ANSWER".to_owned()]);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn receive_answer_metadata() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    info!("test: the metadata of the answer is reported");
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    let answer = Answer {
+        metadata: Some(vec![0x01, 0x02]),
+        ..Answer::new("ANSWER".to_owned(), PROTOCOL_VERSION)
+    };
+    cm.received_answer(remote_id, answer.to_wire())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.answer_metadata(), vec![vec![0x01, 0x02]]);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn outbound_pre_offer() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_pre_offer(true).expect(error_line!());
+
+    info!("test: the pre-offer is sent as the call starts");
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.start_outgoing_count(), 1);
+    assert_eq!(context.pre_offers_sent(), 1);
+    assert_eq!(context.offers_sent(), 0);
+
+    info!("test: the offer follows");
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.pre_offers_sent(), 1);
+    assert_eq!(context.offers_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn app_messages() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    info!("test: sending an app message");
+    let buffered_amount = active_connection
+        .data_channel_buffered_amount()
+        .expect(error_line!());
+    active_connection
+        .send_app_message(b"REACTION")
+        .expect(error_line!());
+    assert!(
+        active_connection
+            .data_channel_buffered_amount()
+            .expect(error_line!())
+            > buffered_amount
+    );
+
+    info!("test: receiving app messages");
+    active_connection
+        .inject_remote_app_message(active_call.call_id(), b"CAPTION-1".to_vec())
+        .expect(error_line!());
+    active_connection
+        .inject_remote_app_message(CallId::new(PRNG.gen::<u64>()), b"OTHER".to_vec())
+        .expect(error_line!());
+    active_connection
+        .inject_remote_app_message(active_call.call_id(), b"CAPTION-2".to_vec())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.app_messages(),
+        vec![b"CAPTION-1".to_vec(), b"CAPTION-2".to_vec()]
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+// Collects the frames of the incoming video.
+struct FrameSink {
+    frames: Arc<Mutex<Vec<VideoFrame>>>,
+}
+
+impl VideoSink for FrameSink {
+    fn on_video_frame(&self, frame: VideoFrame) {
+        self.frames.lock().unwrap().push(frame);
+    }
+}
+
+#[test]
+fn incoming_video_sink() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let active_connection = context.active_connection();
+    let frame = VideoFrame {
+        width:        2,
+        height:       2,
+        timestamp_us: 0,
+        data:         vec![0x10; 6],
+    };
+
+    info!("test: frames are dropped without a sink");
+    active_connection
+        .on_incoming_video_frame(frame.clone())
+        .expect(error_line!());
+
+    info!("test: frames are handed to the sink");
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    active_connection
+        .set_incoming_video_sink(Some(Box::new(FrameSink {
+            frames: Arc::clone(&frames),
+        })))
+        .expect(error_line!());
+    active_connection
+        .on_incoming_video_frame(frame.clone())
+        .expect(error_line!());
+
+    assert_eq!(*frames.lock().unwrap(), vec![frame.clone()]);
+
+    info!("test: removing the sink");
+    active_connection
+        .set_incoming_video_sink(None)
+        .expect(error_line!());
+    active_connection
+        .on_incoming_video_frame(frame)
+        .expect(error_line!());
+
+    assert_eq!(frames.lock().unwrap().len(), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+// Produces frames of a test pattern, and reports when it is dropped.
+struct TestPatternSource {
+    remaining:    usize,
+    timestamp_us: i64,
+    dropped:      Sender<()>,
+}
+
+impl VideoSource for TestPatternSource {
+    fn next_frame(&mut self) -> Option<VideoFrame> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.timestamp_us += 33_333;
+        thread::sleep(Duration::from_millis(1));
+        Some(VideoFrame {
+            width:        2,
+            height:       2,
+            timestamp_us: self.timestamp_us,
+            data:         vec![0x80; 6],
+        })
+    }
+}
+
+impl Drop for TestPatternSource {
+    fn drop(&mut self) {
+        let _ = self.dropped.send(());
+    }
+}
+
+#[test]
+fn outgoing_video_source() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let active_connection = context.active_connection();
+    let (dropped, source_dropped) = channel();
+
+    info!("test: the source is polled until it ends");
+    active_connection
+        .set_outgoing_video_source(Some(Box::new(TestPatternSource {
+            remaining:    3,
+            timestamp_us: 0,
+            dropped:      dropped.clone(),
+        })))
+        .expect(error_line!());
+    source_dropped
+        .recv_timeout(Duration::from_secs(5))
+        .expect(error_line!());
+
+    info!("test: replacing the source stops it");
+    active_connection
+        .set_outgoing_video_source(Some(Box::new(TestPatternSource {
+            remaining:    usize::MAX,
+            timestamp_us: 0,
+            dropped:      dropped.clone(),
+        })))
+        .expect(error_line!());
+    active_connection
+        .set_outgoing_video_source(None)
+        .expect(error_line!());
+    source_dropped
+        .recv_timeout(Duration::from_secs(5))
+        .expect(error_line!());
+
+    info!("test: closing the connection stops the source");
+    active_connection
+        .set_outgoing_video_source(Some(Box::new(TestPatternSource {
+            remaining: usize::MAX,
+            timestamp_us: 0,
+            dropped,
+        })))
+        .expect(error_line!());
+    let mut cm = context.cm();
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    source_dropped
+        .recv_timeout(Duration::from_secs(5))
+        .expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+}
+
+// Meters the peak level of the captured and received audio.
+struct PeakMeter {
+    peaks: Arc<Mutex<Vec<(AudioDirection, i16)>>>,
+}
+
+impl AudioTap for PeakMeter {
+    fn on_audio_frame(&self, frame: AudioFrame) {
+        let peak = frame.samples.iter().map(|s| s.saturating_abs()).max();
+        self.peaks
+            .lock()
+            .unwrap()
+            .push((frame.direction, peak.unwrap_or(0)));
+    }
+}
+
+#[test]
+fn audio_tap() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let active_connection = context.active_connection();
+    let captured = AudioFrame {
+        direction:      AudioDirection::Captured,
+        sample_rate_hz: 48000,
+        channels:       1,
+        samples:        vec![100, -2000, 300],
+    };
+    let received = AudioFrame {
+        direction:      AudioDirection::Received,
+        sample_rate_hz: 48000,
+        channels:       2,
+        samples:        vec![-50, 40, 30, 20],
+    };
+
+    info!("test: frames are dropped without a tap");
+    active_connection
+        .on_audio_frame(captured.clone())
+        .expect(error_line!());
+
+    info!("test: frames of both directions are handed to the tap");
+    let peaks = Arc::new(Mutex::new(Vec::new()));
+    active_connection
+        .set_audio_tap(Some(Box::new(PeakMeter {
+            peaks: Arc::clone(&peaks),
+        })))
+        .expect(error_line!());
+    active_connection
+        .on_audio_frame(captured.clone())
+        .expect(error_line!());
+    active_connection
+        .on_audio_frame(received)
+        .expect(error_line!());
+
+    assert_eq!(
+        *peaks.lock().unwrap(),
+        vec![
+            (AudioDirection::Captured, 2000),
+            (AudioDirection::Received, 50)
+        ]
+    );
+
+    info!("test: removing the tap");
+    active_connection.set_audio_tap(None).expect(error_line!());
+    active_connection
+        .on_audio_frame(captured)
+        .expect(error_line!());
+
+    assert_eq!(peaks.lock().unwrap().len(), 2);
+    assert_eq!(context.error_count(), 0);
+}
+
+/// Collects the stream of a recording.
+struct RecordingBuffer {
+    stream:   Arc<Mutex<Vec<u8>>>,
+    finished: Arc<Mutex<bool>>,
+}
+
+impl RecordingSink for RecordingBuffer {
+    fn write(&mut self, data: &[u8]) -> ringrtc::common::Result<()> {
+        self.stream.lock().unwrap().extend_from_slice(data);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> ringrtc::common::Result<()> {
+        *self.finished.lock().unwrap() = true;
+        Ok(())
+    }
+}
+
+#[test]
+fn record_call() {
+    test_init();
+
+    // The simulated send buffer, declared first so it outlives the
+    // call.
+    let buffered_amount = AtomicU64::new(0);
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+    let stream = Arc::new(Mutex::new(Vec::new()));
+    let finished = Arc::new(Mutex::new(false));
+    let sink = || {
+        Box::new(RecordingBuffer {
+            stream:   Arc::clone(&stream),
+            finished: Arc::clone(&finished),
+        })
+    };
+
+    active_connection
+        .set_data_channel(DataChannel::new(&buffered_amount))
+        .expect(error_line!());
+
+    info!("test: recording an inactive call");
+    assert!(cm
+        .start_recording(
+            CallId::new(PRNG.gen::<u64>()),
+            sink(),
+            RecordingConfig::default()
+        )
+        .is_err());
+    assert!(stream.lock().unwrap().is_empty());
+
+    info!("test: recording the active call");
+    cm.start_recording(active_call.call_id(), sink(), RecordingConfig::default())
+        .expect(error_line!());
+
+    assert!(active_connection.recording().expect(error_line!()));
+    let header_len = stream.lock().unwrap().len();
+    assert!(header_len > 0);
+    assert!(buffered_amount.load(Ordering::Acquire) > 0);
+
+    info!("test: recording twice");
+    assert!(cm
+        .start_recording(active_call.call_id(), sink(), RecordingConfig::default())
+        .is_err());
+
+    info!("test: a second of audio is written as a cluster");
+    for direction in &[AudioDirection::Captured, AudioDirection::Received] {
+        for _ in 0..101 {
+            active_connection
+                .on_audio_frame(AudioFrame {
+                    direction:      *direction,
+                    sample_rate_hz: 48000,
+                    channels:       1,
+                    samples:        vec![100; 480],
+                })
+                .expect(error_line!());
+        }
+    }
+    let recorded_len = stream.lock().unwrap().len();
+    assert!(recorded_len > header_len + 100 * 960);
+
+    info!("test: stopping the recording");
+    cm.stop_recording(active_call.call_id())
+        .expect(error_line!());
+
+    assert!(!active_connection.recording().expect(error_line!()));
+    assert!(*finished.lock().unwrap());
+    assert!(stream.lock().unwrap().len() > recorded_len);
+    assert!(cm.stop_recording(active_call.call_id()).is_err());
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn received_remote_recording_status() {
+    test_init();
+
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_remote_recording_status(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteRecordingStarted),
+        1
     );
+
+    info!("test: recording status for an inactive call");
     active_connection
-        .inject_remote_connected(active_call.call_id())
+        .inject_remote_recording_status(CallId::new(PRNG.gen::<u64>()), false)
         .expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteRecordingStopped),
+        0
+    );
+
+    active_connection
+        .inject_remote_recording_status(active_call.call_id(), false)
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
     assert_eq!(
-        active_connection.state().expect(error_line!()),
-        ConnectionState::CallConnected
+        context.event_count(ApplicationEvent::RemoteRecordingStopped),
+        1
     );
+    assert_eq!(context.error_count(), 0);
+}
+
+// An answer negotiating a screen share alongside the camera video.
+const SCREEN_SHARE_ANSWER: &str = "v=0\r\n\
+                                   m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                                   a=rtpmap:96 VP8/90000\r\n\
+                                   m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                                   a=rtpmap:96 VP8/90000\r\n\
+                                   a=content:slides\r\n";
+
+// Connect an outbound call answered with SCREEN_SHARE_ANSWER.
+fn connect_outbound_screen_share_call() -> TestContext {
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
+    cm.call(remote_peer, CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let remote_id = ConnectionId::new(active_call.call_id(), 1 as DeviceId);
+    cm.received_answer(remote_id, SCREEN_SHARE_ANSWER.to_owned())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let mut active_connection = context.active_connection();
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
     assert_eq!(
         active_call.state().expect(error_line!()),
         CallState::Connected
     );
+    assert_eq!(context.error_count(), 0);
 
-    assert_eq!(context.event_count(ApplicationEvent::RemoteConnected), 1);
-    assert_eq!(context.stream_count(), 1);
+    context
+}
+
+#[test]
+fn share_screen() {
+    test_init();
+
+    let context = connect_outbound_screen_share_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    let capabilities = active_connection
+        .remote_capabilities()
+        .expect(error_line!())
+        .expect(error_line!());
+    assert!(capabilities.screen_share);
+    assert!(!active_connection.screen_sharing().expect(error_line!()));
+
+    info!("test: sharing the screen alongside the camera");
+    cm.set_outgoing_video_enabled(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.set_outgoing_screen_share_enabled(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(active_connection.screen_sharing().expect(error_line!()));
+
+    info!("test: holding and resuming the call");
+    cm.set_on_hold(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    cm.set_on_hold(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(active_connection.screen_sharing().expect(error_line!()));
+
+    info!("test: switching back to the camera");
+    cm.set_outgoing_screen_share_enabled(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(!active_connection.screen_sharing().expect(error_line!()));
     assert_eq!(context.error_count(), 0);
     assert_eq!(context.ended_count(), 0);
+}
 
-    cm.hangup().expect(error_line!());
+#[test]
+fn share_screen_not_negotiated() {
+    test_init();
 
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    assert!(cm
+        .set_outgoing_screen_share_enabled(active_call.call_id(), true)
+        .is_err());
     cm.synchronize().expect(error_line!());
+    assert!(!active_connection.screen_sharing().expect(error_line!()));
 
-    assert_eq!(active_call.state().expect(error_line!()), CallState::Closed);
+    // Stopping is always allowed.
+    cm.set_outgoing_screen_share_enabled(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.ended_count(), 1);
-    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
-    assert_eq!(context.hangups_sent(), 1);
+    assert_eq!(context.ended_count(), 0);
 }
 
-// Create multiple call managers, each managing one outbound call.
-//
-// Each call is connected and then followed by a remote hangup.
-//
-// Each call manager is on a separate thread
 #[test]
-fn outbound_multiple_call_managers() {
+fn received_remote_screen_share_status() {
     test_init();
 
-    let n_call_manager = 5;
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
 
-    let mut thread_vec = Vec::new();
-    for i in 0..n_call_manager {
-        info!("test:{}: creating call manager", i);
+    active_connection
+        .inject_remote_screen_share_status(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
-        let child = thread::spawn(move || {
-            outbound_multiple_remote_devices();
-        });
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteSharingScreen),
+        1
+    );
 
-        thread_vec.push(child);
-    }
+    info!("test: screen share status for an inactive call");
+    active_connection
+        .inject_remote_screen_share_status(CallId::new(PRNG.gen::<u64>()), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
-    info!("test: joinging threads");
-    for child in thread_vec {
-        info!("test: joinging thread...");
-        // Make sure no threads panicked
-        assert!(child.join().is_ok());
-    }
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteStoppedSharingScreen),
+        0
+    );
+
+    active_connection
+        .inject_remote_screen_share_status(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteStoppedSharingScreen),
+        1
+    );
+    assert_eq!(context.error_count(), 0);
 }
 
-// Two users call each other at the same time
 #[test]
-fn glare() {
+fn simulcast_layers() {
     test_init();
 
     let context = connect_outbound_call();
     let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+    let layers = vec![
+        SimulcastLayer::new(VideoResolution::new(320, 180), 150_000),
+        SimulcastLayer::new(VideoResolution::new(640, 360), 500_000),
+        SimulcastLayer::new(VideoResolution::new(1280, 720), 1_500_000),
+    ];
 
-    // Create incoming call with same remote
+    info!("test: configuring simulcast layers");
+    active_connection
+        .set_simulcast_layers(layers.clone())
+        .expect(error_line!());
+    assert!(active_connection
+        .set_simulcast_layers(vec![layers[0]; MAX_SIMULCAST_LAYERS + 1])
+        .is_err());
 
-    let remote_peer = {
-        let active_call = context.active_call();
-        let remote_peer = active_call.remote_peer().expect(error_line!());
-        remote_peer.to_owned()
-    };
-    info!("active remote_peer: {}", remote_peer);
+    let simulcast = active_connection.simulcast().expect(error_line!());
+    assert_eq!(simulcast.layers(), &layers[..]);
+    assert_eq!(simulcast.active_layers(), vec![true, true, true]);
 
-    let connection_id = ConnectionId::new(CallId::new(PRNG.gen::<u64>()), 1 as DeviceId);
-    cm.received_offer(
-        remote_peer,
-        connection_id,
-        format!("OFFER-{}", PRNG.gen::<u16>()).to_owned(),
-        SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect(error_line!())
-            .as_millis() as u64,
-    )
-    .expect(error_line!());
+    info!("test: requesting a layer of the remote video");
+    active_connection
+        .request_video_layer(1)
+        .expect(error_line!());
+    assert!(active_connection
+        .request_video_layer(MAX_SIMULCAST_LAYERS)
+        .is_err());
 
+    info!("test: the remote peer requests a layer");
+    active_connection
+        .inject_remote_video_layer_request(active_call.call_id(), 0)
+        .expect(error_line!());
+    active_connection
+        .inject_remote_video_layer_request(CallId::new(PRNG.gen::<u64>()), 2)
+        .expect(error_line!());
+    active_connection
+        .inject_remote_video_layer_request(active_call.call_id(), MAX_SIMULCAST_LAYERS)
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    // glare case should send a busy to the new caller and conclude
-    // the current call.  So two conclude call events.
+    let simulcast = active_connection.simulcast().expect(error_line!());
+    assert_eq!(simulcast.requested_layer(), Some(0));
+    assert_eq!(simulcast.active_layers(), vec![true, false, false]);
 
     assert_eq!(context.error_count(), 0);
-    assert_eq!(
-        context.event_count(ApplicationEvent::EndedReceivedOfferWhileActive),
+    assert_eq!(context.ended_count(), 0);
+}
+
+/// Appends the connection's device to every frame.
+struct DeviceTagCrypto;
+
+impl MediaCryptoProvider for DeviceTagCrypto {
+    fn encrypt(&self, connection_id: ConnectionId, _: MediaKind, frame: &[u8]) -> Option<Vec<u8>> {
+        let mut output = frame.to_vec();
+        output.push(connection_id.remote_device() as u8);
+        Some(output)
+    }
+
+    fn decrypt(&self, connection_id: ConnectionId, _: MediaKind, frame: &[u8]) -> Option<Vec<u8>> {
+        match frame.split_last() {
+            Some((tag, frame)) if *tag == connection_id.remote_device() as u8 => {
+                Some(frame.to_vec())
+            }
+            _ => None,
+        }
+    }
+
+    fn max_overhead(&self, _: MediaKind) -> usize {
         1
-    );
-    assert_eq!(context.busys_sent(), 1);
-    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteGlare), 1);
-    assert_eq!(context.call_concluded_count(), 2);
+    }
 }
 
-// Receive a busy message when trying to establish outbound call
 #[test]
-fn start_outbound_receive_busy() {
+fn media_crypto_provider() {
     test_init();
 
     let context = TestContext::new();
     let mut cm = context.cm();
+    cm.set_media_crypto_provider(Some(Arc::new(DeviceTagCrypto)))
+        .expect(error_line!());
 
-    let remote_peer = format!("REMOTE_PEER-{}", PRNG.gen::<u16>()).to_owned();
-    cm.call(remote_peer).expect(error_line!());
+    cm.call("REMOTE_PEER".to_owned(), CallConfig::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
+    info!("test: sending the offer");
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        "CONTEXT".to_owned(),
+        vec![1 as DeviceId],
+        Vec::new(),
+    )
+    .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    assert_eq!(cm.active_call().is_ok(), true);
-    assert_eq!(context.start_outgoing_count(), 1);
-    assert_eq!(context.start_incoming_count(), 0);
+    // The offer says its frames are encrypted.
+    let connection = active_call
+        .get_connection(1 as DeviceId)
+        .expect(error_line!());
+    assert!(connection.frame_encryption().expect(error_line!()));
+    assert_eq!(context.offers_sent(), 1);
+    assert!(context.media_descriptors()[0].frame_encryption);
 
-    let call_id = {
-        let active_call = context.active_call();
-        assert_eq!(
-            active_call.state().expect(error_line!()),
-            CallState::Starting
-        );
-        active_call.call_id()
-    };
+    info!("test: the provider is kept across a hard reset");
+    connection.hard_reset().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(connection.frame_encryption().expect(error_line!()));
 
-    let mut remote_devices = Vec::<DeviceId>::new();
+    info!("test: removing the provider");
+    connection
+        .set_media_crypto_provider(None)
+        .expect(error_line!());
+    assert!(!connection.frame_encryption().expect(error_line!()));
+    assert_eq!(context.error_count(), 0);
+}
 
-    remote_devices.push(1);
+#[cfg(feature = "srtp_key_export")]
+#[test]
+fn export_srtp_keys() {
+    test_init();
 
-    cm.proceed(
-        call_id,
-        format!("CONTEXT-{}", PRNG.gen::<u16>()).to_owned(),
-        remote_devices,
-    )
-    .expect(error_line!());
+    info!("test: calls must opt in");
+    let context = connect_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    assert!(cm.export_srtp_keys(active_call.call_id()).is_err());
 
-    cm.synchronize().expect(error_line!());
+    let config = CallConfig::default().with_srtp_key_export();
+    let context = start_outbound_n_remote_call_with_config(1, config);
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
 
-    // Receive a busy message
-    let remote_id = ConnectionId::new(call_id, 1 as DeviceId);
-    cm.received_busy(remote_id).expect(error_line!());
+    info!("test: no keys before connecting");
+    assert!(cm.export_srtp_keys(active_call.call_id()).is_err());
 
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    active_connection
+        .on_add_stream(MediaStream::new(ptr::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_remote_connected(active_call.call_id())
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteBusy), 1);
+    info!("test: exporting the keys");
+    let keys = cm
+        .export_srtp_keys(active_call.call_id())
+        .expect(error_line!());
+    assert_eq!(keys.profile, SrtpProtectionProfile::Aes128CmSha1_80);
+    assert_eq!(keys.local_key.len(), 16);
+    assert_eq!(keys.remote_salt.len(), 14);
+    assert_ne!(keys.local_key, keys.remote_key);
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.call_concluded_count(), 1);
 }